@@ -5,7 +5,7 @@ use tui_map::map::projection::{mercator_x, mercator_y, Viewport, WRAP_OFFSETS};
 use tui_map::map::renderer::{LineString, Polygon, LandGrid, MapRenderer};
 use tui_map::map::spatial::FeatureGrid;
 use tui_map::map::globe::GlobeViewport;
-use tui_map::app::FireGrid;
+use tui_map::app::FireField;
 
 // ---------------------------------------------------------------------------
 // 1. BrailleCanvas::set_pixel — tightest inner loop of Bresenham
@@ -198,7 +198,7 @@ fn bench_feature_grid(c: &mut Criterion) {
             let mut raw = Vec::new();
             grid.query_into(min_lon.max(-180.0), min_lat, max_lon.min(180.0), max_lat, &mut raw);
             let n = grid.num_features();
-            let mut seen = vec![0u64; (n + 63) / 64];
+            let mut seen = vec![0u64; n.div_ceil(64)];
             let mut unique = Vec::with_capacity(raw.len().min(n));
             for idx in raw {
                 let word = idx / 64;
@@ -327,50 +327,74 @@ fn bench_land_grid(c: &mut Criterion) {
         });
     });
 
+    // The startup path: rasterized once, then loaded from the disk cache
+    group.bench_function("from_polygons_3_rects", |b| {
+        b.iter(|| {
+            black_box(LandGrid::from_polygons(&polygons));
+        });
+    });
+
     group.finish();
 }
 
 // ---------------------------------------------------------------------------
-// 8. FireGrid::rebuild — O(n) insert from fire Vec
+// 8. FireField — igniting and stepping fires in place on the grid
 // ---------------------------------------------------------------------------
 fn bench_fire_grid(c: &mut Criterion) {
-    use tui_map::app::{Fire, WeaponType};
+    use tui_map::app::{FireConditions, WeaponType};
+    use tui_map::data::landcover::Cover;
 
     let mut group = c.benchmark_group("fire_grid");
 
+    let field = |n_fires: usize| {
+        let mut fires = FireField::new();
+        for i in 0..n_fires {
+            let lon = -100.0 + (i as f64 * 0.01) % 50.0;
+            let lat = 30.0 + (i as f64 * 0.005) % 20.0;
+            fires.ignite(lon, lat, (200 - (i % 200)) as u8, WeaponType::Nuke);
+        }
+        fires
+    };
+
     for &n_fires in &[1000, 10_000, 30_000] {
-        let fires: Vec<Fire> = (0..n_fires)
-            .map(|i| Fire {
-                lon: -100.0 + (i as f64 * 0.01) % 50.0,
-                lat: 30.0 + (i as f64 * 0.005) % 20.0,
-                intensity: (200 - (i % 200)) as u8,
-                weapon_type: WeaponType::Nuke,
-            })
-            .collect();
+        group.bench_with_input(BenchmarkId::new("ignite", n_fires), &n_fires, |b, &n| {
+            b.iter(|| black_box(field(n)));
+        });
 
-        group.bench_with_input(BenchmarkId::new("rebuild", n_fires), &fires, |b, fires| {
-            let mut grid = FireGrid::new(0.25);
-            b.iter(|| {
-                grid.rebuild(black_box(fires));
-            });
+        // Every burning cell decays, spreads and updates both grid levels
+        let fires = field(n_fires);
+        group.bench_with_input(BenchmarkId::new("step", n_fires), &fires, |b, fires| {
+            let mut frame = 0;
+            b.iter_batched(
+                || fires.clone(),
+                |mut fires| {
+                    frame += 5;
+                    let dry = |_, _| FireConditions { rain: 0, wind: (5.0, 0.0), cover: Cover::Forest };
+                    black_box(fires.step(frame, 42, dry, |_, _| Some(Cover::Forest)))
+                },
+                criterion::BatchSize::LargeInput,
+            );
         });
     }
 
-    // fires_in_region query
-    let fires: Vec<Fire> = (0..30_000)
-        .map(|i| Fire {
-            lon: -100.0 + (i as f64 * 0.01) % 50.0,
-            lat: 30.0 + (i as f64 * 0.005) % 20.0,
-            intensity: 200,
-            weapon_type: WeaponType::Nuke,
-        })
-        .collect();
-    let mut grid = FireGrid::new(0.25);
-    grid.rebuild(&fires);
+    // Rebuilding both grid levels from cell states, as loading a snapshot
+    // or a spectator's catch-up does
+    let fires = field(30_000);
+    let cells: Vec<_> = fires.cell_states().collect();
+    group.bench_function("rebuild_30k", |b| {
+        b.iter(|| {
+            let mut rebuilt = FireField::new();
+            for &(cell, intensity, weapon) in &cells {
+                rebuilt.set_cell(cell, intensity, weapon);
+            }
+            black_box(rebuilt)
+        });
+    });
 
+    // fires_in_region query
     group.bench_function("fires_in_region_30k", |b| {
         b.iter(|| {
-            black_box(grid.fires_in_region(-90.0, 35.0, -70.0, 45.0));
+            black_box(fires.fine.fires_in_region(-90.0, 35.0, -70.0, 45.0));
         });
     });
 
@@ -411,7 +435,7 @@ fn bench_draw_linestring_mercator(c: &mut Criterion) {
                 }
 
                 let mut prev: Option<(i32, i32)> = None;
-                for &(mx, my) in &line.mercator {
+                for (mx, my) in line.mercator() {
                     let (px, py) = vp.project_mercator(mx, my, lon_offset);
                     if let Some((prev_x, prev_y)) = prev {
                         let dx = (px - prev_x).abs();
@@ -451,7 +475,7 @@ fn bench_draw_linestring_mercator(c: &mut Criterion) {
                 }
 
                 let mut prev: Option<(i32, i32)> = None;
-                for &(mx, my) in &far_line.mercator {
+                for (mx, my) in far_line.mercator() {
                     let (px, py) = vp.project_mercator(mx, my, lon_offset);
                     if let Some((prev_x, prev_y)) = prev {
                         let dx = (px - prev_x).abs();
@@ -467,6 +491,19 @@ fn bench_draw_linestring_mercator(c: &mut Criterion) {
         });
     });
 
+    // The renderer's own path: projection segments into Bresenham lines
+    for (label, projection) in [
+        ("200pt_feature_segments_mercator", tui_map::map::Projection::new(vp.clone())),
+        ("200pt_feature_segments_globe", tui_map::map::Projection::new(GlobeViewport::new(10.0, 45.0, 350.0, 400, 200))),
+    ] {
+        group.bench_function(label, |b| {
+            let mut canvas = BrailleCanvas::new(200, 50);
+            b.iter(|| {
+                projection.view().segments(&line, &mut |(x0, y0), (x1, y1)| draw_line(&mut canvas, x0, y0, x1, y1));
+            });
+        });
+    }
+
     group.finish();
 }
 
@@ -488,7 +525,7 @@ fn bench_full_render(c: &mut Criterion) {
     for &(label, zoom) in &[("world_1x", 1.0), ("continental_4x", 4.0), ("regional_10x", 10.0)] {
         // Mercator
         group.bench_function(format!("mercator_{label}"), |b| {
-            let projection = tui_map::map::Projection::Mercator(Viewport::new(0.0, 30.0, zoom, width * 2, height * 4));
+            let projection = tui_map::map::Projection::new(Viewport::new(0.0, 30.0, zoom, width * 2, height * 4));
             b.iter(|| {
                 black_box(renderer.render(width, height, &projection));
             });
@@ -496,7 +533,7 @@ fn bench_full_render(c: &mut Criterion) {
 
         // Globe
         group.bench_function(format!("globe_{label}"), |b| {
-            let projection = tui_map::map::Projection::Globe(GlobeViewport::new(0.0, 30.0, width as f64 * 0.35 * zoom, width * 2, height * 4));
+            let projection = tui_map::map::Projection::new(GlobeViewport::new(0.0, 30.0, width as f64 * 0.35 * zoom, width * 2, height * 4));
             b.iter(|| {
                 black_box(renderer.render(width, height, &projection));
             });
@@ -509,6 +546,20 @@ fn bench_full_render(c: &mut Criterion) {
 // ---------------------------------------------------------------------------
 // 11. Full render with REAL GeoJSON data (if available)
 // ---------------------------------------------------------------------------
+/// Swap in the county chunks a view draws, read before timing starts
+fn load_chunks(
+    renderer: &mut MapRenderer,
+    chunks: Option<&mut tui_map::data::chunks::ChunkManager>,
+    projection: &tui_map::map::Projection,
+) {
+    if let Some(chunks) = chunks {
+        if chunks.load_now(Some(projection.visible_bounds())) {
+            let lines = chunks.assemble(std::mem::take(&mut renderer.counties));
+            renderer.set_counties(lines);
+        }
+    }
+}
+
 fn bench_real_data_render(c: &mut Criterion) {
     use std::path::Path;
 
@@ -531,6 +582,9 @@ fn bench_real_data_render(c: &mut Criterion) {
 
     renderer.build_land_grid();
     renderer.build_spatial_indexes();
+    // Counties are drawn from chunks, as the app draws them
+    let _ = tui_map::data::cut_counties(&mut renderer, data_dir);
+    let mut chunks = tui_map::data::county_chunks(data_dir);
 
     eprintln!(
         "  [real_data] Loaded: coast_low={}, coast_med={}, coast_high={}, borders_med={}, borders_high={}, states={}, counties={}",
@@ -557,9 +611,10 @@ fn bench_real_data_render(c: &mut Criterion) {
         ("city_20x", 20.0, -74.0, 40.7),  // NYC
     ] {
         group.bench_function(format!("mercator_{label}"), |b| {
-            let projection = tui_map::map::Projection::Mercator(
+            let projection = tui_map::map::Projection::new(
                 Viewport::new(center_lon, center_lat, zoom, width * 2, height * 4),
             );
+            load_chunks(&mut renderer, chunks.as_mut(), &projection);
             b.iter(|| {
                 black_box(renderer.render(width, height, &projection));
             });
@@ -573,9 +628,10 @@ fn bench_real_data_render(c: &mut Criterion) {
         ("usa_8x", 8.0, -95.0, 38.0),
     ] {
         group.bench_function(format!("globe_{label}"), |b| {
-            let projection = tui_map::map::Projection::Globe(
+            let projection = tui_map::map::Projection::new(
                 GlobeViewport::new(center_lon, center_lat, width as f64 * 0.35 * zoom, width * 2, height * 4),
             );
+            load_chunks(&mut renderer, chunks.as_mut(), &projection);
             b.iter(|| {
                 black_box(renderer.render(width, height, &projection));
             });
@@ -655,6 +711,13 @@ fn bench_real_data_render(c: &mut Criterion) {
                     black_box(LandGrid::build_scanline(&renderer.land_polygons_low));
                 });
             });
+
+            // Startup path: the first call caches the grid, so this times the load
+            group.bench_function("from_polygons_cached_low", |b| {
+                b.iter(|| {
+                    black_box(LandGrid::from_polygons(&renderer.land_polygons_low));
+                });
+            });
         }
 
         if !renderer.land_polygons_medium.is_empty() {
@@ -0,0 +1,15 @@
+//! Rebuild the world compiled into the binary from the Natural Earth files
+//! in `data/`: `cargo run --example embed_world`
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use tui_map::data::embedded::EmbeddedWorld;
+
+const OUT: &str = "assets/world_110m.bin";
+
+fn main() -> Result<()> {
+    let bytes = EmbeddedWorld::from_natural_earth(Path::new("data"))?.encode();
+    std::fs::write(OUT, &bytes).with_context(|| format!("writing {OUT}"))?;
+    println!("Wrote {OUT} ({} KB)", bytes.len() / 1024);
+    Ok(())
+}
@@ -0,0 +1,234 @@
+//! Single-player adversary.
+//!
+//! Every country the player hits files a grievance. After a difficulty-
+//! dependent delay the country answers with a salvo launched from its largest
+//! surviving city, aimed at the player's cities (weighted by population) and —
+//! on harder settings — at the sites the player has been launching from.
+
+use crate::hash::{hash2, hash3, rand_simple};
+
+/// The player's country. Strikes on it never provoke a response.
+pub const PLAYER_COUNTRY: &str = "USA";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    /// Off → Easy → Normal → Hard → Off
+    pub fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(Difficulty::Easy),
+            Some(Difficulty::Easy) => Some(Difficulty::Normal),
+            Some(Difficulty::Normal) => Some(Difficulty::Hard),
+            Some(Difficulty::Hard) => None,
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "EASY",
+            Difficulty::Normal => "NORMAL",
+            Difficulty::Hard => "HARD",
+        }
+    }
+
+    /// Frames between being hit and launching the counterstrike
+    fn reaction_frames(self) -> u64 {
+        match self {
+            Difficulty::Easy => 300,
+            Difficulty::Normal => 180,
+            Difficulty::Hard => 90,
+        }
+    }
+
+    /// Missiles per counterstrike
+    fn salvo(self) -> usize {
+        match self {
+            Difficulty::Easy => 1,
+            Difficulty::Normal => 2,
+            Difficulty::Hard => 4,
+        }
+    }
+
+    /// Aim error in km
+    fn scatter_km(self) -> f64 {
+        match self {
+            Difficulty::Easy => 150.0,
+            Difficulty::Normal => 60.0,
+            Difficulty::Hard => 15.0,
+        }
+    }
+
+    /// Target weight of each player launch, in equivalent city population
+    fn counterforce_weight(self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.0,
+            Difficulty::Normal => 2_000_000.0,
+            Difficulty::Hard => 8_000_000.0,
+        }
+    }
+}
+
+/// A candidate target and its selection weight
+#[derive(Clone, Copy, Debug)]
+pub struct Target {
+    pub lon: f64,
+    pub lat: f64,
+    pub weight: f64,
+}
+
+/// A counterstrike missile to put in the air
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Launch {
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+}
+
+struct Grievance {
+    country: String,
+    due_frame: u64,
+}
+
+pub struct Adversary {
+    pub difficulty: Difficulty,
+    pending: Vec<Grievance>,
+    /// Where the player has launched from, with launch counts
+    player_sites: Vec<(f64, f64, u32)>,
+    /// Total counterstrike missiles launched
+    pub missiles_launched: u32,
+}
+
+impl Adversary {
+    pub fn new(difficulty: Difficulty) -> Self {
+        Self { difficulty, pending: Vec::new(), player_sites: Vec::new(), missiles_launched: 0 }
+    }
+
+    /// A player warhead launched from `origin` landed in `country`. Repeated
+    /// hits while a response is pending don't stack — the salvo is already coming.
+    pub fn record_strike(&mut self, country: &str, origin: (f64, f64), frame: u64) {
+        match self.player_sites.iter_mut().find(|s| (s.0, s.1) == origin) {
+            Some(site) => site.2 += 1,
+            None => self.player_sites.push((origin.0, origin.1, 1)),
+        }
+        if country.is_empty() || country == PLAYER_COUNTRY || self.pending.iter().any(|g| g.country == country) {
+            return;
+        }
+        self.pending.push(Grievance {
+            country: country.to_string(),
+            due_frame: frame + self.difficulty.reaction_frames(),
+        });
+    }
+
+    /// Countries whose counterstrike is ready this frame
+    pub fn due(&mut self, frame: u64) -> Vec<String> {
+        let mut ready = Vec::new();
+        self.pending.retain(|g| {
+            if frame >= g.due_frame {
+                ready.push(g.country.clone());
+                false
+            } else {
+                true
+            }
+        });
+        ready
+    }
+
+    /// Pick a salvo of targets from `cities` (plus known player launch sites)
+    /// by weighted random draw, and launch it from `from`. The draw depends
+    /// only on `frame` and the simulation `seed`.
+    pub fn plan(&mut self, from: (f64, f64), cities: &[Target], frame: u64, seed: u64) -> Vec<Launch> {
+        let counterforce = self.difficulty.counterforce_weight();
+        let mut targets: Vec<Target> = cities.to_vec();
+        if counterforce > 0.0 {
+            targets.extend(self.player_sites.iter().map(|&(lon, lat, n)| Target {
+                lon,
+                lat,
+                weight: counterforce * n as f64,
+            }));
+        }
+        let total: f64 = targets.iter().map(|t| t.weight).sum();
+        if total <= 0.0 {
+            return Vec::new();
+        }
+
+        let scatter_deg = self.difficulty.scatter_km() / 111.0;
+        let key = hash2(seed, frame);
+        let launches: Vec<Launch> = (0..self.difficulty.salvo() as u64)
+            .map(|i| {
+                let mut pick = rand_simple(hash3(key, i, 0xA1)) * total;
+                let target = targets
+                    .iter()
+                    .find(|t| {
+                        pick -= t.weight;
+                        pick <= 0.0
+                    })
+                    .unwrap_or(&targets[targets.len() - 1]);
+                let angle = rand_simple(hash3(key, i, 0xA2)) * std::f64::consts::TAU;
+                let dist = rand_simple(hash3(key, i, 0xA3)) * scatter_deg;
+                Launch {
+                    from,
+                    to: (target.lon + dist * angle.cos(), target.lat + dist * angle.sin()),
+                }
+            })
+            .collect();
+        self.missiles_launched += launches.len() as u32;
+        launches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retaliates_after_reaction_delay() {
+        let mut ai = Adversary::new(Difficulty::Hard);
+        ai.record_strike("RUS", (-101.0, 48.0), 10);
+        ai.record_strike("RUS", (-101.0, 48.0), 20); // already pending
+        ai.record_strike(PLAYER_COUNTRY, (-101.0, 48.0), 20);
+        assert!(ai.due(50).is_empty());
+        assert_eq!(ai.due(100), vec!["RUS".to_string()]);
+        assert!(ai.due(200).is_empty());
+    }
+
+    #[test]
+    fn salvo_favors_heavy_targets() {
+        let mut ai = Adversary::new(Difficulty::Easy);
+        let cities = [
+            Target { lon: -74.0, lat: 40.7, weight: 1e9 },
+            Target { lon: -118.2, lat: 34.0, weight: 1.0 },
+        ];
+        for frame in 0..20 {
+            let salvo = ai.plan((37.6, 55.8), &cities, frame, 7);
+            assert_eq!(salvo.len(), 1);
+            // Easy scatter is ~1.4°
+            assert!((salvo[0].to.0 + 74.0).abs() < 2.0);
+        }
+        assert_eq!(ai.missiles_launched, 20);
+    }
+
+    #[test]
+    fn salvo_depends_only_on_frame_and_seed() {
+        let cities = [
+            Target { lon: -74.0, lat: 40.7, weight: 1.0 },
+            Target { lon: -118.2, lat: 34.0, weight: 1.0 },
+        ];
+        let mut a = Adversary::new(Difficulty::Hard);
+        let mut b = Adversary::new(Difficulty::Hard);
+        assert_eq!(a.plan((37.6, 55.8), &cities, 100, 1), b.plan((37.6, 55.8), &cities, 100, 1));
+        assert_ne!(a.plan((37.6, 55.8), &cities, 100, 1), b.plan((37.6, 55.8), &cities, 100, 2));
+    }
+}
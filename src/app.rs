@@ -1,20 +1,81 @@
-use crate::geo::{normalize_lat, normalize_lon};
-use crate::hash::{hash3, rand_simple};
-use crate::map::{Lod, MapRenderer, Projection, Viewport};
+use crate::ai::{Adversary, Difficulty, Target, PLAYER_COUNTRY};
+use crate::command::{Command, CommandLine, DataAction, GotoTarget, Layer, MemoryAction};
+use crate::data::{self, export};
+use crate::data::chunks::ChunkManager;
+use crate::data::fetch::{DataManager, Resolution};
+use crate::data::geocode::{Gazetteer, GeocodeService, Place};
+use crate::data::landcover::{Cover, LandCover};
+use crate::data::population::PopulationGrid;
+use crate::data::scenario::{Location, ScenarioEvent, ScenarioRunner};
+use crate::data::tiles::TileManager;
+use crate::colors::ColorDepth;
+use crate::dashboard::Dashboard;
+use crate::perf::FrameStats;
+use crate::events::{Event, EventKind, EventLog};
+use crate::faction::{self, Faction, FactionStats};
+use crate::game::{self, GameState, GameStats, Outcome, Standing};
+use crate::net::{self as netplay, Fields, Message, NetSession};
+use crate::spectate::{self, Diff, Spectator, SpectatorHost};
+use crate::stats_csv::{self, StatsCsv};
+use crate::heatmap::Heatmaps;
+use crate::history::{self, History, Snapshot};
+use crate::save::SaveFile;
+use crate::overlay::Overlays;
+use crate::geo::{destination_point, great_circle_km, great_circle_point, normalize_lat, normalize_lon, wrap_lon};
+use crate::hash::{hash2, hash3, rand_simple};
+use crate::describe;
+use crate::gesture::Gestures;
+use crate::macros::{Awaiting, Macros};
+use crate::memory::{self, MemoryBudget};
+use crate::map::{Infrastructure, Lod, MapRenderer, Projection};
+use crate::map::renderer::City;
 use crate::map::globe::GlobeViewport;
+use crate::map::{snapshot, text};
+use crate::sim::contagion::{MIN_INFECTION, SEED_INFECTION};
+use crate::sim::disasters::{self, Hazard, Volcano};
+use crate::targeting::{Mirv, Reticle};
+use crate::theme::{Theme, THEMES};
+use crate::sim::{Climate, ClockSpeed, Eruption, FalloutGrid, Flow, Hurricane, Outbreak, Plume, Response, SimClock, TravelNetwork, Tsunami, WeatherGrid, WindField};
+use crate::sim::travel::Hub;
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Instant;
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum WeaponType {
     Nuke,
     Bio,
     Emp,
     Chem,
+    /// Conventional thermobaric airburst — fires, no fallout
+    Airburst,
+    /// Seabed device that floods nearby coastlines
+    Tsunami,
+    /// Kinetic impactor — enormous blast and a global dust veil
+    Asteroid,
 }
 
 impl WeaponType {
+    /// Palette order; number keys select by position
+    pub const ALL: [WeaponType; 7] = [
+        WeaponType::Nuke,
+        WeaponType::Bio,
+        WeaponType::Emp,
+        WeaponType::Chem,
+        WeaponType::Airburst,
+        WeaponType::Tsunami,
+        WeaponType::Asteroid,
+    ];
+
     pub fn max_frames(self) -> u8 {
         match self {
             WeaponType::Emp => 30,
+            WeaponType::Asteroid => 90,
             _ => 60,
         }
     }
@@ -25,6 +86,9 @@ impl WeaponType {
             WeaponType::Bio => "☣",
             WeaponType::Emp => "⚡",
             WeaponType::Chem => "☠",
+            WeaponType::Airburst => "✸",
+            WeaponType::Tsunami => "≋",
+            WeaponType::Asteroid => "☄",
         }
     }
 
@@ -34,6 +98,79 @@ impl WeaponType {
             WeaponType::Bio => "BIO",
             WeaponType::Emp => "EMP",
             WeaponType::Chem => "CHEM",
+            WeaponType::Airburst => "AIRBURST",
+            WeaponType::Tsunami => "TSUNAMI",
+            WeaponType::Asteroid => "ASTEROID",
+        }
+    }
+
+    /// Nuclear devices take the selected warhead yield
+    pub fn is_nuclear(self) -> bool {
+        matches!(self, WeaponType::Nuke | WeaponType::Emp | WeaponType::Tsunami)
+    }
+
+    /// Fixed payload of non-nuclear weapons, for the weapon palette
+    pub fn payload(self) -> Option<&'static str> {
+        match self {
+            WeaponType::Bio => Some("20 kg agent"),
+            WeaponType::Chem => Some("5 t agent"),
+            WeaponType::Airburst => Some("40 t TNT"),
+            WeaponType::Asteroid => Some("1 km rock"),
+            _ => None,
+        }
+    }
+
+    /// Blast radius relative to a nuclear warhead of the same yield
+    pub fn radius_scale(self) -> f64 {
+        match self {
+            WeaponType::Emp => 1.5,
+            WeaponType::Airburst => 0.4,
+            WeaponType::Asteroid => 6.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Frames before the next launch is allowed
+    pub fn cooldown_frames(self) -> u64 {
+        match self {
+            WeaponType::Airburst => 6,
+            WeaponType::Tsunami => 60,
+            WeaponType::Asteroid => 300,
+            _ => 15,
+        }
+    }
+
+    /// Frames to restock one round of a limited stockpile
+    pub fn restock_frames(self) -> u64 {
+        match self {
+            WeaponType::Airburst => 180,
+            WeaponType::Nuke | WeaponType::Emp => 600,
+            WeaponType::Bio | WeaponType::Chem => 900,
+            WeaponType::Tsunami => 1800,
+            WeaponType::Asteroid => 3600,
+        }
+    }
+
+    /// Position in the palette
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|&w| w == self).unwrap_or(0)
+    }
+
+    /// Weapon by its label, in any case (`nuke`, `bio`, ...)
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|w| w.label().eq_ignore_ascii_case(s))
+    }
+
+    /// One-line summary of what the weapon leaves behind
+    pub fn effects(self) -> &'static str {
+        match self {
+            WeaponType::Nuke => "blast, firestorm, fallout",
+            WeaponType::Bio => "drifting pathogen cloud, heavy fallout",
+            WeaponType::Emp => "wide pulse, no fires or fallout",
+            WeaponType::Chem => "toxic cloud, fires, fallout",
+            WeaponType::Airburst => "fires only, no fallout",
+            WeaponType::Tsunami => "floods coasts (detonate at sea)",
+            WeaponType::Asteroid => "huge blast, global dust veil",
         }
     }
 }
@@ -48,7 +185,8 @@ pub struct Explosion {
     pub weapon_type: WeaponType,
 }
 
-/// A spreading fire
+/// A burning point: a fire cell's center, or a spark scattered inside
+/// one for drawing
 #[derive(Clone)]
 pub struct Fire {
     pub lon: f64,
@@ -57,29 +195,627 @@ pub struct Fire {
     pub weapon_type: WeaponType,
 }
 
-/// Radioactive fallout zone
+/// How a strike reaches its target
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Delivery {
+    /// ICBM from a land silo
+    Silo,
+    /// SLBM from the submarine — shorter range
+    Submarine,
+    /// Crewed bomber — slow and visible, but can be retargeted en route
+    Bomber,
+}
+
+impl Delivery {
+    pub fn next(self) -> Self {
+        match self {
+            Delivery::Silo => Delivery::Submarine,
+            Delivery::Submarine => Delivery::Bomber,
+            Delivery::Bomber => Delivery::Silo,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Delivery::Silo => "ICBM",
+            Delivery::Submarine => "SLBM",
+            Delivery::Bomber => "BOMBER",
+        }
+    }
+
+    /// By label, in any case (`icbm`, `slbm`, `bomber`)
+    pub fn parse(s: &str) -> Option<Self> {
+        [Delivery::Silo, Delivery::Submarine, Delivery::Bomber].into_iter().find(|d| d.label().eq_ignore_ascii_case(s))
+    }
+}
+
+/// Rounds on hand for one weapon. Without a capacity the supply is
+/// endless; with one, each launch takes a round and spent rounds come back
+/// one at a time on the weapon's restock timer.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stockpile {
+    pub capacity: Option<u32>,
+    pub remaining: u32,
+    /// Frames until the next round is restocked
+    pub restock_in: u64,
+}
+
+impl Stockpile {
+    pub fn new(capacity: Option<u32>) -> Self {
+        Self { capacity, remaining: capacity.unwrap_or(0), restock_in: 0 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.capacity.is_some() && self.remaining == 0
+    }
+
+    /// Use a round if there is one
+    pub fn take(&mut self, restock_frames: u64) -> bool {
+        if self.capacity.is_none() {
+            return true;
+        }
+        if self.remaining == 0 {
+            return false;
+        }
+        if self.restock_in == 0 {
+            self.restock_in = restock_frames;
+        }
+        self.remaining -= 1;
+        true
+    }
+
+    /// Advance the restock timer a frame
+    pub fn tick(&mut self, restock_frames: u64) {
+        let Some(capacity) = self.capacity else { return };
+        if self.remaining >= capacity || self.restock_in == 0 {
+            return;
+        }
+        self.restock_in -= 1;
+        if self.restock_in == 0 {
+            self.remaining += 1;
+            if self.remaining < capacity {
+                self.restock_in = restock_frames;
+            }
+        }
+    }
+}
+
+/// Selectable warhead yield. Blast, thermal and fallout radii all scale with
+/// the cube root of yield, like real overpressure and fireball radii.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Yield {
+    Kt15,
+    Kt300,
+    Mt1,
+    Mt50,
+}
+
+impl Yield {
+    /// Blast radius of a 1 MT warhead. Real 5 psi radii are a few km; this
+    /// is exaggerated so strikes read at continental zoom.
+    const BLAST_KM_PER_MT: f64 = 150.0;
+
+    pub fn next(self) -> Self {
+        match self {
+            Yield::Kt15 => Yield::Kt300,
+            Yield::Kt300 => Yield::Mt1,
+            Yield::Mt1 => Yield::Mt50,
+            Yield::Mt50 => Yield::Kt15,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Yield::Kt15 => "15 kt",
+            Yield::Kt300 => "300 kt",
+            Yield::Mt1 => "1 MT",
+            Yield::Mt50 => "50 MT",
+        }
+    }
+
+    pub fn kilotons(self) -> f64 {
+        match self {
+            Yield::Kt15 => 15.0,
+            Yield::Kt300 => 300.0,
+            Yield::Mt1 => 1_000.0,
+            Yield::Mt50 => 50_000.0,
+        }
+    }
+
+    /// `15kt`, `300kt`, `1mt` or `50mt` (case and spacing ignored)
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().replace(' ', "").as_str() {
+            "15kt" => Some(Yield::Kt15),
+            "300kt" => Some(Yield::Kt300),
+            "1mt" | "1000kt" => Some(Yield::Mt1),
+            "50mt" => Some(Yield::Mt50),
+            _ => None,
+        }
+    }
+
+    /// Blast radius: ∝ yield^(1/3)
+    pub fn blast_radius_km(self) -> f64 {
+        Self::BLAST_KM_PER_MT * (self.kilotons() / 1_000.0).cbrt()
+    }
+}
+
+/// A yield in kilotons, written the way `Yield::label` writes the presets:
+/// "15 kt", "1 MT", "1.5 MT"
+pub fn kilotons_label(kt: f64) -> String {
+    if kt < 1_000.0 {
+        format!("{kt:.0} kt")
+    } else {
+        format!("{} MT", (kt / 10.0).round() / 100.0)
+    }
+}
+
+/// Thermal (fire-starting) radius relative to the blast radius. Both scale
+/// as yield^(1/3), so the ratio is fixed.
+const THERMAL_RADIUS_RATIO: f64 = 1.4;
+
+/// Ballistic-missile submarine. Moves toward `destination` (always open water).
+#[derive(Clone)]
+pub struct Submarine {
+    pub lon: f64,
+    pub lat: f64,
+    pub destination: Option<(f64, f64)>,
+}
+
+impl Submarine {
+    pub const RANGE_KM: f64 = 4000.0;
+    pub const SPEED_KM_PER_FRAME: f64 = 12.0;
+}
+
+/// A bomber en route to its target; drops the weapon on arrival
+#[derive(Clone)]
+pub struct Bomber {
+    /// Airbase it took off from
+    pub origin: (f64, f64),
+    pub lon: f64,
+    pub lat: f64,
+    pub target: (f64, f64),
+    pub radius_km: f64,
+    pub weapon_type: WeaponType,
+    /// Warhead yield (kilotons), when known, for the event log
+    pub yield_kt: Option<f64>,
+    /// Recent positions, oldest first
+    pub trail: Vec<(f64, f64)>,
+}
+
+impl Bomber {
+    pub const SPEED_KM_PER_FRAME: f64 = 25.0;
+    const TRAIL_POINTS: usize = 40;
+}
+
+/// A missile launch site the player fires from
 #[derive(Clone)]
-pub struct Fallout {
+pub struct LaunchSite {
+    pub name: &'static str,
     pub lon: f64,
     pub lat: f64,
+}
+
+/// Simulation rates selectable with `<`/`>`
+const SIM_RATES: [f64; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+
+/// Most simulation steps run for one rendered frame
+const MAX_STEPS_PER_FRAME: usize = 16;
+
+/// Why the adversary can't be turned on
+const NO_ADVERSARY_IN_PANDEMIC: &str = "No adversary in pandemic mode";
+
+/// Most alerts held for the main loop to take
+const MAX_ALERTS: usize = 32;
+
+/// Most craters kept; every map cell is tested against each one, so the
+/// oldest give way past this
+const MAX_CRATERS: usize = 64;
+
+/// Zoom `:goto` flies to for bare coordinates
+const GOTO_COORDS_ZOOM: f64 = 10.0;
+
+/// Latitude panning Mercator toward a pole suggests the polar map at
+const POLAR_HINT_LAT: f64 = 75.0;
+
+/// Frames without input before the globe starts turning on its own (5 s)
+const AUTO_ROTATE_IDLE_FRAMES: u32 = 5 * 60;
+
+/// Auto-rotation speed: a full turn every two minutes, in radians/frame
+const AUTO_ROTATE_SPEED: f64 = std::f64::consts::TAU / (120.0 * 60.0);
+
+/// Globe spin kept per frame at zoom 1, before `friction` scales it
+const SPIN_DECAY: f64 = 0.995;
+
+/// Flat-map pan speed kept per frame, before `friction` scales it
+const PAN_DECAY: f64 = 0.9;
+
+/// Flat-map glide below this (pixels/frame) stops
+const MIN_PAN_SPEED: f64 = 0.2;
+
+/// Why nothing can be launched while the timeline shows the past
+const HISTORY_READ_ONLY: &str = "Viewing history: Esc returns to now";
+
+/// `0.25x`, `2x`
+pub fn format_rate(rate: f64) -> String {
+    format!("{rate}x")
+}
+
+/// Per-step mortality as the share of the infected lost a minute: `7.2%/min`
+pub fn format_lethality(mortality: f64) -> String {
+    let percent = Outbreak::lethality_per_minute(mortality) * 100.0;
+    if percent < 1.0 { format!("{percent:.2}%/min") } else { format!("{percent:.1}%/min") }
+}
+
+/// `tui-map-<unix secs>.<extension>` in the working directory
+fn timestamped_path(extension: &str) -> PathBuf {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    PathBuf::from(format!("tui-map-{secs}.{extension}"))
+}
+
+/// Where the clock starts in seeded runs: 2025-06-21 12:00 UTC
+const SEEDED_START_UNIX_SECS: f64 = 1_750_507_200.0;
+
+/// Built-in silo fields: (name, lon, lat)
+const DEFAULT_SILOS: &[(&str, f64, f64)] = &[
+    ("Minot", -101.35, 48.42),
+    ("Malmstrom", -111.18, 47.51),
+    ("F.E. Warren", -104.87, 41.13),
+];
+
+/// Silo fields for a player on the Eastern bloc: (name, lon, lat)
+const EASTERN_SILOS: &[(&str, f64, f64)] = &[
+    ("Kozelsk", 35.78, 54.03),
+    ("Tatishchevo", 45.60, 51.67),
+    ("Uzhur", 89.83, 55.32),
+];
+
+/// A missile in flight along a great-circle arc; detonates on arrival
+#[derive(Clone)]
+pub struct Projectile {
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    pub frame: u16,
     pub radius_km: f64,
+    pub weapon_type: WeaponType,
+    /// Launched by the adversary or the other player (ABM batteries only engage these)
+    pub hostile: bool,
+    /// Network id shared with the other player (0 if not replicated)
+    pub id: u32,
+    /// MIRV bus: where its warheads go once it splits
+    pub warheads: Vec<(f64, f64)>,
+    /// MIRV warhead: where its bus released it. `from` stays the launch
+    /// site, so the strike is still traced back to it.
+    pub released_at: Option<(f64, f64)>,
+    /// Warhead yield (kilotons), when known, for the event log
+    pub yield_kt: Option<f64>,
+}
+
+impl Projectile {
+    /// Flight time in frames (~2s at 60fps), independent of range
+    pub const FLIGHT_FRAMES: u16 = 120;
+    /// Frame a MIRV bus splits; its warheads fly the rest of the way
+    pub const SPLIT_FRAME: u16 = 84;
+    /// An asteroid's fall through the atmosphere (~0.75s at 60fps)
+    pub const ENTRY_FRAMES: u16 = 45;
+
+    /// Frames from launch (or release) to impact
+    pub fn flight_frames(&self) -> u16 {
+        if self.weapon_type == WeaponType::Asteroid {
+            Self::ENTRY_FRAMES
+        } else if self.released_at.is_some() {
+            Self::FLIGHT_FRAMES - Self::SPLIT_FRAME
+        } else {
+            Self::FLIGHT_FRAMES
+        }
+    }
+
+    /// Fraction of the flight completed, 0.0..=1.0
+    pub fn progress(&self) -> f64 {
+        (self.frame as f64 / self.flight_frames() as f64).min(1.0)
+    }
+
+    /// Ground-track position at flight fraction `t`
+    pub fn position_at(&self, t: f64) -> (f64, f64) {
+        let from = self.released_at.unwrap_or(self.from);
+        great_circle_point(from.0, from.1, self.to.0, self.to.1, t)
+    }
+
+    /// The warheads a bus releases at its current position, with ids both
+    /// players derive from the bus's
+    pub fn split(&self) -> Vec<Projectile> {
+        let at = self.position_at(self.progress());
+        self.warheads.iter().enumerate().map(|(i, &to)| Projectile {
+            from: self.from,
+            to,
+            frame: 0,
+            radius_km: self.radius_km,
+            weapon_type: self.weapon_type,
+            hostile: self.hostile,
+            id: if self.id == 0 { 0 } else { 1 << 31 | self.id << 5 | (i as u32 + 1) },
+            warheads: Vec::new(),
+            released_at: Some(at),
+            yield_kt: self.yield_kt,
+        }).collect()
+    }
+}
+
+/// Anti-ballistic-missile battery that engages hostile missiles passing within range
+#[derive(Clone)]
+pub struct DefenseSite {
+    pub lon: f64,
+    pub lat: f64,
+    /// Frames until the battery can fire again
+    pub cooldown: u16,
+    /// Frames left knocked offline by an EMP
+    pub blackout: u32,
+}
+
+impl DefenseSite {
+    pub const RANGE_KM: f64 = 1200.0;
+    pub const COOLDOWN_FRAMES: u16 = 90;
+
+    pub fn is_ready(&self) -> bool {
+        self.cooldown == 0 && self.blackout == 0
+    }
+
+    /// Kill probability against a missile `dist_km` away: ~85% overhead,
+    /// falling off linearly to nothing at the edge of range.
+    pub fn hit_probability(dist_km: f64) -> f64 {
+        (0.85 * (1.0 - dist_km / Self::RANGE_KM)).max(0.0)
+    }
+}
+
+/// Interceptor burst at the point of engagement (hit or miss)
+#[derive(Clone)]
+pub struct Interception {
+    pub lon: f64,
+    pub lat: f64,
+    pub frame: u8,
+    pub hit: bool,
+}
+
+impl Interception {
+    pub const FRAMES: u8 = 24;
+}
+
+/// Radioactive fallout zone, carried downwind as a plume from ground zero
+#[derive(Clone)]
+pub struct Fallout {
+    pub lon: f64,
+    pub lat: f64,
     pub intensity: u16, // Decays slowly over many frames
+    pub plume: Plume,
 }
 
-/// Persistent gas cloud that expands as it decays
+/// Persistent gas cloud that spreads and drifts with the wind as it decays
 #[derive(Clone)]
 pub struct GasCloud {
     pub lon: f64,
     pub lat: f64,
-    pub current_radius_km: f64,
-    pub max_radius_km: f64,
     pub intensity: u16,
     pub weapon_type: WeaponType,
+    pub plume: Plume,
+}
+
+/// Intensity of a fresh gas cloud, which loses a unit a frame
+const GAS_CLOUD_INTENSITY: u16 = 2000;
+
+/// Blast radius of adversary warheads
+const HOSTILE_RADIUS_KM: f64 = 120.0;
+
+/// How long an EMP knocks out power and ABM batteries (45s at 60fps)
+const EMP_BLACKOUT_FRAMES: u32 = 45 * 60;
+
+/// Flooding reach of a tsunami bomb, in blast radii
+const TSUNAMI_REACH: f64 = 4.0;
+
+/// A nuke at sea raises a smaller wave if there's coast within this many
+/// blast radii
+const NUKE_TSUNAMI_REACH: f64 = 3.0;
+const NUKE_TSUNAMI_STRENGTH: f64 = 0.35;
+
+/// Share of a coastal city a full-height wave kills
+const FLOOD_LETHALITY: f64 = 0.5;
+
+/// Dust veil added per asteroid impact
+const ASTEROID_DUST: f64 = 0.6;
+
+/// Soot and dust an asteroid impact throws into the stratosphere (Tg)
+const ASTEROID_SOOT_TG: f64 = 30.0;
+
+/// Ground track an asteroid streaks along before impact, and the bearing
+/// it comes in from (shallow entries are the common ones)
+const ASTEROID_ENTRY_KM: f64 = 2500.0;
+const ASTEROID_ENTRY_BEARING: f64 = 70.0;
+
+/// Crater radius as a share of the impact's blast radius
+const CRATER_RATIO: f64 = 0.05;
+
+/// Sulfate aerosols an eruption puts into the stratosphere, counted as
+/// soot (Pinatubo cooled the world about half a degree)
+const ERUPTION_AEROSOL_TG: f64 = 2.0;
+
+/// Frames between fresh lava flows at an erupting vent, and fires per flow
+const LAVA_FRAMES: u16 = 30;
+const LAVA_CELLS: u64 = 12;
+
+/// Ongoing damage rate under a fresh ash plume
+const ASH_RATE: f64 = 0.002;
+
+/// Share of a coastal city's people a category 5 eye wall kills per
+/// damage tick
+const HURRICANE_LETHALITY: f64 = 0.002;
+
+/// R0 steps by this much, up to `MAX_R0`
+const R0_STEP: f64 = 0.25;
+const MAX_R0: f64 = 10.0;
+
+/// Lethality scales by this factor a step, within bounds
+const LETHALITY_STEP: f64 = 1.5;
+const MIN_MORTALITY: f64 = 1e-5;
+const MAX_MORTALITY: f64 = 0.01;
+
+/// Radius of a wildfire the disaster roll starts
+const WILDFIRE_RADIUS_KM: f64 = 80.0;
+
+/// Fraction of the dust veil left after each step (~4 min half-life at 60fps)
+const DUST_RETAINED_PER_STEP: f64 = 0.99995;
+
+/// Fallout intensity lost per simulated second. At the default 60x clock
+/// this is ~60 units per wall second — a 1000-unit nuke plume lasts ~17s.
+const FALLOUT_DECAY_PER_SIM_SEC: f64 = 1.0;
+
+/// Ground dose laid down per unit of fallout damage rate (Gy). A fresh 1 Mt
+/// plume leaves several lethal doses near ground zero.
+const DOSE_GY_PER_RATE: f64 = 200.0;
+
+/// Frames between population recovery steps
+const RECOVERY_INTERVAL: u64 = 60;
+
+/// Logistic regrowth rate per recovery step. A city at half its size gets
+/// back to 90% in about half an hour of play.
+const RECOVERY_RATE: f64 = 0.0012;
+
+/// Ground dose (Gy) above which a city doesn't rebuild
+const RECOVERY_MAX_DOSE_GY: f32 = 0.5;
+
+/// What's burning, falling or drifting over one point, for the hover
+/// tooltip
+#[derive(Default, Debug, PartialEq)]
+pub struct Hazards {
+    /// Hottest fire in the 0.25° cell
+    pub fire: Option<(u8, WeaponType)>,
+    /// Fallout coming down from passing plumes (Gy per simulated hour)
+    pub fallout_gy_h: f64,
+    /// Dose already on the ground (Gy)
+    pub ground_gy: f32,
+    /// Gas concentration by weapon, as a share of a fresh cloud's center
+    pub gas: Vec<(WeaponType, f64)>,
+}
+
+impl Hazards {
+    /// Fallout rates and doses below this read as none
+    const MIN_GY: f64 = 0.01;
+    /// Gas thinner than this reads as clear air
+    const MIN_GAS: f64 = 0.01;
+
+    pub fn is_empty(&self) -> bool {
+        self.fire.is_none() && self.fallout_gy_h < Self::MIN_GY && (self.ground_gy as f64) < Self::MIN_GY && self.gas.is_empty()
+    }
+}
+
+/// First point of a corridor or area strike
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StrikeAnchor {
+    pub lon: f64,
+    pub lat: f64,
+    /// Screen cell it was set from, to tell a drag from a click
+    pub cell: (u16, u16),
+}
+
+/// A target marked for the next strike package, with the weapon and blast
+/// radius selected when it was marked
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QueuedStrike {
+    pub lon: f64,
+    pub lat: f64,
+    pub weapon: WeaponType,
+    pub radius_km: f64,
+    pub yield_kt: Option<f64>,
+}
+
+/// Most targets one strike package holds
+pub const MAX_QUEUED_STRIKES: usize = 24;
+
+/// Least frames between launches in a strike package
+const PACKAGE_STAGGER_FRAMES: u64 = 20;
+
+/// A pattern strike as it would launch now: its two points and targets
+pub struct PlannedStrike {
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    pub targets: Vec<(f64, f64)>,
+}
+
+/// Animated camera move (search results). Interpolates center along the
+/// shortest longitude path and zoom in log space.
+#[derive(Clone)]
+pub struct FlyTo {
+    from: (f64, f64, f64),
+    to: (f64, f64, f64),
+    frame: u32,
+}
+
+impl FlyTo {
+    const FRAMES: u32 = 45;
+
+    /// Camera (lon, lat, zoom) at the current frame, eased in and out.
+    fn sample(&self) -> (f64, f64, f64) {
+        let t = (self.frame as f64 / Self::FRAMES as f64).min(1.0);
+        let t = t * t * (3.0 - 2.0 * t);
+        let dlon = wrap_lon(self.to.0 - self.from.0);
+        let lon = wrap_lon(self.from.0 + dlon * t);
+        let lat = self.from.1 + (self.to.1 - self.from.1) * t;
+        let zoom = (self.from.2.ln() + (self.to.2.ln() - self.from.2.ln()) * t).exp();
+        (lon, lat, zoom)
+    }
+}
+
+/// Default zoom per scroll-wheel notch
+pub const DEFAULT_ZOOM_RATE: f64 = 1.1;
+
+/// Notches one wheel step counts for with Ctrl or Alt held
+const FAST_ZOOM_NOTCHES: i32 = 4;
+
+/// Share of the remaining wheel zoom applied each frame
+const ZOOM_EASE: f64 = 0.35;
+
+/// Wheel zoom still to apply, eased in over a few frames. Stored in log
+/// space so notches in and out cancel exactly.
+#[derive(Clone, Copy, Debug)]
+pub struct ZoomEase {
+    px: i32,
+    py: i32,
+    log_factor: f64,
+}
+
+/// Center lon/lat, zoom and projection, and the generations of the map
+/// data and the cities: what a view description depends on
+type ViewKey = (f64, f64, f64, &'static str, u64, u64);
+
+/// What fills the main area
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum View {
+    Map,
+    /// Casualty charts and the worst-hit cities
+    Stats,
+}
+
+/// How area overlays — fires, radiation, burn scars, population — are drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FillMode {
+    /// Shade glyphs (`░▒▓█`), one color per cell
+    Shades,
+    /// Half blocks, a color for each of the two halves of a cell
+    HalfBlocks,
 }
 
-/// Multi-resolution fire grid for viewport-aware rendering.
+impl FillMode {
+    /// Fill rows per character row
+    pub fn rows(self) -> usize {
+        match self {
+            Self::Shades => 1,
+            Self::HalfBlocks => 2,
+        }
+    }
+}
+
+/// One level of the fire field's hierarchy: the hottest fire per cell.
 /// Configurable cell resolution enables hierarchical spatial queries:
 /// coarse (1°) for zoomed-out, fine (0.25°) for medium zoom.
+#[derive(Clone)]
 pub struct FireGrid {
     /// Max intensity per cell (0 = no fire)
     pub cells: Vec<u8>,
@@ -88,6 +824,8 @@ pub struct FireGrid {
     pub width: usize,
     pub height: usize,
     pub resolution: f64,
+    /// Cells with any fire in them
+    pub burning: usize,
 }
 
 impl FireGrid {
@@ -101,25 +839,36 @@ impl FireGrid {
             width,
             height,
             resolution,
+            burning: 0,
         }
     }
 
-    /// Rebuild grid from fires Vec - called after fire updates
-    pub fn rebuild(&mut self, fires: &[Fire]) {
-        self.cells.fill(0);
-        for fire in fires {
-            let lon_idx = (normalize_lon(fire.lon) / self.resolution) as usize;
-            let lat_idx = (normalize_lat(fire.lat) / self.resolution) as usize;
-            let idx = lat_idx * self.width + lon_idx;
-            if idx < self.cells.len() {
-                if fire.intensity > self.cells[idx] {
-                    self.cells[idx] = fire.intensity;
-                    self.weapons[idx] = fire.weapon_type;
-                }
-            }
+    /// Set a cell's hottest fire, keeping the burning count
+    pub fn set(&mut self, idx: usize, intensity: u8, weapon: WeaponType) {
+        match (self.cells[idx] > 0, intensity > 0) {
+            (false, true) => self.burning += 1,
+            (true, false) => self.burning -= 1,
+            _ => {}
+        }
+        self.cells[idx] = intensity;
+        self.weapons[idx] = weapon;
+    }
+
+    /// Raise a cell to `intensity` if it isn't that hot already
+    fn raise(&mut self, idx: usize, intensity: u8, weapon: WeaponType) {
+        if intensity > self.cells[idx] {
+            self.set(idx, intensity, weapon);
         }
     }
 
+    /// Hottest fire in the cell over a point, if it's burning
+    pub fn at(&self, lon: f64, lat: f64) -> Option<(u8, WeaponType)> {
+        let col = ((normalize_lon(lon) / self.resolution) as usize).min(self.width - 1);
+        let row = ((normalize_lat(lat) / self.resolution) as usize).min(self.height - 1);
+        let idx = row * self.width + col;
+        (self.cells[idx] > 0).then(|| (self.cells[idx], self.weapons[idx]))
+    }
+
     /// Query fires within viewport bounds only (not all cells).
     /// Returns (lon, lat, intensity, weapon_type) at cell centers.
     pub fn fires_in_region(&self, min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64) -> Vec<(f64, f64, u8, WeaponType)> {
@@ -148,41 +897,683 @@ impl FireGrid {
     }
 }
 
-/// Application state
-pub struct App {
-    pub projection: Projection,
-    pub map_renderer: MapRenderer,
-    pub should_quit: bool,
-    /// Last mouse position for drag tracking
-    pub last_mouse: Option<(u16, u16)>,
-    /// Current mouse position for cursor marker
-    pub mouse_pos: Option<(u16, u16)>,
-    /// Active explosions
-    pub explosions: Vec<Explosion>,
-    /// Active fires
-    pub fires: Vec<Fire>,
-    /// Coarse 1° fire grid for zoomed-out rendering
-    pub fire_grid: FireGrid,
-    /// Fine 0.25° fire grid for medium-zoom rendering
-    pub fire_grid_fine: FireGrid,
-    /// Fallout zones
-    pub fallout: Vec<Fallout>,
-    /// Persistent gas clouds (Bio/Chem)
-    pub gas_clouds: Vec<GasCloud>,
-    /// Total casualties
-    pub casualties: u64,
+/// Fire cells per side of a 0.25° tile: 0.05° cells
+const FIRE_SUBCELLS: usize = 5;
+const FIRE_CELL_DEG: f64 = 0.25 / FIRE_SUBCELLS as f64;
+/// Points scattered through each fire cell when fires are drawn one by one
+const SPARKS_PER_CELL: u64 = 4;
+
+/// Degrees embers drift downwind per m/s of wind
+const EMBER_CARRY_DEG: f64 = 0.012;
+
+/// What a burning cell has to work with
+pub struct FireConditions {
+    /// Rain, 0 (dry) to 255 (downpour)
+    pub rain: u8,
+    /// Surface wind (u, v) in m/s
+    pub wind: (f64, f64),
+    pub cover: Cover,
+}
+
+/// The 0.05° fire cells of one 0.25° tile
+#[derive(Clone)]
+struct FireTile {
+    intensity: [u8; FIRE_SUBCELLS * FIRE_SUBCELLS],
+    weapon: [WeaponType; FIRE_SUBCELLS * FIRE_SUBCELLS],
+}
+
+/// Every fire on the map, held in a grid hierarchy updated in place. Fires
+/// burn in 0.05° cells, an intensity and weapon each, kept in the 0.25°
+/// tiles that have any. A tile holds the same cells as one cell of the
+/// `fine` level, and 4×4 of those make a `coarse` cell. Each level holds
+/// the hottest fire below it. Spreading and decay walk only burning cells,
+/// and room for fires is limited only by land.
+#[derive(Clone)]
+pub struct FireField {
+    /// Tiles with fires by `fine` index, ordered so stepping is repeatable
+    tiles: BTreeMap<u32, FireTile>,
+    /// Burning 0.05° cells
+    burning: usize,
+    /// 1° level for zoomed-out rendering
+    pub coarse: FireGrid,
+    /// 0.25° level for medium zoom, damage and burn scars
+    pub fine: FireGrid,
+}
+
+impl FireField {
+    pub fn new() -> Self {
+        Self { tiles: BTreeMap::new(), burning: 0, coarse: FireGrid::new(1.0), fine: FireGrid::new(0.25) }
+    }
+
+    /// Burning 0.05° cells
+    pub fn len(&self) -> usize {
+        self.burning
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.burning == 0
+    }
+
+    /// Tile and cell-in-tile holding a point
+    fn locate(&self, lon: f64, lat: f64) -> (u32, usize) {
+        let per_row = self.fine.width * FIRE_SUBCELLS;
+        let col = ((normalize_lon(lon) / FIRE_CELL_DEG) as usize).min(per_row - 1);
+        let row = ((normalize_lat(lat) / FIRE_CELL_DEG) as usize).min(self.fine.height * FIRE_SUBCELLS - 1);
+        let tile = (row / FIRE_SUBCELLS) * self.fine.width + col / FIRE_SUBCELLS;
+        (tile as u32, (row % FIRE_SUBCELLS) * FIRE_SUBCELLS + col % FIRE_SUBCELLS)
+    }
+
+    /// Center of a cell, with `width` tiles to a row
+    fn center(width: usize, tile: u32, sub: usize) -> (f64, f64) {
+        let tile = tile as usize;
+        let col = (tile % width) * FIRE_SUBCELLS + sub % FIRE_SUBCELLS;
+        let row = (tile / width) * FIRE_SUBCELLS + sub / FIRE_SUBCELLS;
+        ((col as f64 + 0.5) * FIRE_CELL_DEG - 180.0, (row as f64 + 0.5) * FIRE_CELL_DEG - 90.0)
+    }
+
+    /// `coarse` index of the cell over a `fine` one
+    fn coarse_index(&self, fine: usize) -> usize {
+        let ratio = (self.coarse.resolution / self.fine.resolution) as usize;
+        let (x, y) = (fine % self.fine.width, fine / self.fine.width);
+        (y / ratio) * self.coarse.width + x / ratio
+    }
+
+    /// Start a fire, or stoke one already burning in the same cell up to
+    /// `intensity`
+    pub fn ignite(&mut self, lon: f64, lat: f64, intensity: u8, weapon: WeaponType) {
+        if intensity == 0 {
+            return;
+        }
+        let (id, sub) = self.locate(lon, lat);
+        let tile = self.tiles.entry(id).or_insert_with(|| FireTile {
+            intensity: [0; FIRE_SUBCELLS * FIRE_SUBCELLS],
+            weapon: [WeaponType::Nuke; FIRE_SUBCELLS * FIRE_SUBCELLS],
+        });
+        if intensity <= tile.intensity[sub] {
+            return;
+        }
+        if tile.intensity[sub] == 0 {
+            self.burning += 1;
+        }
+        tile.intensity[sub] = intensity;
+        tile.weapon[sub] = weapon;
+        self.fine.raise(id as usize, intensity, weapon);
+        let coarse = self.coarse_index(id as usize);
+        self.coarse.raise(coarse, intensity, weapon);
+    }
+
+    /// Decay every burning cell (every fifth frame, faster in rain and on
+    /// thin fuel) and let the hot ones throw embers downwind, then bring the
+    /// `fine` and `coarse` levels up to date. Embers only catch where
+    /// `ground_at` finds land, as hot as its fuel allows. Returns the summed
+    /// intensity burning at the start of the step.
+    pub fn step(
+        &mut self,
+        frame: u64,
+        seed: u64,
+        conditions_at: impl Fn(f64, f64) -> FireConditions,
+        ground_at: impl Fn(f64, f64) -> Option<Cover>,
+    ) -> u64 {
+        let decay = frame.is_multiple_of(5);
+        let mut total: u64 = 0;
+        let mut spread = Vec::new();
+        let mut touched = Vec::with_capacity(self.tiles.len());
+        for (&id, tile) in self.tiles.iter_mut() {
+            let mut hottest = (0, WeaponType::Nuke);
+            for sub in 0..FIRE_SUBCELLS * FIRE_SUBCELLS {
+                let mut intensity = tile.intensity[sub];
+                if intensity == 0 {
+                    continue;
+                }
+                total += intensity as u64;
+                let (lon, lat) = Self::center(self.fine.width, id, sub);
+                let FireConditions { rain, wind: (u, v), cover } = conditions_at(lon, lat);
+
+                // Slow decay; rain knocks fires down up to 8× faster, and
+                // grass and desert burn out sooner than forest
+                if decay {
+                    intensity = intensity.saturating_sub((1 + rain / 32).saturating_mul(cover.burnout()));
+                }
+
+                // Fires spread like wildfire unless it's pouring — fastest
+                // through forest and in a strong wind, which carries the
+                // embers downwind
+                if intensity > 60 && rain < 96 {
+                    let key = hash3(id as u64 ^ seed, sub as u64, frame);
+                    let chance = cover.spread_chance() * (1.0 + u.hypot(v) / 10.0);
+                    if rand_simple(key) < chance {
+                        let spreads = if rand_simple(hash2(key, 1)) > 0.7 { 2 } else { 1 };
+                        let drift = (u * EMBER_CARRY_DEG / lat.to_radians().cos().max(0.1), v * EMBER_CARRY_DEG);
+                        for s in 0..spreads {
+                            let spread_seed = hash3(key, s, 2);
+                            let dist = (0.03 + rand_simple(spread_seed) * 0.15) * cover.reach();
+                            let angle = rand_simple(spread_seed.wrapping_mul(31337)) * std::f64::consts::TAU;
+                            spread.push((lon + dist * angle.cos() + drift.0, lat + dist * angle.sin() + drift.1, intensity, tile.weapon[sub]));
+                        }
+                    }
+                }
+
+                tile.intensity[sub] = intensity;
+                if intensity == 0 {
+                    self.burning -= 1;
+                } else if intensity > hottest.0 {
+                    hottest = (intensity, tile.weapon[sub]);
+                }
+            }
+            self.fine.set(id as usize, hottest.0, hottest.1);
+            touched.push(id);
+        }
+        self.tiles.retain(|&id, _| self.fine.cells[id as usize] > 0);
+
+        // Coarse cells over the tiles just stepped take their hottest again
+        let mut coarse: Vec<usize> = touched.iter().map(|&id| self.coarse_index(id as usize)).collect();
+        coarse.sort_unstable();
+        coarse.dedup();
+        for idx in coarse {
+            self.refresh_coarse(idx);
+        }
+
+        // New fires only take on land, and start burning next step. Thin
+        // fuel leaves them too cool to spread much further.
+        for (lon, lat, intensity, weapon) in spread {
+            if let Some(cover) = ground_at(lon, lat) {
+                self.ignite(lon, lat, ((intensity as f64 * cover.fuel()) as u8).saturating_sub(10), weapon);
+            }
+        }
+        total
+    }
+
+    /// A `coarse` cell takes the hottest of the `fine` cells under it again
+    fn refresh_coarse(&mut self, idx: usize) {
+        let ratio = (self.coarse.resolution / self.fine.resolution) as usize;
+        let (cx, cy) = (idx % self.coarse.width, idx / self.coarse.width);
+        let mut hottest = (0, WeaponType::Nuke);
+        for y in cy * ratio..(cy + 1) * ratio {
+            for x in cx * ratio..(cx + 1) * ratio {
+                let fine = y * self.fine.width + x;
+                if self.fine.cells[fine] > hottest.0 {
+                    hottest = (self.fine.cells[fine], self.fine.weapons[fine]);
+                }
+            }
+        }
+        self.coarse.set(idx, hottest.0, hottest.1);
+    }
+
+    /// Every burning 0.05° cell as (cell number, intensity, weapon), cells
+    /// numbered row by row from the south-west corner
+    pub fn cell_states(&self) -> impl Iterator<Item = (u32, u8, WeaponType)> + '_ {
+        let per_row = self.fine.width * FIRE_SUBCELLS;
+        self.tiles.iter().flat_map(move |(&id, tile)| {
+            let (tile_col, tile_row) = (id as usize % self.fine.width, id as usize / self.fine.width);
+            (0..FIRE_SUBCELLS * FIRE_SUBCELLS).filter(move |&sub| tile.intensity[sub] > 0).map(move |sub| {
+                let row = tile_row * FIRE_SUBCELLS + sub / FIRE_SUBCELLS;
+                let col = tile_col * FIRE_SUBCELLS + sub % FIRE_SUBCELLS;
+                ((row * per_row + col) as u32, tile.intensity[sub], tile.weapon[sub])
+            })
+        })
+    }
+
+    /// Set a cell numbered as in `cell_states` to exactly `intensity`, 0 to
+    /// put it out, for mirroring another instance's fires
+    pub fn set_cell(&mut self, cell: u32, intensity: u8, weapon: WeaponType) {
+        let per_row = self.fine.width * FIRE_SUBCELLS;
+        let (row, col) = (cell as usize / per_row, cell as usize % per_row);
+        if row >= self.fine.height * FIRE_SUBCELLS {
+            return;
+        }
+        let id = ((row / FIRE_SUBCELLS) * self.fine.width + col / FIRE_SUBCELLS) as u32;
+        let sub = (row % FIRE_SUBCELLS) * FIRE_SUBCELLS + col % FIRE_SUBCELLS;
+        if intensity == 0 && !self.tiles.contains_key(&id) {
+            return;
+        }
+        let tile = self.tiles.entry(id).or_insert_with(|| FireTile {
+            intensity: [0; FIRE_SUBCELLS * FIRE_SUBCELLS],
+            weapon: [WeaponType::Nuke; FIRE_SUBCELLS * FIRE_SUBCELLS],
+        });
+        match (tile.intensity[sub] > 0, intensity > 0) {
+            (false, true) => self.burning += 1,
+            (true, false) => self.burning -= 1,
+            _ => {}
+        }
+        tile.intensity[sub] = intensity;
+        tile.weapon[sub] = weapon;
+        let hottest = (0..FIRE_SUBCELLS * FIRE_SUBCELLS)
+            .max_by_key(|&i| tile.intensity[i])
+            .map_or((0, WeaponType::Nuke), |i| (tile.intensity[i], tile.weapon[i]));
+        if hottest.0 == 0 {
+            self.tiles.remove(&id);
+        }
+        self.fine.set(id as usize, hottest.0, hottest.1);
+        let coarse = self.coarse_index(id as usize);
+        self.refresh_coarse(coarse);
+    }
+
+    /// Every burning cell, as a fire at its center
+    pub fn cells(&self) -> impl Iterator<Item = Fire> + '_ {
+        self.tiles.iter().flat_map(move |(&id, tile)| {
+            (0..FIRE_SUBCELLS * FIRE_SUBCELLS).filter(move |&sub| tile.intensity[sub] > 0).map(move |sub| {
+                let (lon, lat) = Self::center(self.fine.width, id, sub);
+                Fire { lon, lat, intensity: tile.intensity[sub], weapon_type: tile.weapon[sub] }
+            })
+        })
+    }
+
+    /// Fires for drawing up close: a few points scattered through each
+    /// burning cell in the region, a little cooler or hotter than the cell.
+    /// Longitudes may run past ±180 to match an unwrapped view.
+    pub fn sparks_in(&self, min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64) -> impl Iterator<Item = Fire> + '_ {
+        // Shift a longitude by whole turns into the region, if it fits
+        let into_region = move |lon: f64| [lon, lon - 360.0, lon + 360.0].into_iter().find(|l| (min_lon..=max_lon).contains(l));
+        self.cells()
+            .filter(move |f| f.lat >= min_lat - FIRE_CELL_DEG && f.lat <= max_lat + FIRE_CELL_DEG)
+            .filter_map(move |f| Some(Fire { lon: into_region(f.lon)?, ..f }))
+            .flat_map(|fire| {
+                let key = hash2(fire.lon.to_bits(), fire.lat.to_bits());
+                (0..SPARKS_PER_CELL).map(move |i| {
+                    let jitter = |salt| (rand_simple(hash3(key, i, salt)) - 0.5) * FIRE_CELL_DEG;
+                    let heat = (rand_simple(hash3(key, i, 3)) * 30.0) as u8;
+                    Fire { lon: fire.lon + jitter(1), lat: fire.lat + jitter(2), intensity: fire.intensity.saturating_sub(heat), ..fire }
+                })
+            })
+    }
+
+    /// Burning tiles as (`fine` index, hottest fire)
+    pub fn burning_tiles(&self) -> impl Iterator<Item = (usize, u8)> + '_ {
+        self.tiles.keys().map(|&id| (id as usize, self.fine.cells[id as usize]))
+    }
+}
+
+impl Default for FireField {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An impact crater. Craters are never filled in, so like burn scars they
+/// stay on the map for the rest of the session, the newest `MAX_CRATERS`
+/// of them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Crater {
+    pub lon: f64,
+    pub lat: f64,
+    /// Out to the top of the rim
+    pub radius_km: f64,
+}
+
+impl Crater {
+    /// Ejecta blankets reach this many crater radii from the center
+    pub const EJECTA_REACH: f64 = 1.8;
+
+    /// Distance from the center in crater radii, if the point is within
+    /// the ejecta
+    pub fn reach(&self, lon: f64, lat: f64) -> Option<f64> {
+        // A degree of latitude is ~111 km, and no nearer in longitude
+        if (lat - self.lat).abs() * 111.0 > self.radius_km * Self::EJECTA_REACH {
+            return None;
+        }
+        let reach = great_circle_km(self.lon, self.lat, lon, lat) / self.radius_km;
+        (reach < Self::EJECTA_REACH).then_some(reach)
+    }
+}
+
+/// Scorched ground: the hottest fire each 0.25° cell (the fallout grid's
+/// cells) has ever seen. Scars never fade, so a long session accumulates its
+/// history on the map.
+#[derive(Clone)]
+pub struct BurnScarGrid {
+    cells: Vec<u8>,
+    burned: usize,
+}
+
+impl BurnScarGrid {
+    pub const RESOLUTION: f64 = FalloutGrid::RESOLUTION;
+    const WIDTH: usize = FalloutGrid::WIDTH;
+    const HEIGHT: usize = FalloutGrid::HEIGHT;
+
+    pub fn new() -> Self {
+        Self { cells: vec![0; Self::WIDTH * Self::HEIGHT], burned: 0 }
+    }
+
+    /// Scorch the cells under burning fires (max intensity wins). Fire
+    /// tiles are laid out like scar cells, so their indexes carry over.
+    pub fn record(&mut self, fires: &FireField) {
+        for (idx, intensity) in fires.burning_tiles() {
+            let cell = &mut self.cells[idx];
+            if *cell == 0 && intensity > 0 {
+                self.burned += 1;
+            }
+            *cell = (*cell).max(intensity);
+        }
+    }
+
+    /// Hottest burn a point has seen (0 = never burned)
+    pub fn burn_at(&self, lon: f64, lat: f64) -> u8 {
+        self.cells[FalloutGrid::index(lon, lat)]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.burned == 0
+    }
+
+    /// Ground ever burned (km²)
+    pub fn area_km2(&self) -> f64 {
+        let cell_km = Self::RESOLUTION * 111.0;
+        self.cells().map(|(row, _, _)| {
+            let lat = row as f64 * Self::RESOLUTION - 90.0 + Self::RESOLUTION / 2.0;
+            cell_km * cell_km * lat.to_radians().cos()
+        }).sum()
+    }
+
+    /// Burned cells as a count then (cell index, hottest burn) each, for
+    /// save files
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(4 + self.burned * 5);
+        out.extend_from_slice(&(self.burned as u32).to_le_bytes());
+        for (idx, &burn) in self.cells.iter().enumerate().filter(|&(_, &burn)| burn > 0) {
+            out.extend_from_slice(&(idx as u32).to_le_bytes());
+            out.push(burn);
+        }
+        out
+    }
+
+    pub fn decode(payload: &[u8]) -> Result<Self> {
+        let mut r = Fields { buf: payload };
+        let n = r.u32()? as usize;
+        if n > r.buf.len() / 5 {
+            bail!("{n} burn scars exceed payload");
+        }
+        let mut grid = Self::new();
+        for _ in 0..n {
+            let (idx, burn) = (r.u32()? as usize, r.u8()?);
+            let Some(cell) = grid.cells.get_mut(idx) else { bail!("burn scar cell {idx} out of range") };
+            if *cell == 0 && burn > 0 {
+                grid.burned += 1;
+            }
+            *cell = burn;
+        }
+        if !r.buf.is_empty() {
+            bail!("{} trailing bytes", r.buf.len());
+        }
+        Ok(grid)
+    }
+
+    /// Burned cells as (row, col, hottest burn)
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        self.cells.iter().enumerate()
+            .filter(|&(_, &burn)| burn > 0)
+            .map(|(idx, &burn)| (idx / Self::WIDTH, idx % Self::WIDTH, burn))
+    }
+}
+
+impl Default for BurnScarGrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// What scrubbing the timeline swaps out, kept whole so returning to
+/// "now" loses nothing the snapshots leave out
+struct LiveWorld {
+    fires: FireField,
+    /// (population, infection) of every city
+    cities: Vec<(u64, f32)>,
+    casualties: u64,
+    sim_time: f64,
+}
+
+/// Application state
+pub struct App {
+    pub projection: Projection,
+    pub map_renderer: MapRenderer,
+    pub should_quit: bool,
+    /// Last mouse position for drag tracking
+    pub last_mouse: Option<(u16, u16)>,
+    /// Current mouse position for cursor marker
+    pub mouse_pos: Option<(u16, u16)>,
+    /// Shift-drag rectangle to zoom to, as the (column, row) it started at
+    /// and the one the mouse is at now
+    pub box_zoom: Option<((u16, u16), (u16, u16))>,
+    /// Keyboard targeting: the arrow keys move the cursor instead of the
+    /// map, for terminals that don't report the mouse
+    pub crosshair: bool,
+    /// Terminal graphics protocol the basemap is drawn as an image with,
+    /// rather than in braille
+    pub graphics: Option<crate::graphics::Protocol>,
+    /// Cities wiped out and retaliations launched since the main loop last
+    /// took them, for the bell and desktop notifications. Only the latest
+    /// `MAX_ALERTS` are kept when nothing takes them.
+    pub alerts: Vec<String>,
+    /// Socket scripts drive the app through, answered between frames
+    pub control: Option<crate::control::ControlSocket>,
+    /// Active explosions
+    pub explosions: Vec<Explosion>,
+    /// Missiles in flight
+    pub projectiles: Vec<Projectile>,
+    /// Player launch sites
+    pub silos: Vec<LaunchSite>,
+    /// Launch site override (None = nearest silo to the target)
+    pub selected_silo: Option<usize>,
+    /// ABM batteries
+    pub defenses: Vec<DefenseSite>,
+    /// Interceptor bursts being animated
+    pub interceptions: Vec<Interception>,
+    /// Clicks place/remove ABM batteries instead of launching
+    pub placing_defense: bool,
+    /// How clicks pick strike targets
+    pub reticle: Reticle,
+    /// First point of a corridor or area strike, waiting for the second
+    pub strike_anchor: Option<StrikeAnchor>,
+    /// Targets marked for the next strike package, in launch order
+    pub strike_queue: Vec<QueuedStrike>,
+    /// Frame the package's next launch is due, while it's being launched
+    package_next_frame: Option<u64>,
+    /// How new strikes are delivered
+    pub delivery: Delivery,
+    pub submarine: Submarine,
+    pub bombers: Vec<Bomber>,
+    /// Retaliating opponent (None = free play)
+    pub adversary: Option<Adversary>,
+    /// Faction sidebar visible
+    pub show_sidebar: bool,
+    /// Recent strikes, intercepts and retaliation waves
+    pub events: EventLog,
+    /// Event log panel visible
+    pub show_event_log: bool,
+    /// Legend box listing the visible layers
+    pub show_legend: bool,
+    /// Map or stats dashboard
+    pub view: View,
+    /// Time series for the stats dashboard, sampled every step
+    pub dashboard: Dashboard,
+    /// The map's static layers as last composited, reused between frames
+    pub backdrop: crate::ui::Backdrop,
+    /// Where `K` writes screenshots (None = a timestamped PNG)
+    pub screenshot_path: Option<PathBuf>,
+    /// Screenshot resolution in pixels
+    pub screenshot_size: (u32, u32),
+    /// Where `Ctrl+E` writes GeoJSON exports (None = a timestamped file)
+    pub export_path: Option<PathBuf>,
+    /// Where `Ctrl+T` writes text dumps of the map (None = a timestamped file)
+    pub ansi_path: Option<PathBuf>,
+    /// Per-faction population, indexed by `Faction::index` (refreshed every 15 frames)
+    pub faction_stats: [FactionStats; 3],
+    /// Scripted timeline being played
+    pub scenario: Option<ScenarioRunner>,
+    /// Free play, an objective in progress, or the end summary
+    pub game: GameState,
+    pub stats: GameStats,
+    /// Connection to the other player in a two-player game
+    pub net: Option<NetSession>,
+    /// Broadcasting this world to spectators (`--broadcast`)
+    pub spectators: Option<SpectatorHost>,
+    /// Watching someone else's world (`--watch`): the simulation is theirs
+    pub spectating: Option<Spectator>,
+    /// Time series of the run's totals (`--stats-out`)
+    pub stats_out: Option<StatsCsv<File>>,
+    /// Time-lapse snapshots for the timeline scrubber
+    pub history: History,
+    /// The live world, put aside while the timeline shows an earlier one
+    live: Option<Box<LiveWorld>>,
+    /// Memory budget, and the detail tiers dropped to meet it
+    pub memory: MemoryBudget,
+    /// A dropped detail tier being read back on a worker thread
+    tier_reload: Option<(Lod, Receiver<Result<data::TierData>>)>,
+    /// Seed mixed into every random draw (fires, spreading, ABM rolls, AI
+    /// targeting); set for reproducible runs
+    pub seed: Option<u64>,
+    /// Simulation frozen (rendering and the camera keep going)
+    pub paused: bool,
+    /// Simulation steps per 60fps frame, from `SIM_RATES`
+    pub sim_rate: f64,
+    /// Fractional steps owed to the simulation
+    step_accum: f64,
+    /// Active fires
+    pub fires: FireField,
+    /// Ground scorched by past fires
+    pub burn_scars: BurnScarGrid,
+    /// Draw the burn scars
+    pub show_scars: bool,
+    /// Gridded population outside the city list (`--population`)
+    pub population: Option<PopulationGrid>,
+    /// What's on the ground, for fire spread (`--landcover`)
+    pub land_cover: LandCover,
+    /// Fallout zones
+    pub fallout: Vec<Fallout>,
+    /// Radiation dose left on the ground by fallout
+    pub radiation: FalloutGrid,
+    /// Persistent gas clouds (Bio/Chem)
+    pub gas_clouds: Vec<GasCloud>,
+    /// Total casualties
+    pub casualties: u64,
     /// Frame counter for animation randomness
     pub frame: u64,
     /// Currently selected weapon
     pub active_weapon: WeaponType,
+    /// Warhead yield for nuclear weapons
+    pub warhead_yield: Yield,
+    /// Rounds on hand, by palette position
+    pub stockpiles: [Stockpile; WeaponType::ALL.len()],
+    /// Missiles carry MIRV buses instead of single warheads
+    pub mirv_armed: bool,
+    pub mirv: Mirv,
+    /// Weapon palette popup open
+    pub show_arsenal: bool,
+    /// Detailed state panel over the map's top left corner
+    pub show_hud: bool,
+    /// Describe the view in words instead of drawing it, for screen readers
+    pub text_mode: bool,
+    /// Last description and the view it was for
+    description: Option<(ViewKey, String)>,
+    /// Global dust veil from asteroid impacts (0–1), dims the whole map
+    pub dust: f64,
+    /// Asteroid impact craters, oldest first, at most `MAX_CRATERS`
+    pub craters: Vec<Crater>,
+    /// City grid indices currently blacked out by an EMP
+    pub blacked_out: Vec<usize>,
+    /// Basic reproduction number for new bio outbreaks
+    pub r0: f64,
+    /// Fraction of the infected who die per contagion step, for new outbreaks
+    pub mortality: f64,
+    /// Peaceful pandemic mode: weapons are off and a click seeds an
+    /// outbreak in the nearest city
+    pub pandemic: bool,
+    /// Flight routes between the biggest cities, which outbreaks hop along
+    /// and refugees fly out on
+    pub travel: TravelNetwork,
+    /// Bio outbreak in progress (None once cured and cleared)
+    pub outbreak: Option<Outbreak>,
+    /// City grid indices with an active infection
+    pub infected: Vec<usize>,
+    /// Wavefronts from detonations at sea
+    pub tsunamis: Vec<Tsunami>,
+    /// Roll for volcanoes, hurricanes and wildfires at random
+    pub disasters: bool,
+    pub eruptions: Vec<Eruption>,
+    pub hurricanes: Vec<Hurricane>,
+    /// Survivors on the move from struck cities
+    pub refugees: Vec<Flow>,
+    /// Each country's emergency services, which keep some of the people
+    /// caught in fires, fallout and gas alive
+    pub response: Response,
+    /// Stratospheric soot and the cooling it causes
+    pub climate: Climate,
+    /// Draw the infection overlay
+    pub show_infection: bool,
+    /// Draw the flight routes of the travel network
+    pub show_flights: bool,
+    /// Draw the radiation dose overlay
+    pub show_radiation: bool,
+    /// Draw the population density choropleth
+    pub show_population: bool,
     /// Last frame when a nuke was launched (for cooldown)
     last_nuke_frame: u64,
     /// Globe horizontal spin momentum (radians/frame, vertical axis only)
     spin_velocity: f64,
+    /// Flat-map pan momentum (pixels/frame)
+    pan_velocity: (f64, f64),
+    /// Sub-pixel pan momentum carried to the next frame
+    pan_carry: (f64, f64),
+    /// How quickly momentum from a flick dies away: 1 is the default, 2
+    /// stops twice as fast. Shared by the globe's spin and flat-map glides.
+    pub friction: f64,
+    /// Spin the globe slowly whenever the user leaves it alone
+    pub auto_rotate: bool,
+    /// Rendered frames since the last key or mouse event
+    idle_frames: u32,
     /// Reusable fire map buffers (avoids per-frame allocation)
     pub fire_map_intensity: Vec<u8>,
     pub fire_map_weapon: Vec<WeaponType>,
     pub fire_map_dims: (usize, usize),
+    /// Vector-tile basemap fetcher (None = Natural Earth only)
+    pub tiles: Option<TileManager>,
+    /// Counties streamed from their chunks on disk (None = loaded whole, or none)
+    pub county_chunks: Option<ChunkManager>,
+    /// Search box contents while typing (None = closed)
+    pub search: Option<String>,
+    /// `:` command line and its history
+    pub command: CommandLine,
+    /// Recorded input registers and the one playing
+    pub macros: Macros,
+    /// Double clicks, scroll mode and trackpad jitter
+    pub gestures: Gestures,
+    /// Natural Earth downloads and checks
+    pub data_manager: DataManager,
+    /// Layers drawn over the map by providers registered from outside
+    pub overlays: Overlays,
+    /// Gridded data drawn as shaded ground
+    pub heatmaps: Heatmaps,
+    /// Place-name lookups for queries that miss the loaded cities
+    pub geocoder: Option<GeocodeService>,
+    /// One-line feedback shown in the status bar (search results, errors)
+    pub status_message: Option<String>,
+    /// In-progress camera animation
+    pub fly_to: Option<FlyTo>,
+    /// Zoom factor per scroll-wheel notch
+    pub zoom_rate: f64,
+    /// Wheel zoom easing toward the cursor
+    pub zoom_ease: Option<ZoomEase>,
+    /// Simulated UTC time — drives day/night and time-based decay
+    pub clock: SimClock,
+    /// Shade the night side of the terminator
+    pub show_night: bool,
+    /// Sun glint off the globe's oceans
+    pub show_glint: bool,
+    /// Glyphs or half blocks for area overlays
+    pub fill_mode: FillMode,
+    /// Colors of the map and everything drawn over it
+    pub theme: &'static Theme,
+    /// What the terminal can show; frames are quantized down to it
+    pub color_depth: ColorDepth,
+    /// Frame timings, the frame-rate cap and effect detail
+    pub perf: FrameStats,
+    /// Fractional fallout decay carried between ticks
+    fallout_decay_accum: f64,
+    /// Surface wind that carries fallout and gas plumes
+    pub wind: WindField,
+    /// Procedural cloud/rain cover (2°), rebuilt every 30 frames
+    pub weather: WeatherGrid,
+    /// Draw the cloud and rain overlay
+    pub show_weather: bool,
 }
 
 impl App {
@@ -193,276 +1584,2908 @@ impl App {
         let inner_height = height.saturating_sub(3); // 2 for border + 1 for status bar
         let pixel_width = inner_width * 2;
         let pixel_height = inner_height * 4;
+        let clock = SimClock::now().with_fixed_step(SimClock::FRAME_SECS);
+        let wind = WindField::seasonal(clock.unix_secs());
+        let mut weather = WeatherGrid::new(2.0);
+        weather.update(&wind, clock.unix_secs());
 
         Self {
-            projection: Projection::Globe(GlobeViewport::new(0.0, 20.0, pixel_width as f64 * 0.35, pixel_width, pixel_height)),
+            projection: Projection::new(GlobeViewport::new(0.0, 20.0, pixel_width as f64 * 0.35, pixel_width, pixel_height)),
             map_renderer: MapRenderer::new(),
             should_quit: false,
             last_mouse: None,
+            box_zoom: None,
+            crosshair: false,
+            graphics: None,
+            alerts: Vec::new(),
+            control: None,
             mouse_pos: None,
             explosions: Vec::new(),
-            fires: Vec::new(),
-            fire_grid: FireGrid::new(1.0),
-            fire_grid_fine: FireGrid::new(0.25),
+            projectiles: Vec::new(),
+            silos: DEFAULT_SILOS
+                .iter()
+                .map(|&(name, lon, lat)| LaunchSite { name, lon, lat })
+                .collect(),
+            selected_silo: None,
+            defenses: Vec::new(),
+            interceptions: Vec::new(),
+            placing_defense: false,
+            reticle: Reticle::Point,
+            strike_anchor: None,
+            strike_queue: Vec::new(),
+            package_next_frame: None,
+            delivery: Delivery::Silo,
+            // On station in the North Atlantic
+            submarine: Submarine { lon: -40.0, lat: 45.0, destination: None },
+            bombers: Vec::new(),
+            adversary: None,
+            show_sidebar: true,
+            events: EventLog::default(),
+            show_event_log: false,
+            show_legend: false,
+            view: View::Map,
+            dashboard: Dashboard::default(),
+            backdrop: Default::default(),
+            screenshot_path: None,
+            screenshot_size: (1920, 1080),
+            export_path: None,
+            ansi_path: None,
+            faction_stats: [FactionStats::default(); 3],
+            scenario: None,
+            game: GameState::FreePlay,
+            stats: GameStats { started_sim_secs: clock.unix_secs(), ..Default::default() },
+            net: None,
+            spectators: None,
+            stats_out: None,
+            spectating: None,
+            history: History::default(),
+            memory: MemoryBudget::default(),
+            tier_reload: None,
+            live: None,
+            seed: None,
+            paused: false,
+            sim_rate: 1.0,
+            step_accum: 0.0,
+            fires: FireField::new(),
+            burn_scars: BurnScarGrid::new(),
+            show_scars: true,
+            population: None,
+            land_cover: LandCover::climatic(),
             fallout: Vec::new(),
+            radiation: FalloutGrid::new(),
             gas_clouds: Vec::new(),
             casualties: 0,
             active_weapon: WeaponType::Nuke,
+            warhead_yield: Yield::Kt300,
+            stockpiles: Default::default(),
+            mirv_armed: false,
+            mirv: Mirv::default(),
+            show_arsenal: false,
+            show_hud: false,
+            text_mode: false,
+            description: None,
+            dust: 0.0,
+            craters: Vec::new(),
+            blacked_out: Vec::new(),
+            r0: Outbreak::DEFAULT_R0,
+            mortality: Outbreak::DEFAULT_MORTALITY,
+            pandemic: false,
+            travel: TravelNetwork::default(),
+            outbreak: None,
+            infected: Vec::new(),
+            tsunamis: Vec::new(),
+            disasters: false,
+            eruptions: Vec::new(),
+            hurricanes: Vec::new(),
+            refugees: Vec::new(),
+            response: Response::default(),
+            climate: Climate::default(),
+            show_infection: true,
+            show_flights: false,
+            show_radiation: false,
+            show_population: false,
             frame: 0,
             last_nuke_frame: 0,
             spin_velocity: 0.0,
+            pan_velocity: (0.0, 0.0),
+            pan_carry: (0.0, 0.0),
+            friction: 1.0,
+            auto_rotate: false,
+            idle_frames: 0,
             fire_map_intensity: Vec::new(),
             fire_map_weapon: Vec::new(),
             fire_map_dims: (0, 0),
+            tiles: None,
+            county_chunks: None,
+            search: None,
+            command: CommandLine::default(),
+            macros: Macros::default(),
+            gestures: Gestures::default(),
+            overlays: Overlays::default(),
+            heatmaps: Heatmaps::default(),
+            data_manager: DataManager::new(PathBuf::from("data")),
+            geocoder: None,
+            status_message: None,
+            fly_to: None,
+            zoom_rate: DEFAULT_ZOOM_RATE,
+            zoom_ease: None,
+            wind,
+            clock,
+            show_night: true,
+            show_glint: false,
+            fill_mode: FillMode::Shades,
+            theme: &THEMES[0],
+            color_depth: ColorDepth::TrueColor,
+            perf: FrameStats::default(),
+            fallout_decay_accum: 0.0,
+            weather,
+            show_weather: true,
+        }
+    }
+
+    /// Make the run reproducible: seed the random draws and start the clock at
+    /// a fixed instant
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = Some(seed);
+        self.clock = SimClock::new(SEEDED_START_UNIX_SECS, ClockSpeed::Minute).with_fixed_step(SimClock::FRAME_SECS);
+        self.stats.started_sim_secs = self.clock.unix_secs();
+        self.wind = WindField::seasonal(self.clock.unix_secs());
+        self.weather.update(&self.wind, self.clock.unix_secs());
+    }
+
+    fn rng_seed(&self) -> u64 {
+        self.seed.unwrap_or(0)
+    }
+
+    /// Update viewport size when terminal resizes
+    pub fn resize(&mut self, width: usize, height: usize) {
+        let inner_width = width.saturating_sub(2);
+        let inner_height = height.saturating_sub(3);
+        self.projection.set_size(inner_width * 2, inner_height * 4);
+    }
+
+    /// Pan the map
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        self.fly_to = None;
+        let before = self.projection.center_lat().abs();
+        self.projection.pan(dx, dy);
+        // Mercator stretches the poles out of reach: point at the map that
+        // doesn't, once, as the view gets there
+        let after = self.projection.center_lat().abs();
+        if self.projection.name() == "Mercator" && before < POLAR_HINT_LAT && after >= POLAR_HINT_LAT {
+            self.status_message = Some("Near the pole — :projection polar for a polar map".to_string());
+        }
+    }
+
+    /// Zoom in
+    pub fn zoom_in(&mut self) {
+        match self.mouse_pos {
+            Some((col, row)) => self.zoom_in_at(col, row),
+            None => {
+                self.fly_to = None;
+                self.projection.zoom_in();
+            }
+        }
+    }
+
+    /// Zoom out
+    pub fn zoom_out(&mut self) {
+        match self.mouse_pos {
+            Some((col, row)) => self.zoom_out_at(col, row),
+            None => {
+                self.fly_to = None;
+                self.projection.zoom_out();
+            }
+        }
+    }
+
+    /// Zoom in towards a screen position (terminal column/row)
+    pub fn zoom_in_at(&mut self, col: u16, row: u16) {
+        let px = ((col.saturating_sub(1)) as i32) * 2;
+        let py = ((row.saturating_sub(1)) as i32) * 4;
+        self.fly_to = None;
+        self.projection.zoom_in_at(px, py);
+    }
+
+    /// Zoom out from a screen position (terminal column/row)
+    pub fn zoom_out_at(&mut self, col: u16, row: u16) {
+        let px = ((col.saturating_sub(1)) as i32) * 2;
+        let py = ((row.saturating_sub(1)) as i32) * 4;
+        self.fly_to = None;
+        self.projection.zoom_out_at(px, py);
+    }
+
+    /// Scroll-wheel zoom at a screen position: `notches` of `zoom_rate`
+    /// (negative zooms out), four times as many when `fast`. Eased in over
+    /// the next frames; further notches add to what is still pending.
+    pub fn scroll_zoom(&mut self, col: u16, row: u16, notches: i32, fast: bool) {
+        let px = ((col.saturating_sub(1)) as i32) * 2;
+        let py = ((row.saturating_sub(1)) as i32) * 4;
+        let notches = if fast { notches * FAST_ZOOM_NOTCHES } else { notches };
+        let pending = self.zoom_ease.map_or(0.0, |ease| ease.log_factor);
+        self.fly_to = None;
+        self.zoom_ease = Some(ZoomEase { px, py, log_factor: pending + notches as f64 * self.zoom_rate.ln() });
+    }
+
+    /// Request quit
+    pub fn quit(&mut self) {
+        self.should_quit = true;
+    }
+
+    /// Get current zoom level as a string
+    pub fn zoom_level(&self) -> String {
+        format!("{:.1}x", self.projection.effective_zoom())
+    }
+
+    /// Get current center coordinates as a string
+    pub fn center_coords(&self) -> String {
+        let lat = self.projection.center_lat();
+        let lon = self.projection.center_lon();
+        format!(
+            "{:.1}°{}, {:.1}°{}",
+            lat.abs(),
+            if lat >= 0.0 { "N" } else { "S" },
+            lon.abs(),
+            if lon >= 0.0 { "E" } else { "W" }
+        )
+    }
+
+    /// Request tiles for the current view and hand any newly loaded ones
+    /// to the renderer. Cheap when nothing changed — call once per frame.
+    pub fn update_tiles(&mut self) {
+        let Some(tiles) = self.tiles.as_mut() else { return };
+        let (pixel_width, _) = self.projection.size();
+        let bounds = self.projection.visible_bounds();
+        if tiles.update(bounds, self.projection.effective_zoom(), pixel_width) {
+            let generation = tiles.generation;
+            self.map_renderer.set_tiles(tiles.visible_tiles(), generation);
+        }
+    }
+
+    /// Stream in the county chunks around the view while counties are
+    /// drawn, and swap the loaded ones in when the set changes
+    pub fn update_chunks(&mut self) {
+        self.refresh_chunks(false);
+    }
+
+    /// Read the county chunks the view needs before returning, for frames
+    /// drawn once: screenshots, text dumps and benchmarks
+    pub fn load_visible_chunks(&mut self) {
+        self.refresh_chunks(true);
+    }
+
+    fn refresh_chunks(&mut self, now: bool) {
+        let Some(chunks) = self.county_chunks.as_mut() else { return };
+        let (_, counties_zoom) = MapRenderer::detail_zooms(self.projection.is_globe());
+        let shown = self.map_renderer.settings.show_counties && self.projection.effective_zoom() >= counties_zoom;
+        let bounds = shown.then(|| self.projection.visible_bounds());
+        if if now { chunks.load_now(bounds) } else { chunks.update(bounds) } {
+            let current = std::mem::take(&mut self.map_renderer.counties);
+            self.map_renderer.set_counties(chunks.assemble(current));
+        }
+    }
+
+    /// Bytes held per layer, biggest first: the renderer's, the county
+    /// chunks' and the timeline's
+    pub fn memory_usage(&self) -> Vec<(&'static str, usize)> {
+        let mut usage = self.map_renderer.memory();
+        usage.push(("history", self.history.bytes()));
+        usage.sort_by_key(|&(_, bytes)| std::cmp::Reverse(bytes));
+        usage
+    }
+
+    /// `:memory`: the total against the budget, then each layer's share
+    pub fn memory_report(&self) -> String {
+        let usage = self.memory_usage();
+        let total: usize = usage.iter().map(|&(_, bytes)| bytes).sum();
+        let mut report = format!("Memory {}", memory::format_bytes(total));
+        if let Some(limit) = self.memory.limit {
+            report += &format!(" of {}", memory::format_bytes(limit));
+        }
+        let layers: Vec<String> = usage.iter()
+            .filter(|&&(_, bytes)| bytes > 0)
+            .map(|&(name, bytes)| format!("{name} {}", memory::format_bytes(bytes)))
+            .collect();
+        report += &format!(": {}", layers.join(", "));
+        if !self.memory.evicted.is_empty() {
+            let tiers: Vec<&str> = self.memory.evicted.iter().map(|&lod| if lod == Lod::High { "high detail" } else { "medium detail" }).collect();
+            report += &format!(" ({} dropped)", tiers.join(", "));
+        }
+        report
+    }
+
+    /// Keep memory under the budget: read back a dropped detail tier the
+    /// view needs again, and when over, drop county chunks out of view and
+    /// then the detail tier needed longest ago
+    pub fn update_memory(&mut self) {
+        let zoom = self.projection.effective_zoom();
+        let lod = Lod::from_zoom(zoom);
+        let check = self.memory.need(lod);
+        let dir = self.data_manager.dir.clone();
+        self.receive_tier();
+        if check && self.tier_reload.is_none() && self.memory.evicted.contains(&lod) {
+            let (tx, rx) = mpsc::channel();
+            let (dir, language) = (dir.clone(), self.map_renderer.settings.names.clone());
+            thread::spawn(move || {
+                let _ = tx.send(data::read_tier(&dir, lod, language.as_deref()));
+            });
+            self.tier_reload = Some((lod, rx));
+        }
+        let Some(limit) = self.memory.limit.filter(|_| check) else { return };
+        let total = |app: &Self| app.memory_usage().iter().map(|&(_, bytes)| bytes).sum::<usize>();
+        if total(self) <= limit {
+            return;
+        }
+        if let Some(chunks) = self.county_chunks.as_mut() {
+            if chunks.evict_unwanted() {
+                let current = std::mem::take(&mut self.map_renderer.counties);
+                self.map_renderer.set_counties(chunks.assemble(current));
+            }
+        }
+        while total(self) > limit {
+            let renderer = &self.map_renderer;
+            let Some(tier) = self.memory.victim(zoom, |t| renderer.has_tier(t) && data::has_tier_files(&dir, t)) else { break };
+            self.map_renderer.drop_tier(tier);
+            self.memory.evicted.push(tier);
+        }
+    }
+
+    /// Put a tier read back on the worker thread in, once it's read. It
+    /// stays listed as dropped until then, or if it couldn't be read.
+    fn receive_tier(&mut self) {
+        let Some((lod, reader)) = &self.tier_reload else { return };
+        let lod = *lod;
+        let result = match reader.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err(anyhow!("reader stopped")),
+        };
+        self.tier_reload = None;
+        match result {
+            Ok(tier) => {
+                data::install_tier(&mut self.map_renderer, tier);
+                self.memory.evicted.retain(|&t| t != lod);
+            }
+            Err(e) => self.status_message = Some(format!("Not reloaded: {e}")),
+        }
+    }
+
+    /// Open the search box
+    pub fn begin_search(&mut self) {
+        self.search = Some(String::new());
+        self.status_message = None;
+    }
+
+    /// Close the search box without searching
+    pub fn cancel_search(&mut self) {
+        self.search = None;
+    }
+
+    /// Run the typed query: loaded cities first, then the geocoder chain.
+    pub fn submit_search(&mut self) {
+        let Some(query) = self.search.take() else { return };
+        let query = query.trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+
+        if let Some(city) = self.map_renderer.find_city(&query) {
+            let (lon, lat, name) = (city.lon, city.lat, city.name.clone());
+            self.status_message = Some(format!("→ {name}"));
+            self.start_fly_to(lon, lat, 20.0);
+        } else if let Some(geocoder) = self.geocoder.as_mut() {
+            self.status_message = Some(format!("searching \"{query}\"…"));
+            geocoder.submit(query);
+        } else {
+            self.status_message = Some(format!("not found: {query}"));
+        }
+    }
+
+    /// Open the `:` command line
+    pub fn begin_command(&mut self) {
+        self.command.open();
+        self.status_message = None;
+    }
+
+    pub fn cancel_command(&mut self) {
+        self.command.close();
+    }
+
+    /// Run the typed command
+    pub fn submit_command(&mut self) {
+        if let Some(line) = self.command.submit() {
+            self.run_command(&line);
+        }
+    }
+
+    /// Run one `:` command, reporting what it did or why it couldn't
+    pub fn run_command(&mut self, line: &str) {
+        match Command::parse(line) {
+            Ok(command) => self.execute(command),
+            Err(e) => self.status_message = Some(format!(":{} — {e}", line.trim())),
+        }
+    }
+
+    pub(crate) fn execute(&mut self, command: Command) {
+        match command {
+            Command::Goto(target) => self.goto(target),
+            Command::Layer(layer, switch) => {
+                if layer == Layer::Density && self.population.is_none() {
+                    self.status_message = Some("No population raster loaded (--population FILE)".to_string());
+                    return;
+                }
+                let shown = self.layer_mut(layer);
+                *shown = switch.apply(*shown);
+                let state = if *shown { "on" } else { "off" };
+                self.status_message = Some(format!("Layer {}: {state}", layer.name()));
+            }
+            Command::Weapon(weapon) => {
+                self.select_weapon(weapon);
+                self.status_message = Some(format!("Weapon: {}", weapon.label()));
+            }
+            Command::Yield(warhead) => {
+                self.warhead_yield = warhead;
+                self.status_message = Some(format!("Warhead yield: {}", warhead.label()));
+            }
+            Command::Delivery(delivery) => {
+                self.delivery = delivery;
+                self.status_message = Some(format!("Delivery: {}", delivery.label()));
+            }
+            Command::Reticle(reticle) => {
+                // Cycle to it, for the hint about how to aim it
+                while self.reticle != reticle {
+                    self.cycle_reticle();
+                }
+            }
+            Command::Mirv(switch) => {
+                if switch.apply(self.mirv_armed) != self.mirv_armed {
+                    self.toggle_mirv();
+                }
+            }
+            Command::Ai(_) if self.pandemic => self.status_message = Some(NO_ADVERSARY_IN_PANDEMIC.to_string()),
+            Command::Ai(difficulty) => {
+                self.adversary = difficulty.map(Adversary::new);
+                self.status_message = Some(format!("Adversary: {}", difficulty.map_or("off", |d| d.label())));
+            }
+            Command::Theme(theme) => {
+                self.theme = theme;
+                self.status_message = Some(format!("Theme: {}", theme.name));
+            }
+            Command::Projection(name) => {
+                self.projection.switch_to(name);
+                self.status_message = Some(format!("Projection: {}", self.projection.name()));
+            }
+            Command::Seed(seed) => {
+                self.set_seed(seed);
+                self.status_message = Some(format!("Seed: {seed}"));
+            }
+            Command::Pandemic(switch) => {
+                if switch.apply(self.pandemic) != self.pandemic {
+                    self.set_pandemic(!self.pandemic);
+                }
+            }
+            Command::Disasters(switch) => {
+                if switch.apply(self.disasters) != self.disasters {
+                    self.toggle_disasters();
+                }
+            }
+            Command::Pause(switch) => {
+                if switch.apply(self.paused) != self.paused {
+                    self.toggle_pause();
+                }
+            }
+            Command::Save(path) => {
+                let path = match path {
+                    Some(p) if p.extension().is_none() => p.with_extension("sav"),
+                    Some(p) => p,
+                    None => timestamped_path("sav"),
+                };
+                self.status_message = Some(match self.save_game(&path) {
+                    Ok(()) => format!("Saved the world to {}", path.display()),
+                    Err(e) => format!("Save failed: {e:#}"),
+                });
+            }
+            Command::Load(path) => {
+                self.status_message = Some(match self.load_game(&path) {
+                    Ok(()) => format!("Loaded {}", path.display()),
+                    Err(e) => format!("Load failed: {e:#}"),
+                });
+            }
+            Command::Export(path) => {
+                let path = path.map(|p| if p.extension().is_some() { p } else { p.with_extension("geojson") });
+                self.export_geojson_to(path);
+            }
+            Command::Record(Some(register)) => self.record_macro(register),
+            Command::Record(None) => self.stop_macro_recording(true),
+            Command::Play(register, times) => self.play_macro(register, times),
+            Command::Overlay(None) => {
+                let list: Vec<String> = self.overlays.list()
+                    .map(|(name, shown)| format!("{name} {}", if shown { "on" } else { "off" }))
+                    .collect();
+                self.status_message = Some(format!("Overlays: {}", list.join(", ")));
+            }
+            Command::Overlay(Some((name, switch))) => {
+                self.status_message = Some(match self.overlays.set(&name, switch) {
+                    Ok((name, shown)) => format!("Overlay {name}: {}", if shown { "on" } else { "off" }),
+                    Err(e) => e,
+                });
+            }
+            Command::Heatmap(None) => {
+                let list: Vec<String> = self.heatmaps.layers.iter()
+                    .map(|h| format!("{} {}", h.name, if h.shown { "on" } else { "off" }))
+                    .collect();
+                self.status_message = Some(match list.is_empty() {
+                    true => "No heatmaps loaded (--heatmap <file>)".to_string(),
+                    false => format!("Heatmaps: {}", list.join(", ")),
+                });
+            }
+            Command::Heatmap(Some((name, switch))) => {
+                self.status_message = Some(match self.heatmaps.set(&name, switch) {
+                    Ok((name, shown)) => format!("Heatmap {name}: {}", if shown { "on" } else { "off" }),
+                    Err(e) => e,
+                });
+            }
+            Command::Data(DataAction::Show) => self.open_data_manager(),
+            Command::Data(DataAction::Download(resolution)) => self.download_data(resolution),
+            Command::Data(DataAction::Verify) => self.verify_data(),
+            Command::Memory(MemoryAction::Report) => self.status_message = Some(self.memory_report()),
+            Command::Memory(MemoryAction::Budget(limit)) => {
+                self.memory.limit = limit;
+                self.status_message = Some(match limit {
+                    Some(bytes) => format!("Memory budget: {}", memory::format_bytes(bytes)),
+                    None => "Memory budget: off".to_string(),
+                });
+            }
+            Command::Screenshot => self.take_screenshot(),
+            Command::Help => {
+                let names: Vec<&str> = crate::command::names().collect();
+                self.status_message = Some(format!("Commands: {} (Tab completes)", names.join(" ")));
+            }
+            Command::Quit => self.quit(),
+        }
+    }
+
+    /// `Ctrl+Q`: stop recording, or ask for a register to record into
+    pub fn begin_macro_record(&mut self) {
+        if self.macros.recording().is_some() {
+            self.stop_macro_recording(false);
+        } else {
+            self.macros.awaiting = Some(Awaiting::Record);
+            self.status_message = Some("Record macro into register (a–z, 0–9)".to_string());
+        }
+    }
+
+    /// `@`: ask for a register to play
+    pub fn begin_macro_play(&mut self) {
+        self.macros.awaiting = Some(Awaiting::Play);
+        self.status_message = Some("Play macro from register (a–z, 0–9, @ for the last)".to_string());
+    }
+
+    /// The key after `Ctrl+Q` or `@`: a register name, or anything else to
+    /// cancel
+    pub fn macro_register(&mut self, key: Option<char>) {
+        let Some(awaiting) = self.macros.awaiting.take() else { return };
+        match (awaiting, key) {
+            (Awaiting::Record, Some(c)) if Macros::is_register(c) => self.record_macro(c),
+            (Awaiting::Play, Some(c)) if Macros::is_register(c) || c == '@' => self.play_macro(c, 1),
+            _ => self.status_message = None,
+        }
+    }
+
+    fn record_macro(&mut self, register: char) {
+        self.macros.start_recording(register, Instant::now());
+        self.status_message = Some(format!("Recording into {register} (Ctrl+Q or :record stops)"));
+    }
+
+    /// Keep what was recorded, less the key or command that stopped it
+    fn stop_macro_recording(&mut self, from_command: bool) {
+        self.status_message = Some(match self.macros.stop_recording(from_command) {
+            Some((register, count)) => format!("Recorded {count} inputs into {register} (@{register} plays)"),
+            None => "Not recording".to_string(),
+        });
+    }
+
+    fn play_macro(&mut self, register: char, times: u32) {
+        self.status_message = Some(match self.macros.play(register, times, Instant::now()) {
+            Ok(register) => format!("Playing @{register} (Esc stops)"),
+            Err(e) => e,
+        });
+    }
+
+    /// Stop a playing macro where it is
+    pub fn stop_macro(&mut self) {
+        self.macros.stop_playing();
+        self.status_message = Some("Macro stopped".to_string());
+    }
+
+    /// Show the data manager with what's on disk now
+    pub fn open_data_manager(&mut self) {
+        let manager = &mut self.data_manager;
+        manager.select(manager.resolution);
+        manager.open = true;
+    }
+
+    pub fn close_data_manager(&mut self) {
+        self.data_manager.open = false;
+    }
+
+    /// Fetch the Natural Earth files a resolution still lacks
+    pub fn download_data(&mut self, resolution: Resolution) {
+        self.data_manager.open = true;
+        if self.data_manager.is_busy() {
+            self.status_message = Some("Already downloading".to_string());
+            return;
+        }
+        self.status_message = Some(match self.data_manager.download(resolution) {
+            0 => format!("All {} files are present", resolution.label()),
+            n => format!("Downloading {n} Natural Earth files ({})", resolution.label()),
+        });
+    }
+
+    /// Check downloaded files against their recorded checksums
+    pub fn verify_data(&mut self) {
+        self.data_manager.open = true;
+        self.data_manager.verify();
+    }
+
+    /// Follow the data manager's downloads. Call once per frame; true once
+    /// new files are in place and the world needs loading again.
+    pub fn update_downloads(&mut self) -> bool {
+        let Some(count) = self.data_manager.poll() else { return false };
+        self.status_message = Some(format!("Downloaded {count} files, map reloaded"));
+        true
+    }
+
+    /// Reload the layers fed by data files that changed on disk
+    pub fn reload_data_files(&mut self, changed: &[PathBuf]) {
+        let mut reloaded = Vec::new();
+        let mut not_cut = None;
+        for path in changed {
+            match data::reload_file(&mut self.map_renderer, &self.data_manager.dir, path) {
+                Ok(Some(layer)) => {
+                    match layer {
+                        "counties" => {
+                            if let Err(e) = data::cut_counties(&mut self.map_renderer, &self.data_manager.dir) {
+                                not_cut = Some(format!("{e:#}"));
+                            }
+                            self.county_chunks = data::county_chunks(&self.data_manager.dir);
+                        }
+                        // Routes and snapshots name cities by their index in the grid
+                        "cities" => {
+                            self.rebuild_travel();
+                            self.live = None;
+                            self.history.clear();
+                        }
+                        _ => {}
+                    }
+                    reloaded.push(layer);
+                }
+                Ok(None) => {}
+                Err(e) => self.status_message = Some(format!("Not reloaded: {e}")),
+            }
+        }
+        if !reloaded.is_empty() {
+            self.refresh_faction_stats();
+            let note = not_cut.map_or(String::new(), |e| format!(" ({e}; kept whole)"));
+            self.status_message = Some(format!("Reloaded {}{note}", reloaded.join(", ")));
+        }
+    }
+
+    /// The flag behind a `:layer`
+    fn layer_mut(&mut self, layer: Layer) -> &mut bool {
+        let settings = &mut self.map_renderer.settings;
+        match layer {
+            Layer::Coastlines => &mut settings.show_coastlines,
+            Layer::Borders => &mut settings.show_borders,
+            Layer::States => &mut settings.show_states,
+            Layer::Counties => &mut settings.show_counties,
+            Layer::Cities => &mut settings.show_cities,
+            Layer::Labels => &mut settings.show_labels,
+            Layer::Population => &mut settings.show_population,
+            Layer::Roads => &mut settings.show_roads,
+            Layer::Railways => &mut settings.show_railways,
+            Layer::Airports => &mut settings.show_airports,
+            Layer::Ports => &mut settings.show_ports,
+            Layer::Fills => &mut settings.show_fills,
+            Layer::Factions => &mut settings.show_factions,
+            Layer::Density => &mut self.show_population,
+            Layer::Weather => &mut self.show_weather,
+            Layer::Night => &mut self.show_night,
+            Layer::Glint => &mut self.show_glint,
+            Layer::Infection => &mut self.show_infection,
+            Layer::Flights => &mut self.show_flights,
+            Layer::Radiation => &mut self.show_radiation,
+            Layer::Scars => &mut self.show_scars,
+            Layer::Sidebar => &mut self.show_sidebar,
+            Layer::Events => &mut self.show_event_log,
+            Layer::Legend => &mut self.show_legend,
+            Layer::Hud => &mut self.show_hud,
+            Layer::Perf => &mut self.perf.show_hud,
+        }
+    }
+
+    /// Fly to coordinates, or to a place: a loaded city, then a country's
+    /// centroid, then whatever the geocoder chain finds
+    fn goto(&mut self, target: GotoTarget) {
+        match target {
+            GotoTarget::Coords { lon, lat } => {
+                self.status_message = Some(format!("→ {lat:.2}, {lon:.2}"));
+                self.start_fly_to(lon, lat, GOTO_COORDS_ZOOM);
+            }
+            GotoTarget::Place(name) => {
+                if let Some(city) = self.map_renderer.find_city(&name) {
+                    let (lon, lat, name) = (city.lon, city.lat, city.name.clone());
+                    self.status_message = Some(format!("→ {name}"));
+                    self.start_fly_to(lon, lat, 20.0);
+                } else if let Some((country, polygon)) = self.map_renderer.find_country(&name) {
+                    let (lon, lat) = polygon.centroid();
+                    let (min_lon, min_lat, max_lon, max_lat) = polygon.bbox;
+                    // Bigger countries get a wider view
+                    let span = (max_lon - min_lon).max(max_lat - min_lat).max(1.0);
+                    self.status_message = Some(format!("→ {country}"));
+                    self.start_fly_to(lon, lat, (9.0 - 1.6 * span.ln()).clamp(1.5, 8.0));
+                } else if let Some(geocoder) = self.geocoder.as_mut() {
+                    self.status_message = Some(format!("searching \"{name}\"…"));
+                    geocoder.submit(name);
+                } else {
+                    self.status_message = Some(format!("not found: {name}"));
+                }
+            }
+        }
+    }
+
+    /// Drain finished geocoder lookups. Call once per frame.
+    pub fn update_search(&mut self) {
+        while let Some((query, result)) = self.geocoder.as_mut().and_then(|g| g.poll()) {
+            match result {
+                Ok(Some(Place { name, lon, lat, zoom })) => {
+                    self.status_message = Some(format!("→ {name}"));
+                    self.start_fly_to(lon, lat, zoom);
+                }
+                Ok(None) => self.status_message = Some(format!("not found: {query}")),
+                Err(e) => self.status_message = Some(format!("search failed: {e}")),
+            }
+        }
+    }
+
+    /// Show what the overlays have to report, such as a feed failing.
+    /// Call once per frame.
+    pub fn update_overlays(&mut self) {
+        let messages = self.overlays.messages();
+        if !messages.is_empty() {
+            self.status_message = Some(messages.join("; "));
+        }
+    }
+
+    /// Animate the camera to (lon, lat) at the given effective zoom
+    pub fn start_fly_to(&mut self, lon: f64, lat: f64, zoom: f64) {
+        let from = (self.projection.center_lon(), self.projection.center_lat(), self.projection.effective_zoom());
+        self.stop_momentum();
+        self.fly_to = Some(FlyTo { from, to: (lon, lat, zoom.clamp(1.0, 100.0)), frame: 0 });
+        self.zoom_ease = None;
+    }
+
+    /// Simulated time and speed for the status bar, e.g. "2026-10-16 14:32Z 60x"
+    pub fn clock_status(&self) -> String {
+        format!("{} {}", self.clock.format_utc(), self.clock.speed().label())
+    }
+
+    /// Tile loading progress for the status bar, e.g. "tiles 12 (+3)"
+    pub fn tile_status(&self) -> Option<String> {
+        let tiles = self.tiles.as_ref()?;
+        let pending = tiles.pending_count();
+        Some(if pending > 0 {
+            format!("tiles {} (+{})", tiles.loaded_count(), pending)
+        } else {
+            format!("tiles {}", tiles.loaded_count())
+        })
+    }
+
+    /// Get current LOD level as a string
+    pub fn lod_level(&self) -> &'static str {
+        match Lod::from_zoom(self.projection.effective_zoom()) {
+            Lod::Low => "110m",
+            Lod::Medium => "50m",
+            Lod::High => "10m",
+        }
+    }
+
+    /// Handle mouse drag
+    pub fn handle_drag(&mut self, x: u16, y: u16) {
+        if let Some((last_x, last_y)) = self.last_mouse {
+            let dx = last_x as i32 - x as i32;
+            let dy = last_y as i32 - y as i32;
+            let zoom = self.projection.effective_zoom();
+            let scale = if zoom < 2.0 { 2 } else if zoom < 4.0 { 3 } else { 4 };
+            self.pan(dx * scale, dy * scale);
+
+            if self.projection.is_globe() {
+                // Pixels per radian of arc, i.e. the globe's radius
+                let radius = self.projection.deg_to_pixels(1.0_f64.to_degrees());
+                let ax = (dx * scale) as f64 / radius;
+                self.spin_velocity = self.spin_velocity * 0.5 + ax * 0.5;
+            } else {
+                let (vx, vy) = self.pan_velocity;
+                self.pan_velocity = (vx * 0.5 + (dx * scale) as f64 * 0.5, vy * 0.5 + (dy * scale) as f64 * 0.5);
+            }
+        }
+        self.last_mouse = Some((x, y));
+    }
+
+    /// Reset drag state when mouse button released — momentum persists
+    pub fn end_drag(&mut self) {
+        self.last_mouse = None;
+    }
+
+    /// Cancel spin and pan momentum (called on new drag start)
+    pub fn start_drag(&mut self, x: u16, y: u16) {
+        self.stop_momentum();
+        self.fly_to = None;
+        self.last_mouse = Some((x, y));
+    }
+
+    fn stop_momentum(&mut self) {
+        self.spin_velocity = 0.0;
+        self.pan_velocity = (0.0, 0.0);
+        self.pan_carry = (0.0, 0.0);
+    }
+
+    /// Start a zoom rectangle at a screen position (terminal column/row)
+    pub fn start_box_zoom(&mut self, col: u16, row: u16) {
+        self.stop_momentum();
+        self.fly_to = None;
+        self.box_zoom = Some(((col, row), (col, row)));
+    }
+
+    /// Stretch the zoom rectangle to the mouse
+    pub fn drag_box_zoom(&mut self, col: u16, row: u16) {
+        if let Some((_, corner)) = self.box_zoom.as_mut() {
+            *corner = (col, row);
+        }
+    }
+
+    /// Fly to fit whatever is inside the zoom rectangle. A rectangle too
+    /// small to mean anything, or wholly off the globe, does nothing.
+    pub fn finish_box_zoom(&mut self) {
+        let Some(((c0, r0), (c1, r1))) = self.box_zoom.take() else { return };
+        if c0.abs_diff(c1) < 2 || r0.abs_diff(r1) < 1 {
+            return;
+        }
+        // Screen cells to braille pixels, past the map's border
+        let x0 = (c0.min(c1).saturating_sub(1) as i32) * 2;
+        let x1 = (c0.max(c1).saturating_sub(1) as i32) * 2 + 1;
+        let y0 = (r0.min(r1).saturating_sub(1) as i32) * 4;
+        let y1 = (r0.max(r1).saturating_sub(1) as i32) * 4 + 3;
+        // Sample a grid over the rectangle rather than just its corners:
+        // on the globe the edges bulge, and some of it may be off the disc
+        const SAMPLES: i32 = 8;
+        let points: Vec<(f64, f64)> = (0..=SAMPLES)
+            .flat_map(|i| (0..=SAMPLES).map(move |j| (x0 + (x1 - x0) * i / SAMPLES, y0 + (y1 - y0) * j / SAMPLES)))
+            .filter_map(|(px, py)| self.projection.unproject(px, py))
+            .collect();
+        if let Some((lon, lat, zoom)) = self.projection.fit(&points) {
+            self.start_fly_to(lon, lat, zoom);
+        }
+    }
+
+    /// Turn keyboard targeting on or off. Turned on, the crosshair starts
+    /// where the mouse last was, or in the middle of the map.
+    pub fn toggle_crosshair(&mut self) {
+        self.crosshair = !self.crosshair;
+        if self.crosshair {
+            let (cols, rows) = self.map_cells();
+            let inside = self.mouse_pos.is_some_and(|(col, row)| (1..=cols).contains(&col) && (1..=rows).contains(&row));
+            if !inside {
+                self.mouse_pos = Some((cols / 2 + 1, rows / 2 + 1));
+            }
+            self.status_message = Some("Crosshair: arrows aim, Enter fires, Tab next city, Esc done".to_string());
+        } else {
+            self.status_message = Some("Crosshair off".to_string());
+        }
+    }
+
+    /// Move the crosshair by whole cells. Pushed against the edge of the
+    /// map, it pans the map instead.
+    pub fn move_crosshair(&mut self, dx: i32, dy: i32) {
+        let (cols, rows) = self.map_cells();
+        let (col, row) = self.mouse_pos.unwrap_or((cols / 2 + 1, rows / 2 + 1));
+        let to_col = (col as i32 + dx).clamp(1, cols.max(1) as i32);
+        let to_row = (row as i32 + dy).clamp(1, rows.max(1) as i32);
+        let (over_x, over_y) = (col as i32 + dx - to_col, row as i32 + dy - to_row);
+        if over_x != 0 || over_y != 0 {
+            self.pan(over_x * 2, over_y * 4);
+        }
+        self.mouse_pos = Some((to_col as u16, to_row as u16));
+    }
+
+    /// Put the crosshair on the next city on screen, biggest first, after
+    /// the one it's on now (or before it, going `back`)
+    pub fn crosshair_to_next_city(&mut self, back: bool) {
+        let (cols, rows) = self.map_cells();
+        let projection = &self.projection;
+        let mut cities: Vec<(&City, (u16, u16))> = self
+            .map_renderer
+            .city_grid
+            .iter()
+            .filter_map(|city| {
+                let (px, py) = projection.project_point(city.lon, city.lat)?;
+                let cell = (px.div_euclid(2) + 1, py.div_euclid(4) + 1);
+                let on_map = (1..=cols as i32).contains(&cell.0) && (1..=rows as i32).contains(&cell.1);
+                on_map.then_some((city, (cell.0 as u16, cell.1 as u16)))
+            })
+            .collect();
+        if cities.is_empty() {
+            self.status_message = Some("No cities in view".to_string());
+            return;
+        }
+        cities.sort_by(|a, b| b.0.population.cmp(&a.0.population).then_with(|| a.0.name.cmp(&b.0.name)));
+        let n = cities.len();
+        let next = match cities.iter().position(|&(_, cell)| Some(cell) == self.mouse_pos) {
+            Some(i) if back => (i + n - 1) % n,
+            Some(i) => (i + 1) % n,
+            None if back => n - 1,
+            None => 0,
+        };
+        let (city, cell) = cities[next];
+        self.status_message = Some(format!("⌖ {} ({})", city.name, city.cached_pop_label));
+        self.mouse_pos = Some(cell);
+    }
+
+    /// The map's size in terminal cells, inside its border
+    fn map_cells(&self) -> (u16, u16) {
+        let (width, height) = self.projection.size();
+        ((width / 2) as u16, (height / 4) as u16)
+    }
+
+    /// Update mouse cursor position
+    pub fn set_mouse_pos(&mut self, col: u16, row: u16) {
+        self.mouse_pos = Some((col, row));
+    }
+
+    /// Get mouse position in braille pixel coordinates (for rendering marker)
+    pub fn mouse_pixel_pos(&self) -> Option<(i32, i32)> {
+        self.mouse_pos.map(|(col, row)| {
+            // Convert terminal coords to braille pixel coords
+            // Account for border (1 cell offset)
+            let px = ((col.saturating_sub(1)) as i32) * 2;
+            let py = ((row.saturating_sub(1)) as i32) * 4;
+            (px, py)
+        })
+    }
+
+    /// Fire, fallout and gas at a point. Fallout rates come from the same
+    /// per-puff doses the ground takes every 10 frames, spread over the
+    /// simulated time those frames cover at the current clock speed.
+    pub fn hazards_at(&self, lon: f64, lat: f64) -> Hazards {
+        let ticks_per_hour = 3600.0 / (10.0 * self.clock.speed().multiplier() / 60.0);
+        let fallout_gy_h = self.fallout.iter()
+            .map(|z| (z.intensity as f64 / 10000.0) * 0.05 * z.plume.concentration_at(lon, lat))
+            .sum::<f64>() * DOSE_GY_PER_RATE * ticks_per_hour;
+        let mut gas: Vec<(WeaponType, f64)> = Vec::new();
+        for cloud in &self.gas_clouds {
+            let share = cloud.intensity as f64 / GAS_CLOUD_INTENSITY as f64 * cloud.plume.concentration_at(lon, lat);
+            match gas.iter_mut().find(|(w, _)| *w == cloud.weapon_type) {
+                Some((_, c)) => *c += share,
+                None => gas.push((cloud.weapon_type, share)),
+            }
+        }
+        gas.retain(|&(_, c)| c >= Hazards::MIN_GAS);
+        Hazards { fire: self.fires.fine.at(lon, lat), fallout_gy_h, ground_gy: self.radiation.dose_at(lon, lat), gas }
+    }
+
+    /// Where the mouse is on the map and what's there, if anything
+    pub fn hovered_hazards(&self) -> Option<(f64, f64, Hazards)> {
+        let (px, py) = self.mouse_pixel_pos()?;
+        let (lon, lat) = self.projection.unproject(px, py)?;
+        let hazards = self.hazards_at(lon, lat);
+        (!hazards.is_empty()).then_some((lon, lat, hazards))
+    }
+
+    /// Select active weapon
+    pub fn select_weapon(&mut self, weapon: WeaponType) {
+        self.active_weapon = weapon;
+    }
+
+    /// Select by palette position (number keys); empty slots are ignored
+    pub fn select_weapon_slot(&mut self, slot: usize) {
+        if let Some(&weapon) = WeaponType::ALL.get(slot) {
+            self.select_weapon(weapon);
+        }
+    }
+
+    /// Show/hide the weapon palette
+    pub fn toggle_arsenal(&mut self) {
+        self.show_arsenal = !self.show_arsenal;
+    }
+
+    /// Show/hide the detailed HUD
+    pub fn toggle_hud(&mut self) {
+        self.show_hud = !self.show_hud;
+    }
+
+    /// Switch between drawing the map and describing it
+    pub fn toggle_text_mode(&mut self) {
+        self.text_mode = !self.text_mode;
+        self.status_message = Some(format!("Text mode: {}", if self.text_mode { "on" } else { "off" }));
+    }
+
+    /// The view in words, rebuilt only when the view moves or the world in
+    /// it changes, so a screen reader isn't handed the same text again
+    /// every frame
+    pub fn view_description(&mut self) -> &str {
+        let view = (
+            self.projection.center_lon(),
+            self.projection.center_lat(),
+            self.projection.effective_zoom(),
+            self.projection.name(),
+            self.map_renderer.data_generation(),
+            self.map_renderer.city_grid.generation(),
+        );
+        if self.description.as_ref().is_none_or(|(at, _)| *at != view) {
+            self.description = Some((view, describe::describe(&self.map_renderer, &self.projection)));
+        }
+        self.description.as_ref().map_or("", |(_, text)| text)
+    }
+
+    /// Surface wind (u, v in m/s) under the mouse, else at the view's center
+    pub fn hud_wind(&self) -> (f64, f64) {
+        let at = self.mouse_pixel_pos().and_then(|(px, py)| self.projection.unproject(px, py));
+        let (lon, lat) = at.unwrap_or((self.projection.center_lon(), self.projection.center_lat()));
+        self.wind.sample(lon, lat)
+    }
+
+    /// Cycle the warhead yield (15 kt → 300 kt → 1 MT → 50 MT)
+    pub fn cycle_yield(&mut self) {
+        self.warhead_yield = self.warhead_yield.next();
+        self.status_message = Some(format!("Warhead yield: {}", self.warhead_yield.label()));
+    }
+
+    /// Arm or disarm MIRVs on missile launches
+    pub fn toggle_mirv(&mut self) {
+        self.mirv_armed = !self.mirv_armed;
+        self.status_message = Some(if self.mirv_armed {
+            format!("MIRV: {} warheads over {:.0} km", self.mirv.warheads, self.mirv.spread_km)
+        } else {
+            "MIRV: off".to_string()
+        });
+    }
+
+    pub fn toggle_disasters(&mut self) {
+        self.disasters = !self.disasters;
+        self.status_message = Some(format!("Natural disasters: {}", if self.disasters { "on" } else { "off" }));
+    }
+
+    /// Blast radius of a weapon. Nuclear weapons use the selected yield;
+    /// the rest are sized against a 1 MT warhead.
+    pub fn blast_radius_km(&self, weapon: WeaponType) -> f64 {
+        let warhead = if weapon.is_nuclear() { self.warhead_yield } else { Yield::Mt1 };
+        warhead.blast_radius_km() * weapon.radius_scale()
+    }
+
+    /// Warhead yield of a weapon in kilotons: the selected yield for nuclear
+    /// weapons, none for the rest
+    pub fn yield_kt(&self, weapon: WeaponType) -> Option<f64> {
+        weapon.is_nuclear().then(|| self.warhead_yield.kilotons())
+    }
+
+    /// Yield (nuclear) or payload shown for a weapon
+    pub fn payload_label(&self, weapon: WeaponType) -> &'static str {
+        weapon.payload().unwrap_or(self.warhead_yield.label())
+    }
+
+    /// Cycle the launch site: nearest → each silo in turn → nearest
+    pub fn cycle_launch_site(&mut self) {
+        self.selected_silo = match self.selected_silo {
+            None if !self.silos.is_empty() => Some(0),
+            Some(i) if i + 1 < self.silos.len() => Some(i + 1),
+            _ => None,
+        };
+        self.status_message = Some(format!("Launch site: {}", self.launch_site_label()));
+    }
+
+    /// The selected silo, or "nearest silo"
+    pub fn launch_site_label(&self) -> &'static str {
+        self.selected_silo.map_or("nearest silo", |i| self.silos[i].name)
+    }
+
+    /// Where a missile aimed at (lon, lat) launches from
+    fn launch_origin(&self, lon: f64, lat: f64) -> (f64, f64) {
+        let site = match self.selected_silo {
+            Some(i) => self.silos.get(i),
+            None => self.silos.iter().min_by(|a, b| {
+                let da = fast_distance_km(a.lon, a.lat, lon, lat);
+                let db = fast_distance_km(b.lon, b.lat, lon, lat);
+                da.total_cmp(&db)
+            }),
+        };
+        // No silos — strike lands where it's aimed with no visible flight
+        site.map_or((lon, lat), |s| (s.lon, s.lat))
+    }
+
+    /// Cycle delivery platform: ICBM → SLBM → bomber
+    pub fn cycle_delivery(&mut self) {
+        self.delivery = self.delivery.next();
+        self.status_message = Some(format!("Delivery: {}", self.delivery.label()));
+    }
+
+    /// Send the submarine to the cursor (SLBM mode) or retarget the most
+    /// recently launched bomber still in the air (bomber mode)
+    pub fn redirect_platform(&mut self, col: u16, row: u16) {
+        let px = ((col.saturating_sub(1)) as i32) * 2;
+        let py = ((row.saturating_sub(1)) as i32) * 4;
+        let Some((lon, lat)) = self.projection.unproject(px, py) else {
+            return;
+        };
+        match self.delivery {
+            Delivery::Submarine => {
+                if self.map_renderer.is_on_land(lon, lat) {
+                    self.status_message = Some("Submarines need open water".to_string());
+                } else {
+                    self.submarine.destination = Some((lon, lat));
+                    self.status_message = Some("Submarine under way".to_string());
+                }
+            }
+            Delivery::Bomber => match self.bombers.last_mut() {
+                Some(bomber) => {
+                    bomber.target = (lon, lat);
+                    self.status_message = Some("Bomber retargeted".to_string());
+                }
+                None => self.status_message = Some("No bomber airborne".to_string()),
+            },
+            Delivery::Silo => {}
+        }
+    }
+
+    /// Move the submarine and bombers; bombers that reach their target drop
+    fn update_platforms(&mut self) {
+        let sub = &mut self.submarine;
+        if let Some((dlon, dlat)) = sub.destination {
+            let remaining = great_circle_km(sub.lon, sub.lat, dlon, dlat);
+            if remaining <= Submarine::SPEED_KM_PER_FRAME {
+                (sub.lon, sub.lat) = (dlon, dlat);
+                sub.destination = None;
+            } else {
+                let t = Submarine::SPEED_KM_PER_FRAME / remaining;
+                (sub.lon, sub.lat) = great_circle_point(sub.lon, sub.lat, dlon, dlat, t);
+            }
+        }
+
+        let frame = self.frame;
+        let mut arrived = Vec::new();
+        self.bombers.retain_mut(|b| {
+            if frame.is_multiple_of(3) {
+                b.trail.push((b.lon, b.lat));
+                if b.trail.len() > Bomber::TRAIL_POINTS {
+                    b.trail.remove(0);
+                }
+            }
+            let (tlon, tlat) = b.target;
+            let remaining = great_circle_km(b.lon, b.lat, tlon, tlat);
+            if remaining <= Bomber::SPEED_KM_PER_FRAME {
+                arrived.push((b.origin, b.target, b.weapon_type, b.radius_km, b.yield_kt));
+                return false;
+            }
+            let t = Bomber::SPEED_KM_PER_FRAME / remaining;
+            (b.lon, b.lat) = great_circle_point(b.lon, b.lat, tlon, tlat, t);
+            true
+        });
+        for (origin, (lon, lat), weapon, radius_km, yield_kt) in arrived {
+            if let Some(net) = self.net.as_ref() {
+                net.send(Message::Detonation { lon, lat, weapon, radius_km, yield_kt });
+            }
+            self.detonate(lon, lat, weapon, radius_km, yield_kt);
+            self.provoke(origin, lon, lat);
+        }
+    }
+
+    /// Cycle the adversary: off → easy → normal → hard → off
+    pub fn cycle_difficulty(&mut self) {
+        if self.pandemic {
+            self.status_message = Some(NO_ADVERSARY_IN_PANDEMIC.to_string());
+            return;
+        }
+        self.adversary = Difficulty::cycle(self.adversary.as_ref().map(|a| a.difficulty)).map(Adversary::new);
+        let label = self.adversary.as_ref().map_or("off", |a| a.difficulty.label());
+        self.status_message = Some(format!("Adversary: {label}"));
+    }
+
+    /// Let the adversary know a player warhead launched from `origin` hit (lon, lat)
+    fn provoke(&mut self, origin: (f64, f64), lon: f64, lat: f64) {
+        let Some(ai) = self.adversary.as_mut() else { return };
+        if let Some(country) = self.map_renderer.country_near(lon, lat, 5.0) {
+            ai.record_strike(country, origin, self.frame);
+        }
+    }
+
+    /// Launch any counterstrikes that are due, from each aggrieved country's
+    /// largest surviving city toward the player's cities
+    fn update_adversary(&mut self) {
+        let seed = self.rng_seed();
+        let Some(ai) = self.adversary.as_mut() else { return };
+        let due = ai.due(self.frame);
+        if due.is_empty() {
+            return;
+        }
+
+        let cities: Vec<Target> = self.map_renderer.city_grid.iter()
+            .filter(|c| c.country == PLAYER_COUNTRY && c.population > 0)
+            .map(|c| Target {
+                lon: c.lon,
+                lat: c.lat,
+                weight: c.population as f64 * if c.is_capital { 2.0 } else { 1.0 },
+            })
+            .collect();
+
+        for country in due {
+            let Some(launcher) = self.map_renderer.largest_city_in(&country) else {
+                continue;
+            };
+            let salvo = ai.plan((launcher.lon, launcher.lat), &cities, self.frame, seed);
+            if salvo.is_empty() {
+                continue;
+            }
+            self.status_message = Some(format!("⚠ {country} retaliates: {} inbound", salvo.len()));
+            self.alerts.push(format!("{country} retaliates: {} inbound", salvo.len()));
+            self.events.push(Event {
+                sim_secs: self.clock.unix_secs(),
+                kind: EventKind::Retaliation,
+                text: format!("{country} retaliates, {} inbound", salvo.len()),
+                casualties: None,
+                yield_kt: None,
+            });
+            self.stats.waves += 1;
+            self.projectiles.extend(salvo.into_iter().map(|launch| Projectile {
+                from: launch.from,
+                to: launch.to,
+                frame: 0,
+                radius_km: HOSTILE_RADIUS_KM,
+                weapon_type: WeaponType::Nuke,
+                hostile: true,
+                id: 0,
+                warheads: Vec::new(),
+                released_at: None,
+                yield_kt: None,
+            }));
+        }
+    }
+
+    /// Re-tally per-faction population from the city list
+    pub fn refresh_faction_stats(&mut self) {
+        self.faction_stats = faction::tally(
+            self.map_renderer.city_grid.iter().map(|c| (c.country.as_str(), c.population, c.original_population)),
+        );
+    }
+
+    /// Population snapshot for judging the objective
+    pub fn standing(&self) -> Standing {
+        let mut standing = Standing {
+            hostiles_in_flight: self.projectiles.iter().any(|p| p.hostile),
+            ..Default::default()
+        };
+        for stats in &self.faction_stats {
+            standing.world.population += stats.population;
+            standing.world.original_population += stats.original_population;
+        }
+        for city in self.map_renderer.city_grid.iter().filter(|c| c.country == PLAYER_COUNTRY) {
+            standing.player.population += city.population;
+            standing.player.original_population += city.original_population;
+        }
+        standing
+    }
+
+    /// Sample stats and check the objective (summary screen freezes both)
+    fn update_game(&mut self) {
+        if self.game.is_over() {
+            return;
+        }
+        self.stats.peak_fires = self.stats.peak_fires.max(self.fires.len());
+        if self.frame.is_multiple_of(game::HISTORY_INTERVAL) {
+            self.stats.history.push(self.casualties);
+        }
+        if let GameState::Playing(objective) = self.game {
+            if self.frame.is_multiple_of(15) {
+                if let Some(outcome) = objective.judge(&self.standing(), &self.stats) {
+                    self.end_game(outcome);
+                }
+            }
+        }
+    }
+
+    /// Stop the clock on the game and bring up the summary
+    pub fn end_game(&mut self, outcome: Outcome) {
+        if self.game.is_over() {
+            return;
+        }
+        self.stats.history.push(self.casualties);
+        self.stats.ended = Some((self.frame, self.clock.unix_secs()));
+        self.game = GameState::Over { outcome, objective: self.game.objective() };
+    }
+
+    /// Close the summary and keep playing in free play
+    pub fn dismiss_summary(&mut self) {
+        if self.game.is_over() {
+            self.game = GameState::FreePlay;
+            self.stats.ended = None;
+        }
+    }
+
+    /// Join a two-player game: launch from our faction's silos
+    pub fn join_network(&mut self, session: NetSession) {
+        if session.faction == Faction::East {
+            self.silos = EASTERN_SILOS
+                .iter()
+                .map(|&(name, lon, lat)| LaunchSite { name, lon, lat })
+                .collect();
+            self.selected_silo = None;
+            // On station in the North Pacific
+            self.submarine = Submarine { lon: 165.0, lat: 40.0, destination: None };
+        }
+        self.status_message = Some(format!(
+            "Connected: you are the {}",
+            session.faction.label().to_lowercase()
+        ));
+        self.net = Some(session);
+    }
+
+    /// Apply the other player's launches and the host's city sync; as host,
+    /// send our own city sync every `SYNC_INTERVAL` frames
+    fn update_net(&mut self) {
+        let received = self.receive_net();
+        if let Some(net) = self.net.as_mut().filter(|net| net.is_host) {
+            if self.frame.is_multiple_of(netplay::SYNC_INTERVAL) {
+                if self.frame.is_multiple_of(netplay::FULL_SYNC_INTERVAL) {
+                    net.resync();
+                }
+                let changed = net.city_updates(self.map_renderer.city_grid.iter().map(|c| c.population));
+                if !changed.is_empty() {
+                    net.send(Message::Cities { casualties: self.casualties, changed });
+                }
+            }
+        }
+        self.apply_net(received);
+    }
+
+    /// Messages the other player has sent since the last call. The game
+    /// ends here if they left.
+    fn receive_net(&mut self) -> Vec<Message> {
+        let Some(net) = self.net.as_mut() else { return Vec::new() };
+        let mut received = Vec::new();
+        let mut disconnected = None;
+        while let Some(msg) = net.poll() {
+            match msg {
+                Ok(Message::Bye) => disconnected = Some("Opponent left the game".to_string()),
+                Ok(msg) => received.push(msg),
+                Err(e) => disconnected = Some(format!("Connection lost: {e}")),
+            }
+        }
+        if let Some(reason) = disconnected {
+            self.status_message = Some(reason);
+            self.net = None;
+        }
+        received
+    }
+
+    fn apply_net(&mut self, received: Vec<Message>) {
+        for msg in received {
+            match msg {
+                Message::Launch { id, from, to, weapon, radius_km, yield_kt, warheads } => {
+                    self.projectiles.push(Projectile {
+                        from,
+                        to,
+                        frame: 0,
+                        radius_km,
+                        weapon_type: weapon,
+                        hostile: true,
+                        id,
+                        warheads,
+                        released_at: None,
+                        yield_kt,
+                    });
+                }
+                Message::Detonation { lon, lat, weapon, radius_km, yield_kt } => self.detonate(lon, lat, weapon, radius_km, yield_kt),
+                Message::Intercepted { id } => {
+                    if let Some(i) = self.projectiles.iter().position(|p| !p.hostile && p.id == id) {
+                        let p = self.projectiles.swap_remove(i);
+                        let (lon, lat) = p.position_at(p.progress());
+                        self.interceptions.push(Interception { lon, lat, frame: 0, hit: true });
+                    }
+                }
+                Message::Cities { casualties, changed } => {
+                    for (idx, population) in changed {
+                        if let Some(city) = self.map_renderer.city_grid.get_mut(idx as usize) {
+                            city.set_population(population);
+                        }
+                    }
+                    self.casualties = casualties;
+                }
+                Message::Welcome { .. } | Message::Bye => {}
+            }
+        }
+    }
+
+    /// Watch someone else's world: apply the diffs they've sent
+    fn update_spectating(&mut self) {
+        for diff in self.receive_diffs() {
+            self.apply_diff(diff);
+        }
+    }
+
+    /// Diffs the watched host has sent since the last call. Watching stops
+    /// here if the connection dropped.
+    fn receive_diffs(&mut self) -> Vec<Diff> {
+        let Some(spectator) = self.spectating.as_mut() else { return Vec::new() };
+        let mut received = Vec::new();
+        let mut disconnected = None;
+        while let Some(diff) = spectator.poll() {
+            match diff {
+                Ok(diff) => received.push(diff),
+                Err(e) => disconnected = Some(format!("Stopped watching {}: {e}", spectator.host)),
+            }
+        }
+        if let Some(reason) = disconnected {
+            self.status_message = Some(reason);
+            self.spectating = None;
+        }
+        received
+    }
+
+    /// While history is shown the simulation holds still, but the other
+    /// player and a watched host carry on. What they send goes to the live
+    /// world put aside, and the snapshot is shown over it again.
+    fn update_parked_world(&mut self) {
+        let (messages, diffs) = (self.receive_net(), self.receive_diffs());
+        if messages.is_empty() && diffs.is_empty() {
+            return;
+        }
+        let Some(cursor) = self.history.cursor() else { return };
+        self.unpark_live();
+        self.apply_net(messages);
+        for diff in diffs {
+            self.apply_diff(diff);
+        }
+        self.park_live();
+        if let Some(snapshot) = self.history.snapshot(cursor) {
+            self.show_snapshot(&snapshot);
+        }
+    }
+
+    /// Bring this world in line with a host's diff. Blasts are only shown;
+    /// the damage they did arrives as city populations and fires.
+    pub fn apply_diff(&mut self, diff: Diff) {
+        for (idx, population) in diff.cities {
+            if let Some(city) = self.map_renderer.city_grid.get_mut(idx as usize) {
+                city.set_population(population);
+            }
+        }
+        for (cell, intensity, weapon) in diff.fires {
+            self.fires.set_cell(cell, intensity, weapon);
+        }
+        for (lon, lat, weapon_type, radius_km) in diff.explosions {
+            self.explosions.push(Explosion { lon, lat, frame: 0, radius_km, weapon_type });
+        }
+        self.casualties = diff.casualties;
+        self.clock.set_unix_secs(diff.sim_time);
+    }
+
+    /// Save the live world, with its burn scars, to `path`
+    pub fn save_game(&self, path: &Path) -> Result<()> {
+        if self.history.is_scrubbing() {
+            bail!("return to now (Esc) before saving");
+        }
+        let file = SaveFile {
+            cities: self.map_renderer.city_grid.len() as u32,
+            snapshot: self.history_snapshot(),
+            scars: self.burn_scars.clone(),
+        };
+        file.write(path)
+    }
+
+    /// Replace the world with one saved by `save_game`. The timeline starts
+    /// again from here.
+    pub fn load_game(&mut self, path: &Path) -> Result<()> {
+        let file = SaveFile::read(path)?;
+        let cities = self.map_renderer.city_grid.len();
+        if file.cities as usize != cities {
+            bail!("saved over {} cities but {cities} are loaded; load the same map data", file.cities);
+        }
+        self.live = None;
+        self.history.clear();
+        self.show_snapshot(&file.snapshot);
+        for city in self.map_renderer.city_grid.iter_mut() {
+            city.displaced = 0;
+        }
+        self.infected = file.snapshot.infections.iter().map(|&(idx, _)| idx as usize).collect();
+        self.frame = file.snapshot.frame;
+        self.burn_scars = file.scars;
+        Ok(())
+    }
+
+    /// The world as the timeline keeps it
+    fn history_snapshot(&self) -> Snapshot {
+        let grid = &self.map_renderer.city_grid;
+        Snapshot {
+            frame: self.frame,
+            sim_time: self.clock.unix_secs(),
+            casualties: self.casualties,
+            cities: grid.iter().enumerate()
+                .filter(|(_, c)| c.population != c.original_population)
+                .map(|(i, c)| (i as u32, c.population))
+                .collect(),
+            infections: self.infected.iter().filter_map(|&i| grid.get(i).map(|c| (i as u32, c.infection))).collect(),
+            fires: self.fires.cell_states().collect(),
+        }
+    }
+
+    /// Step the timeline `by` snapshots (negative is back in time). The
+    /// first step back puts the live world aside; stepping past the newest
+    /// snapshot brings it back.
+    pub fn scrub(&mut self, by: isize) {
+        if self.history.is_empty() {
+            self.status_message = Some("No history yet".to_string());
+            return;
+        }
+        if self.live.is_none() {
+            self.park_live();
+        }
+        match self.history.seek(by) {
+            Some(snapshot) => {
+                self.show_snapshot(&snapshot);
+                self.status_message = Some("Viewing history: Alt+←/→ to scrub, Esc for now".to_string());
+            }
+            None => self.return_to_now(),
+        }
+    }
+
+    /// Leave the timeline and put the live world back
+    pub fn return_to_now(&mut self) {
+        self.history.go_live();
+        if self.live.is_some() {
+            self.unpark_live();
+            self.status_message = Some("Back to now".to_string());
+        }
+    }
+
+    /// Put the live world aside while history is shown
+    fn park_live(&mut self) {
+        let cities = self.map_renderer.city_grid.iter().map(|c| (c.population, c.infection)).collect();
+        self.live = Some(Box::new(LiveWorld {
+            fires: self.fires.clone(),
+            cities,
+            casualties: self.casualties,
+            sim_time: self.clock.unix_secs(),
+        }));
+    }
+
+    /// Put the live world set aside by `park_live` back in place
+    fn unpark_live(&mut self) {
+        let Some(live) = self.live.take() else { return };
+        self.fires = live.fires;
+        for (city, (population, infection)) in self.map_renderer.city_grid.iter_mut().zip(live.cities) {
+            city.set_population(population);
+            city.infection = infection;
+        }
+        self.casualties = live.casualties;
+        self.clock.set_unix_secs(live.sim_time);
+    }
+
+    /// Show a snapshot in place of the live world
+    fn show_snapshot(&mut self, snapshot: &Snapshot) {
+        let grid = &mut self.map_renderer.city_grid;
+        for city in grid.iter_mut() {
+            city.set_population(city.original_population);
+            city.infection = 0.0;
+        }
+        for &(idx, population) in &snapshot.cities {
+            if let Some(city) = grid.get_mut(idx as usize) {
+                city.set_population(population);
+            }
+        }
+        for &(idx, infection) in &snapshot.infections {
+            if let Some(city) = grid.get_mut(idx as usize) {
+                city.infection = infection;
+            }
+        }
+        self.fires = FireField::new();
+        for &(cell, intensity, weapon) in &snapshot.fires {
+            self.fires.set_cell(cell, intensity, weapon);
+        }
+        self.casualties = snapshot.casualties;
+        self.clock.set_unix_secs(snapshot.sim_time);
+    }
+
+    /// Fire any scenario events due this frame
+    fn update_scenario(&mut self) {
+        let Some(runner) = self.scenario.as_mut() else { return };
+        let due = runner.tick();
+        let finished = runner.is_finished();
+        for event in due {
+            self.apply_scenario_event(event);
+        }
+        if finished {
+            let name = self.scenario.take().and_then(|r| r.scenario.name);
+            if let Some(name) = name {
+                self.status_message = Some(format!("Scenario complete: {name}"));
+            }
+        }
+    }
+
+    fn apply_scenario_event(&mut self, event: ScenarioEvent) {
+        let locate = |app: &mut Self, at: &Location| {
+            let found = app.resolve_location(at);
+            if found.is_none() {
+                app.status_message = Some(format!("Scenario: unknown place {at:?}"));
+            }
+            found
+        };
+        match event {
+            ScenarioEvent::Strike { at, weapon, radius_km } => {
+                if let Some((lon, lat)) = locate(self, &at) {
+                    self.launch_at(lon, lat, weapon, radius_km, None);
+                }
+            }
+            ScenarioEvent::Wildfire { at, radius_km } => {
+                if let Some((lon, lat)) = locate(self, &at) {
+                    self.start_wildfire(lon, lat, radius_km);
+                }
+            }
+            ScenarioEvent::Eruption { at } => {
+                let volcano = match &at {
+                    Location::Named(name) => Volcano::find(name),
+                    Location::Coords(..) => None,
+                };
+                match volcano {
+                    Some(v) => self.erupt(v.name, v.lon, v.lat),
+                    None => {
+                        if let Some((lon, lat)) = locate(self, &at) {
+                            self.erupt("Volcano", lon, lat);
+                        }
+                    }
+                }
+            }
+            ScenarioEvent::Hurricane { at } => {
+                if let Some((lon, lat)) = locate(self, &at) {
+                    let seed = hash2(self.rng_seed(), self.frame);
+                    self.form_hurricane(Hurricane::new(lon, lat, seed));
+                }
+            }
+            ScenarioEvent::FlyTo { at, zoom } => {
+                if let Some((lon, lat)) = locate(self, &at) {
+                    let zoom = zoom.unwrap_or_else(|| self.projection.effective_zoom());
+                    self.start_fly_to(lon, lat, zoom);
+                }
+            }
+            ScenarioEvent::Clock(speed) => self.clock.set_speed(speed),
+            ScenarioEvent::Adversary(level) if !self.pandemic => self.adversary = level.map(Adversary::new),
+            ScenarioEvent::Adversary(_) => {}
+            ScenarioEvent::Message(text) => self.status_message = Some(text),
+        }
+    }
+
+    /// Coordinates for a scenario location: loaded cities first, then the gazetteer
+    fn resolve_location(&self, at: &Location) -> Option<(f64, f64)> {
+        match at {
+            Location::Coords(lon, lat) => Some((*lon, *lat)),
+            Location::Named(name) => self
+                .map_renderer
+                .find_city(name)
+                .map(|c| (c.lon, c.lat))
+                .or_else(|| Gazetteer::builtin().lookup(name).map(|p| (p.lon, p.lat))),
+        }
+    }
+
+    /// Toggle ABM placement mode
+    pub fn toggle_defense_placement(&mut self) {
+        self.placing_defense = !self.placing_defense;
+        self.status_message = self.placing_defense
+            .then(|| "Placing ABM batteries — click to add/remove, d to finish".to_string());
+    }
+
+    /// Primary click action: seed an outbreak in pandemic mode, place/remove
+    /// a battery in placement mode, otherwise launch — or, for a pattern reticle, set its first point
+    /// or launch the pattern once it has one
+    pub fn click_target(&mut self, col: u16, row: u16) {
+        if self.history.is_scrubbing() {
+            self.status_message = Some(HISTORY_READ_ONLY.to_string());
+        } else if self.pandemic {
+            self.seed_outbreak(col, row);
+        } else if self.placing_defense {
+            self.toggle_defense_at(col, row);
+        } else if self.reticle == Reticle::Point {
+            self.launch_nuke(col, row);
+        } else if self.strike_anchor.is_some() {
+            self.launch_pattern(col, row);
+        } else {
+            let px = ((col.saturating_sub(1)) as i32) * 2;
+            let py = ((row.saturating_sub(1)) as i32) * 4;
+            if let Some((lon, lat)) = self.projection.unproject(px, py) {
+                self.strike_anchor = Some(StrikeAnchor { lon, lat, cell: (col, row) });
+            }
+        }
+    }
+
+    /// Button released: a drag away from a pattern's first point launches
+    /// the pattern to where it ended
+    pub fn release_target(&mut self, col: u16, row: u16) {
+        if self.strike_anchor.is_some_and(|a| a.cell != (col, row)) {
+            self.launch_pattern(col, row);
+        }
+    }
+
+    /// Next reticle (point → corridor → area)
+    pub fn cycle_reticle(&mut self) {
+        self.reticle = self.reticle.next();
+        self.strike_anchor = None;
+        self.status_message = Some(match self.reticle {
+            Reticle::Point => "Reticle: point".to_string(),
+            Reticle::Corridor => "Reticle: corridor — click or drag from one end to the other".to_string(),
+            Reticle::Area => "Reticle: area — click or drag from corner to corner".to_string(),
+        });
+    }
+
+    /// Drop a pattern's first point
+    pub fn cancel_pattern(&mut self) {
+        self.strike_anchor = None;
+    }
+
+    /// Mark a target for the strike package with the active weapon, or
+    /// unmark the one already there
+    pub fn queue_target(&mut self, col: u16, row: u16) {
+        let px = ((col.saturating_sub(1)) as i32) * 2;
+        let py = ((row.saturating_sub(1)) as i32) * 4;
+        let Some((lon, lat)) = self.projection.unproject(px, py) else { return };
+        if self.history.is_scrubbing() {
+            self.status_message = Some(HISTORY_READ_ONLY.to_string());
+            return;
+        }
+        if self.pandemic {
+            self.status_message = Some("Pandemic mode: weapons are off".to_string());
+            return;
+        }
+
+        let grab_km = 4.0 / self.projection.deg_to_pixels(1.0).max(1e-6) * 111.0;
+        if let Some(i) = self.strike_queue.iter().position(|t| fast_distance_km(t.lon, t.lat, lon, lat) < grab_km) {
+            self.strike_queue.remove(i);
+            self.status_message = Some(format!("Target {} removed ({} queued)", i + 1, self.strike_queue.len()));
+            return;
+        }
+        if self.strike_queue.len() >= MAX_QUEUED_STRIKES {
+            self.status_message = Some(format!("Strike queue full ({MAX_QUEUED_STRIKES} targets)"));
+            return;
+        }
+        // Don't promise more rounds than the stockpile will have
+        let weapon = self.active_weapon;
+        let stock = self.stockpiles[weapon.index()];
+        if let Some(capacity) = stock.capacity {
+            if self.strike_queue.iter().filter(|t| t.weapon == weapon).count() as u32 >= capacity {
+                self.status_message = Some(format!("{} stockpile holds only {capacity}", weapon.label()));
+                return;
+            }
+        }
+        let radius_km = self.blast_radius_km(weapon);
+        let yield_kt = self.yield_kt(weapon);
+        self.strike_queue.push(QueuedStrike { lon, lat, weapon, radius_km, yield_kt });
+        self.status_message = Some(format!("Target {} queued: {}", self.strike_queue.len(), weapon.label()));
+    }
+
+    /// Start launching the queued targets, one after another
+    pub fn launch_queue(&mut self) {
+        if self.strike_queue.is_empty() {
+            self.status_message = Some("Strike queue is empty (Shift+right-click to add targets)".to_string());
+            return;
+        }
+        if self.package_next_frame.is_none() {
+            self.package_next_frame = Some(self.frame);
+            self.status_message = Some(format!("Launching strike package: {} targets", self.strike_queue.len()));
+        }
+    }
+
+    /// Drop every queued target, stopping a package mid-launch
+    pub fn clear_queue(&mut self) {
+        let held = self.strike_queue.len();
+        self.strike_queue.clear();
+        self.status_message = Some(match self.package_next_frame.take() {
+            Some(_) => format!("Strike package aborted, {held} held back"),
+            None => "Strike queue cleared".to_string(),
+        });
+    }
+
+    pub fn is_launching_package(&self) -> bool {
+        self.package_next_frame.is_some()
+    }
+
+    /// Launch the package's next target once the last one's reload is done.
+    /// A target whose stockpile is empty waits for a restock.
+    fn update_strike_package(&mut self) {
+        let Some(due) = self.package_next_frame else { return };
+        let Some(&next) = self.strike_queue.first() else {
+            self.package_next_frame = None;
+            self.status_message = Some("Strike package away".to_string());
+            return;
+        };
+        if self.frame < due || self.stockpiles[next.weapon.index()].is_empty() {
+            return;
+        }
+        self.strike_queue.remove(0);
+        if self.launch_at(next.lon, next.lat, next.weapon, next.radius_km, next.yield_kt) {
+            self.last_nuke_frame = self.frame;
+        }
+        self.package_next_frame = Some(self.frame + next.weapon.cooldown_frames().max(PACKAGE_STAGGER_FRAMES));
+    }
+
+    /// The pattern laid out so far, ending at the mouse
+    pub fn planned_strikes(&self) -> Option<PlannedStrike> {
+        let anchor = self.strike_anchor?;
+        let (px, py) = self.mouse_pixel_pos()?;
+        let to = self.projection.unproject(px, py)?;
+        let from = (anchor.lon, anchor.lat);
+        let targets = self.reticle.targets(from, to, self.blast_radius_km(self.active_weapon));
+        Some(PlannedStrike { from, to, targets })
+    }
+
+    /// Launch the pattern from its first point to the screen position
+    fn launch_pattern(&mut self, col: u16, row: u16) {
+        if self.frame < self.last_nuke_frame + self.active_weapon.cooldown_frames() {
+            return;
+        }
+        let px = ((col.saturating_sub(1)) as i32) * 2;
+        let py = ((row.saturating_sub(1)) as i32) * 4;
+        let Some(to) = self.projection.unproject(px, py) else { return };
+        let Some(anchor) = self.strike_anchor.take() else { return };
+
+        let weapon = self.active_weapon;
+        let radius_km = self.blast_radius_km(weapon);
+        let yield_kt = self.yield_kt(weapon);
+        let targets = self.reticle.targets((anchor.lon, anchor.lat), to, radius_km);
+        let launched = targets.iter().filter(|&&(lon, lat)| self.launch_at(lon, lat, weapon, radius_km, yield_kt)).count();
+        if launched > 0 {
+            self.last_nuke_frame = self.frame;
+            let missed = targets.len() - launched;
+            self.status_message = Some(format!(
+                "{} strike: {launched} × {}{}",
+                if self.reticle == Reticle::Corridor { "Corridor" } else { "Area" },
+                weapon.label(),
+                if missed > 0 { format!(" ({missed} not launched)") } else { String::new() },
+            ));
+        }
+    }
+
+    /// Add a battery at the screen position, or remove one already near it
+    fn toggle_defense_at(&mut self, col: u16, row: u16) {
+        let px = ((col.saturating_sub(1)) as i32) * 2;
+        let py = ((row.saturating_sub(1)) as i32) * 4;
+        let Some((lon, lat)) = self.projection.unproject(px, py) else {
+            return;
+        };
+
+        // "Near" = within a couple of character cells (4 braille px) at the current zoom
+        let grab_km = 4.0 / self.projection.deg_to_pixels(1.0).max(1e-6) * 111.0;
+        if let Some(i) = self.defenses.iter().position(|d| fast_distance_km(d.lon, d.lat, lon, lat) < grab_km) {
+            self.defenses.swap_remove(i);
+        } else {
+            self.defenses.push(DefenseSite { lon, lat, cooldown: 0, blackout: 0 });
+        }
+        self.status_message = Some(format!("ABM batteries: {}", self.defenses.len()));
+    }
+
+    /// Ready batteries take one shot at each missile in range. A hit destroys
+    /// the missile in flight; either way the battery goes on cooldown.
+    fn update_defenses(&mut self) {
+        for site in &mut self.defenses {
+            site.cooldown = site.cooldown.saturating_sub(1);
+        }
+        self.update_blackouts();
+
+        let frame = self.frame;
+        let seed = self.rng_seed();
+        let defenses = &mut self.defenses;
+        let interceptions = &mut self.interceptions;
+        let stats = &mut self.stats;
+        let mut shot_down = Vec::new();
+        let mut downed = Vec::new();
+        self.projectiles.retain(|p| {
+            // Interceptors are no use against a falling rock
+            if !p.hostile || p.weapon_type == WeaponType::Asteroid {
+                return true;
+            }
+            let (lon, lat) = p.position_at(p.progress());
+            for (i, site) in defenses.iter_mut().enumerate() {
+                if !site.is_ready() {
+                    continue;
+                }
+                let dist = fast_distance_km(site.lon, site.lat, lon, lat);
+                if dist > DefenseSite::RANGE_KM {
+                    continue;
+                }
+                site.cooldown = DefenseSite::COOLDOWN_FRAMES;
+                let roll = rand_simple(hash3(hash2(seed, i as u64), p.frame as u64, frame));
+                let hit = roll < DefenseSite::hit_probability(dist);
+                interceptions.push(Interception { lon, lat, frame: 0, hit });
+                if hit {
+                    stats.intercepts += 1;
+                    downed.push((lon, lat));
+                    if p.id != 0 {
+                        shot_down.push(p.id);
+                    }
+                    return false;
+                }
+            }
+            true
+        });
+
+        if let Some(net) = self.net.as_ref() {
+            for id in shot_down {
+                net.send(Message::Intercepted { id });
+            }
+        }
+        for (lon, lat) in downed {
+            let place = self.place_name(lon, lat, 300.0);
+            self.log_event(EventKind::Intercept, format!("ABM intercept near {place}"), None);
+        }
+
+        self.interceptions.retain_mut(|b| {
+            b.frame += 1;
+            b.frame < Interception::FRAMES
+        });
+    }
+
+    /// Share of the active weapon's reload still to go, 0 when ready
+    pub fn reload_remaining(&self) -> f64 {
+        let cooldown = self.active_weapon.cooldown_frames();
+        let ready_at = self.last_nuke_frame + cooldown;
+        if self.frame >= ready_at {
+            return 0.0;
+        }
+        (ready_at - self.frame) as f64 / cooldown as f64
+    }
+
+    /// Launch the active weapon at the given screen position
+    pub fn launch_nuke(&mut self, col: u16, row: u16) {
+        if self.frame < self.last_nuke_frame + self.active_weapon.cooldown_frames() {
+            return;
+        }
+
+        let px = ((col.saturating_sub(1)) as i32) * 2;
+        let py = ((row.saturating_sub(1)) as i32) * 4;
+
+        // On globe, clicking outside the sphere does nothing
+        let (lon, lat) = match self.projection.unproject(px, py) {
+            Some(coords) => coords,
+            None => return,
+        };
+
+        let weapon = self.active_weapon;
+        let radius_km = self.blast_radius_km(weapon);
+
+        if self.launch_at(lon, lat, weapon, radius_km, self.yield_kt(weapon)) {
+            self.last_nuke_frame = self.frame;
+        }
+    }
+
+    /// Send a strike at (lon, lat) with the current delivery platform.
+    /// Returns false if the target is out of range.
+    pub fn launch_at(&mut self, lon: f64, lat: f64, weapon: WeaponType, radius_km: f64, yield_kt: Option<f64>) -> bool {
+        if let Some(spectator) = &self.spectating {
+            self.status_message = Some(format!("Watching {}: launches are theirs", spectator.host));
+            return false;
+        }
+        if self.history.is_scrubbing() {
+            self.status_message = Some(HISTORY_READ_ONLY.to_string());
+            return false;
+        }
+        if self.pandemic {
+            self.status_message = Some("Pandemic mode: weapons are off".to_string());
+            return false;
+        }
+        let stock = self.stockpiles[weapon.index()];
+        if stock.is_empty() {
+            self.status_message = Some(format!(
+                "{} stockpile empty (next in {:.0}s)", weapon.label(), (stock.restock_in as f64 / 60.0).ceil()
+            ));
+            return false;
+        }
+        match self.delivery {
+            // Rocks fall from space, whatever the platform and however far
+            _ if weapon == WeaponType::Asteroid => {
+                let from = destination_point(lon, lat, ASTEROID_ENTRY_BEARING, ASTEROID_ENTRY_KM);
+                self.fire_missile(from, (lon, lat), weapon, radius_km, yield_kt);
+            }
+            Delivery::Silo => {
+                let from = self.launch_origin(lon, lat);
+                self.fire_missile(from, (lon, lat), weapon, radius_km, yield_kt);
+            }
+            Delivery::Submarine => {
+                let sub = &self.submarine;
+                let range = great_circle_km(sub.lon, sub.lat, lon, lat);
+                if range > Submarine::RANGE_KM {
+                    self.status_message = Some(format!(
+                        "Out of SLBM range ({:.0} / {:.0} km)", range, Submarine::RANGE_KM
+                    ));
+                    return false;
+                }
+                self.fire_missile((sub.lon, sub.lat), (lon, lat), weapon, radius_km, yield_kt);
+            }
+            Delivery::Bomber => {
+                let (base_lon, base_lat) = self.launch_origin(lon, lat);
+                self.bombers.push(Bomber {
+                    origin: (base_lon, base_lat),
+                    lon: base_lon,
+                    lat: base_lat,
+                    target: (lon, lat),
+                    radius_km,
+                    weapon_type: weapon,
+                    yield_kt,
+                    trail: Vec::new(),
+                });
+            }
+        }
+        self.stockpiles[weapon.index()].take(weapon.restock_frames());
+        self.stats.strikes_launched += 1;
+        true
+    }
+
+    /// Put one of our missiles in the air (and tell the other player about
+    /// it). With MIRVs armed it's a bus that splits the yield among its warheads.
+    fn fire_missile(&mut self, from: (f64, f64), to: (f64, f64), weapon: WeaponType, radius_km: f64, yield_kt: Option<f64>) {
+        let (warheads, radius_km, yield_kt) = if self.mirv_armed && weapon != WeaponType::Asteroid {
+            let warheads = self.mirv.footprint(to);
+            let share = warheads.len() as f64;
+            (warheads, self.mirv.warhead_radius_km(radius_km), yield_kt.map(|kt| kt / share))
+        } else {
+            (Vec::new(), radius_km, yield_kt)
+        };
+        let mut id = 0;
+        if let Some(net) = self.net.as_mut() {
+            id = net.next_launch_id();
+            net.send(Message::Launch { id, from, to, weapon, radius_km, yield_kt, warheads: warheads.clone() });
+        }
+        self.projectiles.push(Projectile {
+            from,
+            to,
+            frame: 0,
+            radius_km,
+            weapon_type: weapon,
+            hostile: false,
+            id,
+            warheads,
+            released_at: None,
+            yield_kt,
+        });
+    }
+
+    /// Warhead arrival: blast, gas, fires, fallout and immediate casualties
+    fn detonate(&mut self, lon: f64, lat: f64, weapon: WeaponType, radius_km: f64, yield_kt: Option<f64>) {
+        self.explosions.push(Explosion {
+            lon,
+            lat,
+            frame: 0,
+            radius_km,
+            weapon_type: weapon,
+        });
+
+        // Spawn gas clouds (Bio and Chem)
+        match weapon {
+            WeaponType::Bio | WeaponType::Chem => {
+                self.gas_clouds.push(GasCloud {
+                    lon,
+                    lat,
+                    intensity: GAS_CLOUD_INTENSITY,
+                    weapon_type: weapon,
+                    plume: Plume::new(lon, lat, radius_km * 0.5, radius_km * 3.0),
+                });
+            }
+            _ => {}
+        }
+
+        // Spawn fires (weapon-dependent)
+        match weapon {
+            WeaponType::Bio | WeaponType::Emp | WeaponType::Tsunami => {
+                // Bio, EMP and the underwater tsunami device produce no fires
+            }
+            _ => {
+                let fire_scale = match weapon {
+                    WeaponType::Chem => 0.6,  // 60% fire count
+                    _ => 1.0,
+                };
+                self.spawn_fires(lon, lat, radius_km * THERMAL_RADIUS_RATIO, weapon, fire_scale);
+            }
+        }
+
+        // Create fallout zone (weapon-dependent)
+        match weapon {
+            WeaponType::Emp | WeaponType::Airburst | WeaponType::Asteroid => {
+                // Non-nuclear (or pulse-only) weapons leave no fallout
+            }
+            _ => {
+                let (fallout_radius_mult, fallout_intensity) = match weapon {
+                    WeaponType::Bio => (3.0, 3000),    // 3× radius, 3× intensity
+                    WeaponType::Chem => (2.5, 2000),   // 2.5× radius, 2× intensity
+                    _ => (2.0, 1000),                   // Nuke default
+                };
+                let fallout_radius_km = radius_km * fallout_radius_mult;
+                self.fallout.push(Fallout {
+                    lon,
+                    lat,
+                    intensity: fallout_intensity,
+                    plume: Plume::new(lon, lat, fallout_radius_km, fallout_radius_km * 1.5),
+                });
+            }
+        }
+
+        // Only a detonation at sea raises a wave
+        let at_sea = !self.map_renderer.is_on_land(lon, lat);
+        match weapon {
+            WeaponType::Tsunami if at_sea => {
+                self.tsunamis.push(Tsunami::new(lon, lat, radius_km * TSUNAMI_REACH, 1.0));
+            }
+            WeaponType::Nuke if at_sea && self.surface_within(lon, lat, radius_km * NUKE_TSUNAMI_REACH, true) => {
+                self.tsunamis.push(Tsunami::new(lon, lat, radius_km * NUKE_TSUNAMI_REACH, NUKE_TSUNAMI_STRENGTH));
+            }
+            WeaponType::Asteroid => {
+                self.dust = (self.dust + ASTEROID_DUST).min(1.0);
+                self.climate.inject(ASTEROID_SOOT_TG);
+                if self.craters.len() == MAX_CRATERS {
+                    self.craters.remove(0);
+                }
+                self.craters.push(Crater { lon, lat, radius_km: radius_km * CRATER_RATIO });
+            }
+            WeaponType::Emp => self.apply_blackout(lon, lat, radius_km),
+            // The pathogen carries well beyond the blast
+            WeaponType::Bio => self.seed_infection(lon, lat, radius_km * 2.0),
+            _ => {}
+        }
+
+        // Pulses only fry electronics; chemical and biological agents leave
+        // structures standing
+        let wrecked = match weapon {
+            WeaponType::Emp => self.map_renderer.destroy_infrastructure(lon, lat, radius_km, Infrastructure::emp_vulnerable),
+            WeaponType::Bio | WeaponType::Chem => 0,
+            _ => self.map_renderer.destroy_infrastructure(lon, lat, radius_km, |_| true),
+        };
+        self.stats.infrastructure_destroyed += wrecked as u32;
+
+        // Calculate immediate blast casualties
+        let before = self.casualties;
+        self.apply_blast_damage(lon, lat, radius_km);
+
+        let place = self.place_name(lon, lat, radius_km + 100.0);
+        let payload = match (weapon.payload(), yield_kt) {
+            (Some(payload), _) => payload.to_string(),
+            (None, Some(kt)) => kilotons_label(kt),
+            (None, None) => format!("{radius_km:.0} km"),
+        };
+        self.events.push(Event {
+            sim_secs: self.clock.unix_secs(),
+            kind: EventKind::Strike(weapon),
+            text: format!("{} {payload} → {place}", weapon.label()),
+            casualties: Some(self.casualties - before),
+            yield_kt,
+        });
+    }
+
+    /// The nearest city within `reach_km`, or the coordinates
+    fn place_name(&self, lon: f64, lat: f64, reach_km: f64) -> String {
+        let grid = &self.map_renderer.city_grid;
+        grid.query_radius(lon, lat, reach_km / 111.0)
+            .into_iter()
+            .filter_map(|idx| grid.get(idx))
+            .map(|c| (fast_distance_km(lon, lat, c.lon, c.lat), c))
+            .filter(|&(dist, _)| dist <= reach_km)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map_or_else(
+                || format!("{:.1}°{} {:.1}°{}", lat.abs(), if lat >= 0.0 { 'N' } else { 'S' }, lon.abs(), if lon >= 0.0 { 'E' } else { 'W' }),
+                |(_, c)| c.name.clone(),
+            )
+    }
+
+    fn log_event(&mut self, kind: EventKind, text: String, casualties: Option<u64>) {
+        self.events.push(Event { sim_secs: self.clock.unix_secs(), kind, text, casualties, yield_kt: None });
+    }
+
+    /// Cities that have lost anyone, worst first
+    pub fn damaged_cities(&self) -> Vec<&City> {
+        let mut cities: Vec<_> = self.map_renderer.city_grid.iter()
+            .filter(|c| c.population < c.original_population)
+            .collect();
+        cities.sort_by_key(|c| std::cmp::Reverse(c.original_population - c.population));
+        cities
+    }
+
+    /// Switch between the map and the stats dashboard
+    pub fn toggle_view(&mut self) {
+        self.view = match self.view {
+            View::Map => View::Stats,
+            View::Stats => View::Map,
+        };
+    }
+
+    /// Fires and plume puffs, for renders outside the terminal view
+    fn overlays(&self) -> snapshot::Overlays {
+        snapshot::Overlays {
+            fires: self.fires.sparks_in(-180.0, -90.0, 180.0, 90.0).map(|f| (f.lon, f.lat, f.intensity)).collect(),
+            plumes: self.fallout.iter().map(|z| &z.plume)
+                .chain(self.gas_clouds.iter().map(|c| &c.plume))
+                .flat_map(|plume| plume.puffs.iter().map(|p| (p.lon, p.lat, p.radius_km)))
+                .collect(),
+        }
+    }
+
+    /// Re-render the current view at `width`×`height` and write it to
+    /// `path` as PNG or SVG (by extension)
+    pub fn screenshot(&mut self, path: &Path, width: u32, height: u32) -> Result<()> {
+        self.load_visible_chunks();
+        snapshot::save(path, &self.map_renderer, &self.projection, &self.overlays(), width, height)
+    }
+
+    /// The map view as text: ANSI-colored braille, or plain braille for a
+    /// `.txt` path. `-` writes to stdout.
+    pub fn write_text_frame(&mut self, path: &Path) -> Result<()> {
+        self.load_visible_chunks();
+        let frame = text::compose(&self.map_renderer, &self.projection, &self.overlays());
+        let plain = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("txt"));
+        let out = if plain { frame.to_plain() } else { frame.to_ansi() };
+        if path == Path::new("-") {
+            std::io::stdout().write_all(out.as_bytes())?;
+            return Ok(());
+        }
+        std::fs::write(path, out).with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// Dump the map view to a text file and report where it went
+    pub fn dump_text_frame(&mut self) {
+        let path = self.ansi_path.clone().unwrap_or_else(|| timestamped_path("ans"));
+        self.status_message = Some(match self.write_text_frame(&path) {
+            Ok(()) => format!("Saved text frame to {}", path.display()),
+            Err(e) => format!("Text dump failed: {e:#}"),
+        });
+    }
+
+    /// Save a screenshot of the current view and report where it went
+    pub fn take_screenshot(&mut self) {
+        let path = self.screenshot_path.clone().unwrap_or_else(|| timestamped_path("png"));
+        let (width, height) = self.screenshot_size;
+        self.status_message = Some(match self.screenshot(&path, width, height) {
+            Ok(()) => format!("Saved {width}×{height} screenshot to {}", path.display()),
+            Err(e) => format!("Screenshot failed: {e:#}"),
+        });
+    }
+
+    /// Export fires, plumes, contaminated ground and damaged cities as
+    /// GeoJSON and report where they went
+    pub fn export_geojson(&mut self) {
+        self.export_geojson_to(None);
+    }
+
+    /// Export to `path`, or where `export_geojson` would
+    fn export_geojson_to(&mut self, path: Option<PathBuf>) {
+        let path = path.or_else(|| self.export_path.clone()).unwrap_or_else(|| timestamped_path("geojson"));
+        self.status_message = Some(match export::save(self, &path) {
+            Ok(()) => format!("Exported world state to {}", path.display()),
+            Err(e) => format!("Export failed: {e:#}"),
+        });
+    }
+
+    /// Show/hide the event log panel, following the newest entries
+    pub fn toggle_event_log(&mut self) {
+        self.show_event_log = !self.show_event_log;
+        self.events.scroll_by(-(self.events.len() as isize));
+    }
+
+    /// Cut power to cities and ABM batteries inside an EMP footprint.
+    /// Repeat pulses restart the clock.
+    fn apply_blackout(&mut self, lon: f64, lat: f64, radius_km: f64) {
+        let candidates = self.map_renderer.city_grid.query_radius(lon, lat, (radius_km + 50.0) / 111.0);
+        for idx in candidates {
+            let Some(city) = self.map_renderer.city_grid.get_mut(idx) else { continue };
+            if city.population == 0 || fast_distance_km(lon, lat, city.lon, city.lat) > radius_km + city.radius_km {
+                continue;
+            }
+            if city.blackout_frames == 0 {
+                self.blacked_out.push(idx);
+            }
+            city.blackout_frames = EMP_BLACKOUT_FRAMES;
+        }
+        for site in &mut self.defenses {
+            if fast_distance_km(lon, lat, site.lon, site.lat) <= radius_km {
+                site.blackout = EMP_BLACKOUT_FRAMES;
+            }
+        }
+    }
+
+    /// Infect cities under a bio strike, hardest at the center. Starts a new
+    /// outbreak (and cure clock) unless one is already spreading.
+    fn seed_infection(&mut self, lon: f64, lat: f64, radius_km: f64) {
+        if self.outbreak.is_none_or(|o| o.is_cured()) {
+            self.outbreak = Some(Outbreak::new(self.r0, self.mortality));
+        }
+        let candidates = self.map_renderer.city_grid.query_radius(lon, lat, (radius_km + 50.0) / 111.0);
+        for idx in candidates {
+            let Some(city) = self.map_renderer.city_grid.get_mut(idx) else { continue };
+            let dist = fast_distance_km(lon, lat, city.lon, city.lat);
+            if city.population == 0 || dist > radius_km + city.radius_km {
+                continue;
+            }
+            if city.infection == 0.0 {
+                self.infected.push(idx);
+            }
+            let exposure = (1.0 - dist / radius_km).max(0.0) as f32;
+            city.infection = city.infection.max(0.05 + 0.25 * exposure);
+        }
+    }
+
+    /// Advance the outbreak: grow and kill within infected cities, then let
+    /// contagious ones seed their neighbors
+    fn update_contagion(&mut self) {
+        let Some(outbreak) = self.outbreak.as_mut() else { return };
+        outbreak.cure_in = outbreak.cure_in.saturating_sub(1);
+        if outbreak.cure_in == 0 && self.infected.is_empty() {
+            self.outbreak = None;
+            return;
+        }
+        if !self.frame.is_multiple_of(Outbreak::INTERVAL) {
+            return;
+        }
+        let outbreak = *outbreak;
+        let seed = self.rng_seed();
+
+        let grid = &mut self.map_renderer.city_grid;
+        let mut killed = 0;
+        let mut contagious = Vec::new();
+        let alerts = &mut self.alerts;
+        self.infected.retain(|&idx| {
+            let Some(city) = grid.get_mut(idx) else { return false };
+            city.infection = outbreak.grow(city.infection);
+            let dead = (city.population as f64 * outbreak.deaths(city.infection)) as u64;
+            if dead > 0 {
+                city.set_population(city.population - dead);
+                killed += dead;
+                if city.population == 0 {
+                    alerts.push(format!("{} wiped out by plague", city.name));
+                }
+            }
+            if city.infection < MIN_INFECTION || city.population == 0 {
+                city.infection = 0.0;
+                return false;
+            }
+            if city.infection >= Outbreak::CONTAGIOUS {
+                contagious.push((idx, city.lon, city.lat, city.infection));
+            }
+            true
+        });
+        self.casualties += killed;
+
+        if outbreak.is_cured() {
+            return;
+        }
+        // Travellers carry it along the routes, however far
+        for &(source, _, _, infection) in &contagious {
+            for (idx, weight) in self.travel.neighbors(source) {
+                let Some(city) = grid.get_mut(idx) else { continue };
+                if city.infection > 0.0 || city.population == 0 {
+                    continue;
+                }
+                let chance = outbreak.route_chance(infection, weight);
+                if rand_simple(hash3(hash2(seed, source as u64), idx as u64, !self.frame)) < chance {
+                    city.infection = SEED_INFECTION;
+                    self.infected.push(idx);
+                }
+            }
+        }
+        for (source, lon, lat, infection) in contagious {
+            for idx in grid.query_radius(lon, lat, Outbreak::SPREAD_KM / 111.0) {
+                let Some(city) = grid.get_mut(idx) else { continue };
+                if city.infection > 0.0 || city.population == 0 {
+                    continue;
+                }
+                let chance = outbreak.spread_chance(infection, fast_distance_km(lon, lat, city.lon, city.lat));
+                if rand_simple(hash3(hash2(seed, source as u64), idx as u64, self.frame)) < chance {
+                    city.infection = SEED_INFECTION;
+                    self.infected.push(idx);
+                }
+            }
+        }
+    }
+
+    /// Link the biggest cities loaded into the travel network. Call once
+    /// the cities are in: routes are kept by city grid index.
+    pub fn rebuild_travel(&mut self) {
+        let grid = &self.map_renderer.city_grid;
+        self.travel = TravelNetwork::build(grid.iter().enumerate().map(|(idx, c)| Hub { idx, lon: c.lon, lat: c.lat, population: c.population }));
+    }
+
+    /// Enter or leave pandemic mode. Entering stands the adversary down and
+    /// drops any queued strikes.
+    pub fn set_pandemic(&mut self, on: bool) {
+        self.pandemic = on;
+        if on {
+            self.adversary = None;
+            self.clear_queue();
+            self.strike_anchor = None;
+            if self.travel.is_empty() {
+                self.rebuild_travel();
+            }
+        }
+        self.status_message = Some(if on {
+            "Pandemic mode — click a city to seed an outbreak".to_string()
+        } else {
+            "Pandemic mode off".to_string()
+        });
+    }
+
+    /// Start (or add to) an outbreak in the city nearest the click
+    pub fn seed_outbreak(&mut self, col: u16, row: u16) {
+        let px = ((col.saturating_sub(1)) as i32) * 2;
+        let py = ((row.saturating_sub(1)) as i32) * 4;
+        let Some((lon, lat)) = self.projection.unproject(px, py) else { return };
+        let grab_km = 6.0 / self.projection.deg_to_pixels(1.0).max(1e-6) * 111.0;
+        let grid = &self.map_renderer.city_grid;
+        let nearest = grid.query_radius(lon, lat, grab_km / 111.0)
+            .into_iter()
+            .filter_map(|idx| grid.get(idx).map(|c| (idx, c)))
+            .filter(|(_, c)| c.population > 0)
+            .map(|(idx, c)| (fast_distance_km(lon, lat, c.lon, c.lat), idx))
+            .filter(|&(dist, _)| dist <= grab_km)
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        let Some((_, idx)) = nearest else {
+            self.status_message = Some("No city there to seed".to_string());
+            return;
+        };
+        self.infect_city(idx);
+    }
+
+    /// Patient zero: make a city contagious, starting an outbreak if none is spreading
+    fn infect_city(&mut self, idx: usize) {
+        if self.outbreak.is_none_or(|o| o.is_cured()) {
+            self.outbreak = Some(Outbreak::new(self.r0, self.mortality));
+        }
+        let Some(city) = self.map_renderer.city_grid.get_mut(idx) else { return };
+        if city.infection == 0.0 {
+            self.infected.push(idx);
         }
+        city.infection = city.infection.max(Outbreak::CONTAGIOUS);
+        let text = format!("Outbreak in {}", city.name);
+        self.log_event(EventKind::Outbreak, text, None);
     }
 
-    /// Update viewport size when terminal resizes
-    pub fn resize(&mut self, width: usize, height: usize) {
-        let inner_width = width.saturating_sub(2);
-        let inner_height = height.saturating_sub(3);
-        self.projection.set_size(inner_width * 2, inner_height * 4);
+    /// Step R0 for new outbreaks and the one spreading now
+    pub fn adjust_r0(&mut self, up: bool) {
+        let step = if up { R0_STEP } else { -R0_STEP };
+        self.r0 = (self.r0 + step).clamp(R0_STEP, MAX_R0);
+        if let Some(outbreak) = self.outbreak.as_mut() {
+            outbreak.r0 = self.r0;
+        }
+        self.status_message = Some(format!("R0: {:.2}", self.r0));
     }
 
-    /// Pan the map
-    pub fn pan(&mut self, dx: i32, dy: i32) {
-        self.projection.pan(dx, dy);
+    /// Scale lethality for new outbreaks and the one spreading now
+    pub fn adjust_lethality(&mut self, up: bool) {
+        let factor = if up { LETHALITY_STEP } else { 1.0 / LETHALITY_STEP };
+        self.mortality = (self.mortality * factor).clamp(MIN_MORTALITY, MAX_MORTALITY);
+        if let Some(outbreak) = self.outbreak.as_mut() {
+            outbreak.mortality = self.mortality;
+        }
+        self.status_message = Some(format!("Lethality: {}", format_lethality(self.mortality)));
     }
 
-    /// Zoom in
-    pub fn zoom_in(&mut self) {
-        match self.mouse_pos {
-            Some((col, row)) => self.zoom_in_at(col, row),
-            None => self.projection.zoom_in(),
+    /// Wash out soot and, past famine onset, shrink every city a little
+    fn update_climate(&mut self) {
+        self.climate.step();
+        if !self.frame.is_multiple_of(Climate::DECLINE_INTERVAL) {
+            return;
+        }
+        let rate = self.climate.decline_rate();
+        if rate <= 0.0 {
+            return;
+        }
+        let mut killed = 0;
+        for city in self.map_renderer.city_grid.iter_mut() {
+            let dead = ((city.population as f64 * rate) as u64).min(city.population);
+            if dead > 0 {
+                city.set_population(city.population - dead);
+                killed += dead;
+            }
         }
+        self.casualties += killed;
     }
 
-    /// Zoom out
-    pub fn zoom_out(&mut self) {
-        match self.mouse_pos {
-            Some((col, row)) => self.zoom_out_at(col, row),
-            None => self.projection.zoom_out(),
-        }
+    /// Show/hide the infection overlay
+    pub fn toggle_infection(&mut self) {
+        self.show_infection = !self.show_infection;
     }
 
-    /// Zoom in towards a screen position (terminal column/row)
-    pub fn zoom_in_at(&mut self, col: u16, row: u16) {
-        let px = ((col.saturating_sub(1)) as i32) * 2;
-        let py = ((row.saturating_sub(1)) as i32) * 4;
-        self.projection.zoom_in_at(px, py);
+    /// Show/hide the flight routes
+    pub fn toggle_flights(&mut self) {
+        self.show_flights = !self.show_flights;
+        self.status_message = Some(format!("Flight routes {}", if self.show_flights { "on" } else { "off" }));
     }
 
-    /// Zoom out from a screen position (terminal column/row)
-    pub fn zoom_out_at(&mut self, col: u16, row: u16) {
-        let px = ((col.saturating_sub(1)) as i32) * 2;
-        let py = ((row.saturating_sub(1)) as i32) * 4;
-        self.projection.zoom_out_at(px, py);
+    /// Show/hide the sun's reflection on the globe
+    pub fn toggle_glint(&mut self) {
+        self.show_glint = !self.show_glint;
+        let state = if self.show_glint { "on" } else { "off" };
+        self.status_message = Some(if self.is_globe() { format!("Sun glint {state}") } else { format!("Sun glint {state} (globe only)") });
     }
 
-    /// Request quit
-    pub fn quit(&mut self) {
-        self.should_quit = true;
+    /// Switch map lines between plain and anti-aliased
+    pub fn toggle_antialias(&mut self) {
+        let on = self.map_renderer.toggle_antialias();
+        self.status_message = Some(if on { "Anti-aliased lines".to_string() } else { "Plain lines".to_string() });
     }
 
-    /// Get current zoom level as a string
-    pub fn zoom_level(&self) -> String {
-        format!("{:.1}x", self.projection.effective_zoom())
+    /// Switch area overlays between shade glyphs and half blocks
+    pub fn toggle_fill_mode(&mut self) {
+        self.fill_mode = match self.fill_mode {
+            FillMode::Shades => FillMode::HalfBlocks,
+            FillMode::HalfBlocks => FillMode::Shades,
+        };
+        self.status_message = Some(match self.fill_mode {
+            FillMode::Shades => "Area fills: shades".to_string(),
+            FillMode::HalfBlocks => "Area fills: half blocks".to_string(),
+        });
     }
 
-    /// Get current center coordinates as a string
-    pub fn center_coords(&self) -> String {
-        let lat = self.projection.center_lat();
-        let lon = self.projection.center_lon();
-        format!(
-            "{:.1}°{}, {:.1}°{}",
-            lat.abs(),
-            if lat >= 0.0 { "N" } else { "S" },
-            lon.abs(),
-            if lon >= 0.0 { "E" } else { "W" }
-        )
+    /// Switch to the next color theme
+    pub fn cycle_theme(&mut self) {
+        self.theme = self.theme.next();
+        self.status_message = Some(format!("Theme: {}", self.theme.name));
     }
 
-    /// Get current LOD level as a string
-    pub fn lod_level(&self) -> &'static str {
-        match Lod::from_zoom(self.projection.effective_zoom()) {
-            Lod::Low => "110m",
-            Lod::Medium => "50m",
-            Lod::High => "10m",
+    /// Show/hide the performance HUD
+    pub fn toggle_perf_hud(&mut self) {
+        self.perf.show_hud = !self.perf.show_hud;
+    }
+
+    /// Step the frame-rate cap: 30, 60, uncapped
+    pub fn cycle_fps_cap(&mut self) {
+        self.perf.cap = self.perf.cap.next();
+        self.status_message = Some(format!("Frame rate: {}", self.perf.cap.label()));
+    }
+
+    /// Show/hide the radiation dose overlay
+    pub fn toggle_radiation(&mut self) {
+        self.show_radiation = !self.show_radiation;
+    }
+
+    /// Show/hide the population density choropleth
+    pub fn toggle_population(&mut self) {
+        if self.population.is_none() {
+            self.status_message = Some("No population raster loaded (--population FILE)".to_string());
+            return;
         }
+        self.show_population = !self.show_population;
     }
 
-    /// Handle mouse drag
-    pub fn handle_drag(&mut self, x: u16, y: u16) {
-        if let Some((last_x, last_y)) = self.last_mouse {
-            let dx = last_x as i32 - x as i32;
-            let dy = last_y as i32 - y as i32;
-            let zoom = self.projection.effective_zoom();
-            let scale = if zoom < 2.0 { 2 } else if zoom < 4.0 { 3 } else { 4 };
-            self.pan(dx * scale, dy * scale);
+    /// Show/hide one infrastructure layer, saying so since most are only
+    /// drawn once zoomed in
+    pub fn toggle_infrastructure(&mut self, kind: Infrastructure) {
+        let shown = self.map_renderer.toggle_infrastructure(kind);
+        self.status_message = Some(format!("{} {}", kind.label(), if shown { "shown" } else { "hidden" }));
+    }
 
-            if let Projection::Globe(ref g) = self.projection {
-                let ax = (dx * scale) as f64 / g.radius;
-                self.spin_velocity = self.spin_velocity * 0.5 + ax * 0.5;
+    /// Count down blackouts; power comes back when they reach zero
+    fn update_blackouts(&mut self) {
+        let grid = &mut self.map_renderer.city_grid;
+        self.blacked_out.retain(|&idx| match grid.get_mut(idx) {
+            Some(city) => {
+                city.blackout_frames = city.blackout_frames.saturating_sub(1);
+                city.blackout_frames > 0
             }
+            None => false,
+        });
+        for site in &mut self.defenses {
+            site.blackout = site.blackout.saturating_sub(1);
         }
-        self.last_mouse = Some((x, y));
     }
 
-    /// Reset drag state when mouse button released — momentum persists
-    pub fn end_drag(&mut self) {
-        self.last_mouse = None;
+    /// Surviving cities grow back toward their original size less those who
+    /// fled them, logistically: slowly when nearly wiped out or nearly
+    /// rebuilt. A city without power, with an outbreak or on contaminated
+    /// ground doesn't.
+    fn update_recovery(&mut self) {
+        let seed = hash2(self.rng_seed(), self.frame);
+        let radiation = &self.radiation;
+        let mut born = 0;
+        for (idx, city) in self.map_renderer.city_grid.iter_mut().enumerate() {
+            let capacity = city.original_population.saturating_sub(city.displaced);
+            if city.population == 0 || city.population >= capacity {
+                continue;
+            }
+            if city.blackout_frames > 0 || city.infection > 0.0 || radiation.dose_at(city.lon, city.lat) > RECOVERY_MAX_DOSE_GY {
+                continue;
+            }
+            let population = city.population as f64;
+            let growth = RECOVERY_RATE * population * (1.0 - population / capacity as f64);
+            // Round at random so small towns grow too, on average
+            let growth = (growth + rand_simple(hash2(seed, idx as u64))) as u64;
+            let growth = growth.min(capacity - city.population);
+            if growth > 0 {
+                city.set_population(city.population + growth);
+                born += growth;
+            }
+        }
+        self.stats.births += born;
     }
 
-    /// Cancel spin momentum (called on new drag start)
-    pub fn start_drag(&mut self, x: u16, y: u16) {
-        self.spin_velocity = 0.0;
-        self.last_mouse = Some((x, y));
+    /// Send a share of a struck city's survivors to the nearest cities still
+    /// intact and clear of the blast, and to those its flight routes reach
+    fn displace(&mut self, idx: usize, blast: (f64, f64), radius_km: f64) {
+        let grid = &self.map_renderer.city_grid;
+        let Some(city) = grid.get(idx) else { return };
+        let leaving = (city.population as f64 * Flow::SHARE) as u64;
+        if leaving < Flow::MIN_PEOPLE {
+            return;
+        }
+        let from = (city.lon, city.lat);
+        let safe = |c: &City| {
+            c.population > 0 && c.population >= c.original_population
+                && fast_distance_km(blast.0, blast.1, c.lon, c.lat) > radius_km + c.radius_km
+        };
+        let mut refuges = Vec::new();
+        for degrees in Flow::SEARCH_DEG {
+            refuges = grid
+                .query_radius(from.0, from.1, degrees)
+                .into_iter()
+                .filter(|&i| i != idx)
+                .filter_map(|i| grid.get(i).map(|c| (i, c)))
+                .filter(|&(_, c)| safe(c))
+                .map(|(i, c)| (fast_distance_km(from.0, from.1, c.lon, c.lat), i, (c.lon, c.lat)))
+                .collect();
+            if !refuges.is_empty() {
+                break;
+            }
+        }
+        refuges.sort_by(|a, b| a.0.total_cmp(&b.0));
+        refuges.truncate(Flow::DESTINATIONS);
+        for (to, _) in self.travel.neighbors(idx) {
+            let Some(c) = grid.get(to) else { continue };
+            if safe(c) && !refuges.iter().any(|r| r.1 == to) {
+                refuges.push((fast_distance_km(from.0, from.1, c.lon, c.lat), to, (c.lon, c.lat)));
+            }
+        }
+        for &(_, to, to_pos) in &refuges {
+            self.refugees.push(Flow::new(idx, from, to, to_pos, leaving / refuges.len() as u64));
+        }
     }
 
-    /// Update mouse cursor position
-    pub fn set_mouse_pos(&mut self, col: u16, row: u16) {
-        self.mouse_pos = Some((col, row));
+    /// Move the next share of each stream of refugees, as many as the
+    /// town taking them in has room for. A stream stops if either end is
+    /// destroyed.
+    fn update_refugees(&mut self) {
+        let grid = &mut self.map_renderer.city_grid;
+        self.refugees.retain_mut(|flow| {
+            let leaving = flow.tick();
+            let (Some(from), Some(to)) = (grid.get(flow.from), grid.get(flow.to)) else { return false };
+            if from.population == 0 || to.population == 0 {
+                return false;
+            }
+            let room = (to.original_population as f64 * (1.0 + Flow::MAX_INFLUX)) as u64;
+            let leaving = leaving.min(from.population).min(room.saturating_sub(to.population));
+            let (from_population, to_population) = (from.population - leaving, to.population + leaving);
+            if let Some(from) = grid.get_mut(flow.from) {
+                from.set_population(from_population);
+                from.displaced += leaving;
+            }
+            if let Some(to) = grid.get_mut(flow.to) {
+                to.set_population(to_population);
+            }
+            !flow.is_done()
+        });
     }
 
-    /// Get mouse position in braille pixel coordinates (for rendering marker)
-    pub fn mouse_pixel_pos(&self) -> Option<(i32, i32)> {
-        self.mouse_pos.map(|(col, row)| {
-            // Convert terminal coords to braille pixel coords
-            // Account for border (1 cell offset)
-            let px = ((col.saturating_sub(1)) as i32) * 2;
-            let py = ((row.saturating_sub(1)) as i32) * 4;
-            (px, py)
+    /// Emergency services rebuild toward what's left of each country
+    fn rebuild_response(&mut self) {
+        let mut populations: HashMap<&str, (u64, u64)> = HashMap::new();
+        for city in self.map_renderer.city_grid.iter() {
+            let total = populations.entry(city.country.as_str()).or_default();
+            total.0 += city.population;
+            total.1 += city.original_population;
+        }
+        self.response.rebuild(&populations);
+    }
+
+    /// Deaths less births: how many fewer people there are than at the start
+    pub fn net_casualties(&self) -> u64 {
+        self.casualties.saturating_sub(self.stats.births)
+    }
+
+    /// Is there land (or, with `land` false, open water) at any of eight
+    /// points `km` out from (lon, lat)?
+    fn surface_within(&self, lon: f64, lat: f64, km: f64, land: bool) -> bool {
+        let cos_lat = lat.to_radians().cos().max(0.1);
+        (0..8).any(|i| {
+            let angle = i as f64 * std::f64::consts::FRAC_PI_4;
+            let dlat = km * angle.sin() / 111.0;
+            let dlon = km * angle.cos() / (111.0 * cos_lat);
+            self.map_renderer.is_on_land(lon + dlon, lat + dlat) == land
         })
     }
 
-    /// Select active weapon
-    pub fn select_weapon(&mut self, weapon: WeaponType) {
-        self.active_weapon = weapon;
+    /// Advance wavefronts and flood the coasts they reach
+    fn update_tsunamis(&mut self) {
+        let mut tsunamis = std::mem::take(&mut self.tsunamis);
+        for wave in &mut tsunamis {
+            let landfalls = wave.step(|lon, lat| self.map_renderer.is_on_land(lon, lat));
+            let amplitude = wave.amplitude();
+            for (lon, lat) in landfalls {
+                self.flood_coast(wave, lon, lat, amplitude);
+            }
+        }
+        tsunamis.retain(|wave| !wave.is_spent());
+        self.tsunamis = tsunamis;
     }
 
-    /// Launch the active weapon at the given screen position
-    pub fn launch_nuke(&mut self, col: u16, row: u16) {
-        const NUKE_COOLDOWN_FRAMES: u64 = 15;
+    /// Random disasters while they're on, then eruptions and storms run
+    /// their course
+    fn update_disasters(&mut self) {
+        if self.disasters && self.frame.is_multiple_of(disasters::ROLL_FRAMES) {
+            let key = hash2(self.rng_seed(), self.frame);
+            match disasters::roll(self.rng_seed(), self.frame) {
+                Some(Hazard::Volcano) => {
+                    let v = &disasters::VOLCANOES[(key % disasters::VOLCANOES.len() as u64) as usize];
+                    self.erupt(v.name, v.lon, v.lat);
+                }
+                Some(Hazard::Hurricane) => self.form_hurricane(Hurricane::spawn(key)),
+                Some(Hazard::Wildfire) => {
+                    // Open ground outside the polar ice
+                    let spot = (0..20u64).map(|i| {
+                        let lon = rand_simple(hash3(key, i, 1)) * 360.0 - 180.0;
+                        let lat = rand_simple(hash3(key, i, 2)) * 110.0 - 55.0;
+                        (lon, lat)
+                    }).find(|&(lon, lat)| self.map_renderer.is_on_land(lon, lat));
+                    if let Some((lon, lat)) = spot {
+                        self.start_wildfire(lon, lat, WILDFIRE_RADIUS_KM);
+                    }
+                }
+                None => {}
+            }
+        }
 
-        if self.frame < self.last_nuke_frame + NUKE_COOLDOWN_FRAMES {
-            return;
+        // Lava keeps welling up while the volcano erupts
+        let mut vents = Vec::new();
+        self.eruptions.retain_mut(|eruption| {
+            eruption.intensity = eruption.intensity.saturating_sub(1);
+            if eruption.intensity.is_multiple_of(LAVA_FRAMES) {
+                vents.push((eruption.lon, eruption.lat, eruption.intensity));
+            }
+            eruption.intensity > 0
+        });
+        for (lon, lat, intensity) in vents {
+            self.flow_lava(lon, lat, intensity);
         }
 
-        let px = ((col.saturating_sub(1)) as i32) * 2;
-        let py = ((row.saturating_sub(1)) as i32) * 4;
+        let mut hurricanes = std::mem::take(&mut self.hurricanes);
+        for storm in &mut hurricanes {
+            storm.step(self.map_renderer.is_on_land(storm.lon, storm.lat));
+            if self.frame.is_multiple_of(10) {
+                self.batter_coast(storm.lon, storm.lat, storm.strength);
+            }
+        }
+        hurricanes.retain(|storm| !storm.is_spent());
+        self.hurricanes = hurricanes;
+    }
 
-        // On globe, clicking outside the sphere does nothing
-        let (lon, lat) = match self.projection.unproject(px, py) {
-            Some(coords) => coords,
-            None => return,
-        };
+    /// Set a volcano erupting, or stoke one that already is
+    fn erupt(&mut self, name: &str, lon: f64, lat: f64) {
+        if let Some(eruption) = self.eruptions.iter_mut().find(|e| e.name == name && e.lon == lon && e.lat == lat) {
+            eruption.intensity = Eruption::INTENSITY;
+            return;
+        }
+        self.eruptions.push(Eruption::new(name, lon, lat));
+        self.flow_lava(lon, lat, Eruption::INTENSITY);
+        self.climate.inject(ERUPTION_AEROSOL_TG);
+        self.log_event(EventKind::Disaster(Hazard::Volcano), format!("{name} erupts"), None);
+    }
 
-        self.last_nuke_frame = self.frame;
+    /// Lava fires around a vent, hotter while the eruption is young
+    fn flow_lava(&mut self, lon: f64, lat: f64, intensity: u16) {
+        let heat = 140 + (115 * intensity as u32 / Eruption::INTENSITY as u32) as u8;
+        let key = hash3(self.rng_seed(), self.frame, 0x1A7A);
+        let cos_lat = lat.to_radians().cos().max(0.1);
+        for i in 0..LAVA_CELLS {
+            let angle = rand_simple(hash3(key, i, 1)) * std::f64::consts::TAU;
+            let dist = Eruption::LAVA_KM * rand_simple(hash3(key, i, 2));
+            let (lon, lat) = (lon + dist * angle.cos() / (111.0 * cos_lat), lat + dist * angle.sin() / 111.0);
+            if self.map_renderer.is_on_land(lon, lat) {
+                self.fires.ignite(lon, lat, heat, WeaponType::Nuke);
+            }
+        }
+    }
 
-        let weapon = self.active_weapon;
-        let base_radius = 50.0 + 700.0 / self.projection.effective_zoom();
-        let radius_km = match weapon {
-            WeaponType::Emp => base_radius * 1.5,
-            _ => base_radius,
-        };
+    fn form_hurricane(&mut self, storm: Hurricane) {
+        let place = self.place_name(storm.lon, storm.lat, Hurricane::RADIUS_KM);
+        self.log_event(EventKind::Disaster(Hazard::Hurricane), format!("Hurricane forms near {place}"), None);
+        self.hurricanes.push(storm);
+    }
 
-        self.explosions.push(Explosion {
-            lon,
-            lat,
-            frame: 0,
-            radius_km,
-            weapon_type: weapon,
-        });
+    fn start_wildfire(&mut self, lon: f64, lat: f64, radius_km: f64) {
+        self.spawn_fires(lon, lat, radius_km, WeaponType::Nuke, 0.5);
+        let place = self.place_name(lon, lat, radius_km + 100.0);
+        self.log_event(EventKind::Disaster(Hazard::Wildfire), format!("Wildfire near {place}"), None);
+    }
 
-        // Spawn gas clouds (Bio and Chem)
-        match weapon {
-            WeaponType::Bio | WeaponType::Chem => {
-                self.gas_clouds.push(GasCloud {
-                    lon,
-                    lat,
-                    current_radius_km: radius_km * 0.5,
-                    max_radius_km: radius_km * 3.0,
-                    intensity: 2000,
-                    weapon_type: weapon,
-                });
+    /// Wind and storm surge: coastal cities under a hurricane lose a share
+    /// of their people, more near the eye and in a stronger storm
+    fn batter_coast(&mut self, lon: f64, lat: f64, strength: f64) {
+        const COAST_KM: f64 = 40.0;
+        let reach = Hurricane::RADIUS_KM;
+        let candidates = self.map_renderer.city_grid.query_radius(lon, lat, (reach + 50.0) / 111.0);
+        for idx in candidates {
+            let Some(city) = self.map_renderer.city_grid.get(idx) else { continue };
+            let dist = fast_distance_km(lon, lat, city.lon, city.lat);
+            if city.population == 0 || dist > reach || !self.surface_within(city.lon, city.lat, COAST_KM.max(city.radius_km), false) {
+                continue;
+            }
+            let falloff = 1.0 - dist / reach;
+            if let Some(city) = self.map_renderer.city_grid.get_mut(idx) {
+                let killed = (city.population as f64 * HURRICANE_LETHALITY * strength * strength * falloff) as u64;
+                city.set_population(city.population - killed);
+                self.casualties += killed;
             }
-            _ => {}
         }
+    }
 
-        // Spawn fires (weapon-dependent)
-        match weapon {
-            WeaponType::Bio | WeaponType::Emp => {
-                // Bio and EMP produce no fires
-            }
-            _ => {
-                let area_km2 = std::f64::consts::PI * radius_km * radius_km;
-                let fire_scale = match weapon {
-                    WeaponType::Chem => 0.6,  // 60% fire count
-                    _ => 1.0,
-                };
-                let target_fires = (((area_km2 / 5.0) * fire_scale) as usize + 200).min(20000);
+    /// Flood coastal cities around a landfall point, each at most once per wave
+    fn flood_coast(&mut self, wave: &mut Tsunami, lon: f64, lat: f64, amplitude: f64) {
+        const LANDFALL_KM: f64 = 50.0;
+        const COAST_KM: f64 = 40.0;
 
-                self.fires.reserve(target_fires);
+        let candidates = self.map_renderer.city_grid.query_radius(lon, lat, (LANDFALL_KM + 50.0) / 111.0);
+        for idx in candidates {
+            if wave.flooded.contains(&idx) {
+                continue;
+            }
+            let Some(city) = self.map_renderer.city_grid.get(idx) else { continue };
+            if city.population == 0
+                || fast_distance_km(lon, lat, city.lon, city.lat) > LANDFALL_KM + city.radius_km
+                || !self.surface_within(city.lon, city.lat, COAST_KM.max(city.radius_km), false)
+            {
+                continue;
+            }
+            wave.flooded.push(idx);
+            if let Some(city) = self.map_renderer.city_grid.get_mut(idx) {
+                let killed = (city.population as f64 * FLOOD_LETHALITY * amplitude.min(1.0)) as u64;
+                city.set_population(city.population - killed);
+                self.casualties += killed;
+            }
+        }
+    }
 
-                let cos_lat = lat.to_radians().cos().max(0.1);
-                let mut spawned = 0;
-                let mut attempt = 0;
+    /// Scatter fires over a disc, densest (and hottest) toward the center.
+    /// Water cells are skipped. `fire_scale` scales the fire count.
+    fn spawn_fires(&mut self, lon: f64, lat: f64, radius_km: f64, weapon_type: WeaponType, fire_scale: f64) {
+        let area_km2 = std::f64::consts::PI * radius_km * radius_km;
+        let target_fires = (((area_km2 / 5.0) * fire_scale) as usize + 200).min(20000);
 
-                while spawned < target_fires && attempt < target_fires * 2 {
-                    let angle = rand_simple((attempt as u64).wrapping_mul(7919)) * std::f64::consts::TAU;
-                    let rand_dist = rand_simple((attempt as u64).wrapping_mul(6547));
-                    let dist = radius_km * rand_dist.sqrt();
+        let cos_lat = lat.to_radians().cos().max(0.1);
+        // Each strike gets its own scatter pattern
+        let strike_key = hash2(self.rng_seed(), self.frame);
+        let mut spawned = 0;
+        let mut attempt = 0;
 
-                    let dlat = (dist * angle.sin()) / 111.0;
-                    let dlon = (dist * angle.cos()) / (111.0 * cos_lat);
+        while spawned < target_fires && attempt < target_fires * 2 {
+            let angle = rand_simple(hash3(strike_key, attempt as u64, 7919)) * std::f64::consts::TAU;
+            let rand_dist = rand_simple(hash3(strike_key, attempt as u64, 6547));
+            let dist = radius_km * rand_dist.sqrt();
 
-                    let fire_lon = lon + dlon;
-                    let fire_lat = lat + dlat;
+            let dlat = (dist * angle.sin()) / 111.0;
+            let dlon = (dist * angle.cos()) / (111.0 * cos_lat);
 
-                    attempt += 1;
+            let fire_lon = lon + dlon;
+            let fire_lat = lat + dlat;
 
-                    if !self.map_renderer.is_on_land(fire_lon, fire_lat) {
-                        continue;
-                    }
+            attempt += 1;
 
-                    let center_factor = 1.0 - (dist / radius_km);
-                    let base_intensity = 60.0 + center_factor * 160.0;
-                    let intensity = (base_intensity + rand_simple((attempt as u64).wrapping_add(1000)) * 20.0).min(255.0) as u8;
+            if !self.map_renderer.is_on_land(fire_lon, fire_lat) {
+                continue;
+            }
 
-                    self.fires.push(Fire {
-                        lon: fire_lon,
-                        lat: fire_lat,
-                        intensity,
-                        weapon_type: weapon,
-                    });
+            let center_factor = 1.0 - (dist / radius_km);
+            let base_intensity = 60.0 + center_factor * 160.0;
+            let intensity = (base_intensity + rand_simple(hash3(strike_key, attempt as u64, 1000)) * 20.0).min(255.0) as u8;
 
-                    spawned += 1;
-                }
-            }
-        }
+            self.fires.ignite(fire_lon, fire_lat, intensity, weapon_type);
 
-        // Create fallout zone (weapon-dependent)
-        match weapon {
-            WeaponType::Emp => {
-                // EMP produces no fallout
-            }
-            _ => {
-                let (fallout_radius_mult, fallout_intensity) = match weapon {
-                    WeaponType::Bio => (3.0, 3000),    // 3× radius, 3× intensity
-                    WeaponType::Chem => (2.5, 2000),   // 2.5× radius, 2× intensity
-                    _ => (2.0, 1000),                   // Nuke default
-                };
-                self.fallout.push(Fallout {
-                    lon,
-                    lat,
-                    radius_km: radius_km * fallout_radius_mult,
-                    intensity: fallout_intensity,
-                });
-            }
+            spawned += 1;
         }
-
-        // Calculate immediate blast casualties
-        self.apply_blast_damage(lon, lat, radius_km);
     }
 
     /// Apply blast damage to cities within radius
@@ -473,6 +4496,8 @@ impl App {
         // Query spatial grid for cities in expanded radius
         let candidate_indices = self.map_renderer.city_grid.query_radius(lon, lat, query_radius_degrees);
 
+        let mut struck = Vec::new();
+        let mut countries: Vec<String> = Vec::new();
         for &idx in &candidate_indices {
             if let Some(city) = self.map_renderer.city_grid.get_mut(idx) {
                 // Skip dead cities early
@@ -511,138 +4536,330 @@ impl App {
 
                     city.set_population(city.population.saturating_sub(killed));
                     self.casualties += killed;
+                    if killed > 0 && !city.country.is_empty() && !countries.contains(&city.country) {
+                        countries.push(city.country.clone());
+                    }
+                    if city.population == 0 {
+                        self.alerts.push(format!("{} destroyed", city.name));
+                    } else {
+                        struck.push(idx);
+                    }
                 }
             }
         }
+        for idx in struck {
+            self.displace(idx, (lon, lat), radius_km);
+        }
+        for country in countries {
+            if let Some(status) = self.response.strike(&country) {
+                let saving = Response::MAX_RELIEF * self.response.level(&country) * 100.0;
+                let text = format!("{country} emergency response {}: saving {saving:.0}% of the injured", status.label());
+                self.log_event(EventKind::Response, text, None);
+            }
+        }
+
+        // Everyone else under the blast, on the same falloff
+        if let Some(grid) = self.population.as_mut() {
+            self.casualties += grid.kill_within(lon, lat, radius_km, |dist| {
+                if dist < radius_km * 0.3 {
+                    (1.0 - (dist / (radius_km * 0.3)).powi(2)).max(0.8)
+                } else {
+                    (1.0 - (dist / radius_km).powi(2)) * 0.7
+                }
+            });
+        }
     }
 
-    /// Update explosion animations, returns true if any are active
-    pub fn update_explosions(&mut self) -> bool {
-        // Increment global frame counter for randomness
-        self.frame = self.frame.wrapping_add(1);
+    /// Advance one rendered frame: camera animation, then as many fixed
+    /// simulation steps as `wall_dt` seconds are worth at the current rate
+    /// (none while paused or behind the summary screen). Returns the
+    /// number of steps run.
+    pub fn update(&mut self, wall_dt: f64) -> usize {
+        self.update_camera();
+        if self.history.is_scrubbing() {
+            self.update_parked_world();
+        }
+        if self.paused || self.history.is_scrubbing() || self.game.is_over() {
+            return 0;
+        }
+        self.step_accum += wall_dt.min(0.25) * self.sim_rate / SimClock::FRAME_SECS;
+        let mut steps = 0;
+        while self.step_accum >= 1.0 {
+            if steps == MAX_STEPS_PER_FRAME {
+                // Too far behind (8x on a slow machine) — drop the backlog rather than stall
+                self.step_accum = 0.0;
+                break;
+            }
+            self.step();
+            self.step_accum -= 1.0;
+            steps += 1;
+        }
+        steps
+    }
+
+    /// Freeze or resume the simulation; rendering and the camera keep going
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.status_message = Some(if self.paused { "Paused".to_string() } else { "Resumed".to_string() });
+    }
+
+    /// Step the simulation rate up (`faster`) or down through `SIM_RATES`
+    pub fn change_sim_rate(&mut self, faster: bool) {
+        let i = SIM_RATES.iter().position(|&r| r == self.sim_rate).unwrap_or(2);
+        let i = if faster { (i + 1).min(SIM_RATES.len() - 1) } else { i.saturating_sub(1) };
+        self.sim_rate = SIM_RATES[i];
+        self.status_message = Some(format!("Simulation speed {}", format_rate(self.sim_rate)));
+    }
+
+    /// Start or stop turning the globe while idle
+    pub fn toggle_auto_rotate(&mut self) {
+        self.auto_rotate = !self.auto_rotate;
+        let state = if self.auto_rotate { "on" } else { "off" };
+        self.status_message = Some(if self.is_globe() { format!("Auto-rotate {state}") } else { format!("Auto-rotate {state} (globe only)") });
+    }
+
+    /// A key or mouse event arrived: hand the globe back to the user. Any
+    /// auto-rotation left in `spin_velocity` winds down like a flick would.
+    pub fn note_input(&mut self) {
+        self.idle_frames = 0;
+    }
+
+    /// Fly-to animation and globe spin momentum. View only — the simulation never reads it.
+    fn update_camera(&mut self) {
+        // Advance camera animation
+        if let Some(fly) = self.fly_to.as_mut() {
+            fly.frame += 1;
+            let (lon, lat, zoom) = fly.sample();
+            let done = fly.frame >= FlyTo::FRAMES;
+            self.projection.set_view(lon, lat, zoom);
+            if done {
+                self.fly_to = None;
+            }
+        }
+
+        // Wheel zoom: a share of what is left each frame, the rest at once
+        // when it is too small to see
+        if let Some(ease) = self.zoom_ease.as_mut() {
+            let step = if ease.log_factor.abs() < 0.002 { ease.log_factor } else { ease.log_factor * ZOOM_EASE };
+            ease.log_factor -= step;
+            let (px, py, done) = (ease.px, ease.py, ease.log_factor == 0.0);
+            self.projection.zoom_at(px, py, step.exp());
+            if done {
+                self.zoom_ease = None;
+            }
+        }
+
+        // Left alone long enough, ease the spin up to auto-rotation speed
+        // (eastward, as the Earth turns) instead of letting it decay
+        self.idle_frames = self.idle_frames.saturating_add(1);
+        let idle = self.auto_rotate && self.idle_frames >= AUTO_ROTATE_IDLE_FRAMES && self.fly_to.is_none() && self.is_globe();
 
         // Apply globe spin momentum (only when not dragging)
-        if self.last_mouse.is_none() {
-            if self.spin_velocity.abs() > 0.0001 {
-                if let Projection::Globe(ref mut g) = self.projection {
-                    g.apply_momentum(self.spin_velocity, 0.0);
-                    // Decay faster when zoomed in — same angular velocity moves more screen pixels
-                    let decay = 0.995_f64.powf(g.effective_zoom());
-                    self.spin_velocity *= decay;
-                }
+        if self.last_mouse.is_none() && (idle || self.spin_velocity.abs() > 0.0001) {
+            if idle {
+                self.spin_velocity += (-AUTO_ROTATE_SPEED - self.spin_velocity) * 0.02;
+            }
+            self.projection.view_mut().spin(self.spin_velocity);
+            if !idle {
+                // Decay faster when zoomed in — same angular velocity moves more screen pixels
+                let decay = SPIN_DECAY.powf(self.projection.effective_zoom() * self.friction);
+                self.spin_velocity *= decay;
             }
         }
 
+        // Flat maps glide on after a flick, whole pixels at a time
+        let (vx, vy) = self.pan_velocity;
+        if self.last_mouse.is_none() && vx.hypot(vy) > MIN_PAN_SPEED && !self.is_globe() {
+            let (cx, cy) = (self.pan_carry.0 + vx, self.pan_carry.1 + vy);
+            let (dx, dy) = (cx.round(), cy.round());
+            self.pan_carry = (cx - dx, cy - dy);
+            self.pan(dx as i32, dy as i32);
+            let decay = PAN_DECAY.powf(self.friction);
+            self.pan_velocity = (vx * decay, vy * decay);
+        }
+    }
+
+    /// One simulation step. Touches neither the view nor the terminal, so it
+    /// also drives headless runs. Returns true if any effects are active.
+    pub fn step(&mut self) -> bool {
+        // Increment global frame counter for randomness
+        self.frame = self.frame.wrapping_add(1);
+        if self.alerts.len() > MAX_ALERTS {
+            self.alerts.drain(..self.alerts.len() - MAX_ALERTS);
+        }
+        let sim_dt = self.clock.tick();
+
         self.explosions.retain_mut(|exp| {
             exp.frame += 1;
             exp.frame < exp.weapon_type.max_frames()
         });
-
-        // Update fires - VERY slow decay and VERY aggressive spreading
-        // Pre-allocate for spreading fires (estimate ~15% spread rate × avg 1.5 fires)
-        let mut new_fires = Vec::with_capacity(self.fires.len() / 5);
-        self.fires.retain_mut(|fire| {
-            // VERY SLOW decay - only decay every 5 frames (5x longer fires!)
-            if self.frame % 5 == 0 {
-                fire.intensity = fire.intensity.saturating_sub(1);
-            }
-
-            // VERY aggressive spreading - fires spread like wildfire
-            let should_check_spread = fire.intensity > 60;  // Even weak fires spread
-            if should_check_spread {
-                // Use both lon and lat for unique per-fire randomness
-                let lon_bits = (fire.lon * 10000.0).to_bits();
-                let lat_bits = (fire.lat * 10000.0).to_bits();
-                let rand_val = rand_simple(hash3(lon_bits, lat_bits, self.frame));
-                if rand_val > 0.85 {  // Much more frequent spreading (was 0.92)
-                    // Spawn 1-3 spread fires per spread event
-                    let num_spreads = if rand_simple(hash3(lat_bits, lon_bits, self.frame)) > 0.7 { 2 } else { 1 };
-
-                    for s in 0..num_spreads {
-                        // Include frame so each spread event goes a different direction
-                        let spread_seed = hash3(lon_bits, lat_bits, self.frame.wrapping_add(s as u64));
-                        let spread_dist = 0.03 + rand_simple(spread_seed) * 0.15;
-                        let angle = rand_simple(spread_seed.wrapping_mul(31337)) * std::f64::consts::TAU;
-
-                        let new_lon = fire.lon + spread_dist * angle.cos();
-                        let new_lat = fire.lat + spread_dist * angle.sin();
-
-                        // Collect all potential spread fires (land check happens later)
-                        new_fires.push(Fire {
-                            lon: new_lon,
-                            lat: new_lat,
-                            intensity: fire.intensity.saturating_sub(10),
-                            weapon_type: fire.weapon_type,
-                        });
-                    }
-                }
+
+        if self.spectating.is_some() {
+            self.update_spectating();
+            self.dashboard.record(self.casualties, self.fires.fine.burning, self.radiation.area_km2());
+            return !self.explosions.is_empty() || !self.fires.is_empty();
+        }
+
+        self.update_scenario();
+        self.update_net();
+        self.update_defenses();
+        self.update_platforms();
+        self.update_strike_package();
+        for (weapon, stock) in WeaponType::ALL.iter().zip(&mut self.stockpiles) {
+            stock.tick(weapon.restock_frames());
+        }
+
+        // Advance missiles; detonate the ones that arrived
+        let mut arrived = Vec::new();
+        let mut released = Vec::new();
+        self.projectiles.retain_mut(|p| {
+            p.frame += 1;
+            if !p.warheads.is_empty() && p.frame >= Projectile::SPLIT_FRAME {
+                released.extend(p.split());
+                return false;
+            }
+            if p.frame >= p.flight_frames() {
+                arrived.push((p.from, p.to, p.weapon_type, p.radius_km, p.yield_kt, p.hostile));
+                return false;
+            }
+            true
+        });
+        self.projectiles.extend(released);
+        for (origin, (lon, lat), weapon, radius_km, yield_kt, hostile) in arrived {
+            self.detonate(lon, lat, weapon, radius_km, yield_kt);
+            if !hostile {
+                self.provoke(origin, lon, lat);
             }
+        }
+        self.update_adversary();
+        self.update_contagion();
+        self.update_tsunamis();
+        self.update_disasters();
+        if self.frame.is_multiple_of(Flow::TICK_FRAMES) {
+            self.update_refugees();
+        }
 
-            fire.intensity > 0
-        });
+        if self.dust > 0.0 {
+            self.dust *= DUST_RETAINED_PER_STEP;
+            if self.dust < 0.01 {
+                self.dust = 0.0;
+            }
+        }
 
-        // Filter out fires that would spawn on water (only keep land fires)
-        new_fires.retain(|fire| self.map_renderer.is_on_land(fire.lon, fire.lat));
+        // Fires decay and spread in place on their grid
+        let (weather, wind, cover) = (&self.weather, &self.wind, &self.land_cover);
+        let renderer = &self.map_renderer;
+        let burning = self.fires.step(
+            self.frame,
+            self.rng_seed(),
+            |lon, lat| FireConditions { rain: weather.rain_at(lon, lat), wind: wind.sample(lon, lat), cover: cover.at(lon, lat) },
+            |lon, lat| renderer.is_on_land(lon, lat).then(|| cover.at(lon, lat)),
+        );
 
-        // Add spread fires (massive limit for apocalyptic infernos)
-        // Check cap BEFORE spawning to avoid wasted allocations
-        let fires_remaining = 30000_usize.saturating_sub(self.fires.len());
-        if fires_remaining > 0 {
-            let to_add = new_fires.len().min(fires_remaining);
-            self.fires.extend(new_fires.into_iter().take(to_add));
-        }
+        // Firestorms loft soot into the stratosphere
+        self.climate.inject(burning as f64 * Climate::TG_PER_FIRE_INTENSITY);
+        self.update_climate();
 
-        // Update fallout - decay slowly
+        // Update fallout - decay on simulated time, not frames, so clock
+        // speed controls how fast contamination clears
+        self.fallout_decay_accum += sim_dt * FALLOUT_DECAY_PER_SIM_SEC;
+        let decay = self.fallout_decay_accum.floor();
+        self.fallout_decay_accum -= decay;
+        let decay = decay.min(u16::MAX as f64) as u16;
+        let wind = &self.wind;
         self.fallout.retain_mut(|zone| {
-            zone.intensity = zone.intensity.saturating_sub(1);
+            zone.intensity = zone.intensity.saturating_sub(decay);
+            zone.plume.step(wind, sim_dt);
             zone.intensity > 0
         });
+        self.radiation.decay(sim_dt);
+
+        for eruption in &mut self.eruptions {
+            eruption.plume.step(wind, sim_dt);
+        }
 
-        // Update gas clouds - expand radius asymptotically, decay intensity
+        // Update gas clouds - spread and drift downwind, decay intensity
         self.gas_clouds.retain_mut(|cloud| {
-            let gap = cloud.max_radius_km - cloud.current_radius_km;
-            cloud.current_radius_km += gap * 0.005;
+            cloud.plume.step(wind, sim_dt);
             cloud.intensity = cloud.intensity.saturating_sub(1);
             cloud.intensity > 0
         });
 
+        // Belts migrate with the season — re-derive occasionally
+        if self.frame.is_multiple_of(600) {
+            self.wind.update_season(self.clock.unix_secs());
+        }
+
+        if self.frame.is_multiple_of(RECOVERY_INTERVAL) {
+            self.update_recovery();
+            self.rebuild_response();
+        }
+        if self.frame.is_multiple_of(15) {
+            self.refresh_faction_stats();
+        }
+        self.update_game();
+
+        // Weather evolves on sim time; a rebuild every 30 frames is smooth enough
+        if self.frame.is_multiple_of(30) {
+            self.weather.update(&self.wind, self.clock.unix_secs());
+        }
+
         // Apply ongoing damage every 10 frames (imperceptible skip)
         // Flipped join: iterate cities and probe fire grid, not fires → city query.
         // O(7K cities) with O(1) grid lookups instead of O(25K fires) with HashMap queries.
-        if self.frame % 10 == 0 {
+        if self.frame.is_multiple_of(10) {
             self.apply_fire_damage_to_cities();
+            self.apply_fire_damage_to_population();
 
-            // Fallout damage (few zones, keep the per-zone city query)
-            for i in 0..self.fallout.len() {
-                let zone = &self.fallout[i];
-                if zone.intensity > 0 {
-                    let rate = (zone.intensity as f64 / 10000.0) * 0.05;
-                    let lon = zone.lon;
-                    let lat = zone.lat;
-                    let radius_km = zone.radius_km;
-                    self.apply_ongoing_damage(lon, lat, radius_km, rate);
-                }
+            // Fallout and gas damage per puff (few zones, keep the per-puff city query).
+            // Dose is split by puff weight so a plume's total stays constant as it streaks.
+            let mut doses: Vec<(f64, f64, f64, f64)> = Vec::new();
+            for zone in self.fallout.iter().filter(|z| z.intensity > 0) {
+                let rate = (zone.intensity as f64 / 10000.0) * 0.05;
+                Self::collect_plume_doses(&zone.plume, rate, &mut doses);
+            }
+            // Whatever falls on the ground stays there
+            for &(lon, lat, radius_km, rate) in &doses {
+                self.radiation.deposit(lon, lat, radius_km, (rate * DOSE_GY_PER_RATE) as f32);
+            }
+            for cloud in self.gas_clouds.iter().filter(|c| c.intensity > 0) {
+                let rate = (cloud.intensity as f64 / 10000.0) * 0.03;
+                Self::collect_plume_doses(&cloud.plume, rate, &mut doses);
+            }
+            // Ashfall collapses roofs and chokes, but far less than gas
+            for eruption in &self.eruptions {
+                let rate = (eruption.intensity as f64 / Eruption::INTENSITY as f64) * ASH_RATE;
+                Self::collect_plume_doses(&eruption.plume, rate, &mut doses);
             }
+            for (lon, lat, radius_km, rate) in doses {
+                self.apply_ongoing_damage(lon, lat, radius_km, rate);
+            }
+        }
 
-            // Gas cloud damage
-            for i in 0..self.gas_clouds.len() {
-                let cloud = &self.gas_clouds[i];
-                if cloud.intensity > 0 {
-                    let rate = (cloud.intensity as f64 / 10000.0) * 0.03;
-                    let lon = cloud.lon;
-                    let lat = cloud.lat;
-                    let radius_km = cloud.current_radius_km;
-                    self.apply_ongoing_damage(lon, lat, radius_km, rate);
-                }
+        // Fires spread and decay slowly, so scorching every 5 frames is plenty
+        if self.frame.is_multiple_of(5) {
+            self.burn_scars.record(&self.fires);
+        }
+
+        self.dashboard.record(self.casualties, self.fires.fine.burning, self.radiation.area_km2());
+        if self.frame.is_multiple_of(history::SNAPSHOT_FRAMES) {
+            self.history.record(&self.history_snapshot());
+        }
+
+        if self.frame.is_multiple_of(spectate::SYNC_INTERVAL) {
+            if let Some(mut host) = self.spectators.take() {
+                host.broadcast(self);
+                self.spectators = Some(host);
             }
         }
 
-        // Rebuild fire grids every 5 frames — fires spread/decay slowly,
-        // so the grid is accurate enough between rebuilds.
-        // Saves 60K grid insertions/frame → 12K/frame (5× reduction).
-        if self.frame % 5 == 0 {
-            self.fire_grid.rebuild(&self.fires);
-            self.fire_grid_fine.rebuild(&self.fires);
+        if self.frame.is_multiple_of(stats_csv::INTERVAL) {
+            if let Some(mut csv) = self.stats_out.take() {
+                match csv.record(self) {
+                    Ok(()) => self.stats_out = Some(csv),
+                    Err(e) => self.status_message = Some(format!("Stats export stopped: {e}")),
+                }
+            }
         }
 
         !self.explosions.is_empty() || !self.fires.is_empty() || !self.fallout.is_empty() || !self.gas_clouds.is_empty()
@@ -653,9 +4870,9 @@ impl App {
     /// Damage scales by fire intensity (not just presence) for distance-aware decay.
     fn apply_fire_damage_to_cities(&mut self) {
         let rate = 0.01;
-        let res = self.fire_grid_fine.resolution;
-        let width = self.fire_grid_fine.width;
-        let height = self.fire_grid_fine.height;
+        let res = self.fires.fine.resolution;
+        let width = self.fires.fine.width;
+        let height = self.fires.fine.height;
 
         for idx in 0..self.map_renderer.city_grid.len() {
             let (pop, orig_pop) = {
@@ -678,7 +4895,7 @@ impl App {
                 for dx in -1i32..=1 {
                     let nx = (cx + dx).clamp(0, width as i32 - 1) as usize;
                     let ny = (cy + dy).clamp(0, height as i32 - 1) as usize;
-                    let cell_intensity = self.fire_grid_fine.cells[ny * width + nx];
+                    let cell_intensity = self.fires.fine.cells[ny * width + nx];
                     if cell_intensity > 50 {
                         // Normalize intensity to 0..1, center cell weighted more
                         let weight = if dx == 0 && dy == 0 { 2.0 } else { 1.0 };
@@ -688,8 +4905,8 @@ impl App {
             }
 
             if intensity_sum > 0.0 {
-                let damage = (pop as f64 * rate * intensity_sum) as u64;
                 if let Some(city) = self.map_renderer.city_grid.get_mut(idx) {
+                    let damage = (pop as f64 * rate * intensity_sum * self.response.mortality(&city.country)) as u64;
                     if damage == 0 && city.population < orig_pop / 20 {
                         // Collapse: infrastructure fails below 5% of original
                         self.casualties += city.population;
@@ -698,6 +4915,9 @@ impl App {
                         city.set_population(city.population.saturating_sub(damage));
                         self.casualties += damage;
                     }
+                    if city.population == 0 {
+                        self.alerts.push(format!("{} burned out", city.name));
+                    }
                 }
             }
         }
@@ -705,16 +4925,30 @@ impl App {
 
     /// Toggle between Mercator and Globe projection
     pub fn toggle_projection(&mut self) {
-        let old = std::mem::replace(
-            &mut self.projection,
-            Projection::Mercator(Viewport::world(1, 1)), // placeholder
-        );
-        self.projection = old.toggle();
+        self.projection.toggle();
+        self.status_message = Some(format!("Projection: {}", self.projection.name()));
     }
 
     /// Whether we're in globe mode
     pub fn is_globe(&self) -> bool {
-        matches!(self.projection, Projection::Globe(_))
+        self.projection.is_globe()
+    }
+
+    /// Burn the raster population under the fire grid, at the same rate as
+    /// cities caught in a fire
+    fn apply_fire_damage_to_population(&mut self) {
+        let Some(grid) = self.population.as_mut() else { return };
+        for (lon, lat, intensity, _) in self.fires.fine.fires_in_region(-180.0, -90.0, 180.0, 90.0) {
+            if intensity > 50 {
+                self.casualties += grid.kill_at(lon, lat, 0.01 * intensity as f64 / 255.0);
+            }
+        }
+    }
+
+    /// Split a plume's damage rate across its puffs as (lon, lat, radius_km, rate)
+    fn collect_plume_doses(plume: &Plume, rate: f64, out: &mut Vec<(f64, f64, f64, f64)>) {
+        let total = plume.total_weight() as f64;
+        out.extend(plume.puffs.iter().map(|p| (p.lon, p.lat, p.radius_km, rate * p.weight as f64 / total)));
     }
 
     /// Apply ongoing fallout damage with inverse-square distance falloff.
@@ -739,7 +4973,7 @@ impl App {
                     // Distance falloff: full rate at center, drops with square of distance
                     let normalized = (dist / radius_km).min(1.0);
                     let falloff = (1.0 - normalized * normalized).max(0.0);
-                    let damage = (city.population as f64 * rate * falloff) as u64;
+                    let damage = (city.population as f64 * rate * falloff * self.response.mortality(&city.country)) as u64;
 
                     if damage == 0 && city.population < city.original_population / 20 {
                         // Collapse: infrastructure fails below 5% of original
@@ -749,6 +4983,9 @@ impl App {
                         city.set_population(city.population.saturating_sub(damage));
                         self.casualties += damage;
                     }
+                    if city.population == 0 {
+                        self.alerts.push(format!("{} lost to fallout", city.name));
+                    }
                 }
             }
         }
@@ -776,3 +5013,1053 @@ fn fast_distance_km(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
     R * (dx * dx + dy * dy).sqrt()
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_runs_steps_for_wall_time_and_rate() {
+        let mut app = App::new(80, 24);
+        assert_eq!(app.update(SimClock::FRAME_SECS * 3.0), 3);
+
+        app.change_sim_rate(false);
+        app.change_sim_rate(false);
+        assert_eq!(app.sim_rate, 0.25);
+        let steps: usize = (0..8).map(|_| app.update(SimClock::FRAME_SECS)).sum();
+        assert_eq!(steps, 2);
+
+        for _ in 0..10 {
+            app.change_sim_rate(true);
+        }
+        assert_eq!(app.sim_rate, 8.0);
+        assert_eq!(app.update(SimClock::FRAME_SECS), 8);
+
+        let frame = app.frame;
+        app.toggle_pause();
+        assert_eq!(app.update(1.0), 0);
+        assert_eq!(app.frame, frame);
+
+        // The world holds still behind the summary screen
+        app.toggle_pause();
+        app.end_game(Outcome::Victory);
+        assert_eq!(app.update(1.0), 0);
+        app.dismiss_summary();
+        assert!(app.update(SimClock::FRAME_SECS) > 0);
+    }
+
+    #[test]
+    fn surviving_cities_regrow_unless_blacked_out_infected_or_contaminated() {
+        let mut app = App::new(80, 24);
+        for (lon, lat, name) in [(139.7, 35.7, "Tokyo"), (77.2, 28.6, "Delhi"), (-58.4, -34.6, "Buenos Aires"), (2.35, 48.85, "Paris"), (-74.0, 40.7, "New York")] {
+            app.map_renderer.add_city(lon, lat, name, 2_000_000, false, false, "");
+        }
+        for i in 0..5 {
+            let city = app.map_renderer.city_grid.get_mut(i).unwrap();
+            city.set_population(city.original_population / 2);
+        }
+        let city = |app: &App, i: usize| app.map_renderer.city_grid.get(i).unwrap().clone();
+        app.map_renderer.city_grid.get_mut(1).unwrap().blackout_frames = 100;
+        app.map_renderer.city_grid.get_mut(2).unwrap().infection = 0.2;
+        let (lon, lat) = (city(&app, 3).lon, city(&app, 3).lat);
+        app.radiation.deposit(lon, lat, 30.0, 5.0);
+        app.map_renderer.city_grid.get_mut(4).unwrap().set_population(0);
+        app.casualties = 10_000_000;
+
+        // Half an hour of play
+        for _ in 0..1800 {
+            app.update_recovery();
+        }
+        let rebuilt = city(&app, 0);
+        assert!(rebuilt.population > rebuilt.original_population * 17 / 20, "{}", rebuilt.population);
+        assert!(rebuilt.population <= rebuilt.original_population);
+        for i in 1..4 {
+            assert_eq!(city(&app, i).population, city(&app, i).original_population / 2, "{}", city(&app, i).name);
+        }
+        assert_eq!(city(&app, 4).population, 0);
+        assert_eq!(app.stats.births, rebuilt.population - rebuilt.original_population / 2);
+        assert_eq!(app.net_casualties(), 10_000_000 - app.stats.births);
+    }
+
+    #[test]
+    fn survivors_flee_to_the_nearest_intact_cities() {
+        let mut app = App::new(80, 24);
+        for (lon, name) in [(0.0, "Struck"), (1.0, "Near"), (1.5, "Nearer still"), (-2.0, "Further"), (2.5, "Furthest")] {
+            app.map_renderer.add_city(lon, 0.0, name, 1_000_000, false, false, "");
+        }
+        let population = |app: &App, i: usize| app.map_renderer.city_grid.get(i).unwrap().population;
+        app.apply_blast_damage(0.0, 0.2, 100.0);
+        let survivors = population(&app, 0);
+        assert!(survivors > 0 && survivors < 1_000_000, "{survivors}");
+        let mut routes: Vec<usize> = app.refugees.iter().map(|f| f.to).collect();
+        routes.sort_unstable();
+        assert_eq!(routes, [1, 2, 3]);
+
+        for _ in 0..Flow::TICKS {
+            app.update_refugees();
+        }
+        assert!(app.refugees.is_empty());
+        let fled = survivors - population(&app, 0);
+        assert_eq!(fled, (survivors as f64 * Flow::SHARE) as u64 / 3 * 3);
+        let arrived: u64 = (1..4).map(|i| population(&app, i) - 1_000_000).sum();
+        assert_eq!(arrived, fled);
+        assert_eq!(population(&app, 4), 1_000_000);
+    }
+
+    #[test]
+    fn refugees_are_not_born_again_where_they_left() {
+        let mut app = App::new(80, 24);
+        for (lon, name, population) in [(0.0, "Struck", 10_000_000), (1.0, "Town", 100_000), (1.5, "City", 5_000_000)] {
+            app.map_renderer.add_city(lon, 0.0, name, population, false, false, "");
+        }
+        let total = |app: &App| app.map_renderer.city_grid.iter().map(|c| c.population).sum::<u64>();
+        let originally = total(&app);
+        app.apply_blast_damage(0.0, 0.2, 100.0);
+        let killed = app.casualties;
+        assert_eq!(total(&app), originally - killed);
+
+        for _ in 0..Flow::TICKS {
+            app.update_refugees();
+        }
+        assert_eq!(total(&app), originally - killed, "moving people neither adds nor loses any");
+        let town = app.map_renderer.city_grid.get(1).unwrap();
+        assert_eq!(town.population, 150_000, "the town takes in half its size, no more");
+
+        for _ in 0..100_000 {
+            app.frame += 1;
+            app.update_recovery();
+        }
+        // Births replace the dead and nobody else
+        assert_eq!(total(&app), originally);
+        assert_eq!(app.stats.births, killed);
+        let struck = app.map_renderer.city_grid.get(0).unwrap();
+        assert_eq!(struck.population, struck.original_population - struck.displaced);
+    }
+
+    #[test]
+    fn refugees_also_fly_out_along_flight_routes() {
+        let mut app = App::new(80, 24);
+        for (lon, name) in [(0.0, "Struck"), (3.0, "Near"), (100.0, "Far hub")] {
+            app.map_renderer.add_city(lon, 0.0, name, 5_000_000, false, false, "");
+        }
+        app.rebuild_travel();
+        app.apply_blast_damage(0.0, 0.2, 100.0);
+        let mut routes: Vec<usize> = app.refugees.iter().map(|f| f.to).collect();
+        routes.sort_unstable();
+        assert_eq!(routes, [1, 2]);
+    }
+
+    #[test]
+    fn a_broken_emergency_response_lets_more_die_in_the_fires() {
+        let mut app = App::new(80, 24);
+        app.map_renderer.add_city(2.35, 48.85, "Paris", 2_000_000, true, false, "FRA");
+        app.map_renderer.add_city(13.4, 52.5, "Berlin", 2_000_000, true, false, "DEU");
+        for _ in 0..2 {
+            app.apply_blast_damage(2.6, 48.85, 10.0);
+        }
+        let logged: Vec<_> = app.events.visible(10).filter(|e| e.kind == EventKind::Response).map(|e| e.text.clone()).collect();
+        assert_eq!(logged, ["FRA emergency response strained: saving 30% of the injured"]);
+        app.response.strike("FRA");
+        app.response.strike("FRA");
+
+        let before: Vec<u64> = app.map_renderer.city_grid.iter().map(|c| c.population).collect();
+        for (lon, lat) in [(2.35, 48.85), (13.4, 52.5)] {
+            app.fires.ignite(lon, lat, 250, WeaponType::Nuke);
+        }
+        app.apply_fire_damage_to_cities();
+        let lost: Vec<u64> = app.map_renderer.city_grid.iter().zip(&before).map(|(c, b)| b - c.population).collect();
+        // Berlin's response saves 60%, Paris's nobody
+        let ratio = lost[1] as f64 / before[1] as f64 / (lost[0] as f64 / before[0] as f64);
+        assert!((ratio - 0.4).abs() < 0.01, "{lost:?}");
+    }
+
+    #[test]
+    fn idle_globe_turns_east_until_touched() {
+        let mut app = App::new(120, 40);
+        app.auto_rotate = true;
+        app.paused = true;
+        let lon = app.projection.center_lon();
+        for _ in 1..AUTO_ROTATE_IDLE_FRAMES {
+            app.update(0.0);
+        }
+        assert_eq!(app.projection.center_lon(), lon);
+
+        for _ in 0..600 {
+            app.update(0.0);
+        }
+        // The surface moves east under the camera, so the view drifts west
+        let turned = lon - app.projection.center_lon();
+        assert!(turned > 1.0, "{turned}");
+        assert!((app.spin_velocity + AUTO_ROTATE_SPEED).abs() < AUTO_ROTATE_SPEED * 0.01);
+
+        // Input lets the spin wind down, until the globe is idle again
+        app.note_input();
+        for _ in 1..AUTO_ROTATE_IDLE_FRAMES {
+            app.update(0.0);
+        }
+        assert!(app.spin_velocity.abs() < AUTO_ROTATE_SPEED * 0.5);
+    }
+
+    #[test]
+    fn tsunami_floods_only_coastal_cities() {
+        let mut app = App::new(80, 24);
+        // Land north of 26°N, open sea to the south
+        app.map_renderer.add_land_polygon(
+            vec![vec![(-100.0, 26.0), (-80.0, 26.0), (-80.0, 40.0), (-100.0, 40.0), (-100.0, 26.0)]],
+            Lod::Low,
+        );
+        app.map_renderer.add_city(-90.0, 26.2, "Shoreline", 1_000_000, false, false, "USA");
+        app.map_renderer.add_city(-92.0, 31.0, "Inland", 1_000_000, false, false, "USA");
+        app.map_renderer.build_land_grid();
+        app.map_renderer.build_spatial_indexes();
+
+        // Far enough offshore that the blast itself misses the coast
+        app.detonate(-90.0, 24.0, WeaponType::Tsunami, 100.0, None);
+        assert_eq!(app.tsunamis.len(), 1);
+        while !app.tsunamis.is_empty() {
+            app.update_tsunamis();
+        }
+        let population = |name: &str| {
+            app.map_renderer.city_grid.iter().find(|c| c.name == name).unwrap().population
+        };
+        assert!(population("Shoreline") < 1_000_000);
+        assert_eq!(population("Inland"), 1_000_000);
+        assert_eq!(app.casualties, 1_000_000 - population("Shoreline"));
+
+        // On land the device is only a blast
+        app.detonate(-85.0, 35.0, WeaponType::Tsunami, 100.0, None);
+        assert!(app.tsunamis.is_empty());
+    }
+
+    #[test]
+    fn rural_strike_kills_raster_population() {
+        let mut app = App::new(80, 24);
+        let raster = "ncols 1\nnrows 1\nxllcorner 0\nyllcorner 0\ncellsize 1\n160000";
+        app.population = Some(PopulationGrid::parse(raster).unwrap());
+
+        // No cities anywhere, but the countryside is not empty
+        app.detonate(0.5, 0.5, WeaponType::Airburst, 60.0, None);
+        assert!(app.casualties > 40_000, "{}", app.casualties);
+        assert_eq!(app.population.as_ref().unwrap().casualties(), app.casualties);
+
+        // A strike over empty ground kills nobody
+        let before = app.casualties;
+        app.detonate(40.0, 40.0, WeaponType::Airburst, 60.0, None);
+        assert_eq!(app.casualties, before);
+    }
+
+    #[test]
+    fn burn_scars_outlast_the_fire() {
+        let mut app = App::new(80, 24);
+        assert!(app.burn_scars.is_empty());
+        app.fires.ignite(20.0, 10.0, 200, WeaponType::Nuke);
+        app.burn_scars.record(&app.fires);
+        app.fires = FireField::new();
+        app.fires.ignite(20.0, 10.0, 90, WeaponType::Nuke);
+        app.burn_scars.record(&app.fires);
+        app.fires = FireField::new();
+
+        assert!(!app.burn_scars.is_empty());
+        assert_eq!(app.burn_scars.burn_at(20.0, 10.0), 200);
+        assert_eq!(app.burn_scars.burn_at(21.0, 10.0), 0);
+    }
+
+    #[test]
+    fn eruptions_plume_ash_until_they_die_down() {
+        let mut app = App::new(80, 24);
+        app.erupt("Etna", 15.0, 37.75);
+        app.erupt("Etna", 15.0, 37.75);
+        assert_eq!(app.eruptions.len(), 1);
+        assert_eq!(app.events.visible(5).filter(|e| e.kind == EventKind::Disaster(Hazard::Volcano)).count(), 1);
+
+        app.eruptions[0].intensity = 2;
+        app.update_disasters();
+        assert_eq!(app.eruptions.len(), 1);
+        app.update_disasters();
+        assert!(app.eruptions.is_empty());
+    }
+
+    #[test]
+    fn disasters_roll_only_when_enabled() {
+        let mut app = App::new(80, 24);
+        for frame in (0..60 * 600).step_by(disasters::ROLL_FRAMES as usize) {
+            app.frame = frame;
+            app.update_disasters();
+        }
+        assert!(app.events.is_empty());
+
+        app.disasters = true;
+        for frame in (0..60 * 600).step_by(disasters::ROLL_FRAMES as usize) {
+            app.frame = frame;
+            app.update_disasters();
+        }
+        assert!(!app.events.is_empty());
+    }
+
+    #[test]
+    fn asteroids_leave_craters_and_dust() {
+        let mut app = App::new(80, 24);
+        app.detonate(20.0, 10.0, WeaponType::Asteroid, 300.0, None);
+        assert!(app.dust > 0.0);
+        assert_eq!(app.craters.len(), 1);
+        let crater = app.craters[0];
+        assert_eq!(crater.reach(20.0, 10.0), Some(0.0));
+        assert!(crater.reach(20.0, 10.0 + crater.radius_km / 111.0).is_some());
+        assert_eq!(crater.reach(30.0, 10.0), None);
+
+        for i in 0..MAX_CRATERS {
+            app.detonate(-170.0 + i as f64 * 5.0, -40.0, WeaponType::Asteroid, 10.0, None);
+        }
+        assert_eq!(app.craters.len(), MAX_CRATERS);
+        assert_eq!(app.craters[0].lon, -170.0, "the oldest crater gives way");
+    }
+
+    #[test]
+    fn fire_field_levels_track_cells_and_spread_is_uncapped() {
+        let mut fires = FireField::new();
+        fires.ignite(20.01, 10.01, 120, WeaponType::Nuke);
+        fires.ignite(20.02, 10.02, 80, WeaponType::Nuke);
+        fires.ignite(20.3, 10.6, 250, WeaponType::Bio);
+        assert_eq!(fires.len(), 2);
+        assert_eq!(fires.fine.burning, 2);
+        assert_eq!(fires.coarse.burning, 1);
+        assert_eq!(fires.coarse.fires_in_region(19.0, 9.0, 22.0, 12.0)[0].2, 250);
+
+        // Burning out drops the cell from both levels
+        for frame in 1..=5 * 120 {
+            let downpour = |_, _| FireConditions { rain: 255, wind: (0.0, 0.0), cover: Cover::Forest };
+            fires.step(frame, 1, downpour, |_, _| None);
+        }
+        assert_eq!((fires.len(), fires.fine.burning), (0, 0));
+        assert_eq!(fires.coarse.burning, 0);
+
+        // On dry land hot fires keep spreading past 30,000 cells
+        let mut fires = FireField::new();
+        for i in 0..40 {
+            fires.ignite(i as f64 * 5.0 - 100.0, 40.0, 255, WeaponType::Nuke);
+        }
+        let mut frame = 1;
+        while fires.len() < 31_000 && frame < 20_000 {
+            let dry = |_, _| FireConditions { rain: 0, wind: (0.0, 0.0), cover: Cover::Forest };
+            fires.step(frame, 7, dry, |_, _| Some(Cover::Forest));
+            frame += 1;
+        }
+        assert!(fires.len() >= 31_000, "{} fires", fires.len());
+        assert_eq!(fires.cells().count(), fires.len());
+    }
+
+    #[test]
+    fn forest_fires_race_downwind_while_desert_fires_die_out() {
+        let burn = |cover: Cover, wind: (f64, f64), frames: u64| {
+            let mut fires = FireField::new();
+            fires.ignite(0.0, 0.0, 255, WeaponType::Nuke);
+            for frame in 1..=frames {
+                fires.step(frame, 3, |_, _| FireConditions { rain: 0, wind, cover }, |_, _| Some(cover));
+            }
+            fires
+        };
+
+        // Even the hottest fire is out in desert by the time it would
+        // have burned out alone
+        let desert = burn(Cover::Desert, (0.0, 0.0), 450);
+        assert!(desert.is_empty(), "{} desert fires", desert.len());
+
+        let forest = burn(Cover::Forest, (0.0, 0.0), 300);
+        let grass = burn(Cover::Grass, (0.0, 0.0), 300);
+        assert!(forest.len() > 2 * grass.len().max(1), "forest {} vs grass {}", forest.len(), grass.len());
+
+        // A westerly pushes the front east of the ignition point
+        let windy = burn(Cover::Forest, (10.0, 0.0), 300);
+        let mean_lon = windy.cells().map(|f| f.lon).sum::<f64>() / windy.len() as f64;
+        assert!(mean_lon > 0.5, "front centered at {mean_lon}");
+    }
+
+    #[test]
+    fn panning_mercator_toward_a_pole_suggests_the_polar_map() {
+        let mut app = App::new(122, 42);
+        app.projection.switch_to("Mercator");
+        app.projection.set_view(0.0, 70.0, 4.0);
+        while app.projection.center_lat() < POLAR_HINT_LAT {
+            app.pan(0, -6);
+        }
+        assert!(app.status_message.as_deref().is_some_and(|m| m.contains("polar")));
+
+        app.run_command("projection polar");
+        assert_eq!(app.projection.name(), "Polar");
+        // Up and over the pole: the view keeps going instead of stopping
+        while app.projection.center_lon().abs() < 90.0 {
+            app.pan(0, -2);
+        }
+        let (lon, lat) = app.projection.view().center();
+        assert!(lat > POLAR_HINT_LAT && wrap_lon(lon - 180.0).abs() < 1.0, "({lon}, {lat})");
+    }
+
+    #[test]
+    fn description_follows_the_view() {
+        let mut app = App::new(122, 42);
+        app.projection.switch_to("Mercator");
+        app.projection.set_view(139.7, 35.7, 2.0);
+        app.map_renderer.add_city(139.7, 35.7, "Tokyo", 37_000_000, true, true, "JPN");
+        app.map_renderer.build_spatial_indexes();
+        let tokyo = app.view_description().to_string();
+        assert!(tokyo.starts_with("Viewing East Asia at 2.0x"), "{tokyo}");
+        assert!(tokyo.contains("Tokyo 37.0M"), "{tokyo}");
+        app.paused = true;
+        app.update(0.0);
+        assert_eq!(app.view_description(), tokyo);
+
+        // A strike changes what's there to describe
+        app.map_renderer.city_grid.get_mut(0).unwrap().set_population(1_000_000);
+        assert!(app.view_description().contains("Tokyo 1.0M"), "{}", app.view_description());
+        app.projection.set_view(-30.0, 0.0, 2.0);
+        assert!(app.view_description().starts_with("Viewing the Atlantic Ocean"));
+    }
+
+    #[test]
+    fn flat_maps_glide_after_a_flick() {
+        let mut app = App::new(122, 42);
+        app.projection.switch_to("Mercator");
+        app.paused = true;
+        app.start_drag(60, 20);
+        for col in (40..60).rev().step_by(4) {
+            app.handle_drag(col, 20);
+        }
+        app.end_drag();
+        let released = app.projection.center_lon();
+        app.update(0.0);
+        let first = app.projection.center_lon() - released;
+        assert!(first > 0.0, "{first}");
+        for _ in 0..200 {
+            app.update(0.0);
+        }
+        let glided = app.projection.center_lon() - released;
+        assert!(glided > first * 3.0, "{glided}");
+        let stopped = app.projection.center_lon();
+        app.update(0.0);
+        assert_eq!(app.projection.center_lon(), stopped);
+
+        // Stiffer friction stops it sooner
+        let mut stiff = App::new(122, 42);
+        stiff.projection.switch_to("Mercator");
+        stiff.paused = true;
+        stiff.friction = 4.0;
+        stiff.start_drag(60, 20);
+        for col in (40..60).rev().step_by(4) {
+            stiff.handle_drag(col, 20);
+        }
+        stiff.end_drag();
+        let released = stiff.projection.center_lon();
+        for _ in 0..200 {
+            stiff.update(0.0);
+        }
+        assert!(stiff.projection.center_lon() - released < glided);
+    }
+
+    #[test]
+    fn wheel_zoom_eases_toward_the_cursor() {
+        for projection in ["Mercator", "Globe"] {
+            let mut app = App::new(122, 42);
+            app.projection.switch_to(projection);
+            app.paused = true;
+            let zoom = app.projection.effective_zoom();
+            let (col, row) = (80, 15);
+            let under = app.projection.unproject((col - 1) * 2, (row - 1) * 4).unwrap();
+            app.scroll_zoom(col as u16, row as u16, 1, false);
+            app.scroll_zoom(col as u16, row as u16, 1, true);
+            app.update(0.0);
+            let first = app.projection.effective_zoom() / zoom;
+            assert!(first > 1.0 && first < 1.3, "{projection}: {first}");
+            while app.zoom_ease.is_some() {
+                app.update(0.0);
+            }
+            let ratio = app.projection.effective_zoom() / zoom;
+            assert!((ratio - 1.1_f64.powi(5)).abs() < 0.01, "{projection}: {ratio}");
+            let (px, py) = app.projection.project_point(under.0, under.1).unwrap();
+            assert!((px - (col - 1) * 2).abs() <= 2 && (py - (row - 1) * 4).abs() <= 2, "{projection}: ({px}, {py})");
+        }
+    }
+
+    #[test]
+    fn box_zoom_fits_the_rectangle_on_flat_maps_and_the_globe() {
+        for projection in ["Mercator", "Globe"] {
+            let mut app = App::new(122, 42);
+            app.projection.switch_to(projection);
+            let zoom = app.projection.effective_zoom();
+            // A quarter of the map's width, right of center
+            app.start_box_zoom(61, 16);
+            app.drag_box_zoom(91, 26);
+            assert!(app.box_zoom.is_some());
+            app.finish_box_zoom();
+            assert!(app.box_zoom.is_none());
+            let fly = app.fly_to.as_ref().expect("flying");
+            let (lon, lat, to_zoom) = fly.to;
+            assert!(lon > 0.0 && lat.abs() < 45.0, "{projection}: ({lon}, {lat})");
+            assert!(to_zoom > 2.5 * zoom, "{projection}: {zoom} to {to_zoom}");
+        }
+
+        // A click with shift held isn't a rectangle
+        let mut app = App::new(122, 42);
+        app.start_box_zoom(61, 16);
+        app.finish_box_zoom();
+        assert!(app.fly_to.is_none());
+    }
+
+    #[test]
+    fn crosshair_moves_by_cells_pans_at_the_edge_and_snaps_to_cities() {
+        let mut app = App::new(82, 43);
+        app.mouse_pos = None;
+        app.toggle_crosshair();
+        assert!(app.crosshair);
+        assert_eq!(app.mouse_pos, Some((41, 21)));
+        app.move_crosshair(3, -1);
+        assert_eq!(app.mouse_pos, Some((44, 20)));
+
+        // Past the right edge the map moves instead
+        let lon = app.projection.center_lon();
+        app.move_crosshair(100, 0);
+        assert_eq!(app.mouse_pos, Some((80, 20)));
+        assert!(app.projection.center_lon() > lon);
+
+        app.projection.switch_to("Mercator");
+        app.projection.set_view(0.0, 0.0, 1.0);
+        app.map_renderer.add_city(139.7, 35.7, "Tokyo", 37_000_000, true, true, "JPN");
+        app.map_renderer.add_city(77.2, 28.6, "Delhi", 32_000_000, true, true, "IND");
+        app.map_renderer.add_city(-58.4, -34.6, "Buenos Aires", 15_000_000, true, true, "ARG");
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            app.crosshair_to_next_city(false);
+            seen.push(app.status_message.clone().unwrap());
+        }
+        assert!(seen[0].contains("Tokyo") && seen[1].contains("Delhi") && seen[2].contains("Buenos Aires"), "{seen:?}");
+        assert_eq!(seen[3], seen[0], "wraps around");
+        app.crosshair_to_next_city(true);
+        assert!(app.status_message.as_deref().unwrap().contains("Buenos Aires"));
+
+        app.toggle_crosshair();
+        assert!(!app.crosshair);
+    }
+
+    #[test]
+    fn goto_command_flies_to_coordinates_and_reports_errors() {
+        let mut app = App::new(80, 24);
+        app.begin_command();
+        app.command.text = Some("goto 35.68,139.76".to_string());
+        app.submit_command();
+        let fly = app.fly_to.as_ref().expect("flying");
+        assert_eq!((fly.to.0, fly.to.1), (139.76, 35.68));
+        assert!(!app.command.is_open());
+
+        app.fly_to = None;
+        app.command.text = Some("goto 91,0".to_string());
+        app.submit_command();
+        assert!(app.fly_to.is_none());
+        assert!(app.status_message.as_deref().unwrap().contains("latitude out of range"));
+    }
+
+    #[test]
+    fn commands_set_layers_weapons_and_the_seed() {
+        let mut app = App::new(80, 24);
+        app.map_renderer.settings.show_counties = true;
+        app.run_command("layer counties off");
+        assert!(!app.map_renderer.settings.show_counties);
+        app.run_command("layer counties off");
+        assert!(!app.map_renderer.settings.show_counties);
+        app.run_command("layer counties");
+        assert!(app.map_renderer.settings.show_counties);
+        // `hud` is the detailed HUD on H; the performance HUD is `perf`
+        let (hud, perf) = (app.show_hud, app.perf.show_hud);
+        app.run_command("layer hud");
+        assert_eq!((app.show_hud, app.perf.show_hud), (!hud, perf));
+        app.run_command("layer perf");
+        assert_eq!((app.show_hud, app.perf.show_hud), (!hud, !perf));
+
+        app.run_command("weapon emp");
+        assert_eq!(app.active_weapon, WeaponType::Emp);
+        app.run_command("seed 42");
+        assert_eq!(app.seed, Some(42));
+        app.run_command("theme norad");
+        assert_eq!(app.theme.name, "norad");
+        app.run_command("projection globe");
+        assert!(app.is_globe());
+        app.run_command("reticle area");
+        assert_eq!(app.reticle, Reticle::Area);
+
+        app.run_command("weapon laser");
+        assert!(app.status_message.as_deref().unwrap().contains("unknown weapon"));
+        assert_eq!(app.active_weapon, WeaponType::Emp);
+    }
+
+    #[test]
+    fn strike_packages_launch_queued_targets_one_reload_apart() {
+        let mut app = App::new(80, 24);
+        app.active_weapon = WeaponType::Airburst;
+        for col in [20, 40, 60] {
+            app.queue_target(col, 12);
+        }
+        app.queue_target(40, 12);
+        assert_eq!(app.strike_queue.len(), 2, "marking a target again unmarks it");
+        app.queue_target(50, 8);
+
+        app.launch_queue();
+        app.step();
+        assert_eq!(app.stats.strikes_launched, 1);
+        for _ in 0..PACKAGE_STAGGER_FRAMES {
+            app.step();
+        }
+        assert_eq!(app.stats.strikes_launched, 2);
+        for _ in 0..2 * PACKAGE_STAGGER_FRAMES {
+            app.step();
+        }
+        assert_eq!(app.stats.strikes_launched, 3);
+        assert!(app.strike_queue.is_empty() && !app.is_launching_package());
+
+        // The queue won't take more than the stockpile holds
+        app.stockpiles[WeaponType::Airburst.index()] = Stockpile::new(Some(1));
+        app.queue_target(20, 12);
+        app.queue_target(60, 12);
+        assert_eq!(app.strike_queue.len(), 1);
+    }
+
+    #[test]
+    fn empty_stockpiles_hold_fire_until_restocked() {
+        let mut app = App::new(80, 24);
+        app.stockpiles[WeaponType::Airburst.index()] = Stockpile::new(Some(2));
+        let radius_km = app.blast_radius_km(WeaponType::Airburst);
+        assert!(app.launch_at(20.0, 10.0, WeaponType::Airburst, radius_km, None));
+        assert!(app.launch_at(20.0, 10.0, WeaponType::Airburst, radius_km, None));
+        assert!(!app.launch_at(20.0, 10.0, WeaponType::Airburst, radius_km, None));
+        assert_eq!(app.stats.strikes_launched, 2);
+        // Unlimited weapons are untouched
+        assert!(app.launch_at(20.0, 10.0, WeaponType::Nuke, radius_km, None));
+
+        for _ in 0..WeaponType::Airburst.restock_frames() {
+            app.step();
+        }
+        assert_eq!(app.stockpiles[WeaponType::Airburst.index()].remaining, 1);
+        assert!(app.launch_at(20.0, 10.0, WeaponType::Airburst, radius_km, None));
+    }
+
+    #[test]
+    fn mirv_bus_splits_into_smaller_warheads_around_the_aim_point() {
+        let mut app = App::new(80, 24);
+        app.mirv_armed = true;
+        app.mirv = Mirv { warheads: 4, spread_km: 100.0 };
+        let radius_km = app.blast_radius_km(WeaponType::Airburst);
+        assert!(app.launch_at(20.0, 10.0, WeaponType::Airburst, radius_km, None));
+        assert_eq!(app.projectiles.len(), 1);
+
+        for _ in 0..Projectile::SPLIT_FRAME {
+            app.step();
+        }
+        assert_eq!(app.projectiles.len(), 4);
+        assert!(app.projectiles.iter().all(|p| p.released_at.is_some() && p.radius_km < radius_km));
+
+        for _ in 0..Projectile::FLIGHT_FRAMES - Projectile::SPLIT_FRAME {
+            app.step();
+        }
+        assert!(app.projectiles.is_empty());
+        assert_eq!(app.explosions.len(), 4);
+        assert!(app.explosions.iter().all(|e| great_circle_km(20.0, 10.0, e.lon, e.lat) <= 100.0 + 1e-6));
+    }
+
+    #[test]
+    fn corridor_reticle_launches_a_pattern_on_the_second_click() {
+        let mut app = App::new(80, 24);
+        app.frame = 1_000;
+        app.reticle = Reticle::Corridor;
+        app.click_target(20, 10);
+        app.release_target(20, 10);
+        assert!(app.strike_anchor.is_some());
+        assert_eq!(app.stats.strikes_launched, 0);
+
+        app.click_target(60, 12);
+        assert!(app.strike_anchor.is_none());
+        assert!(app.stats.strikes_launched > 1, "{}", app.stats.strikes_launched);
+        assert_eq!(app.last_nuke_frame, 1_000);
+    }
+
+    #[test]
+    fn hazards_read_back_from_fires_plumes_and_gas() {
+        let mut app = App::new(80, 24);
+        assert!(app.hazards_at(20.0, 10.0).is_empty());
+
+        app.fires.ignite(20.0, 10.0, 204, WeaponType::Nuke);
+        app.detonate(20.0, 10.0, WeaponType::Nuke, app.blast_radius_km(WeaponType::Nuke), None);
+        app.detonate(-40.0, 50.0, WeaponType::Chem, 80.0, None);
+        let here = app.hazards_at(20.0, 10.0);
+        assert_eq!(here.fire.map(|(_, w)| w), Some(WeaponType::Nuke));
+        assert!(here.fire.unwrap().0 >= 204);
+        assert!(here.fallout_gy_h > 1.0, "{} Gy/h", here.fallout_gy_h);
+        assert!(here.gas.is_empty());
+
+        // A fresh cloud is at full strength over its center
+        let gas = app.hazards_at(-40.0, 50.0).gas;
+        assert_eq!(gas.len(), 1);
+        assert_eq!(gas[0].0, WeaponType::Chem);
+        assert!((gas[0].1 - 1.0).abs() < 0.01, "{}", gas[0].1);
+        assert!(app.hazards_at(100.0, -30.0).is_empty());
+    }
+
+    #[test]
+    fn strikes_are_logged_with_their_toll() {
+        let mut app = App::new(80, 24);
+        app.map_renderer.add_city(77.2, 28.6, "Delhi", 10_000_000, true, true, "IND");
+        app.map_renderer.build_spatial_indexes();
+        app.warhead_yield = Yield::Mt1;
+
+        app.detonate(77.3, 28.6, WeaponType::Nuke, app.blast_radius_km(WeaponType::Nuke), app.yield_kt(WeaponType::Nuke));
+        app.detonate(-30.0, -40.0, WeaponType::Bio, 80.0, None);
+        // A scripted strike gives only a radius; a MIRV warhead carries a share
+        app.detonate(-30.0, -41.0, WeaponType::Nuke, 80.0, None);
+        app.detonate(-30.0, -42.0, WeaponType::Nuke, 60.0, Some(1_000.0 / 3.0));
+
+        let events: Vec<_> = app.events.visible(10).collect();
+        assert_eq!(events[0].kind, EventKind::Strike(WeaponType::Nuke));
+        assert_eq!(events[0].text, "NUKE 1 MT → Delhi");
+        assert_eq!(events[0].casualties, Some(app.casualties));
+        assert_eq!(events[1].text, "BIO 20 kg agent → 40.0°S 30.0°W");
+        assert_eq!(events[1].casualties, Some(0));
+        assert_eq!(events[2].text, "NUKE 80 km → 41.0°S 30.0°W");
+        assert_eq!(events[3].text, "NUKE 333 kt → 42.0°S 30.0°W");
+        assert_eq!(events[3].yield_kt, Some(1_000.0 / 3.0));
+        assert_eq!(kilotons_label(1_500.0), "1.5 MT");
+        assert_eq!(app.damaged_cities()[0].name, "Delhi");
+        assert_eq!(app.alerts, ["Delhi destroyed"]);
+    }
+
+    #[test]
+    fn cities_lost_to_fallout_raise_alerts_and_alerts_are_capped() {
+        let mut app = App::new(80, 24);
+        app.map_renderer.add_city(10.0, 50.0, "Berlin", 1_000_000, false, false, "DEU");
+        app.map_renderer.build_spatial_indexes();
+        app.map_renderer.city_grid.get_mut(0).unwrap().set_population(10);
+        app.apply_ongoing_damage(10.0, 50.0, 30.0, 0.001);
+        assert_eq!(app.alerts, ["Berlin lost to fallout"]);
+
+        app.alerts = (0..100).map(|i| i.to_string()).collect();
+        app.step();
+        assert_eq!(app.alerts.len(), MAX_ALERTS);
+        assert_eq!(app.alerts.last().unwrap(), "99");
+    }
+
+    #[test]
+    fn heavy_soot_shrinks_cities_worldwide() {
+        let mut app = App::new(80, 24);
+        app.map_renderer.add_city(10.0, 50.0, "Berlin", 1_000_000, false, false, "DEU");
+        app.map_renderer.add_city(-120.0, -30.0, "Faraway", 1_000_000, false, false, "CHL");
+        app.map_renderer.build_spatial_indexes();
+
+        app.climate.inject(Climate::FAMINE_TG / 2.0);
+        for _ in 0..Climate::DECLINE_INTERVAL {
+            app.update_climate();
+        }
+        assert_eq!(app.casualties, 0);
+
+        app.climate.inject(100.0);
+        for _ in 0..Climate::DECLINE_INTERVAL {
+            app.frame += 1;
+            app.update_climate();
+        }
+        assert!(app.map_renderer.city_grid.iter().all(|c| c.population < 1_000_000));
+        assert_eq!(app.casualties, 2_000_000 - app.map_renderer.city_grid.iter().map(|c| c.population).sum::<u64>());
+    }
+
+    #[test]
+    fn emp_blacks_out_cities_and_defenses_for_a_while() {
+        let mut app = App::new(80, 24);
+        app.map_renderer.add_city(10.0, 50.0, "Inside", 500_000, false, false, "DEU");
+        app.map_renderer.add_city(40.0, 50.0, "Outside", 500_000, false, false, "RUS");
+        app.map_renderer.build_spatial_indexes();
+        app.defenses.push(DefenseSite { lon: 10.5, lat: 50.0, cooldown: 0, blackout: 0 });
+
+        app.detonate(10.0, 50.0, WeaponType::Emp, 200.0, None);
+        let blackout = |app: &App, name: &str| {
+            app.map_renderer.city_grid.iter().find(|c| c.name == name).unwrap().blackout_frames
+        };
+        assert_eq!(blackout(&app, "Inside"), EMP_BLACKOUT_FRAMES);
+        assert_eq!(blackout(&app, "Outside"), 0);
+        assert!(!app.defenses[0].is_ready());
+        // A second pulse refreshes the timer without double-counting the city
+        app.detonate(10.0, 50.0, WeaponType::Emp, 200.0, None);
+        assert_eq!(app.blacked_out.len(), 1);
+
+        for _ in 0..EMP_BLACKOUT_FRAMES {
+            app.update_blackouts();
+        }
+        assert_eq!(blackout(&app, "Inside"), 0);
+        assert!(app.blacked_out.is_empty());
+        assert!(app.defenses[0].is_ready());
+    }
+
+    #[test]
+    fn bio_strike_infects_and_spreads() {
+        let mut app = App::new(80, 24);
+        app.set_seed(3);
+        app.map_renderer.add_city(10.0, 50.0, "Ground Zero", 2_000_000, false, false, "DEU");
+        app.map_renderer.add_city(11.5, 50.5, "Neighbor", 2_000_000, false, false, "DEU");
+        app.map_renderer.build_spatial_indexes();
+
+        // Off-center, so the blast spares the city but the pathogen reaches it
+        app.detonate(10.0, 49.5, WeaponType::Bio, 30.0, None);
+        assert_eq!(app.infected.len(), 1);
+        assert!(app.outbreak.is_some());
+
+        let casualties = app.casualties;
+        for _ in 0..6000 {
+            app.frame += 1;
+            app.update_contagion();
+        }
+        assert_eq!(app.infected.len(), 2);
+        assert!(app.casualties > casualties);
+    }
+
+    #[test]
+    fn scrubbing_shows_the_past_read_only_and_returns_to_now() {
+        let mut app = App::new(80, 24);
+        app.map_renderer.add_city(10.0, 50.0, "Target", 2_000_000, false, false, "DEU");
+        app.map_renderer.build_spatial_indexes();
+        for _ in 0..history::SNAPSHOT_FRAMES {
+            app.step();
+        }
+        app.detonate(10.0, 50.0, WeaponType::Nuke, 30.0, None);
+        for _ in 0..history::SNAPSHOT_FRAMES {
+            app.step();
+        }
+        assert_eq!(app.history.len(), 2);
+        let live = (app.map_renderer.city_grid.get(0).unwrap().population, app.casualties, app.fires.len());
+        assert!(live.1 > 0 && live.2 > 0);
+
+        app.scrub(-2);
+        assert!(app.history.is_scrubbing());
+        assert_eq!(app.map_renderer.city_grid.get(0).unwrap().population, 2_000_000);
+        assert_eq!((app.casualties, app.fires.len()), (0, 0));
+        assert!(!app.launch_at(10.0, 50.0, WeaponType::Nuke, 30.0, None));
+        assert_eq!(app.update(1.0), 0);
+
+        app.scrub(1);
+        assert!(!app.fires.is_empty());
+        app.scrub(1);
+        assert!(!app.history.is_scrubbing());
+        assert_eq!((app.map_renderer.city_grid.get(0).unwrap().population, app.casualties, app.fires.len()), live);
+    }
+
+    #[test]
+    fn a_watched_world_carries_on_behind_the_history_shown() {
+        use crate::spectate::{Spectator, SpectatorHost};
+        let mut host = SpectatorHost::start(std::net::IpAddr::from([127, 0, 0, 1]), 0).unwrap();
+        let mut world = App::new(80, 24);
+        let mut app = App::new(80, 24);
+        for app in [&mut world, &mut app] {
+            app.map_renderer.add_city(2.35, 48.85, "Paris", 2_000_000, true, false, "FRA");
+        }
+        app.history.record(&app.history_snapshot());
+        app.spectating = Some(Spectator::connect(&format!("127.0.0.1:{}", host.port())).unwrap());
+        app.scrub(-1);
+
+        world.map_renderer.city_grid.get_mut(0).unwrap().set_population(1_000_000);
+        world.casualties = 1_000_000;
+        let deadline = Instant::now() + std::time::Duration::from_secs(5);
+        while app.live.as_ref().is_none_or(|live| live.casualties == 0) {
+            assert!(Instant::now() < deadline, "no diff within 5s");
+            host.broadcast(&world);
+            app.update(0.0);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert!(app.history.is_scrubbing());
+        assert_eq!((app.map_renderer.city_grid.get(0).unwrap().population, app.casualties), (2_000_000, 0));
+
+        app.return_to_now();
+        assert_eq!((app.map_renderer.city_grid.get(0).unwrap().population, app.casualties), (1_000_000, 1_000_000));
+    }
+
+    #[test]
+    fn a_saved_world_loads_back_with_its_burn_scars() {
+        let mut app = App::new(80, 24);
+        app.map_renderer.add_city(10.0, 50.0, "Target", 2_000_000, false, false, "DEU");
+        app.map_renderer.build_spatial_indexes();
+        app.detonate(10.0, 50.0, WeaponType::Nuke, 30.0, None);
+        for _ in 0..10 {
+            app.step();
+        }
+        app.burn_scars.record(&app.fires);
+        let saved = (app.map_renderer.city_grid.get(0).unwrap().population, app.casualties, app.fires.len());
+        let path = std::env::temp_dir().join(format!("tui-map-save-{}", std::process::id()));
+        app.save_game(&path).unwrap();
+
+        let mut fresh = App::new(80, 24);
+        assert!(fresh.load_game(&path).is_err(), "no cities loaded to match the save");
+        fresh.map_renderer.add_city(10.0, 50.0, "Target", 2_000_000, false, false, "DEU");
+        fresh.map_renderer.build_spatial_indexes();
+        fresh.load_game(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!((fresh.map_renderer.city_grid.get(0).unwrap().population, fresh.casualties, fresh.fires.len()), saved);
+        assert_eq!(fresh.burn_scars.burn_at(10.0, 50.0), app.burn_scars.burn_at(10.0, 50.0));
+        assert!(!fresh.burn_scars.is_empty());
+    }
+
+    #[test]
+    fn over_budget_the_detail_tier_not_in_view_is_dropped_until_needed() {
+        let dir = std::env::temp_dir().join(format!("tui-map-memory-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let features: Vec<String> = (0..20)
+            .map(|i| format!(r#"{{"type":"Feature","properties":{{}},"geometry":{{"type":"LineString","coordinates":[[{i},0],[{i}.5,1],[{i},2]]}}}}"#))
+            .collect();
+        std::fs::write(dir.join("ne_10m_coastline.json"), format!(r#"{{"type":"FeatureCollection","features":[{}]}}"#, features.join(","))).unwrap();
+
+        let mut app = App::new(80, 24);
+        app.data_manager = DataManager::new(dir.clone());
+        data::load_all_geojson(&mut app.map_renderer, &dir).unwrap();
+        app.map_renderer.build_spatial_indexes();
+        let high = app.map_renderer.coastlines_high.len();
+        assert!(app.memory_report().contains("coastlines"));
+
+        app.memory.limit = Some(1);
+        for _ in 0..60 {
+            app.update_memory();
+        }
+        // The derived medium tier has no file to come back from, so stays
+        assert_eq!(app.memory.evicted, vec![Lod::High]);
+        assert!(app.map_renderer.coastlines_high.is_empty());
+        assert!(!app.map_renderer.coastlines_medium.is_empty());
+        assert!(app.memory_report().contains("high detail dropped"));
+
+        // Read back on a worker, and listed as dropped until it's in
+        app.projection.set_view(10.0, 1.0, 20.0);
+        let deadline = Instant::now() + std::time::Duration::from_secs(5);
+        while !app.memory.evicted.is_empty() {
+            assert!(Instant::now() < deadline, "high detail not read back within 5s");
+            assert!(app.map_renderer.coastlines_high.is_empty());
+            app.update_memory();
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert_eq!(app.map_renderer.coastlines_high.len(), high);
+
+        // Zoomed out just short of needing it, it isn't dropped again
+        app.projection.set_view(10.0, 1.0, 7.0);
+        for _ in 0..60 {
+            app.update_memory();
+        }
+        assert!(app.memory.evicted.is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reloaded_cities_get_new_routes_and_a_new_timeline() {
+        let dir = std::env::temp_dir().join(format!("tui-map-reload-cities-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut app = App::new(80, 24);
+        app.data_manager = DataManager::new(dir.clone());
+        app.map_renderer.add_city(139.7, 35.7, "Tokyo", 30_000_000, false, false, "JPN");
+        app.map_renderer.add_city(-74.0, 40.7, "New York", 18_000_000, false, false, "USA");
+        app.rebuild_travel();
+        assert_eq!(app.travel.routes.len(), 1);
+        app.history.record(&app.history_snapshot());
+        app.scrub(-1);
+
+        let cities = dir.join("ne_10m_cities.json");
+        std::fs::write(&cities, r#"{"type":"FeatureCollection","features":[{"type":"Feature","properties":{"name":"Lagos","pop_max":15000000},"geometry":{"type":"Point","coordinates":[3.4,6.5]}}]}"#).unwrap();
+        app.reload_data_files(std::slice::from_ref(&cities));
+        assert_eq!(app.map_renderer.city_grid.len(), 1);
+        assert!(app.travel.routes.is_empty(), "no routes left to the cities that went");
+        assert!(app.history.is_empty() && !app.history.is_scrubbing(), "snapshots of the old cities are gone");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn pandemic_mode_spreads_along_routes_with_weapons_off() {
+        let mut app = App::new(80, 24);
+        app.set_seed(3);
+        app.map_renderer.add_city(139.7, 35.7, "Tokyo", 30_000_000, false, false, "JPN");
+        app.map_renderer.add_city(-74.0, 40.7, "New York", 18_000_000, false, false, "USA");
+        app.map_renderer.build_spatial_indexes();
+        app.set_pandemic(true);
+        assert_eq!(app.travel.routes.len(), 1);
+        assert!(!app.launch_at(139.7, 35.7, WeaponType::Nuke, 30.0, None));
+        // Nobody fights back in a pandemic
+        app.cycle_difficulty();
+        app.run_command("ai hard");
+        assert!(app.adversary.is_none());
+
+        let tokyo = app.map_renderer.city_grid.iter().position(|c| c.name == "Tokyo").unwrap();
+        app.infect_city(tokyo);
+        assert_eq!(app.infected, vec![tokyo]);
+        app.adjust_r0(true);
+        assert_eq!(app.outbreak.map(|o| o.r0), Some(app.r0));
+
+        // An ocean apart, so only travellers can carry it
+        for _ in 0..6000 {
+            app.frame += 1;
+            app.update_contagion();
+        }
+        assert_eq!(app.infected.len(), 2);
+    }
+
+    fn missile(from: (f64, f64), weapon_type: WeaponType, hostile: bool) -> Projectile {
+        Projectile {
+            from,
+            to: (from.0 + 20.0, from.1),
+            frame: 0,
+            radius_km: 50.0,
+            weapon_type,
+            hostile,
+            id: 0,
+            warheads: Vec::new(),
+            released_at: None,
+            yield_kt: None,
+        }
+    }
+
+    #[test]
+    fn abm_odds_fall_off_with_range() {
+        assert!(DefenseSite::hit_probability(0.0) > DefenseSite::hit_probability(600.0));
+        assert!(DefenseSite::hit_probability(600.0) > DefenseSite::hit_probability(1100.0));
+        assert_eq!(DefenseSite::hit_probability(DefenseSite::RANGE_KM), 0.0);
+
+        // Over many engagements, a missile overhead is downed far more often
+        // than one near the edge of range (~9° of longitude at the equator)
+        let hits = |offset: f64| {
+            let mut app = App::new(80, 24);
+            app.defenses.push(DefenseSite { lon: 0.0, lat: 0.0, cooldown: 0, blackout: 0 });
+            for _ in 0..200 {
+                app.frame += 1;
+                app.defenses[0].cooldown = 0;
+                app.projectiles = vec![missile((offset, 0.0), WeaponType::Nuke, true)];
+                app.update_defenses();
+            }
+            app.stats.intercepts
+        };
+        let (near, far) = (hits(0.0), hits(9.0));
+        assert!(near > 140 && far < 60 && far > 0, "near {near}, far {far}");
+        assert_eq!(hits(12.0), 0);
+    }
+
+    #[test]
+    fn abm_sites_fire_once_per_cooldown_and_only_at_hostile_missiles() {
+        let mut app = App::new(80, 24);
+        app.defenses.push(DefenseSite { lon: 10.0, lat: 0.0, cooldown: 0, blackout: 0 });
+
+        app.projectiles.push(missile((10.0, 0.0), WeaponType::Nuke, true));
+        app.update_defenses();
+        assert_eq!(app.interceptions.len(), 1);
+        assert_eq!(app.defenses[0].cooldown, DefenseSite::COOLDOWN_FRAMES);
+
+        // Still cooling down: a second missile overhead flies through
+        app.projectiles = vec![missile((10.0, 0.0), WeaponType::Nuke, true)];
+        app.update_defenses();
+        assert_eq!(app.interceptions.len(), 1);
+        assert_eq!(app.projectiles.len(), 1);
+
+        // Ready again, it ignores falling rocks and its own side's missiles
+        app.defenses[0].cooldown = 0;
+        app.projectiles = vec![
+            missile((10.0, 0.0), WeaponType::Asteroid, true),
+            missile((10.0, 0.0), WeaponType::Nuke, false),
+        ];
+        app.update_defenses();
+        assert_eq!(app.interceptions.len(), 1);
+        assert_eq!(app.projectiles.len(), 2);
+        assert!(app.defenses[0].is_ready());
+    }
+}
@@ -0,0 +1,103 @@
+//! `--bench-frame N`: time whole frames offscreen and report the averages.
+//!
+//! Every projection gets N frames drawn through the real UI into an
+//! in-memory terminal, with one simulation step per frame. The view pans a
+//! little every frame so the backdrop cache never serves a render for free.
+
+use crate::app::App;
+use crate::map::Projection;
+use crate::perf::Phase;
+use crate::ui;
+use ratatui::{backend::TestBackend, Terminal};
+use simd_json::{json, OwnedValue};
+use std::time::{Duration, Instant};
+
+/// Pixels the view moves between frames
+const PAN_PER_FRAME: i32 = 2;
+
+/// Timings for one phase over a run of frames, in ms
+struct Timings {
+    samples: Vec<f64>,
+}
+
+impl Timings {
+    fn new(frames: usize) -> Self {
+        Self { samples: Vec::with_capacity(frames) }
+    }
+
+    fn push(&mut self, elapsed: Duration) {
+        self.samples.push(elapsed.as_secs_f64() * 1000.0);
+    }
+
+    fn summary(mut self) -> OwnedValue {
+        self.samples.sort_by(f64::total_cmp);
+        let n = self.samples.len().max(1);
+        let mean = self.samples.iter().sum::<f64>() / n as f64;
+        let p95 = self.samples.get((n * 95).div_ceil(100).saturating_sub(1)).copied().unwrap_or(0.0);
+        let max = self.samples.last().copied().unwrap_or(0.0);
+        let round = |ms: f64| (ms * 1000.0).round() / 1000.0;
+        json!({ "mean_ms": round(mean), "p95_ms": round(p95), "max_ms": round(max) })
+    }
+}
+
+/// Draw `frames` frames in each projection and summarize how long they took
+pub fn run(app: &mut App, width: u16, height: u16, frames: usize) -> OwnedValue {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).expect("in-memory terminal");
+    let mut views = Vec::new();
+    for name in Projection::NAMES {
+        app.projection.switch_to(name);
+        let mut render = Timings::new(frames);
+        let mut sim = Timings::new(frames);
+        let mut features = 0;
+        for _ in 0..frames {
+            app.load_visible_chunks();
+            let start = Instant::now();
+            terminal.draw(|frame| ui::render(frame, app)).expect("in-memory terminal");
+            render.push(start.elapsed());
+            app.perf.record(Phase::Render, start.elapsed());
+            features = features.max(app.perf.features);
+
+            let start = Instant::now();
+            app.step();
+            sim.push(start.elapsed());
+            app.perf.record(Phase::Sim, start.elapsed());
+            app.pan(PAN_PER_FRAME, 0);
+        }
+        views.push(json!({
+            "projection": name,
+            "render": render.summary(),
+            "sim": sim.summary(),
+            "max_features": features as u64,
+        }));
+    }
+    json!({
+        "frames": frames as u64,
+        "width": width as u64,
+        "height": height as u64,
+        "views": views,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simd_json::prelude::*;
+
+    #[test]
+    fn reports_every_projection() {
+        let mut app = App::new(60, 20);
+        crate::data::generate_simple_world(&mut app.map_renderer);
+        app.map_renderer.build_spatial_indexes();
+        let report = run(&mut app, 60, 20, 5);
+        assert_eq!(report["frames"].as_u64(), Some(5));
+        let views = report["views"].as_array().unwrap();
+        assert_eq!(views.len(), Projection::NAMES.len());
+        assert_eq!(app.frame, 5 * views.len() as u64);
+        assert_eq!(views[1]["projection"].as_str(), Some("Globe"));
+        for view in views {
+            let render = &view["render"];
+            let (mean, p95, max) = (render["mean_ms"].as_f64().unwrap(), render["p95_ms"].as_f64().unwrap(), render["max_ms"].as_f64().unwrap());
+            assert!(mean > 0.0 && p95 <= max, "{render:?}");
+        }
+    }
+}
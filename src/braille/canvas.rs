@@ -31,6 +31,25 @@ impl BrailleCanvas {
         }
     }
 
+    /// Bytes the dots take up
+    pub fn heap_bytes(&self) -> usize {
+        self.pixels.capacity()
+    }
+
+    /// Erase every dot, keeping the buffer
+    pub fn clear(&mut self) {
+        self.pixels.fill(0);
+    }
+
+    /// Blank canvas at new character dimensions, reusing the buffer's
+    /// allocation where it's big enough
+    pub fn reset(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.pixels.clear();
+        self.pixels.resize(width * height, 0);
+    }
+
     /// Set a pixel at the given coordinates.
     /// Braille dot layout per character:
     /// ```text
@@ -66,6 +85,7 @@ impl BrailleCanvas {
 
     /// Convert the canvas to a string of Braille characters
     #[cfg(test)]
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         (0..self.height)
             .map(|row| self.row_to_string(row))
@@ -93,6 +113,12 @@ impl BrailleCanvas {
         &self.pixels[start..start + self.width]
     }
 
+    /// Number of character columns.
+    #[inline(always)]
+    pub fn char_width(&self) -> usize {
+        self.width
+    }
+
     /// Number of character rows.
     #[inline(always)]
     pub fn char_height(&self) -> usize {
@@ -123,6 +149,20 @@ mod tests {
         assert_eq!(canvas.to_string(), "⣿"); // U+28FF (all dots)
     }
 
+    #[test]
+    fn reset_reuses_the_buffer_blank() {
+        let mut canvas = BrailleCanvas::new(4, 4);
+        canvas.set_pixel(0, 0);
+        let buffer = canvas.pixels.as_ptr();
+        canvas.reset(3, 2);
+        assert_eq!(canvas.to_string(), "⠀⠀⠀\n⠀⠀⠀");
+        assert_eq!(canvas.pixels.as_ptr(), buffer);
+
+        canvas.set_pixel(5, 7);
+        canvas.clear();
+        assert_eq!(canvas.to_string(), "⠀⠀⠀\n⠀⠀⠀");
+    }
+
     #[test]
     fn test_diagonal() {
         let mut canvas = BrailleCanvas::new(2, 1);
@@ -0,0 +1,95 @@
+use super::BrailleCanvas;
+
+/// Ordered-dither thresholds over one braille cell, in eighths: DITHER[y & 3][x & 1].
+/// Spread so that any coverage level lights its share of a cell's dots
+/// evenly instead of in a clump.
+static DITHER: [[u8; 2]; 4] = [
+    [0, 4],
+    [6, 2],
+    [1, 5],
+    [7, 3],
+];
+
+/// Fractional ink per braille pixel, for anti-aliased lines. Lines are
+/// drawn at `scale`× the resolution and add their ink to the braille pixel
+/// each fine pixel falls in, so a line clipping a pixel's corner leaves less
+/// than one running through it. `resolve` turns coverage into dots with an
+/// ordered dither: a pixel half covered is lit about half the time.
+#[derive(Clone)]
+pub struct CoverageCanvas {
+    width: usize,  // Pixels
+    height: usize, // Pixels
+    scale: usize,
+    coverage: Vec<f32>,
+}
+
+impl CoverageCanvas {
+    /// Canvas matching a `BrailleCanvas` of the given character dimensions,
+    /// taking lines drawn at `scale` fine pixels per braille pixel
+    pub fn new(width: usize, height: usize, scale: usize) -> Self {
+        Self { width: width * 2, height: height * 4, scale: scale.max(1), coverage: vec![0.0; width * height * 8] }
+    }
+
+    /// Add ink to a fine pixel; off-canvas pixels are ignored. A line one
+    /// fine pixel wide running straight through a braille pixel adds 1.
+    #[inline(always)]
+    pub fn add(&mut self, x: i32, y: i32, amount: f32) {
+        if x < 0 || y < 0 {
+            return;
+        }
+        let (x, y) = (x as usize / self.scale, y as usize / self.scale);
+        if x < self.width && y < self.height {
+            self.coverage[y * self.width + x] += amount / self.scale as f32;
+        }
+    }
+
+    /// Dots wherever coverage beats the pixel's dither threshold
+    pub fn resolve(&self) -> BrailleCanvas {
+        let mut canvas = BrailleCanvas::new(self.width / 2, self.height / 4);
+        self.resolve_into(&mut canvas);
+        canvas
+    }
+
+    /// `resolve` onto an existing canvas of the same size
+    pub fn resolve_into(&self, canvas: &mut BrailleCanvas) {
+        for (i, &ink) in self.coverage.iter().enumerate() {
+            let (x, y) = (i % self.width, i / self.width);
+            if ink * 8.0 > DITHER[y & 3][x & 1] as f32 + 0.5 {
+                canvas.set_pixel(x, y);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dither_lights_dots_in_proportion_to_coverage() {
+        let lit = |ink: f32| {
+            let mut canvas = CoverageCanvas::new(1, 1, 1);
+            for y in 0..4 {
+                for x in 0..2 {
+                    canvas.add(x, y, ink);
+                }
+            }
+            canvas.resolve().row_raw(0)[0].count_ones()
+        };
+        assert_eq!(lit(0.0), 0);
+        assert_eq!(lit(0.25), 2);
+        assert_eq!(lit(0.5), 4);
+        assert_eq!(lit(1.0), 8);
+        // Overlapping lines saturate rather than overflow
+        assert_eq!(lit(3.0), 8);
+
+        // At 4× a line through a pixel inks it fully; one grazing its
+        // corner for a single fine pixel barely at all
+        let mut canvas = CoverageCanvas::new(1, 1, 4);
+        for x in 0..4 {
+            canvas.add(x, 1, 1.0);
+        }
+        canvas.add(7, 15, 1.0);
+        assert_eq!(canvas.resolve().row_raw(0)[0], 0x01);
+    }
+}
@@ -1,3 +1,5 @@
 mod canvas;
+mod coverage;
 
 pub use canvas::BrailleCanvas;
+pub use coverage::CoverageCanvas;
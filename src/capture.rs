@@ -0,0 +1,284 @@
+//! Session capture: every drawn frame goes to a `FrameSink`.
+//!
+//! `CastWriter` records an asciinema v2 cast. Like a terminal backend, it
+//! diffs each frame against the last one it wrote and emits only the cells
+//! that changed, as cursor moves, SGR codes and text. Frames arriving faster
+//! than `MIN_INTERVAL` are skipped; the next one written carries their
+//! changes, so nothing is lost but the in-between motion. On finish, a
+//! frame still held back is written, and an empty event at the last frame's
+//! time keeps the final screen up for as long as it was shown.
+
+use anyhow::{Context, Result};
+use ratatui::buffer::{Buffer, Cell};
+use ratatui::style::{Color, Modifier};
+use simd_json::prelude::*;
+use simd_json::json;
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Receives each frame after it's drawn
+pub trait FrameSink {
+    /// `secs` is the time since the session started
+    fn frame(&mut self, secs: f64, buffer: &Buffer) -> Result<()>;
+    fn finish(&mut self) -> Result<()>;
+}
+
+/// asciinema v2 recorder
+pub struct CastWriter<W: Write> {
+    out: W,
+    /// Last frame written, diffed against the next
+    last: Option<Buffer>,
+    last_secs: f64,
+    /// Latest frame skipped as too soon, written on finish if nothing follows
+    pending: Option<(f64, Buffer)>,
+    /// Time of the last event written and of the last frame seen
+    event_secs: f64,
+    end_secs: f64,
+}
+
+impl CastWriter<BufWriter<File>> {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> CastWriter<W> {
+    /// Cap on recorded frames per second
+    pub const MIN_INTERVAL: f64 = 1.0 / 20.0;
+
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            last: None,
+            last_secs: f64::NEG_INFINITY,
+            pending: None,
+            event_secs: f64::NEG_INFINITY,
+            end_secs: f64::NEG_INFINITY,
+        }
+    }
+
+    fn event(&mut self, secs: f64, kind: &str, data: &str) -> Result<()> {
+        let secs = (secs * 1000.0).round() / 1000.0;
+        writeln!(self.out, "{}", json!([secs, kind, data]).encode())?;
+        self.event_secs = secs;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> CastWriter<W> {
+    fn write_frame(&mut self, secs: f64, buffer: &Buffer) -> Result<()> {
+        let area = buffer.area;
+        let blank = Buffer::empty(area);
+        let last = self.last.take();
+        let previous = match &last {
+            Some(last) if last.area == area => last,
+            Some(_) => {
+                self.event(secs, "r", &format!("{}x{}", area.width, area.height))?;
+                &blank
+            }
+            None => {
+                let unix_secs = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs());
+                let header = json!({
+                    "version": 2,
+                    "width": area.width,
+                    "height": area.height,
+                    "timestamp": unix_secs,
+                    "env": {"TERM": "xterm-256color"},
+                });
+                writeln!(self.out, "{}", header.encode())?;
+                &blank
+            }
+        };
+
+        // Fresh start or new size: clear the screen and paint it all
+        let mut data = String::new();
+        if !matches!(&last, Some(last) if last.area == area) {
+            data.push_str("\x1b[0m\x1b[2J");
+        }
+        write_updates(&mut data, &previous.diff(buffer));
+        if !data.is_empty() {
+            self.event(secs, "o", &data)?;
+        }
+        self.last = Some(buffer.clone());
+        self.last_secs = secs;
+        Ok(())
+    }
+}
+
+impl<W: Write> FrameSink for CastWriter<W> {
+    fn frame(&mut self, secs: f64, buffer: &Buffer) -> Result<()> {
+        self.end_secs = secs;
+        if secs - self.last_secs < Self::MIN_INTERVAL {
+            match &mut self.pending {
+                Some((pending_secs, pending)) => {
+                    *pending_secs = secs;
+                    pending.clone_from(buffer);
+                }
+                None => self.pending = Some((secs, buffer.clone())),
+            }
+            return Ok(());
+        }
+        self.pending = None;
+        self.write_frame(secs, buffer)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if let Some((secs, buffer)) = self.pending.take() {
+            self.write_frame(secs, &buffer)?;
+        }
+        // Hold the last screen until the session ended
+        if self.last.is_some() && (self.end_secs * 1000.0).round() > (self.event_secs * 1000.0).round() {
+            self.event(self.end_secs, "o", "")?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Changed cells as terminal output: a cursor move wherever the next cell
+/// isn't where the cursor already is, and SGR codes when the style changes
+fn write_updates(out: &mut String, updates: &[(u16, u16, &Cell)]) {
+    let mut cursor = None;
+    let mut style = None;
+    for &(x, y, cell) in updates {
+        if cursor != Some((x, y)) {
+            write!(out, "\x1b[{};{}H", y + 1, x + 1).ok();
+        }
+        let cell_style = (cell.fg, cell.bg, cell.modifier);
+        if style != Some(cell_style) {
+            out.push_str("\x1b[0");
+            push_color(out, cell.fg, false);
+            push_color(out, cell.bg, true);
+            for (modifier, code) in MODIFIER_CODES {
+                if cell.modifier.contains(modifier) {
+                    write!(out, ";{code}").ok();
+                }
+            }
+            out.push('m');
+            style = Some(cell_style);
+        }
+        out.push_str(cell.symbol());
+        cursor = Some((x + 1, y));
+    }
+    if style.is_some() {
+        out.push_str("\x1b[0m");
+    }
+}
+
+const MODIFIER_CODES: [(Modifier, u8); 9] = [
+    (Modifier::BOLD, 1),
+    (Modifier::DIM, 2),
+    (Modifier::ITALIC, 3),
+    (Modifier::UNDERLINED, 4),
+    (Modifier::SLOW_BLINK, 5),
+    (Modifier::RAPID_BLINK, 6),
+    (Modifier::REVERSED, 7),
+    (Modifier::HIDDEN, 8),
+    (Modifier::CROSSED_OUT, 9),
+];
+
+/// `;<sgr>` for a foreground (or background) color; nothing for the default
+fn push_color(out: &mut String, color: Color, background: bool) {
+    let base = if background { 40 } else { 30 };
+    let offset = match color {
+        Color::Reset => return,
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 60,
+        Color::LightRed => 61,
+        Color::LightGreen => 62,
+        Color::LightYellow => 63,
+        Color::LightBlue => 64,
+        Color::LightMagenta => 65,
+        Color::LightCyan => 66,
+        Color::White => 67,
+        Color::Indexed(i) => return write!(out, ";{};5;{i}", base + 8).unwrap_or_default(),
+        Color::Rgb(r, g, b) => return write!(out, ";{};2;{r};{g};{b}", base + 8).unwrap_or_default(),
+    };
+    write!(out, ";{}", base + offset).ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    fn lines(cast: Vec<u8>) -> Vec<simd_json::OwnedValue> {
+        String::from_utf8(cast).unwrap()
+            .lines()
+            .map(|line| simd_json::to_owned_value(&mut line.as_bytes().to_vec()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn cast_records_only_changed_cells() {
+        let mut cast = CastWriter::new(Vec::new());
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 10, 2));
+        buffer.set_string(0, 0, "hi", Style::default().fg(Color::Rgb(1, 2, 3)));
+        cast.frame(0.0, &buffer).unwrap();
+
+        // Too soon: skipped, but folded into the next frame written
+        buffer.set_string(0, 1, "x", Style::default());
+        cast.frame(0.01, &buffer).unwrap();
+        buffer.set_string(5, 1, "y", Style::default().fg(Color::Cyan));
+        cast.frame(0.5, &buffer).unwrap();
+        cast.frame(0.6, &buffer).unwrap();
+
+        cast.frame(0.7, &Buffer::empty(Rect::new(0, 0, 4, 1))).unwrap();
+        cast.finish().unwrap();
+
+        let events = lines(cast.into_inner());
+        assert_eq!(events[0]["version"].as_u64(), Some(2));
+        assert_eq!(events[0]["width"].as_u64(), Some(10));
+        assert_eq!(events[1][1].as_str(), Some("o"));
+        assert!(events[1][2].as_str().unwrap().contains("\x1b[1;1H\x1b[0;38;2;1;2;3mhi"));
+        assert_eq!(events[2][0].as_f64(), Some(0.5));
+        assert_eq!(events[2][2].as_str(), Some("\x1b[2;1H\x1b[0mx\x1b[2;6H\x1b[0;36my\x1b[0m"));
+        // An unchanged frame writes nothing; a resize repaints from scratch
+        assert_eq!(events[3][1].as_str(), Some("r"));
+        assert_eq!(events[3][2].as_str(), Some("4x1"));
+        assert_eq!(events.len(), 5);
+    }
+
+    #[test]
+    fn finish_writes_the_held_back_frame_and_the_final_hold() {
+        let mut cast = CastWriter::new(Vec::new());
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 1));
+        cast.frame(0.0, &buffer).unwrap();
+        cast.frame(1.0, &buffer).unwrap();
+        buffer.set_string(0, 0, "z", Style::default());
+        cast.frame(1.01, &buffer).unwrap();
+        cast.finish().unwrap();
+        let events = lines(cast.into_inner());
+        assert_eq!(events[2][0].as_f64(), Some(1.01));
+        assert_eq!(events[2][2].as_str(), Some("\x1b[1;1H\x1b[0mz\x1b[0m"));
+        assert_eq!(events.len(), 3);
+
+        // Unchanged to the end: an empty event keeps it on screen
+        let mut cast = CastWriter::new(Vec::new());
+        cast.frame(0.0, &buffer).unwrap();
+        cast.frame(1.0, &buffer).unwrap();
+        cast.frame(2.5, &buffer).unwrap();
+        cast.finish().unwrap();
+        let events = lines(cast.into_inner());
+        assert_eq!(events[2][0].as_f64(), Some(2.5));
+        assert_eq!(events[2][2].as_str(), Some(""));
+        assert_eq!(events.len(), 3);
+    }
+}
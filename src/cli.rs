@@ -0,0 +1,581 @@
+use crate::ai::Difficulty;
+use crate::app::WeaponType;
+use crate::colors::ColorDepth;
+use crate::game::Objective;
+use crate::gesture::ScrollMode;
+use crate::graphics::Protocol;
+use crate::heatmap::HeatmapSpec;
+use crate::map::fonts::LabelFonts;
+use crate::perf::FpsCap;
+use crate::targeting::Mirv;
+use crate::theme::Theme;
+use anyhow::{anyhow, bail, Result};
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Command-line options. Hand-rolled parsing — a handful of flags
+/// doesn't justify pulling in an argument-parser dependency.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Args {
+    /// Vector tile URL template (`{z}/{x}/{y}` placeholders) for the online basemap
+    pub tile_url: Option<String>,
+    /// Local `{z}/{x}/{y}.mvt` directory tree used as an offline tile source
+    pub tile_dir: Option<PathBuf>,
+    /// On-disk tile cache directory (defaults to the system temp dir)
+    pub tile_cache: Option<PathBuf>,
+    /// Maximum tile zoom level to request
+    pub tile_max_zoom: Option<u8>,
+    /// Wind grid file replacing the built-in seasonal wind pattern
+    pub wind_grid: Option<PathBuf>,
+    /// Gridded population raster (ESRI ASCII) for casualties outside cities
+    pub population: Option<PathBuf>,
+    /// Land-cover raster (ESRI ASCII, IGBP classes) for fire spread
+    pub land_cover: Option<PathBuf>,
+    /// Start with a retaliating adversary at this difficulty
+    pub ai: Option<Difficulty>,
+    /// Scenario script to play on startup
+    pub scenario: Option<PathBuf>,
+    /// Play toward a win/loss objective instead of free play
+    pub objective: Option<Objective>,
+    /// Host a two-player game on this port
+    pub host: Option<u16>,
+    /// Address the game host, spectator broadcast and metrics endpoint
+    /// listen on (loopback unless given)
+    pub bind: Option<IpAddr>,
+    /// Join a two-player game at `HOST:PORT`
+    pub connect: Option<String>,
+    /// Let spectators watch this game on this port
+    pub broadcast: Option<u16>,
+    /// Watch a game broadcast at `HOST:PORT`, read-only
+    pub watch: Option<String>,
+    /// Run the simulation without a terminal and print stats as JSON
+    pub headless: bool,
+    /// Simulation steps to run in headless mode
+    pub ticks: Option<u64>,
+    /// Time this many offscreen frames per projection and print the averages
+    pub bench_frames: Option<usize>,
+    /// Roll for random natural disasters from the start
+    pub disasters: bool,
+    /// Start in pandemic mode, with weapons off
+    pub pandemic: bool,
+    /// Seed for a reproducible run
+    pub seed: Option<u64>,
+    /// Basic reproduction number of bio-weapon outbreaks
+    pub r0: Option<f64>,
+    /// Zoom factor per scroll-wheel notch
+    pub zoom_rate: Option<f64>,
+    /// How quickly spin and pan momentum die away (1 = default)
+    pub friction: Option<f64>,
+    /// What vertical scrolling does
+    pub scroll: Option<ScrollMode>,
+    /// Longest gap between the clicks of a double click
+    pub double_click: Option<Duration>,
+    /// Where screenshots go (`.png` or `.svg`)
+    pub screenshot: Option<PathBuf>,
+    /// Stockpile capacity per weapon (palette order; None = unlimited)
+    pub stockpile: Option<[Option<u32>; WeaponType::ALL.len()]>,
+    /// Arm missiles with this many MIRV warheads from the start
+    pub mirv: Option<u8>,
+    /// Radius MIRV warheads spread over around the aim point, in km
+    pub mirv_spread: Option<f64>,
+    /// Screenshot resolution in pixels
+    pub screenshot_size: Option<(u32, u32)>,
+    /// Where GeoJSON exports of the world state go
+    pub export: Option<PathBuf>,
+    /// Where text dumps of the map go (`-` for stdout, `.txt` for no color)
+    pub ansi: Option<PathBuf>,
+    /// Record the session as an asciinema v2 cast
+    pub capture: Option<PathBuf>,
+    /// Draw coastlines and borders anti-aliased
+    pub antialias: bool,
+    /// How far simplified lines may stray from the original, in pixels
+    pub simplify: Option<f64>,
+    /// Color theme to start with
+    pub theme: Option<&'static Theme>,
+    /// Color depth, instead of detecting the terminal's
+    pub colors: Option<ColorDepth>,
+    /// Frame-rate cap
+    pub fps: Option<FpsCap>,
+    /// Keep full effect detail even when frames run over budget
+    pub no_degrade: bool,
+    /// Satellites to show: a TLE file, or `celestrak:<group>`
+    pub tle: Option<String>,
+    /// Satellites whose ground tracks are drawn, by name or catalog number
+    pub track: Vec<String>,
+    /// Earthquake feed to show: a USGS feed name, or a GeoJSON file
+    pub quakes: Option<String>,
+    /// URL or file of moving points to show, as JSON
+    pub live: Option<String>,
+    /// Seconds between fetches of the live points
+    pub live_every: Option<f64>,
+    /// Gridded data to draw as heatmaps
+    pub heatmaps: Vec<HeatmapSpec>,
+    /// Terminal graphics protocol to draw the basemap as an image with
+    pub graphics: Option<Protocol>,
+    /// Leave the window title alone
+    pub no_title: bool,
+    /// Describe the view in words instead of drawing it
+    pub text_mode: bool,
+    /// Language to read place names in, as a Natural Earth `name_<lang>` code
+    pub names: Option<String>,
+    /// Letter variants for capital and country labels
+    pub label_fonts: Option<LabelFonts>,
+    /// Ring the bell when a city is destroyed or a retaliation launches
+    pub bell: bool,
+    /// Send those as desktop notifications (OSC 9)
+    pub notify: bool,
+    /// Serve Prometheus metrics and a JSON snapshot over HTTP on this port
+    pub metrics_port: Option<u16>,
+    /// Take JSON commands from scripts on this Unix socket
+    pub control: Option<PathBuf>,
+    /// Append a row of totals per simulated second to this CSV file
+    pub stats_out: Option<PathBuf>,
+    /// Bytes of map data to keep loaded, dropping detail beyond it
+    pub memory_budget: Option<usize>,
+}
+
+impl Args {
+    /// Where listeners bind: `--bind`, or loopback so nothing is exposed
+    /// to the network unless asked for
+    pub fn bind_addr(&self) -> IpAddr {
+        self.bind.unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+    }
+
+    /// Parse from the process arguments (skipping the program name).
+    pub fn from_env() -> Result<Self> {
+        Self::parse(std::env::args().skip(1))
+    }
+
+    /// Parse from an explicit argument list.
+    pub fn parse<I, S>(args: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut out = Self::default();
+        let mut iter = args.into_iter().map(Into::into);
+
+        while let Some(arg) = iter.next() {
+            // Support both `--flag value` and `--flag=value`
+            let (flag, inline) = match arg.split_once('=') {
+                Some((f, v)) if f.starts_with("--") => (f.to_string(), Some(v.to_string())),
+                _ => (arg, None),
+            };
+            let mut value = |name: &str| -> Result<String> {
+                inline
+                    .clone()
+                    .or_else(|| iter.next())
+                    .ok_or_else(|| anyhow!("{name} requires a value"))
+            };
+
+            match flag.as_str() {
+                "--tiles" => out.tile_url = Some(value("--tiles")?),
+                "--tile-dir" => out.tile_dir = Some(PathBuf::from(value("--tile-dir")?)),
+                "--tile-cache" => out.tile_cache = Some(PathBuf::from(value("--tile-cache")?)),
+                "--tile-max-zoom" => {
+                    let v = value("--tile-max-zoom")?;
+                    out.tile_max_zoom = Some(v.parse().map_err(|_| anyhow!("invalid zoom: {v}"))?);
+                }
+                "--wind" => out.wind_grid = Some(PathBuf::from(value("--wind")?)),
+                "--population" => out.population = Some(PathBuf::from(value("--population")?)),
+                "--landcover" => out.land_cover = Some(PathBuf::from(value("--landcover")?)),
+                "--ai" => {
+                    let v = value("--ai")?;
+                    out.ai = match v.as_str() {
+                        "off" => None,
+                        _ => Some(Difficulty::parse(&v).ok_or_else(|| anyhow!("invalid difficulty: {v}"))?),
+                    };
+                }
+                "--scenario" | "--script" => out.scenario = Some(PathBuf::from(value(&flag)?)),
+                "--objective" => out.objective = Some(Objective::parse(&value("--objective")?)?),
+                "--host" => {
+                    let v = value("--host")?;
+                    out.host = Some(v.parse().map_err(|_| anyhow!("invalid port: {v}"))?);
+                }
+                "--bind" => {
+                    let v = value("--bind")?;
+                    out.bind = Some(v.parse().map_err(|_| anyhow!("invalid bind address: {v}"))?);
+                }
+                "--connect" => out.connect = Some(value("--connect")?),
+                "--broadcast" => {
+                    let v = value("--broadcast")?;
+                    out.broadcast = Some(v.parse().map_err(|_| anyhow!("invalid port: {v}"))?);
+                }
+                "--watch" => out.watch = Some(value("--watch")?),
+                "--headless" => out.headless = true,
+                "--disasters" => out.disasters = true,
+                "--pandemic" => out.pandemic = true,
+                "--antialias" => out.antialias = true,
+                "--simplify" => {
+                    let v = value("--simplify")?;
+                    let px: f64 = v.parse().map_err(|_| anyhow!("invalid tolerance: {v}"))?;
+                    if !px.is_finite() {
+                        bail!("invalid tolerance: {v}");
+                    }
+                    if px < 0.0 {
+                        bail!("tolerance can't be negative: {v}");
+                    }
+                    out.simplify = Some(px);
+                }
+                "--theme" => {
+                    let v = value("--theme")?;
+                    out.theme = Some(Theme::by_name(&v).ok_or_else(|| anyhow!("unknown theme: {v}"))?);
+                }
+                "--colors" => {
+                    let v = value("--colors")?;
+                    out.colors = Some(ColorDepth::parse(&v).ok_or_else(|| anyhow!("invalid color depth (expected truecolor, 256 or 16): {v}"))?);
+                }
+                "--fps" => {
+                    let v = value("--fps")?;
+                    out.fps = Some(FpsCap::parse(&v).ok_or_else(|| anyhow!("invalid frame rate (expected 30, 60 or uncapped): {v}"))?);
+                }
+                "--no-degrade" => out.no_degrade = true,
+                "--no-title" => out.no_title = true,
+                "--text-mode" => out.text_mode = true,
+                "--bell" => out.bell = true,
+                "--notify" => out.notify = true,
+                "--metrics-port" => {
+                    let v = value("--metrics-port")?;
+                    out.metrics_port = Some(v.parse().map_err(|_| anyhow!("invalid metrics port: {v}"))?);
+                }
+                "--control" => out.control = Some(PathBuf::from(value("--control")?)),
+                "--stats-out" => out.stats_out = Some(PathBuf::from(value("--stats-out")?)),
+                "--memory-budget" => out.memory_budget = Some(crate::memory::parse_size(&value("--memory-budget")?)?),
+                "--tle" => out.tle = Some(value("--tle")?),
+                "--track" => out.track.push(value("--track")?),
+                "--quakes" => out.quakes = Some(value("--quakes")?),
+                "--heatmap" => out.heatmaps.push(HeatmapSpec::parse(&value("--heatmap")?)?),
+                "--graphics" => {
+                    let v = value("--graphics")?;
+                    out.graphics = match v.as_str() {
+                        "auto" => Protocol::detect(),
+                        "off" => None,
+                        _ => Some(Protocol::parse(&v).ok_or_else(|| anyhow!("invalid graphics protocol (expected kitty, sixel, auto or off): {v}"))?),
+                    };
+                }
+                "--live" => out.live = Some(value("--live")?),
+                "--live-every" => {
+                    let v = value("--live-every")?;
+                    let secs: f64 = v.parse().map_err(|_| anyhow!("invalid interval: {v}"))?;
+                    if secs < 1.0 || !secs.is_finite() {
+                        bail!("live points can't be fetched more than once a second: {v}");
+                    }
+                    out.live_every = Some(secs);
+                }
+                "--seed" => {
+                    let v = value("--seed")?;
+                    out.seed = Some(v.parse().map_err(|_| anyhow!("invalid seed: {v}"))?);
+                }
+                "--r0" => {
+                    let v = value("--r0")?;
+                    let r0: f64 = v.parse().map_err(|_| anyhow!("invalid R0: {v}"))?;
+                    if r0 <= 0.0 || !r0.is_finite() {
+                        bail!("R0 must be positive: {v}");
+                    }
+                    out.r0 = Some(r0);
+                }
+                "--zoom-rate" => {
+                    let v = value("--zoom-rate")?;
+                    let rate: f64 = v.parse().map_err(|_| anyhow!("invalid zoom rate: {v}"))?;
+                    if !(rate > 1.0 && rate <= 4.0) {
+                        bail!("zoom rate must be above 1 and at most 4: {v}");
+                    }
+                    out.zoom_rate = Some(rate);
+                }
+                "--names" => {
+                    let v = value("--names")?.to_lowercase();
+                    if !crate::data::NAME_LANGUAGES.contains(&v.as_str()) {
+                        bail!("unknown name language (expected one of {}): {v}", crate::data::NAME_LANGUAGES.join(", "));
+                    }
+                    out.names = Some(v);
+                }
+                "--label-fonts" => {
+                    let v = value("--label-fonts")?;
+                    out.label_fonts = Some(LabelFonts::parse(&v).ok_or_else(|| anyhow!("invalid label fonts (expected plain, bold or smallcaps): {v}"))?);
+                }
+                "--friction" => {
+                    let v = value("--friction")?;
+                    let friction: f64 = v.parse().map_err(|_| anyhow!("invalid friction: {v}"))?;
+                    if !(friction > 0.0 && friction <= 100.0) {
+                        bail!("friction must be above 0 and at most 100: {v}");
+                    }
+                    out.friction = Some(friction);
+                }
+                "--scroll" => {
+                    let v = value("--scroll")?;
+                    out.scroll = Some(ScrollMode::parse(&v).ok_or_else(|| anyhow!("invalid scroll mode (expected zoom or pan): {v}"))?);
+                }
+                "--double-click" => {
+                    let v = value("--double-click")?;
+                    let ms: u64 = v.parse().map_err(|_| anyhow!("invalid double-click window (ms): {v}"))?;
+                    out.double_click = Some(Duration::from_millis(ms));
+                }
+                "--stockpile" => out.stockpile = Some(parse_stockpile(&value("--stockpile")?)?),
+                "--mirv" => {
+                    let v = value("--mirv")?;
+                    let n = v.parse::<u8>().ok().filter(|n| (Mirv::MIN_WARHEADS..=Mirv::MAX_WARHEADS).contains(n));
+                    out.mirv = Some(n.ok_or_else(|| {
+                        anyhow!("invalid warhead count (expected {} to {}): {v}", Mirv::MIN_WARHEADS, Mirv::MAX_WARHEADS)
+                    })?);
+                }
+                "--mirv-spread" => {
+                    let v = value("--mirv-spread")?;
+                    let km: f64 = v.parse().map_err(|_| anyhow!("invalid MIRV spread: {v}"))?;
+                    if km <= 0.0 || !km.is_finite() {
+                        bail!("MIRV spread must be positive: {v}");
+                    }
+                    out.mirv_spread = Some(km);
+                }
+                "--screenshot" => out.screenshot = Some(PathBuf::from(value("--screenshot")?)),
+                "--screenshot-size" => {
+                    let v = value("--screenshot-size")?;
+                    let size = v.split_once('x')
+                        .and_then(|(w, h)| Some((w.parse::<u32>().ok()?, h.parse::<u32>().ok()?)))
+                        .filter(|&(w, h)| (1..=16384).contains(&w) && (1..=16384).contains(&h));
+                    out.screenshot_size = Some(size.ok_or_else(|| anyhow!("invalid screenshot size (expected WIDTHxHEIGHT): {v}"))?);
+                }
+                "--export" => out.export = Some(PathBuf::from(value("--export")?)),
+                "--ansi" => out.ansi = Some(PathBuf::from(value("--ansi")?)),
+                "--capture" => out.capture = Some(PathBuf::from(value("--capture")?)),
+                "--ticks" => {
+                    let v = value("--ticks")?;
+                    out.ticks = Some(v.parse().map_err(|_| anyhow!("invalid tick count: {v}"))?);
+                }
+                "--bench-frame" => {
+                    let v = value("--bench-frame")?;
+                    let n = v.parse::<usize>().ok().filter(|&n| n > 0);
+                    out.bench_frames = Some(n.ok_or_else(|| anyhow!("invalid frame count: {v}"))?);
+                }
+                other => bail!("unknown argument: {other}"),
+            }
+        }
+
+        if out.host.is_some() && out.connect.is_some() {
+            bail!("--host and --connect are mutually exclusive");
+        }
+        if out.headless && (out.host.is_some() || out.connect.is_some()) {
+            bail!("--headless can't be combined with a two-player game");
+        }
+        if out.watch.is_some() && (out.host.is_some() || out.connect.is_some() || out.broadcast.is_some() || out.headless) {
+            bail!("--watch only watches: it can't be combined with --host, --connect, --broadcast or --headless");
+        }
+        if out.headless && out.capture.is_some() {
+            bail!("--capture records the terminal UI, so it can't be combined with --headless");
+        }
+        if !out.track.is_empty() && out.tle.is_none() {
+            bail!("--track needs satellites to pick from (--tle)");
+        }
+        if out.live_every.is_some() && out.live.is_none() {
+            bail!("--live-every needs a source to fetch (--live)");
+        }
+        if out.bench_frames.is_some() && (out.headless || out.capture.is_some() || out.host.is_some() || out.connect.is_some() || out.watch.is_some()) {
+            bail!("--bench-frame draws offscreen on its own: it can't be combined with --headless, --capture or a network game");
+        }
+        if out.ticks.is_some() && !out.headless {
+            bail!("--ticks only applies with --headless");
+        }
+
+        Ok(out)
+    }
+}
+
+/// Comma-separated capacities: a bare count (or `inf`) sets every weapon,
+/// `weapon=count` one of them, e.g. `10,asteroid=1,airburst=inf`
+fn parse_stockpile(spec: &str) -> Result<[Option<u32>; WeaponType::ALL.len()]> {
+    let mut capacities = [None; WeaponType::ALL.len()];
+    for entry in spec.split(',').map(str::trim) {
+        let (weapon, count) = match entry.split_once('=') {
+            Some((name, count)) => {
+                let weapon = WeaponType::parse(name.trim()).ok_or_else(|| anyhow!("unknown weapon in stockpile: {name}"))?;
+                (Some(weapon), count.trim())
+            }
+            None => (None, entry),
+        };
+        let capacity = match count {
+            "inf" => None,
+            n => Some(n.parse::<u32>().map_err(|_| anyhow!("invalid stockpile count (expected a number or inf): {n}"))?),
+        };
+        match weapon {
+            Some(weapon) => capacities[weapon.index()] = capacity,
+            None => capacities = [capacity; WeaponType::ALL.len()],
+        }
+    }
+    Ok(capacities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tile_flags() {
+        let args = Args::parse([
+            "--tiles", "https://example.com/{z}/{x}/{y}.mvt",
+            "--tile-cache=/tmp/tiles",
+            "--tile-max-zoom", "12",
+            "--wind", "winds.txt",
+            "--population=gpw.asc",
+            "--landcover", "igbp.asc",
+            "--ai=hard",
+            "--scenario", "scenarios/demo.txt",
+            "--objective=survive:2",
+            "--antialias",
+            "--simplify=1.5",
+            "--theme", "norad",
+            "--colors=256",
+            "--fps", "30",
+            "--no-degrade",
+            "--tle", "celestrak:stations",
+            "--track", "iss",
+            "--track=25544",
+            "--quakes", "4.5_week",
+            "--live", "https://example.com/flights.json",
+            "--live-every=5",
+            "--heatmap", "sst_anomaly.asc,ramp=thermal,range=-3..3",
+            "--heatmap=nightlights.asc,log",
+            "--graphics", "kitty",
+            "--no-title",
+            "--text-mode",
+            "--bell",
+            "--notify",
+            "--disasters",
+            "--pandemic",
+            "--metrics-port=9090",
+            "--control", "/tmp/tui-map.sock",
+            "--stats-out", "run.csv",
+            "--memory-budget", "512M",
+        ]).unwrap();
+        assert_eq!(args.tile_url.as_deref(), Some("https://example.com/{z}/{x}/{y}.mvt"));
+        assert_eq!(args.tile_cache, Some(PathBuf::from("/tmp/tiles")));
+        assert_eq!(args.tile_max_zoom, Some(12));
+        assert_eq!(args.wind_grid, Some(PathBuf::from("winds.txt")));
+        assert_eq!(args.population, Some(PathBuf::from("gpw.asc")));
+        assert_eq!(args.land_cover, Some(PathBuf::from("igbp.asc")));
+        assert_eq!(args.ai, Some(Difficulty::Hard));
+        assert_eq!(args.scenario, Some(PathBuf::from("scenarios/demo.txt")));
+        assert_eq!(args.objective, Some(Objective::Survive { waves: 2, percent: 50.0 }));
+        assert!(args.antialias);
+        assert_eq!(args.simplify, Some(1.5));
+        assert_eq!(args.theme.map(|t| t.name), Some("norad"));
+        assert_eq!(args.colors, Some(ColorDepth::Ansi256));
+        assert_eq!(args.fps, Some(FpsCap::Thirty));
+        assert!(args.no_degrade);
+        assert_eq!(args.tle.as_deref(), Some("celestrak:stations"));
+        assert_eq!(args.track, ["iss", "25544"]);
+        assert_eq!(args.quakes.as_deref(), Some("4.5_week"));
+        assert_eq!(args.live.as_deref(), Some("https://example.com/flights.json"));
+        assert_eq!(args.live_every, Some(5.0));
+        assert_eq!(args.heatmaps.len(), 2);
+        assert_eq!(args.heatmaps[0].range, Some((-3.0, 3.0)));
+        assert_eq!(args.heatmaps[1].scale, crate::heatmap::Scale::Log);
+        assert_eq!(args.graphics, Some(Protocol::Kitty));
+        assert!(args.no_title && args.text_mode && args.bell && args.notify && args.disasters && args.pandemic);
+        assert_eq!(args.metrics_port, Some(9090));
+        assert_eq!(args.control, Some(PathBuf::from("/tmp/tui-map.sock")));
+        assert_eq!(args.stats_out, Some(PathBuf::from("run.csv")));
+        assert_eq!(args.memory_budget, Some(512 << 20));
+        assert!(Args::parse(["--live-every", "5"]).is_err());
+        assert!(Args::parse(["--live", "ships.json", "--live-every", "0.1"]).is_err());
+        assert!(Args::parse(["--track", "iss"]).is_err());
+    }
+
+    #[test]
+    fn parses_network_flags() {
+        assert_eq!(Args::parse(["--host", "7777"]).unwrap().host, Some(7777));
+        assert_eq!(Args::parse(["--connect=10.0.0.2:7777"]).unwrap().connect.as_deref(), Some("10.0.0.2:7777"));
+        assert!(Args::parse(["--host", "99999"]).is_err());
+        assert_eq!(Args::parse(["--host", "7777"]).unwrap().bind_addr().to_string(), "127.0.0.1");
+        assert_eq!(Args::parse(["--bind=0.0.0.0"]).unwrap().bind_addr().to_string(), "0.0.0.0");
+        assert!(Args::parse(["--bind", "anywhere"]).is_err());
+        assert!(Args::parse(["--host", "7777", "--connect", "a:1"]).is_err());
+        let args = Args::parse(["--host", "7777", "--broadcast", "7778"]).unwrap();
+        assert_eq!(args.broadcast, Some(7778));
+        assert_eq!(Args::parse(["--watch", "10.0.0.2:7778"]).unwrap().watch.as_deref(), Some("10.0.0.2:7778"));
+        assert!(Args::parse(["--watch", "a:1", "--connect", "a:2"]).is_err());
+    }
+
+    #[test]
+    fn parses_headless_flags() {
+        let args = Args::parse(["--headless", "--script", "run.txt", "--ticks=600", "--seed", "42"]).unwrap();
+        assert!(args.headless);
+        assert_eq!(args.seed, Some(42));
+        assert_eq!(args.scenario, Some(PathBuf::from("run.txt")));
+        assert_eq!(args.ticks, Some(600));
+        assert!(Args::parse(["--ticks", "600"]).is_err());
+        assert!(Args::parse(["--headless", "--host", "7777"]).is_err());
+    }
+
+    #[test]
+    fn parses_bench_frames() {
+        assert_eq!(Args::parse(["--bench-frame", "300"]).unwrap().bench_frames, Some(300));
+        assert!(Args::parse(["--bench-frame=0"]).is_err());
+        assert!(Args::parse(["--bench-frame", "300", "--headless"]).is_err());
+    }
+
+    #[test]
+    fn parses_camera_flags() {
+        assert_eq!(Args::parse(["--zoom-rate", "1.25"]).unwrap().zoom_rate, Some(1.25));
+        assert!(Args::parse(["--zoom-rate=0.9"]).is_err());
+        assert_eq!(Args::parse(["--names", "ZH"]).unwrap().names.as_deref(), Some("zh"));
+        assert!(Args::parse(["--names", "klingon"]).is_err());
+        assert_eq!(Args::parse(["--label-fonts", "bold"]).unwrap().label_fonts, Some(LabelFonts::Bold));
+        assert!(Args::parse(["--label-fonts", "comic"]).is_err());
+        assert_eq!(Args::parse(["--friction", "2"]).unwrap().friction, Some(2.0));
+        assert!(Args::parse(["--friction", "0"]).is_err());
+        let args = Args::parse(["--scroll", "pan", "--double-click=0"]).unwrap();
+        assert_eq!(args.scroll, Some(ScrollMode::Pan));
+        assert_eq!(args.double_click, Some(Duration::ZERO));
+        assert!(Args::parse(["--scroll", "swipe"]).is_err());
+    }
+
+    #[test]
+    fn parses_stockpiles() {
+        let caps = Args::parse(["--stockpile", "10, asteroid=1,airburst=inf"]).unwrap().stockpile.unwrap();
+        assert_eq!(caps[WeaponType::Nuke.index()], Some(10));
+        assert_eq!(caps[WeaponType::Asteroid.index()], Some(1));
+        assert_eq!(caps[WeaponType::Airburst.index()], None);
+        assert!(Args::parse(["--stockpile", "nukes=3"]).is_err());
+        assert!(Args::parse(["--stockpile", "-1"]).is_err());
+    }
+
+    #[test]
+    fn parses_mirv_flags() {
+        let args = Args::parse(["--mirv", "8", "--mirv-spread=120"]).unwrap();
+        assert_eq!(args.mirv, Some(8));
+        assert_eq!(args.mirv_spread, Some(120.0));
+        assert!(Args::parse(["--mirv", "1"]).is_err());
+        assert!(Args::parse(["--mirv", "17"]).is_err());
+        assert!(Args::parse(["--mirv-spread", "0"]).is_err());
+    }
+
+    #[test]
+    fn parses_screenshot_flags() {
+        let args = Args::parse(["--screenshot", "map.svg", "--screenshot-size=3840x2160"]).unwrap();
+        assert_eq!(args.screenshot, Some(PathBuf::from("map.svg")));
+        assert_eq!(args.screenshot_size, Some((3840, 2160)));
+        assert!(Args::parse(["--screenshot-size", "1920"]).is_err());
+        assert!(Args::parse(["--screenshot-size", "0x100"]).is_err());
+        assert_eq!(Args::parse(["--export=run.geojson"]).unwrap().export, Some(PathBuf::from("run.geojson")));
+        assert_eq!(Args::parse(["--ansi", "-"]).unwrap().ansi, Some(PathBuf::from("-")));
+        assert_eq!(Args::parse(["--capture", "out.cast"]).unwrap().capture, Some(PathBuf::from("out.cast")));
+        assert!(Args::parse(["--capture", "out.cast", "--headless"]).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_and_missing_values() {
+        assert!(Args::parse(["--bogus"]).is_err());
+        assert!(Args::parse(["--tiles"]).is_err());
+        assert!(Args::parse(["--tile-max-zoom", "x"]).is_err());
+        assert!(Args::parse(["--ai", "brutal"]).is_err());
+        assert!(Args::parse(["--objective", "conquer"]).is_err());
+        assert!(Args::parse(["--r0", "-1"]).is_err());
+        assert!(Args::parse(["--zoom-rate", "1"]).is_err());
+        assert!(Args::parse(["--simplify", "-1"]).is_err());
+        assert_eq!(Args::parse(["--simplify", "NaN"]).unwrap_err().to_string(), "invalid tolerance: NaN");
+        assert!(Args::parse(["--theme", "neon"]).is_err());
+        assert!(Args::parse(["--colors", "88"]).is_err());
+        assert!(Args::parse(["--fps", "45"]).is_err());
+        assert!(Args::parse(["--graphics", "iterm"]).is_err());
+    }
+}
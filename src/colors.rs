@@ -0,0 +1,195 @@
+//! Terminal color depth.
+//!
+//! Fires, explosions, fills and the globe's sky are drawn in 24-bit color.
+//! On a terminal that only has the xterm 256-color palette or the sixteen
+//! named colors, each finished frame is quantized to the nearest color the
+//! terminal has. The depth comes from `COLORTERM`, then from the terminfo
+//! entry for `TERM`, then from the name in `TERM` itself.
+
+use crate::halfblock::Rgb;
+use crate::theme::{luma, ANSI};
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+use std::path::PathBuf;
+
+/// How many colors the terminal can show
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    /// The xterm 256-color palette
+    Ansi256,
+    /// The sixteen named colors (Linux console, plain xterm)
+    Ansi16,
+}
+
+/// Channel levels of the 6×6×6 color cube at indexes 16–231
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// terminfo's numeric capability index for `colors`
+const TERMINFO_COLORS: usize = 13;
+
+impl ColorDepth {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "truecolor" | "24bit" => Some(Self::TrueColor),
+            "256" => Some(Self::Ansi256),
+            "16" => Some(Self::Ansi16),
+            _ => None,
+        }
+    }
+
+    /// The depth of the terminal we're running in
+    pub fn detect() -> Self {
+        let term = std::env::var("TERM").ok();
+        let colors = term.as_deref().and_then(terminfo_colors);
+        Self::from_env(std::env::var("COLORTERM").ok().as_deref(), term.as_deref(), colors)
+    }
+
+    /// Depth from `COLORTERM`, `TERM` and the terminfo `colors` count
+    pub fn from_env(colorterm: Option<&str>, term: Option<&str>, terminfo_colors: Option<i32>) -> Self {
+        if matches!(colorterm, Some("truecolor" | "24bit")) {
+            return Self::TrueColor;
+        }
+        // No TERM at all means a console that isn't a Unix terminal
+        // (Windows), which handles 24-bit color
+        let Some(term) = term else { return Self::TrueColor };
+        match terminfo_colors {
+            Some(n) if n >= 1 << 24 => Self::TrueColor,
+            Some(n) if n >= 256 => Self::Ansi256,
+            Some(_) => Self::Ansi16,
+            None if term.contains("direct") || term.contains("truecolor") => Self::TrueColor,
+            None if term.contains("256") => Self::Ansi256,
+            None => Self::Ansi16,
+        }
+    }
+
+    /// The nearest color this depth can show. Named and indexed colors
+    /// pass through.
+    pub fn quantize(self, color: Color) -> Color {
+        let Color::Rgb(r, g, b) = color else { return color };
+        match self {
+            Self::TrueColor => color,
+            Self::Ansi256 => Color::Indexed(nearest_256((r, g, b))),
+            Self::Ansi16 => nearest_16((r, g, b)),
+        }
+    }
+
+    /// Quantize every cell of a drawn frame
+    pub fn apply(self, buf: &mut Buffer) {
+        if self == Self::TrueColor {
+            return;
+        }
+        for cell in &mut buf.content {
+            cell.fg = self.quantize(cell.fg);
+            cell.bg = self.quantize(cell.bg);
+        }
+    }
+}
+
+fn distance((r1, g1, b1): Rgb, (r2, g2, b2): Rgb) -> i32 {
+    // Weighted toward green, where the eye tells shades apart best
+    let (dr, dg, db) = (r1 as i32 - r2 as i32, g1 as i32 - g2 as i32, b1 as i32 - b2 as i32);
+    2 * dr * dr + 4 * dg * dg + 3 * db * db
+}
+
+/// Palette index of the nearest cube color or gray-ramp step
+fn nearest_256(rgb: Rgb) -> u8 {
+    let level = |c: u8| CUBE.iter().enumerate().min_by_key(|&(_, &l)| (l as i32 - c as i32).abs()).unwrap().0;
+    let (ri, gi, bi) = (level(rgb.0), level(rgb.1), level(rgb.2));
+    let cube = (CUBE[ri], CUBE[gi], CUBE[bi]);
+    // Grays 232–255 run from 8 to 238 in steps of 10
+    let step = ((luma(rgb) - 8.0) / 10.0).round().clamp(0.0, 23.0) as u8;
+    let gray = 8 + step * 10;
+    if distance(rgb, (gray, gray, gray)) < distance(rgb, cube) {
+        232 + step
+    } else {
+        16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8
+    }
+}
+
+fn nearest_16(rgb: Rgb) -> Color {
+    ANSI.iter().min_by_key(|&&(_, c)| distance(rgb, c)).unwrap().0
+}
+
+/// The `colors` capability of the terminfo entry for `term`, if one is
+/// installed
+fn terminfo_colors(term: &str) -> Option<i32> {
+    let first = term.chars().next()?;
+    let var = |k| std::env::var_os(k).map(PathBuf::from);
+    let mut dirs: Vec<PathBuf> = var("TERMINFO").into_iter()
+        .chain(var("HOME").map(|h| h.join(".terminfo")))
+        .collect();
+    if let Some(list) = std::env::var_os("TERMINFO_DIRS") {
+        dirs.extend(std::env::split_paths(&list));
+    }
+    dirs.extend(["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo", "/usr/lib/terminfo"].map(PathBuf::from));
+    // Entries sit under their first letter, or its hex code on macOS
+    dirs.iter()
+        .flat_map(|dir| [dir.join(first.to_string()), dir.join(format!("{:x}", first as u32))])
+        .find_map(|sub| std::fs::read(sub.join(term)).ok())
+        .and_then(|bytes| terminfo_number(&bytes, TERMINFO_COLORS))
+}
+
+/// A numeric capability from a compiled terminfo entry, in either the
+/// legacy 16-bit or the extended 32-bit number format
+fn terminfo_number(bytes: &[u8], index: usize) -> Option<i32> {
+    let word = |i: usize| bytes.get(i * 2..i * 2 + 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as usize);
+    let width = match word(0)? {
+        0o432 => 2,
+        0o1036 => 4,
+        _ => return None,
+    };
+    let (names, bools, numbers) = (word(1)?, word(2)?, word(3)?);
+    if index >= numbers {
+        return None;
+    }
+    // Numbers start on an even offset after the header, names and booleans
+    let start = (12 + names + bools).next_multiple_of(2) + index * width;
+    let b = bytes.get(start..start + width)?;
+    let n = match width {
+        2 => i16::from_le_bytes([b[0], b[1]]) as i32,
+        _ => i32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+    };
+    (n >= 0).then_some(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_comes_from_colorterm_then_terminfo_then_the_name() {
+        assert_eq!(ColorDepth::from_env(Some("truecolor"), Some("xterm"), Some(8)), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::from_env(None, Some("xterm-256color"), Some(256)), ColorDepth::Ansi256);
+        assert_eq!(ColorDepth::from_env(None, Some("linux"), Some(8)), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::from_env(None, Some("xterm-direct"), Some(1 << 24)), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::from_env(None, Some("screen-256color"), None), ColorDepth::Ansi256);
+        assert_eq!(ColorDepth::from_env(None, Some("vt100"), None), ColorDepth::Ansi16);
+
+        // Header, a 2-byte name, 3 booleans (padded to even), then numbers
+        // with `colors` at index 13
+        let mut entry = Vec::new();
+        for word in [0o432u16, 2, 3, 14, 0, 0] {
+            entry.extend(word.to_le_bytes());
+        }
+        entry.extend([b'x', 0, 1, 1, 0, 0]);
+        for n in 0..14i16 {
+            entry.extend((if n == 13 { 256i16 } else { -1 }).to_le_bytes());
+        }
+        assert_eq!(terminfo_number(&entry, TERMINFO_COLORS), Some(256));
+        assert_eq!(terminfo_number(&entry, 0), None);
+        assert_eq!(terminfo_number(&entry[..12], TERMINFO_COLORS), None);
+    }
+
+    #[test]
+    fn gradients_quantize_to_the_nearest_palette_color() {
+        let fire = Color::Rgb(255, 120, 0);
+        assert_eq!(ColorDepth::TrueColor.quantize(fire), fire);
+        // Cube color (5, 2, 0), and a dark gray lands on the gray ramp
+        assert_eq!(ColorDepth::Ansi256.quantize(fire), Color::Indexed(16 + 36 * 5 + 6 * 2));
+        assert_eq!(ColorDepth::Ansi256.quantize(Color::Rgb(50, 50, 52)), Color::Indexed(236));
+        assert_eq!(ColorDepth::Ansi16.quantize(Color::Rgb(200, 30, 0)), Color::Red);
+        assert_eq!(ColorDepth::Ansi16.quantize(Color::Rgb(10, 12, 8)), Color::Black);
+        assert_eq!(ColorDepth::Ansi16.quantize(Color::Cyan), Color::Cyan);
+    }
+}
@@ -0,0 +1,735 @@
+//! The `:` command line, vim style: every toggle and setting by name, so
+//! none of the key chords need remembering.
+//!
+//! ```text
+//! :goto 35.68,139.76      latitude, longitude
+//! :goto 33.87S 151.21E    hemisphere letters, either order
+//! :goto tokyo             a loaded city, a country, or a gazetteer entry
+//! :layer counties off     show, hide or (without on/off) toggle a layer
+//! :weapon emp             select a weapon by name or palette number
+//! :theme norad            and yield, delivery, reticle, mirv, ai,
+//!                         projection, seed, pause, disasters, pandemic,
+//!                         screenshot, quit
+//! :save game1.sav         save the world to pick up later with `:load`
+//! :export run.geojson     export the world state as GeoJSON
+//! :record t               record input into a macro register; alone, stop
+//! :play t 3               play a register, a number of times over
+//! :data download 10m      fetch Natural Earth files; `:data verify` checks them
+//! :overlay graticule on   show or hide a registered overlay; alone, list them
+//! :heatmap sst off        show or hide a loaded heatmap; alone, list them
+//! :memory 512M            set the memory budget (or off); alone, report usage
+//! ```
+//!
+//! Any unambiguous prefix of a command name works (`:lay`, `:th`). `Tab`
+//! completes command names and their arguments; `Up`/`Down` step through
+//! earlier commands.
+
+use crate::ai::Difficulty;
+use crate::app::{Delivery, WeaponType, Yield};
+use crate::data::fetch::Resolution;
+use crate::macros::Macros;
+use crate::memory;
+use crate::map::projection::Projection;
+use crate::targeting::Reticle;
+use crate::theme::{Theme, THEMES};
+use anyhow::{anyhow, bail, Result};
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Goto(GotoTarget),
+    Layer(Layer, Switch),
+    Weapon(WeaponType),
+    Yield(Yield),
+    Delivery(Delivery),
+    Reticle(Reticle),
+    Mirv(Switch),
+    /// Adversary difficulty, or `None` to turn it off
+    Ai(Option<Difficulty>),
+    Theme(&'static Theme),
+    /// A projection from the `g` cycle, by name
+    Projection(&'static str),
+    Seed(u64),
+    Pause(Switch),
+    /// Random volcanoes, hurricanes and wildfires
+    Disasters(Switch),
+    /// Weapons off; clicks seed outbreaks
+    Pandemic(Switch),
+    /// Record input into a macro register, or stop recording
+    Record(Option<char>),
+    /// Play a macro register a number of times
+    Play(char, u32),
+    Data(DataAction),
+    /// Show or hide an overlay by name, or with `None`, list them
+    Overlay(Option<(String, Switch)>),
+    /// Show or hide a heatmap by name, or with `None`, list them
+    Heatmap(Option<(String, Switch)>),
+    /// Save the world; without a file, to a timestamped one
+    Save(Option<PathBuf>),
+    /// Load a world saved by `Save`
+    Load(PathBuf),
+    /// Export the world state as GeoJSON; without a file, to `--export` or a
+    /// timestamped one
+    Export(Option<PathBuf>),
+    Memory(MemoryAction),
+    Screenshot,
+    Help,
+    Quit,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GotoTarget {
+    Coords { lon: f64, lat: f64 },
+    /// A city, country or other place, looked up by name
+    Place(String),
+}
+
+/// What `:data` does
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataAction {
+    /// Show the data manager
+    Show,
+    Download(Resolution),
+    Verify,
+}
+
+/// What `:memory` does
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryAction {
+    /// Report bytes used per layer
+    Report,
+    /// Set the budget in bytes, or lift it with `None`
+    Budget(Option<usize>),
+}
+
+/// Turn something on, off, or over
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Switch {
+    On,
+    Off,
+    Toggle,
+}
+
+impl Switch {
+    /// `on`/`off` and their usual spellings; nothing at all toggles
+    fn parse(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "" | "toggle" => Ok(Switch::Toggle),
+            "on" | "1" | "true" | "yes" | "show" => Ok(Switch::On),
+            "off" | "0" | "false" | "no" | "hide" => Ok(Switch::Off),
+            other => Err(anyhow!("expected on or off, not {other}")),
+        }
+    }
+
+    /// The new state of something that's currently `on`
+    pub fn apply(self, on: bool) -> bool {
+        match self {
+            Switch::On => true,
+            Switch::Off => false,
+            Switch::Toggle => !on,
+        }
+    }
+}
+
+/// Everything `:layer` can show or hide
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layer {
+    Coastlines,
+    Borders,
+    States,
+    Counties,
+    Cities,
+    Labels,
+    /// Population figures next to city names
+    Population,
+    Roads,
+    Railways,
+    Airports,
+    Ports,
+    /// The population density raster
+    Density,
+    Weather,
+    Night,
+    Glint,
+    Infection,
+    /// Flight routes of the travel network
+    Flights,
+    Radiation,
+    /// Ground scorched by past fires
+    Scars,
+    /// Land and lake fills, and the country fills factions tint
+    Fills,
+    Factions,
+    Sidebar,
+    Events,
+    /// The detailed HUD (`H`)
+    Hud,
+    /// The performance HUD (`Ctrl+F`)
+    Perf,
+    Legend,
+}
+
+impl Layer {
+    pub const ALL: [Layer; 26] = [
+        Layer::Coastlines,
+        Layer::Borders,
+        Layer::States,
+        Layer::Counties,
+        Layer::Cities,
+        Layer::Labels,
+        Layer::Population,
+        Layer::Roads,
+        Layer::Railways,
+        Layer::Airports,
+        Layer::Ports,
+        Layer::Density,
+        Layer::Weather,
+        Layer::Night,
+        Layer::Glint,
+        Layer::Infection,
+        Layer::Flights,
+        Layer::Radiation,
+        Layer::Scars,
+        Layer::Fills,
+        Layer::Factions,
+        Layer::Sidebar,
+        Layer::Events,
+        Layer::Hud,
+        Layer::Perf,
+        Layer::Legend,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Layer::Coastlines => "coastlines",
+            Layer::Borders => "borders",
+            Layer::States => "states",
+            Layer::Counties => "counties",
+            Layer::Cities => "cities",
+            Layer::Labels => "labels",
+            Layer::Population => "population",
+            Layer::Roads => "roads",
+            Layer::Railways => "railways",
+            Layer::Airports => "airports",
+            Layer::Ports => "ports",
+            Layer::Density => "density",
+            Layer::Weather => "weather",
+            Layer::Night => "night",
+            Layer::Glint => "glint",
+            Layer::Infection => "infection",
+            Layer::Flights => "flights",
+            Layer::Radiation => "radiation",
+            Layer::Scars => "scars",
+            Layer::Fills => "fills",
+            Layer::Factions => "factions",
+            Layer::Sidebar => "sidebar",
+            Layer::Events => "events",
+            Layer::Hud => "hud",
+            Layer::Perf => "perf",
+            Layer::Legend => "legend",
+        }
+    }
+}
+
+/// Command names and their arguments, for parsing, `:help` and the prompt
+const COMMANDS: [(&str, &str); 26] = [
+    ("goto", "<lat>,<lon> | <place>"),
+    ("layer", "<layer> [on|off]"),
+    ("weapon", "<name> | <1-7>"),
+    ("yield", "15kt | 300kt | 1mt | 50mt"),
+    ("delivery", "icbm | slbm | bomber"),
+    ("reticle", "point | corridor | area"),
+    ("mirv", "[on|off]"),
+    ("ai", "off | easy | normal | hard"),
+    ("theme", "<name>"),
+    ("projection", "<name>"),
+    ("seed", "<number>"),
+    ("pause", "[on|off]"),
+    ("disasters", "[on|off]"),
+    ("pandemic", "[on|off]"),
+    ("record", "[register]"),
+    ("play", "<register> [times]"),
+    ("data", "[download 110m|50m|10m | verify]"),
+    ("overlay", "[<name> [on|off]]"),
+    ("heatmap", "[<name> [on|off]]"),
+    ("save", "[file]"),
+    ("load", "<file>"),
+    ("export", "[file]"),
+    ("memory", "[<budget> | off]"),
+    ("screenshot", ""),
+    ("help", ""),
+    ("quit", ""),
+];
+
+const SWITCHES: [&str; 3] = ["on", "off", "toggle"];
+
+/// The full name of a command typed as itself, a vim-style short form, or
+/// any prefix only it starts with
+fn resolve(name: &str) -> Result<&'static str> {
+    let name = name.to_ascii_lowercase();
+    let short = match name.as_str() {
+        "g" | "go" => Some("goto"),
+        "w" => Some("save"),
+        "q" => Some("quit"),
+        "h" => Some("help"),
+        _ => None,
+    };
+    if let Some(full) = short {
+        return Ok(full);
+    }
+    unique_prefix(&name, names(), |c| c, "command")
+}
+
+/// The one option named `word`, or whose name starts with it, ignoring case
+fn unique_prefix<T: Copy>(word: &str, options: impl IntoIterator<Item = T>, name: impl Fn(T) -> &'static str, what: &str) -> Result<T> {
+    let matches: Vec<T> = options
+        .into_iter()
+        .filter(|&o| name(o).get(..word.len()).is_some_and(|n| n.eq_ignore_ascii_case(word)))
+        .collect();
+    if let Some(&exact) = matches.iter().find(|&&o| name(o).eq_ignore_ascii_case(word)) {
+        return Ok(exact);
+    }
+    match matches[..] {
+        [] => Err(anyhow!("unknown {what}: {word}")),
+        [only] => Ok(only),
+        _ => {
+            let names: Vec<&str> = matches.into_iter().map(name).collect();
+            Err(anyhow!("ambiguous {what}: {word} ({})", names.join(", ")))
+        }
+    }
+}
+
+/// `command <usage>` for a line whose command is known, e.g. to prompt for
+/// its arguments as they're typed
+pub fn usage(line: &str) -> Option<String> {
+    let name = line.trim().trim_start_matches(':').split_whitespace().next()?;
+    let name = resolve(name).ok()?;
+    let (_, args) = COMMANDS.iter().find(|&&(c, _)| c == name)?;
+    Some(format!("{name} {args}").trim_end().to_string())
+}
+
+/// Every command name, for `:help`
+pub fn names() -> impl Iterator<Item = &'static str> {
+    COMMANDS.iter().map(|&(c, _)| c)
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Self> {
+        let line = line.trim().trim_start_matches(':').trim_start();
+        let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        if name.is_empty() {
+            bail!("empty command");
+        }
+        let name = resolve(name)?;
+        let usage = || anyhow!("usage: {}", usage(name).unwrap_or_default());
+        let word = rest.to_ascii_lowercase();
+        Ok(match name {
+            "goto" => {
+                if rest.is_empty() {
+                    return Err(usage());
+                }
+                Command::Goto(match parse_coords(rest)? {
+                    Some((lon, lat)) => GotoTarget::Coords { lon, lat },
+                    None => GotoTarget::Place(rest.to_string()),
+                })
+            }
+            "layer" => {
+                let (layer, switch) = word.split_once(char::is_whitespace).unwrap_or((&word, ""));
+                if layer.is_empty() {
+                    return Err(usage());
+                }
+                let layer = unique_prefix(layer, Layer::ALL, Layer::name, "layer")?;
+                Command::Layer(layer, Switch::parse(switch.trim())?)
+            }
+            "weapon" => {
+                let weapon = match word.parse::<usize>() {
+                    Ok(n) => n.checked_sub(1).and_then(|i| WeaponType::ALL.get(i).copied()),
+                    Err(_) => WeaponType::parse(&word),
+                };
+                Command::Weapon(weapon.ok_or_else(|| if word.is_empty() { usage() } else { anyhow!("unknown weapon: {word}") })?)
+            }
+            "yield" => Command::Yield(Yield::parse(&word).ok_or_else(usage)?),
+            "delivery" => Command::Delivery(Delivery::parse(&word).ok_or_else(usage)?),
+            "reticle" => Command::Reticle(Reticle::parse(&word).ok_or_else(usage)?),
+            "mirv" => Command::Mirv(Switch::parse(&word)?),
+            "ai" => Command::Ai(match word.as_str() {
+                "off" | "none" => None,
+                _ => Some(Difficulty::parse(&word).ok_or_else(usage)?),
+            }),
+            "theme" => Command::Theme(Theme::by_name(&word).ok_or_else(|| {
+                if word.is_empty() { usage() } else { anyhow!("unknown theme: {word}") }
+            })?),
+            "projection" => {
+                if word.is_empty() {
+                    return Err(usage());
+                }
+                Command::Projection(unique_prefix(&word, Projection::NAMES, |p| p, "projection")?)
+            }
+            "seed" => Command::Seed(rest.parse().map_err(|_| usage())?),
+            "pause" => Command::Pause(Switch::parse(&word)?),
+            "disasters" => Command::Disasters(Switch::parse(&word)?),
+            "pandemic" => Command::Pandemic(Switch::parse(&word)?),
+            "record" => Command::Record(match rest {
+                "" => None,
+                _ => Some(parse_register(rest).ok_or_else(usage)?),
+            }),
+            "play" => {
+                let (register, times) = rest.split_once(char::is_whitespace).unwrap_or((rest, "1"));
+                let register = parse_register(register.trim_start_matches('@')).or((register == "@@").then_some('@'));
+                match (register, times.trim().parse()) {
+                    (Some(register), Ok(times)) if times > 0 => Command::Play(register, times),
+                    _ => return Err(usage()),
+                }
+            }
+            "data" => Command::Data(match word.split_whitespace().collect::<Vec<_>>()[..] {
+                [] => DataAction::Show,
+                ["download"] => DataAction::Download(Resolution::High),
+                ["download", resolution] => DataAction::Download(Resolution::parse(resolution).ok_or_else(usage)?),
+                ["verify"] => DataAction::Verify,
+                _ => return Err(usage()),
+            }),
+            "overlay" => Command::Overlay(parse_named_switch(&word)?),
+            "heatmap" => Command::Heatmap(parse_named_switch(&word)?),
+            "save" => Command::Save((!rest.is_empty()).then(|| PathBuf::from(rest))),
+            "load" if rest.is_empty() => return Err(usage()),
+            "load" => Command::Load(PathBuf::from(rest)),
+            "export" => Command::Export((!rest.is_empty()).then(|| PathBuf::from(rest))),
+            "memory" => Command::Memory(match word.as_str() {
+                "" => MemoryAction::Report,
+                "off" | "none" => MemoryAction::Budget(None),
+                _ => MemoryAction::Budget(Some(memory::parse_size(&word)?)),
+            }),
+            "screenshot" => Command::Screenshot,
+            "help" => Command::Help,
+            "quit" => Command::Quit,
+            other => bail!("unknown command: {other}"),
+        })
+    }
+}
+
+/// What a `Tab` press leaves on the command line
+#[derive(Debug, PartialEq)]
+pub struct Completion {
+    pub line: String,
+    /// Everything that could come next, when more than one thing could
+    pub candidates: Vec<String>,
+}
+
+/// Complete the last word of `line`: a command name, or an argument of the
+/// command already typed. One match is filled in and followed by a space;
+/// several are filled in as far as they agree.
+pub fn complete(line: &str) -> Completion {
+    let mut words: Vec<&str> = line.split_whitespace().collect();
+    let current = if line.is_empty() || line.ends_with(char::is_whitespace) { "" } else { words.pop().unwrap_or("") };
+    let options: Vec<String> = match words.split_first() {
+        None => names().map(String::from).collect(),
+        Some((name, args)) => match resolve(name) {
+            Ok(name) => arguments(name, args.len()),
+            Err(_) => Vec::new(),
+        },
+    };
+    let lower = current.to_ascii_lowercase();
+    let candidates: Vec<String> = options.into_iter().filter(|o| o.starts_with(&lower)).collect();
+    let word = match &candidates[..] {
+        [] => return Completion { line: line.to_string(), candidates },
+        [only] => format!("{only} "),
+        [first, rest @ ..] => {
+            let common = rest.iter().fold(first.len(), |n, c| {
+                first.bytes().zip(c.bytes()).take(n).take_while(|(a, b)| a == b).count()
+            });
+            first[..common.max(current.len())].to_string()
+        }
+    };
+    let line = words.iter().map(|w| format!("{w} ")).collect::<String>() + &word;
+    let candidates = if candidates.len() > 1 { candidates } else { Vec::new() };
+    Completion { line, candidates }
+}
+
+/// What can go in argument `position` of `command`
+fn arguments(command: &str, position: usize) -> Vec<String> {
+    let words: Vec<&str> = match (command, position) {
+        ("layer", 0) => Layer::ALL.iter().map(|l| l.name()).collect(),
+        ("layer" | "overlay" | "heatmap", 1) | ("mirv" | "pause" | "disasters" | "pandemic", 0) => SWITCHES.to_vec(),
+        ("weapon", 0) => return WeaponType::ALL.iter().map(|w| w.label().to_ascii_lowercase()).collect(),
+        ("yield", 0) => vec!["15kt", "300kt", "1mt", "50mt"],
+        ("delivery", 0) => vec!["icbm", "slbm", "bomber"],
+        ("reticle", 0) => Reticle::ALL.iter().map(|r| r.label()).collect(),
+        ("ai", 0) => vec!["off", "easy", "normal", "hard"],
+        ("theme", 0) => THEMES.iter().map(|t| t.name).collect(),
+        ("data", 0) => vec!["download", "verify"],
+        ("memory", 0) => vec!["off"],
+        ("data", 1) => Resolution::ALL.iter().map(|r| r.label()).collect(),
+        ("projection", 0) => return Projection::NAMES.iter().map(|p| p.to_ascii_lowercase()).collect(),
+        _ => Vec::new(),
+    };
+    words.into_iter().map(String::from).collect()
+}
+
+/// Earlier commands kept
+const HISTORY_LEN: usize = 100;
+
+/// The line being typed, with the commands run before it
+#[derive(Clone, Debug, Default)]
+pub struct CommandLine {
+    /// What's typed, while the line is open
+    pub text: Option<String>,
+    /// Choices from the last `Tab` that matched more than one
+    pub candidates: Vec<String>,
+    history: Vec<String>,
+    /// Position while stepping through the history, and the line that was
+    /// being typed before
+    browsing: Option<(usize, String)>,
+}
+
+impl CommandLine {
+    pub fn is_open(&self) -> bool {
+        self.text.is_some()
+    }
+
+    pub fn open(&mut self) {
+        self.text = Some(String::new());
+        self.candidates.clear();
+        self.browsing = None;
+    }
+
+    pub fn close(&mut self) {
+        self.text = None;
+        self.candidates.clear();
+        self.browsing = None;
+    }
+
+    pub fn push(&mut self, c: char) {
+        if let Some(text) = self.text.as_mut() {
+            text.push(c);
+            self.candidates.clear();
+        }
+    }
+
+    /// Delete a character; on an empty line, close it as vim does
+    pub fn backspace(&mut self) {
+        match self.text.as_mut() {
+            Some(text) if text.is_empty() => self.close(),
+            Some(text) => {
+                text.pop();
+                self.candidates.clear();
+            }
+            None => {}
+        }
+    }
+
+    pub fn complete(&mut self) {
+        if let Some(text) = self.text.as_mut() {
+            let completion = complete(text);
+            *text = completion.line;
+            self.candidates = completion.candidates;
+        }
+    }
+
+    /// Step back to an earlier command
+    pub fn previous(&mut self) {
+        let Some(text) = self.text.as_mut() else { return };
+        let i = match &self.browsing {
+            Some((i, _)) => i.saturating_sub(1),
+            None if self.history.is_empty() => return,
+            None => {
+                self.browsing = Some((self.history.len(), text.clone()));
+                self.history.len() - 1
+            }
+        };
+        if let Some((at, _)) = self.browsing.as_mut() {
+            *at = i;
+        }
+        *text = self.history[i].clone();
+        self.candidates.clear();
+    }
+
+    /// Step forward again, back to the line being typed at the end
+    pub fn next(&mut self) {
+        let (Some(text), Some((i, draft))) = (self.text.as_mut(), self.browsing.as_mut()) else { return };
+        *i += 1;
+        match self.history.get(*i) {
+            Some(line) => *text = line.clone(),
+            None => {
+                *text = std::mem::take(draft);
+                self.browsing = None;
+            }
+        }
+        self.candidates.clear();
+    }
+
+    /// Close the line, returning what was typed and remembering it
+    pub fn submit(&mut self) -> Option<String> {
+        let line = self.text.take()?;
+        self.close();
+        let line = line.trim().to_string();
+        if line.is_empty() {
+            return None;
+        }
+        if self.history.last() != Some(&line) {
+            self.history.push(line.clone());
+            if self.history.len() > HISTORY_LEN {
+                self.history.remove(0);
+            }
+        }
+        Some(line)
+    }
+}
+
+/// A single letter or digit naming a macro register
+fn parse_register(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if Macros::is_register(c) => Some(c),
+        _ => None,
+    }
+}
+
+/// `<name> [on|off]` for `:overlay` and `:heatmap`, or `None` for nothing
+/// at all
+fn parse_named_switch(word: &str) -> Result<Option<(String, Switch)>> {
+    Ok(match word.split_once(char::is_whitespace) {
+        _ if word.is_empty() => None,
+        Some((name, switch)) => Some((name.to_string(), Switch::parse(switch.trim())?)),
+        None => Some((word.to_string(), Switch::Toggle)),
+    })
+}
+
+/// `lat,lon` or `lat lon` in decimal degrees, returned as (lon, lat).
+/// With hemisphere letters (`33.87S 151.21E`) the order can be either
+/// way round. `None` if the text isn't coordinates at all; an error if it
+/// is but they're out of range.
+fn parse_coords(s: &str) -> Result<Option<(f64, f64)>> {
+    let parts: Vec<&str> = s.split([',', ' ']).map(str::trim).filter(|p| !p.is_empty()).collect();
+    let [a, b] = parts[..] else { return Ok(None) };
+    let (Some(a), Some(b)) = (parse_degrees(a), parse_degrees(b)) else { return Ok(None) };
+    let (lat, lon) = match (a, b) {
+        ((lon, Some(Axis::Lon)), (lat, _)) | ((lat, _), (lon, Some(Axis::Lon))) => (lat, lon),
+        ((lon, _), (lat, Some(Axis::Lat))) => (lat, lon),
+        ((lat, _), (lon, _)) => (lat, lon),
+    };
+    if !(-90.0..=90.0).contains(&lat) {
+        bail!("latitude out of range: {lat}");
+    }
+    if !(-180.0..=180.0).contains(&lon) {
+        bail!("longitude out of range: {lon}");
+    }
+    Ok(Some((lon, lat)))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Axis {
+    Lat,
+    Lon,
+}
+
+/// A signed decimal degree, with the axis its hemisphere letter implies
+fn parse_degrees(s: &str) -> Option<(f64, Option<Axis>)> {
+    let s = s.trim_end_matches('°');
+    let (number, sign, axis) = match s.chars().last()?.to_ascii_uppercase() {
+        'N' => (&s[..s.len() - 1], 1.0, Some(Axis::Lat)),
+        'S' => (&s[..s.len() - 1], -1.0, Some(Axis::Lat)),
+        'E' => (&s[..s.len() - 1], 1.0, Some(Axis::Lon)),
+        'W' => (&s[..s.len() - 1], -1.0, Some(Axis::Lon)),
+        _ => (s, 1.0, None),
+    };
+    let value: f64 = number.trim_end_matches('°').parse().ok()?;
+    if !value.is_finite() || (axis.is_some() && value < 0.0) {
+        return None;
+    }
+    Some((value * sign, axis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goto(line: &str) -> GotoTarget {
+        match Command::parse(line).unwrap() {
+            Command::Goto(target) => target,
+            other => panic!("{other:?}"),
+        }
+    }
+
+    #[test]
+    fn goto_takes_coordinates_or_names() {
+        assert_eq!(goto(":goto 35.68,139.76"), GotoTarget::Coords { lon: 139.76, lat: 35.68 });
+        assert_eq!(goto("goto -33.87 151.21"), GotoTarget::Coords { lon: 151.21, lat: -33.87 });
+        assert_eq!(goto("g 151.21E, 33.87S"), GotoTarget::Coords { lon: 151.21, lat: -33.87 });
+        assert_eq!(goto("goto 40.7°N 74.0°W"), GotoTarget::Coords { lon: -74.0, lat: 40.7 });
+        assert_eq!(goto(":goto tokyo"), GotoTarget::Place("tokyo".to_string()));
+        assert_eq!(goto("goto  New York "), GotoTarget::Place("New York".to_string()));
+
+        assert!(Command::parse("goto 95,10").is_err());
+        assert!(Command::parse("goto").is_err());
+        assert!(Command::parse("launch everything").is_err());
+    }
+
+    #[test]
+    fn settings_parse_by_name_or_prefix() {
+        assert_eq!(Command::parse(":layer counties off").unwrap(), Command::Layer(Layer::Counties, Switch::Off));
+        assert_eq!(Command::parse("lay count").unwrap(), Command::Layer(Layer::Counties, Switch::Toggle));
+        assert_eq!(Command::parse("weapon emp").unwrap(), Command::Weapon(WeaponType::Emp));
+        assert_eq!(Command::parse("weapon 7").unwrap(), Command::Weapon(WeaponType::Asteroid));
+        assert_eq!(Command::parse("th NORAD").unwrap(), Command::Theme(Theme::by_name("norad").unwrap()));
+        assert_eq!(Command::parse("seed 42").unwrap(), Command::Seed(42));
+        assert_eq!(Command::parse("proj robin").unwrap(), Command::Projection("Robinson"));
+        assert_eq!(Command::parse("ai off").unwrap(), Command::Ai(None));
+        assert_eq!(Command::parse("dis off").unwrap(), Command::Disasters(Switch::Off));
+        assert_eq!(Command::parse("pan").unwrap(), Command::Pandemic(Switch::Toggle));
+        assert_eq!(Command::parse("w game1").unwrap(), Command::Save(Some(PathBuf::from("game1"))));
+        assert_eq!(Command::parse("load game1.sav").unwrap(), Command::Load(PathBuf::from("game1.sav")));
+        assert_eq!(Command::parse("ex").unwrap(), Command::Export(None));
+        assert!(Command::parse("load").is_err());
+        assert!(Command::parse("proj moon").is_err());
+        assert!(Command::parse("proj").is_err());
+
+        assert!(Command::parse("p").unwrap_err().to_string().contains("ambiguous"));
+        assert!(Command::parse("layer moon").is_err());
+        assert!(Command::parse("weapon 8").is_err());
+        assert!(Command::parse("seed forty").is_err());
+
+        assert_eq!(Command::parse("record t").unwrap(), Command::Record(Some('t')));
+        assert_eq!(Command::parse("record").unwrap(), Command::Record(None));
+        assert_eq!(Command::parse("play @t 3").unwrap(), Command::Play('t', 3));
+        assert_eq!(Command::parse("play @@").unwrap(), Command::Play('@', 1));
+        assert!(Command::parse("play tt").is_err());
+        assert_eq!(Command::parse("data download 50m").unwrap(), Command::Data(DataAction::Download(Resolution::Medium)));
+        assert_eq!(Command::parse("overlay Graticule off").unwrap(), Command::Overlay(Some(("graticule".to_string(), Switch::Off))));
+        assert!(Command::parse("data download 5m").is_err());
+        assert_eq!(Command::parse("heatmap sst").unwrap(), Command::Heatmap(Some(("sst".to_string(), Switch::Toggle))));
+        assert_eq!(Command::parse("heatmap").unwrap(), Command::Heatmap(None));
+        assert_eq!(Command::parse("mem").unwrap(), Command::Memory(MemoryAction::Report));
+        assert_eq!(Command::parse("memory 2G").unwrap(), Command::Memory(MemoryAction::Budget(Some(2 << 30))));
+        assert_eq!(Command::parse("memory off").unwrap(), Command::Memory(MemoryAction::Budget(None)));
+        assert!(Command::parse("memory lots").is_err());
+    }
+
+    #[test]
+    fn tab_completes_commands_then_their_arguments() {
+        assert_eq!(complete("lay").line, "layer ");
+        assert_eq!(complete("layer cou").line, "layer counties ");
+        assert_eq!(complete("layer counties o").candidates, vec!["on", "off"]);
+        assert_eq!(complete("weapon A").line, "weapon a");
+        assert_eq!(complete("weapon A").candidates, vec!["airburst", "asteroid"]);
+        assert_eq!(complete("s").candidates, vec!["seed", "save", "screenshot"]);
+        assert_eq!(complete("e").line, "export ");
+        assert_eq!(complete("rec").line, "record ");
+        assert_eq!(complete("goto par"), Completion { line: "goto par".to_string(), candidates: Vec::new() });
+
+        let mut line = CommandLine::default();
+        for command in ["seed 1", "seed 2"] {
+            line.open();
+            command.chars().for_each(|c| line.push(c));
+            line.submit();
+        }
+        line.open();
+        line.push('x');
+        line.previous();
+        line.previous();
+        assert_eq!(line.text.as_deref(), Some("seed 1"));
+        line.next();
+        line.next();
+        assert_eq!(line.text.as_deref(), Some("x"));
+    }
+}
@@ -0,0 +1,316 @@
+//! Remote control over a Unix socket, so scripts and demos can drive the
+//! map while someone watches.
+//!
+//! Each line a client sends is one JSON command, and each gets one JSON line
+//! back, `{"ok":true,"message":...}` or `{"ok":false,"error":...}`:
+//!
+//! ```text
+//! {"cmd":"fly_to","lon":139.7,"lat":35.7,"zoom":8}
+//! {"cmd":"launch","lon":139.7,"lat":35.7,"weapon":"nuke"}
+//! {"cmd":"toggle_layer","layer":"borders","on":false}
+//! {"cmd":"screenshot","path":"tokyo.png"}
+//! {"cmd":"command","line":"theme norad"}
+//! {"cmd":"status"}
+//! ```
+//!
+//! `command` takes the `:` commands that change the view and the game, not
+//! the ones that quit, read or write files anywhere, or replay input.
+//! `screenshot` writes only to a bare file name, in the working directory.
+//!
+//! Connections are read on their own threads, a line of up to `MAX_LINE`
+//! bytes at a time. The commands are carried out on the main loop between
+//! frames, like keys, and the reply goes back once they have been. Only
+//! the user who started the map can connect: the socket is set to mode
+//! 0600 as soon as it's bound, before a connection is accepted.
+
+use crate::app::{App, WeaponType};
+use crate::command::Command;
+use anyhow::{anyhow, bail, Result};
+use geojson::JsonValue;
+use simd_json::json;
+use simd_json::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, Sender};
+
+/// A command line from a client, and where its reply goes
+type Request = (String, Sender<String>);
+
+/// Longest command line read; a client sending more is cut off
+const MAX_LINE: u64 = 64 * 1024;
+
+pub struct ControlSocket {
+    requests: Receiver<Request>,
+    path: PathBuf,
+}
+
+impl ControlSocket {
+    /// Listen at `path`. A socket left there by a run that didn't shut down
+    /// cleanly is replaced; one still being listened on, or anything else
+    /// there, is an error.
+    #[cfg(unix)]
+    pub fn bind(path: &Path) -> Result<Self> {
+        use anyhow::Context;
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+        use std::os::unix::net::{UnixListener, UnixStream};
+        use std::sync::mpsc;
+        use std::thread;
+
+        if std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+            if UnixStream::connect(path).is_ok() {
+                bail!("{} is in use by another map", path.display());
+            }
+            std::fs::remove_file(path).with_context(|| format!("removing stale socket {}", path.display()))?;
+        }
+        let listener = UnixListener::bind(path).with_context(|| format!("listening on {}", path.display()))?;
+        // Narrowed before any connection is accepted
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("restricting {}", path.display()))?;
+        let (tx, requests) = mpsc::channel::<Request>();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let tx = tx.clone();
+                thread::spawn(move || {
+                    let Ok(mut writer) = stream.try_clone() else { return };
+                    let mut reader = BufReader::new(stream);
+                    loop {
+                        let mut line = Vec::new();
+                        match (&mut reader).take(MAX_LINE + 1).read_until(b'\n', &mut line) {
+                            Ok(0) | Err(_) => return,
+                            Ok(_) if !line.ends_with(b"\n") && line.len() as u64 > MAX_LINE => {
+                                let error = format!("line longer than {MAX_LINE} bytes");
+                                let _ = writeln!(writer, "{}", json!({ "ok": false, "error": error }).encode());
+                                return;
+                            }
+                            Ok(_) => {}
+                        }
+                        let Ok(line) = String::from_utf8(line) else { return };
+                        if line.trim().is_empty() {
+                            continue;
+                        }
+                        let (reply_tx, reply) = mpsc::channel();
+                        if tx.send((line.trim_end().to_string(), reply_tx)).is_err() {
+                            return;
+                        }
+                        let Ok(reply) = reply.recv() else { return };
+                        if writeln!(writer, "{reply}").is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+        Ok(Self { requests, path: path.to_path_buf() })
+    }
+
+    #[cfg(not(unix))]
+    pub fn bind(path: &Path) -> Result<Self> {
+        bail!("control sockets need a Unix system: {}", path.display())
+    }
+
+    /// Carry out every command that has come in since the last call, and
+    /// answer each
+    pub fn serve(app: &mut App) {
+        let Some(socket) = app.control.as_ref() else { return };
+        let pending: Vec<Request> = socket.requests.try_iter().collect();
+        for (line, reply) in pending {
+            let _ = reply.send(handle(app, &line));
+        }
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Carry out one command line and give the reply to send back
+pub fn handle(app: &mut App, line: &str) -> String {
+    let reply = match dispatch(app, line) {
+        Ok(message) => json!({ "ok": true, "message": message }),
+        Err(e) => json!({ "ok": false, "error": format!("{e:#}") }),
+    };
+    reply.encode()
+}
+
+fn dispatch(app: &mut App, line: &str) -> Result<String> {
+    let mut bytes = line.as_bytes().to_vec();
+    let request: JsonValue = simd_json::serde::from_slice(&mut bytes).map_err(|e| anyhow!("not JSON: {e}"))?;
+    let cmd = request.get("cmd").and_then(JsonValue::as_str).ok_or_else(|| anyhow!("missing \"cmd\""))?;
+    let number = |key: &str| request.get(key).and_then(JsonValue::as_f64);
+    let text = |key: &str| request.get(key).and_then(JsonValue::as_str);
+    let position = || -> Result<(f64, f64)> {
+        let (Some(lon), Some(lat)) = (number("lon"), number("lat")) else { bail!("{cmd} needs \"lon\" and \"lat\"") };
+        if !(-90.0..=90.0).contains(&lat) || !lon.is_finite() {
+            bail!("no such place: {lon}, {lat}");
+        }
+        Ok((crate::geo::wrap_lon(lon), lat))
+    };
+    match cmd {
+        "fly_to" => {
+            let (lon, lat) = position()?;
+            let zoom = number("zoom").unwrap_or_else(|| app.projection.effective_zoom());
+            app.start_fly_to(lon, lat, zoom);
+            Ok(format!("flying to {lat:.2}, {lon:.2}"))
+        }
+        "launch" => {
+            let (lon, lat) = position()?;
+            let weapon = match text("weapon") {
+                Some(name) => WeaponType::parse(name).ok_or_else(|| anyhow!("unknown weapon: {name}"))?,
+                None => app.active_weapon,
+            };
+            if !app.launch_at(lon, lat, weapon, app.blast_radius_km(weapon), app.yield_kt(weapon)) {
+                bail!("{}", app.status_message.clone().unwrap_or_else(|| "out of range".to_string()));
+            }
+            Ok(format!("{} launched at {lat:.2}, {lon:.2}", weapon.label()))
+        }
+        "toggle_layer" => {
+            let layer = text("layer").ok_or_else(|| anyhow!("toggle_layer needs \"layer\""))?;
+            let switch = match request.get("on").and_then(JsonValue::as_bool) {
+                Some(true) => "on",
+                Some(false) => "off",
+                None => "toggle",
+            };
+            run(app, &format!("layer {layer} {switch}"))
+        }
+        "screenshot" => match text("path") {
+            Some(path) if Path::new(path).file_name().is_none_or(|name| name != path) => {
+                bail!("screenshot path must be a file name, without a directory: {path}")
+            }
+            Some(path) => {
+                let (width, height) = app.screenshot_size;
+                app.screenshot(Path::new(path), width, height)?;
+                Ok(format!("saved {width}×{height} screenshot to {path}"))
+            }
+            None => run(app, "screenshot"),
+        },
+        "command" => run(app, text("line").ok_or_else(|| anyhow!("command needs \"line\""))?),
+        "status" => Ok(format!(
+            "{} at {} {}, {} casualties",
+            app.projection.name(),
+            app.center_coords(),
+            app.zoom_level(),
+            app.casualties
+        )),
+        _ => bail!("unknown cmd: {cmd} (fly_to, launch, toggle_layer, screenshot, command or status)"),
+    }
+}
+
+/// Whether a client may run a `:` command: not one that quits, reads or
+/// writes a file it names, downloads data, or replays input
+fn allowed(command: &Command) -> bool {
+    !matches!(
+        command,
+        Command::Quit
+            | Command::Load(_)
+            | Command::Save(Some(_))
+            | Command::Export(Some(_))
+            | Command::Data(_)
+            | Command::Record(_)
+            | Command::Play(..)
+    )
+}
+
+/// Run a `:` command line, answering with what it put in the status bar
+fn run(app: &mut App, line: &str) -> Result<String> {
+    let command = Command::parse(line)?;
+    if !allowed(&command) {
+        bail!("not allowed over the control socket: {}", line.trim());
+    }
+    app.status_message = None;
+    app.execute(command);
+    Ok(app.status_message.clone().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_drive_the_app_and_answer_in_json() {
+        let mut app = App::new(80, 24);
+        let ok = |reply: String| {
+            let mut bytes = reply.into_bytes();
+            let value: JsonValue = simd_json::serde::from_slice(&mut bytes).unwrap();
+            assert_eq!(value.get("ok").and_then(JsonValue::as_bool), Some(true), "{value:?}");
+            value.get("message").and_then(JsonValue::as_str).unwrap().to_string()
+        };
+
+        ok(handle(&mut app, r#"{"cmd":"fly_to","lon":139.7,"lat":35.7,"zoom":8}"#));
+        assert!(app.fly_to.is_some());
+
+        let shown = app.map_renderer.settings.show_borders;
+        let message = ok(handle(&mut app, r#"{"cmd":"toggle_layer","layer":"borders"}"#));
+        assert_eq!(app.map_renderer.settings.show_borders, !shown);
+        assert!(message.starts_with("Layer borders"), "{message}");
+        ok(handle(&mut app, r#"{"cmd":"toggle_layer","layer":"borders","on":true}"#));
+        assert!(app.map_renderer.settings.show_borders);
+
+        let message = ok(handle(&mut app, r#"{"cmd":"launch","lon":2.35,"lat":48.85,"weapon":"nuke"}"#));
+        assert!(message.starts_with("NUKE launched"), "{message}");
+        assert_eq!(app.projectiles.len(), 1);
+
+        ok(handle(&mut app, r#"{"cmd":"command","line":"theme norad"}"#));
+        assert_eq!(app.theme.name, "norad");
+
+        for bad in [
+            "not json",
+            r#"{"lon":1}"#,
+            r#"{"cmd":"launch","lon":1}"#,
+            r#"{"cmd":"dance"}"#,
+            r#"{"cmd":"command","line":"frobnicate"}"#,
+            r#"{"cmd":"command","line":"quit"}"#,
+            r#"{"cmd":"command","line":"save /etc/passwd"}"#,
+            r#"{"cmd":"command","line":"load world.sav"}"#,
+            r#"{"cmd":"screenshot","path":"/tmp/shot.png"}"#,
+            r#"{"cmd":"screenshot","path":"../shot.png"}"#,
+        ] {
+            assert!(handle(&mut app, bad).starts_with(r#"{"ok":false,"error":"#), "{bad}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn clients_are_answered_once_the_main_loop_serves_them() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixStream;
+
+        let path = std::env::temp_dir().join(format!("tui-map-control-{}.sock", std::process::id()));
+        let mut app = App::new(80, 24);
+        app.control = Some(ControlSocket::bind(&path).unwrap());
+        let mut client = UnixStream::connect(&path).unwrap();
+        writeln!(client, r#"{{"cmd":"status"}}"#).unwrap();
+        let mut replies = BufReader::new(client.try_clone().unwrap());
+
+        // Nothing happens until the main loop gets to it
+        let mut line = String::new();
+        for _ in 0..200 {
+            ControlSocket::serve(&mut app);
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            client.set_nonblocking(true).unwrap();
+            let read = replies.read_line(&mut line);
+            client.set_nonblocking(false).unwrap();
+            if read.is_ok_and(|n| n > 0) {
+                break;
+            }
+        }
+        assert!(line.starts_with(r#"{"ok":true,"message":""#), "{line}");
+        assert!(line.contains("casualties"), "{line}");
+
+        // Only its owner may connect, and a second map can't take it over
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert!(ControlSocket::bind(&path).is_err());
+
+        // An endless line is cut off
+        let mut flood = UnixStream::connect(&path).unwrap();
+        let _ = flood.write_all(&vec![b'x'; MAX_LINE as usize + 2]);
+        let mut reply = String::new();
+        let _ = BufReader::new(flood).read_line(&mut reply);
+        assert!(reply.starts_with(r#"{"ok":false,"error":"line longer"#), "{reply}");
+
+        app.control = None;
+        assert!(!path.exists(), "socket removed");
+    }
+}
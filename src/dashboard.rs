@@ -0,0 +1,98 @@
+//! Time series behind the stats dashboard.
+//!
+//! Each series is a ring buffer sampled once per simulation step, so the
+//! charts always cover the most recent minute of play.
+
+use std::collections::VecDeque;
+
+pub struct TimeSeries {
+    samples: VecDeque<f64>,
+}
+
+impl TimeSeries {
+    /// A minute of steps at 60fps
+    pub const CAPACITY: usize = 3600;
+
+    pub fn new() -> Self {
+        Self { samples: VecDeque::with_capacity(Self::CAPACITY) }
+    }
+
+    pub fn push(&mut self, value: f64) {
+        if self.samples.len() == Self::CAPACITY {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    pub fn latest(&self) -> f64 {
+        self.samples.back().copied().unwrap_or(0.0)
+    }
+
+    pub fn max(&self) -> f64 {
+        self.samples.iter().copied().fold(0.0, f64::max)
+    }
+
+    /// Squeeze the samples into `width` points, keeping each bucket's peak
+    /// so short spikes still show
+    pub fn resampled(&self, width: usize) -> Vec<f64> {
+        let n = self.samples.len();
+        if n <= width || width == 0 {
+            return self.samples.iter().copied().collect();
+        }
+        (0..width)
+            .map(|i| {
+                let (start, end) = (i * n / width, ((i + 1) * n / width).max(i * n / width + 1));
+                self.samples.range(start..end).copied().fold(0.0, f64::max)
+            })
+            .collect()
+    }
+}
+
+impl Default for TimeSeries {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Everything the dashboard charts
+#[derive(Default)]
+pub struct Dashboard {
+    pub casualties: TimeSeries,
+    /// 0.25° cells on fire
+    pub burning_cells: TimeSeries,
+    /// Ground carrying any radiation dose (km²)
+    pub fallout_km2: TimeSeries,
+}
+
+impl Dashboard {
+    pub fn record(&mut self, casualties: u64, burning_cells: usize, fallout_km2: f64) {
+        self.casualties.push(casualties as f64);
+        self.burning_cells.push(burning_cells as f64);
+        self.fallout_km2.push(fallout_km2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_buffer_keeps_the_latest_minute() {
+        let mut series = TimeSeries::new();
+        for i in 0..TimeSeries::CAPACITY + 10 {
+            series.push(i as f64);
+        }
+        assert_eq!(series.latest(), (TimeSeries::CAPACITY + 9) as f64);
+        assert_eq!(series.resampled(TimeSeries::CAPACITY * 2).len(), TimeSeries::CAPACITY);
+        assert_eq!(series.resampled(TimeSeries::CAPACITY * 2)[0], 10.0);
+
+        let mut spiky = TimeSeries::new();
+        for i in 0..100 {
+            spiky.push(if i == 37 { 50.0 } else { 1.0 });
+        }
+        let points = spiky.resampled(10);
+        assert_eq!(points.len(), 10);
+        assert_eq!(points[3], 50.0);
+        assert_eq!(spiky.max(), 50.0);
+    }
+}
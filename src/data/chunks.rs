@@ -0,0 +1,416 @@
+//! County lines cut into lon/lat chunks on disk, streamed in as the view
+//! moves.
+//!
+//! Admin-2 files are big, and only a sliver of them is ever on screen:
+//! counties appear when zoomed in close. So once loaded they're cut into
+//! `CHUNK_DEG`-square chunks under
+//! `chunks/counties/` in the data directory, with a manifest that records
+//! which source files the cut came from. Each line is written as its
+//! packed points (`map::packed`), so it goes to disk and back without
+//! re-encoding, into every chunk its bounding box overlaps, so a long line
+//! reaching into view from a chunk that isn't loaded still shows. Each
+//! chunk file is zstd-compressed whole. Later
+//! runs skip the GeoJSON and
+//! `ChunkManager` reads just the chunks around the view on a worker
+//! thread, keeping at most `CAPACITY` of them in memory. The lines live
+//! in the renderer; the manager only tracks which chunk each run of them
+//! came from, so nothing is held twice but a line crossing into another
+//! loaded chunk.
+
+use crate::map::packed::PackedCoords;
+use crate::map::LineString;
+use anyhow::{bail, Context, Result};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+/// Chunk size in degrees: 72 columns by 36 rows
+pub const CHUNK_DEG: f64 = 5.0;
+const COLS: i64 = (360.0 / CHUNK_DEG) as i64;
+const ROWS: i64 = (180.0 / CHUNK_DEG) as i64;
+/// Chunks kept loaded; enough for a screenful and the ring around it
+pub const CAPACITY: usize = 64;
+/// A view wanting more chunks than this is too far out for counties
+const MAX_WANTED: usize = 48;
+
+const MANIFEST: &str = "manifest";
+/// Written ahead of the source stamp, so a cut in an older layout is made
+/// again
+const FORMAT: &str = "v4";
+/// zstd level chunks are written at: cut once, read back often
+const ZSTD_LEVEL: i32 = 9;
+
+/// A chunk's column (from 180°W) and row (from 90°S)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChunkId {
+    pub col: u16,
+    pub row: u16,
+}
+
+impl ChunkId {
+    /// The chunk holding a point. Longitudes outside ±180 wrap.
+    pub fn containing(lon: f64, lat: f64) -> Self {
+        let col = ((lon + 180.0) / CHUNK_DEG).floor() as i64;
+        let row = ((lat + 90.0) / CHUNK_DEG).floor() as i64;
+        Self { col: col.rem_euclid(COLS) as u16, row: row.clamp(0, ROWS - 1) as u16 }
+    }
+
+    fn file(self, dir: &Path) -> PathBuf {
+        dir.join(format!("{}_{}.bin", self.col, self.row))
+    }
+}
+
+/// Chunks covering a bounding box, one chunk wider all round so panning
+/// finds its neighbours already loaded. Empty when the box needs more than
+/// `MAX_WANTED`.
+pub fn chunks_for_bounds(min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64) -> Vec<ChunkId> {
+    let around = chunks_overlapping(min_lon - CHUNK_DEG, min_lat - CHUNK_DEG, max_lon + CHUNK_DEG, max_lat + CHUNK_DEG);
+    if around.len() > MAX_WANTED {
+        return Vec::new();
+    }
+    around
+}
+
+/// Chunks a bounding box overlaps, each once however far it wraps
+fn chunks_overlapping(min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64) -> Vec<ChunkId> {
+    let col0 = ((min_lon + 180.0) / CHUNK_DEG).floor() as i64;
+    let col1 = (((max_lon + 180.0) / CHUNK_DEG).floor() as i64).min(col0 + COLS - 1);
+    let row0 = (((min_lat + 90.0) / CHUNK_DEG).floor() as i64).max(0);
+    let row1 = (((max_lat + 90.0) / CHUNK_DEG).floor() as i64).min(ROWS - 1);
+    (row0..=row1)
+        .flat_map(|row| (col0..=col1).map(move |col| ChunkId { col: col.rem_euclid(COLS) as u16, row: row as u16 }))
+        .collect()
+}
+
+/// Whether `dir` holds a complete cut made from sources matching `stamp`
+pub fn is_current(dir: &Path, stamp: &str) -> bool {
+    fs::read_to_string(dir.join(MANIFEST)).is_ok_and(|m| m.lines().next() == Some(format!("{FORMAT} {stamp}").as_str()))
+}
+
+/// Cut `lines` into chunks under `dir`, replacing any earlier cut. Each
+/// line goes to every chunk its bounding box overlaps. The manifest is
+/// written last, so a cut that's interrupted isn't trusted. Returns how
+/// many chunks were written.
+pub fn partition(lines: &[LineString], dir: &Path, stamp: &str) -> Result<usize> {
+    let mut chunks: HashMap<ChunkId, Vec<u8>> = HashMap::new();
+    for line in lines {
+        let (min_lon, min_lat, max_lon, max_lat) = line.bbox;
+        let packed = line.coords.as_bytes();
+        for id in chunks_overlapping(min_lon, min_lat, max_lon, max_lat) {
+            let out = chunks.entry(id).or_default();
+            out.extend_from_slice(&(line.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(packed.len() as u32).to_le_bytes());
+            out.extend_from_slice(packed);
+        }
+    }
+
+    if dir.exists() {
+        fs::remove_dir_all(dir).with_context(|| format!("clearing {}", dir.display()))?;
+    }
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    chunks.par_iter().try_for_each(|(id, bytes)| -> Result<()> {
+        let compressed = zstd::encode_all(bytes.as_slice(), ZSTD_LEVEL)?;
+        fs::write(id.file(dir), compressed).with_context(|| format!("writing {}", id.file(dir).display()))
+    })?;
+    let mut manifest = format!("{FORMAT} {stamp}\n");
+    for id in chunks.keys() {
+        manifest.push_str(&format!("{} {}\n", id.col, id.row));
+    }
+    fs::write(dir.join(MANIFEST), manifest)?;
+    Ok(chunks.len())
+}
+
+/// Read a chunk file back into lines, simplified copies and all
+fn read_chunk(path: &Path) -> Result<Vec<LineString>> {
+    let compressed = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let bytes = zstd::decode_all(compressed.as_slice()).with_context(|| format!("decompressing {}", path.display()))?;
+    let mut rest = bytes.as_slice();
+    let mut lines = Vec::new();
+    while !rest.is_empty() {
+        let Some((header, tail)) = rest.split_first_chunk::<8>() else { bail!("{}: truncated", path.display()) };
+        let count = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+        let size = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+        if size > tail.len() {
+            bail!("{}: {size} bytes of points exceed chunk", path.display());
+        }
+        let (packed, tail) = tail.split_at(size);
+        let coords = PackedCoords::from_bytes(packed.to_vec(), count).with_context(|| format!("reading {}", path.display()))?;
+        let mut line = LineString::from_coords(coords);
+        line.build_simplified();
+        lines.push(line);
+        rest = tail;
+    }
+    Ok(lines)
+}
+
+type ChunkResult = (ChunkId, Result<Vec<LineString>>);
+
+/// Owns the chunk-reading worker and the loaded chunks
+pub struct ChunkManager {
+    dir: PathBuf,
+    requests: Sender<ChunkId>,
+    results: Receiver<ChunkResult>,
+    /// Chunks the manifest lists; the rest of the world has no counties
+    available: HashSet<ChunkId>,
+    /// Loaded chunks, with when each was last wanted
+    loaded: HashMap<ChunkId, u64>,
+    /// Chunks read but not yet handed out by `assemble`
+    arrived: Vec<(ChunkId, Vec<LineString>)>,
+    /// The chunks making up the lines last assembled, in order, with how
+    /// many lines each has
+    layout: Vec<(ChunkId, usize)>,
+    pending: HashSet<ChunkId>,
+    /// Chunks that failed to read — not retried for the rest of the session
+    failed: HashSet<ChunkId>,
+    /// Chunks around the current view
+    wanted: Vec<ChunkId>,
+    clock: u64,
+    pub last_error: Option<String>,
+}
+
+impl ChunkManager {
+    /// Stream the cut in `dir`, if its manifest can be read
+    pub fn open(dir: PathBuf) -> Result<Self> {
+        let manifest = fs::read_to_string(dir.join(MANIFEST)).with_context(|| format!("reading {}", dir.join(MANIFEST).display()))?;
+        let available = manifest
+            .lines()
+            .skip(1)
+            .filter_map(|l| {
+                let (col, row) = l.split_once(' ')?;
+                Some(ChunkId { col: col.parse().ok()?, row: row.parse().ok()? })
+            })
+            .collect();
+
+        let (req_tx, req_rx) = mpsc::channel::<ChunkId>();
+        let (res_tx, res_rx) = mpsc::channel::<ChunkResult>();
+        let worker_dir = dir.clone();
+        thread::spawn(move || {
+            for id in req_rx {
+                if res_tx.send((id, read_chunk(&id.file(&worker_dir)))).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            dir,
+            requests: req_tx,
+            results: res_rx,
+            available,
+            loaded: HashMap::new(),
+            arrived: Vec::new(),
+            layout: Vec::new(),
+            pending: HashSet::new(),
+            failed: HashSet::new(),
+            wanted: Vec::new(),
+            clock: 0,
+            last_error: None,
+        })
+    }
+
+    /// Take in finished chunks and ask for the ones around `bounds`, or
+    /// none when counties aren't drawn. Returns true when the lines shown
+    /// should be `assemble`d again.
+    pub fn update(&mut self, bounds: Option<(f64, f64, f64, f64)>) -> bool {
+        self.clock += 1;
+        let mut changed = false;
+        while let Ok((id, result)) = self.results.try_recv() {
+            // Read meanwhile by `load_now`
+            if self.pending.remove(&id) {
+                changed |= self.take_in(id, result);
+            }
+        }
+
+        let wanted: Vec<ChunkId> = bounds
+            .map(|(min_lon, min_lat, max_lon, max_lat)| chunks_for_bounds(min_lon, min_lat, max_lon, max_lat))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|id| self.available.contains(id))
+            .collect();
+        for id in &wanted {
+            if let Some(stamp) = self.loaded.get_mut(id) {
+                *stamp = self.clock;
+            } else if !self.pending.contains(id) && !self.failed.contains(id) {
+                self.pending.insert(*id);
+                let _ = self.requests.send(*id);
+            }
+        }
+        if wanted != self.wanted {
+            self.wanted = wanted;
+            changed = true;
+        }
+
+        // Drop the least recently wanted chunks beyond capacity
+        while self.loaded.len() > CAPACITY {
+            let Some(oldest) = self.loaded.iter().min_by_key(|(_, &s)| s).map(|(id, _)| *id) else { break };
+            self.loaded.remove(&oldest);
+            changed = true;
+        }
+        changed
+    }
+
+    /// `update`, reading the chunks around `bounds` here and now rather
+    /// than on the worker, for a frame that's drawn once and must be whole
+    pub fn load_now(&mut self, bounds: Option<(f64, f64, f64, f64)>) -> bool {
+        let mut changed = self.update(bounds);
+        for id in self.wanted.clone() {
+            if self.pending.remove(&id) {
+                changed |= self.take_in(id, read_chunk(&id.file(&self.dir)));
+            }
+        }
+        changed
+    }
+
+    /// Keep a chunk that was read, or note that it couldn't be. Returns
+    /// true if it was kept.
+    fn take_in(&mut self, id: ChunkId, result: Result<Vec<LineString>>) -> bool {
+        match result {
+            Ok(lines) => {
+                self.loaded.insert(id, self.clock);
+                self.arrived.push((id, lines));
+                true
+            }
+            Err(e) => {
+                self.last_error = Some(format!("{e:#}"));
+                self.failed.insert(id);
+                false
+            }
+        }
+    }
+
+    /// Drop every loaded chunk outside the view, to save memory. Returns
+    /// true if any went, so the lines should be `assemble`d again.
+    pub fn evict_unwanted(&mut self) -> bool {
+        let before = self.loaded.len();
+        self.loaded.retain(|id, _| self.wanted.contains(id));
+        self.loaded.len() != before
+    }
+
+    /// The lines of the loaded chunks, from `current` (what the last call
+    /// returned) less the chunks dropped since, plus those newly read.
+    /// Lines are moved, never copied.
+    pub fn assemble(&mut self, current: Vec<LineString>) -> Vec<LineString> {
+        let mut current = current.into_iter();
+        let mut lines = Vec::new();
+        let mut layout = Vec::new();
+        for (id, count) in std::mem::take(&mut self.layout) {
+            let chunk = current.by_ref().take(count);
+            if self.loaded.contains_key(&id) {
+                lines.extend(chunk);
+                layout.push((id, count));
+            } else {
+                chunk.for_each(drop);
+            }
+        }
+        for (id, chunk) in std::mem::take(&mut self.arrived) {
+            if self.loaded.contains_key(&id) {
+                layout.push((id, chunk.len()));
+                lines.extend(chunk);
+            }
+        }
+        self.layout = layout;
+        lines
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn loaded_count(&self) -> usize {
+        self.loaded.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tui-map-chunks-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn chunks_wrap_and_refuse_wide_views() {
+        assert_eq!(ChunkId::containing(-180.0, -90.0), ChunkId { col: 0, row: 0 });
+        assert_eq!(ChunkId::containing(181.0, 90.0), ChunkId { col: 0, row: 35 });
+        let around = chunks_for_bounds(-2.0, 51.0, 2.0, 53.0);
+        assert_eq!(around.len(), 4 * 3);
+        assert!(around.contains(&ChunkId::containing(-6.0, 46.0)));
+        assert!(chunks_for_bounds(-180.0, -60.0, 180.0, 60.0).is_empty());
+        assert!(chunks_for_bounds(178.0, 0.0, 183.0, 1.0).iter().any(|id| id.col == 0));
+    }
+
+    #[test]
+    fn counties_stream_in_around_the_view_and_out_past_capacity() {
+        let dir = temp_dir("stream");
+        // One little county in each chunk along the equator
+        let lines: Vec<LineString> = (0..COLS)
+            .map(|c| {
+                let lon = -180.0 + (c as f64 + 0.5) * CHUNK_DEG;
+                LineString::new(vec![(lon - 1.0, 0.5), (lon + 1.0, 1.5), (lon, 2.0)])
+            })
+            .collect();
+        assert_eq!(partition(&lines, &dir, "v1").unwrap(), COLS as usize);
+        assert!(is_current(&dir, "v1"));
+        assert!(!is_current(&dir, "v2"));
+
+        let mut chunks = ChunkManager::open(dir.clone()).unwrap();
+        let settle = |chunks: &mut ChunkManager, bounds| {
+            let start = Instant::now();
+            chunks.update(bounds);
+            while chunks.pending_count() > 0 && start.elapsed() < Duration::from_secs(5) {
+                thread::sleep(Duration::from_millis(5));
+                chunks.update(bounds);
+            }
+        };
+        assert!(!chunks.update(None));
+        settle(&mut chunks, Some((1.0, 0.0, 4.0, 2.0)));
+        // The view's chunk and one either side
+        assert_eq!(chunks.loaded_count(), 3);
+        let mut counties = chunks.assemble(Vec::new());
+        assert_eq!(counties.len(), 3);
+        let original: Vec<_> = lines[36].points().collect();
+        assert!(counties.iter().any(|l| l.points().collect::<Vec<_>>() == original));
+
+        // Panning round the world keeps memory bounded
+        for c in 0..COLS {
+            let lon = -180.0 + c as f64 * CHUNK_DEG;
+            settle(&mut chunks, Some((lon, 0.0, lon + 1.0, 1.0)));
+            counties = chunks.assemble(counties);
+        }
+        assert!(chunks.loaded_count() <= CAPACITY);
+        assert_eq!(counties.len(), chunks.loaded_count());
+        assert!(chunks.last_error.is_none());
+
+        // Over a memory budget, only the view's chunks stay
+        assert!(chunks.evict_unwanted());
+        assert_eq!(chunks.assemble(counties).len(), 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn long_lines_are_in_every_chunk_they_cross_and_load_at_once() {
+        let dir = temp_dir("long");
+        // From 2.5°E to 22.5°E: five chunks, the middle one at 10–15°E
+        let long = LineString::new(vec![(2.5, 1.0), (22.5, 2.0)]);
+        assert_eq!(partition(std::slice::from_ref(&long), &dir, "v1").unwrap(), 5);
+        // Written zstd-compressed
+        let written = fs::read(ChunkId::containing(12.5, 1.0).file(&dir)).unwrap();
+        assert_eq!(written[..4], [0x28, 0xB5, 0x2F, 0xFD]);
+
+        // Far enough west that only the line's western end is near the view
+        let mut chunks = ChunkManager::open(dir.clone()).unwrap();
+        assert!(chunks.load_now(Some((-4.0, 0.0, -3.0, 1.0))));
+        assert_eq!((chunks.pending_count(), chunks.loaded_count()), (0, 1));
+        let counties = chunks.assemble(Vec::new());
+        assert_eq!(counties.len(), 1);
+        assert_eq!(counties[0].points().collect::<Vec<_>>(), long.points().collect::<Vec<_>>());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
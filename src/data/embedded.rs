@@ -0,0 +1,219 @@
+//! A low-resolution world compiled into the binary, so the map looks right
+//! with no files at all: the 110m coastlines, country borders thinned from
+//! the 50m set, and the cities of a million people or more. Files in
+//! `data/` are used instead whenever there are any.
+//!
+//! The format keeps it to a few tens of kilobytes. Coordinates are stored
+//! in hundredths of a degree, each point as the zigzag varint difference
+//! from the one before it. Lines carry on from where the previous one
+//! ended, so consecutive lines cost no more than their points do.
+//!
+//! ```text
+//! "TMW1"
+//! coastlines  varint count, then per line: varint points, point deltas
+//! borders     the same
+//! cities      varint count, then per city: point delta, varint
+//!             population, flags (1 capital, 2 megacity), then name and
+//!             country code, each a varint length and UTF-8
+//! ```
+//!
+//! `make embedded-world` rebuilds it from the Natural Earth files in
+//! `data/`.
+
+use super::{extract_cities, parse_geojson, process_geojson_lines, CityData};
+use crate::map::geometry::simplify_douglas_peucker;
+use crate::map::{LineString, Lod, MapRenderer};
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::Path;
+
+static WORLD: &[u8] = include_bytes!("../../assets/world_110m.bin");
+
+const MAGIC: &[u8; 4] = b"TMW1";
+
+/// Stored units per degree
+const SCALE: f64 = 100.0;
+
+/// Cities smaller than this are left out
+const MIN_POPULATION: u64 = 1_000_000;
+
+/// Douglas-Peucker tolerance for thinning the 50m borders, in degrees
+const BORDER_TOLERANCE_DEG: f64 = 0.08;
+
+type Line = Vec<(f64, f64)>;
+
+pub struct EmbeddedWorld {
+    coastlines: Vec<Line>,
+    borders: Vec<Line>,
+    cities: Vec<CityData>,
+}
+
+impl EmbeddedWorld {
+    /// Build it from the Natural Earth files in `data_dir`: the 110m
+    /// coastlines, the 50m borders and the 10m cities
+    pub fn from_natural_earth(data_dir: &Path) -> Result<Self> {
+        let read = |file: &str| {
+            let path = data_dir.join(file);
+            let content = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+            parse_geojson(content).with_context(|| format!("parsing {}", path.display()))
+        };
+        let mut coastlines = Vec::new();
+        process_geojson_lines(&read("ne_110m_coastline.json")?, |line| coastlines.push(line));
+        let mut borders = Vec::new();
+        process_geojson_lines(&read("ne_50m_borders.json")?, |line| {
+            let line = simplify_douglas_peucker(&line, BORDER_TOLERANCE_DEG);
+            if line.len() >= 2 {
+                borders.push(line);
+            }
+        });
+        let mut cities: Vec<CityData> =
+            extract_cities(&read("ne_10m_cities.json")?, None).into_iter().filter(|c| c.population >= MIN_POPULATION).collect();
+        cities.sort_by_key(|c| std::cmp::Reverse(c.population));
+        Ok(Self { coastlines, borders, cities })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        let mut at = (0, 0);
+        for lines in [&self.coastlines, &self.borders] {
+            write_varint(&mut out, lines.len() as u64);
+            for line in lines {
+                write_varint(&mut out, line.len() as u64);
+                for &point in line {
+                    write_point(&mut out, &mut at, point);
+                }
+            }
+        }
+        write_varint(&mut out, self.cities.len() as u64);
+        for city in &self.cities {
+            write_point(&mut out, &mut at, (city.lon, city.lat));
+            write_varint(&mut out, city.population);
+            out.push(city.is_capital as u8 | (city.is_megacity as u8) << 1);
+            for text in [&city.name, &city.country] {
+                write_varint(&mut out, text.len() as u64);
+                out.extend_from_slice(text.as_bytes());
+            }
+        }
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let Some(body) = bytes.strip_prefix(MAGIC) else { bail!("not an embedded world") };
+        let mut reader = Reader { bytes: body, at: (0, 0) };
+        let mut sections = [Vec::new(), Vec::new()];
+        for lines in &mut sections {
+            for _ in 0..reader.varint()? {
+                let n = reader.varint()?;
+                lines.push((0..n).map(|_| reader.point()).collect::<Result<Line>>()?);
+            }
+        }
+        let [coastlines, borders] = sections;
+        let mut cities = Vec::new();
+        for _ in 0..reader.varint()? {
+            let (lon, lat) = reader.point()?;
+            let population = reader.varint()?;
+            let flags = reader.take(1)?[0];
+            let name = reader.text()?;
+            let country = reader.text()?;
+            cities.push(CityData { lon, lat, name, population, is_capital: flags & 1 != 0, is_megacity: flags & 2 != 0, country, english_name: None });
+        }
+        Ok(Self { coastlines, borders, cities })
+    }
+
+    /// Add everything to the renderer: the coastlines at low detail (the
+    /// renderer falls back to them when zoomed in), borders at medium
+    pub fn add_to(self, renderer: &mut MapRenderer) {
+        for line in self.coastlines {
+            renderer.add_coastline(line, Lod::Low);
+        }
+        for line in self.borders {
+            renderer.borders_medium.push(LineString::new(line));
+        }
+        for c in self.cities {
+            renderer.add_city(c.lon, c.lat, &c.name, c.population, c.is_capital, c.is_megacity, &c.country);
+        }
+    }
+}
+
+/// Load the world compiled into the binary
+pub fn load(renderer: &mut MapRenderer) -> Result<()> {
+    EmbeddedWorld::decode(WORLD)?.add_to(renderer);
+    Ok(())
+}
+
+fn write_varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        out.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+fn write_point(out: &mut Vec<u8>, at: &mut (i64, i64), (lon, lat): (f64, f64)) {
+    let next = ((lon * SCALE).round() as i64, (lat * SCALE).round() as i64);
+    for delta in [next.0 - at.0, next.1 - at.1] {
+        write_varint(out, ((delta << 1) ^ (delta >> 63)) as u64);
+    }
+    *at = next;
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    /// The last point read, in stored units
+    at: (i64, i64),
+}
+
+impl Reader<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8]> {
+        if self.bytes.len() < n {
+            bail!("embedded world is truncated");
+        }
+        let (head, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte < 0x80 {
+                return Ok(value);
+            }
+        }
+        bail!("varint too long")
+    }
+
+    fn point(&mut self) -> Result<(f64, f64)> {
+        let mut delta = || self.varint().map(|v| (v >> 1) as i64 ^ -((v & 1) as i64));
+        let (dx, dy) = (delta()?, delta()?);
+        self.at = (self.at.0 + dx, self.at.1 + dy);
+        Ok((self.at.0 as f64 / SCALE, self.at.1 as f64 / SCALE))
+    }
+
+    fn text(&mut self) -> Result<String> {
+        let len = self.varint()? as usize;
+        Ok(String::from_utf8(self.take(len)?.to_vec())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_built_in_world_decodes_to_whole_continents() {
+        let world = EmbeddedWorld::decode(WORLD).unwrap();
+        assert!(world.coastlines.len() > 100, "{}", world.coastlines.len());
+        assert!(world.borders.len() > 100, "{}", world.borders.len());
+        assert!(world.cities.iter().any(|c| c.name == "Tokyo" && c.country == "JPN" && c.is_capital));
+        assert!(world.cities.iter().all(|c| c.population >= MIN_POPULATION));
+        let points = world.coastlines.iter().flatten();
+        assert!(points.clone().all(|&(lon, lat)| (-180.0..=180.0).contains(&lon) && (-90.0..=90.0).contains(&lat)));
+
+        // Re-encoding gives back the same bytes
+        assert_eq!(world.encode(), WORLD);
+        assert!(EmbeddedWorld::decode(&WORLD[..WORLD.len() / 2]).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! World state as GeoJSON, for inspecting a session in QGIS or on the web.
+//!
+//! Everything is a feature in one FeatureCollection with a `kind` property:
+//! `fire` and `city` points, `fallout` and `gas` plumes (each puff a circle
+//! polygon), and `burned` and `radiation` areas built from the 0.25° grids.
+//! Grid cells are merged into one rectangle per run along a row, which keeps
+//! large contaminated areas to a manageable number of polygons.
+
+use crate::app::{App, BurnScarGrid};
+use crate::geo::{destination_point, wrap_lon};
+use crate::sim::FalloutGrid;
+use anyhow::{Context, Result};
+use geojson::{Feature, FeatureCollection, Geometry, JsonValue, Value};
+use std::path::Path;
+
+/// Vertices around each circle
+const CIRCLE_SEGMENTS: usize = 32;
+
+/// Radiation dose bands (Gy): light contamination, radiation sickness, lethal
+const DOSE_BANDS: [(f32, Option<f32>); 3] = [(0.01, Some(1.0)), (1.0, Some(FalloutGrid::LETHAL_GY)), (FalloutGrid::LETHAL_GY, None)];
+
+/// Closed, counter-clockwise ring approximating a circle of `radius_km`
+/// on the ground. Longitudes are left unwrapped, so a circle straddling the
+/// antimeridian runs past ±180 rather than splitting in two.
+pub fn circle(lon: f64, lat: f64, radius_km: f64) -> Vec<Vec<f64>> {
+    (0..=CIRCLE_SEGMENTS)
+        .map(|i| {
+            // Bearings run anticlockwise: north, west, south, east
+            let bearing = 360.0 * (1.0 - (i % CIRCLE_SEGMENTS) as f64 / CIRCLE_SEGMENTS as f64);
+            let (x, y) = destination_point(lon, lat, bearing, radius_km);
+            vec![lon + wrap_lon(x - lon), y]
+        })
+        .collect()
+}
+
+/// Polygons covering grid cells given as (row, col), row 0 at the south
+/// pole and col 0 at -180°. Adjacent cells in a row share one rectangle.
+pub fn grid_multipolygon(cells: impl IntoIterator<Item = (usize, usize)>, resolution: f64) -> Vec<Vec<Vec<Vec<f64>>>> {
+    let mut cells: Vec<_> = cells.into_iter().collect();
+    cells.sort_unstable();
+
+    let mut runs: Vec<(usize, usize, usize)> = Vec::new();
+    for (row, col) in cells {
+        match runs.last_mut() {
+            Some((r, _, end)) if *r == row && *end == col => *end += 1,
+            _ => runs.push((row, col, col + 1)),
+        }
+    }
+
+    runs.into_iter()
+        .map(|(row, start, end)| {
+            let (west, east) = (start as f64 * resolution - 180.0, end as f64 * resolution - 180.0);
+            let (south, north) = (row as f64 * resolution - 90.0, (row + 1) as f64 * resolution - 90.0);
+            vec![vec![vec![west, south], vec![east, south], vec![east, north], vec![west, north], vec![west, south]]]
+        })
+        .collect()
+}
+
+fn feature(value: Value, kind: &str) -> Feature {
+    let mut feature = Feature::from(Geometry::new(value));
+    feature.set_property("kind", kind);
+    feature
+}
+
+/// Everything on the ground right now
+pub fn world_state(app: &App) -> FeatureCollection {
+    let mut features = Vec::new();
+
+    for fire in app.fires.cells() {
+        let mut f = feature(Value::Point(vec![fire.lon, fire.lat]), "fire");
+        f.set_property("intensity", fire.intensity);
+        f.set_property("weapon", fire.weapon_type.label());
+        features.push(f);
+    }
+
+    for zone in &app.fallout {
+        let puffs = zone.plume.puffs.iter().map(|p| vec![circle(p.lon, p.lat, p.radius_km)]).collect();
+        let mut f = feature(Value::MultiPolygon(puffs), "fallout");
+        f.set_property("intensity", zone.intensity);
+        f.set_property("source", vec![zone.lon, zone.lat]);
+        features.push(f);
+    }
+
+    for cloud in &app.gas_clouds {
+        let puffs = cloud.plume.puffs.iter().map(|p| vec![circle(p.lon, p.lat, p.radius_km)]).collect();
+        let mut f = feature(Value::MultiPolygon(puffs), "gas");
+        f.set_property("intensity", cloud.intensity);
+        f.set_property("weapon", cloud.weapon_type.label());
+        f.set_property("source", vec![cloud.lon, cloud.lat]);
+        features.push(f);
+    }
+
+    for crater in &app.craters {
+        let rim = circle(crater.lon, crater.lat, crater.radius_km);
+        let mut f = feature(Value::Polygon(vec![rim]), "crater");
+        f.set_property("radius_km", crater.radius_km);
+        features.push(f);
+    }
+
+    if !app.burn_scars.is_empty() {
+        let cells = app.burn_scars.cells().map(|(row, col, _)| (row, col));
+        features.push(feature(Value::MultiPolygon(grid_multipolygon(cells, BurnScarGrid::RESOLUTION)), "burned"));
+    }
+
+    for (min_gy, max_gy) in DOSE_BANDS {
+        let cells: Vec<_> = app.radiation.cells()
+            .filter(|&(_, _, gy)| gy >= min_gy && max_gy.is_none_or(|max| gy < max))
+            .map(|(row, col, _)| (row, col))
+            .collect();
+        if cells.is_empty() {
+            continue;
+        }
+        let mut f = feature(Value::MultiPolygon(grid_multipolygon(cells, FalloutGrid::RESOLUTION)), "radiation");
+        f.set_property("min_gy", min_gy);
+        f.set_property("max_gy", max_gy.map_or(JsonValue::Null, JsonValue::from));
+        features.push(f);
+    }
+
+    for city in app.damaged_cities() {
+        let mut f = feature(Value::Point(vec![city.lon, city.lat]), "city");
+        f.set_property("name", city.name.as_str());
+        f.set_property("country", city.country.as_str());
+        f.set_property("population", city.population);
+        f.set_property("original_population", city.original_population);
+        f.set_property("casualties", city.original_population - city.population);
+        features.push(f);
+    }
+
+    FeatureCollection { bbox: None, features, foreign_members: None }
+}
+
+/// Write `world_state` to `path`
+pub fn save(app: &App, path: &Path) -> Result<()> {
+    std::fs::write(path, world_state(app).to_string()).with_context(|| format!("writing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo::great_circle_km;
+
+    #[test]
+    fn circles_are_closed_counter_clockwise_rings() {
+        let ring = circle(179.5, 60.0, 100.0);
+        assert_eq!(ring.len(), CIRCLE_SEGMENTS + 1);
+        assert_eq!(ring.first(), ring.last());
+        for p in &ring {
+            assert!((great_circle_km(179.5, 60.0, p[0], p[1]) - 100.0).abs() < 1.0);
+        }
+        // Unwrapped across the antimeridian
+        assert!(ring.iter().any(|p| p[0] > 180.0));
+
+        // Shoelace: positive area is counter-clockwise
+        let area: f64 = ring.windows(2).map(|w| w[0][0] * w[1][1] - w[1][0] * w[0][1]).sum();
+        assert!(area > 0.0);
+    }
+
+    #[test]
+    fn grid_cells_merge_into_row_runs() {
+        let polygons = grid_multipolygon([(360, 722), (360, 720), (360, 721), (361, 720), (360, 730)], 0.25);
+        assert_eq!(polygons.len(), 3);
+        assert_eq!(polygons[0][0], [[0.0, 0.0], [0.75, 0.0], [0.75, 0.25], [0.0, 0.25], [0.0, 0.0]]);
+        assert_eq!(polygons[1][0][0], [2.5, 0.0]);
+        assert_eq!(polygons[2][0][0], [0.0, 0.25]);
+    }
+}
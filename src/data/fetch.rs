@@ -0,0 +1,430 @@
+//! Downloading the Natural Earth files the map loads from the data
+//! directory.
+//!
+//! Files come from the GeoJSON exports of one natural-earth-vector release
+//! and are saved under the names `load_all_geojson` looks for.
+//! Each resolution brings the coarser ones with it, since the map switches
+//! detail with zoom. A download goes to a `.part` file and only replaces the
+//! real one once it's complete. Failed attempts are retried a few times,
+//! waiting longer each time. The SHA-256 of every file fetched is written
+//! to `SHA256SUMS` next to it, so `verify` can tell a file that has been
+//! truncated or edited since from one that hasn't. Nothing checks a
+//! download against a known sum for the release, so that only catches
+//! changes after the fact.
+//!
+//! Downloads run on a worker thread and report progress over a channel, so
+//! the map keeps drawing. The HTTPS client needs the `online` feature;
+//! without it every download fails with a note saying so.
+
+use anyhow::{bail, Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+/// A tagged release rather than `master`, so the files don't change under us
+pub const BASE_URL: &str = "https://raw.githubusercontent.com/nvkelso/natural-earth-vector/v5.1.2/geojson";
+
+/// Checksums of downloaded files, in `sha256sum` format
+const SUMS_FILE: &str = "SHA256SUMS";
+
+/// Tries per file before giving up on it
+const ATTEMPTS: u32 = 3;
+
+/// Natural Earth's three scales
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Resolution {
+    Low,
+    Medium,
+    High,
+}
+
+impl Resolution {
+    pub const ALL: [Resolution; 3] = [Resolution::Low, Resolution::Medium, Resolution::High];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Resolution::Low => "110m",
+            Resolution::Medium => "50m",
+            Resolution::High => "10m",
+        }
+    }
+
+    /// `110m`, `50m` or `10m`
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| r.label().eq_ignore_ascii_case(s))
+    }
+}
+
+/// A file the map loads, and where upstream keeps it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dataset {
+    /// Name in the data directory
+    pub file: &'static str,
+    /// Upstream name, without the `.geojson`
+    pub source: &'static str,
+    /// The coarsest resolution that downloads it
+    pub resolution: Resolution,
+}
+
+impl Dataset {
+    pub fn url(&self) -> String {
+        format!("{BASE_URL}/{}.geojson", self.source)
+    }
+}
+
+const DATASETS: [Dataset; 10] = [
+    Dataset { file: "ne_110m_coastline.json", source: "ne_110m_coastline", resolution: Resolution::Low },
+    Dataset { file: "ne_110m_admin_0_countries.json", source: "ne_110m_admin_0_countries", resolution: Resolution::Low },
+    // Cities are the targets, so every resolution needs them
+    Dataset { file: "ne_10m_cities.json", source: "ne_10m_populated_places_simple", resolution: Resolution::Low },
+    Dataset { file: "ne_50m_coastline.json", source: "ne_50m_coastline", resolution: Resolution::Medium },
+    Dataset { file: "ne_50m_borders.json", source: "ne_50m_admin_0_boundary_lines_land", resolution: Resolution::Medium },
+    Dataset { file: "ne_50m_admin_0_countries.json", source: "ne_50m_admin_0_countries", resolution: Resolution::Medium },
+    Dataset { file: "ne_10m_coastline.json", source: "ne_10m_coastline", resolution: Resolution::High },
+    Dataset { file: "ne_10m_borders.json", source: "ne_10m_admin_0_boundary_lines_land", resolution: Resolution::High },
+    Dataset { file: "ne_10m_states.json", source: "ne_10m_admin_1_states_provinces_lines", resolution: Resolution::High },
+    Dataset { file: "ne_10m_admin_0_countries.json", source: "ne_10m_admin_0_countries", resolution: Resolution::High },
+];
+
+/// Everything a resolution needs
+pub fn datasets(resolution: Resolution) -> impl Iterator<Item = Dataset> {
+    DATASETS.into_iter().filter(move |d| d.resolution <= resolution)
+}
+
+/// Whether any file from the list is already in `dir`
+pub fn has_any(dir: &Path) -> bool {
+    DATASETS.iter().any(|d| dir.join(d.file).exists())
+}
+
+/// What the worker says about one file
+#[derive(Clone, Debug, PartialEq)]
+enum Update {
+    Progress { index: usize, bytes: u64, total: Option<u64> },
+    Retrying { index: usize, attempt: u32, error: String },
+    Done { index: usize },
+    Failed { index: usize, error: String },
+    Checked { index: usize, state: FileState },
+}
+
+/// Where one file stands
+#[derive(Clone, Debug, PartialEq)]
+pub enum FileState {
+    Missing,
+    Present,
+    Queued,
+    Downloading { bytes: u64, total: Option<u64> },
+    Retrying { attempt: u32, error: String },
+    Downloaded,
+    Failed(String),
+    /// Matches the checksum recorded when it was downloaded
+    Verified,
+    /// Changed since it was downloaded
+    Mismatch,
+    /// Not downloaded here, so there's nothing to check it against
+    Unrecorded,
+}
+
+/// The data manager: which files are present, and any download or check
+/// in progress
+pub struct DataManager {
+    pub dir: PathBuf,
+    /// Whether the panel is showing
+    pub open: bool,
+    pub resolution: Resolution,
+    pub files: Vec<(Dataset, FileState)>,
+    updates: Option<Receiver<Update>>,
+    /// Files fetched by the current batch
+    fetched: usize,
+}
+
+impl DataManager {
+    pub fn new(dir: PathBuf) -> Self {
+        let mut manager = Self { dir, open: false, resolution: Resolution::Low, files: Vec::new(), updates: None, fetched: 0 };
+        manager.select(Resolution::High);
+        manager
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.updates.is_some()
+    }
+
+    /// List the files for a resolution and whether each is on disk
+    pub fn select(&mut self, resolution: Resolution) {
+        if self.is_busy() {
+            return;
+        }
+        self.resolution = resolution;
+        self.files = datasets(resolution)
+            .map(|d| (d, if self.dir.join(d.file).exists() { FileState::Present } else { FileState::Missing }))
+            .collect();
+    }
+
+    /// Fetch whatever a resolution still lacks, or has that failed its
+    /// check. Returns how many files were queued.
+    pub fn download(&mut self, resolution: Resolution) -> usize {
+        if self.is_busy() {
+            return 0;
+        }
+        let mismatched: Vec<&str> =
+            self.files.iter().filter(|(_, s)| *s == FileState::Mismatch).map(|(d, _)| d.file).collect();
+        self.select(resolution);
+        let mut jobs = Vec::new();
+        for (index, (dataset, state)) in self.files.iter_mut().enumerate() {
+            if *state == FileState::Missing || mismatched.contains(&dataset.file) {
+                *state = FileState::Queued;
+                jobs.push((index, *dataset));
+            }
+        }
+        let count = jobs.len();
+        if count > 0 {
+            self.fetched = 0;
+            self.updates = Some(spawn(self.dir.clone(), jobs, Job::Download));
+        }
+        count
+    }
+
+    /// Check every present file against its recorded checksum, on the
+    /// worker since hashing the big ones takes a moment
+    pub fn verify(&mut self) {
+        if self.is_busy() {
+            return;
+        }
+        self.select(self.resolution);
+        let jobs: Vec<(usize, Dataset)> = self
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, state))| *state == FileState::Present)
+            .map(|(index, (dataset, _))| (index, *dataset))
+            .collect();
+        if !jobs.is_empty() {
+            self.fetched = 0;
+            self.updates = Some(spawn(self.dir.clone(), jobs, Job::Verify));
+        }
+    }
+
+    /// Apply what the worker has reported. Returns how many files were
+    /// fetched once a batch finishes having fetched any.
+    pub fn poll(&mut self) -> Option<usize> {
+        let updates = self.updates.as_ref()?;
+        loop {
+            let update = match updates.try_recv() {
+                Ok(update) => update,
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => {
+                    self.updates = None;
+                    return (self.fetched > 0).then_some(self.fetched);
+                }
+            };
+            let (index, state) = match update {
+                Update::Progress { index, bytes, total } => (index, FileState::Downloading { bytes, total }),
+                Update::Retrying { index, attempt, error } => (index, FileState::Retrying { attempt, error }),
+                Update::Done { index } => {
+                    self.fetched += 1;
+                    (index, FileState::Downloaded)
+                }
+                Update::Failed { index, error } => (index, FileState::Failed(error)),
+                Update::Checked { index, state } => (index, state),
+            };
+            if let Some((_, slot)) = self.files.get_mut(index) {
+                *slot = state;
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Job {
+    Download,
+    Verify,
+}
+
+fn spawn(dir: PathBuf, jobs: Vec<(usize, Dataset)>, job: Job) -> Receiver<Update> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for (index, dataset) in jobs {
+            let update = match job {
+                Job::Download => match download(&dir, &dataset, index, &tx) {
+                    Ok(()) => Update::Done { index },
+                    Err(e) => Update::Failed { index, error: format!("{e:#}") },
+                },
+                Job::Verify => Update::Checked {
+                    index,
+                    state: verify(&dir, &dataset).unwrap_or_else(|e| FileState::Failed(format!("{e:#}"))),
+                },
+            };
+            if tx.send(update).is_err() {
+                return;
+            }
+        }
+    });
+    rx
+}
+
+/// Fetch one file into `dir`, retrying, and record its checksum
+fn download(dir: &Path, dataset: &Dataset, index: usize, tx: &Sender<Update>) -> Result<()> {
+    // Without a client there's nothing to retry
+    let attempts = if cfg!(feature = "online") { ATTEMPTS } else { 1 };
+    let url = dataset.url();
+    let mut attempt = 1;
+    let body = loop {
+        let progress = |bytes, total| {
+            let _ = tx.send(Update::Progress { index, bytes, total });
+        };
+        match fetch(&url, progress) {
+            Ok(body) => break body,
+            Err(e) if attempt < attempts => {
+                let _ = tx.send(Update::Retrying { index, attempt, error: format!("{e:#}") });
+                thread::sleep(Duration::from_secs(2u64.pow(attempt)));
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    };
+    if !body.trim_ascii_start().starts_with(b"{") {
+        bail!("{url} didn't return GeoJSON");
+    }
+    let sum = sha256_hex(&body);
+    fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    let path = dir.join(dataset.file);
+    let part = path.with_extension("json.part");
+    fs::write(&part, &body).with_context(|| format!("writing {}", part.display()))?;
+    fs::rename(&part, &path).with_context(|| format!("moving {} into place", part.display()))?;
+    record_sum(dir, dataset.file, &sum)
+}
+
+/// GET a URL, reporting bytes received (and the total, when the server
+/// says) as they come in. A body shorter than promised is an error.
+#[cfg(feature = "online")]
+fn fetch(url: &str, mut progress: impl FnMut(u64, Option<u64>)) -> Result<Vec<u8>> {
+    use std::io::Read;
+    /// Report progress at most this often, in bytes
+    const REPORT_EVERY: usize = 256 * 1024;
+
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(Duration::from_secs(15))
+        .timeout_read(Duration::from_secs(60))
+        .user_agent(concat!("tui-map/", env!("CARGO_PKG_VERSION")))
+        .build();
+    let resp = agent.get(url).call().with_context(|| format!("GET {url}"))?;
+    let total: Option<u64> = resp.header("Content-Length").and_then(|v| v.parse().ok());
+    let mut reader = resp.into_reader();
+    let mut body = Vec::with_capacity(total.unwrap_or(0) as usize);
+    let mut chunk = vec![0; 64 * 1024];
+    let mut reported = 0;
+    loop {
+        let n = reader.read(&mut chunk).with_context(|| format!("reading {url}"))?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+        if body.len() - reported >= REPORT_EVERY {
+            reported = body.len();
+            progress(reported as u64, total);
+        }
+    }
+    progress(body.len() as u64, total);
+    if let Some(total) = total.filter(|&t| t != body.len() as u64) {
+        bail!("connection dropped after {} of {total} bytes", body.len());
+    }
+    Ok(body)
+}
+
+#[cfg(not(feature = "online"))]
+fn fetch(_url: &str, _progress: impl FnMut(u64, Option<u64>)) -> Result<Vec<u8>> {
+    bail!("downloads need a build with `--features online`");
+}
+
+/// GET a small file whole, without reporting progress
+pub fn get(url: &str) -> Result<Vec<u8>> {
+    fetch(url, |_, _| {})
+}
+
+/// Compare a file against the checksum recorded when it was downloaded
+fn verify(dir: &Path, dataset: &Dataset) -> Result<FileState> {
+    let path = dir.join(dataset.file);
+    if !path.exists() {
+        return Ok(FileState::Missing);
+    }
+    let Some(expected) = read_sums(dir)?.remove(dataset.file) else {
+        return Ok(FileState::Unrecorded);
+    };
+    let data = fs::read(&path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(if sha256_hex(&data) == expected { FileState::Verified } else { FileState::Mismatch })
+}
+
+fn read_sums(dir: &Path) -> Result<BTreeMap<String, String>> {
+    let path = dir.join(SUMS_FILE);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let content = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(parse_sums(&content))
+}
+
+/// File names and their sums from `sha256sum` output. `#` starts a comment.
+fn parse_sums(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once("  "))
+        .map(|(sum, file)| (file.trim().to_string(), sum.to_ascii_lowercase()))
+        .collect()
+}
+
+/// Add or replace a file's line in `SHA256SUMS`
+fn record_sum(dir: &Path, file: &str, sum: &str) -> Result<()> {
+    let mut sums = read_sums(dir)?;
+    sums.insert(file.to_string(), sum.to_string());
+    let out: String = sums.iter().map(|(file, sum)| format!("{sum}  {file}\n")).collect();
+    let path = dir.join(SUMS_FILE);
+    fs::write(&path, out).with_context(|| format!("writing {}", path.display()))
+}
+
+/// SHA-256 of `data` as lowercase hex
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_matches_the_standard_vectors() {
+        assert_eq!(sha256_hex(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(sha256_hex(b"abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(
+            sha256_hex(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn checksums_catch_files_changed_since_download() {
+        let dir = std::env::temp_dir().join(format!("tui-map-fetch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dataset = datasets(Resolution::Low).next().unwrap();
+        let path = dir.join(dataset.file);
+        fs::write(&path, "{}").unwrap();
+        assert_eq!(verify(&dir, &dataset).unwrap(), FileState::Unrecorded);
+        record_sum(&dir, dataset.file, &sha256_hex(b"{}")).unwrap();
+        assert_eq!(verify(&dir, &dataset).unwrap(), FileState::Verified);
+        fs::write(&path, "{\"type\":").unwrap();
+        assert_eq!(verify(&dir, &dataset).unwrap(), FileState::Mismatch);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let sums = parse_sums("# v5.1.2\nAB12  ne_110m_coastline.geojson\ncd34 *broken\n");
+        assert_eq!(sums.len(), 1);
+        assert_eq!(sums["ne_110m_coastline.geojson"], "ab12");
+
+        // Each resolution brings the coarser files along
+        assert_eq!(datasets(Resolution::Low).count(), 3);
+        assert!(datasets(Resolution::High).any(|d| d.file == "ne_110m_coastline.json"));
+        assert_eq!(Resolution::parse("10M"), Some(Resolution::High));
+    }
+}
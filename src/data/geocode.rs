@@ -0,0 +1,327 @@
+//! Place-name geocoding for the search box.
+//!
+//! Lookups go through a chain of `Geocoder`s on a background thread:
+//! Nominatim (with the `online` feature) first, then an offline gazetteer.
+//! Loaded Natural Earth cities are matched synchronously before any of this.
+//!
+//! Gazetteer files list longitude before latitude, like every other
+//! coordinate pair in the crate: `name<TAB>lon<TAB>lat[<TAB>zoom]`.
+
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+/// A geocoded location.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Place {
+    pub name: String,
+    pub lon: f64,
+    pub lat: f64,
+    /// Suggested zoom for framing the result (country vs. street address)
+    pub zoom: f64,
+}
+
+/// Resolves free-text queries to coordinates. Implementations may block —
+/// they only ever run on the geocoding worker thread.
+pub trait Geocoder: Send {
+    fn geocode(&self, query: &str) -> Result<Option<Place>>;
+}
+
+/// Offline name → coordinate table. Ships with a handful of countries and
+/// regions (cities come from Natural Earth), extendable via a TSV file.
+pub struct Gazetteer {
+    entries: Vec<Place>,
+}
+
+/// Built-in entries: (name, lon, lat, zoom)
+const BUILTIN_PLACES: &[(&str, f64, f64, f64)] = &[
+    ("United States", -98.5, 39.8, 3.0),
+    ("Canada", -106.3, 56.1, 2.5),
+    ("Mexico", -102.5, 23.6, 4.0),
+    ("Brazil", -51.9, -14.2, 3.0),
+    ("Argentina", -63.6, -38.4, 3.0),
+    ("United Kingdom", -3.4, 55.4, 5.0),
+    ("France", 2.2, 46.2, 5.0),
+    ("Germany", 10.5, 51.2, 5.0),
+    ("Spain", -3.7, 40.5, 5.0),
+    ("Italy", 12.6, 41.9, 5.0),
+    ("Russia", 105.3, 61.5, 2.0),
+    ("China", 104.2, 35.9, 3.0),
+    ("India", 78.9, 20.6, 3.5),
+    ("Japan", 138.3, 36.2, 5.0),
+    ("Australia", 133.8, -25.3, 3.0),
+    ("South Africa", 22.9, -30.6, 4.5),
+    ("Egypt", 30.8, 26.8, 5.0),
+    ("Nigeria", 8.7, 9.1, 5.0),
+    ("Europe", 15.0, 50.0, 3.0),
+    ("Africa", 20.0, 2.0, 2.0),
+    ("Asia", 90.0, 35.0, 1.5),
+    ("North America", -100.0, 45.0, 1.8),
+    ("South America", -60.0, -15.0, 2.0),
+    ("Antarctica", 0.0, -82.0, 2.0),
+    ("California", -119.5, 37.2, 5.0),
+    ("Texas", -99.3, 31.5, 5.0),
+    ("Florida", -81.7, 28.1, 5.5),
+    ("Alaska", -152.0, 64.0, 3.5),
+    ("Siberia", 100.0, 62.0, 2.5),
+    ("Scandinavia", 15.0, 63.0, 3.5),
+    ("Middle East", 45.0, 29.0, 3.5),
+];
+
+impl Gazetteer {
+    pub fn builtin() -> Self {
+        let entries = BUILTIN_PLACES
+            .iter()
+            .map(|&(name, lon, lat, zoom)| Place { name: name.to_string(), lon, lat, zoom })
+            .collect();
+        Self { entries }
+    }
+
+    /// Append entries from a `name<TAB>lon<TAB>lat[<TAB>zoom]` file.
+    /// Malformed lines and `#` comments are skipped.
+    pub fn load_tsv(&mut self, path: &Path) -> Result<usize> {
+        let content = fs::read_to_string(path)?;
+        let before = self.entries.len();
+        for line in content.lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut cols = line.split('\t');
+            let (Some(name), Some(lon), Some(lat)) = (cols.next(), cols.next(), cols.next()) else {
+                continue;
+            };
+            let (Ok(lon), Ok(lat)) = (lon.trim().parse::<f64>(), lat.trim().parse::<f64>()) else {
+                continue;
+            };
+            let zoom = cols.next().and_then(|z| z.trim().parse().ok()).unwrap_or(8.0);
+            self.entries.push(Place { name: name.trim().to_string(), lon, lat, zoom });
+        }
+        Ok(self.entries.len() - before)
+    }
+
+    /// Case-insensitive lookup: exact name first, then prefix.
+    pub fn lookup(&self, query: &str) -> Option<&Place> {
+        let q = query.trim().to_lowercase();
+        if q.is_empty() {
+            return None;
+        }
+        self.entries
+            .iter()
+            .find(|p| p.name.to_lowercase() == q)
+            .or_else(|| self.entries.iter().find(|p| p.name.to_lowercase().starts_with(&q)))
+    }
+}
+
+impl Geocoder for Gazetteer {
+    fn geocode(&self, query: &str) -> Result<Option<Place>> {
+        Ok(self.lookup(query).cloned())
+    }
+}
+
+/// OpenStreetMap Nominatim search API.
+#[cfg(feature = "online")]
+pub struct Nominatim {
+    endpoint: String,
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "online")]
+impl Nominatim {
+    pub const DEFAULT_ENDPOINT: &'static str = "https://nominatim.openstreetmap.org/search";
+
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        // Nominatim's usage policy requires an identifying User-Agent
+        let agent = ureq::AgentBuilder::new()
+            .timeout(std::time::Duration::from_secs(10))
+            .user_agent(concat!("tui-map/", env!("CARGO_PKG_VERSION")))
+            .build();
+        Self { endpoint: endpoint.into(), agent }
+    }
+}
+
+#[cfg(feature = "online")]
+impl Geocoder for Nominatim {
+    fn geocode(&self, query: &str) -> Result<Option<Place>> {
+        let body = self
+            .agent
+            .get(&self.endpoint)
+            .query("q", query)
+            .query("format", "jsonv2")
+            .query("limit", "1")
+            .call()?
+            .into_string()?;
+        parse_nominatim(&body)
+    }
+}
+
+/// Parse the first hit of a Nominatim `jsonv2` response.
+/// Zoom is derived from the result's bounding box so countries frame whole.
+#[cfg_attr(not(feature = "online"), allow(dead_code))]
+fn parse_nominatim(body: &str) -> Result<Option<Place>> {
+    use simd_json::prelude::*;
+
+    let mut bytes = body.as_bytes().to_vec();
+    let value = simd_json::to_owned_value(&mut bytes)?;
+    let Some(hit) = value.as_array().and_then(|a| a.first()) else {
+        return Ok(None);
+    };
+
+    let num = |v: Option<&simd_json::OwnedValue>| v.and_then(|v| v.as_str()).and_then(|s| s.parse::<f64>().ok());
+    let (Some(lat), Some(lon)) = (num(hit.get("lat")), num(hit.get("lon"))) else {
+        return Ok(None);
+    };
+    let name = hit.get("display_name").and_then(|v| v.as_str()).unwrap_or("?");
+    // Keep just the leading component — full display names overflow the status bar
+    let name = name.split(',').next().unwrap_or(name).trim().to_string();
+
+    // boundingbox = [south, north, west, east] as strings
+    let zoom = hit
+        .get("boundingbox")
+        .and_then(|b| b.as_array())
+        .filter(|b| b.len() == 4)
+        .and_then(|b| {
+            let s = num(b.first())?;
+            let n = num(b.get(1))?;
+            let w = num(b.get(2))?;
+            let e = num(b.get(3))?;
+            let span = (n - s).max(e - w).max(0.01);
+            Some((180.0 / span).clamp(1.0, 100.0))
+        })
+        .unwrap_or(8.0);
+
+    Ok(Some(Place { name, lon, lat, zoom }))
+}
+
+type GeocodeResult = (String, Result<Option<Place>>);
+
+/// Runs geocoder lookups on a worker thread so the UI never blocks on HTTP.
+pub struct GeocodeService {
+    requests: Sender<String>,
+    results: Receiver<GeocodeResult>,
+    in_flight: usize,
+}
+
+impl GeocodeService {
+    /// Spawn the worker. Geocoders are tried in order until one returns a hit;
+    /// errors from earlier ones are only reported if nothing matches.
+    pub fn new(geocoders: Vec<Box<dyn Geocoder>>) -> Self {
+        let (req_tx, req_rx) = mpsc::channel::<String>();
+        let (res_tx, res_rx) = mpsc::channel::<GeocodeResult>();
+
+        thread::spawn(move || {
+            for query in req_rx {
+                let mut last_err = None;
+                let mut found = None;
+                for geocoder in &geocoders {
+                    match geocoder.geocode(&query) {
+                        Ok(Some(place)) => {
+                            found = Some(place);
+                            break;
+                        }
+                        Ok(None) => {}
+                        Err(e) => last_err = Some(e),
+                    }
+                }
+                let result = match (found, last_err) {
+                    (Some(place), _) => Ok(Some(place)),
+                    (None, Some(e)) => Err(e),
+                    (None, None) => Ok(None),
+                };
+                if res_tx.send((query, result)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self { requests: req_tx, results: res_rx, in_flight: 0 }
+    }
+
+    /// Default chain: Nominatim when built with `online`, then the gazetteer
+    /// (plus `data/gazetteer.tsv` if present).
+    pub fn with_defaults(data_dir: &Path) -> Self {
+        let mut chain: Vec<Box<dyn Geocoder>> = Vec::new();
+        #[cfg(feature = "online")]
+        chain.push(Box::new(Nominatim::new(Nominatim::DEFAULT_ENDPOINT)));
+
+        let mut gazetteer = Gazetteer::builtin();
+        let _ = gazetteer.load_tsv(&data_dir.join("gazetteer.tsv"));
+        chain.push(Box::new(gazetteer));
+        Self::new(chain)
+    }
+
+    pub fn submit(&mut self, query: String) {
+        if self.requests.send(query).is_ok() {
+            self.in_flight += 1;
+        }
+    }
+
+    /// Next finished lookup, if any.
+    pub fn poll(&mut self) -> Option<GeocodeResult> {
+        let result = self.results.try_recv().ok()?;
+        self.in_flight = self.in_flight.saturating_sub(1);
+        Some(result)
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.in_flight > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gazetteer_matches_case_insensitive_and_prefix() {
+        let g = Gazetteer::builtin();
+        assert_eq!(g.lookup("japan").unwrap().name, "Japan");
+        assert_eq!(g.lookup("  south af").unwrap().name, "South Africa");
+        assert!(g.lookup("atlantis").is_none());
+        assert!(g.lookup("").is_none());
+    }
+
+    #[test]
+    fn gazetteer_loads_tsv() {
+        let path = std::env::temp_dir().join(format!("tui_map_gazetteer_{}.tsv", std::process::id()));
+        fs::write(&path, "# name\tlon\tlat\nTimbuktu\t-3.01\t16.77\nbroken line\nFoo\tx\t1\n").unwrap();
+        let mut g = Gazetteer::builtin();
+        assert_eq!(g.load_tsv(&path).unwrap(), 1);
+        let p = g.lookup("timbuktu").unwrap();
+        assert_eq!((p.lon, p.lat, p.zoom), (-3.01, 16.77, 8.0));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn parses_nominatim_response() {
+        let body = r#"[{"lat":"48.8588897","lon":"2.3200410","display_name":"Paris, Île-de-France, France",
+            "boundingbox":["48.8155755","48.9021560","2.2241220","2.4697602"]}]"#;
+        let place = parse_nominatim(body).unwrap().unwrap();
+        assert_eq!(place.name, "Paris");
+        assert!((place.lat - 48.8588897).abs() < 1e-9);
+        assert!(place.zoom > 50.0);
+        assert!(parse_nominatim("[]").unwrap().is_none());
+    }
+
+    #[test]
+    fn service_falls_through_chain() {
+        struct Failing;
+        impl Geocoder for Failing {
+            fn geocode(&self, _: &str) -> Result<Option<Place>> {
+                anyhow::bail!("offline")
+            }
+        }
+        let mut svc = GeocodeService::new(vec![Box::new(Failing), Box::new(Gazetteer::builtin())]);
+        svc.submit("Brazil".into());
+        let (query, result) = loop {
+            if let Some(r) = svc.poll() {
+                break r;
+            }
+            thread::yield_now();
+        };
+        assert_eq!(query, "Brazil");
+        assert_eq!(result.unwrap().unwrap().name, "Brazil");
+        assert!(!svc.is_busy());
+    }
+}
@@ -0,0 +1,266 @@
+//! Coarse land cover, for how fires behave on the ground they reach.
+//!
+//! Without a file, cover follows the climate belts — rainforest at the
+//! equator, savanna, the subtropical deserts, temperate forest and
+//! grassland, boreal forest, then tundra and ice — with noise so the belts
+//! break up, and the ground under big cities is urban. A raster in the ESRI
+//! ASCII grid format with MODIS/IGBP class codes (MCD12C1 exports to it)
+//! replaces the belts wherever it has data.
+
+use crate::data::raster::AsciiGrid;
+use crate::geo::{great_circle_km, normalize_lat, normalize_lon};
+use crate::hash::{hash2, rand_simple};
+use crate::map::renderer::City;
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// What's on the ground, as far as a fire is concerned
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cover {
+    Forest,
+    /// Grassland, savanna, shrubs, cropland and tundra
+    Grass,
+    /// Desert, rock and ice
+    Desert,
+    Urban,
+}
+
+impl Cover {
+    /// From a MODIS/IGBP land-cover class; water and unclassified have none
+    pub fn from_igbp(class: u8) -> Option<Self> {
+        match class {
+            1..=5 => Some(Self::Forest),
+            6..=12 | 14 => Some(Self::Grass),
+            13 => Some(Self::Urban),
+            15 | 16 => Some(Self::Desert),
+            _ => None,
+        }
+    }
+
+    /// Chance per frame a hot fire here throws embers
+    pub fn spread_chance(self) -> f64 {
+        match self {
+            Self::Forest => 0.22,
+            Self::Grass => 0.15,
+            Self::Urban => 0.10,
+            Self::Desert => 0.03,
+        }
+    }
+
+    /// How far embers fly, relative to grass: crown fires leap ahead
+    pub fn reach(self) -> f64 {
+        match self {
+            Self::Forest => 1.5,
+            _ => 1.0,
+        }
+    }
+
+    /// Share of the parent fire's heat a new fire catches with here
+    pub fn fuel(self) -> f64 {
+        match self {
+            Self::Forest => 1.0,
+            Self::Grass => 0.9,
+            Self::Urban => 0.85,
+            Self::Desert => 0.35,
+        }
+    }
+
+    /// How many times faster than forest a fire here burns itself out
+    pub fn burnout(self) -> u8 {
+        match self {
+            Self::Forest | Self::Urban => 1,
+            Self::Grass => 2,
+            Self::Desert => 3,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Forest => "forest",
+            Self::Grass => "grassland",
+            Self::Desert => "desert",
+            Self::Urban => "urban",
+        }
+    }
+}
+
+/// Land cover on a fixed 0.5° grid
+pub struct LandCover {
+    cells: Vec<Cover>,
+}
+
+impl LandCover {
+    pub const RESOLUTION: f64 = 0.5;
+    const WIDTH: usize = 720;
+    const HEIGHT: usize = 360;
+    /// Spacing of the noise that breaks up the climate belts, in degrees
+    const NOISE_DEG: f64 = 8.0;
+
+    /// Cover from the climate belts alone. The belts are worked out once
+    /// and copied for every world after.
+    pub fn climatic() -> Self {
+        static BELTS: OnceLock<Vec<Cover>> = OnceLock::new();
+        let cells = BELTS.get_or_init(|| {
+            (0..Self::WIDTH * Self::HEIGHT)
+                .map(|idx| {
+                    let (lon, lat) = Self::center(idx);
+                    Self::belt(lon, lat)
+                })
+                .collect()
+        });
+        Self { cells: cells.clone() }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("reading land-cover raster {}", path.display()))?;
+        Self::parse(&content)
+    }
+
+    /// Climate belts overlaid with an IGBP class raster
+    pub fn parse(content: &str) -> Result<Self> {
+        let raster = AsciiGrid::parse(content)?;
+        let mut cover = Self::climatic();
+        // Coarse source cells cover every grid cell under them; fine ones
+        // land in the cell under their center
+        let split = (raster.cellsize / Self::RESOLUTION).round().max(1.0) as usize;
+        let step = raster.cellsize / split as f64;
+        raster.for_each_cell("land-cover", |lon, lat, class| {
+            let Some(kind) = Cover::from_igbp(class as u8) else { return };
+            for i in 0..split {
+                for j in 0..split {
+                    cover.cells[Self::index(lon + (i as f64 + 0.5) * step, lat + (j as f64 + 0.5) * step)] = kind;
+                }
+            }
+        })?;
+        Ok(cover)
+    }
+
+    fn index(lon: f64, lat: f64) -> usize {
+        let col = ((normalize_lon(lon) / Self::RESOLUTION) as usize).min(Self::WIDTH - 1);
+        let row = ((normalize_lat(lat) / Self::RESOLUTION) as usize).min(Self::HEIGHT - 1);
+        row * Self::WIDTH + col
+    }
+
+    /// Center of a cell
+    fn center(idx: usize) -> (f64, f64) {
+        let (row, col) = (idx / Self::WIDTH, idx % Self::WIDTH);
+        (
+            (col as f64 + 0.5) * Self::RESOLUTION - 180.0,
+            (row as f64 + 0.5) * Self::RESOLUTION - 90.0,
+        )
+    }
+
+    /// Smooth value noise in 0..1, wrapping at the antimeridian
+    fn noise(lon: f64, lat: f64, salt: u64) -> f64 {
+        let cols = (360.0 / Self::NOISE_DEG) as u64;
+        let (x, y) = (normalize_lon(lon) / Self::NOISE_DEG, normalize_lat(lat) / Self::NOISE_DEG);
+        let (x0, y0) = (x.floor() as u64, y.floor() as u64);
+        let (fx, fy) = (x - x.floor(), y - y.floor());
+        let corner = |cx: u64, cy: u64| rand_simple(hash2((cx % cols) ^ salt, cy));
+        let (sx, sy) = (fx * fx * (3.0 - 2.0 * fx), fy * fy * (3.0 - 2.0 * fy));
+        let top = corner(x0, y0) + (corner(x0 + 1, y0) - corner(x0, y0)) * sx;
+        let bottom = corner(x0, y0 + 1) + (corner(x0 + 1, y0 + 1) - corner(x0, y0 + 1)) * sx;
+        top + (bottom - top) * sy
+    }
+
+    /// Climate-belt cover, with boundaries wandering up to 6° either way
+    fn belt(lon: f64, lat: f64) -> Cover {
+        let band = lat.abs() + (Self::noise(lon, lat, 0) - 0.5) * 12.0;
+        match band {
+            b if b < 10.0 => Cover::Forest,
+            b if b < 17.0 => Cover::Grass,
+            b if b < 33.0 => Cover::Desert,
+            b if b < 45.0 && Self::noise(lon, lat, 1) < 0.5 => Cover::Grass,
+            b if b < 65.0 => Cover::Forest,
+            b if b < 72.0 => Cover::Grass,
+            _ => Cover::Desert,
+        }
+    }
+
+    /// Pave the ground under cities of half a million or more, out to a
+    /// radius that grows with population
+    pub fn mark_cities<'a>(&mut self, cities: impl IntoIterator<Item = &'a City>) {
+        for city in cities.into_iter().filter(|c| c.original_population >= 500_000) {
+            let radius_km = 6.0 * (city.original_population as f64 / 100_000.0).sqrt();
+            let reach = (radius_km / 111.0 / Self::RESOLUTION).ceil() as i32 + 1;
+            // Degrees of longitude shrink toward the poles
+            let cos_lat = city.lat.to_radians().cos().max(0.01);
+            let reach_lon = (radius_km / (111.0 * cos_lat) / Self::RESOLUTION).ceil().min(Self::WIDTH as f64 / 2.0) as i32 + 1;
+            self.cells[Self::index(city.lon, city.lat)] = Cover::Urban;
+            for dy in -reach..=reach {
+                for dx in -reach_lon..=reach_lon {
+                    let lon = city.lon + dx as f64 * Self::RESOLUTION;
+                    let lat = city.lat + dy as f64 * Self::RESOLUTION;
+                    let (cx, cy) = Self::center(Self::index(lon, lat));
+                    if great_circle_km(city.lon, city.lat, cx, cy) <= radius_km {
+                        self.cells[Self::index(lon, lat)] = Cover::Urban;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn at(&self, lon: f64, lat: f64) -> Cover {
+        self.cells[Self::index(lon, lat)]
+    }
+}
+
+impl Default for LandCover {
+    fn default() -> Self {
+        Self::climatic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn belts_follow_climate_and_rasters_override_them() {
+        let cover = LandCover::climatic();
+        assert_eq!(cover.at(-60.0, -2.0), Cover::Forest);
+        assert_eq!(cover.at(10.0, 24.0), Cover::Desert);
+        assert_eq!(cover.at(100.0, 55.0), Cover::Forest);
+        assert_eq!(cover.at(40.0, -85.0), Cover::Desert);
+
+        // A 1° forest cell and a 1° urban cell over the Sahara; water and
+        // nodata leave the belts alone
+        let raster = "ncols 3\nnrows 1\nxllcorner 10\nyllcorner 24\ncellsize 1\nNODATA_value 255\n1 13 17\n";
+        let cover = LandCover::parse(raster).unwrap();
+        assert_eq!(cover.at(10.2, 24.2), Cover::Forest);
+        assert_eq!(cover.at(10.7, 24.7), Cover::Forest);
+        assert_eq!(cover.at(11.5, 24.5), Cover::Urban);
+        assert_eq!(cover.at(12.5, 24.5), Cover::Desert);
+        assert!(LandCover::parse("ncols 2\nnrows 1\nxllcorner 0\nyllcorner 0\ncellsize 1\n5").is_err());
+    }
+
+    #[test]
+    fn northern_cities_pave_as_far_east_as_north() {
+        let city = City {
+            lon: 30.25,
+            lat: 70.25,
+            name: "Norilsk".into(),
+            population: 20_000_000,
+            original_population: 20_000_000,
+            is_capital: false,
+            is_megacity: false,
+            country: "RUS".into(),
+            radius_km: 0.0,
+            cached_pop_label: String::new(),
+            blackout_frames: 0,
+            infection: 0.0,
+            displaced: 0,
+            english_name: None,
+        };
+        let mut cover = LandCover::climatic();
+        cover.mark_cities([&city]);
+        // 85 km out: under a degree north, over two degrees east at 70°N
+        assert_eq!(cover.at(30.25, 70.75), Cover::Urban);
+        assert_ne!(cover.at(30.25, 71.25), Cover::Urban);
+        assert_eq!(cover.at(32.25, 70.25), Cover::Urban);
+        assert_eq!(cover.at(28.25, 70.25), Cover::Urban);
+        assert_ne!(cover.at(33.25, 70.25), Cover::Urban);
+    }
+}
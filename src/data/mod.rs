@@ -1,12 +1,26 @@
-use crate::map::{LineString, Lod, MapRenderer};
-use anyhow::Result;
+pub mod chunks;
+pub mod embedded;
+pub mod export;
+pub mod fetch;
+pub mod geocode;
+pub mod landcover;
+pub mod population;
+pub mod raster;
+pub mod scenario;
+pub mod tiles;
+pub mod watch;
+
+use crate::map::geometry::ring_centroid;
+use crate::map::renderer::{PlaceKind, PlaceLabel, Polygon};
+use crate::map::{Facility, Features, Infrastructure, LineString, Lod, MapRenderer, Route};
+use anyhow::{bail, Context, Result};
 use geojson::{GeoJson, Geometry, Value};
 use rayon::prelude::*;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 /// Parse GeoJSON using SIMD-accelerated JSON parsing
-fn parse_geojson(content: String) -> Result<GeoJson> {
+pub(crate) fn parse_geojson(content: String) -> Result<GeoJson> {
     let mut bytes = content.into_bytes();
     Ok(simd_json::serde::from_slice(&mut bytes)?)
 }
@@ -19,9 +33,13 @@ struct CityData {
     population: u64,
     is_capital: bool,
     is_megacity: bool,
+    country: String,
+    /// The English name, when `name` is in another language
+    english_name: Option<String>,
 }
 
 /// What kind of geometry a file contains and where it goes
+#[derive(Clone, Copy, PartialEq)]
 enum FileKind {
     Coastline(Lod),
     Border(Lod),
@@ -29,18 +47,75 @@ enum FileKind {
     County,
     City,
     LandPolygon(Lod),
+    Lake,
+    Country,
+    /// Admin-1 polygons, read for their names
+    Province,
+    /// Roads and railways
+    Route(Infrastructure),
+    /// Airports and ports
+    Facility(Infrastructure),
 }
 
+impl FileKind {
+    /// The layer the file feeds, as reported when it's reloaded
+    fn layer(self) -> &'static str {
+        match self {
+            FileKind::Coastline(_) => "coastlines",
+            FileKind::Border(_) => "borders",
+            FileKind::State => "states",
+            FileKind::County => "counties",
+            FileKind::City => "cities",
+            FileKind::LandPolygon(_) => "land",
+            FileKind::Lake => "lakes",
+            FileKind::Country => "countries",
+            FileKind::Province => "province names",
+            FileKind::Route(Infrastructure::Railway) => "railways",
+            FileKind::Route(_) => "roads",
+            FileKind::Facility(Infrastructure::Port) => "ports",
+            FileKind::Facility(_) => "airports",
+        }
+    }
+}
+
+/// Polygon rings tagged with an ISO alpha-3 country code
+type CountryRings = (String, Vec<Vec<(f64, f64)>>);
+
+/// Names as (ISO alpha-3 code, name)
+type CountryNames = Vec<(String, String)>;
+
 /// Result of loading + parsing a single file in parallel
 enum LoadResult {
     Lines(Vec<LineString>, FileKind),
     Polygons(Vec<Vec<Vec<(f64, f64)>>>, Lod),
+    Lakes(Vec<Vec<Vec<(f64, f64)>>>),
     Cities(Vec<CityData>),
+    /// Admin-0 polygons with their ISO alpha-3 code, the countries' names,
+    /// and their English names where those differ
+    Countries(Vec<CountryRings>, CountryNames, CountryNames, Vec<PlaceLabel>),
+    Places(Vec<PlaceLabel>),
+    Facilities(Vec<Facility>),
     Failed(String, String), // filename, error
 }
 
-/// Load a single file and parse its geometries (no renderer dependency)
-fn load_file(path: &Path, kind: FileKind) -> LoadResult {
+/// Languages Natural Earth has `name_<lang>` fields for
+pub const NAME_LANGUAGES: [&str; 26] = [
+    "ar", "bn", "de", "el", "en", "es", "fa", "fr", "he", "hi", "hu", "id", "it",
+    "ja", "ko", "nl", "pl", "pt", "ru", "sv", "tr", "uk", "ur", "vi", "zh", "zht",
+];
+
+/// A feature's name in `language`, from `name_<lang>` or `NAME_<LANG>`,
+/// if it has a non-empty one
+fn localized_name<'a>(props: Option<&'a geojson::JsonObject>, language: Option<&str>) -> Option<&'a str> {
+    let (props, language) = (props?, language?);
+    [format!("name_{language}"), format!("NAME_{}", language.to_uppercase())]
+        .iter()
+        .find_map(|key| props.get(key).and_then(|v| v.as_str()).filter(|name| !name.is_empty()))
+}
+
+/// Load a single file and parse its geometries (no renderer dependency).
+/// Names are read in `language` where the file has them.
+fn load_file(path: &Path, kind: FileKind, language: Option<&str>) -> LoadResult {
     let content = match fs::read_to_string(path) {
         Ok(c) => c,
         Err(e) => return LoadResult::Failed(
@@ -58,7 +133,7 @@ fn load_file(path: &Path, kind: FileKind) -> LoadResult {
 
     match kind {
         FileKind::City => {
-            let cities = extract_cities(&geojson);
+            let cities = extract_cities(&geojson, language);
             LoadResult::Cities(cities)
         }
         FileKind::LandPolygon(lod) => {
@@ -66,6 +141,17 @@ fn load_file(path: &Path, kind: FileKind) -> LoadResult {
             process_geojson_polygons(&geojson, |p| polygons.push(p));
             LoadResult::Polygons(polygons, lod)
         }
+        FileKind::Lake => {
+            let mut polygons = Vec::new();
+            process_geojson_polygons(&geojson, |p| polygons.push(p));
+            LoadResult::Lakes(polygons)
+        }
+        FileKind::Country => {
+            let (countries, names, english) = extract_countries(&geojson, language);
+            LoadResult::Countries(countries, names, english, extract_place_labels(&geojson, PlaceKind::Country, language))
+        }
+        FileKind::Province => LoadResult::Places(extract_place_labels(&geojson, PlaceKind::State, language)),
+        FileKind::Facility(kind) => LoadResult::Facilities(extract_facilities(&geojson, kind)),
         _ => {
             let mut lines = Vec::new();
             process_geojson_lines(&geojson, |pts| lines.push(LineString::new(pts)));
@@ -75,17 +161,16 @@ fn load_file(path: &Path, kind: FileKind) -> LoadResult {
 }
 
 /// Extract city data from parsed GeoJSON
-fn extract_cities(geojson: &GeoJson) -> Vec<CityData> {
+fn extract_cities(geojson: &GeoJson, language: Option<&str>) -> Vec<CityData> {
     let mut cities = Vec::new();
     if let GeoJson::FeatureCollection(fc) = geojson {
         for feature in &fc.features {
             let props = feature.properties.as_ref();
 
-            let name = props
-                .and_then(|p| p.get("name"))
-                .and_then(|v| v.as_str())
-                .unwrap_or("Unknown")
-                .to_string();
+            let english = props.and_then(|p| p.get("name")).and_then(|v| v.as_str());
+            let localized = localized_name(props, language);
+            let name = localized.or(english).unwrap_or("Unknown").to_string();
+            let english_name = english.filter(|english| localized.is_some_and(|l| l != *english)).map(str::to_string);
 
             let population = props
                 .and_then(|p| {
@@ -109,6 +194,12 @@ fn extract_cities(geojson: &GeoJson) -> Vec<CityData> {
                 .map(|v| v >= 1.0)
                 .unwrap_or(false);
 
+            let country = props
+                .and_then(|p| p.get("adm0_a3").or_else(|| p.get("sov_a3")))
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+
             if let Some(ref geometry) = feature.geometry {
                 if let Value::Point(ref coords) = geometry.value {
                     if coords.len() >= 2 {
@@ -119,6 +210,8 @@ fn extract_cities(geojson: &GeoJson) -> Vec<CityData> {
                             population,
                             is_capital,
                             is_megacity,
+                            country,
+                            english_name,
                         });
                     }
                 }
@@ -128,46 +221,149 @@ fn extract_cities(geojson: &GeoJson) -> Vec<CityData> {
     cities
 }
 
-/// Load all available Natural Earth GeoJSON data into the map renderer
-pub fn load_all_geojson(renderer: &mut MapRenderer, data_dir: &Path) -> Result<()> {
-    // Collect all file tasks
-    let mut tasks: Vec<(PathBuf, FileKind)> = Vec::new();
+/// Extract airports or ports (Point features)
+fn extract_facilities(geojson: &GeoJson, kind: Infrastructure) -> Vec<Facility> {
+    let mut facilities = Vec::new();
+    if let GeoJson::FeatureCollection(fc) = geojson {
+        for feature in &fc.features {
+            let name = feature.properties.as_ref()
+                .and_then(|p| p.get("name"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            if let Some(Value::Point(coords)) = feature.geometry.as_ref().map(|g| &g.value) {
+                if coords.len() >= 2 {
+                    facilities.push(Facility { lon: coords[0], lat: coords[1], name, kind, destroyed: false });
+                }
+            }
+        }
+    }
+    facilities
+}
 
-    // Coastlines
-    for (filename, lod) in [
-        ("ne_110m_coastline.json", Lod::Low),
-        ("natural-earth.json", Lod::Medium),
-        ("ne_50m_coastline.json", Lod::Medium),
-        ("ne_10m_coastline.json", Lod::High),
-    ] {
-        let path = data_dir.join(filename);
-        if path.exists() {
-            tasks.push((path, FileKind::Coastline(lod)));
+/// Extract admin-0 polygons, keeping each feature's country code
+fn extract_countries(geojson: &GeoJson, language: Option<&str>) -> (Vec<CountryRings>, CountryNames, CountryNames) {
+    let mut countries = Vec::new();
+    let mut names = Vec::new();
+    let mut english_names = Vec::new();
+    if let GeoJson::FeatureCollection(fc) = geojson {
+        for feature in &fc.features {
+            let props = feature.properties.as_ref();
+            let get = |keys: &[&str]| keys.iter().find_map(|k| props.and_then(|p| p.get(*k)).and_then(|v| v.as_str()));
+            // Natural Earth uses upper-case keys; ISO_A3 is "-99" for some disputed areas
+            let code = ["ADM0_A3", "adm0_a3", "ISO_A3", "iso_a3"]
+                .iter()
+                .filter_map(|k| get(&[k]))
+                .find(|c| c.len() == 3 && *c != "-99")
+                .unwrap_or("")
+                .to_string();
+            if code.is_empty() {
+                continue;
+            }
+            let english = get(&["ADMIN", "admin", "NAME", "name"]);
+            let localized = localized_name(props, language);
+            if let Some(name) = localized.or(english) {
+                names.push((code.clone(), name.to_string()));
+            }
+            if let Some(english) = english.filter(|english| localized.is_some_and(|l| l != *english)) {
+                english_names.push((code.clone(), english.to_string()));
+            }
+            if let Some(ref geometry) = feature.geometry {
+                process_geometry_polygons(geometry, &mut |rings| countries.push((code.clone(), rings)));
+            }
         }
     }
+    (countries, names, english_names)
+}
 
-    // Borders
-    for (filename, lod) in [
-        ("ne_50m_borders.json", Lod::Medium),
-        ("ne_10m_borders.json", Lod::High),
-    ] {
-        let path = data_dir.join(filename);
-        if path.exists() {
-            tasks.push((path, FileKind::Border(lod)));
+/// Extract a name label per feature of an admin-0 or admin-1 file: at the
+/// dataset's label point if it has one, otherwise the centroid of the
+/// feature's largest polygon
+fn extract_place_labels(geojson: &GeoJson, kind: PlaceKind, language: Option<&str>) -> Vec<PlaceLabel> {
+    let mut labels = Vec::new();
+    let GeoJson::FeatureCollection(fc) = geojson else { return labels };
+    for feature in &fc.features {
+        let props = feature.properties.as_ref();
+        let get = |keys: &[&str]| keys.iter().find_map(|k| props.and_then(|p| p.get(*k)).filter(|v| !v.is_null()));
+        let Some(name) = localized_name(props, language).or_else(|| get(&["NAME", "name", "ADMIN", "admin"]).and_then(|v| v.as_str())) else { continue };
+        let min_label = get(&["MIN_LABEL", "min_label"]).and_then(|v| v.as_f64());
+
+        let label_point = get(&["LABEL_X", "label_x"]).and_then(|v| v.as_f64()).zip(get(&["LABEL_Y", "label_y"]).and_then(|v| v.as_f64()));
+        let point = label_point.or_else(|| {
+            let mut largest: Option<(f64, (f64, f64))> = None;
+            if let Some(ref geometry) = feature.geometry {
+                process_geometry_polygons(geometry, &mut |rings| {
+                    if let Some(exterior) = rings.first() {
+                        let (area, centroid) = ring_centroid(exterior);
+                        if largest.is_none_or(|(a, _)| area.abs() > a) {
+                            largest = Some((area.abs(), centroid));
+                        }
+                    }
+                });
+            }
+            largest.map(|(_, c)| c)
+        });
+        if let Some((lon, lat)) = point {
+            labels.push(PlaceLabel::new(lon, lat, name, kind, min_label));
         }
     }
+    labels
+}
 
-    // States
-    let states_path = data_dir.join("ne_10m_states.json");
-    if states_path.exists() {
-        tasks.push((states_path, FileKind::State));
+/// The files read from the data directory and what each holds, in load
+/// order. Of the country, province and lake files only the first present,
+/// the most detailed, is read.
+const FILES: &[(&str, FileKind)] = &[
+    ("ne_110m_coastline.json", FileKind::Coastline(Lod::Low)),
+    ("natural-earth.json", FileKind::Coastline(Lod::Medium)),
+    ("ne_50m_coastline.json", FileKind::Coastline(Lod::Medium)),
+    ("ne_10m_coastline.json", FileKind::Coastline(Lod::High)),
+    ("ne_50m_borders.json", FileKind::Border(Lod::Medium)),
+    ("ne_10m_borders.json", FileKind::Border(Lod::High)),
+    ("ne_10m_states.json", FileKind::State),
+    ("ne_10m_admin_2_counties.json", FileKind::County),
+    ("ne_10m_cities.json", FileKind::City),
+    ("ne_10m_admin_0_countries.json", FileKind::Country),
+    ("ne_50m_admin_0_countries.json", FileKind::Country),
+    ("ne_110m_admin_0_countries.json", FileKind::Country),
+    ("ne_10m_admin_1_states_provinces.json", FileKind::Province),
+    ("ne_50m_admin_1_states_provinces.json", FileKind::Province),
+    ("ne_10m_lakes.json", FileKind::Lake),
+    ("ne_50m_lakes.json", FileKind::Lake),
+    ("ne_110m_lakes.json", FileKind::Lake),
+    ("ne_10m_roads.json", FileKind::Route(Infrastructure::Road)),
+    ("ne_10m_railroads.json", FileKind::Route(Infrastructure::Railway)),
+    ("ne_10m_airports.json", FileKind::Facility(Infrastructure::Airport)),
+    ("ne_10m_ports.json", FileKind::Facility(Infrastructure::Port)),
+    ("ne_110m_land.json", FileKind::LandPolygon(Lod::Low)),
+    ("ne_50m_land.json", FileKind::LandPolygon(Lod::Medium)),
+    ("ne_10m_land.json", FileKind::LandPolygon(Lod::High)),
+];
+
+/// What a data file holds, by its name: one of `FILES`, or a GADM
+/// admin-2 file (`gadm41_<ISO>_2.json`) of counties
+fn file_kind(name: &str) -> Option<FileKind> {
+    if name.starts_with("gadm41_") && name.ends_with("_2.json") {
+        return Some(FileKind::County);
     }
+    FILES.iter().find(|(file, _)| *file == name).map(|&(_, kind)| kind)
+}
 
-    // Counties (NE + GADM)
-    let counties_path = data_dir.join("ne_10m_admin_2_counties.json");
-    if counties_path.exists() {
-        tasks.push((counties_path, FileKind::County));
+/// The files present in `data_dir` to load, with what each holds
+fn file_tasks(data_dir: &Path) -> Vec<(PathBuf, FileKind)> {
+    let mut tasks: Vec<(PathBuf, FileKind)> = Vec::new();
+    for &(filename, kind) in FILES {
+        let first_only = matches!(kind, FileKind::Country | FileKind::Province | FileKind::Lake);
+        if first_only && tasks.iter().any(|(_, k)| *k == kind) {
+            continue;
+        }
+        let path = data_dir.join(filename);
+        if path.exists() {
+            tasks.push((path, kind));
+        }
     }
+
+    // Counties from GADM, alongside Natural Earth's
     if let Ok(entries) = fs::read_dir(data_dir) {
         for entry in entries.flatten() {
             let path = entry.path();
@@ -178,32 +374,191 @@ pub fn load_all_geojson(renderer: &mut MapRenderer, data_dir: &Path) -> Result<(
             }
         }
     }
+    tasks
+}
 
-    // Cities
-    let cities_path = data_dir.join("ne_10m_cities.json");
-    if cities_path.exists() {
-        tasks.push((cities_path, FileKind::City));
+/// Where the counties are kept cut into chunks
+fn county_chunk_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join("chunks").join("counties")
+}
+
+/// Name, size and modification time of each county file, so a chunk cut
+/// can tell whether it's stale. Empty when there are no county files.
+fn county_stamp(tasks: &[(PathBuf, FileKind)]) -> String {
+    let mut parts: Vec<String> = tasks
+        .iter()
+        .filter(|(_, kind)| *kind == FileKind::County)
+        .map(|(path, _)| {
+            let meta = fs::metadata(path).ok();
+            let modified = meta.as_ref().and_then(|m| m.modified().ok())
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_secs());
+            let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            format!("{name}:{}:{modified}", meta.map_or(0, |m| m.len()))
+        })
+        .collect();
+    parts.sort();
+    parts.join(";")
+}
+
+/// Cut the loaded counties into chunks under the data directory and let
+/// them go from memory; `county_chunks` then brings them back a chunk at a
+/// time as the view needs them. Returns whether there were any to cut. If
+/// the cut can't be written the counties stay loaded whole.
+pub fn cut_counties(renderer: &mut MapRenderer, data_dir: &Path) -> Result<bool> {
+    if renderer.counties.is_empty() {
+        return Ok(false);
     }
+    let stamp = county_stamp(&file_tasks(data_dir));
+    chunks::partition(&renderer.counties, &county_chunk_dir(data_dir), &stamp).context("cutting counties into chunks")?;
+    renderer.set_counties(Vec::new());
+    Ok(true)
+}
 
-    // Land polygons
-    for (filename, lod) in [
-        ("ne_110m_land.json", Lod::Low),
-        ("ne_50m_land.json", Lod::Medium),
-        ("ne_10m_land.json", Lod::High),
-    ] {
-        let path = data_dir.join(filename);
-        if path.exists() {
-            tasks.push((path, FileKind::LandPolygon(lod)));
-        }
+/// The counties streamed from their chunks, if they've been cut from the
+/// county files now present
+pub fn county_chunks(data_dir: &Path) -> Option<chunks::ChunkManager> {
+    let stamp = county_stamp(&file_tasks(data_dir));
+    let dir = county_chunk_dir(data_dir);
+    if stamp.is_empty() || !chunks::is_current(&dir, &stamp) {
+        return None;
+    }
+    chunks::ChunkManager::open(dir).ok()
+}
+
+/// Load all available Natural Earth GeoJSON data into the map renderer.
+/// Counties already cut into chunks are skipped, to be streamed in by
+/// `county_chunks`; otherwise they're loaded whole, for `cut_counties`.
+pub fn load_all_geojson(renderer: &mut MapRenderer, data_dir: &Path) -> Result<()> {
+    let mut tasks = file_tasks(data_dir);
+    let stamp = county_stamp(&tasks);
+    let chunked = !stamp.is_empty() && chunks::is_current(&county_chunk_dir(data_dir), &stamp);
+    if chunked {
+        tasks.retain(|(_, kind)| *kind != FileKind::County);
     }
 
     // Load + parse all files in parallel
+    let language = renderer.settings.names.as_deref();
     let results: Vec<LoadResult> = tasks
         .into_par_iter()
-        .map(|(path, kind)| load_file(&path, kind))
+        .map(|(path, kind)| load_file(&path, kind, language))
         .collect();
+    for result in &results {
+        if let LoadResult::Failed(filename, error) = result {
+            eprintln!("Warning: Failed to load {}: {}", filename, error);
+        }
+    }
+    merge(renderer, results);
+    Ok(())
+}
+
+/// Reload the layer a data file feeds after the file was edited, added or
+/// removed. The layer is loaded again from every file that feeds it, then
+/// replaces what was there (damage included), and its index is rebuilt.
+/// Returns the layer's name, or `None` if no layer reads the file, as when
+/// a more detailed one shadows it. A file that fails to parse leaves the
+/// layer as it was.
+pub fn reload_file(renderer: &mut MapRenderer, data_dir: &Path, changed: &Path) -> Result<Option<&'static str>> {
+    let Some(kind) = changed.file_name().and_then(|n| n.to_str()).and_then(file_kind) else { return Ok(None) };
+    let tasks: Vec<(PathBuf, FileKind)> = file_tasks(data_dir).into_iter().filter(|(_, k)| *k == kind).collect();
+    if changed.exists() && !tasks.iter().any(|(path, _)| path == changed) {
+        return Ok(None);
+    }
+    let language = renderer.settings.names.as_deref();
+    let results: Vec<LoadResult> = tasks.into_par_iter().map(|(path, kind)| load_file(&path, kind, language)).collect();
+    if let Some(LoadResult::Failed(filename, error)) = results.iter().find(|r| matches!(r, LoadResult::Failed(..))) {
+        bail!("{filename}: {error}");
+    }
+
+    match kind {
+        FileKind::Coastline(Lod::Low) => renderer.coastlines_low.clear(),
+        FileKind::Coastline(Lod::Medium) => renderer.coastlines_medium.clear(),
+        FileKind::Coastline(Lod::High) => renderer.coastlines_high.clear(),
+        FileKind::Border(Lod::High) => renderer.borders_high.clear(),
+        FileKind::Border(_) => renderer.borders_medium.clear(),
+        FileKind::State => renderer.states.clear(),
+        FileKind::County => renderer.counties.clear(),
+        FileKind::City => renderer.clear_cities(),
+        FileKind::LandPolygon(Lod::Low) => renderer.land_polygons_low.clear(),
+        FileKind::LandPolygon(Lod::Medium) => renderer.land_polygons_medium.clear(),
+        FileKind::LandPolygon(Lod::High) => renderer.land_polygons_high.clear(),
+        FileKind::Lake => renderer.lakes.clear(),
+        FileKind::Country => {
+            renderer.country_polygons.clear();
+            renderer.country_names.clear();
+            renderer.country_names_en.clear();
+            renderer.place_labels.retain(|p| p.kind != PlaceKind::Country);
+        }
+        FileKind::Province => renderer.place_labels.retain(|p| p.kind != PlaceKind::State),
+        FileKind::Route(Infrastructure::Railway) => renderer.railways.clear(),
+        FileKind::Route(_) => renderer.roads.clear(),
+        FileKind::Facility(facility) => renderer.facilities.retain(|f| f.kind != facility),
+    }
+    merge(renderer, results);
+
+    match kind {
+        FileKind::Coastline(lod) => renderer.reindex(Features::Coastlines(lod)),
+        FileKind::Border(lod) => renderer.reindex(Features::Borders(lod)),
+        FileKind::State => renderer.reindex(Features::States),
+        FileKind::County => renderer.reindex(Features::Counties),
+        FileKind::Route(Infrastructure::Railway) => renderer.reindex(Features::Railways),
+        FileKind::Route(_) => renderer.reindex(Features::Roads),
+        FileKind::Facility(_) => renderer.reindex(Features::Facilities),
+        FileKind::LandPolygon(_) => {
+            renderer.land_grid = None;
+            renderer.build_land_grid();
+        }
+        FileKind::Country => renderer.build_country_grid(),
+        FileKind::City | FileKind::Lake | FileKind::Province => {}
+    }
+    renderer.invalidate_cache();
+    Ok(Some(kind.layer()))
+}
 
-    // Merge results sequentially into renderer (just pushing to Vecs — fast)
+/// Whether a file feeds the detail tier `drop_tier` frees for `lod`
+fn in_tier(kind: FileKind, lod: Lod) -> bool {
+    match kind {
+        FileKind::Coastline(l) | FileKind::LandPolygon(l) => l == lod,
+        FileKind::Border(l) => l == lod && lod == Lod::High,
+        _ => false,
+    }
+}
+
+/// Whether `data_dir` has files for the detail tier `lod`, so it can be
+/// dropped to save memory and read again later
+pub fn has_tier_files(data_dir: &Path, lod: Lod) -> bool {
+    file_tasks(data_dir).iter().any(|&(_, kind)| in_tier(kind, lod))
+}
+
+/// A detail tier read back from its files, for `install_tier`
+pub struct TierData {
+    lod: Lod,
+    results: Vec<LoadResult>,
+}
+
+/// Read a detail tier dropped to save memory back from its files. Touches
+/// no renderer, so it can run off the main thread.
+pub fn read_tier(data_dir: &Path, lod: Lod, language: Option<&str>) -> Result<TierData> {
+    let tasks: Vec<(PathBuf, FileKind)> = file_tasks(data_dir).into_iter().filter(|&(_, kind)| in_tier(kind, lod)).collect();
+    let results: Vec<LoadResult> = tasks.into_par_iter().map(|(path, kind)| load_file(&path, kind, language)).collect();
+    if let Some(LoadResult::Failed(filename, error)) = results.iter().find(|r| matches!(r, LoadResult::Failed(..))) {
+        bail!("{filename}: {error}");
+    }
+    Ok(TierData { lod, results })
+}
+
+/// Put a tier read by `read_tier` back in the renderer, and index it again
+pub fn install_tier(renderer: &mut MapRenderer, tier: TierData) {
+    renderer.drop_tier(tier.lod);
+    merge(renderer, tier.results);
+    renderer.reindex(Features::Coastlines(tier.lod));
+    if tier.lod == Lod::High {
+        renderer.reindex(Features::Borders(tier.lod));
+    }
+}
+
+/// Merge loaded files into the renderer, in order (just pushing to Vecs — fast)
+fn merge(renderer: &mut MapRenderer, results: Vec<LoadResult>) {
     for result in results {
         match result {
             LoadResult::Lines(lines, kind) => {
@@ -227,6 +582,8 @@ pub fn load_all_geojson(renderer: &mut MapRenderer, data_dir: &Path) -> Result<(
                     }
                     FileKind::State => renderer.states.extend(lines),
                     FileKind::County => renderer.counties.extend(lines),
+                    FileKind::Route(Infrastructure::Railway) => renderer.railways.extend(lines.into_iter().map(Route::new)),
+                    FileKind::Route(_) => renderer.roads.extend(lines.into_iter().map(Route::new)),
                     _ => {}
                 }
             }
@@ -235,21 +592,32 @@ pub fn load_all_geojson(renderer: &mut MapRenderer, data_dir: &Path) -> Result<(
                     renderer.add_land_polygon(rings, lod);
                 }
             }
+            LoadResult::Lakes(polygons) => renderer.lakes.extend(polygons.into_iter().map(Polygon::new)),
             LoadResult::Cities(cities) => {
                 for city in cities {
                     renderer.add_city(
                         city.lon, city.lat, &city.name,
-                        city.population, city.is_capital, city.is_megacity,
+                        city.population, city.is_capital, city.is_megacity, &city.country,
                     );
+                    if let Some(english_name) = city.english_name {
+                        let last = renderer.city_grid.len() - 1;
+                        renderer.city_grid.get_mut(last).expect("just added").english_name = Some(english_name);
+                    }
                 }
             }
-            LoadResult::Failed(filename, error) => {
-                eprintln!("Warning: Failed to load {}: {}", filename, error);
+            LoadResult::Countries(countries, names, english, labels) => {
+                renderer.country_polygons.extend(
+                    countries.into_iter().map(|(code, rings)| (code, Polygon::new(rings))),
+                );
+                renderer.country_names.extend(names);
+                renderer.country_names_en.extend(english);
+                renderer.place_labels.extend(labels);
             }
+            LoadResult::Places(labels) => renderer.place_labels.extend(labels),
+            LoadResult::Facilities(facilities) => renderer.facilities.extend(facilities),
+            LoadResult::Failed(..) => {}
         }
     }
-
-    Ok(())
 }
 
 /// Process GeoJSON and extract line features
@@ -291,18 +659,18 @@ where
                 add_line(line);
             }
         }
+        // Every ring is outlined, holes included: an enclave's border is
+        // its surroundings' hole
         Value::Polygon(rings) => {
-            if let Some(exterior) = rings.first() {
-                let line: Vec<(f64, f64)> = exterior.iter().map(|c| (c[0], c[1])).collect();
+            for ring in rings {
+                let line: Vec<(f64, f64)> = ring.iter().map(|c| (c[0], c[1])).collect();
                 add_line(line);
             }
         }
         Value::MultiPolygon(polygons) => {
-            for rings in polygons {
-                if let Some(exterior) = rings.first() {
-                    let line: Vec<(f64, f64)> = exterior.iter().map(|c| (c[0], c[1])).collect();
-                    add_line(line);
-                }
+            for ring in polygons.iter().flatten() {
+                let line: Vec<(f64, f64)> = ring.iter().map(|c| (c[0], c[1])).collect();
+                add_line(line);
             }
         }
         Value::GeometryCollection(geometries) => {
@@ -455,18 +823,125 @@ pub fn generate_simple_world(renderer: &mut MapRenderer) {
         Lod::Low,
     );
 
-    // Major cities with populations (is_capital, is_megacity)
-    renderer.add_city(-74.0, 40.7, "New York", 18_800_000, false, true);
-    renderer.add_city(-0.1, 51.5, "London", 9_000_000, true, true);
-    renderer.add_city(2.3, 48.9, "Paris", 11_000_000, true, true);
-    renderer.add_city(139.7, 35.7, "Tokyo", 37_400_000, true, true);
-    renderer.add_city(151.2, -33.9, "Sydney", 5_300_000, false, false);
-    renderer.add_city(-43.2, -22.9, "Rio", 13_500_000, false, true);
-    renderer.add_city(37.6, 55.8, "Moscow", 12_500_000, true, true);
-    renderer.add_city(116.4, 39.9, "Beijing", 21_500_000, true, true);
-    renderer.add_city(77.2, 28.6, "Delhi", 32_900_000, true, true);
-    renderer.add_city(-118.2, 34.0, "Los Angeles", 12_400_000, false, true);
-    renderer.add_city(-77.0, 38.9, "Washington", 5_300_000, true, false);
-    renderer.add_city(-99.1, 19.4, "Mexico City", 21_800_000, true, true);
-    renderer.add_city(-58.4, -34.6, "Buenos Aires", 15_000_000, true, true);
+    // Major cities with populations (is_capital, is_megacity, country)
+    renderer.add_city(-74.0, 40.7, "New York", 18_800_000, false, true, "USA");
+    renderer.add_city(-0.1, 51.5, "London", 9_000_000, true, true, "GBR");
+    renderer.add_city(2.3, 48.9, "Paris", 11_000_000, true, true, "FRA");
+    renderer.add_city(139.7, 35.7, "Tokyo", 37_400_000, true, true, "JPN");
+    renderer.add_city(151.2, -33.9, "Sydney", 5_300_000, false, false, "AUS");
+    renderer.add_city(-43.2, -22.9, "Rio", 13_500_000, false, true, "BRA");
+    renderer.add_city(37.6, 55.8, "Moscow", 12_500_000, true, true, "RUS");
+    renderer.add_city(116.4, 39.9, "Beijing", 21_500_000, true, true, "CHN");
+    renderer.add_city(77.2, 28.6, "Delhi", 32_900_000, true, true, "IND");
+    renderer.add_city(-118.2, 34.0, "Los Angeles", 12_400_000, false, true, "USA");
+    renderer.add_city(-77.0, 38.9, "Washington", 5_300_000, true, false, "USA");
+    renderer.add_city(-99.1, 19.4, "Mexico City", 21_800_000, true, true, "MEX");
+    renderer.add_city(-58.4, -34.6, "Buenos Aires", 15_000_000, true, true, "ARG");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_edited_file_reloads_just_its_layer() {
+        let dir = std::env::temp_dir().join(format!("tui-map-reload-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let states = dir.join("ne_10m_states.json");
+        let lines = |n: usize| {
+            let features: Vec<String> = (0..n)
+                .map(|i| format!(r#"{{"type":"Feature","properties":{{}},"geometry":{{"type":"LineString","coordinates":[[{i},0],[{i},5]]}}}}"#))
+                .collect();
+            format!(r#"{{"type":"FeatureCollection","features":[{}]}}"#, features.join(","))
+        };
+        fs::write(&states, lines(1)).unwrap();
+        let mut renderer = MapRenderer::new();
+        generate_simple_world(&mut renderer);
+        load_all_geojson(&mut renderer, &dir).unwrap();
+        let coastlines = renderer.coastlines_low.len();
+        assert_eq!(renderer.states.len(), 1);
+
+        fs::write(&states, lines(3)).unwrap();
+        assert_eq!(reload_file(&mut renderer, &dir, &states).unwrap(), Some("states"));
+        assert_eq!(renderer.states.len(), 3);
+        assert_eq!(renderer.coastlines_low.len(), coastlines, "other layers are left alone");
+
+        // A half-written file keeps what was there; an unknown one is ignored
+        fs::write(&states, "{\"type\": \"Feat").unwrap();
+        assert!(reload_file(&mut renderer, &dir, &states).is_err());
+        assert_eq!(renderer.states.len(), 3);
+        assert_eq!(reload_file(&mut renderer, &dir, &dir.join("mine.json")).unwrap(), None);
+
+        fs::remove_file(&states).unwrap();
+        assert_eq!(reload_file(&mut renderer, &dir, &states).unwrap(), Some("states"));
+        assert!(renderer.states.is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn counties_are_cut_into_chunks_once_and_then_streamed() {
+        let dir = std::env::temp_dir().join(format!("tui-map-counties-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("ne_10m_admin_2_counties.json"),
+            r#"{"type":"FeatureCollection","features":[{"type":"Feature","properties":{},"geometry":{"type":"LineString","coordinates":[[-100,40],[-99,41]]}}]}"#,
+        )
+        .unwrap();
+        assert!(county_chunks(&dir).is_none());
+
+        // Loading alone writes nothing
+        let mut renderer = MapRenderer::new();
+        load_all_geojson(&mut renderer, &dir).unwrap();
+        assert_eq!(renderer.counties.len(), 1);
+        let chunk_dir = county_chunk_dir(&dir);
+        assert!(!chunk_dir.exists());
+
+        assert!(cut_counties(&mut renderer, &dir).unwrap());
+        assert!(renderer.counties.is_empty(), "cut counties leave memory");
+        assert!(county_chunks(&dir).is_some());
+
+        // The next run reads the cut rather than the GeoJSON
+        let cut = fs::metadata(chunk_dir.join("manifest")).unwrap().modified().unwrap();
+        let mut renderer = MapRenderer::new();
+        load_all_geojson(&mut renderer, &dir).unwrap();
+        assert!(!cut_counties(&mut renderer, &dir).unwrap());
+        assert_eq!(fs::metadata(chunk_dir.join("manifest")).unwrap().modified().unwrap(), cut);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn names_load_in_the_chosen_language() {
+        let city = |name: &str, props: &str| {
+            format!(r#"{{"type":"Feature","properties":{{"name":"{name}","pop_max":1000000{props}}},"geometry":{{"type":"Point","coordinates":[116.4,39.9]}}}}"#)
+        };
+        let geojson: GeoJson = format!(
+            r#"{{"type":"FeatureCollection","features":[{},{}]}}"#,
+            city("Beijing", r#","name_zh":"北京","name_ru":"""#),
+            city("Paris", "")
+        )
+        .parse()
+        .unwrap();
+        let names = |language| extract_cities(&geojson, language).into_iter().map(|c| c.name).collect::<Vec<_>>();
+        assert_eq!(names(None), ["Beijing", "Paris"]);
+        assert_eq!(names(Some("zh")), ["北京", "Paris"]);
+        assert_eq!(names(Some("ru")), ["Beijing", "Paris"], "an empty name falls back");
+
+        // The English names still find what's shown in another language
+        let countries: GeoJson = r#"{"type":"FeatureCollection","features":[{"type":"Feature","properties":{"ADM0_A3":"CHN","ADMIN":"China","NAME_ZH":"中国"},"geometry":{"type":"Polygon","coordinates":[[[100,30],[110,30],[110,40],[100,30]]]}}]}"#
+            .parse()
+            .unwrap();
+        let (polygons, country_names, english) = extract_countries(&countries, Some("zh"));
+        let mut renderer = MapRenderer::new();
+        merge(&mut renderer, vec![
+            LoadResult::Cities(extract_cities(&geojson, Some("zh"))),
+            LoadResult::Countries(polygons, country_names, english, Vec::new()),
+        ]);
+        assert_eq!(renderer.find_city("beijing").map(|c| c.name.as_str()), Some("北京"));
+        assert_eq!(renderer.find_city("北京").map(|c| c.name.as_str()), Some("北京"));
+        assert_eq!(renderer.find_city("Par").map(|c| c.name.as_str()), Some("Paris"));
+        assert_eq!(renderer.find_country("china").map(|(name, _)| name), Some("中国"));
+        assert_eq!(renderer.find_country("中国").map(|(name, _)| name), Some("中国"));
+    }
 }
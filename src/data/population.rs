@@ -0,0 +1,196 @@
+//! Gridded population, for casualties outside the city list.
+//!
+//! Rasters are read in the ESRI ASCII grid format that GPW (Gridded
+//! Population of the World) ships its population-count layers in, with
+//! people per cell. Any resolution is resampled onto a fixed 0.25° grid.
+
+use crate::geo::{great_circle_km, normalize_lat, normalize_lon};
+use crate::map::renderer::City;
+use crate::data::raster::AsciiGrid;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+pub struct PopulationGrid {
+    /// People per cell
+    cells: Vec<f32>,
+    /// People per cell before any strikes, for resets
+    original: Vec<f32>,
+    /// City populations already taken out (survives resets)
+    cities_excluded: bool,
+}
+
+impl PopulationGrid {
+    pub const RESOLUTION: f64 = 0.25;
+    const WIDTH: usize = 1440;
+    const HEIGHT: usize = 720;
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("reading population raster {}", path.display()))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let raster = AsciiGrid::parse(content)?;
+        let mut cells = vec![0.0; Self::WIDTH * Self::HEIGHT];
+        // Coarse source cells are spread evenly over the fine cells they cover
+        let split = (raster.cellsize / Self::RESOLUTION).round().max(1.0) as usize;
+        let step = raster.cellsize / split as f64;
+        raster.for_each_cell("population", |lon, lat, people| {
+            if people <= 0.0 {
+                return;
+            }
+            let share = (people / (split * split) as f64) as f32;
+            for i in 0..split {
+                for j in 0..split {
+                    let idx = Self::index(lon + (i as f64 + 0.5) * step, lat + (j as f64 + 0.5) * step);
+                    cells[idx] += share;
+                }
+            }
+        })?;
+        Ok(Self { original: cells.clone(), cells, cities_excluded: false })
+    }
+
+    fn index(lon: f64, lat: f64) -> usize {
+        let col = ((normalize_lon(lon) / Self::RESOLUTION) as usize).min(Self::WIDTH - 1);
+        let row = ((normalize_lat(lat) / Self::RESOLUTION) as usize).min(Self::HEIGHT - 1);
+        row * Self::WIDTH + col
+    }
+
+    /// Center of a cell
+    fn center(idx: usize) -> (f64, f64) {
+        let (row, col) = (idx / Self::WIDTH, idx % Self::WIDTH);
+        (
+            (col as f64 + 0.5) * Self::RESOLUTION - 180.0,
+            (row as f64 + 0.5) * Self::RESOLUTION - 90.0,
+        )
+    }
+
+    /// Take listed cities out of the raster so their people aren't counted
+    /// twice. What's left is the population outside the city list. Only the
+    /// first call counts, so reloading the same world after a reset is safe.
+    pub fn exclude_cities<'a>(&mut self, cities: impl IntoIterator<Item = &'a City>) {
+        if std::mem::replace(&mut self.cities_excluded, true) {
+            return;
+        }
+        for city in cities {
+            let idx = Self::index(city.lon, city.lat);
+            self.original[idx] = (self.original[idx] - city.original_population as f32).max(0.0);
+            self.cells[idx] = self.cells[idx].min(self.original[idx]);
+        }
+    }
+
+    /// Bring everyone back (session reset)
+    pub fn restore(&mut self) {
+        self.cells.copy_from_slice(&self.original);
+    }
+
+    pub fn total(&self) -> f64 {
+        self.cells.iter().map(|&p| p as f64).sum()
+    }
+
+    /// People killed since load
+    pub fn casualties(&self) -> u64 {
+        let original: f64 = self.original.iter().map(|&p| p as f64).sum();
+        (original - self.total()).max(0.0).round() as u64
+    }
+
+    pub fn people_at(&self, lon: f64, lat: f64) -> f32 {
+        self.cells[Self::index(lon, lat)]
+    }
+
+    /// People per km² at a point
+    pub fn density_at(&self, lon: f64, lat: f64) -> f32 {
+        let cell_km = Self::RESOLUTION * 111.0;
+        let area = cell_km * cell_km * lat.to_radians().cos().max(0.01);
+        self.people_at(lon, lat) / area as f32
+    }
+
+    /// Kill people in every cell whose center lies within `radius_km` of
+    /// (lon, lat). `lethality` maps distance in km to the fraction killed.
+    /// Returns the number killed.
+    pub fn kill_within(&mut self, lon: f64, lat: f64, radius_km: f64, lethality: impl Fn(f64) -> f64) -> u64 {
+        let dlat = radius_km / 111.0;
+        let dlon = (dlat / lat.to_radians().cos().max(0.05)).min(180.0);
+        let row_of = |lat: f64| ((normalize_lat(lat) / Self::RESOLUTION) as usize).min(Self::HEIGHT - 1);
+        let (first_col, cols) = (
+            (normalize_lon(lon - dlon) / Self::RESOLUTION) as usize,
+            (2.0 * dlon / Self::RESOLUTION).ceil() as usize + 1,
+        );
+        let mut killed = 0.0;
+        for row in row_of(lat - dlat)..=row_of(lat + dlat) {
+            // Columns wrap around the antimeridian
+            for col in (first_col..first_col + cols.min(Self::WIDTH)).map(|c| c % Self::WIDTH) {
+                let idx = row * Self::WIDTH + col;
+                if self.cells[idx] <= 0.0 {
+                    continue;
+                }
+                let (cx, cy) = Self::center(idx);
+                let dist = great_circle_km(lon, lat, cx, cy);
+                if dist <= radius_km {
+                    let dead = self.cells[idx] * lethality(dist).clamp(0.0, 1.0) as f32;
+                    self.cells[idx] -= dead;
+                    killed += dead as f64;
+                }
+            }
+        }
+        killed as u64
+    }
+
+    /// Kill `fraction` of the people in the cell at (lon, lat)
+    pub fn kill_at(&mut self, lon: f64, lat: f64, fraction: f64) -> u64 {
+        let cell = &mut self.cells[Self::index(lon, lat)];
+        let dead = *cell * fraction.clamp(0.0, 1.0) as f32;
+        *cell -= dead;
+        dead as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RASTER: &str = "\
+ncols 4
+nrows 2
+xllcorner 10
+yllcorner 40
+cellsize 0.5
+NODATA_value -9999
+400 0 -9999 80
+4 4 4 4
+";
+
+    #[test]
+    fn parses_and_resamples_ascii_grid() {
+        let grid = PopulationGrid::parse(RASTER).unwrap();
+        assert!((grid.total() - 496.0).abs() < 1e-3);
+        // Each 0.5° cell is spread over four 0.25° cells; the top row is 40.5–41°N
+        assert_eq!(grid.people_at(10.1, 40.9), 100.0);
+        assert_eq!(grid.people_at(10.4, 40.6), 100.0);
+        assert_eq!(grid.people_at(11.1, 40.9), 0.0);
+        assert_eq!(grid.people_at(11.6, 40.9), 20.0);
+        assert_eq!(grid.people_at(10.1, 40.1), 1.0);
+
+        assert!(PopulationGrid::parse("ncols 2\nnrows 1\nxllcorner 0\nyllcorner 0\ncellsize 1\n5").is_err());
+        assert!(PopulationGrid::parse("ncols 1\nnrows 1\ncellsize 1\n5").is_err());
+        assert!(PopulationGrid::parse("ncols 0\nnrows 1\nxllcorner 0\nyllcorner 0\ncellsize 1\n").is_err());
+        assert!(PopulationGrid::parse("ncols 1\nnrows 2.5\nxllcorner 0\nyllcorner 0\ncellsize 1\n5").is_err());
+        assert!(PopulationGrid::parse("ncols 1\nnrows 1\nxllcorner 0\nyllcorner 0\ncellsize 1\n5 6").is_err());
+    }
+
+    #[test]
+    fn kills_only_inside_the_radius() {
+        let mut grid = PopulationGrid::parse(RASTER).unwrap();
+        let killed = grid.kill_within(10.25, 40.75, 30.0, |_| 1.0);
+        assert_eq!(killed, 400);
+        assert_eq!(grid.people_at(10.1, 40.9), 0.0);
+        assert_eq!(grid.people_at(11.6, 40.9), 20.0);
+        assert_eq!(grid.kill_at(11.6, 40.9, 0.5), 10);
+        assert_eq!(grid.casualties(), 410);
+
+        grid.restore();
+        assert_eq!(grid.casualties(), 0);
+        assert_eq!(grid.people_at(10.1, 40.9), 100.0);
+    }
+}
@@ -0,0 +1,87 @@
+//! ESRI ASCII grids, the plain-text raster format gridded population and
+//! land-cover layers ship in: a short header (`ncols`, `nrows`,
+//! `xllcorner`, `yllcorner`, `cellsize`, optional `NODATA_value`), then rows
+//! of values from north to south. The `xllcenter`/`yllcenter` forms work
+//! too.
+
+use anyhow::{bail, Context, Result};
+use std::iter::Peekable;
+use std::str::SplitWhitespace;
+
+pub struct AsciiGrid<'a> {
+    pub ncols: usize,
+    pub nrows: usize,
+    pub cellsize: f64,
+    /// Lower-left corner of the lower-left cell
+    pub x0: f64,
+    pub y0: f64,
+    nodata: Option<f64>,
+    values: Peekable<SplitWhitespace<'a>>,
+}
+
+impl<'a> AsciiGrid<'a> {
+    /// Read the header, leaving the values for `for_each_cell`
+    pub fn parse(content: &'a str) -> Result<Self> {
+        let mut tokens = content.split_whitespace().peekable();
+        let (mut ncols, mut nrows, mut cellsize) = (None, None, None);
+        let (mut x0, mut y0, mut nodata) = (None, None, None);
+        let mut centered = false;
+        while let Some(key) = tokens.next_if(|t| t.starts_with(|c: char| c.is_ascii_alphabetic())) {
+            let value = tokens.next().with_context(|| format!("missing value for {key}"))?;
+            let number = || value.parse::<f64>().with_context(|| format!("invalid {key}: {value}"));
+            let count = || match value.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(n),
+                _ => bail!("{key} must be a positive whole number: {value}"),
+            };
+            match key.to_ascii_lowercase().as_str() {
+                "ncols" => ncols = Some(count()?),
+                "nrows" => nrows = Some(count()?),
+                "cellsize" => cellsize = Some(number()?),
+                "xllcorner" => x0 = Some(number()?),
+                "yllcorner" => y0 = Some(number()?),
+                "xllcenter" => (x0, centered) = (Some(number()?), true),
+                "yllcenter" => (y0, centered) = (Some(number()?), true),
+                "nodata_value" => nodata = Some(number()?),
+                _ => bail!("unknown raster header key {key}"),
+            }
+        }
+        let (Some(ncols), Some(nrows), Some(cellsize), Some(x0), Some(y0)) = (ncols, nrows, cellsize, x0, y0) else {
+            bail!("raster header needs ncols, nrows, xllcorner, yllcorner and cellsize");
+        };
+        if !(cellsize > 0.0 && cellsize.is_finite()) {
+            bail!("invalid cellsize: {cellsize}");
+        }
+        // Every value takes at least a digit and a separator, so a header
+        // promising more cells than that is wrong (and would have callers
+        // allocate for them)
+        if ncols.checked_mul(nrows).is_none_or(|cells| cells > content.len() / 2 + 1) {
+            bail!("raster of {ncols}×{nrows} cells is larger than its file");
+        }
+        let (x0, y0) = if centered { (x0 - cellsize / 2.0, y0 - cellsize / 2.0) } else { (x0, y0) };
+        Ok(Self { ncols, nrows, cellsize, x0, y0, nodata, values: tokens })
+    }
+
+    /// Call `f` with the lower-left corner and value of every cell that has
+    /// data. `what` names the values in errors.
+    pub fn for_each_cell(self, what: &str, mut f: impl FnMut(f64, f64, f64)) -> Result<()> {
+        let mut count = 0;
+        for token in self.values {
+            let value: f64 = token.parse().with_context(|| format!("invalid {what} value: {token}"))?;
+            let (row, col) = (count / self.ncols, count % self.ncols);
+            count += 1;
+            if row >= self.nrows {
+                bail!("{what} raster has more than {} values", self.ncols * self.nrows);
+            }
+            if self.nodata == Some(value) {
+                continue;
+            }
+            let lon = self.x0 + col as f64 * self.cellsize;
+            let lat = self.y0 + (self.nrows - 1 - row) as f64 * self.cellsize;
+            f(lon, lat, value);
+        }
+        if count != self.ncols * self.nrows {
+            bail!("{what} raster expects {} values, found {count}", self.ncols * self.nrows);
+        }
+        Ok(())
+    }
+}
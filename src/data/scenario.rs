@@ -0,0 +1,391 @@
+//! Scenario scripts: a plain-text timeline of events.
+//!
+//! ```text
+//! # Comments start with '#'
+//! name "Pacific Exchange"
+//! at 0s     clock 3600x
+//! at 2s     fly Tokyo zoom 6
+//! at 5s     strike 139.7,35.7 with nuke
+//! at 30s    strike "Los Angeles" with chem radius 80
+//! at 40s    strike Seattle yield 1mt
+//! at 45s    wildfire California radius 150
+//! at 50s    volcano Fuji
+//! at 55s    hurricane 135,15
+//! at 1m     say "Second wave inbound"
+//! at 1m10s  ai hard
+//! ```
+//!
+//! Times are seconds of play from when the scenario starts (`30`, `30s`,
+//! `1m30s`, `t=30s`). Locations are `lon,lat` or a place name — a loaded
+//! city or a gazetteer entry — resolved when the event fires. A volcano is
+//! named from the built-in list, or placed anywhere like the others.
+
+use crate::ai::Difficulty;
+use crate::app::{WeaponType, Yield};
+use crate::sim::ClockSpeed;
+use anyhow::{anyhow, bail, Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// Frames per scenario second (the main loop targets ~60fps)
+pub const FRAMES_PER_SECOND: u64 = 60;
+
+/// Default blast radius for scripted strikes (km)
+const DEFAULT_STRIKE_RADIUS_KM: f64 = 100.0;
+/// Default wildfire radius (km)
+const DEFAULT_WILDFIRE_RADIUS_KM: f64 = 120.0;
+
+/// Where an event happens
+#[derive(Clone, Debug, PartialEq)]
+pub enum Location {
+    Coords(f64, f64),
+    /// Place name, looked up when the event fires
+    Named(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScenarioEvent {
+    Strike { at: Location, weapon: WeaponType, radius_km: f64 },
+    Wildfire { at: Location, radius_km: f64 },
+    Eruption { at: Location },
+    Hurricane { at: Location },
+    FlyTo { at: Location, zoom: Option<f64> },
+    Clock(ClockSpeed),
+    Adversary(Option<Difficulty>),
+    Message(String),
+}
+
+/// An event and the scenario frame it fires on
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduledEvent {
+    pub frame: u64,
+    pub event: ScenarioEvent,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Scenario {
+    pub name: Option<String>,
+    /// Sorted by frame; events on the same frame keep file order
+    pub events: Vec<ScheduledEvent>,
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("in scenario {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut scenario = Self::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            parse_line(line, &mut scenario).with_context(|| format!("line {}: {line}", i + 1))?;
+        }
+        scenario.events.sort_by_key(|e| e.frame);
+        Ok(scenario)
+    }
+}
+
+fn parse_line(line: &str, scenario: &mut Scenario) -> Result<()> {
+    let tokens = tokenize(line)?;
+    let mut iter = tokens.into_iter().peekable();
+    match iter.next().as_deref() {
+        Some("name") => {
+            scenario.name = Some(iter.next().ok_or_else(|| anyhow!("name needs a value"))?);
+            Ok(())
+        }
+        Some("at") => {
+            let time = iter.next().ok_or_else(|| anyhow!("missing time"))?;
+            let frame = parse_time(&time)?;
+            let command = iter.next().ok_or_else(|| anyhow!("missing command"))?;
+            let args: Vec<String> = iter.collect();
+            let event = parse_event(&command, &args)?;
+            scenario.events.push(ScheduledEvent { frame, event });
+            Ok(())
+        }
+        Some(other) => bail!("expected `at` or `name`, found `{other}`"),
+        None => Ok(()),
+    }
+}
+
+/// Split on whitespace, keeping "quoted strings" together
+fn tokenize(line: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut s = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(ch) => s.push(ch),
+                    None => bail!("unterminated string"),
+                }
+            }
+            tokens.push(s);
+        } else {
+            let mut s = String::new();
+            while let Some(&ch) = chars.peek() {
+                if ch.is_whitespace() {
+                    break;
+                }
+                s.push(ch);
+                chars.next();
+            }
+            tokens.push(s);
+        }
+    }
+    Ok(tokens)
+}
+
+/// `30`, `30s`, `1m30s`, `2m`, with an optional `t=` prefix → frames
+fn parse_time(s: &str) -> Result<u64> {
+    let s = s.strip_prefix("t=").unwrap_or(s);
+    let bad = || anyhow!("invalid time `{s}`");
+    let (minutes, rest) = match s.split_once('m') {
+        Some((m, rest)) => (m.parse::<f64>().map_err(|_| bad())?, rest),
+        None => (0.0, s),
+    };
+    let rest = rest.strip_suffix('s').unwrap_or(rest);
+    let seconds = if rest.is_empty() { 0.0 } else { rest.parse::<f64>().map_err(|_| bad())? };
+    let total = minutes * 60.0 + seconds;
+    if total < 0.0 || !total.is_finite() {
+        return Err(bad());
+    }
+    Ok((total * FRAMES_PER_SECOND as f64).round() as u64)
+}
+
+/// Keywords that end a bare-word place name
+const KEYWORDS: &[&str] = &["with", "radius", "zoom", "nuke", "bio", "emp", "chem", "airburst", "tsunami", "asteroid", "yield"];
+
+/// Location from the front of `args`; returns it and the remaining args
+fn parse_location(args: &[String]) -> Result<(Location, &[String])> {
+    let first = args.first().ok_or_else(|| anyhow!("missing location"))?;
+    if let Some((lon, lat)) = first.split_once(',') {
+        if let (Ok(lon), Ok(lat)) = (lon.trim().parse::<f64>(), lat.trim().parse::<f64>()) {
+            if !(-90.0..=90.0).contains(&lat) {
+                bail!("latitude out of range: {lat}");
+            }
+            return Ok((Location::Coords(lon, lat), &args[1..]));
+        }
+    }
+    let words = args.iter().take_while(|a| !KEYWORDS.contains(&a.to_lowercase().as_str())).count().max(1);
+    Ok((Location::Named(args[..words].join(" ")), &args[words..]))
+}
+
+fn parse_weapon(s: &str) -> Result<WeaponType> {
+    WeaponType::parse(s).ok_or_else(|| anyhow!("unknown weapon `{s}`"))
+}
+
+/// `key value` pairs after the location, e.g. `with chem radius 80`
+fn parse_options<'a>(args: &'a [String], allowed: &[&str]) -> Result<Vec<(&'a str, &'a str)>> {
+    let mut out = Vec::new();
+    let mut iter = args.iter();
+    while let Some(key) = iter.next() {
+        // Weapons may be given bare: `strike Paris chem`
+        if allowed.contains(&"with") && parse_weapon(key).is_ok() {
+            out.push(("with", key.as_str()));
+            continue;
+        }
+        if !allowed.contains(&key.as_str()) {
+            bail!("unexpected `{key}`");
+        }
+        let value = iter.next().ok_or_else(|| anyhow!("`{key}` needs a value"))?;
+        out.push((key.as_str(), value.as_str()));
+    }
+    Ok(out)
+}
+
+fn parse_km(s: &str) -> Result<f64> {
+    let v: f64 = s.trim_end_matches("km").parse().map_err(|_| anyhow!("invalid radius `{s}`"))?;
+    if v <= 0.0 {
+        bail!("radius must be positive");
+    }
+    Ok(v)
+}
+
+fn parse_event(command: &str, args: &[String]) -> Result<ScenarioEvent> {
+    match command {
+        "strike" => {
+            let (at, rest) = parse_location(args)?;
+            let mut weapon = WeaponType::Nuke;
+            let mut radius_km = None;
+            let mut warhead = None;
+            for (key, value) in parse_options(rest, &["with", "radius", "yield"])? {
+                match key {
+                    "with" => weapon = parse_weapon(value)?,
+                    "yield" => {
+                        warhead = Some(Yield::parse(value).ok_or_else(|| {
+                            anyhow!("unknown yield `{value}` (expected 15kt, 300kt, 1mt or 50mt)")
+                        })?)
+                    }
+                    _ => radius_km = Some(parse_km(value)?),
+                }
+            }
+            // An explicit radius wins over one derived from the yield
+            let radius_km = radius_km
+                .or_else(|| warhead.map(|w| w.blast_radius_km() * weapon.radius_scale()))
+                .unwrap_or(DEFAULT_STRIKE_RADIUS_KM);
+            Ok(ScenarioEvent::Strike { at, weapon, radius_km })
+        }
+        "wildfire" => {
+            let (at, rest) = parse_location(args)?;
+            let mut radius_km = DEFAULT_WILDFIRE_RADIUS_KM;
+            for (_, value) in parse_options(rest, &["radius"])? {
+                radius_km = parse_km(value)?;
+            }
+            Ok(ScenarioEvent::Wildfire { at, radius_km })
+        }
+        "volcano" => {
+            let (at, rest) = parse_location(args)?;
+            parse_options(rest, &[])?;
+            Ok(ScenarioEvent::Eruption { at })
+        }
+        "hurricane" => {
+            let (at, rest) = parse_location(args)?;
+            parse_options(rest, &[])?;
+            Ok(ScenarioEvent::Hurricane { at })
+        }
+        "fly" => {
+            let (at, rest) = parse_location(args)?;
+            let mut zoom = None;
+            for (_, value) in parse_options(rest, &["zoom"])? {
+                zoom = Some(value.parse().map_err(|_| anyhow!("invalid zoom `{value}`"))?);
+            }
+            Ok(ScenarioEvent::FlyTo { at, zoom })
+        }
+        "clock" => {
+            let speed = match args.first().map(|s| s.as_str()) {
+                Some("pause") | Some("paused") => ClockSpeed::Paused,
+                Some("1x") => ClockSpeed::RealTime,
+                Some("60x") => ClockSpeed::Minute,
+                Some("3600x") => ClockSpeed::Hour,
+                other => bail!("clock speed must be pause, 1x, 60x or 3600x (got {other:?})"),
+            };
+            Ok(ScenarioEvent::Clock(speed))
+        }
+        "ai" => {
+            let level = args.first().ok_or_else(|| anyhow!("ai needs a difficulty"))?;
+            match level.as_str() {
+                "off" => Ok(ScenarioEvent::Adversary(None)),
+                _ => Difficulty::parse(level)
+                    .map(|d| ScenarioEvent::Adversary(Some(d)))
+                    .ok_or_else(|| anyhow!("invalid difficulty `{level}`")),
+            }
+        }
+        "say" | "message" => {
+            if args.is_empty() {
+                bail!("{command} needs text");
+            }
+            Ok(ScenarioEvent::Message(args.join(" ")))
+        }
+        other => bail!("unknown command `{other}`"),
+    }
+}
+
+/// Plays a scenario against the frame counter
+pub struct ScenarioRunner {
+    pub scenario: Scenario,
+    next: usize,
+    elapsed: u64,
+}
+
+impl ScenarioRunner {
+    pub fn new(scenario: Scenario) -> Self {
+        Self { scenario, next: 0, elapsed: 0 }
+    }
+
+    /// Advance one frame and return the events now due
+    pub fn tick(&mut self) -> Vec<ScenarioEvent> {
+        let mut due = Vec::new();
+        while let Some(e) = self.scenario.events.get(self.next) {
+            if e.frame > self.elapsed {
+                break;
+            }
+            due.push(e.event.clone());
+            self.next += 1;
+        }
+        self.elapsed += 1;
+        due
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.scenario.events.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_timeline() {
+        let s = Scenario::parse(
+            "# demo\nname \"Test run\"\nat 1m strike \"Los Angeles\" with chem radius 80km\n\
+             at t=30s strike 139.7,35.7 nuke\nat 45 wildfire San Francisco\nat 0s clock 3600x\n\
+             at 2s fly Tokyo zoom 6\nat 1m10s ai hard\n",
+        )
+        .unwrap();
+        assert_eq!(s.name.as_deref(), Some("Test run"));
+        let frames: Vec<u64> = s.events.iter().map(|e| e.frame).collect();
+        assert_eq!(frames, vec![0, 120, 1800, 2700, 3600, 4200]);
+        assert_eq!(
+            s.events[2].event,
+            ScenarioEvent::Strike { at: Location::Coords(139.7, 35.7), weapon: WeaponType::Nuke, radius_km: 100.0 }
+        );
+        assert_eq!(
+            s.events[3].event,
+            ScenarioEvent::Wildfire { at: Location::Named("San Francisco".into()), radius_km: 120.0 }
+        );
+        assert_eq!(
+            s.events[4].event,
+            ScenarioEvent::Strike { at: Location::Named("Los Angeles".into()), weapon: WeaponType::Chem, radius_km: 80.0 }
+        );
+        assert_eq!(s.events[5].event, ScenarioEvent::Adversary(Some(Difficulty::Hard)));
+    }
+
+    #[test]
+    fn yield_sets_strike_radius() {
+        let s = Scenario::parse("at 1s strike Tokyo yield 1mt\nat 2s strike Osaka with emp yield 1MT radius 40\n").unwrap();
+        assert_eq!(
+            s.events[0].event,
+            ScenarioEvent::Strike { at: Location::Named("Tokyo".into()), weapon: WeaponType::Nuke, radius_km: 150.0 }
+        );
+        assert!(matches!(s.events[1].event, ScenarioEvent::Strike { radius_km: 40.0, .. }));
+        assert!(Scenario::parse("at 1s strike Tokyo yield 2mt").is_err());
+    }
+
+    #[test]
+    fn parses_natural_disasters() {
+        let s = Scenario::parse("at 1s volcano Mount St. Helens\nat 2s hurricane -60,15\n").unwrap();
+        assert_eq!(s.events[0].event, ScenarioEvent::Eruption { at: Location::Named("Mount St. Helens".into()) });
+        assert_eq!(s.events[1].event, ScenarioEvent::Hurricane { at: Location::Coords(-60.0, 15.0) });
+        assert!(Scenario::parse("at 1s hurricane Miami radius 50").is_err());
+    }
+
+    #[test]
+    fn reports_line_numbers() {
+        let err = Scenario::parse("at 1s say hi\n\nat 2s launch Paris\n").unwrap_err();
+        assert!(format!("{err:#}").contains("line 3"), "{err:#}");
+        assert!(Scenario::parse("at soon say hi").is_err());
+        assert!(Scenario::parse("at 1s strike 0,95").is_err());
+        assert!(Scenario::parse("at 1s say \"unterminated").is_err());
+    }
+
+    #[test]
+    fn runner_releases_events_on_time() {
+        let mut runner = ScenarioRunner::new(Scenario::parse("at 0 say a\nat 2 say b").unwrap());
+        assert_eq!(runner.tick(), vec![ScenarioEvent::Message("a".into())]);
+        for _ in 1..120 {
+            assert!(runner.tick().is_empty());
+        }
+        assert_eq!(runner.tick(), vec![ScenarioEvent::Message("b".into())]);
+        assert!(runner.is_finished());
+    }
+}
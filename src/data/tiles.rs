@@ -0,0 +1,761 @@
+//! Remote vector-tile basemap: Mapbox Vector Tile decoding, tile math,
+//! an on-disk tile cache, and a background fetch worker with an in-memory LRU.
+//!
+//! The render loop never blocks on I/O — `TileManager::update` enqueues
+//! missing tiles for the worker thread and drains whatever has finished.
+
+use crate::data::fetch::sha256_hex;
+use crate::map::LineString;
+use anyhow::{anyhow, bail, Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+
+/// Slippy-map tile address (XYZ scheme, y=0 at the north edge).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TileId {
+    pub z: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl TileId {
+    pub fn new(z: u8, x: u32, y: u32) -> Self {
+        Self { z, x, y }
+    }
+
+    /// Tile containing a geographic point at zoom `z`.
+    pub fn containing(lon: f64, lat: f64, z: u8) -> Self {
+        let n = (1u32 << z) as f64;
+        let lat_rad = lat.clamp(-85.0511, 85.0511).to_radians();
+        let x = ((lon + 180.0) / 360.0 * n).floor().clamp(0.0, n - 1.0) as u32;
+        let y = ((1.0 - (lat_rad.tan() + 1.0 / lat_rad.cos()).ln() / PI) / 2.0 * n)
+            .floor()
+            .clamp(0.0, n - 1.0) as u32;
+        Self { z, x, y }
+    }
+
+    /// Geographic bounds as (min_lon, min_lat, max_lon, max_lat).
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        let (min_lon, max_lat) = tile_to_lonlat(self.z, self.x as f64, self.y as f64);
+        let (max_lon, min_lat) = tile_to_lonlat(self.z, self.x as f64 + 1.0, self.y as f64 + 1.0);
+        (min_lon, min_lat, max_lon, max_lat)
+    }
+
+    /// Substitute `{z}`, `{x}`, `{y}` placeholders in a URL or path template.
+    pub fn fill_template(&self, template: &str) -> String {
+        template
+            .replace("{z}", &self.z.to_string())
+            .replace("{x}", &self.x.to_string())
+            .replace("{y}", &self.y.to_string())
+    }
+}
+
+/// Convert fractional tile coordinates at zoom `z` to (lon, lat).
+#[inline]
+fn tile_to_lonlat(z: u8, tx: f64, ty: f64) -> (f64, f64) {
+    let n = (1u32 << z) as f64;
+    let lon = tx / n * 360.0 - 180.0;
+    let lat = (PI * (1.0 - 2.0 * ty / n)).sinh().atan().to_degrees();
+    (lon, lat)
+}
+
+/// Pick a tile zoom so one tile spans roughly 256 braille pixels.
+pub fn tile_zoom_for(effective_zoom: f64, pixel_width: usize, max_zoom: u8) -> u8 {
+    let world_px = effective_zoom * pixel_width as f64;
+    (world_px / 256.0).log2().floor().clamp(0.0, max_zoom as f64) as u8
+}
+
+/// Tiles covering a geographic bounding box. Longitudes outside ±180 wrap.
+/// Capped to avoid flooding the worker when bounds are degenerate.
+pub fn tiles_for_bounds(min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64, z: u8) -> Vec<TileId> {
+    const MAX_TILES: usize = 64;
+    let n = 1i64 << z;
+    let top_left = TileId::containing(min_lon.max(-180.0), max_lat, z);
+    let bottom_right = TileId::containing(max_lon.min(180.0), min_lat, z);
+
+    // Horizontal span in tile units — may exceed one world when wrapping
+    let tile_deg = 360.0 / n as f64;
+    let x0 = ((min_lon + 180.0) / tile_deg).floor() as i64;
+    let x1 = ((max_lon + 180.0) / tile_deg).floor() as i64;
+    let x1 = x1.min(x0 + n - 1);
+
+    let mut tiles = Vec::new();
+    for y in top_left.y..=bottom_right.y {
+        for x in x0..=x1 {
+            tiles.push(TileId::new(z, x.rem_euclid(n) as u32, y));
+            if tiles.len() >= MAX_TILES {
+                return tiles;
+            }
+        }
+    }
+    tiles
+}
+
+// ── Protobuf wire-format reader (just enough for MVT) ──────────────────────
+
+struct PbReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> PbReader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self.buf.get(self.pos).ok_or_else(|| anyhow!("truncated varint"))?;
+            self.pos += 1;
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        bail!("varint too long")
+    }
+
+    /// Next (field number, wire type), or None at end of buffer.
+    fn next_field(&mut self) -> Result<Option<(u32, u8)>> {
+        if self.pos >= self.buf.len() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        Ok(Some(((key >> 3) as u32, (key & 7) as u8)))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.varint()? as usize;
+        let end = self.pos.checked_add(len).filter(|&e| e <= self.buf.len())
+            .ok_or_else(|| anyhow!("truncated length-delimited field"))?;
+        let out = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn skip(&mut self, wire: u8) -> Result<()> {
+        match wire {
+            0 => { self.varint()?; }
+            1 => self.pos += 8,
+            2 => { self.bytes()?; }
+            5 => self.pos += 4,
+            w => bail!("unsupported wire type {w}"),
+        }
+        if self.pos > self.buf.len() {
+            bail!("truncated fixed-width field");
+        }
+        Ok(())
+    }
+}
+
+#[inline(always)]
+fn zigzag(v: u32) -> i32 {
+    ((v >> 1) as i32) ^ -((v & 1) as i32)
+}
+
+/// Feature geometry type from the MVT spec.
+const GEOM_LINESTRING: u64 = 2;
+const GEOM_POLYGON: u64 = 3;
+
+/// Decoded lines from one MVT layer, still in tile-local integer coordinates.
+struct RawLayer<'a> {
+    name: &'a str,
+    extent: u32,
+    lines: Vec<Vec<(i32, i32)>>,
+}
+
+fn decode_layer(buf: &[u8]) -> Result<RawLayer<'_>> {
+    let mut pb = PbReader::new(buf);
+    let mut name = "";
+    let mut extent = 4096;
+    let mut lines = Vec::new();
+
+    while let Some((field, wire)) = pb.next_field()? {
+        match (field, wire) {
+            (1, 2) => name = std::str::from_utf8(pb.bytes()?).unwrap_or(""),
+            (2, 2) => decode_feature(pb.bytes()?, &mut lines)?,
+            (5, 0) => extent = pb.varint()? as u32,
+            (_, w) => pb.skip(w)?,
+        }
+    }
+    Ok(RawLayer { name, extent, lines })
+}
+
+fn decode_feature(buf: &[u8], lines: &mut Vec<Vec<(i32, i32)>>) -> Result<()> {
+    let mut pb = PbReader::new(buf);
+    let mut geom_type = 0;
+    let mut geometry: &[u8] = &[];
+
+    while let Some((field, wire)) = pb.next_field()? {
+        match (field, wire) {
+            (3, 0) => geom_type = pb.varint()?,
+            (4, 2) => geometry = pb.bytes()?,
+            (_, w) => pb.skip(w)?,
+        }
+    }
+
+    // Points carry no line geometry worth drawing on a basemap
+    if geom_type != GEOM_LINESTRING && geom_type != GEOM_POLYGON {
+        return Ok(());
+    }
+
+    let mut g = PbReader::new(geometry);
+    let (mut cx, mut cy) = (0i32, 0i32);
+    let mut current: Vec<(i32, i32)> = Vec::new();
+
+    while g.pos < g.buf.len() {
+        let cmd = g.varint()? as u32;
+        let (id, count) = (cmd & 7, cmd >> 3);
+        match id {
+            1 | 2 => {
+                for _ in 0..count {
+                    cx = cx.wrapping_add(zigzag(g.varint()? as u32));
+                    cy = cy.wrapping_add(zigzag(g.varint()? as u32));
+                    if id == 1 && current.len() > 1 {
+                        lines.push(std::mem::take(&mut current));
+                    } else if id == 1 {
+                        current.clear();
+                    }
+                    current.push((cx, cy));
+                }
+            }
+            7 => {
+                if let Some(&first) = current.first() {
+                    current.push(first);
+                }
+            }
+            other => bail!("unknown geometry command {other}"),
+        }
+    }
+    if current.len() > 1 {
+        lines.push(current);
+    }
+    Ok(())
+}
+
+/// Split a ring wherever a segment runs along the tile edge.
+/// Water/land polygons are clipped to tile bounds, so their outlines
+/// would otherwise draw a visible grid of tile borders.
+fn split_on_tile_edges(line: Vec<(i32, i32)>, extent: i32) -> Vec<Vec<(i32, i32)>> {
+    let on_edge = |a: (i32, i32), b: (i32, i32)| {
+        (a.0 <= 0 && b.0 <= 0)
+            || (a.0 >= extent && b.0 >= extent)
+            || (a.1 <= 0 && b.1 <= 0)
+            || (a.1 >= extent && b.1 >= extent)
+    };
+
+    let mut out = Vec::new();
+    let mut run: Vec<(i32, i32)> = Vec::new();
+    for w in line.windows(2) {
+        if on_edge(w[0], w[1]) {
+            if run.len() > 1 {
+                out.push(std::mem::take(&mut run));
+            }
+            run.clear();
+            continue;
+        }
+        if run.is_empty() {
+            run.push(w[0]);
+        }
+        run.push(w[1]);
+    }
+    if run.len() > 1 {
+        out.push(run);
+    }
+    out
+}
+
+/// Basemap category a vector-tile layer name maps onto.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum TileLayerKind {
+    Coastline,
+    Border,
+}
+
+/// Map layer names from common schemas (OpenMapTiles, Mapbox Streets,
+/// Protomaps basemaps) onto our coastline/border layers.
+fn classify_layer(name: &str) -> Option<TileLayerKind> {
+    match name {
+        "water" | "ocean" | "earth" | "land" | "coastline" => Some(TileLayerKind::Coastline),
+        "boundary" | "boundaries" | "admin" => Some(TileLayerKind::Border),
+        _ => None,
+    }
+}
+
+/// A decoded tile ready for rendering — precomputed LineStrings in lon/lat.
+pub struct DecodedTile {
+    pub id: TileId,
+    pub coastlines: Vec<LineString>,
+    pub borders: Vec<LineString>,
+}
+
+/// Decode raw MVT bytes into basemap lines for the given tile.
+pub fn decode_tile(id: TileId, data: &[u8]) -> Result<DecodedTile> {
+    if data.starts_with(&[0x1f, 0x8b]) {
+        bail!("tile {}/{}/{} is gzip-compressed; serve it uncompressed", id.z, id.x, id.y);
+    }
+
+    let mut tile = DecodedTile { id, coastlines: Vec::new(), borders: Vec::new() };
+    let mut pb = PbReader::new(data);
+
+    while let Some((field, wire)) = pb.next_field()? {
+        if (field, wire) != (3, 2) {
+            pb.skip(wire)?;
+            continue;
+        }
+        let layer = decode_layer(pb.bytes()?)?;
+        let Some(kind) = classify_layer(layer.name) else { continue };
+        let extent = layer.extent.max(1) as f64;
+
+        for raw in layer.lines {
+            let parts = match kind {
+                TileLayerKind::Coastline => split_on_tile_edges(raw, layer.extent as i32),
+                TileLayerKind::Border => vec![raw],
+            };
+            for part in parts {
+                let pts: Vec<(f64, f64)> = part
+                    .iter()
+                    .map(|&(px, py)| {
+                        tile_to_lonlat(id.z, id.x as f64 + px as f64 / extent, id.y as f64 + py as f64 / extent)
+                    })
+                    .collect();
+                let line = LineString::new(pts);
+                match kind {
+                    TileLayerKind::Coastline => tile.coastlines.push(line),
+                    TileLayerKind::Border => tile.borders.push(line),
+                }
+            }
+        }
+    }
+
+    Ok(tile)
+}
+
+// ── Tile sources ───────────────────────────────────────────────────────────
+
+/// Where raw tile bytes come from. Implementations run on the worker thread.
+pub trait TileSource: Send {
+    fn fetch(&self, id: TileId) -> Result<Vec<u8>>;
+}
+
+/// Local directory of `{z}/{x}/{y}.mvt` files (e.g. an extracted tile pyramid).
+pub struct DirSource {
+    root: PathBuf,
+}
+
+impl DirSource {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+}
+
+impl TileSource for DirSource {
+    fn fetch(&self, id: TileId) -> Result<Vec<u8>> {
+        let path = self.root.join(id.fill_template("{z}/{x}/{y}.mvt"));
+        fs::read(&path).with_context(|| format!("reading {}", path.display()))
+    }
+}
+
+/// HTTP(S) tile server addressed by a `{z}/{x}/{y}` URL template.
+#[cfg(feature = "online")]
+pub struct HttpSource {
+    template: String,
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "online")]
+impl HttpSource {
+    pub fn new(template: String) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(std::time::Duration::from_secs(15))
+            .user_agent(concat!("tui-map/", env!("CARGO_PKG_VERSION")))
+            .build();
+        Self { template, agent }
+    }
+}
+
+#[cfg(feature = "online")]
+impl TileSource for HttpSource {
+    fn fetch(&self, id: TileId) -> Result<Vec<u8>> {
+        use std::io::Read;
+        const MAX_TILE_BYTES: u64 = 8 * 1024 * 1024;
+
+        let url = id.fill_template(&self.template);
+        let resp = self.agent.get(&url).call().with_context(|| format!("GET {url}"))?;
+        let mut buf = Vec::new();
+        resp.into_reader().take(MAX_TILE_BYTES + 1).read_to_end(&mut buf)?;
+        if buf.len() as u64 > MAX_TILE_BYTES {
+            bail!("{url}: tile larger than {MAX_TILE_BYTES} bytes");
+        }
+        Ok(buf)
+    }
+}
+
+/// Build a source from a URL template: http(s) needs the `online` feature.
+pub fn source_from_url(template: &str) -> Result<Box<dyn TileSource>> {
+    if template.starts_with("http://") || template.starts_with("https://") {
+        #[cfg(feature = "online")]
+        return Ok(Box::new(HttpSource::new(template.to_string())));
+        #[cfg(not(feature = "online"))]
+        bail!("remote tiles need a build with `--features online`");
+    }
+    Ok(Box::new(DirSource::new(PathBuf::from(template))))
+}
+
+/// On-disk tile cache: `<root>/{z}/{x}/{y}.mvt`, raw (undecoded) bytes.
+pub struct DiskCache {
+    root: PathBuf,
+}
+
+impl DiskCache {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Default cache location alongside the land-grid cache.
+    pub fn default_root() -> PathBuf {
+        std::env::temp_dir().join("tui_map_tiles")
+    }
+
+    /// Cache for the tiles of one URL template, in a directory under
+    /// `root` named by its hash, so another server's tiles are never served
+    /// in their place
+    pub fn for_source(root: PathBuf, template: &str) -> Self {
+        Self::new(root.join(&sha256_hex(template.as_bytes())[..16]))
+    }
+
+    fn path(&self, id: TileId) -> PathBuf {
+        self.root.join(id.fill_template("{z}/{x}/{y}.mvt"))
+    }
+
+    pub fn get(&self, id: TileId) -> Option<Vec<u8>> {
+        fs::read(self.path(id)).ok()
+    }
+
+    pub fn put(&self, id: TileId, data: &[u8]) {
+        let path = self.path(id);
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(path, data);
+    }
+}
+
+// ── In-memory LRU + background worker ──────────────────────────────────────
+
+/// Fixed-capacity LRU keyed by tile. Recency is a monotonically increasing
+/// stamp; eviction scans for the minimum — capacity is small (tens of tiles).
+pub struct TileLru {
+    entries: HashMap<TileId, (Arc<DecodedTile>, u64)>,
+    capacity: usize,
+    clock: u64,
+}
+
+impl TileLru {
+    pub fn new(capacity: usize) -> Self {
+        Self { entries: HashMap::new(), capacity: capacity.max(1), clock: 0 }
+    }
+
+    pub fn get(&mut self, id: &TileId) -> Option<Arc<DecodedTile>> {
+        self.clock += 1;
+        let clock = self.clock;
+        self.entries.get_mut(id).map(|(tile, stamp)| {
+            *stamp = clock;
+            Arc::clone(tile)
+        })
+    }
+
+    pub fn contains(&self, id: &TileId) -> bool {
+        self.entries.contains_key(id)
+    }
+
+    pub fn insert(&mut self, tile: Arc<DecodedTile>) {
+        self.clock += 1;
+        if !self.entries.contains_key(&tile.id) && self.entries.len() >= self.capacity {
+            if let Some(oldest) = self.entries.iter().min_by_key(|(_, (_, s))| *s).map(|(k, _)| *k) {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.entries.insert(tile.id, (tile, self.clock));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+type TileResult = (TileId, Result<DecodedTile>);
+
+/// A tile from the cache, or else fetched and cached once it decodes, so a
+/// corrupt download isn't kept
+fn load_tile(source: &dyn TileSource, cache: Option<&DiskCache>, id: TileId) -> Result<DecodedTile> {
+    if let Some(bytes) = cache.and_then(|c| c.get(id)) {
+        return decode_tile(id, &bytes);
+    }
+    let bytes = source.fetch(id)?;
+    let tile = decode_tile(id, &bytes)?;
+    if let Some(c) = cache {
+        c.put(id, &bytes);
+    }
+    Ok(tile)
+}
+
+/// Owns the fetch worker and the decoded-tile LRU.
+pub struct TileManager {
+    requests: Sender<TileId>,
+    results: Receiver<TileResult>,
+    lru: TileLru,
+    pending: HashSet<TileId>,
+    /// Tiles that failed to load — not retried for the rest of the session
+    failed: HashSet<TileId>,
+    /// Tiles covering the current viewport, in request order
+    visible: Vec<TileId>,
+    pub max_zoom: u8,
+    /// Bumped whenever the visible tile set gains or loses data.
+    /// Feeds the render cache key so new tiles trigger a redraw.
+    pub generation: u64,
+    pub last_error: Option<String>,
+}
+
+impl TileManager {
+    pub fn new(source: Box<dyn TileSource>, cache: Option<DiskCache>, max_zoom: u8) -> Self {
+        let (req_tx, req_rx) = mpsc::channel::<TileId>();
+        let (res_tx, res_rx) = mpsc::channel::<TileResult>();
+
+        thread::spawn(move || {
+            for id in req_rx {
+                let result = load_tile(source.as_ref(), cache.as_ref(), id);
+                if res_tx.send((id, result)).is_err() {
+                    break; // manager dropped
+                }
+            }
+        });
+
+        Self {
+            requests: req_tx,
+            results: res_rx,
+            lru: TileLru::new(96),
+            pending: HashSet::new(),
+            failed: HashSet::new(),
+            visible: Vec::new(),
+            max_zoom,
+            generation: 0,
+            last_error: None,
+        }
+    }
+
+    /// Request tiles for the viewport and drain finished fetches.
+    /// Returns true when the visible data changed.
+    pub fn update(&mut self, bounds: (f64, f64, f64, f64), effective_zoom: f64, pixel_width: usize) -> bool {
+        let mut changed = false;
+
+        while let Ok((id, result)) = self.results.try_recv() {
+            self.pending.remove(&id);
+            match result {
+                Ok(tile) => {
+                    self.lru.insert(Arc::new(tile));
+                    changed |= self.visible.contains(&id);
+                }
+                Err(e) => {
+                    self.last_error = Some(format!("{e:#}"));
+                    self.failed.insert(id);
+                }
+            }
+        }
+
+        let z = tile_zoom_for(effective_zoom, pixel_width, self.max_zoom);
+        let (min_lon, min_lat, max_lon, max_lat) = bounds;
+        let wanted = tiles_for_bounds(min_lon, min_lat, max_lon, max_lat, z);
+        if wanted != self.visible {
+            self.visible = wanted;
+            changed = true;
+        }
+
+        for &id in &self.visible {
+            if !self.lru.contains(&id) && !self.pending.contains(&id) && !self.failed.contains(&id) {
+                self.pending.insert(id);
+                let _ = self.requests.send(id);
+            }
+        }
+
+        if changed {
+            self.generation += 1;
+        }
+        changed
+    }
+
+    /// Decoded tiles for the current viewport (only those already loaded).
+    /// While zooming, falls back to any loaded ancestor so the map never blanks.
+    pub fn visible_tiles(&mut self) -> Vec<Arc<DecodedTile>> {
+        let mut out: Vec<Arc<DecodedTile>> = Vec::new();
+        for id in self.visible.clone() {
+            let mut cur = id;
+            loop {
+                if let Some(t) = self.lru.get(&cur) {
+                    if !out.iter().any(|o| o.id == t.id) {
+                        out.push(t);
+                    }
+                    break;
+                }
+                if cur.z == 0 {
+                    break;
+                }
+                cur = TileId::new(cur.z - 1, cur.x / 2, cur.y / 2);
+            }
+        }
+        out
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn loaded_count(&self) -> usize {
+        self.lru.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(out: &mut Vec<u8>, mut v: u64) {
+        while v >= 0x80 {
+            out.push((v as u8) | 0x80);
+            v >>= 7;
+        }
+        out.push(v as u8);
+    }
+
+    fn field_bytes(out: &mut Vec<u8>, field: u32, data: &[u8]) {
+        varint(out, ((field as u64) << 3) | 2);
+        varint(out, data.len() as u64);
+        out.extend_from_slice(data);
+    }
+
+    fn zz(v: i32) -> u64 {
+        ((v << 1) ^ (v >> 31)) as u32 as u64
+    }
+
+    /// Encode a single-layer tile with one linestring feature.
+    fn encode_tile(layer_name: &str, points: &[(i32, i32)]) -> Vec<u8> {
+        let mut geom = Vec::new();
+        varint(&mut geom, (1 << 3) | 1); // MoveTo ×1
+        varint(&mut geom, zz(points[0].0));
+        varint(&mut geom, zz(points[0].1));
+        varint(&mut geom, (((points.len() - 1) as u64) << 3) | 2); // LineTo ×n
+        for w in points.windows(2) {
+            varint(&mut geom, zz(w[1].0 - w[0].0));
+            varint(&mut geom, zz(w[1].1 - w[0].1));
+        }
+
+        let mut feature = Vec::new();
+        varint(&mut feature, 3 << 3);
+        varint(&mut feature, GEOM_LINESTRING);
+        field_bytes(&mut feature, 4, &geom);
+
+        let mut layer = Vec::new();
+        field_bytes(&mut layer, 1, layer_name.as_bytes());
+        field_bytes(&mut layer, 2, &feature);
+        varint(&mut layer, 5 << 3);
+        varint(&mut layer, 4096);
+
+        let mut tile = Vec::new();
+        field_bytes(&mut tile, 3, &layer);
+        tile
+    }
+
+    /// Serves the same bytes for every tile
+    struct FixedSource(Vec<u8>);
+
+    impl TileSource for FixedSource {
+        fn fetch(&self, _id: TileId) -> Result<Vec<u8>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn only_tiles_that_decode_are_cached_per_server() {
+        let root = std::env::temp_dir().join(format!("tui-map-tile-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let id = TileId::new(2, 1, 1);
+
+        let cache = DiskCache::for_source(root.clone(), "https://a.example/{z}/{x}/{y}.mvt");
+        assert!(load_tile(&FixedSource(vec![0x0A, 0xFF]), Some(&cache), id).is_err());
+        assert!(cache.get(id).is_none());
+        let good = encode_tile("boundary", &[(0, 0), (4096, 4096)]);
+        assert!(load_tile(&FixedSource(good.clone()), Some(&cache), id).is_ok());
+        assert_eq!(cache.get(id), Some(good));
+
+        // Another server starts with an empty cache of its own
+        let other = DiskCache::for_source(root.clone(), "https://b.example/{z}/{x}/{y}.mvt");
+        assert!(other.get(id).is_none());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn tile_math_roundtrip() {
+        let id = TileId::containing(139.7, 35.7, 8);
+        let (min_lon, min_lat, max_lon, max_lat) = id.bounds();
+        assert!(min_lon <= 139.7 && 139.7 <= max_lon);
+        assert!(min_lat <= 35.7 && 35.7 <= max_lat);
+        assert_eq!(TileId::containing(0.0, 0.0, 0), TileId::new(0, 0, 0));
+    }
+
+    #[test]
+    fn tiles_for_bounds_wraps_dateline() {
+        let tiles = tiles_for_bounds(170.0, -10.0, 190.0, 10.0, 2);
+        assert!(tiles.iter().any(|t| t.x == 3));
+        assert!(tiles.iter().any(|t| t.x == 0), "east of 180 should wrap to x=0");
+    }
+
+    #[test]
+    fn decodes_linestring_to_lonlat() {
+        let data = encode_tile("boundary", &[(0, 2048), (4096, 2048)]);
+        let tile = decode_tile(TileId::new(0, 0, 0), &data).unwrap();
+        assert_eq!(tile.borders.len(), 1);
+        let (min_lon, _, max_lon, _) = tile.borders[0].bbox;
+        assert!((min_lon + 180.0).abs() < 1e-9);
+        assert!((max_lon - 180.0).abs() < 1e-9);
+        assert!(tile.coastlines.is_empty());
+    }
+
+    #[test]
+    fn unknown_layers_are_ignored() {
+        let data = encode_tile("poi", &[(0, 0), (10, 10)]);
+        let tile = decode_tile(TileId::new(3, 1, 1), &data).unwrap();
+        assert!(tile.borders.is_empty() && tile.coastlines.is_empty());
+    }
+
+    #[test]
+    fn coastline_rings_drop_tile_edges() {
+        // Square hugging the left tile edge: the x=0 segment should vanish
+        let ring = vec![(0, 0), (100, 0), (100, 100), (0, 100), (0, 0)];
+        let parts = split_on_tile_edges(ring, 4096);
+        let total: usize = parts.iter().map(|p| p.len() - 1).sum();
+        assert_eq!(total, 2, "top edge and left edge segments removed");
+    }
+
+    #[test]
+    fn lru_evicts_least_recent() {
+        let mk = |x| Arc::new(DecodedTile { id: TileId::new(1, x, 0), coastlines: vec![], borders: vec![] });
+        let mut lru = TileLru::new(2);
+        lru.insert(mk(0));
+        lru.insert(mk(1));
+        lru.get(&TileId::new(1, 0, 0));
+        lru.insert(mk(2));
+        assert!(lru.contains(&TileId::new(1, 0, 0)));
+        assert!(!lru.contains(&TileId::new(1, 1, 0)));
+        assert_eq!(lru.len(), 2);
+    }
+}
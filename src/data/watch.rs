@@ -0,0 +1,124 @@
+//! Watch the data directory for GeoJSON files being edited, added or
+//! removed, so the layers they feed reload while the map runs.
+//!
+//! The operating system reports changes as they happen (`notify`). A
+//! change is passed on once the file has gone `SETTLE` without another,
+//! so a file still being written isn't read half-way. If the directory
+//! can't be watched, nothing is reported and the map carries on.
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+/// How long a file must go unchanged before it's read
+const SETTLE: Duration = Duration::from_secs(1);
+
+pub struct DataWatcher {
+    /// Kept alive for as long as events are wanted
+    _watcher: Option<RecommendedWatcher>,
+    events: Option<Receiver<notify::Result<notify::Event>>>,
+    /// Files seen to change, and when they were last seen to
+    pending: HashMap<PathBuf, Instant>,
+}
+
+impl DataWatcher {
+    /// Watch `dir`, taking the files in it now as loaded
+    pub fn new(dir: PathBuf, now: Instant) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .and_then(|mut watcher| watcher.watch(&dir, RecursiveMode::NonRecursive).map(|()| watcher));
+        let (watcher, events) = match watcher {
+            Ok(watcher) => (Some(watcher), Some(rx)),
+            Err(_) => (None, None),
+        };
+        let mut watcher = Self { _watcher: watcher, events, pending: HashMap::new() };
+        watcher.rescan(now);
+        watcher
+    }
+
+    /// Take the files as they are now as loaded, after the whole world
+    /// was loaded again
+    pub fn rescan(&mut self, now: Instant) {
+        self.drain(now);
+        self.pending.clear();
+    }
+
+    /// Note the GeoJSON files named by events that have come in
+    fn drain(&mut self, now: Instant) {
+        let Some(events) = &self.events else { return };
+        for event in events.try_iter().flatten() {
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            for path in event.paths.into_iter().filter(|p| is_geojson(p)) {
+                self.pending.insert(path, now);
+            }
+        }
+    }
+
+    /// Files that changed and have settled, or were removed, since the
+    /// last poll that found any
+    pub fn poll(&mut self, now: Instant) -> Vec<PathBuf> {
+        self.drain(now);
+        let mut changed: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, &seen)| now.saturating_duration_since(seen) >= SETTLE)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in &changed {
+            self.pending.remove(path);
+        }
+        changed.sort();
+        changed
+    }
+}
+
+fn is_geojson(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// Poll at `at` until an event for some file has come in, and a little
+    /// longer for any that follow it
+    fn wait_for_event(watcher: &mut DataWatcher, at: Instant) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while watcher.pending.is_empty() {
+            assert!(Instant::now() < deadline, "no file event within 5s");
+            assert!(watcher.poll(at).is_empty(), "reported before settling");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(watcher.poll(at).is_empty(), "reported before settling");
+    }
+
+    #[test]
+    fn files_are_reported_once_they_settle() {
+        let dir = std::env::temp_dir().join(format!("tui-map-watch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("old.json"), "{}").unwrap();
+        let mut watcher = DataWatcher::new(dir.clone(), Instant::now());
+
+        fs::write(dir.join("notes.txt"), "not GeoJSON").unwrap();
+        fs::write(dir.join("overlay.json"), "{}").unwrap();
+        let t0 = Instant::now();
+        wait_for_event(&mut watcher, t0);
+        assert_eq!(watcher.poll(t0 + SETTLE), vec![dir.join("overlay.json")]);
+        assert!(watcher.poll(t0 + SETTLE * 2).is_empty());
+
+        fs::remove_file(dir.join("old.json")).unwrap();
+        let t1 = Instant::now();
+        wait_for_event(&mut watcher, t1);
+        assert_eq!(watcher.poll(t1 + SETTLE), vec![dir.join("old.json")]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
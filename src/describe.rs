@@ -0,0 +1,127 @@
+//! Plain-language description of the view, for text mode.
+//!
+//! Screen readers can't make sense of braille art, so text mode replaces
+//! the map with a sentence or two built from the same queries the renderer
+//! uses: what the view is centered on, how far in it is, and the biggest
+//! cities on screen. "Viewing Western Europe (France) at 4.0x on the
+//! Mercator map, centered on 48.86°N 2.35°E. 3 cities visible: Paris 11.0M,
+//! London 9.0M, Brussels 2.1M."
+
+use crate::map::renderer::MapRenderer;
+use crate::map::Projection;
+
+/// Cities named before the rest are counted
+const MAX_NAMED_CITIES: usize = 5;
+
+/// Zoom from which the country under the center is named too
+const COUNTRY_ZOOM: f64 = 3.0;
+
+/// (west, east, south, north, name), first match wins
+const REGIONS: [(f64, f64, f64, f64, &str); 18] = [
+    (-180.0, 180.0, 66.0, 90.0, "the Arctic"),
+    (-180.0, 180.0, -90.0, -60.0, "Antarctica"),
+    (-118.0, -59.0, 7.0, 23.0, "Central America and the Caribbean"),
+    (-170.0, -50.0, 23.0, 66.0, "North America"),
+    (-82.0, -34.0, -56.0, 7.0, "South America"),
+    (-25.0, 32.0, 55.0, 66.0, "Northern Europe"),
+    (-11.0, 15.0, 36.0, 55.0, "Western Europe"),
+    (15.0, 45.0, 42.0, 55.0, "Eastern Europe"),
+    (-18.0, 32.0, 15.0, 37.0, "North Africa"),
+    (32.0, 63.0, 12.0, 42.0, "the Middle East"),
+    (-18.0, 52.0, -35.0, 15.0, "Sub-Saharan Africa"),
+    (45.0, 180.0, 55.0, 66.0, "Siberia"),
+    (45.0, 92.0, 36.0, 55.0, "Central Asia"),
+    (60.0, 92.0, 5.0, 36.0, "South Asia"),
+    (92.0, 146.0, 18.0, 55.0, "East Asia"),
+    (92.0, 155.0, -11.0, 18.0, "Southeast Asia"),
+    (110.0, 180.0, -50.0, -11.0, "Oceania"),
+    (-180.0, -118.0, -60.0, 66.0, "the Pacific Ocean"),
+];
+
+/// Region name for a point, falling back to the ocean it's in
+pub fn region_name(lon: f64, lat: f64) -> &'static str {
+    REGIONS
+        .iter()
+        .find(|&&(w, e, s, n, _)| (w..e).contains(&lon) && (s..n).contains(&lat))
+        .map_or_else(|| match lon {
+            l if (-70.0..20.0).contains(&l) => "the Atlantic Ocean",
+            l if (20.0..110.0).contains(&l) && lat < 25.0 => "the Indian Ocean",
+            _ => "the Pacific Ocean",
+        }, |&(.., name)| name)
+}
+
+/// Describe what the view shows
+pub fn describe(renderer: &MapRenderer, projection: &Projection) -> String {
+    let (lon, lat) = (projection.center_lon(), projection.center_lat());
+    let zoom = projection.effective_zoom();
+    let mut text = format!("Viewing {}", region_name(lon, lat));
+    let country = (zoom >= COUNTRY_ZOOM)
+        .then(|| renderer.country_near(lon, lat, 2.0))
+        .flatten()
+        .map(|code| renderer.country_names.iter().find(|(c, _)| c == code).map_or(code, |(_, name)| name.as_str()));
+    if let Some(country) = country {
+        text.push_str(&format!(" ({country})"));
+    }
+    text.push_str(&format!(
+        " at {zoom:.1}x on the {} map, centered on {:.2}°{} {:.2}°{}.",
+        projection.name(),
+        lat.abs(),
+        if lat >= 0.0 { 'N' } else { 'S' },
+        lon.abs(),
+        if lon >= 0.0 { 'E' } else { 'W' },
+    ));
+
+    let (width, height) = projection.size();
+    let on_screen = |px: i32, py: i32| (0..width as i32).contains(&px) && (0..height as i32).contains(&py);
+    let mut cities: Vec<_> = renderer
+        .city_grid
+        .iter()
+        .filter(|c| c.population > 0 && projection.project_point(c.lon, c.lat).is_some_and(|(x, y)| on_screen(x, y)))
+        .collect();
+    cities.sort_by_key(|c| std::cmp::Reverse(c.population));
+    match cities.len() {
+        0 => text.push_str(" No cities visible."),
+        n => {
+            let named: Vec<String> = cities.iter().take(MAX_NAMED_CITIES).map(|c| format!("{} {}", c.name, c.cached_pop_label)).collect();
+            text.push_str(&format!(" {n} {} visible: {}", if n == 1 { "city" } else { "cities" }, named.join(", ")));
+            if n > MAX_NAMED_CITIES {
+                text.push_str(&format!(" and {} more", n - MAX_NAMED_CITIES));
+            }
+            text.push('.');
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Viewport;
+
+    #[test]
+    fn names_regions_and_oceans() {
+        assert_eq!(region_name(2.35, 48.86), "Western Europe");
+        assert_eq!(region_name(139.7, 35.7), "East Asia");
+        assert_eq!(region_name(-99.1, 19.4), "Central America and the Caribbean");
+        assert_eq!(region_name(-30.0, 0.0), "the Atlantic Ocean");
+        assert_eq!(region_name(80.0, -20.0), "the Indian Ocean");
+        assert_eq!(region_name(0.0, 80.0), "the Arctic");
+    }
+
+    #[test]
+    fn lists_the_biggest_cities_on_screen() {
+        let mut renderer = MapRenderer::new();
+        for (name, lon, lat, population, country) in [
+            ("Paris", 2.35, 48.86, 11_000_000, "FRA"),
+            ("London", -0.13, 51.51, 9_000_000, "GBR"),
+            ("Tokyo", 139.7, 35.7, 37_000_000, "JPN"),
+        ] {
+            renderer.add_city(lon, lat, name, population, false, false, country);
+        }
+        renderer.country_names.push(("FRA".to_string(), "France".to_string()));
+        let projection = Projection::new(Viewport::new(2.0, 50.0, 8.0, 200, 160));
+        let text = describe(&renderer, &projection);
+        assert!(text.starts_with("Viewing Western Europe (France) at 8.0x on the Mercator map"), "{text}");
+        assert!(text.ends_with("2 cities visible: Paris 11.0M, London 9.0M."), "{text}");
+    }
+}
@@ -0,0 +1,147 @@
+//! Running record of what happened, for the event log panel.
+//!
+//! Strikes, intercepts, retaliation waves, failing emergency services and
+//! natural disasters are logged with the simulated time they happened at. The log keeps the
+//! most recent `CAPACITY` entries and a scroll position measured back from
+//! the newest.
+
+use crate::app::WeaponType;
+use crate::sim::disasters::Hazard;
+use std::collections::VecDeque;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EventKind {
+    Strike(WeaponType),
+    Intercept,
+    Retaliation,
+    /// A country's emergency response was run down further
+    Response,
+    /// A volcano, hurricane or wildfire
+    Disaster(Hazard),
+    /// Patient zero of a pandemic-mode outbreak
+    Outbreak,
+}
+
+#[derive(Clone, Debug)]
+pub struct Event {
+    /// Simulated time (unix seconds)
+    pub sim_secs: f64,
+    pub kind: EventKind,
+    pub text: String,
+    /// Immediate deaths, for strikes
+    pub casualties: Option<u64>,
+    /// Warhead yield (kilotons), for nuclear strikes whose yield is known
+    pub yield_kt: Option<f64>,
+}
+
+impl Event {
+    /// "HH:MM" UTC
+    pub fn time_label(&self) -> String {
+        let sod = (self.sim_secs.floor() as i64).rem_euclid(86_400);
+        format!("{:02}:{:02}", sod / 3600, (sod / 60) % 60)
+    }
+}
+
+#[derive(Default)]
+pub struct EventLog {
+    entries: VecDeque<Event>,
+    /// Entries scrolled back from the newest (0 = following the log)
+    scroll: usize,
+}
+
+impl EventLog {
+    pub const CAPACITY: usize = 500;
+
+    pub fn push(&mut self, event: Event) {
+        if self.entries.len() == Self::CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(event);
+        if self.scroll > 0 {
+            // Keep a scrolled-back view on the same entries, or on the
+            // oldest left if they've aged out
+            self.scroll = (self.scroll + 1).min(self.entries.len() - 1);
+        }
+    }
+
+    /// Scroll back (positive) or forward (negative) through the log
+    pub fn scroll_by(&mut self, delta: isize) {
+        let max = self.entries.len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max);
+    }
+
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    /// The `rows` entries ending `scroll` back from the newest, oldest first
+    pub fn visible(&self, rows: usize) -> impl Iterator<Item = &Event> {
+        let end = self.entries.len() - self.scroll.min(self.entries.len());
+        self.entries.range(end.saturating_sub(rows)..end)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(sim_secs: f64, text: impl Into<String>) -> Event {
+        Event { sim_secs, kind: EventKind::Intercept, text: text.into(), casualties: None, yield_kt: None }
+    }
+
+    fn texts<'a>(events: impl Iterator<Item = &'a Event>) -> Vec<&'a str> {
+        events.map(|e| e.text.as_str()).collect()
+    }
+
+    #[test]
+    fn scrollback_stays_put_as_events_arrive() {
+        let mut log = EventLog::default();
+        for i in 0..5 {
+            log.push(event(3600.0 * 14.0 + 120.0, i.to_string()));
+        }
+        assert_eq!(texts(log.visible(2)), ["3", "4"]);
+        assert_eq!(log.visible(1).next().unwrap().time_label(), "14:02");
+
+        log.scroll_by(2);
+        assert_eq!(texts(log.visible(2)), ["1", "2"]);
+        log.push(event(0.0, "5"));
+        assert_eq!(texts(log.visible(2)), ["1", "2"]);
+
+        log.scroll_by(100);
+        assert_eq!(texts(log.visible(2)), ["0"]);
+        log.scroll_by(-100);
+        assert_eq!(texts(log.visible(2)), ["4", "5"]);
+
+        for i in 0..EventLog::CAPACITY {
+            log.push(event(0.0, i.to_string()));
+        }
+        assert_eq!(log.len(), EventLog::CAPACITY);
+    }
+
+    #[test]
+    fn a_full_log_keeps_the_scrolled_back_view() {
+        let mut log = EventLog::default();
+        for i in 0..EventLog::CAPACITY {
+            log.push(event(0.0, i.to_string()));
+        }
+        log.scroll_by(10);
+        assert_eq!(texts(log.visible(1)), ["489"]);
+        log.push(event(0.0, "new"));
+        assert_eq!(texts(log.visible(1)), ["489"]);
+
+        // Scrolled to the very oldest, which then ages out
+        log.scroll_by(EventLog::CAPACITY as isize);
+        assert_eq!(texts(log.visible(1)), ["1"]);
+        log.push(event(0.0, "newer"));
+        assert_eq!(texts(log.visible(1)), ["2"]);
+        assert_eq!(log.scroll(), EventLog::CAPACITY - 1);
+    }
+}
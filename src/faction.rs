@@ -0,0 +1,111 @@
+//! Factions: every country belongs to one of three blocs, which tint the map
+//! borders and are tallied in the sidebar.
+
+/// Geopolitical bloc a country belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Faction {
+    West,
+    East,
+    NonAligned,
+}
+
+/// Western bloc members (ISO alpha-3)
+const WEST: &[&str] = &[
+    "USA", "CAN", "GBR", "FRA", "DEU", "ITA", "ESP", "PRT", "NLD", "BEL", "LUX", "DNK", "NOR",
+    "ISL", "POL", "CZE", "SVK", "HUN", "ROU", "BGR", "GRC", "TUR", "EST", "LVA", "LTU", "SVN",
+    "HRV", "ALB", "MNE", "MKD", "FIN", "SWE", "JPN", "KOR", "AUS", "NZL", "ISR", "TWN", "PHL",
+];
+
+/// Eastern bloc members (ISO alpha-3)
+const EAST: &[&str] = &[
+    "RUS", "CHN", "PRK", "BLR", "IRN", "SYR", "CUB", "VEN", "NIC", "KAZ", "KGZ", "TJK", "ARM",
+    "MNG", "LAO", "MMR", "ERI",
+];
+
+impl Faction {
+    pub const ALL: [Faction; 3] = [Faction::West, Faction::East, Faction::NonAligned];
+
+    /// Faction for an ISO alpha-3 country code (unknown codes are non-aligned)
+    pub fn of(country: &str) -> Self {
+        if WEST.contains(&country) {
+            Faction::West
+        } else if EAST.contains(&country) {
+            Faction::East
+        } else {
+            Faction::NonAligned
+        }
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            Faction::West => 0,
+            Faction::East => 1,
+            Faction::NonAligned => 2,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Faction::West => "WESTERN BLOC",
+            Faction::East => "EASTERN BLOC",
+            Faction::NonAligned => "NON-ALIGNED",
+        }
+    }
+
+    /// Border tint (RGB)
+    pub fn color(self) -> (u8, u8, u8) {
+        match self {
+            Faction::West => (80, 150, 255),
+            Faction::East => (255, 90, 80),
+            Faction::NonAligned => (170, 170, 110),
+        }
+    }
+}
+
+/// Population totals for one faction
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FactionStats {
+    pub population: u64,
+    pub original_population: u64,
+}
+
+impl FactionStats {
+    pub fn casualties(&self) -> u64 {
+        self.original_population.saturating_sub(self.population)
+    }
+
+    /// Fraction of the original population still alive (1.0 if none)
+    pub fn surviving(&self) -> f64 {
+        if self.original_population == 0 {
+            1.0
+        } else {
+            self.population as f64 / self.original_population as f64
+        }
+    }
+}
+
+/// Sum city populations per faction from (country, population, original) rows,
+/// indexed by `Faction::index`
+pub fn tally<'a>(cities: impl Iterator<Item = (&'a str, u64, u64)>) -> [FactionStats; 3] {
+    let mut stats = [FactionStats::default(); 3];
+    for (country, population, original) in cities {
+        let s = &mut stats[Faction::of(country).index()];
+        s.population += population;
+        s.original_population += original;
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tallies_casualties_per_faction() {
+        let stats = tally([("USA", 5, 10), ("GBR", 3, 3), ("RUS", 0, 8), ("BRA", 7, 7), ("", 1, 1)].into_iter());
+        assert_eq!(stats[Faction::West.index()].population, 8);
+        assert_eq!(stats[Faction::West.index()].casualties(), 5);
+        assert_eq!(stats[Faction::East.index()].surviving(), 0.0);
+        assert_eq!(stats[Faction::NonAligned.index()].population, 8);
+    }
+}
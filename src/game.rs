@@ -0,0 +1,241 @@
+//! Game modes layered over free play.
+//!
+//! Free play never ends on its own. With an objective (`--objective`), the
+//! game is judged every few frames and ends in victory or defeat; either way
+//! (or when the player ends a session) the summary screen shows what happened.
+
+use crate::faction::FactionStats;
+use anyhow::{anyhow, bail, Result};
+
+/// Frames between casualty samples for the summary chart
+pub const HISTORY_INTERVAL: u64 = 30;
+
+/// What the player is trying to achieve
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Objective {
+    /// Bring world population below `percent` of where it started
+    Depopulate { percent: f64 },
+    /// Weather `waves` adversary counterstrikes with at least `percent` of
+    /// the player's population alive
+    Survive { waves: u32, percent: f64 },
+}
+
+impl Objective {
+    /// `depopulate[:PERCENT]` or `survive[:WAVES[:PERCENT]]`
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut parts = s.split(':');
+        let kind = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let mut number = |default: f64| -> Result<f64> {
+            match parts.next() {
+                None => Ok(default),
+                Some(v) => v.trim_end_matches('%').parse().map_err(|_| anyhow!("invalid number: {v}")),
+            }
+        };
+        let objective = match kind.as_str() {
+            "depopulate" => Objective::Depopulate { percent: number(50.0)? },
+            "survive" => {
+                let waves = number(3.0)?;
+                if !((0.0..=u32::MAX as f64).contains(&waves) && waves.fract() == 0.0) {
+                    bail!("waves must be a whole number: {waves}");
+                }
+                Objective::Survive { waves: waves as u32, percent: number(50.0)? }
+            }
+            other => bail!("unknown objective: {other} (expected depopulate or survive)"),
+        };
+        let percent = match objective {
+            Objective::Depopulate { percent } | Objective::Survive { percent, .. } => percent,
+        };
+        if !(0.0..=100.0).contains(&percent) {
+            bail!("percentage out of range: {percent}");
+        }
+        if parts.next().is_some() {
+            bail!("too many fields in objective: {s}");
+        }
+        Ok(objective)
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Objective::Depopulate { percent } => format!("Reduce world population below {percent:.0}%"),
+            Objective::Survive { waves, percent } => {
+                format!("Survive {waves} retaliation waves with {percent:.0}% of your population")
+            }
+        }
+    }
+
+    /// Short progress readout for the status bar
+    pub fn progress(&self, standing: &Standing, stats: &GameStats) -> String {
+        match self {
+            Objective::Depopulate { percent } => {
+                format!("WORLD {:.0}%→{percent:.0}%", standing.world.surviving() * 100.0)
+            }
+            Objective::Survive { waves, .. } => {
+                format!("WAVE {}/{waves} POP {:.0}%", stats.waves.min(*waves), standing.player.surviving() * 100.0)
+            }
+        }
+    }
+
+    /// Decide the game, if it's over
+    pub fn judge(&self, standing: &Standing, stats: &GameStats) -> Option<Outcome> {
+        match *self {
+            Objective::Depopulate { percent } => {
+                (standing.world.surviving() * 100.0 < percent).then_some(Outcome::Victory)
+            }
+            Objective::Survive { waves, percent } => {
+                if standing.player.surviving() * 100.0 < percent {
+                    Some(Outcome::Defeat)
+                } else if stats.waves >= waves && !standing.hostiles_in_flight {
+                    Some(Outcome::Victory)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Victory,
+    Defeat,
+    /// The player ended the session
+    Ended,
+}
+
+impl Outcome {
+    pub fn label(self) -> &'static str {
+        match self {
+            Outcome::Victory => "VICTORY",
+            Outcome::Defeat => "DEFEAT",
+            Outcome::Ended => "SESSION ENDED",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameState {
+    /// Sandbox: no objective, never ends on its own
+    FreePlay,
+    /// Working toward an objective
+    Playing(Objective),
+    /// Finished — the summary screen is up
+    Over { outcome: Outcome, objective: Option<Objective> },
+}
+
+impl GameState {
+    /// Fresh state for an optional objective
+    pub fn new(objective: Option<Objective>) -> Self {
+        objective.map_or(GameState::FreePlay, GameState::Playing)
+    }
+
+    pub fn objective(&self) -> Option<Objective> {
+        match *self {
+            GameState::FreePlay => None,
+            GameState::Playing(objective) => Some(objective),
+            GameState::Over { objective, .. } => objective,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        matches!(self, GameState::Over { .. })
+    }
+}
+
+/// Population snapshot objectives are judged against
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Standing {
+    pub world: FactionStats,
+    pub player: FactionStats,
+    pub hostiles_in_flight: bool,
+}
+
+/// Running totals for the end summary
+#[derive(Clone, Debug, Default)]
+pub struct GameStats {
+    pub strikes_launched: u32,
+    /// Adversary counterstrikes received
+    pub waves: u32,
+    pub intercepts: u32,
+    /// Most fires burning at once
+    pub peak_fires: usize,
+    /// Roads, railways, airports and ports knocked out
+    pub infrastructure_destroyed: u32,
+    /// People born back into rebuilding cities
+    pub births: u64,
+    /// Total casualties, sampled every `HISTORY_INTERVAL` frames
+    pub history: Vec<u64>,
+    /// Simulated time the game started at (unix seconds)
+    pub started_sim_secs: f64,
+    /// Frame and simulated time the game ended on
+    pub ended: Option<(u64, f64)>,
+}
+
+impl GameStats {
+    /// (play seconds, simulated seconds) since the start, frozen once ended.
+    /// Play time assumes the ~60fps main loop.
+    pub fn elapsed(&self, frame: u64, sim_secs: f64) -> (f64, f64) {
+        let (frame, sim_secs) = self.ended.unwrap_or((frame, sim_secs));
+        (frame as f64 / 60.0, sim_secs - self.started_sim_secs)
+    }
+
+    /// Resample the casualty history to `width` points for a chart
+    pub fn history_resampled(&self, width: usize) -> Vec<u64> {
+        if self.history.len() <= width || width == 0 {
+            return self.history.clone();
+        }
+        (0..width)
+            .map(|i| self.history[i * (self.history.len() - 1) / (width - 1).max(1)])
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standing(world: (u64, u64), player: (u64, u64), hostiles_in_flight: bool) -> Standing {
+        Standing {
+            world: FactionStats { population: world.0, original_population: world.1 },
+            player: FactionStats { population: player.0, original_population: player.1 },
+            hostiles_in_flight,
+        }
+    }
+
+    #[test]
+    fn parses_objectives() {
+        assert_eq!(Objective::parse("depopulate").unwrap(), Objective::Depopulate { percent: 50.0 });
+        assert_eq!(
+            Objective::parse("survive:5:25%").unwrap(),
+            Objective::Survive { waves: 5, percent: 25.0 }
+        );
+        assert!(Objective::parse("conquer").is_err());
+        assert!(Objective::parse("depopulate:150").is_err());
+        assert!(Objective::parse("survive:2:50:1").is_err());
+        assert!(Objective::parse("survive:-2").is_err());
+        assert!(Objective::parse("survive:NaN").is_err());
+        assert!(Objective::parse("survive:2.5").is_err());
+        assert!(Objective::parse("depopulate:NaN").is_err());
+    }
+
+    #[test]
+    fn judges_survival() {
+        let goal = Objective::Survive { waves: 2, percent: 50.0 };
+        let mut stats = GameStats { waves: 2, ..Default::default() };
+        assert_eq!(goal.judge(&standing((90, 100), (60, 100), true), &stats), None);
+        assert_eq!(goal.judge(&standing((90, 100), (60, 100), false), &stats), Some(Outcome::Victory));
+        stats.waves = 1;
+        assert_eq!(goal.judge(&standing((90, 100), (40, 100), false), &stats), Some(Outcome::Defeat));
+
+        let goal = Objective::Depopulate { percent: 50.0 };
+        assert_eq!(goal.judge(&standing((60, 100), (0, 0), false), &stats), None);
+        assert_eq!(goal.judge(&standing((40, 100), (0, 0), false), &stats), Some(Outcome::Victory));
+    }
+
+    #[test]
+    fn resamples_history_keeping_endpoints() {
+        let stats = GameStats { history: (0..100).collect(), ..Default::default() };
+        let chart = stats.history_resampled(10);
+        assert_eq!(chart.len(), 10);
+        assert_eq!((chart[0], chart[9]), (0, 99));
+    }
+}
@@ -3,15 +3,118 @@
 #[inline(always)]
 pub fn normalize_lon(lon: f64) -> f64 {
     let v = lon + 180.0;
-    if v >= 0.0 && v < 360.0 {
+    if (0.0..360.0).contains(&v) {
         v
     } else {
         v.rem_euclid(360.0)
     }
 }
 
+/// Wrap longitude into [-180, 180)
+#[inline(always)]
+pub fn wrap_lon(lon: f64) -> f64 {
+    normalize_lon(lon) - 180.0
+}
+
 /// Normalize latitude from [-90, 90] to [0, 180) for grid indexing
 #[inline(always)]
 pub fn normalize_lat(lat: f64) -> f64 {
     (lat + 90.0).clamp(0.0, 179.999)
 }
+
+/// Point a fraction `t` of the way along the great circle between two
+/// coordinates (spherical linear interpolation). Returns (lon, lat).
+pub fn great_circle_point(lon1: f64, lat1: f64, lon2: f64, lat2: f64, t: f64) -> (f64, f64) {
+    let to_vec = |lon: f64, lat: f64| {
+        let (lon, lat) = (lon.to_radians(), lat.to_radians());
+        [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+    };
+    let a = to_vec(lon1, lat1);
+    let b = to_vec(lon2, lat2);
+    let dot = (a[0] * b[0] + a[1] * b[1] + a[2] * b[2]).clamp(-1.0, 1.0);
+    let omega = dot.acos();
+    // Coincident (or antipodal) endpoints have no unique arc — fall back to lerp
+    let (wa, wb) = if omega.sin().abs() < 1e-9 {
+        (1.0 - t, t)
+    } else {
+        (((1.0 - t) * omega).sin() / omega.sin(), (t * omega).sin() / omega.sin())
+    };
+    let p = [wa * a[0] + wb * b[0], wa * a[1] + wb * b[1], wa * a[2] + wb * b[2]];
+    let lat = p[2].clamp(-1.0, 1.0).asin().to_degrees();
+    let lon = p[1].atan2(p[0]).to_degrees();
+    (lon, lat)
+}
+
+/// Great-circle (haversine) distance in kilometers
+pub fn great_circle_km(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    const R: f64 = 6371.0;
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dphi = phi2 - phi1;
+    let dlambda = (lon2 - lon1).to_radians();
+    let a = (dphi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (dlambda / 2.0).sin().powi(2);
+    2.0 * R * a.sqrt().min(1.0).asin()
+}
+
+/// Point `km` along the great circle leaving (lon, lat) on `bearing_deg`
+/// (clockwise from north). Returns (lon, lat).
+pub fn destination_point(lon: f64, lat: f64, bearing_deg: f64, km: f64) -> (f64, f64) {
+    const R: f64 = 6371.0;
+    let (phi1, lambda1) = (lat.to_radians(), lon.to_radians());
+    let (delta, theta) = (km / R, bearing_deg.to_radians());
+    let phi2 = (phi1.sin() * delta.cos() + phi1.cos() * delta.sin() * theta.cos()).clamp(-1.0, 1.0).asin();
+    let lambda2 = lambda1
+        + (theta.sin() * delta.sin() * phi1.cos()).atan2(delta.cos() - phi1.sin() * phi2.sin());
+    (wrap_lon(lambda2.to_degrees()), phi2.to_degrees())
+}
+
+/// Eight-point compass name for a bearing (clockwise from north)
+pub fn compass_point(bearing_deg: f64) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    POINTS[((bearing_deg.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn great_circle_hits_endpoints_and_crosses_dateline() {
+        let (lon, lat) = great_circle_point(-100.0, 40.0, 30.0, 50.0, 0.0);
+        assert!((lon + 100.0).abs() < 1e-9 && (lat - 40.0).abs() < 1e-9);
+        let (lon, lat) = great_circle_point(-100.0, 40.0, 30.0, 50.0, 1.0);
+        assert!((lon - 30.0).abs() < 1e-9 && (lat - 50.0).abs() < 1e-9);
+
+        // Short way across the Pacific, not back through Greenwich
+        let (lon, lat) = great_circle_point(170.0, 0.0, -170.0, 0.0, 0.5);
+        assert!((wrap_lon(lon) + 180.0).abs() < 1e-9 && lat.abs() < 1e-9);
+
+        // Transpolar routes bow poleward of both endpoints
+        let (_, lat) = great_circle_point(-100.0, 48.0, 40.0, 55.0, 0.5);
+        assert!(lat > 65.0);
+    }
+
+    #[test]
+    fn great_circle_distance() {
+        // Quarter of the equator
+        assert!((great_circle_km(0.0, 0.0, 90.0, 0.0) - 10007.5).abs() < 1.0);
+        // Across the dateline is short
+        assert!(great_circle_km(179.5, 0.0, -179.5, 0.0) < 112.0);
+    }
+
+    #[test]
+    fn compass_points_wrap_at_north() {
+        assert_eq!(compass_point(0.0), "N");
+        assert_eq!(compass_point(350.0), "N");
+        assert_eq!(compass_point(-90.0), "W");
+        assert_eq!(compass_point(135.0), "SE");
+    }
+
+    #[test]
+    fn destination_point_round_trips_distance() {
+        let (lon, lat) = destination_point(-74.0, 40.7, 90.0, 500.0);
+        assert!((great_circle_km(-74.0, 40.7, lon, lat) - 500.0).abs() < 1e-6);
+        assert!(lon > -74.0);
+        let (lon, lat) = destination_point(179.0, 0.0, 90.0, 333.6);
+        assert!((lon + 178.0).abs() < 0.01 && lat.abs() < 1e-9, "{lon} {lat}");
+    }
+}
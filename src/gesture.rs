@@ -0,0 +1,178 @@
+//! Turns raw mouse events into what they mean on the map.
+//!
+//! Trackpads and touch screens come through the terminal as ordinary mouse
+//! sequences: a tap is a left click, a two-finger swipe a burst of scroll
+//! events, a pinch usually scroll with Ctrl held. Two taps close together
+//! are a double click, which zooms in. Vertical scroll zooms by default; in
+//! pan mode it moves the map and only Ctrl or Alt scroll zooms. Trackpads
+//! wobble at the end of a swipe, so a scroll that reverses the one just
+//! before it is dropped.
+
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use std::time::{Duration, Instant};
+
+/// Default window for the second click of a double click
+pub const DEFAULT_DOUBLE_CLICK: Duration = Duration::from_millis(350);
+
+/// A scroll against the previous one this soon after it is jitter
+const SCROLL_DEBOUNCE: Duration = Duration::from_millis(60);
+
+/// Pixels one scroll event pans
+const SCROLL_PAN: i32 = 15;
+
+/// What vertical scrolling does
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScrollMode {
+    /// Zoom toward the mouse (a mouse wheel)
+    #[default]
+    Zoom,
+    /// Pan up and down (a two-finger trackpad swipe); Ctrl or Alt zooms
+    Pan,
+}
+
+impl ScrollMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "zoom" => Some(Self::Zoom),
+            "pan" => Some(Self::Pan),
+            _ => None,
+        }
+    }
+}
+
+/// A mouse event after gesture detection
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Input {
+    /// Anything without a gesture of its own, to handle as it is
+    Mouse(MouseEvent),
+    /// Second left press soon after the first, at the same cell
+    DoubleClick { col: u16, row: u16 },
+    /// Wheel notches toward the mouse (negative zooms out)
+    Zoom { col: u16, row: u16, notches: i32, fast: bool },
+    /// Pixels to pan the map by
+    Pan(i32, i32),
+}
+
+pub struct Gestures {
+    pub scroll: ScrollMode,
+    /// Longest gap between the clicks of a double click (zero turns it off)
+    pub double_click: Duration,
+    last_click: Option<(Instant, u16, u16)>,
+    last_scroll: Option<(Instant, MouseEventKind)>,
+}
+
+impl Default for Gestures {
+    fn default() -> Self {
+        Self { scroll: ScrollMode::Zoom, double_click: DEFAULT_DOUBLE_CLICK, last_click: None, last_scroll: None }
+    }
+}
+
+impl Gestures {
+    /// Classify a mouse event that arrived at `now`. `None` drops it.
+    pub fn input(&mut self, mouse: MouseEvent, now: Instant) -> Option<Input> {
+        let (col, row) = (mouse.column, mouse.row);
+        let fast = mouse.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match mouse.kind {
+            MouseEventKind::ScrollUp
+            | MouseEventKind::ScrollDown
+            | MouseEventKind::ScrollLeft
+            | MouseEventKind::ScrollRight => {
+                let previous = self.last_scroll.replace((now, mouse.kind));
+                if previous.is_some_and(|(at, kind)| now.duration_since(at) < SCROLL_DEBOUNCE && reverses(kind, mouse.kind)) {
+                    // Keep the swipe's direction, so a run of wobble stays dropped
+                    self.last_scroll = previous;
+                    return None;
+                }
+                Some(match mouse.kind {
+                    MouseEventKind::ScrollLeft => Input::Pan(-SCROLL_PAN, 0),
+                    MouseEventKind::ScrollRight => Input::Pan(SCROLL_PAN, 0),
+                    kind => {
+                        let up = kind == MouseEventKind::ScrollUp;
+                        match self.scroll {
+                            ScrollMode::Pan if !fast => Input::Pan(0, if up { -SCROLL_PAN } else { SCROLL_PAN }),
+                            // Ctrl already means a pinch in pan mode, so it doesn't speed it up too
+                            mode => Input::Zoom { col, row, notches: if up { 1 } else { -1 }, fast: fast && mode == ScrollMode::Zoom },
+                        }
+                    }
+                })
+            }
+            MouseEventKind::Down(MouseButton::Left) if mouse.modifiers.is_empty() => {
+                let previous = self.last_click.replace((now, col, row));
+                let double = previous.is_some_and(|(at, c, r)| {
+                    now.duration_since(at) <= self.double_click && c.abs_diff(col) <= 1 && r.abs_diff(row) <= 1
+                });
+                if double {
+                    // A third click starts over rather than zooming again
+                    self.last_click = None;
+                    return Some(Input::DoubleClick { col, row });
+                }
+                Some(Input::Mouse(mouse))
+            }
+            // Dragging between the clicks makes them two separate presses
+            MouseEventKind::Drag(_) => {
+                self.last_click = None;
+                Some(Input::Mouse(mouse))
+            }
+            _ => Some(Input::Mouse(mouse)),
+        }
+    }
+}
+
+/// Whether scroll `b` goes the opposite way to `a`
+fn reverses(a: MouseEventKind, b: MouseEventKind) -> bool {
+    use MouseEventKind::*;
+    matches!((a, b), (ScrollUp, ScrollDown) | (ScrollDown, ScrollUp) | (ScrollLeft, ScrollRight) | (ScrollRight, ScrollLeft))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mouse(kind: MouseEventKind, col: u16, modifiers: KeyModifiers) -> MouseEvent {
+        MouseEvent { kind, column: col, row: 10, modifiers }
+    }
+
+    fn ms(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn two_quick_clicks_are_a_double_click() {
+        let mut gestures = Gestures::default();
+        let t = Instant::now();
+        let click = mouse(MouseEventKind::Down(MouseButton::Left), 20, KeyModifiers::NONE);
+        assert_eq!(gestures.input(click, t), Some(Input::Mouse(click)));
+        assert_eq!(gestures.input(click, ms(t, 200)), Some(Input::DoubleClick { col: 20, row: 10 }));
+        assert_eq!(gestures.input(click, ms(t, 300)), Some(Input::Mouse(click)));
+
+        // Too slow, too far apart, or dragged in between
+        assert_eq!(gestures.input(click, ms(t, 1000)), Some(Input::Mouse(click)));
+        let far = mouse(MouseEventKind::Down(MouseButton::Left), 30, KeyModifiers::NONE);
+        assert_eq!(gestures.input(far, ms(t, 1100)), Some(Input::Mouse(far)));
+        gestures.input(mouse(MouseEventKind::Drag(MouseButton::Left), 30, KeyModifiers::NONE), ms(t, 1150));
+        assert_eq!(gestures.input(far, ms(t, 1200)), Some(Input::Mouse(far)));
+
+        gestures.double_click = Duration::ZERO;
+        assert_eq!(gestures.input(far, ms(t, 1201)), Some(Input::Mouse(far)));
+    }
+
+    #[test]
+    fn scroll_zooms_or_pans_and_drops_jitter() {
+        let mut gestures = Gestures::default();
+        let t = Instant::now();
+        let up = mouse(MouseEventKind::ScrollUp, 20, KeyModifiers::NONE);
+        let down = mouse(MouseEventKind::ScrollDown, 20, KeyModifiers::NONE);
+        assert_eq!(gestures.input(up, t), Some(Input::Zoom { col: 20, row: 10, notches: 1, fast: false }));
+        assert_eq!(gestures.input(down, ms(t, 20)), None);
+        assert_eq!(gestures.input(down, ms(t, 40)), None);
+        assert!(matches!(gestures.input(up, ms(t, 50)), Some(Input::Zoom { notches: 1, .. })));
+        assert!(matches!(gestures.input(down, ms(t, 200)), Some(Input::Zoom { notches: -1, .. })));
+
+        gestures.scroll = ScrollMode::Pan;
+        assert_eq!(gestures.input(up, ms(t, 400)), Some(Input::Pan(0, -SCROLL_PAN)));
+        let pinch = mouse(MouseEventKind::ScrollUp, 20, KeyModifiers::CONTROL);
+        assert_eq!(gestures.input(pinch, ms(t, 410)), Some(Input::Zoom { col: 20, row: 10, notches: 1, fast: false }));
+        let left = mouse(MouseEventKind::ScrollLeft, 20, KeyModifiers::NONE);
+        assert_eq!(gestures.input(left, ms(t, 420)), Some(Input::Pan(-SCROLL_PAN, 0)));
+    }
+}
@@ -0,0 +1,556 @@
+//! Real images in the terminal, for terminals that can show them.
+//!
+//! With the kitty graphics protocol (kitty, WezTerm, Ghostty) the basemap's
+//! coastlines and borders are drawn at the terminal's pixel resolution, the
+//! way screenshots are, and placed beneath the text. The braille layers for
+//! them are left out; cities, weapons, overlays and the rest still draw as
+//! text on top. The image is only sent again when the view changes, and
+//! while the view is moving, only once it has held still for `SETTLE`.
+//!
+//! Sixel (mlterm, foot, xterm with sixel built in) has no layers: an image
+//! overwrites the cells it lands on, and text written later overwrites the
+//! image. So sixel is used for the heatmaps alone, painted into the map
+//! cells left blank and transparent over everything else. It's sent again
+//! whenever those blank cells change.
+
+use crate::app::{App, View};
+use crate::hash::{hash2, hash3};
+use crate::heatmap::{ColorRamp, Scale};
+use crate::map::snapshot::{self, Raster, Rgb};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ratatui::buffer::Buffer;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// Image id the basemap is sent under, so each one replaces the last
+const IMAGE_ID: u32 = 1;
+
+/// Base64 bytes per escape sequence, the protocol's limit
+const CHUNK: usize = 4096;
+
+/// Cell size to assume when the terminal won't report its pixels
+const FALLBACK_CELL_PX: (u32, u32) = (8, 16);
+
+/// How long the view must hold still before the basemap is drawn again
+const SETTLE: Duration = Duration::from_millis(150);
+
+/// Widest image drawn; bigger terminals get it scaled up
+const MAX_WIDTH_PX: u32 = 2560;
+
+/// Colors per heatmap in a sixel image, and how many heatmaps get one
+const SIXEL_STEPS: usize = 64;
+const SIXEL_LAYERS: usize = 4;
+
+/// Drawn beneath cells with a background color as well as beneath text,
+/// so popups with one still cover the map
+const Z_INDEX: i32 = -1_073_741_825;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    Kitty,
+    Sixel,
+}
+
+impl Protocol {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "kitty" => Some(Self::Kitty),
+            "sixel" => Some(Self::Sixel),
+            _ => None,
+        }
+    }
+
+    /// The protocol of the terminal we're running in, if it has one. Kitty
+    /// is known by its environment; sixel by asking the terminal, and by
+    /// its name when it won't say.
+    pub fn detect() -> Option<Self> {
+        let var = |name| std::env::var(name).ok();
+        let guess = Self::from_env(var("TERM").as_deref(), var("TERM_PROGRAM").as_deref(), var("KITTY_WINDOW_ID").is_some());
+        if guess == Some(Self::Kitty) {
+            return guess;
+        }
+        match query_device_attributes() {
+            Some(answer) => da1_has_sixel(&answer).then_some(Self::Sixel),
+            None => guess,
+        }
+    }
+
+    /// Protocol from `TERM`, `TERM_PROGRAM` and whether `KITTY_WINDOW_ID`
+    /// is set. Kitty's is preferred where a terminal has both. Plain xterm
+    /// may or may not be built with sixel, so it takes `--graphics sixel`.
+    pub fn from_env(term: Option<&str>, term_program: Option<&str>, kitty_window: bool) -> Option<Self> {
+        let kitty = kitty_window
+            || term.is_some_and(|t| t.contains("kitty") || t.contains("ghostty"))
+            || matches!(term_program, Some("WezTerm" | "ghostty"));
+        let sixel = term.is_some_and(|t| ["mlterm", "foot", "contour", "sixel"].iter().any(|name| t.contains(name)));
+        if kitty {
+            Some(Self::Kitty)
+        } else {
+            sixel.then_some(Self::Sixel)
+        }
+    }
+}
+
+/// How long to wait for the terminal to answer a query
+const QUERY_TIMEOUT: Duration = Duration::from_millis(300);
+
+/// Most bytes read back from a query
+const QUERY_MAX: usize = 256;
+
+/// Whether a primary device attributes answer (`ESC [ ? 62 ; 4 ; … c`)
+/// lists sixel graphics, attribute 4
+pub fn da1_has_sixel(answer: &[u8]) -> bool {
+    let Some(start) = answer.windows(3).position(|w| w == b"\x1b[?") else { return false };
+    let rest = &answer[start + 3..];
+    let Some(end) = rest.iter().position(|&b| b == b'c') else { return false };
+    rest[..end].split(|&b| b == b';').skip(1).any(|attr| attr == b"4")
+}
+
+/// Ask the terminal for its primary device attributes, followed by a
+/// status report every terminal answers, so one that ignores the first
+/// doesn't leave us waiting. The bytes read up to the status answer, or
+/// None when there's no terminal or it didn't answer in time.
+#[cfg(unix)]
+fn query_device_attributes() -> Option<Vec<u8>> {
+    use std::fs::OpenOptions;
+    use std::io::Read;
+    use std::os::fd::AsRawFd;
+
+    let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty").ok()?;
+    let was_raw = crossterm::terminal::is_raw_mode_enabled().unwrap_or(false);
+    if !was_raw {
+        crossterm::terminal::enable_raw_mode().ok()?;
+    }
+    let answer = (|| {
+        tty.write_all(b"\x1b[c\x1b[5n").ok()?;
+        tty.flush().ok()?;
+        let deadline = Instant::now() + QUERY_TIMEOUT;
+        let mut answer = Vec::new();
+        let mut buf = [0u8; 64];
+        while !answer.ends_with(b"\x1b[0n") && answer.len() < QUERY_MAX {
+            let left = deadline.checked_duration_since(Instant::now())?;
+            let mut fd = libc::pollfd { fd: tty.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+            // SAFETY: one valid pollfd, borrowed for the call
+            if unsafe { libc::poll(&mut fd, 1, left.as_millis() as libc::c_int) } <= 0 {
+                return None;
+            }
+            let n = tty.read(&mut buf).ok().filter(|&n| n > 0)?;
+            answer.extend_from_slice(&buf[..n]);
+        }
+        Some(answer)
+    })();
+    if !was_raw {
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+    answer
+}
+
+#[cfg(not(unix))]
+fn query_device_attributes() -> Option<Vec<u8>> {
+    None
+}
+
+/// The basemap image on screen with the kitty protocol, and what it was
+/// drawn from
+#[derive(Default)]
+pub struct Basemap {
+    /// Key of the view the image on screen shows
+    shown: Option<u64>,
+    /// A view not drawn yet, and since when it has held
+    pending: Option<(u64, Instant)>,
+}
+
+impl Basemap {
+
+    /// Draw the basemap under the map's cells once the view has changed
+    /// and then held for `SETTLE`, or take it away when the map isn't
+    /// showing. The first image goes out straight away.
+    pub fn update(&mut self, out: &mut impl Write, app: &App, now: Instant) -> io::Result<()> {
+        if app.view != View::Map || app.text_mode {
+            self.pending = None;
+            if self.shown.take().is_some() {
+                out.write_all(&delete())?;
+                out.flush()?;
+            }
+            return Ok(());
+        }
+        let key = view_key(app);
+        if self.shown == Some(key) {
+            self.pending = None;
+            return Ok(());
+        }
+        if self.shown.is_some() {
+            match self.pending {
+                Some((pending, since)) if pending == key && now.duration_since(since) >= SETTLE => {}
+                Some((pending, _)) if pending == key => return Ok(()),
+                _ => {
+                    self.pending = Some((key, now));
+                    return Ok(());
+                }
+            }
+        }
+        self.pending = None;
+        let (width, height) = app.projection.size();
+        let (cols, rows) = ((width / 2) as u32, (height / 4) as u32);
+        if cols == 0 || rows == 0 {
+            return Ok(());
+        }
+        let (cell_w, cell_h) = cell_pixels();
+        let scale = (MAX_WIDTH_PX as f64 / (cols * cell_w) as f64).min(1.0);
+        let px_w = ((cols * cell_w) as f64 * scale).max(1.0) as u32;
+        let px_h = ((rows * cell_h) as f64 * scale).max(1.0) as u32;
+
+        let projection = snapshot::scaled_projection(&app.projection, px_w as usize, px_h as usize);
+        let mut raster = Raster::new(px_w, px_h, snapshot::BACKGROUND);
+        snapshot::draw_basemap(&mut raster, &app.map_renderer, &projection);
+        write_at_map(out, &kitty_image(&raster.encode_png().map_err(io::Error::other)?, cols, rows))?;
+        self.shown = Some(key);
+        Ok(())
+    }
+}
+
+impl Drop for Basemap {
+    fn drop(&mut self) {
+        if self.shown.is_some() {
+            let mut out = io::stdout();
+            let _ = out.write_all(&delete());
+            let _ = out.flush();
+        }
+    }
+}
+
+/// The heatmaps and the population density raster painted with sixel
+/// into the map's blank cells
+#[derive(Default)]
+pub struct SixelHeatmaps {
+    /// Key of what the image on screen was drawn from
+    shown: Option<u64>,
+    /// Palette index per pixel, kept between images
+    pixels: Vec<Option<u8>>,
+}
+
+/// A raster painted into the image: its colors, and how far along them
+/// each point sits
+struct SixelLayer<'a> {
+    ramp: &'a ColorRamp,
+    level: Box<dyn Fn(f64, f64) -> Option<f32> + 'a>,
+}
+
+/// The rasters to paint, top first, and a key naming them
+fn sixel_layers(app: &App) -> (Vec<SixelLayer<'_>>, u64) {
+    let mut layers = Vec::new();
+    let mut key = 0;
+    for heatmap in app.heatmaps.shown().take(SIXEL_LAYERS) {
+        key = heatmap.name.bytes().fold(key, |h, b| hash2(h, b as u64));
+        key = hash3(key, heatmap.range.0.to_bits() as u64, heatmap.range.1.to_bits() as u64);
+        key = hash3(key, heatmap.ramp as *const ColorRamp as u64, (heatmap.scale == Scale::Log) as u64);
+        layers.push(SixelLayer {
+            ramp: heatmap.ramp,
+            level: Box::new(move |lon, lat| Some(heatmap.level(heatmap.value_at(lon, lat)?))),
+        });
+    }
+    if let Some(grid) = app.population.as_ref().filter(|_| app.show_population) {
+        // Strikes thin it out
+        key = hash3(key, u64::MAX, app.casualties);
+        layers.push(SixelLayer {
+            ramp: &ColorRamp::POPULATION,
+            level: Box::new(move |lon, lat| {
+                let density = grid.density_at(lon, lat);
+                (density >= 1.0).then(|| (density.log10() / 3.0).min(1.0))
+            }),
+        });
+    }
+    (layers, key)
+}
+
+impl SixelHeatmaps {
+    /// Paint the shown heatmaps and density into the cells of `buffer`
+    /// left blank, if the view or the layers have changed since they were
+    /// last painted. Cells that fill or empty later keep what they had.
+    /// Returns true when an image is on screen that should no longer be,
+    /// which only redrawing every cell takes away.
+    pub fn update(&mut self, out: &mut impl Write, app: &App, buffer: &Buffer) -> io::Result<bool> {
+        let (layers, layers_key) = sixel_layers(app);
+        let (width, height) = app.projection.size();
+        let (cols, rows) = ((width / 2) as u32, (height / 4) as u32);
+        if app.view != View::Map || app.text_mode || layers.is_empty() || cols == 0 || rows == 0 {
+            return Ok(self.shown.take().is_some());
+        }
+        let (cell_w, cell_h) = cell_pixels();
+        let key = hash3(hash3(view_key(app), cell_w as u64, cell_h as u64), layers_key, layers.len() as u64);
+        if self.shown == Some(key) {
+            return Ok(false);
+        }
+
+        let palette: Vec<Rgb> = layers
+            .iter()
+            .flat_map(|l| (0..SIXEL_STEPS).map(|i| l.ramp.color(i as f32 / (SIXEL_STEPS - 1) as f32)))
+            .collect();
+        // Sampled at braille resolution, two by four per cell, each sample
+        // a block of pixels, in the map cells with nothing drawn in them
+        let (img_w, img_h) = (cols * cell_w, rows * cell_h);
+        self.pixels.clear();
+        self.pixels.resize((img_w * img_h) as usize, None);
+        let pixels = &mut self.pixels;
+        for (cx, cy) in (0..rows).flat_map(|cy| (0..cols).map(move |cx| (cx, cy))) {
+            if buffer.cell((cx as u16 + 1, cy as u16 + 1)).is_some_and(|cell| cell.symbol() != " ") {
+                continue;
+            }
+            for (sx, sy) in (0..2).flat_map(|sx| (0..4).map(move |sy| (sx, sy))) {
+                let Some((lon, lat)) = app.projection.unproject((cx * 2 + sx) as i32, (cy * 4 + sy) as i32) else { continue };
+                let color = layers.iter().enumerate().find_map(|(i, l)| {
+                    let level = (l.level)(lon, lat)?;
+                    Some((i * SIXEL_STEPS + (level * (SIXEL_STEPS - 1) as f32).round() as usize) as u8)
+                });
+                let Some(color) = color else { continue };
+                let x0 = cx * cell_w + sx * cell_w / 2;
+                let y0 = cy * cell_h + sy * cell_h / 4;
+                for y in y0..cy * cell_h + (sy + 1) * cell_h / 4 {
+                    let row = (y * img_w) as usize;
+                    pixels[row + x0 as usize..row + (cx * cell_w + (sx + 1) * cell_w / 2) as usize].fill(Some(color));
+                }
+            }
+        }
+        write_at_map(out, &sixel_image(img_w as usize, img_h as usize, pixels, &palette))?;
+        self.shown = Some(key);
+        Ok(false)
+    }
+}
+
+/// Write an image with its top left inside the map's border, leaving the
+/// cursor where ratatui had it
+fn write_at_map(out: &mut impl Write, image: &[u8]) -> io::Result<()> {
+    out.write_all(b"\x1b7\x1b[2;2H")?;
+    out.write_all(image)?;
+    out.write_all(b"\x1b8")?;
+    out.flush()
+}
+
+/// Everything the image depends on, hashed
+fn view_key(app: &App) -> u64 {
+    let projection = &app.projection;
+    let (width, height) = projection.size();
+    let settings = &app.map_renderer.settings;
+    let mut key = hash3(width as u64, height as u64, projection.effective_zoom().to_bits());
+    key = hash3(key, projection.center_lon().to_bits(), projection.center_lat().to_bits());
+    key = projection.name().bytes().chain(app.theme.name.bytes()).fold(key, |h, b| hash2(h, b as u64));
+    key = hash2(key, app.map_renderer.data_generation());
+    let shown = [settings.show_coastlines, settings.show_borders, settings.show_states];
+    shown.into_iter().fold(key, |h, on| hash2(h, on as u64))
+}
+
+/// The terminal's cell size in pixels
+fn cell_pixels() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => {
+            ((size.width / size.columns).max(1) as u32, (size.height / size.rows).max(1) as u32)
+        }
+        _ => FALLBACK_CELL_PX,
+    }
+}
+
+/// A PNG placed at the cursor and stretched over `cols`×`rows` cells,
+/// replacing any earlier image with the same id. The cursor stays put.
+fn kitty_image(png: &[u8], cols: u32, rows: u32) -> Vec<u8> {
+    let data = BASE64.encode(png);
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(CHUNK).collect();
+    let mut out = Vec::with_capacity(data.len() + chunks.len() * 16 + 64);
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+        if i == 0 {
+            let _ = write!(out, "\x1b_Ga=T,f=100,i={IMAGE_ID},q=2,C=1,c={cols},r={rows},z={Z_INDEX},m={more};");
+        } else {
+            let _ = write!(out, "\x1b_Gm={more};");
+        }
+        out.extend_from_slice(chunk);
+        out.extend_from_slice(b"\x1b\\");
+    }
+    out
+}
+
+/// Take the basemap image off the screen and out of the terminal's memory
+fn delete() -> Vec<u8> {
+    format!("\x1b_Ga=d,d=I,i={IMAGE_ID},q=2\x1b\\").into_bytes()
+}
+
+/// Sixel image of palette indices, `None` left transparent
+fn sixel_image(width: usize, height: usize, pixels: &[Option<u8>], palette: &[Rgb]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(width * height / 3);
+    let _ = write!(out, "\x1bP0;1;0q\"1;1;{width};{height}");
+    let percent = |c: u8| (c as u32 * 100 + 127) / 255;
+    for (i, &(r, g, b)) in palette.iter().enumerate() {
+        let _ = write!(out, "#{i};2;{};{};{}", percent(r), percent(g), percent(b));
+    }
+    for band in (0..height).step_by(6) {
+        let rows = band..(band + 6).min(height);
+        let mut used = vec![false; palette.len()];
+        for y in rows.clone() {
+            for &color in pixels[y * width..(y + 1) * width].iter().flatten() {
+                used[color as usize] = true;
+            }
+        }
+        for color in (0..palette.len()).filter(|&c| used[c]) {
+            let column = |x: usize| {
+                rows.clone().enumerate().fold(0u8, |bits, (dy, y)| bits | ((pixels[y * width + x] == Some(color as u8)) as u8) << dy)
+            };
+            let columns: Vec<u8> = (0..width).map(column).collect();
+            let end = columns.iter().rposition(|&bits| bits != 0).map_or(0, |x| x + 1);
+            let _ = write!(out, "#{color}");
+            let mut x = 0;
+            while x < end {
+                let run = columns[x..end].iter().take_while(|&&bits| bits == columns[x]).count();
+                let ch = (b'?' + columns[x]) as char;
+                match run {
+                    1..=3 => (0..run).for_each(|_| out.push(ch as u8)),
+                    _ => {
+                        let _ = write!(out, "!{run}{ch}");
+                    }
+                }
+                x += run;
+            }
+            out.push(b'$');
+        }
+        out.push(b'-');
+    }
+    out.extend_from_slice(b"\x1b\\");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heatmap::HeatmapLayer;
+
+    #[test]
+    fn the_image_goes_out_in_chunks_the_terminal_can_take() {
+        let png: Vec<u8> = (0..10_000u32).map(|i| (i * 7) as u8).collect();
+        let out = String::from_utf8(kitty_image(&png, 80, 24)).unwrap();
+        let sequences: Vec<&str> = out.split_terminator("\x1b\\").collect();
+        assert_eq!(sequences.len(), BASE64.encode(&png).len().div_ceil(CHUNK));
+        assert!(sequences[0].starts_with("\x1b_Ga=T,f=100,i=1,q=2,C=1,c=80,r=24,z=-1073741825,m=1;"));
+        let mut payload = String::new();
+        for (i, sequence) in sequences.iter().enumerate() {
+            let (keys, data) = sequence.split_once(';').unwrap();
+            assert_eq!(keys.ends_with("m=0"), i + 1 == sequences.len(), "{keys}");
+            assert!(data.len() <= CHUNK);
+            payload.push_str(data);
+        }
+        assert_eq!(payload, BASE64.encode(&png));
+
+        // Sent once per view, and taken away with the map
+        let t0 = Instant::now();
+        let mut app = App::new(82, 43);
+        let mut basemap = Basemap::default();
+        let mut out = Vec::new();
+        basemap.update(&mut out, &app, t0).unwrap();
+        assert!(out.starts_with(b"\x1b7\x1b[2;2H\x1b_Ga=T,f=100,i=1,q=2,C=1,c=80,r=40,"));
+        out.clear();
+        basemap.update(&mut out, &app, t0).unwrap();
+        assert!(out.is_empty());
+        app.view = View::Stats;
+        basemap.update(&mut out, &app, t0).unwrap();
+        assert_eq!(out, delete());
+        basemap.shown = None;
+    }
+
+    #[test]
+    fn the_basemap_waits_for_the_view_to_settle() {
+        let t0 = Instant::now();
+        let mut app = App::new(82, 43);
+        let mut basemap = Basemap::default();
+        let mut out = Vec::new();
+        basemap.update(&mut out, &app, t0).unwrap();
+        out.clear();
+
+        // Panning: nothing is encoded until the view holds still
+        app.projection.pan(5, 0);
+        basemap.update(&mut out, &app, t0).unwrap();
+        app.projection.pan(5, 0);
+        basemap.update(&mut out, &app, t0 + SETTLE).unwrap();
+        assert!(out.is_empty());
+        basemap.update(&mut out, &app, t0 + SETTLE * 2).unwrap();
+        assert!(!out.is_empty());
+        out.clear();
+
+        // A new theme or reloaded data is a new image too
+        app.theme = crate::theme::Theme::by_name("norad").unwrap();
+        basemap.update(&mut out, &app, t0 + SETTLE * 3).unwrap();
+        basemap.update(&mut out, &app, t0 + SETTLE * 4).unwrap();
+        assert!(!out.is_empty());
+        out.clear();
+        app.map_renderer.invalidate_cache();
+        basemap.update(&mut out, &app, t0 + SETTLE * 5).unwrap();
+        basemap.update(&mut out, &app, t0 + SETTLE * 6).unwrap();
+        assert!(!out.is_empty());
+        basemap.shown = None;
+    }
+
+    #[test]
+    fn terminals_are_told_apart_by_their_environment() {
+
+        assert_eq!(Protocol::from_env(Some("xterm-kitty"), None, false), Some(Protocol::Kitty));
+        assert_eq!(Protocol::from_env(Some("xterm-256color"), Some("WezTerm"), false), Some(Protocol::Kitty));
+        assert_eq!(Protocol::from_env(Some("xterm-256color"), Some("Apple_Terminal"), false), None);
+        assert_eq!(Protocol::from_env(Some("mlterm"), None, false), Some(Protocol::Sixel));
+    }
+
+    #[test]
+    fn sixel_is_read_from_the_device_attributes() {
+        assert!(da1_has_sixel(b"\x1b[?62;4;6;22c\x1b[0n"));
+        assert!(da1_has_sixel(b"\x1b[?63;1;2;4c"));
+        assert!(!da1_has_sixel(b"\x1b[?62;22;42c\x1b[0n"));
+        // The first number is the terminal class, not an attribute
+        assert!(!da1_has_sixel(b"\x1b[?4;6c"));
+        assert!(!da1_has_sixel(b"\x1b[0n"));
+    }
+
+    #[test]
+    fn sixel_images_are_encoded_in_bands_of_six_rows() {
+        // Two palette colors over a 3×7 image: one band of six rows, then one
+        let pixels: Vec<Option<u8>> = (0..21).map(|i| match i % 3 {
+            0 => Some(0),
+            1 => Some(1),
+            _ => None,
+        }).collect();
+        let image = String::from_utf8(sixel_image(3, 7, &pixels, &[(255, 0, 0), (0, 0, 255)])).unwrap();
+        assert_eq!(image, "\x1bP0;1;0q\"1;1;3;7#0;2;100;0;0#1;2;0;0;100#0~$#1?~$-#0@$#1?@$-\x1b\\");
+    }
+
+    #[test]
+    fn sixel_heatmaps_are_sent_again_only_for_a_new_view_or_layers() {
+        let spec = crate::heatmap::HeatmapSpec::parse("warm.asc,ramp=fire,range=0..1").unwrap();
+        let warm = "ncols 1\nnrows 1\nxllcorner -180\nyllcorner -90\ncellsize 180\n0.5\n";
+        let mut app = App::new(22, 8);
+        app.heatmaps.layers.push(HeatmapLayer::parse("warm", warm, &spec).unwrap());
+        app.heatmaps.layers[0].shown = false;
+        let mut buffer = Buffer::empty(ratatui::layout::Rect::new(0, 0, 22, 8));
+        let mut heatmaps = SixelHeatmaps::default();
+        let mut out = Vec::new();
+        assert!(!heatmaps.update(&mut out, &app, &buffer).unwrap());
+        assert!(out.is_empty(), "nothing shown");
+
+        app.heatmaps.layers[0].shown = true;
+        heatmaps.update(&mut out, &app, &buffer).unwrap();
+        let (cols, rows) = (20, 5);
+        let (w, h) = cell_pixels();
+        assert!(out.starts_with(format!("\x1b7\x1b[2;2H\x1bP0;1;0q\"1;1;{};{}", cols * w, rows * h).as_bytes()));
+        out.clear();
+        // Glyphs coming and going don't make a new image
+        buffer[(5, 3)].set_char('✹');
+        heatmaps.update(&mut out, &app, &buffer).unwrap();
+        assert!(out.is_empty(), "unchanged");
+
+        app.projection.pan(4, 0);
+        heatmaps.update(&mut out, &app, &buffer).unwrap();
+        assert!(!out.is_empty(), "panned");
+        out.clear();
+        app.heatmaps.layers[0].range = (0.0, 2.0);
+        heatmaps.update(&mut out, &app, &buffer).unwrap();
+        assert!(!out.is_empty(), "rescaled");
+
+        app.heatmaps.layers[0].shown = false;
+        assert!(heatmaps.update(&mut out, &app, &buffer).unwrap(), "needs clearing off");
+        assert!(!heatmaps.update(&mut out, &app, &buffer).unwrap());
+    }
+}
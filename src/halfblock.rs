@@ -0,0 +1,83 @@
+//! Half-block canvas for color fills.
+//!
+//! Each character cell holds two pixels stacked vertically, drawn as `▀`
+//! with the top pixel's color in the foreground and the bottom one's in the
+//! background. Braille gives finer dots but one color per cell; half blocks
+//! give every pixel its own color, which suits area shading (fires,
+//! choropleths) rather than lines.
+
+/// 24-bit color
+pub type Rgb = (u8, u8, u8);
+
+#[derive(Clone)]
+pub struct HalfBlockCanvas {
+    width: usize,  // Characters
+    height: usize, // Characters
+    /// Row-major, two pixel rows per character row
+    pixels: Vec<Option<Rgb>>,
+}
+
+impl HalfBlockCanvas {
+    /// Canvas of `width` × `height` characters: width × height*2 pixels
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, pixels: vec![None; width * height * 2] }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        if x >= self.width || y >= self.height * 2 {
+            return None;
+        }
+        self.pixels[y * self.width + x]
+    }
+
+    /// Color pixel (x, y); out of range is ignored
+    pub fn set(&mut self, x: usize, y: usize, rgb: Rgb) {
+        if x < self.width && y < self.height * 2 {
+            self.pixels[y * self.width + x] = Some(rgb);
+        }
+    }
+
+    /// Like `set`, but keeps a color that's already there
+    pub fn fill(&mut self, x: usize, y: usize, rgb: Rgb) {
+        if self.get(x, y).is_none() {
+            self.set(x, y, rgb);
+        }
+    }
+
+    /// Glyph, foreground and background for a character cell; None if both
+    /// pixels are empty. A lone bottom pixel uses `▄` so the other half
+    /// keeps whatever background is already there.
+    pub fn cell(&self, x: usize, row: usize) -> Option<(char, Rgb, Option<Rgb>)> {
+        match (self.get(x, row * 2), self.get(x, row * 2 + 1)) {
+            (Some(top), Some(bottom)) if top == bottom => Some(('█', top, None)),
+            (Some(top), bottom) => Some(('▀', top, bottom)),
+            (None, Some(bottom)) => Some(('▄', bottom, None)),
+            (None, None) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cells_pick_glyphs_by_which_halves_are_filled() {
+        let mut canvas = HalfBlockCanvas::new(4, 1);
+        let (red, blue) = ((255, 0, 0), (0, 0, 255));
+        canvas.set(0, 0, red);
+        canvas.set(0, 1, blue);
+        canvas.set(1, 0, red);
+        canvas.set(2, 1, blue);
+        canvas.set(3, 0, red);
+        canvas.set(3, 1, red);
+        canvas.fill(3, 1, blue);
+        canvas.set(9, 9, blue);
+
+        assert_eq!(canvas.cell(0, 0), Some(('▀', red, Some(blue))));
+        assert_eq!(canvas.cell(1, 0), Some(('▀', red, None)));
+        assert_eq!(canvas.cell(2, 0), Some(('▄', blue, None)));
+        assert_eq!(canvas.cell(3, 0), Some(('█', red, None)));
+        assert_eq!(canvas.cell(4, 0), None);
+    }
+}
@@ -0,0 +1,180 @@
+//! Headless runs: step the simulation without a terminal and report what
+//! happened as JSON.
+
+use crate::app::App;
+use crate::faction::Faction;
+use crate::geo::{normalize_lat, normalize_lon};
+use simd_json::prelude::*;
+use simd_json::{json, OwnedValue};
+
+/// Cells ever touched by something, at 0.25° — for "area affected" totals
+struct CoverageGrid {
+    cells: Vec<bool>,
+}
+
+impl CoverageGrid {
+    const RESOLUTION: f64 = 0.25;
+    const WIDTH: usize = 1440;
+    const HEIGHT: usize = 720;
+
+    fn new() -> Self {
+        Self { cells: vec![false; Self::WIDTH * Self::HEIGHT] }
+    }
+
+    fn mark(&mut self, lon: f64, lat: f64) {
+        let col = ((normalize_lon(lon) / Self::RESOLUTION) as usize).min(Self::WIDTH - 1);
+        let row = ((normalize_lat(lat) / Self::RESOLUTION) as usize).min(Self::HEIGHT - 1);
+        self.cells[row * Self::WIDTH + col] = true;
+    }
+
+    /// Mark every cell whose center lies within `radius_km` of (lon, lat)
+    fn mark_disc(&mut self, lon: f64, lat: f64, radius_km: f64) {
+        let dlat = radius_km / 111.0;
+        let dlon = dlat / lat.to_radians().cos().max(0.05);
+        let mut y = lat - dlat;
+        while y <= lat + dlat {
+            let half = dlon * (1.0 - ((y - lat) / dlat).powi(2)).max(0.0).sqrt();
+            let mut x = lon - half;
+            while x <= lon + half {
+                self.mark(x, y);
+                x += Self::RESOLUTION;
+            }
+            y += Self::RESOLUTION;
+        }
+        self.mark(lon, lat);
+    }
+
+    fn area_km2(&self) -> f64 {
+        let cell_km = Self::RESOLUTION * 111.0;
+        (0..Self::HEIGHT)
+            .map(|row| {
+                let lat = -90.0 + (row as f64 + 0.5) * Self::RESOLUTION;
+                let marked = self.cells[row * Self::WIDTH..(row + 1) * Self::WIDTH].iter().filter(|&&c| c).count();
+                marked as f64 * cell_km * cell_km * lat.to_radians().cos()
+            })
+            .sum()
+    }
+}
+
+/// Run `ticks` simulation steps and summarize the result
+pub fn run(app: &mut App, ticks: u64) -> OwnedValue {
+    let mut burned = CoverageGrid::new();
+    let mut fallout = CoverageGrid::new();
+    for _ in 0..ticks {
+        app.step();
+        // No bell or notifications to pass them on to
+        app.alerts.clear();
+        // Fires and plumes move slowly — sampling every few steps is plenty
+        if app.frame.is_multiple_of(5) {
+            for fire in app.fires.cells() {
+                burned.mark(fire.lon, fire.lat);
+            }
+        }
+        if app.frame.is_multiple_of(10) {
+            for puff in app.fallout.iter().flat_map(|z| &z.plume.puffs) {
+                fallout.mark_disc(puff.lon, puff.lat, puff.radius_km);
+            }
+        }
+    }
+    app.refresh_faction_stats();
+    report(app, ticks, &burned, &fallout)
+}
+
+fn report(app: &App, ticks: u64, burned: &CoverageGrid, fallout: &CoverageGrid) -> OwnedValue {
+    let cities: Vec<OwnedValue> = app.damaged_cities()
+        .iter()
+        .map(|c| json!({
+            "name": c.name.as_str(),
+            "country": c.country.as_str(),
+            "lon": c.lon,
+            "lat": c.lat,
+            "original_population": c.original_population,
+            "population": c.population,
+            "casualties": c.original_population - c.population,
+        }))
+        .collect();
+
+    let mut factions = json!({});
+    for faction in Faction::ALL {
+        let stats = &app.faction_stats[faction.index()];
+        let key = match faction {
+            Faction::West => "west",
+            Faction::East => "east",
+            Faction::NonAligned => "non_aligned",
+        };
+        factions.insert(key, json!({
+            "population": stats.population,
+            "original_population": stats.original_population,
+            "casualties": stats.casualties(),
+        })).ok();
+    }
+
+    json!({
+        "ticks": ticks,
+        "seed": app.seed,
+        "scenario": app.scenario.as_ref().and_then(|r| r.scenario.name.clone()),
+        "casualties": app.casualties,
+        "rural_casualties": app.population.as_ref().map_or(0, |grid| grid.casualties()),
+        "strikes_launched": app.stats.strikes_launched,
+        "retaliation_waves": app.stats.waves,
+        "missiles_intercepted": app.stats.intercepts,
+        "peak_fires": app.stats.peak_fires as u64,
+        "infrastructure_destroyed": app.stats.infrastructure_destroyed,
+        "fires_burning": app.fires.len() as u64,
+        "burned_area_km2": burned.area_km2().round(),
+        "fallout_coverage_km2": fallout.area_km2().round(),
+        "soot_tg": (app.climate.soot_tg * 100.0).round() / 100.0,
+        "temperature_anomaly_c": (app.climate.temperature_anomaly_c() * 100.0).round() / 100.0,
+        "factions": factions,
+        "cities": cities,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::WeaponType;
+    use crate::data;
+
+    #[test]
+    fn coverage_area_of_a_disc() {
+        let mut grid = CoverageGrid::new();
+        grid.mark_disc(10.0, 0.0, 200.0);
+        let expected = std::f64::consts::PI * 200.0 * 200.0;
+        let area = grid.area_km2();
+        assert!((area - expected).abs() / expected < 0.2, "{area} vs {expected}");
+    }
+
+    fn struck_world(seed: u64) -> App {
+        let mut app = App::new(80, 24);
+        app.set_seed(seed);
+        data::generate_simple_world(&mut app.map_renderer);
+        app.map_renderer.add_city(-98.0, 39.0, "Testville", 1_000_000, false, false, "USA");
+        app.map_renderer.build_land_grid();
+        app.map_renderer.build_spatial_indexes();
+        app.launch_at(-98.0, 39.0, WeaponType::Nuke, 150.0, Some(1_000.0));
+        app
+    }
+
+    #[test]
+    fn reports_strike_damage() {
+        let report = run(&mut struck_world(1), 200);
+        assert_eq!(report["strikes_launched"].as_u64(), Some(1));
+        assert!(report["casualties"].as_u64().unwrap() > 0);
+        assert!(report["burned_area_km2"].as_f64().unwrap() > 0.0);
+        assert!(report["fallout_coverage_km2"].as_f64().unwrap() > 0.0);
+        assert_eq!(report["cities"][0]["name"].as_str(), Some("Testville"));
+        assert_eq!(report["factions"]["west"]["casualties"].as_u64(), report["casualties"].as_u64());
+
+        // Round-trips through the JSON encoder
+        let mut text = report.encode().into_bytes();
+        assert!(simd_json::to_owned_value(&mut text).is_ok());
+    }
+
+    #[test]
+    fn seeded_runs_are_reproducible() {
+        let a = run(&mut struck_world(9), 300).encode();
+        assert_eq!(a, run(&mut struck_world(9), 300).encode());
+        assert_ne!(a, run(&mut struck_world(10), 300).encode());
+    }
+}
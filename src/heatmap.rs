@@ -0,0 +1,340 @@
+//! Heatmaps: any gridded lon/lat → value data drawn as shaded ground, the
+//! way radiation and population density are, under a color ramp of your
+//! choosing.
+//!
+//! Grids are ESRI ASCII rasters (see [`crate::data::raster`]) at any
+//! resolution. Each value is placed on the ramp between the ends of a
+//! range, linearly or on a log scale, and the cell shaded `░▒▓` by how far
+//! along it is. A heatmap is loaded with `--heatmap` and shown or hidden
+//! with `:heatmap`, separately from the game's own layers.
+
+use crate::command::Switch;
+use crate::data::raster::AsciiGrid;
+use crate::halfblock::Rgb;
+use crate::overlay::find_by_name;
+use anyhow::{anyhow, bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Colors spaced evenly from one end of a scale to the other
+#[derive(Debug, PartialEq)]
+pub struct ColorRamp {
+    pub name: &'static str,
+    stops: &'static [Rgb],
+    /// Shade by distance from the middle rather than from the low end, for
+    /// values either side of a neutral zero such as anomalies
+    diverging: bool,
+}
+
+impl ColorRamp {
+    /// The fire palette: ember red through orange to white heat. Burning
+    /// cells are drawn with it too (`flame`).
+    pub const FIRE: ColorRamp = ColorRamp {
+        name: "fire",
+        stops: &[
+            (90, 10, 0),
+            (140, 20, 0),
+            (200, 30, 0),
+            (255, 60, 0),
+            (255, 120, 0),
+            (255, 180, 30),
+            (255, 240, 100),
+            (255, 255, 240),
+        ],
+        diverging: false,
+    };
+    /// Radiation: green through yellow to red
+    pub const RADIATION: ColorRamp =
+        ColorRamp { name: "radiation", stops: &[(60, 110, 30), (230, 210, 30), (230, 40, 30)], diverging: false };
+    /// Population: dark violet up to bright lilac
+    pub const POPULATION: ColorRamp =
+        ColorRamp { name: "population", stops: &[(50, 30, 80), (200, 140, 250)], diverging: false };
+    /// Blue below the middle, red above, for anomalies
+    pub const THERMAL: ColorRamp = ColorRamp {
+        name: "thermal",
+        stops: &[(40, 80, 200), (120, 170, 230), (200, 200, 200), (240, 150, 90), (210, 40, 40)],
+        diverging: true,
+    };
+    /// Perceptually even dark purple to yellow
+    pub const VIRIDIS: ColorRamp = ColorRamp {
+        name: "viridis",
+        stops: &[(68, 1, 84), (59, 82, 139), (33, 145, 140), (94, 201, 98), (253, 231, 37)],
+        diverging: false,
+    };
+
+    pub const ALL: [&'static ColorRamp; 5] =
+        [&Self::FIRE, &Self::RADIATION, &Self::POPULATION, &Self::THERMAL, &Self::VIRIDIS];
+
+    pub fn by_name(name: &str) -> Option<&'static ColorRamp> {
+        Self::ALL.into_iter().find(|r| r.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The color `level` of the way along, from 0 to 1
+    pub fn color(&self, level: f32) -> Rgb {
+        let at = level.clamp(0.0, 1.0) * (self.stops.len() - 1) as f32;
+        let i = (at as usize).min(self.stops.len() - 2);
+        let t = at - i as f32;
+        let (a, b) = (self.stops[i], self.stops[i + 1]);
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t) as u8;
+        (mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+    }
+
+    /// `n` colors from one end to the other, for a legend
+    pub fn swatch(&self, n: usize) -> Vec<Rgb> {
+        (0..n).map(|i| self.color(i as f32 / (n.max(2) - 1) as f32)).collect()
+    }
+
+    /// Shade glyph and color for a cell `level` of the way along: denser
+    /// toward the high end, or toward both ends of a diverging ramp
+    pub fn fill(&self, level: f32) -> (char, Rgb) {
+        let weight = if self.diverging { (level - 0.5).abs() * 2.0 } else { level };
+        let ch = if weight > 0.66 { '▓' } else if weight > 0.33 { '▒' } else { '░' };
+        (ch, self.color(level))
+    }
+
+    /// Glyph and color for a fire at `intensity`: solid blocks at the
+    /// hottest thinning to `░` embers
+    pub fn flame(&self, intensity: u8) -> (char, Rgb) {
+        let ch = match intensity {
+            181.. => '█',
+            101..=180 => '▓',
+            31..=100 => '▒',
+            _ => '░',
+        };
+        (ch, self.color(intensity as f32 / 255.0))
+    }
+}
+
+/// How values are spread along the ramp
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scale {
+    Linear,
+    /// By order of magnitude, for values spanning several
+    Log,
+}
+
+/// A heatmap as given on the command line:
+/// `<file>[,ramp=<name>][,range=<min>..<max>][,log]`
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeatmapSpec {
+    pub path: PathBuf,
+    pub ramp: &'static ColorRamp,
+    /// The values at the two ends of the ramp; the data's own when `None`
+    pub range: Option<(f32, f32)>,
+    pub scale: Scale,
+}
+
+impl HeatmapSpec {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut parts = spec.split(',').map(str::trim);
+        let path = PathBuf::from(parts.next().filter(|p| !p.is_empty()).ok_or_else(|| anyhow!("heatmap needs a file"))?);
+        let mut out = Self { path, ramp: &ColorRamp::VIRIDIS, range: None, scale: Scale::Linear };
+        for option in parts {
+            match option.split_once('=') {
+                Some(("ramp", name)) => {
+                    out.ramp = ColorRamp::by_name(name).ok_or_else(|| {
+                        let names: Vec<&str> = ColorRamp::ALL.iter().map(|r| r.name).collect();
+                        anyhow!("unknown color ramp (expected {}): {name}", names.join(", "))
+                    })?;
+                }
+                Some(("range", range)) => {
+                    let parsed = range
+                        .split_once("..")
+                        .and_then(|(lo, hi)| Some((lo.trim().parse::<f32>().ok()?, hi.trim().parse::<f32>().ok()?)))
+                        .filter(|(lo, hi)| lo < hi);
+                    out.range = Some(parsed.ok_or_else(|| anyhow!("invalid heatmap range (expected MIN..MAX): {range}"))?);
+                }
+                None if option == "log" => out.scale = Scale::Log,
+                _ => bail!("unknown heatmap option: {option}"),
+            }
+        }
+        if out.scale == Scale::Log && out.range.is_some_and(|(lo, _)| lo <= 0.0) {
+            bail!("a log-scale heatmap's range must be above zero");
+        }
+        Ok(out)
+    }
+}
+
+pub struct HeatmapLayer {
+    /// The file's stem, as `:heatmap` knows it
+    pub name: String,
+    pub ramp: &'static ColorRamp,
+    pub scale: Scale,
+    /// The values at the two ends of the ramp
+    pub range: (f32, f32),
+    pub shown: bool,
+    width: usize,
+    height: usize,
+    /// Lower-left corner of the grid and its cell size, in degrees
+    x0: f64,
+    y0: f64,
+    cellsize: f64,
+    /// Row by row from the south, NaN where there's no data
+    values: Vec<f32>,
+}
+
+impl HeatmapLayer {
+    pub fn load(spec: &HeatmapSpec) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(&spec.path).with_context(|| format!("reading heatmap {}", spec.path.display()))?;
+        Self::parse(&name_of(&spec.path), &content, spec).with_context(|| format!("in heatmap {}", spec.path.display()))
+    }
+
+    pub fn parse(name: &str, content: &str, spec: &HeatmapSpec) -> Result<Self> {
+        let raster = AsciiGrid::parse(content)?;
+        let (width, height, cellsize, x0, y0) = (raster.ncols, raster.nrows, raster.cellsize, raster.x0, raster.y0);
+        let cells = width.checked_mul(height).context("heatmap grid too large")?;
+        let mut values = vec![f32::NAN; cells];
+        raster.for_each_cell("heatmap", |lon, lat, value| {
+            let col = ((lon - x0) / cellsize).round() as usize;
+            let row = ((lat - y0) / cellsize).round() as usize;
+            values[row * width + col] = value as f32;
+        })?;
+        let range = match spec.range {
+            Some(range) => range,
+            None => {
+                let data = values.iter().copied().filter(|v| !v.is_nan() && (spec.scale == Scale::Linear || *v > 0.0));
+                let (lo, hi) = data.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+                if lo > hi {
+                    bail!("no values to show");
+                }
+                // A flat grid still needs a range to sit in
+                if lo == hi { (lo, lo + 1.0) } else { (lo, hi) }
+            }
+        };
+        Ok(Self {
+            name: name.to_string(),
+            ramp: spec.ramp,
+            scale: spec.scale,
+            range,
+            shown: true,
+            width,
+            height,
+            x0,
+            y0,
+            cellsize,
+            values,
+        })
+    }
+
+    /// The value of the grid cell holding (lon, lat), if it has one
+    pub fn value_at(&self, lon: f64, lat: f64) -> Option<f32> {
+        let col = ((lon - self.x0).rem_euclid(360.0) / self.cellsize).floor();
+        let row = ((lat - self.y0) / self.cellsize).floor();
+        if col < 0.0 || row < 0.0 || col >= self.width as f64 || row >= self.height as f64 {
+            return None;
+        }
+        Some(self.values[row as usize * self.width + col as usize]).filter(|v| !v.is_nan())
+    }
+
+    /// How far along the ramp `value` sits, from 0 to 1
+    pub fn level(&self, value: f32) -> f32 {
+        let (lo, hi) = self.range;
+        let level = match self.scale {
+            Scale::Linear => (value - lo) / (hi - lo),
+            Scale::Log if value <= 0.0 => 0.0,
+            Scale::Log => (value / lo).log10() / (hi / lo).log10(),
+        };
+        level.clamp(0.0, 1.0)
+    }
+
+    /// How to shade the ground at (lon, lat)
+    pub fn fill_at(&self, lon: f64, lat: f64) -> Option<(char, Rgb)> {
+        self.value_at(lon, lat).map(|v| self.ramp.fill(self.level(v)))
+    }
+
+    /// `steps` values spread evenly along the ramp, low to high, with
+    /// their colors
+    pub fn legend(&self, steps: usize) -> Vec<(f32, Rgb)> {
+        let (lo, hi) = self.range;
+        (0..steps)
+            .map(|i| {
+                let t = i as f32 / (steps.max(2) - 1) as f32;
+                let value = match self.scale {
+                    Scale::Linear => lo + (hi - lo) * t,
+                    Scale::Log => lo * (hi / lo).powf(t),
+                };
+                (value, self.ramp.color(t))
+            })
+            .collect()
+    }
+}
+
+/// The loaded heatmaps, drawn first over last where they overlap
+#[derive(Default)]
+pub struct Heatmaps {
+    pub layers: Vec<HeatmapLayer>,
+}
+
+impl Heatmaps {
+    /// Load each heatmap on the command line
+    pub fn load(specs: &[HeatmapSpec]) -> Result<Self> {
+        Ok(Self { layers: specs.iter().map(HeatmapLayer::load).collect::<Result<_>>()? })
+    }
+
+    pub fn shown(&self) -> impl Iterator<Item = &HeatmapLayer> {
+        self.layers.iter().filter(|l| l.shown)
+    }
+
+    /// Show or hide the heatmap named `name`, or the one name it starts.
+    /// Returns its full name and whether it's now showing.
+    pub fn set(&mut self, name: &str, switch: Switch) -> Result<(String, bool), String> {
+        let index = find_by_name(self.layers.iter().map(|l| l.name.as_str()), name, "heatmap")?;
+        let layer = &mut self.layers[index];
+        layer.shown = switch.apply(layer.shown);
+        Ok((layer.name.clone(), layer.shown))
+    }
+}
+
+/// A heatmap's name from its file: `sst_anomaly.asc` is `sst_anomaly`
+fn name_of(path: &Path) -> String {
+    path.file_stem().map_or_else(|| "heatmap".to_string(), |s| s.to_string_lossy().to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANOMALY: &str = "ncols 4
+nrows 2
+xllcorner -180
+yllcorner -90
+cellsize 90
+NODATA_value -9999
+-2.0 -1.0 0.0 -9999
+ 1.0  2.0 0.5 1.5
+";
+
+    #[test]
+    fn values_are_placed_on_the_ramp_between_the_range_ends() {
+        let spec = HeatmapSpec::parse("anomaly.asc,ramp=thermal,range=-2..2").unwrap();
+        assert_eq!((spec.ramp.name, spec.range, spec.scale), ("thermal", Some((-2.0, 2.0)), Scale::Linear));
+        let heatmap = HeatmapLayer::parse("anomaly", ANOMALY, &spec).unwrap();
+        // North row, west to east
+        assert_eq!(heatmap.value_at(-170.0, 45.0), Some(-2.0));
+        assert_eq!(heatmap.value_at(100.0, 45.0), None, "no data");
+        assert_eq!(heatmap.value_at(-10.0, -45.0), Some(2.0));
+        assert_eq!(heatmap.value_at(190.0, 45.0), Some(-2.0), "wraps round");
+        // Both ends are dense on a diverging ramp, the middle sparse
+        assert_eq!(heatmap.fill_at(-170.0, 45.0), Some(('▓', (40, 80, 200))));
+        assert_eq!(heatmap.fill_at(10.0, 45.0).unwrap().0, '░');
+        let legend = heatmap.legend(3);
+        assert_eq!(legend.iter().map(|&(v, _)| v).collect::<Vec<_>>(), [-2.0, 0.0, 2.0]);
+
+        let spec = HeatmapSpec::parse("anomaly.asc,log").unwrap();
+        let heatmap = HeatmapLayer::parse("anomaly", ANOMALY, &spec).unwrap();
+        assert_eq!(heatmap.range, (0.5, 2.0), "the data's own, above zero");
+        assert_eq!(heatmap.level(1.0), 0.5);
+
+        assert!(HeatmapSpec::parse("a.asc,ramp=neon").is_err());
+        assert!(HeatmapSpec::parse("a.asc,range=3..1").is_err());
+        assert!(HeatmapSpec::parse("a.asc,log,range=0..1").is_err());
+    }
+
+    #[test]
+    fn fires_burn_from_embers_to_white_heat() {
+        assert_eq!(ColorRamp::FIRE.flame(0), ('░', (90, 10, 0)));
+        assert_eq!(ColorRamp::FIRE.flame(120).0, '▓');
+        assert_eq!(ColorRamp::FIRE.flame(255), ('█', (255, 255, 240)));
+        assert_eq!(ColorRamp::FIRE.swatch(2), [(90, 10, 0), (255, 255, 240)]);
+    }
+}
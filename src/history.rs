@@ -0,0 +1,448 @@
+//! Time-lapse history: a snapshot of the world every few simulated
+//! seconds, kept in a ring so the view can be rewound.
+//!
+//! A snapshot holds what the map shows changing: city populations and
+//! infections that differ from a fresh world, the burning fire cells, the
+//! death toll and the clock. Each is encoded compactly the way spectator
+//! diffs are (`spectate`): a few bytes per changed city and burning cell.
+//! Most are stored as what changed since the snapshot before, with a whole
+//! one every `KEYFRAME_EVERY` to rebuild from, so a stretch where nothing
+//! happens costs a few dozen bytes a snapshot. The oldest snapshots drop
+//! off once the ring holds `CAPACITY` of them or `MAX_BYTES`.
+//!
+//! Scrubbing is read-only: the simulation holds still while an earlier
+//! snapshot is shown, and returning to "now" puts the live world back
+//! exactly as it was.
+
+use crate::app::WeaponType;
+use crate::net::{self, Fields};
+use anyhow::{bail, Result};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Simulation steps between snapshots: 5 simulated seconds at 60fps
+pub const SNAPSHOT_FRAMES: u64 = 300;
+/// Snapshots kept: an hour of simulated time
+pub const CAPACITY: usize = 720;
+/// Most bytes the encoded snapshots may take, however few there are
+pub const MAX_BYTES: usize = 32 << 20;
+/// Every this many snapshots one is stored whole rather than as changes,
+/// so showing any snapshot decodes at most this many
+pub const KEYFRAME_EVERY: usize = 60;
+
+/// The world at one moment, as much of it as the timeline replays
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Snapshot {
+    pub frame: u64,
+    pub sim_time: f64,
+    pub casualties: u64,
+    /// (city index, population) for cities not at their original size
+    pub cities: Vec<(u32, u64)>,
+    /// (city index, infected share) for infected cities
+    pub infections: Vec<(u32, f32)>,
+    /// (fire cell, intensity, weapon) for every burning cell
+    pub fires: Vec<(u32, u8, WeaponType)>,
+}
+
+impl Snapshot {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(36 + self.cities.len() * 12 + self.infections.len() * 6 + self.fires.len() * 6);
+        self.write(&mut out);
+        out
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.frame.to_le_bytes());
+        out.extend_from_slice(&self.sim_time.to_le_bytes());
+        out.extend_from_slice(&self.casualties.to_le_bytes());
+        out.extend_from_slice(&(self.cities.len() as u32).to_le_bytes());
+        for (idx, population) in &self.cities {
+            out.extend_from_slice(&idx.to_le_bytes());
+            out.extend_from_slice(&population.to_le_bytes());
+        }
+        out.extend_from_slice(&(self.infections.len() as u32).to_le_bytes());
+        for &(idx, share) in &self.infections {
+            out.extend_from_slice(&idx.to_le_bytes());
+            out.extend_from_slice(&share_code(share).to_le_bytes());
+        }
+        out.extend_from_slice(&(self.fires.len() as u32).to_le_bytes());
+        for &(cell, intensity, weapon) in &self.fires {
+            out.extend_from_slice(&cell.to_le_bytes());
+            out.push(intensity);
+            out.push(net::weapon_code(weapon));
+        }
+    }
+
+    pub fn decode(payload: &[u8]) -> Result<Self> {
+        let mut r = Fields { buf: payload };
+        let snapshot = Self::read(&mut r)?;
+        if !r.buf.is_empty() {
+            bail!("{} trailing bytes", r.buf.len());
+        }
+        Ok(snapshot)
+    }
+
+    fn read(r: &mut Fields) -> Result<Self> {
+        let (frame, sim_time, casualties) = (r.u64()?, r.f64()?, r.u64()?);
+        let n = count(r, 12)?;
+        let cities = (0..n).map(|_| Ok((r.u32()?, r.u64()?))).collect::<Result<_>>()?;
+        let n = count(r, 6)?;
+        let infections = (0..n)
+            .map(|_| {
+                let idx = r.u32()?;
+                let share = u16::from_le_bytes([r.u8()?, r.u8()?]);
+                Ok((idx, share as f32 / 65535.0))
+            })
+            .collect::<Result<_>>()?;
+        let n = count(r, 6)?;
+        let fires = (0..n).map(|_| Ok((r.u32()?, r.u8()?, net::weapon_from(r.u8()?)?))).collect::<Result<_>>()?;
+        Ok(Self { frame, sim_time, casualties, cities, infections, fires })
+    }
+
+    /// The snapshot as it reads back, infections rounded as they're stored
+    fn stored(&self) -> Self {
+        let mut snapshot = self.clone();
+        for (_, share) in &mut snapshot.infections {
+            *share = share_code(*share) as f32 / 65535.0;
+        }
+        snapshot
+    }
+}
+
+/// An infected share in 1/65535ths, finer than any halo shows
+fn share_code(share: f32) -> u16 {
+    (share.clamp(0.0, 1.0) * 65535.0).round() as u16
+}
+
+/// A count of entries `size` bytes each, checked against what's left
+fn count(r: &mut Fields, size: usize) -> Result<usize> {
+    let n = r.u32()? as usize;
+    if n > r.buf.len() / size {
+        bail!("{n} entries exceed snapshot");
+    }
+    Ok(n)
+}
+
+/// What changed from one snapshot to the next: the later one's clock and
+/// toll, its entries that are new or different, and the city, infection
+/// and fire keys it no longer has
+struct Delta {
+    changed: Snapshot,
+    removed: [Vec<u32>; 3],
+}
+
+impl Delta {
+    fn between(old: &Snapshot, new: &Snapshot) -> Self {
+        let (cities, removed_cities) = changes(&old.cities, &new.cities);
+        let (infections, removed_infections) = changes(&old.infections, &new.infections);
+        let (fires, removed_fires) = changes(&keyed(&old.fires), &keyed(&new.fires));
+        Self {
+            changed: Snapshot {
+                frame: new.frame,
+                sim_time: new.sim_time,
+                casualties: new.casualties,
+                cities,
+                infections,
+                fires: fires.into_iter().map(|(cell, (intensity, weapon))| (cell, intensity, weapon)).collect(),
+            },
+            removed: [removed_cities, removed_infections, removed_fires],
+        }
+    }
+
+    /// The later snapshot, from the earlier one
+    fn apply(&self, old: &Snapshot) -> Snapshot {
+        let [cities, infections, fires] = &self.removed;
+        Snapshot {
+            cities: patched(&old.cities, &self.changed.cities, cities),
+            infections: patched(&old.infections, &self.changed.infections, infections),
+            fires: patched(&keyed(&old.fires), &keyed(&self.changed.fires), fires)
+                .into_iter()
+                .map(|(cell, (intensity, weapon))| (cell, intensity, weapon))
+                .collect(),
+            ..self.changed.clone()
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.changed.write(&mut out);
+        for keys in &self.removed {
+            out.extend_from_slice(&(keys.len() as u32).to_le_bytes());
+            for key in keys {
+                out.extend_from_slice(&key.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    fn decode(payload: &[u8]) -> Result<Self> {
+        let mut r = Fields { buf: payload };
+        let changed = Snapshot::read(&mut r)?;
+        let mut removed: [Vec<u32>; 3] = Default::default();
+        for keys in &mut removed {
+            let n = count(&mut r, 4)?;
+            *keys = (0..n).map(|_| r.u32()).collect::<Result<_>>()?;
+        }
+        if !r.buf.is_empty() {
+            bail!("{} trailing bytes", r.buf.len());
+        }
+        Ok(Self { changed, removed })
+    }
+}
+
+/// Fires keyed by cell
+fn keyed(fires: &[(u32, u8, WeaponType)]) -> Vec<(u32, (u8, WeaponType))> {
+    fires.iter().map(|&(cell, intensity, weapon)| (cell, (intensity, weapon))).collect()
+}
+
+/// Entries of `new` that `old` doesn't have as they are, and the keys of
+/// `old` that `new` has dropped
+fn changes<V: Copy + PartialEq>(old: &[(u32, V)], new: &[(u32, V)]) -> (Vec<(u32, V)>, Vec<u32>) {
+    let before: HashMap<u32, V> = old.iter().copied().collect();
+    let after: HashSet<u32> = new.iter().map(|&(key, _)| key).collect();
+    let changed = new.iter().filter(|(key, value)| before.get(key) != Some(value)).copied().collect();
+    let removed = old.iter().map(|&(key, _)| key).filter(|key| !after.contains(key)).collect();
+    (changed, removed)
+}
+
+/// `old` with `changed`'s entries put in and `removed`'s keys taken out
+fn patched<V: Copy>(old: &[(u32, V)], changed: &[(u32, V)], removed: &[u32]) -> Vec<(u32, V)> {
+    let updates: HashMap<u32, V> = changed.iter().copied().collect();
+    let removed: HashSet<u32> = removed.iter().copied().collect();
+    let present: HashSet<u32> = old.iter().map(|&(key, _)| key).collect();
+    let mut out: Vec<(u32, V)> = old
+        .iter()
+        .filter(|(key, _)| !removed.contains(key))
+        .map(|&(key, value)| (key, updates.get(&key).copied().unwrap_or(value)))
+        .collect();
+    out.extend(changed.iter().filter(|(key, _)| !present.contains(key)).copied());
+    out
+}
+
+/// A snapshot as stored: whole, or as a `Delta` from the one before
+struct Entry {
+    frame: u64,
+    keyframe: bool,
+    bytes: Vec<u8>,
+}
+
+/// Encoded snapshots, oldest first, and which one is being shown. The
+/// oldest is always whole.
+pub struct History {
+    entries: VecDeque<Entry>,
+    /// Bytes held by the entries
+    bytes: usize,
+    max_bytes: usize,
+    /// The newest snapshot as stored, for the next one's delta
+    newest: Option<Snapshot>,
+    /// Index of the snapshot shown, or None when live
+    cursor: Option<usize>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self { entries: VecDeque::new(), bytes: 0, max_bytes: MAX_BYTES, newest: None, cursor: None }
+    }
+}
+
+impl History {
+    /// Keep `snapshot`, dropping the oldest while the ring is over its
+    /// count or its bytes
+    pub fn record(&mut self, snapshot: &Snapshot) {
+        let snapshot = snapshot.stored();
+        let since_keyframe = self.entries.iter().rev().take_while(|e| !e.keyframe).count();
+        let (keyframe, bytes) = match &self.newest {
+            Some(newest) if since_keyframe + 1 < KEYFRAME_EVERY => (false, Delta::between(newest, &snapshot).encode()),
+            _ => (true, snapshot.encode()),
+        };
+        self.bytes += bytes.len();
+        self.entries.push_back(Entry { frame: snapshot.frame, keyframe, bytes });
+        self.newest = Some(snapshot);
+        while self.entries.len() > CAPACITY || (self.bytes > self.max_bytes && self.entries.len() > 1) {
+            self.drop_oldest();
+        }
+    }
+
+    /// Drop the oldest snapshot, storing the next one whole if it was
+    /// only the changes from it
+    fn drop_oldest(&mut self) {
+        let Some(oldest) = self.entries.pop_front() else { return };
+        self.bytes -= oldest.bytes.len();
+        self.cursor = self.cursor.map(|c| c.saturating_sub(1));
+        let Some(next) = self.entries.front_mut().filter(|e| !e.keyframe) else { return };
+        let whole = Snapshot::decode(&oldest.bytes).and_then(|old| Ok(Delta::decode(&next.bytes)?.apply(&old)));
+        if let Ok(whole) = whole {
+            let bytes = whole.encode();
+            self.bytes = self.bytes - next.bytes.len() + bytes.len();
+            *next = Entry { frame: next.frame, keyframe: true, bytes };
+        }
+    }
+
+    /// The snapshot at `index`, oldest first, rebuilt from the whole one
+    /// at or before it
+    pub fn snapshot(&self, index: usize) -> Option<Snapshot> {
+        self.entries.get(index)?;
+        let start = (0..=index).rev().find(|&i| self.entries[i].keyframe)?;
+        let mut snapshot = Snapshot::decode(&self.entries[start].bytes).ok()?;
+        for entry in self.entries.range(start + 1..=index) {
+            snapshot = Delta::decode(&entry.bytes).ok()?.apply(&snapshot);
+        }
+        Some(snapshot)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Bytes held by the encoded snapshots
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor
+    }
+
+    pub fn is_scrubbing(&self) -> bool {
+        self.cursor.is_some()
+    }
+
+    /// Frame of each snapshot, oldest first
+    pub fn frames(&self) -> impl Iterator<Item = u64> + '_ {
+        self.entries.iter().map(|e| e.frame)
+    }
+
+    /// Move the cursor `by` snapshots (negative is back in time), starting
+    /// from "now" if live. Stepping past the newest snapshot returns to
+    /// live and gives None.
+    pub fn seek(&mut self, by: isize) -> Option<Snapshot> {
+        let newest = self.entries.len().checked_sub(1)?;
+        let from = self.cursor.map_or(self.entries.len() as isize, |c| c as isize);
+        let to = (from + by).max(0);
+        if to as usize > newest {
+            self.cursor = None;
+            return None;
+        }
+        self.cursor = Some(to as usize);
+        self.snapshot(to as usize)
+    }
+
+    /// Stop showing history
+    pub fn go_live(&mut self) {
+        self.cursor = None;
+    }
+
+    /// Forget everything, as when the world's cities are reloaded
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.bytes = 0;
+        self.newest = None;
+        self.cursor = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(frame: u64) -> Snapshot {
+        Snapshot {
+            frame,
+            sim_time: 1.7e9 + frame as f64,
+            casualties: frame * 1000,
+            cities: vec![(3, 12_345)],
+            infections: vec![(3, 0.5)],
+            fires: vec![(77, 200, WeaponType::Nuke), (78, 40, WeaponType::Chem)],
+        }
+    }
+
+    #[test]
+    fn snapshots_round_trip() {
+        let original = snapshot(600);
+        let decoded = Snapshot::decode(&original.encode()).unwrap();
+        assert_eq!(decoded.cities, original.cities);
+        assert_eq!(decoded.fires, original.fires);
+        assert!((decoded.infections[0].1 - 0.5).abs() < 1e-4);
+        assert!(Snapshot::decode(&original.encode()[..40]).is_err());
+    }
+
+    /// Entries sorted by key, as a delta may put them back in another order
+    fn sorted(mut snapshot: Snapshot) -> Snapshot {
+        snapshot.cities.sort_by_key(|&(idx, _)| idx);
+        snapshot.infections.sort_by_key(|&(idx, _)| idx);
+        snapshot.fires.sort_by_key(|&(cell, ..)| cell);
+        snapshot
+    }
+
+    #[test]
+    fn snapshots_are_rebuilt_from_their_deltas() {
+        let mut history = History::default();
+        let mut recorded = Vec::new();
+        for i in 0..KEYFRAME_EVERY as u32 + 10 {
+            // Cities and fires come, change and go
+            let snapshot = Snapshot {
+                frame: i as u64 * SNAPSHOT_FRAMES,
+                sim_time: 1.7e9 + i as f64,
+                casualties: i as u64 * 1000,
+                cities: (i / 3..i / 3 + 4).map(|idx| (idx, 1000 + idx as u64 * (i as u64 % 2))).collect(),
+                infections: vec![(i % 5, i as f32 / 100.0)],
+                fires: (i..i + 3).map(|cell| (cell, (cell * 7 % 250) as u8, WeaponType::Nuke)).collect(),
+            };
+            history.record(&snapshot);
+            recorded.push(snapshot.stored());
+        }
+        for (i, snapshot) in recorded.into_iter().enumerate() {
+            assert_eq!(history.snapshot(i).map(sorted), Some(sorted(snapshot)), "snapshot {i}");
+        }
+
+        // Nothing happening costs little more than the clock
+        let mut quiet = History::default();
+        quiet.record(&snapshot(0));
+        let whole = quiet.bytes();
+        quiet.record(&snapshot(1));
+        assert!(quiet.bytes() - whole < 64, "{} bytes", quiet.bytes() - whole);
+    }
+
+    #[test]
+    fn the_ring_keeps_under_its_byte_cap() {
+        let mut history = History { max_bytes: 20_000, ..History::default() };
+        let burning = |frame: u64| Snapshot {
+            frame,
+            fires: (0..1000).map(|cell| (cell, (frame % 200) as u8 + 1, WeaponType::Nuke)).collect(),
+            ..Default::default()
+        };
+        for i in 0..40 {
+            history.record(&burning(i));
+        }
+        assert!(history.bytes() <= 20_000, "{} bytes", history.bytes());
+        assert!(history.len() > 1 && history.len() < 40);
+        assert_eq!(history.bytes(), history.entries.iter().map(|e| e.bytes.len()).sum::<usize>());
+        // The oldest left is stored whole and still reads back
+        assert!(history.entries[0].keyframe);
+        let oldest = 40 - history.len() as u64;
+        assert_eq!(history.snapshot(0), Some(burning(oldest)));
+    }
+
+    #[test]
+    fn the_ring_drops_the_oldest_and_seeks_back_to_live() {
+        let mut history = History::default();
+        assert!(history.seek(-1).is_none());
+        for i in 0..CAPACITY as u64 + 5 {
+            history.record(&snapshot(i * SNAPSHOT_FRAMES));
+        }
+        assert_eq!(history.len(), CAPACITY);
+        assert_eq!(history.frames().next(), Some(5 * SNAPSHOT_FRAMES));
+
+        let newest = history.seek(-1).unwrap();
+        assert_eq!(newest.frame, (CAPACITY as u64 + 4) * SNAPSHOT_FRAMES);
+        assert_eq!(history.seek(-10_000).unwrap().frame, 5 * SNAPSHOT_FRAMES);
+        assert_eq!(history.cursor(), Some(0));
+        history.seek(CAPACITY as isize - 1);
+        assert!(history.is_scrubbing());
+        assert!(history.seek(1).is_none());
+        assert!(!history.is_scrubbing());
+    }
+}
@@ -0,0 +1,170 @@
+//! The legend: what each color on the map means, for the layers showing
+//! right now. Built fresh each frame from the app's state, so it follows
+//! layers being toggled, effects appearing and the theme changing.
+
+use crate::app::App;
+use crate::faction::Faction;
+use crate::halfblock::Rgb;
+use crate::heatmap::{ColorRamp, Scale};
+use crate::perf;
+use crate::sim::FalloutGrid;
+use ratatui::style::Color;
+
+/// Colors in a ramp's swatch
+const RAMP_STEPS: usize = 6;
+
+/// How a layer looks, drawn in front of its name
+#[derive(Clone, Debug, PartialEq)]
+pub enum Swatch {
+    /// A line in one color
+    Line(Color),
+    /// A single character
+    Glyph(char, Color),
+    /// A scale from low to high
+    Ramp(Vec<Rgb>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegendEntry {
+    pub name: String,
+    pub swatch: Swatch,
+    /// What the ends of a ramp stand for, e.g. `0.8–80 Gy`
+    pub range: Option<String>,
+}
+
+impl LegendEntry {
+    fn new(name: impl Into<String>, swatch: Swatch) -> Self {
+        Self { name: name.into(), swatch, range: None }
+    }
+
+    fn ramp(name: impl Into<String>, ramp: &ColorRamp, range: String) -> Self {
+        Self { name: name.into(), swatch: Swatch::Ramp(ramp.swatch(RAMP_STEPS)), range: Some(range) }
+    }
+}
+
+/// An entry for every layer showing, lines first, then the ground, then
+/// overlays
+pub fn entries(app: &App) -> Vec<LegendEntry> {
+    let renderer = &app.map_renderer;
+    let settings = &renderer.settings;
+    let theme = app.theme;
+    let mut entries = Vec::new();
+
+    if settings.show_coastlines {
+        entries.push(LegendEntry::new("Coastlines", Swatch::Line(theme.coastline)));
+    }
+    if settings.show_borders {
+        if settings.show_factions {
+            for faction in Faction::ALL {
+                let (r, g, b) = faction.color();
+                entries.push(LegendEntry::new(title_case(faction.label()), Swatch::Line(Color::Rgb(r, g, b))));
+            }
+        } else {
+            entries.push(LegendEntry::new("Borders", Swatch::Line(theme.border)));
+        }
+    }
+    let lines = [
+        ("States", settings.show_states && !renderer.states.is_empty(), theme.state),
+        ("Counties", settings.show_counties && !renderer.counties.is_empty(), theme.county),
+        ("Roads", settings.show_roads && !renderer.roads.is_empty(), theme.road),
+        ("Railways", settings.show_railways && !renderer.railways.is_empty(), theme.railway),
+    ];
+    for (name, shown, color) in lines {
+        if shown {
+            entries.push(LegendEntry::new(name, Swatch::Line(color)));
+        }
+    }
+
+    if !app.fires.is_empty() {
+        entries.push(LegendEntry::ramp("Fires", &ColorRamp::FIRE, "smoulder–blaze".to_string()));
+    }
+    if app.show_radiation && !app.radiation.is_empty() {
+        let lethal = FalloutGrid::LETHAL_GY;
+        let range = format!("{}–{} Gy", short_number(lethal / 10.0), short_number(lethal * 10.0));
+        entries.push(LegendEntry::ramp("Radiation", &ColorRamp::RADIATION, range));
+    }
+    if !app.eruptions.is_empty() {
+        entries.push(LegendEntry::new("Volcanic ash", Swatch::Glyph('▒', Color::Rgb(120, 110, 98))));
+    }
+    if !app.hurricanes.is_empty() {
+        entries.push(LegendEntry::new("Hurricane", Swatch::Glyph('◎', Color::Rgb(200, 200, 255))));
+    }
+    if !app.craters.is_empty() {
+        entries.push(LegendEntry::new("Craters", Swatch::Glyph('█', Color::Rgb(150, 124, 96))));
+    }
+    if app.show_scars && !app.burn_scars.is_empty() {
+        entries.push(LegendEntry::new("Burn scars", Swatch::Glyph('▒', Color::Rgb(60, 56, 52))));
+    }
+    if app.show_population && app.population.is_some() {
+        entries.push(LegendEntry::ramp("Density", &ColorRamp::POPULATION, "1–1000 /km²".to_string()));
+    }
+    for heatmap in app.heatmaps.shown() {
+        let (lo, hi) = heatmap.range;
+        let log = if heatmap.scale == Scale::Log { " log" } else { "" };
+        let range = format!("{}–{}{log}", short_number(lo), short_number(hi));
+        entries.push(LegendEntry::ramp(heatmap.name.clone(), heatmap.ramp, range));
+    }
+    if app.show_flights && !app.travel.is_empty() {
+        entries.push(LegendEntry::new("Flight routes", Swatch::Line(Color::Rgb(140, 190, 255))));
+    }
+    // As drawn: weather is shed first when frames run slow
+    if app.show_weather && app.perf.detail < perf::MIN_DETAIL && !app.weather.is_clear() {
+        entries.push(LegendEntry::new("Clouds", Swatch::Glyph('░', Color::Rgb(55, 55, 65))));
+    }
+
+    for (name, shown) in app.overlays.list() {
+        if shown {
+            entries.push(LegendEntry::new(name, Swatch::Glyph('◇', Color::Gray)));
+        }
+    }
+    entries
+}
+
+/// `WESTERN BLOC` as `Western bloc`
+fn title_case(label: &str) -> String {
+    let lower = label.to_lowercase();
+    let mut chars = lower.chars();
+    chars.next().map_or_else(String::new, |first| first.to_uppercase().chain(chars).collect())
+}
+
+/// A value with as few digits as read well: `0.8`, `12`, `4.5k`
+fn short_number(value: f32) -> String {
+    match value.abs() {
+        v if v >= 10_000.0 => format!("{:.0}k", value / 1000.0),
+        v if v >= 1000.0 => format!("{:.1}k", value / 1000.0),
+        v if v >= 10.0 || value.fract() == 0.0 => format!("{value:.0}"),
+        v if v >= 0.1 => format!("{value:.1}"),
+        _ => format!("{value:.2}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::Switch;
+
+    #[test]
+    fn the_legend_follows_the_layers_showing() {
+        let mut app = App::new(80, 24);
+        let names = |app: &App| entries(app).into_iter().map(|e| e.name).collect::<Vec<_>>();
+        assert_eq!(names(&app)[..2], ["Coastlines", "Borders"]);
+        assert!(!names(&app).contains(&"graticule".to_string()), "overlays start hidden");
+
+        app.map_renderer.settings.show_factions = true;
+        assert_eq!(names(&app)[..4], ["Coastlines", "Western bloc", "Eastern bloc", "Non-aligned"]);
+        app.map_renderer.settings.show_factions = false;
+        app.overlays.set("graticule", Switch::On).unwrap();
+        let shown = names(&app);
+        assert_eq!(shown[..2], ["Coastlines", "Borders"]);
+        assert_eq!(shown.last().map(String::as_str), Some("graticule"));
+
+        assert!(shown.contains(&"Clouds".to_string()));
+        app.weather = crate::sim::weather::WeatherGrid::new(2.0);
+        assert!(!names(&app).contains(&"Clouds".to_string()), "no cloud to show");
+
+        assert_eq!(short_number(0.8), "0.8");
+        assert_eq!(short_number(80.0), "80");
+        assert_eq!(short_number(-3.0), "-3");
+        assert_eq!(short_number(4500.0), "4.5k");
+    }
+}
@@ -1,7 +1,42 @@
+pub mod ai;
 pub mod app;
+pub mod bench;
 pub mod braille;
+pub mod capture;
+pub mod cli;
+pub mod colors;
+pub mod command;
+pub mod control;
+pub mod dashboard;
 pub mod data;
+pub mod describe;
+pub mod events;
+pub mod faction;
+pub mod game;
 pub mod geo;
+pub mod gesture;
+pub mod graphics;
+pub mod halfblock;
 pub mod hash;
+pub mod headless;
+pub mod heatmap;
+pub mod history;
+pub mod legend;
+pub mod live;
+pub mod macros;
 pub mod map;
+pub mod memory;
+pub mod metrics;
+pub mod net;
+pub mod overlay;
+pub mod perf;
+pub mod quakes;
+pub mod satellites;
+pub mod save;
+pub mod sim;
+pub mod spectate;
+pub mod stats_csv;
+pub mod targeting;
+pub mod term;
+pub mod theme;
 pub mod ui;
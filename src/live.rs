@@ -0,0 +1,202 @@
+//! The live points overlay: things that move, such as flights or ships,
+//! from any URL or file that gives their positions as JSON.
+//!
+//! The source is fetched every few seconds on a worker thread. It should
+//! hold an array of objects like
+//!
+//! ```json
+//! [{"id": "BAW117", "lon": -41.2, "lat": 52.7, "label": "BA117 LHR-JFK"}]
+//! ```
+//!
+//! where `id` is a string or number and `label` is optional. Each point
+//! glides from where it was drawn to its new position over one polling
+//! interval, so markers move smoothly rather than jumping at each fetch.
+//! A point missing from `STALE_POLLS` fetches in a row is dropped.
+
+use crate::geo::wrap_lon;
+use crate::map::MapProjection;
+use crate::overlay::{LayerProvider, Poller, Shapes};
+use anyhow::{bail, Context, Result};
+use geojson::JsonValue;
+use ratatui::style::Color;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How often the source is fetched when `--live-every` isn't given
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Fetches a point can be missing from before it's dropped
+const STALE_POLLS: f64 = 3.0;
+
+/// Labels are left off past this many points, which would bury the map
+const MAX_LABELS: usize = 200;
+
+/// One entry in the source
+#[derive(Clone, Debug, PartialEq)]
+pub struct LivePoint {
+    pub id: String,
+    pub lon: f64,
+    pub lat: f64,
+    pub label: String,
+}
+
+/// The entries in a source's JSON. Entries without an id or a position
+/// are skipped.
+pub fn parse_points(text: String) -> Result<Vec<LivePoint>> {
+    let mut bytes = text.into_bytes();
+    let value: JsonValue = simd_json::serde::from_slice(&mut bytes)?;
+    let JsonValue::Array(entries) = value else { bail!("expected a JSON array") };
+    Ok(entries
+        .iter()
+        .filter_map(|entry| {
+            let id = match entry.get("id")? {
+                JsonValue::String(id) => id.clone(),
+                JsonValue::Number(id) => id.to_string(),
+                _ => return None,
+            };
+            let (lon, lat) = (entry.get("lon")?.as_f64()?, entry.get("lat")?.as_f64()?);
+            if !(-90.0..=90.0).contains(&lat) || !lon.is_finite() {
+                return None;
+            }
+            let label = entry.get("label").and_then(JsonValue::as_str).map_or_else(|| id.clone(), str::to_string);
+            Some(LivePoint { id, lon: wrap_lon(lon), lat, label })
+        })
+        .collect())
+}
+
+/// A point on its way from where it was to where it was last reported
+#[derive(Clone, Debug)]
+struct Track {
+    from: (f64, f64),
+    to: (f64, f64),
+    /// When it set off from `from`, unix seconds
+    moved_at: f64,
+    /// When a fetch last had it
+    seen_at: f64,
+    label: String,
+}
+
+pub struct LivePoints {
+    tracks: HashMap<String, Track>,
+    interval: f64,
+    /// Fetches the source, and is dropped with the overlay (`None` in tests)
+    poller: Option<Poller<Vec<LivePoint>>>,
+    message: Option<String>,
+}
+
+impl LivePoints {
+    fn new(interval: Duration, poller: Option<Poller<Vec<LivePoint>>>) -> Self {
+        Self { tracks: HashMap::new(), interval: interval.as_secs_f64(), poller, message: None }
+    }
+
+    /// Start polling `source`, an `http(s)://` URL or a file, every
+    /// `interval`
+    pub fn poll(source: &str, interval: Duration) -> Self {
+        let source = source.to_string();
+        let poller = Poller::spawn(interval, move || {
+            let text = if source.starts_with("http://") || source.starts_with("https://") {
+                String::from_utf8(crate::data::fetch::get(&source)?).context("the source isn't text")?
+            } else {
+                std::fs::read_to_string(&source).with_context(|| format!("reading {source}"))?
+            };
+            parse_points(text)
+        });
+        Self::new(interval, Some(poller))
+    }
+
+    /// Set the points off toward their new positions, from wherever they
+    /// are drawn at `now`, and drop the ones not heard from for a while
+    fn update(&mut self, points: Vec<LivePoint>, now: f64) {
+        for point in points {
+            let to = (point.lon, point.lat);
+            let from = self.tracks.get(&point.id).map_or(to, |track| track.position_at(now, self.interval));
+            self.tracks.insert(point.id, Track { from, to, moved_at: now, seen_at: now, label: point.label });
+        }
+        let expiry = STALE_POLLS * self.interval;
+        self.tracks.retain(|_, track| now - track.seen_at <= expiry);
+    }
+
+    /// What to draw at `now`, in unix seconds
+    pub fn shapes_at(&self, now: f64) -> Shapes {
+        const MARKER: Color = Color::Rgb(120, 220, 255);
+        const TEXT: Color = Color::Rgb(90, 160, 190);
+        let mut shapes = Shapes::default();
+        let labelled = self.tracks.len() <= MAX_LABELS;
+        for track in self.tracks.values() {
+            let (lon, lat) = track.position_at(now, self.interval);
+            shapes.point(lon, lat, '●', MARKER);
+            if labelled {
+                shapes.label(lon, lat, format!(" {}", track.label), TEXT);
+            }
+        }
+        shapes
+    }
+}
+
+impl Track {
+    /// Part way from `from` to `to`, the short way round the world
+    fn position_at(&self, now: f64, interval: f64) -> (f64, f64) {
+        let t = ((now - self.moved_at) / interval).clamp(0.0, 1.0);
+        let dlon = wrap_lon(self.to.0 - self.from.0);
+        (wrap_lon(self.from.0 + dlon * t), self.from.1 + (self.to.1 - self.from.1) * t)
+    }
+}
+
+impl LayerProvider for LivePoints {
+    fn name(&self) -> &str {
+        "live"
+    }
+
+    fn shapes(&mut self, _view: &dyn MapProjection) -> Shapes {
+        self.shapes_at(unix_now())
+    }
+
+    fn message(&mut self) -> Option<String> {
+        let updates: Vec<_> = self.poller.iter().flat_map(Poller::updates).collect();
+        for update in updates {
+            match update {
+                Ok(points) => self.update(points, unix_now()),
+                Err(e) => self.message = Some(format!("live points: {e:#}")),
+            }
+        }
+        self.message.take()
+    }
+}
+
+fn unix_now() -> f64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_glide_to_new_positions_and_expire() {
+        let first = parse_points(r#"[
+            {"id": "BAW117", "lon": 179.0, "lat": 50.0, "label": "BA117"},
+            {"id": 42, "lon": 10.0, "lat": 20.0},
+            {"id": "no-position"}
+        ]"#.to_string()).unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[1].label, "42");
+        assert!(parse_points(r#"{"id": 1}"#.to_string()).is_err());
+
+        let mut live = LivePoints::new(Duration::from_secs(10), None);
+        live.update(first, 0.0);
+        let second = parse_points(r#"[{"id": "BAW117", "lon": -179.0, "lat": 52.0}]"#.to_string()).unwrap();
+        live.update(second, 10.0);
+        let track = &live.tracks["BAW117"];
+        // Half way there, across the antimeridian rather than back round the world
+        assert_eq!(track.position_at(15.0, 10.0), (-180.0, 51.0));
+        assert_eq!(track.position_at(30.0, 10.0), (-179.0, 52.0));
+        assert_eq!(track.label, "BAW117");
+
+        // 42 was last seen at 0, and goes once three intervals have passed
+        live.update(Vec::new(), 30.0);
+        assert_eq!(live.tracks.len(), 2);
+        live.update(Vec::new(), 31.0);
+        assert_eq!(live.tracks.keys().collect::<Vec<_>>(), ["BAW117"]);
+        assert_eq!(live.shapes_at(31.0).points.len(), 1);
+    }
+}
@@ -0,0 +1,221 @@
+//! Vim-style macros: record input into a register, play it back.
+//!
+//! Keys and mouse events are recorded with the time they came in, and
+//! played back at the same pace, so a camera tour flies as it was flown
+//! and a strike pattern lands in the same rhythm. `:` commands are just
+//! keys, so they record too. Mouse events keep their screen cells; after a
+//! resize they land where those cells are now.
+
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Most events queued for playback, so a macro that plays itself ends
+const MAX_QUEUED: usize = 10_000;
+
+/// Gap between repeats of a macro played more than once
+const REPEAT_GAP: Duration = Duration::from_millis(100);
+
+/// Which key the next register name is for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Awaiting {
+    Record,
+    Play,
+}
+
+#[derive(Clone, Debug)]
+struct Timed {
+    /// Since recording started
+    at: Duration,
+    event: Event,
+}
+
+struct Recording {
+    register: char,
+    started: Instant,
+    events: Vec<Timed>,
+}
+
+#[derive(Default)]
+pub struct Macros {
+    registers: BTreeMap<char, Vec<Timed>>,
+    recording: Option<Recording>,
+    /// Events still to play, with when they're due
+    queue: VecDeque<(Instant, Event)>,
+    playing: Option<char>,
+    last_played: Option<char>,
+    pub awaiting: Option<Awaiting>,
+}
+
+impl Macros {
+    /// Registers are letters and digits, as in vim
+    pub fn is_register(c: char) -> bool {
+        c.is_ascii_alphanumeric()
+    }
+
+    pub fn recording(&self) -> Option<char> {
+        self.recording.as_ref().map(|r| r.register)
+    }
+
+    pub fn playing(&self) -> Option<char> {
+        self.playing
+    }
+
+    /// Registers that hold something, with how many events each
+    pub fn registers(&self) -> impl Iterator<Item = (char, usize)> + '_ {
+        self.registers.iter().map(|(&r, events)| (r, events.len()))
+    }
+
+    pub fn start_recording(&mut self, register: char, now: Instant) {
+        self.recording = Some(Recording { register, started: now, events: Vec::new() });
+    }
+
+    /// Note a real input event while recording. Key releases and anything
+    /// that isn't a key, mouse or paste are left out.
+    pub fn record(&mut self, event: &Event, now: Instant) {
+        let Some(recording) = self.recording.as_mut() else { return };
+        let wanted = match event {
+            Event::Key(key) => key.kind == KeyEventKind::Press,
+            Event::Mouse(_) | Event::Paste(_) => true,
+            _ => false,
+        };
+        if wanted {
+            recording.events.push(Timed { at: now.duration_since(recording.started), event: event.clone() });
+        }
+    }
+
+    /// Stop recording and keep what was recorded, less the input that
+    /// stopped it: the stop key itself, or with `from_command`, the
+    /// `:` command line back to the colon that opened it. Returns the
+    /// register and how many events it holds.
+    pub fn stop_recording(&mut self, from_command: bool) -> Option<(char, usize)> {
+        let mut recording = self.recording.take()?;
+        let is_key = |timed: &Timed, code: KeyCode| matches!(&timed.event, Event::Key(k) if k.code == code);
+        if from_command {
+            if let Some(colon) = recording.events.iter().rposition(|t| is_key(t, KeyCode::Char(':'))) {
+                recording.events.truncate(colon);
+            }
+        } else if recording.events.last().is_some_and(|t| {
+            matches!(&t.event, Event::Key(k) if k.code == KeyCode::Char('q') && k.modifiers.contains(KeyModifiers::CONTROL))
+        }) {
+            recording.events.pop();
+        }
+        let count = recording.events.len();
+        self.registers.insert(recording.register, recording.events);
+        Some((recording.register, count))
+    }
+
+    /// Queue a register's events `times` over, ahead of whatever is still
+    /// playing (so a macro that plays another finishes it first). `@` plays
+    /// the last register played. Returns the register, or why it can't.
+    pub fn play(&mut self, register: char, times: u32, now: Instant) -> Result<char, String> {
+        let register = match register {
+            '@' => self.last_played.ok_or("no macro played yet")?,
+            r => r,
+        };
+        if self.recording() == Some(register) {
+            return Err(format!("can't play @{register} while recording it"));
+        }
+        let events = match self.registers.get(&register) {
+            Some(events) if !events.is_empty() => events,
+            _ => return Err(format!("register {register} is empty")),
+        };
+        let times = times.max(1) as usize;
+        if self.queue.len() + events.len() * times > MAX_QUEUED {
+            self.stop_playing();
+            return Err(format!("@{register} is too long (playing itself?)"));
+        }
+        let length = events.last().map_or(Duration::ZERO, |t| t.at) + REPEAT_GAP;
+        let mut queued: Vec<(Instant, Event)> = Vec::with_capacity(events.len() * times);
+        for repeat in 0..times {
+            let start = now + length * repeat as u32;
+            queued.extend(events.iter().map(|t| (start + t.at, t.event.clone())));
+        }
+        // What was already queued waits until this finishes
+        let shift = length * times as u32;
+        for (due, _) in self.queue.iter_mut() {
+            *due += shift;
+        }
+        for event in queued.into_iter().rev() {
+            self.queue.push_front(event);
+        }
+        self.playing = Some(register);
+        self.last_played = Some(register);
+        Ok(register)
+    }
+
+    pub fn stop_playing(&mut self) {
+        self.queue.clear();
+        self.playing = None;
+    }
+
+    /// The next played event, once it's due
+    pub fn next_due(&mut self, now: Instant) -> Option<Event> {
+        let &(due, _) = self.queue.front()?;
+        if due > now {
+            return None;
+        }
+        let (_, event) = self.queue.pop_front()?;
+        if self.queue.is_empty() {
+            self.playing = None;
+        }
+        Some(event)
+    }
+
+    /// How long until the next played event is due
+    pub fn until_next(&self, now: Instant) -> Option<Duration> {
+        self.queue.front().map(|&(due, _)| due.saturating_duration_since(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+
+    fn key(c: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn macros_play_back_at_the_pace_they_were_recorded() {
+        let t0 = Instant::now();
+        let secs = |s: f64| t0 + Duration::from_secs_f64(s);
+        let mut macros = Macros::default();
+        macros.start_recording('a', t0);
+        macros.record(&key('+'), secs(0.5));
+        macros.record(&key('h'), secs(1.5));
+        macros.record(&Event::Key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL)), secs(2.0));
+        assert_eq!(macros.stop_recording(false), Some(('a', 2)));
+
+        assert_eq!(macros.play('a', 2, secs(10.0)), Ok('a'));
+        assert_eq!(macros.next_due(secs(10.4)), None);
+        assert_eq!(macros.next_due(secs(10.5)), Some(key('+')));
+        assert_eq!(macros.until_next(secs(10.5)), Some(Duration::from_secs(1)));
+        assert_eq!(macros.next_due(secs(11.5)), Some(key('h')));
+        // The second time round starts after the first, plus a short gap
+        assert_eq!(macros.next_due(secs(12.0)), None);
+        assert_eq!(macros.next_due(secs(12.2)), Some(key('+')));
+        assert_eq!(macros.playing(), Some('a'));
+        assert_eq!(macros.next_due(secs(20.0)), Some(key('h')));
+        assert_eq!(macros.playing(), None);
+
+        assert!(macros.play('b', 1, t0).is_err());
+        assert_eq!(macros.play('@', 1, t0), Ok('a'));
+    }
+
+    #[test]
+    fn commands_that_stop_recording_are_left_out() {
+        let t0 = Instant::now();
+        let mut macros = Macros::default();
+        macros.start_recording('t', t0);
+        for c in "+:goto paris\r:record".chars() {
+            macros.record(&key(c), t0);
+        }
+        assert_eq!(macros.stop_recording(true), Some(('t', 13)));
+
+        // Too many events queued stops playback: a macro that plays itself ends
+        assert!(macros.play('t', MAX_QUEUED as u32, t0).is_err());
+        assert_eq!(macros.playing(), None);
+    }
+}
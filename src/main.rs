@@ -1,52 +1,330 @@
-mod app;
-mod braille;
-mod data;
-mod geo;
-mod hash;
-mod map;
-mod ui;
-
 use anyhow::Result;
-use app::{App, WeaponType};
+use tui_map::ai::{Adversary, Difficulty};
+use tui_map::app::{App, Stockpile, View};
+use tui_map::capture::{CastWriter, FrameSink};
+use tui_map::cli::Args;
+use tui_map::colors::ColorDepth;
+use tui_map::game::{GameState, Objective, Outcome};
+use tui_map::gesture::Input;
+use tui_map::graphics::{Basemap, Protocol, SixelHeatmaps};
+use tui_map::net::NetSession;
+use tui_map::perf::Phase;
+use tui_map::data::fetch::{self, DataManager, Resolution};
+use tui_map::data::geocode::GeocodeService;
+use tui_map::data::landcover::LandCover;
+use tui_map::data::population::PopulationGrid;
+use tui_map::data::scenario::{Scenario, ScenarioRunner};
+use tui_map::data::tiles::{self, DiskCache, TileManager};
+use tui_map::data::watch::DataWatcher;
+use tui_map::heatmap::Heatmaps;
+use tui_map::live::{self, LivePoints};
+use tui_map::quakes::Quakes;
+use tui_map::satellites::Satellites;
+use tui_map::term::Integration;
+use tui_map::map::{Infrastructure, MapRenderer};
+use tui_map::metrics::MetricsServer;
+use tui_map::control::ControlSocket;
+use tui_map::spectate::{Spectator, SpectatorHost};
+use tui_map::stats_csv::StatsCsv;
+use tui_map::sim::{WindField, WindGrid};
+use tui_map::{bench, data, headless, ui};
 use crossterm::event::{
-    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
-    MouseEvent, MouseEventKind,
+    self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event, KeyCode,
+    KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
 };
 use crossterm::execute;
+use simd_json::prelude::Writable;
 use ratatui::DefaultTerminal;
-use std::path::Path;
-use std::time::Duration;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 fn main() -> Result<()> {
+    // Parse args before touching the terminal so errors print normally
+    let args = Args::from_env()?;
+    let wind = args.wind_grid.as_deref().map(WindGrid::load).transpose()?;
+    let population = args.population.as_deref().map(PopulationGrid::load).transpose()?;
+    let land_cover = args.land_cover.as_deref().map(LandCover::load).transpose()?;
+    let heatmaps = Heatmaps::load(&args.heatmaps)?;
+    let scenario = args.scenario.as_deref().map(Scenario::load).transpose()?;
+    let stats_out = args.stats_out.as_deref().map(StatsCsv::create).transpose()?;
+
+    if args.headless {
+        let mut app = new_app(120, 40, &args, wind, population, land_cover, scenario);
+        app.heatmaps = heatmaps;
+        app.stats_out = stats_out;
+        let report = headless::run(&mut app, args.ticks.unwrap_or(DEFAULT_HEADLESS_TICKS));
+        if let Some(path) = &args.screenshot {
+            let (width, height) = app.screenshot_size;
+            app.screenshot(path, width, height)?;
+        }
+        if let Some(path) = &args.export {
+            data::export::save(&app, path)?;
+        }
+        if let Some(path) = &args.ansi {
+            app.write_text_frame(path)?;
+        }
+        writeln!(std::io::stdout(), "{}", report.encode_pp())?;
+        return Ok(());
+    }
+
+    if let Some(frames) = args.bench_frames {
+        // Time frames at the size this terminal would draw them
+        let (width, height) = crossterm::terminal::size().unwrap_or((120, 40));
+        let mut app = new_app(width as usize, height as usize, &args, wind, population, land_cover, scenario);
+        app.heatmaps = heatmaps;
+        app.stats_out = stats_out;
+        let report = bench::run(&mut app, width, height, frames);
+        writeln!(std::io::stdout(), "{}", report.encode_pp())?;
+        return Ok(());
+    }
+
+    let tile_manager = build_tile_manager(&args)?;
+    let satellites = args.tle.as_deref().map(|source| Satellites::load(source, &args.track)).transpose()?;
+    let net = connect_player(&args)?;
+    let metrics = args.metrics_port.map(|port| MetricsServer::start(args.bind_addr(), port)).transpose()?;
+    let control = args.control.as_deref().map(ControlSocket::bind).transpose()?;
+    let spectators = args.broadcast.map(|port| SpectatorHost::start(args.bind_addr(), port)).transpose()?;
+    let spectating = args.watch.as_deref().map(Spectator::connect).transpose()?;
+    let capture = args.capture.as_deref()
+        .map(|path| CastWriter::create(path).map(|cast| Box::new(cast) as Box<dyn FrameSink>))
+        .transpose()?;
+
     // Initialize terminal
     let mut terminal = ratatui::init();
     terminal.clear()?;
 
-    // Enable mouse capture
-    execute!(std::io::stdout(), EnableMouseCapture)?;
+    // Enable mouse capture, and pastes as one event so they can't fire keys
+    execute!(std::io::stdout(), EnableMouseCapture, EnableBracketedPaste)?;
 
     // Run the app
-    let result = run(&mut terminal);
+    let size = terminal.size()?;
+    let mut app = new_app(size.width as usize, size.height as usize, &args, wind, population, land_cover, scenario);
+    app.heatmaps = heatmaps;
+    app.tiles = tile_manager;
+    app.control = control;
+    app.stats_out = stats_out;
+    app.geocoder = Some(GeocodeService::with_defaults(Path::new(DATA_DIR)));
+    if let Some(satellites) = satellites {
+        app.overlays.register(Box::new(satellites), true);
+    }
+    if let Some(feed) = &args.quakes {
+        app.overlays.register(Box::new(Quakes::poll(feed)), true);
+    }
+    if let Some(source) = &args.live {
+        let interval = args.live_every.map_or(live::DEFAULT_INTERVAL, Duration::from_secs_f64);
+        app.overlays.register(Box::new(LivePoints::poll(source, interval)), true);
+    }
+    if let Some(session) = net {
+        app.join_network(session);
+    }
+    app.spectators = spectators;
+    if let Some(spectator) = spectating {
+        app.status_message = Some(format!("Watching {}", spectator.host));
+        app.spectating = Some(spectator);
+    }
+    let mut term = Integration::new(&mut std::io::stdout(), !args.no_title, args.bell, args.notify)?;
+    let result = run(&mut terminal, app, capture, &mut term, metrics);
+    let _ = term.finish(&mut std::io::stdout());
 
     // Disable mouse capture and restore terminal
-    let _ = execute!(std::io::stdout(), DisableMouseCapture);
+    let _ = execute!(std::io::stdout(), DisableMouseCapture, DisableBracketedPaste);
     ratatui::restore();
 
     result
 }
 
+/// Map data directory, relative to the working directory
+const DATA_DIR: &str = "data";
+
+/// Headless run length when `--ticks` isn't given (one minute at 60fps)
+const DEFAULT_HEADLESS_TICKS: u64 = 3600;
+
+/// Create the app from CLI options and load the world
+fn new_app(
+    width: usize,
+    height: usize,
+    args: &Args,
+    wind: Option<WindGrid>,
+    population: Option<PopulationGrid>,
+    land_cover: Option<LandCover>,
+    scenario: Option<Scenario>,
+) -> App {
+    let mut app = App::new(width, height);
+    if let Some(seed) = args.seed {
+        app.set_seed(seed);
+    }
+    if let Some(r0) = args.r0 {
+        app.r0 = r0;
+    }
+    app.memory.limit = args.memory_budget;
+    if let Some(rate) = args.zoom_rate {
+        app.zoom_rate = rate;
+    }
+    if let Some(friction) = args.friction {
+        app.friction = friction;
+    }
+    app.text_mode = args.text_mode;
+    app.disasters = args.disasters;
+    if let Some(scroll) = args.scroll {
+        app.gestures.scroll = scroll;
+    }
+    if let Some(window) = args.double_click {
+        app.gestures.double_click = window;
+    }
+    if let Some(capacities) = args.stockpile {
+        app.stockpiles = capacities.map(Stockpile::new);
+    }
+    if let Some(warheads) = args.mirv {
+        app.mirv_armed = true;
+        app.mirv.warheads = warheads;
+    }
+    if let Some(km) = args.mirv_spread {
+        app.mirv.spread_km = km;
+    }
+    app.adversary = args.ai.map(Adversary::new);
+    app.scenario = scenario.map(ScenarioRunner::new);
+    app.game = GameState::new(args.objective);
+    // Surviving retaliation needs someone to retaliate
+    if matches!(args.objective, Some(Objective::Survive { .. })) && app.adversary.is_none() {
+        app.adversary = Some(Adversary::new(Difficulty::Normal));
+    }
+    if let Some(grid) = wind {
+        app.wind = WindField::Grid(grid);
+    }
+    app.population = population;
+    if let Some(cover) = land_cover {
+        app.land_cover = cover;
+    }
+    app.screenshot_path = args.screenshot.clone();
+    app.export_path = args.export.clone();
+    // Stdout belongs to the terminal UI, so interactive dumps go to files
+    app.ansi_path = args.ansi.clone().filter(|p| p != Path::new("-"));
+    if let Some(size) = args.screenshot_size {
+        app.screenshot_size = size;
+    }
+    app.map_renderer.settings.names = args.names.clone();
+    load_world(&mut app, Path::new(DATA_DIR));
+    if args.pandemic {
+        app.set_pandemic(true);
+    }
+    app.data_manager = DataManager::new(PathBuf::from(DATA_DIR));
+    // First run: offer to fetch real data rather than leave the rough
+    // built-in world, if this build can fetch anything
+    app.data_manager.open = cfg!(feature = "online") && !args.headless && !fetch::has_any(Path::new(DATA_DIR));
+    app.map_renderer.settings.antialias = args.antialias;
+    if let Some(fonts) = args.label_fonts {
+        app.map_renderer.settings.label_fonts = fonts.for_term(std::env::var("TERM").ok().as_deref());
+    }
+    if let Some(px) = args.simplify {
+        app.map_renderer.settings.simplify = px;
+    }
+    if let Some(theme) = args.theme {
+        app.theme = theme;
+    }
+    app.color_depth = args.colors.unwrap_or_else(ColorDepth::detect);
+    app.graphics = args.graphics;
+    if let Some(cap) = args.fps {
+        app.perf.cap = cap;
+    }
+    app.perf.auto_degrade = !args.no_degrade;
+    app
+}
+
+/// Load all available GeoJSON data (or the world built into the binary) and build
+/// the spatial indexes: land grid for fire filtering, country grid for
+/// faction tinting, feature grids for viewport queries
+fn load_world(app: &mut App, data_dir: &Path) {
+    if data_dir.exists() {
+        let _ = data::load_all_geojson(&mut app.map_renderer, data_dir);
+        if let Err(e) = data::cut_counties(&mut app.map_renderer, data_dir) {
+            app.status_message = Some(format!("{e:#}; counties kept whole"));
+        }
+    }
+    app.county_chunks = data::county_chunks(data_dir);
+    if !app.map_renderer.has_data() && data::embedded::load(&mut app.map_renderer).is_err() {
+        data::generate_simple_world(&mut app.map_renderer);
+    }
+    app.map_renderer.build_land_grid();
+    app.map_renderer.build_country_grid();
+    app.map_renderer.build_spatial_indexes();
+    if let Some(grid) = app.population.as_mut() {
+        grid.exclude_cities(app.map_renderer.city_grid.iter());
+    }
+    app.land_cover.mark_cities(app.map_renderer.city_grid.iter());
+    app.refresh_faction_stats();
+    app.rebuild_travel();
+}
+
+/// Load the world again once the data manager has fetched new files. What
+/// was loaded before is dropped, damage to it included.
+fn reload_world(app: &mut App, data_dir: &Path) {
+    // Snapshots index the old cities
+    app.return_to_now();
+    app.history.clear();
+    let settings = app.map_renderer.settings.clone();
+    app.map_renderer = MapRenderer::new();
+    app.map_renderer.settings = settings;
+    load_world(app, data_dir);
+}
+
+/// Build the vector-tile fetcher from CLI flags, if a tile source was given
+fn build_tile_manager(args: &Args) -> Result<Option<TileManager>> {
+    let source = match (&args.tile_url, &args.tile_dir) {
+        (Some(url), _) => tiles::source_from_url(url)?,
+        (None, Some(dir)) => Box::new(tiles::DirSource::new(dir.clone())),
+        (None, None) => return Ok(None),
+    };
+    // Local directories are already on disk — only cache remote fetches
+    let cache = args.tile_url.as_ref().map(|url| {
+        DiskCache::for_source(args.tile_cache.clone().unwrap_or_else(DiskCache::default_root), url)
+    });
+    Ok(Some(TileManager::new(source, cache, args.tile_max_zoom.unwrap_or(14))))
+}
+
+/// Wait for (or reach) the other player when `--host`/`--connect` was given
+fn connect_player(args: &Args) -> Result<Option<NetSession>> {
+    if let Some(port) = args.host {
+        eprintln!("Waiting for a player to connect on {}:{port}...", args.bind_addr());
+        return NetSession::host(args.bind_addr(), port).map(Some);
+    }
+    args.connect.as_deref().map(NetSession::connect).transpose()
+}
+
 /// Handle mouse events for panning and zooming
 fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    // The dashboard covers the map; don't pan or strike it blind
+    if app.view != View::Map {
+        return;
+    }
+
     // Always track mouse position for cursor marker
     app.set_mouse_pos(mouse.column, mouse.row);
 
+    let mouse = match app.gestures.input(mouse, Instant::now()) {
+        Some(Input::Mouse(mouse)) => mouse,
+        // Scroll eases the zoom towards the mouse position
+        Some(Input::Zoom { col, row, notches, fast }) => return app.scroll_zoom(col, row, notches, fast),
+        Some(Input::Pan(dx, dy)) => return app.pan(dx, dy),
+        // Double click zooms in where it landed; the press still starts a drag
+        Some(Input::DoubleClick { col, row }) => {
+            app.start_drag(col, row);
+            return app.scroll_zoom(col, row, 1, true);
+        }
+        None => return,
+    };
+
     match mouse.kind {
-        // Scroll wheel for zooming towards mouse position
-        MouseEventKind::ScrollUp => app.zoom_in_at(mouse.column, mouse.row),
-        MouseEventKind::ScrollDown => app.zoom_out_at(mouse.column, mouse.row),
-        // Horizontal scroll for panning (trackpad two-finger swipe)
-        MouseEventKind::ScrollLeft => app.pan(-15, 0),
-        MouseEventKind::ScrollRight => app.pan(15, 0),
+        // Shift+drag draws a rectangle to zoom to
+        MouseEventKind::Down(MouseButton::Left) if mouse.modifiers.contains(KeyModifiers::SHIFT) => {
+            app.start_box_zoom(mouse.column, mouse.row);
+        }
+        MouseEventKind::Drag(MouseButton::Left) if app.box_zoom.is_some() => {
+            app.drag_box_zoom(mouse.column, mouse.row);
+        }
+        MouseEventKind::Up(MouseButton::Left) if app.box_zoom.is_some() => {
+            app.finish_box_zoom();
+        }
         // Click and drag to pan
         MouseEventKind::Down(MouseButton::Left) => {
             app.start_drag(mouse.column, mouse.row);
@@ -57,114 +335,463 @@ fn handle_mouse(app: &mut App, mouse: MouseEvent) {
         MouseEventKind::Up(MouseButton::Left) => {
             app.end_drag();
         }
-        // Right click to launch nuke
+        // Shift+right click marks a target for the strike package
+        MouseEventKind::Down(MouseButton::Right) if mouse.modifiers.contains(KeyModifiers::SHIFT) => {
+            app.queue_target(mouse.column, mouse.row);
+        }
+        // Right click to launch nuke (or place an ABM battery)
         MouseEventKind::Down(MouseButton::Right) => {
-            app.launch_nuke(mouse.column, mouse.row);
+            app.click_target(mouse.column, mouse.row);
+        }
+        // Releasing after a drag finishes a corridor or area strike
+        MouseEventKind::Up(MouseButton::Right) => {
+            app.release_target(mouse.column, mouse.row);
         }
         _ => {}
     }
 }
 
-fn run(terminal: &mut DefaultTerminal) -> Result<()> {
-    let size = terminal.size()?;
-    let mut app = App::new(size.width as usize, size.height as usize);
-
-    // Load all available GeoJSON data at different resolutions
-    let data_dir = Path::new("data");
-    if data_dir.exists() {
-        let _ = data::load_all_geojson(&mut app.map_renderer, data_dir);
+/// Rebuild the app for a reset, carrying over services that outlive a session
+fn reset_app(old: &mut App, width: usize, height: usize, data_dir: &Path) -> App {
+    let mut app = App::new(width, height);
+    app.tiles = old.tiles.take();
+    app.geocoder = old.geocoder.take();
+    app.adversary = old.adversary.as_ref().map(|a| Adversary::new(a.difficulty));
+    app.game = GameState::new(old.game.objective());
+    if let Some(seed) = old.seed {
+        app.set_seed(seed);
     }
+    app.r0 = old.r0;
+    app.mortality = old.mortality;
+    app.disasters = old.disasters;
+    app.screenshot_path = old.screenshot_path.take();
+    app.screenshot_size = old.screenshot_size;
+    app.export_path = old.export_path.take();
+    app.ansi_path = old.ansi_path.take();
+    app.stats_out = old.stats_out.take();
+    app.zoom_rate = old.zoom_rate;
+    app.friction = old.friction;
+    app.text_mode = old.text_mode;
+    app.gestures.scroll = old.gestures.scroll;
+    app.gestures.double_click = old.gestures.double_click;
+    if let Some(session) = old.net.take() {
+        app.join_network(session);
+    }
+    if let WindField::Grid(_) = old.wind {
+        app.wind = std::mem::replace(&mut old.wind, WindField::Zonal { season_shift: 0.0 });
+    }
+    app.population = old.population.take().map(|mut grid| {
+        grid.restore();
+        grid
+    });
+    app.land_cover = std::mem::take(&mut old.land_cover);
+    app.map_renderer.settings.names = old.map_renderer.settings.names.clone();
+    load_world(&mut app, data_dir);
+    if old.pandemic {
+        app.set_pandemic(true);
+    }
+    app.map_renderer.settings.antialias = old.map_renderer.settings.antialias;
+    app.map_renderer.settings.simplify = old.map_renderer.settings.simplify;
+    app.map_renderer.settings.label_fonts = old.map_renderer.settings.label_fonts;
+    app.theme = old.theme;
+    app.reticle = old.reticle;
+    app.mirv_armed = old.mirv_armed;
+    app.mirv = old.mirv;
+    app.stockpiles = old.stockpiles.map(|s| Stockpile::new(s.capacity));
+    app.command = std::mem::take(&mut old.command);
+    app.macros = std::mem::take(&mut old.macros);
+    app.overlays = std::mem::take(&mut old.overlays);
+    app.heatmaps = std::mem::take(&mut old.heatmaps);
+    app.data_manager = std::mem::replace(&mut old.data_manager, DataManager::new(data_dir.to_path_buf()));
+    app.color_depth = old.color_depth;
+    app.memory.limit = old.memory.limit;
+    app.perf.cap = old.perf.cap;
+    app.perf.show_hud = old.perf.show_hud;
+    app.perf.auto_degrade = old.perf.auto_degrade;
+    app
+}
 
-    // Fall back to simple world if no data loaded
-    if !app.map_renderer.has_data() {
-        data::generate_simple_world(&mut app.map_renderer);
+/// The next input to handle: a playing macro's next event once it's due,
+/// else the terminal's. `None` once the next frame is due under the cap.
+fn next_event(app: &mut App, frame_start: Instant) -> Result<Option<Event>> {
+    loop {
+        let now = Instant::now();
+        if let Some(event) = app.macros.next_due(now) {
+            return Ok(Some(event));
+        }
+        let frame_wait = app.perf.wait(frame_start);
+        let macro_wait = app.macros.until_next(now);
+        if event::poll(macro_wait.map_or(frame_wait, |wait| wait.min(frame_wait)))? {
+            let event = event::read()?;
+            // Esc stops a playing macro rather than doing what it usually does
+            if app.macros.playing().is_some()
+                && matches!(&event, Event::Key(key) if key.code == KeyCode::Esc && key.kind == KeyEventKind::Press)
+            {
+                app.stop_macro();
+                continue;
+            }
+            app.macros.record(&event, Instant::now());
+            return Ok(Some(event));
+        }
+        // Woke for the macro rather than the frame: go round for its event
+        if macro_wait.is_none_or(|wait| wait >= frame_wait) {
+            return Ok(None);
+        }
     }
+}
 
-    // Build spatial indexes: land grid for fire filtering, feature grids for viewport queries
-    app.map_renderer.build_land_grid();
-    app.map_renderer.build_spatial_indexes();
+fn run(
+    terminal: &mut DefaultTerminal,
+    mut app: App,
+    mut capture: Option<Box<dyn FrameSink>>,
+    term: &mut Integration,
+    mut metrics: Option<MetricsServer>,
+) -> Result<()> {
+    let data_dir = Path::new(DATA_DIR);
+    let mut last_frame = Instant::now();
+    let started = last_frame;
+    // Data files edited while the map runs reload the layers they feed
+    let mut watcher = DataWatcher::new(data_dir.to_path_buf(), started);
+    // The basemap as an image beneath the cells, or heatmaps as one in
+    // the blank cells, when the terminal can show them
+    let mut basemap = (app.graphics == Some(Protocol::Kitty)).then(Basemap::default);
+    let mut sixel = (app.graphics == Some(Protocol::Sixel)).then(SixelHeatmaps::default);
 
     // Main loop
     loop {
+        let frame_start = Instant::now();
+
+        // Pull in any finished tile fetches and geocoder results before drawing
+        app.update_tiles();
+        app.update_chunks();
+        app.update_memory();
+        app.update_search();
+        app.update_overlays();
+        ControlSocket::serve(&mut app);
+        if app.update_downloads() {
+            reload_world(&mut app, data_dir);
+            watcher.rescan(Instant::now());
+        }
+        let changed = watcher.poll(frame_start);
+        if !changed.is_empty() {
+            app.reload_data_files(&changed);
+        }
+
         // Draw
-        terminal.draw(|frame| ui::render(frame, &mut app))?;
-
-        // Handle events with ~60fps target
-        if event::poll(Duration::from_millis(16))? {
-            match event::read()? {
-                Event::Key(key) => {
-                    // Only handle key press events (not release)
-                    if key.kind == KeyEventKind::Press {
-                        match key.code {
-                            KeyCode::Char('q') | KeyCode::Esc => app.quit(),
-
-                            // Pan with hjkl or arrow keys
-                            KeyCode::Left | KeyCode::Char('h') => app.pan(-10, 0),
-                            KeyCode::Right | KeyCode::Char('l') => app.pan(10, 0),
-                            KeyCode::Up | KeyCode::Char('k') => app.pan(0, -6),
-                            KeyCode::Down | KeyCode::Char('j') => app.pan(0, 6),
-
-                            // Zoom
-                            KeyCode::Char('+') | KeyCode::Char('=') => app.zoom_in(),
-                            KeyCode::Char('-') | KeyCode::Char('_') => app.zoom_out(),
-
-                            // Layer toggles
-                            KeyCode::Char('b') | KeyCode::Char('B') => {
-                                app.map_renderer.toggle_borders();
-                            }
-                            KeyCode::Char('s') | KeyCode::Char('S') => {
-                                app.map_renderer.toggle_states();
-                            }
-                            KeyCode::Char('c') | KeyCode::Char('C') => {
-                                app.map_renderer.toggle_cities();
+        let render_start = Instant::now();
+        let drawn = terminal.draw(|frame| ui::render(frame, &mut app))?;
+        if let Some(sink) = capture.as_mut() {
+            sink.frame(started.elapsed().as_secs_f64(), drawn.buffer)?;
+        }
+        if let Some(shown) = basemap.as_mut() {
+            // A terminal that stops taking images falls back to braille
+            if let Err(e) = shown.update(&mut std::io::stdout(), &app, frame_start) {
+                basemap = None;
+                app.graphics = None;
+                app.status_message = Some(format!("Basemap image failed, drawing in braille: {e}"));
+            }
+        }
+        let alerts = std::mem::take(&mut app.alerts);
+        // The map carries on without a title and alerts if they can't be written
+        if let Err(e) = term.update(&mut std::io::stdout(), &app, &alerts) {
+            term.disable();
+            app.status_message = Some(format!("Window title and alerts stopped: {e}"));
+        }
+        if let Some(metrics) = metrics.as_mut() {
+            metrics.publish(&app, frame_start);
+        }
+        if let Some(shown) = sixel.as_mut() {
+            match shown.update(&mut std::io::stdout(), &app, drawn.buffer) {
+                Ok(true) => terminal.clear()?,
+                Ok(false) => {}
+                Err(e) => {
+                    sixel = None;
+                    app.graphics = None;
+                    app.status_message = Some(format!("Heatmap image failed, drawing in characters: {e}"));
+                }
+            }
+        }
+        app.perf.record(Phase::Render, render_start.elapsed());
+
+        // Handle events until the next frame is due under the cap
+        while let Some(event) = next_event(&mut app, frame_start)? {
+            if matches!(event, Event::Key(_) | Event::Mouse(_)) {
+                app.note_input();
+            }
+            match event {
+                // A macro register name after Ctrl+Q or @
+                Event::Key(key) if key.kind == KeyEventKind::Press && app.macros.awaiting.is_some() => {
+                    app.macro_register(match key.code {
+                        KeyCode::Char(c) => Some(c),
+                        _ => None,
+                    });
+                }
+                // The data manager takes keys while it's showing
+                Event::Key(key) if key.kind == KeyEventKind::Press && app.data_manager.open => {
+                    match key.code {
+                        KeyCode::Char('1') => app.download_data(Resolution::Low),
+                        KeyCode::Char('2') => app.download_data(Resolution::Medium),
+                        KeyCode::Char('3') => app.download_data(Resolution::High),
+                        KeyCode::Char('v') | KeyCode::Char('V') => app.verify_data(),
+                        KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => app.close_data_manager(),
+                        _ => {}
+                    }
+                }
+                // Search box captures all keys while open
+                Event::Key(key) if key.kind == KeyEventKind::Press && app.search.is_some() => {
+                    match key.code {
+                        KeyCode::Esc => app.cancel_search(),
+                        KeyCode::Enter => app.submit_search(),
+                        KeyCode::Backspace => {
+                            if let Some(query) = app.search.as_mut() {
+                                query.pop();
                             }
-                            KeyCode::Char('y') | KeyCode::Char('Y') => {
-                                app.map_renderer.toggle_counties();
+                        }
+                        KeyCode::Char(c) => {
+                            if let Some(query) = app.search.as_mut() {
+                                query.push(c);
                             }
-                            KeyCode::Char('L') => {
-                                app.map_renderer.toggle_labels();
+                        }
+                        _ => {}
+                    }
+                }
+                // So does the command line
+                Event::Key(key) if key.kind == KeyEventKind::Press && app.command.is_open() => {
+                    match key.code {
+                        KeyCode::Esc => app.cancel_command(),
+                        KeyCode::Enter => app.submit_command(),
+                        // Backspace on an empty line closes it, as in vim
+                        KeyCode::Backspace => app.command.backspace(),
+                        KeyCode::Tab => app.command.complete(),
+                        KeyCode::Up => app.command.previous(),
+                        KeyCode::Down => app.command.next(),
+                        KeyCode::Char(c) => app.command.push(c),
+                        _ => {}
+                    }
+                }
+                // Summary screen: keep playing, restart or quit
+                Event::Key(key) if key.kind == KeyEventKind::Press && app.game.is_over() => {
+                    match key.code {
+                        KeyCode::Esc | KeyCode::Enter => app.dismiss_summary(),
+                        KeyCode::Char('r') | KeyCode::Char('R') => {
+                            let size = terminal.size()?;
+                            app = reset_app(&mut app, size.width as usize, size.height as usize, data_dir);
+                        }
+                        KeyCode::Char('q') => app.quit(),
+                        _ => {}
+                    }
+                }
+                // Only handle key press events (not release)
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    match key.code {
+                        // Esc leaves placement mode before it quits
+                        KeyCode::Esc if app.history.is_scrubbing() => app.return_to_now(),
+                        KeyCode::Esc if app.placing_defense => app.toggle_defense_placement(),
+                        KeyCode::Esc if app.show_arsenal => app.toggle_arsenal(),
+                        KeyCode::Esc if app.strike_anchor.is_some() => app.cancel_pattern(),
+                        KeyCode::Esc if !app.strike_queue.is_empty() => app.clear_queue(),
+                        KeyCode::Esc if app.crosshair => app.toggle_crosshair(),
+                        KeyCode::Char('q') if key.modifiers.contains(KeyModifiers::CONTROL) => app.begin_macro_record(),
+                        KeyCode::Char('q') | KeyCode::Esc => app.quit(),
+
+                        // Performance HUD and frame-rate cap
+                        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => app.toggle_perf_hud(),
+                        KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => app.cycle_fps_cap(),
+
+                        // Timeline: Alt+arrows step through history (Shift for a minute)
+                        KeyCode::Left | KeyCode::Right if key.modifiers.contains(KeyModifiers::ALT) => {
+                            let step = if key.modifiers.contains(KeyModifiers::SHIFT) { 12 } else { 1 };
+                            app.scrub(if key.code == KeyCode::Left { -step } else { step });
+                        }
+
+                        // Keyboard targeting: arrows aim (Shift for bigger steps), Enter
+                        // fires unless a strike package is queued, Tab picks cities
+                        KeyCode::Char('k') if key.modifiers.contains(KeyModifiers::CONTROL) => app.toggle_crosshair(),
+                        KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down if app.crosshair => {
+                            let step = if key.modifiers.contains(KeyModifiers::SHIFT) { 5 } else { 1 };
+                            match key.code {
+                                KeyCode::Left => app.move_crosshair(-step, 0),
+                                KeyCode::Right => app.move_crosshair(step, 0),
+                                KeyCode::Up => app.move_crosshair(0, -step),
+                                _ => app.move_crosshair(0, step),
                             }
-                            KeyCode::Char('p') | KeyCode::Char('P') => {
-                                app.map_renderer.toggle_population();
+                        }
+                        KeyCode::Enter if app.crosshair && app.strike_queue.is_empty() => {
+                            if let Some((col, row)) = app.mouse_pos {
+                                app.click_target(col, row);
                             }
+                        }
+                        KeyCode::Tab if app.crosshair => app.crosshair_to_next_city(false),
+                        KeyCode::BackTab if app.crosshair => app.crosshair_to_next_city(true),
+
+                        // Pan with hjkl or arrow keys
+                        KeyCode::Left | KeyCode::Char('h') => app.pan(-10, 0),
+                        KeyCode::Right | KeyCode::Char('l') => app.pan(10, 0),
+                        KeyCode::Up | KeyCode::Char('k') => app.pan(0, -6),
+                        KeyCode::Down | KeyCode::Char('j') => app.pan(0, 6),
 
-                            // Toggle globe/mercator
-                            KeyCode::Char('g') | KeyCode::Char('G') => {
-                                app.toggle_projection();
+                        // Zoom
+                        KeyCode::Char('+') | KeyCode::Char('=') => app.zoom_in(),
+                        KeyCode::Char('-') | KeyCode::Char('_') => app.zoom_out(),
+
+                        // Anti-aliased coastlines and borders
+                        KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => app.toggle_antialias(),
+
+                        // Next color theme
+                        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => app.cycle_theme(),
+
+                        // Shade glyphs or half blocks for fires and choropleths
+                        KeyCode::Char('b') if key.modifiers.contains(KeyModifiers::CONTROL) => app.toggle_fill_mode(),
+
+                        // Layer toggles
+                        KeyCode::Char('b') | KeyCode::Char('B') => {
+                            app.map_renderer.toggle_borders();
+                        }
+                        KeyCode::Char('s') | KeyCode::Char('S') => {
+                            app.map_renderer.toggle_states();
+                        }
+                        KeyCode::Char('c') | KeyCode::Char('C') => {
+                            app.map_renderer.toggle_cities();
+                        }
+                        KeyCode::Char('y') | KeyCode::Char('Y') => {
+                            app.map_renderer.toggle_counties();
+                        }
+                        KeyCode::Char('L') => {
+                            app.map_renderer.toggle_labels();
+                        }
+                        KeyCode::Char('p') | KeyCode::Char('P') => {
+                            app.map_renderer.toggle_population();
+                        }
+
+                        // Sun glint on the globe's oceans / cycle projections
+                        KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => app.toggle_glint(),
+                        KeyCode::Char('g') | KeyCode::Char('G') => {
+                            app.toggle_projection();
+                        }
+
+                        // Weapon selection
+                        KeyCode::Char(c @ '1'..='9') => app.select_weapon_slot(c as usize - '1' as usize),
+                        KeyCode::Char('i') | KeyCode::Char('I') => app.toggle_arsenal(),
+                        KeyCode::Char('m') | KeyCode::Char('M') => app.cycle_yield(),
+                        KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => app.toggle_mirv(),
+                        KeyCode::Char('R') => app.cycle_reticle(),
+                        KeyCode::Char('z') | KeyCode::Char('Z') => app.toggle_infection(),
+                        KeyCode::Char('u') | KeyCode::Char('U') => app.toggle_radiation(),
+                        KeyCode::Char('#') => app.toggle_population(),
+                        KeyCode::Char('H') => app.toggle_hud(),
+                        KeyCode::Char('[') => app.toggle_infrastructure(Infrastructure::Road),
+                        KeyCode::Char(']') => app.toggle_infrastructure(Infrastructure::Railway),
+                        KeyCode::Char('{') => app.toggle_infrastructure(Infrastructure::Airport),
+                        KeyCode::Char('}') => app.toggle_infrastructure(Infrastructure::Port),
+
+                        // ABM battery placement mode
+                        KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => app.toggle_disasters(),
+                        KeyCode::Char('d') | KeyCode::Char('D') => app.toggle_defense_placement(),
+
+                        // Delivery platform (ICBM → SLBM → bomber)
+                        KeyCode::Char('v') | KeyCode::Char('V') => app.cycle_delivery(),
+
+                        // GeoJSON export of the world state / text dump of the map
+                        KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => app.export_geojson(),
+                        KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => app.dump_text_frame(),
+                        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => app.toggle_text_mode(),
+
+                        // Move the submarine / retarget the latest bomber to the cursor
+                        KeyCode::Char('e') | KeyCode::Char('E') => {
+                            if let Some((col, row)) = app.mouse_pos {
+                                app.redirect_platform(col, row);
                             }
+                        }
+
+                        // Idle auto-rotation / adversary difficulty (off → easy → normal → hard)
+                        KeyCode::Char('a') if key.modifiers.contains(KeyModifiers::CONTROL) => app.toggle_auto_rotate(),
+                        KeyCode::Char('a') | KeyCode::Char('A') => app.cycle_difficulty(),
 
-                            // Weapon selection
-                            KeyCode::Char('1') => app.select_weapon(WeaponType::Nuke),
-                            KeyCode::Char('2') => app.select_weapon(WeaponType::Bio),
-                            KeyCode::Char('3') => app.select_weapon(WeaponType::Emp),
-                            KeyCode::Char('4') => app.select_weapon(WeaponType::Chem),
-
-                            // Launch weapon at cursor
-                            KeyCode::Char(' ') => {
-                                if let Some((col, row)) = app.mouse_pos {
-                                    app.launch_nuke(col, row);
-                                }
+                        // Flight routes / cycle launch site (nearest → each silo)
+                        KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => app.toggle_flights(),
+                        KeyCode::Char('o') | KeyCode::Char('O') => app.cycle_launch_site(),
+
+                        // Pause / slow down / speed up the simulation
+                        KeyCode::Char(' ') => app.toggle_pause(),
+                        KeyCode::Char('<') | KeyCode::Char(',') => app.change_sim_rate(false),
+                        KeyCode::Char('>') | KeyCode::Char('.') => app.change_sim_rate(true),
+
+                        // Pandemic control panel: R0 and lethality
+                        KeyCode::Char('(') => app.adjust_r0(false),
+                        KeyCode::Char(')') => app.adjust_r0(true),
+                        KeyCode::Char(';') => app.adjust_lethality(false),
+                        KeyCode::Char('\'') => app.adjust_lethality(true),
+
+                        // Launch weapon at cursor
+                        KeyCode::Char('f') => {
+                            if let Some((col, row)) = app.mouse_pos {
+                                app.click_target(col, row);
                             }
+                        }
 
-                            // Reset view
-                            KeyCode::Char('r') | KeyCode::Char('0') => {
-                                let size = terminal.size()?;
-                                app = App::new(size.width as usize, size.height as usize);
-                                let _ = data::load_all_geojson(&mut app.map_renderer, data_dir);
-                                if !app.map_renderer.has_data() {
-                                    data::generate_simple_world(&mut app.map_renderer);
-                                }
-                                app.map_renderer.build_land_grid();
-                                app.map_renderer.build_spatial_indexes();
+                        // Strike queue: mark the cursor's target / launch the package
+                        KeyCode::Char('Q') => {
+                            if let Some((col, row)) = app.mouse_pos {
+                                app.queue_target(col, row);
                             }
+                        }
+                        KeyCode::Enter => app.launch_queue(),
+
+                        // Simulation clock speed (⏸ → 1x → 60x → 3600x)
+                        KeyCode::Char('t') | KeyCode::Char('T') => app.clock.cycle_speed(),
+
+                        // Day/night shading
+                        KeyCode::Char('n') | KeyCode::Char('N') => {
+                            app.show_night = !app.show_night;
+                        }
+
+                        // Faction border tint / sidebar
+                        KeyCode::Char('F') => {
+                            app.map_renderer.settings.show_factions = !app.map_renderer.settings.show_factions;
+                        }
+                        KeyCode::Tab => app.toggle_view(),
+                        KeyCode::BackTab => app.show_sidebar = !app.show_sidebar,
+                        KeyCode::Char('J') => app.toggle_event_log(),
+                        KeyCode::Char('K') => app.take_screenshot(),
+                        KeyCode::PageUp if app.show_event_log => app.events.scroll_by(5),
+                        KeyCode::PageDown if app.show_event_log => app.events.scroll_by(-5),
 
-                            _ => {}
+                        // Weather overlay
+                        KeyCode::Char('w') | KeyCode::Char('W') => {
+                            app.show_weather = !app.show_weather;
                         }
+
+                        // Search for a city or place
+                        KeyCode::Char('/') => app.begin_search(),
+                        KeyCode::Char(':') => app.begin_command(),
+
+                        // Play a recorded macro
+                        KeyCode::Char('@') => app.begin_macro_play(),
+
+                        // End the session and show the summary
+                        KeyCode::Char('x') | KeyCode::Char('X') => app.end_game(Outcome::Ended),
+
+                        // Reset view
+                        KeyCode::Char('r') | KeyCode::Char('0') => {
+                            let size = terminal.size()?;
+                            app = reset_app(&mut app, size.width as usize, size.height as usize, data_dir);
+                        }
+
+                        _ => {}
                     }
                 }
-                Event::Mouse(mouse) => {
+                Event::Mouse(mouse) if !app.game.is_over() => {
                     handle_mouse(&mut app, mouse);
                 }
+                // Pasted text goes into whichever line is open, minus newlines
+                Event::Paste(text) => {
+                    let text = text.chars().filter(|c| !c.is_control());
+                    if let Some(query) = app.search.as_mut() {
+                        query.extend(text);
+                    } else if app.command.is_open() {
+                        text.for_each(|c| app.command.push(c));
+                    }
+                }
                 Event::Resize(width, height) => {
                     app.resize(width as usize, height as usize);
                 }
@@ -172,13 +799,21 @@ fn run(terminal: &mut DefaultTerminal) -> Result<()> {
             }
         }
 
-        // Update explosion animations
-        app.update_explosions();
+        // Advance the simulation by the wall time since the last frame
+        let now = Instant::now();
+        let elapsed = now.duration_since(last_frame);
+        app.update(elapsed.as_secs_f64());
+        app.perf.record(Phase::Sim, now.elapsed());
+        app.perf.end_frame(elapsed);
+        last_frame = now;
 
         if app.should_quit {
             break;
         }
     }
 
+    if let Some(sink) = capture.as_mut() {
+        sink.finish()?;
+    }
     Ok(())
 }
@@ -0,0 +1,181 @@
+use super::renderer::Polygon;
+use crate::geo::{normalize_lat, normalize_lon, wrap_lon};
+
+/// Country ownership raster at 0.5° (720×360). Each cell holds an index into
+/// `codes` (0 = unclaimed). Built from admin-0 polygons when available,
+/// otherwise approximated from city locations (nearest city's country).
+pub struct CountryGrid {
+    cells: Vec<u16>,
+    /// ISO alpha-3 codes; index 0 is the empty "unclaimed" entry
+    codes: Vec<String>,
+}
+
+impl CountryGrid {
+    const RESOLUTION: f64 = 0.5;
+    const WIDTH: usize = 720;
+    const HEIGHT: usize = 360;
+    /// City-seeded grids only claim cells this close to a city
+    const MAX_SEED_DEGREES: f64 = 6.0;
+
+    /// Bytes the cells and codes take up
+    pub fn heap_bytes(&self) -> usize {
+        self.cells.capacity() * 2 + self.codes.iter().map(|c| std::mem::size_of::<String>() + c.capacity()).sum::<usize>()
+    }
+
+    fn empty() -> Self {
+        Self {
+            cells: vec![0; Self::WIDTH * Self::HEIGHT],
+            codes: vec![String::new()],
+        }
+    }
+
+    fn code_index(&mut self, code: &str) -> u16 {
+        match self.codes.iter().position(|c| c == code) {
+            Some(i) => i as u16,
+            None => {
+                self.codes.push(code.to_string());
+                (self.codes.len() - 1) as u16
+            }
+        }
+    }
+
+    /// Scanline-rasterize country polygons (even-odd rule per polygon, like
+    /// `LandGrid::build_scanline`). Later polygons win where they overlap.
+    pub fn from_polygons(countries: &[(String, Polygon)]) -> Self {
+        let mut grid = Self::empty();
+        let mut crossings = Vec::new();
+        for (code, polygon) in countries {
+            let idx = grid.code_index(code);
+            let (_, min_lat, _, max_lat) = polygon.bbox;
+            let row_start = (((min_lat + 90.0) / Self::RESOLUTION).floor().max(0.0) as usize).saturating_sub(1);
+            let row_end = (((max_lat + 90.0) / Self::RESOLUTION).ceil() as usize + 1).min(Self::HEIGHT);
+
+            for row in row_start..row_end {
+                let lat = -90.0 + (row as f64 + 0.5) * Self::RESOLUTION;
+                crossings.clear();
+                for ring in &polygon.rings {
+                    let n = ring.len();
+                    for i in 0..n {
+                        let (x1, y1) = ring[i];
+                        let (x2, y2) = ring[(i + 1) % n];
+                        if (y1 <= lat && y2 > lat) || (y2 <= lat && y1 > lat) {
+                            crossings.push(x1 + (lat - y1) / (y2 - y1) * (x2 - x1));
+                        }
+                    }
+                }
+                crossings.sort_unstable_by(|a, b| a.total_cmp(b));
+                for pair in crossings.chunks_exact(2) {
+                    // Rings drawn past ±180 wrap around to the other edge
+                    let col_start = ((pair[0] + 180.0) / Self::RESOLUTION - 0.5).ceil() as i64;
+                    let col_end = ((pair[1] + 180.0) / Self::RESOLUTION - 0.5).floor() as i64 + 1;
+                    let col_end = col_end.min(col_start + Self::WIDTH as i64);
+                    for col in col_start..col_end {
+                        grid.cells[row * Self::WIDTH + col.rem_euclid(Self::WIDTH as i64) as usize] = idx;
+                    }
+                }
+            }
+        }
+        grid
+    }
+
+    /// Approximate borders as a Voronoi diagram of city locations, restricted
+    /// to cells `is_land` accepts and within `MAX_SEED_DEGREES` of a city.
+    pub fn from_cities<'a>(
+        cities: impl Iterator<Item = (f64, f64, &'a str)>,
+        is_land: impl Fn(f64, f64) -> bool,
+    ) -> Self {
+        const BIN_DEG: f64 = 2.0;
+        const BINS_X: usize = (360.0 / BIN_DEG) as usize;
+        const BINS_Y: usize = (180.0 / BIN_DEG) as usize;
+        let bin_of = |lon: f64, lat: f64| {
+            let bx = (((lon + 180.0) / BIN_DEG) as usize).min(BINS_X - 1);
+            let by = (((lat + 90.0) / BIN_DEG) as usize).min(BINS_Y - 1);
+            (bx, by)
+        };
+
+        let mut grid = Self::empty();
+        let mut bins: Vec<Vec<(f64, f64, u16)>> = vec![Vec::new(); BINS_X * BINS_Y];
+        for (lon, lat, code) in cities {
+            if code.is_empty() {
+                continue;
+            }
+            let idx = grid.code_index(code);
+            let (bx, by) = bin_of(lon, lat);
+            bins[by * BINS_X + bx].push((lon, lat, idx));
+        }
+
+        let reach = (Self::MAX_SEED_DEGREES / BIN_DEG).ceil() as i64;
+        let max_d2 = Self::MAX_SEED_DEGREES * Self::MAX_SEED_DEGREES;
+        for row in 0..Self::HEIGHT {
+            let lat = -90.0 + (row as f64 + 0.5) * Self::RESOLUTION;
+            let cos_lat = lat.to_radians().cos().max(0.1);
+            for col in 0..Self::WIDTH {
+                let lon = -180.0 + (col as f64 + 0.5) * Self::RESOLUTION;
+                if !is_land(lon, lat) {
+                    continue;
+                }
+                let (bx, by) = bin_of(lon, lat);
+                let mut best = (max_d2, 0u16);
+                for dy in -reach..=reach {
+                    let y = by as i64 + dy;
+                    if !(0..BINS_Y as i64).contains(&y) {
+                        continue;
+                    }
+                    for dx in -reach..=reach {
+                        let x = (bx as i64 + dx).rem_euclid(BINS_X as i64);
+                        for &(clon, clat, idx) in &bins[y as usize * BINS_X + x as usize] {
+                            let dlon = wrap_lon(clon - lon) * cos_lat;
+                            let d2 = dlon * dlon + (clat - lat) * (clat - lat);
+                            if d2 < best.0 {
+                                best = (d2, idx);
+                            }
+                        }
+                    }
+                }
+                grid.cells[row * Self::WIDTH + col] = best.1;
+            }
+        }
+        grid
+    }
+
+    /// Country code at a point, if claimed
+    pub fn country_at(&self, lon: f64, lat: f64) -> Option<&str> {
+        let col = (normalize_lon(lon) / Self::RESOLUTION) as usize;
+        let row = (normalize_lat(lat) / Self::RESOLUTION) as usize;
+        let idx = *self.cells.get(row.min(Self::HEIGHT - 1) * Self::WIDTH + col.min(Self::WIDTH - 1))?;
+        (idx != 0).then(|| self.codes[idx as usize].as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rasterizes_country_polygons() {
+        let square = Polygon::new(vec![vec![(10.0, 10.0), (20.0, 10.0), (20.0, 20.0), (10.0, 20.0), (10.0, 10.0)]]);
+        let grid = CountryGrid::from_polygons(&[("AAA".to_string(), square)]);
+        assert_eq!(grid.country_at(15.0, 15.0), Some("AAA"));
+        assert_eq!(grid.country_at(25.0, 15.0), None);
+        assert_eq!(grid.country_at(15.0, -15.0), None);
+
+        // Chukotka-style rings run on past -180 and wrap to the east edge
+        let across = Polygon::new(vec![vec![(-190.0, 60.0), (-170.0, 60.0), (-170.0, 70.0), (-190.0, 70.0), (-190.0, 60.0)]]);
+        let grid = CountryGrid::from_polygons(&[("RUS".to_string(), across)]);
+        assert_eq!(grid.country_at(-175.0, 65.0), Some("RUS"));
+        assert_eq!(grid.country_at(175.0, 65.0), Some("RUS"));
+        assert_eq!(grid.country_at(165.0, 65.0), None);
+    }
+
+    #[test]
+    fn city_seeded_grid_picks_nearest_city() {
+        let cities = [(0.0, 0.0, "AAA"), (4.0, 0.0, "BBB"), (179.5, 0.0, "CCC")];
+        let grid = CountryGrid::from_cities(cities.into_iter(), |_, _| true);
+        assert_eq!(grid.country_at(1.0, 0.5), Some("AAA"));
+        assert_eq!(grid.country_at(3.2, -0.5), Some("BBB"));
+        // Seeds reach across the dateline
+        assert_eq!(grid.country_at(-179.5, 0.0), Some("CCC"));
+        // Too far from any city
+        assert_eq!(grid.country_at(60.0, 40.0), None);
+    }
+}
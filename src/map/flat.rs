@@ -0,0 +1,522 @@
+//! Flat world maps besides Mercator: equirectangular, Robinson, azimuthal
+//! equidistant and polar stereographic.
+//!
+//! Each is a function from (lon, lat) to plane coordinates in radians of
+//! arc, worked out relative to the view center. The cylindrical ones recenter
+//! longitude on the view, so the whole world appears exactly once and lines
+//! break where they cross the far meridian instead of being drawn in
+//! wrapped copies. The azimuthal map is centered on the view point:
+//! distances and bearings from it are true, and the antipode is the rim.
+//! The polar map is centered on whichever pole is in the view's hemisphere,
+//! with the prime meridian running down from the North Pole (up from the
+//! South), and panning slides the plane rather than turning it.
+
+use crate::geo::wrap_lon;
+use crate::map::projection::{clip_segment, segment_on_screen, MapProjection};
+use crate::map::renderer::LineString;
+use std::f64::consts::{PI, TAU};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlatKind {
+    Equirectangular,
+    Robinson,
+    AzimuthalEquidistant,
+    Polar,
+}
+
+/// Latitude past the equator, on the far side from the pole, where the
+/// polar map ends. Stereographic scale blows up toward the other pole.
+const POLAR_LIMIT: f64 = 30.0;
+
+/// The polar map's pole: the south one when the view is centered south of
+/// the equator
+fn polar_south(center_lat: f64) -> bool {
+    center_lat < 0.0
+}
+
+/// Stereographic distance from the pole (radians of plane) for a latitude
+/// measured toward it
+fn polar_rho(lat: f64) -> f64 {
+    2.0 * (PI / 4.0 - lat.to_radians() / 2.0).tan()
+}
+
+/// Pole-centered plane coordinates of a point
+fn polar_plane(lon: f64, lat: f64, south: bool) -> (f64, f64) {
+    let (sin_lon, cos_lon) = lon.to_radians().sin_cos();
+    if south {
+        let rho = polar_rho(-lat);
+        (rho * sin_lon, rho * cos_lon)
+    } else {
+        let rho = polar_rho(lat);
+        (rho * sin_lon, -rho * cos_lon)
+    }
+}
+
+/// Map scale of the polar map at a latitude, relative to the pole
+fn polar_scale(lat: f64, south: bool) -> f64 {
+    let toward = if south { -lat } else { lat };
+    2.0 / (1.0 + toward.to_radians().sin())
+}
+
+/// Robinson's table at 5° steps: parallel length and distance from the equator
+const ROBINSON_X: [f64; 19] = [
+    1.0000, 0.9986, 0.9954, 0.9900, 0.9822, 0.9730, 0.9600, 0.9427, 0.9216, 0.8962,
+    0.8679, 0.8350, 0.7986, 0.7597, 0.7186, 0.6732, 0.6213, 0.5722, 0.5322,
+];
+const ROBINSON_Y: [f64; 19] = [
+    0.0000, 0.0620, 0.1240, 0.1860, 0.2480, 0.3100, 0.3720, 0.4340, 0.4958, 0.5571,
+    0.6176, 0.6769, 0.7346, 0.7903, 0.8435, 0.8936, 0.9394, 0.9761, 1.0000,
+];
+const ROBINSON_XSCALE: f64 = 0.8487;
+const ROBINSON_YSCALE: f64 = 1.3523;
+
+/// Robinson (X, Y) for a latitude, linearly interpolated; Y keeps the sign
+fn robinson_table(lat: f64) -> (f64, f64) {
+    let t = (lat.abs().min(90.0) / 5.0).min(17.999_999);
+    let (i, f) = (t as usize, t.fract());
+    let x = ROBINSON_X[i] + (ROBINSON_X[i + 1] - ROBINSON_X[i]) * f;
+    let y = ROBINSON_Y[i] + (ROBINSON_Y[i + 1] - ROBINSON_Y[i]) * f;
+    (x, y.copysign(lat))
+}
+
+/// Latitude whose Robinson Y is `y` (None past the poles)
+fn robinson_lat(y: f64) -> Option<f64> {
+    let target = y.abs();
+    if target > 1.0 {
+        return None;
+    }
+    let i = ROBINSON_Y.windows(2).position(|w| target <= w[1])?;
+    let f = (target - ROBINSON_Y[i]) / (ROBINSON_Y[i + 1] - ROBINSON_Y[i]);
+    Some(((i as f64 + f) * 5.0).copysign(y))
+}
+
+impl FlatKind {
+    pub const ALL: [FlatKind; 4] = [Self::Equirectangular, Self::Robinson, Self::AzimuthalEquidistant, Self::Polar];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Equirectangular => "Equirectangular",
+            Self::Robinson => "Robinson",
+            Self::AzimuthalEquidistant => "Azimuthal",
+            Self::Polar => "Polar",
+        }
+    }
+
+    /// Plane extent of the whole world as (width, height)
+    fn extent(self) -> (f64, f64) {
+        match self {
+            Self::Equirectangular => (TAU, PI),
+            Self::Robinson => (ROBINSON_XSCALE * TAU, 2.0 * ROBINSON_YSCALE),
+            Self::AzimuthalEquidistant => (TAU, TAU),
+            // Out to the equator, centered on the pole
+            Self::Polar => (2.0 * polar_rho(0.0), 2.0 * polar_rho(0.0)),
+        }
+    }
+
+    /// Plane coordinates (x east, y north) of a point, for a view centered
+    /// on `center`. None for the azimuthal map's antipode, and past
+    /// `POLAR_LIMIT` on the polar map.
+    pub fn forward(self, lon: f64, lat: f64, (center_lon, center_lat): (f64, f64)) -> Option<(f64, f64)> {
+        let dlon = wrap_lon(lon - center_lon).to_radians();
+        match self {
+            Self::Equirectangular => Some((dlon, (lat - center_lat).to_radians())),
+            Self::Robinson => {
+                let (x, y) = robinson_table(lat);
+                let (_, center_y) = robinson_table(center_lat);
+                Some((ROBINSON_XSCALE * x * dlon, ROBINSON_YSCALE * (y - center_y)))
+            }
+            Self::AzimuthalEquidistant => {
+                let (sin_lat, cos_lat) = lat.to_radians().sin_cos();
+                let (sin_c0, cos_c0) = center_lat.to_radians().sin_cos();
+                let cos_c = (sin_c0 * sin_lat + cos_c0 * cos_lat * dlon.cos()).clamp(-1.0, 1.0);
+                let c = cos_c.acos();
+                if c > PI - 1e-6 {
+                    return None;
+                }
+                let k = if c < 1e-9 { 1.0 } else { c / c.sin() };
+                Some((k * cos_lat * dlon.sin(), k * (cos_c0 * sin_lat - sin_c0 * cos_lat * dlon.cos())))
+            }
+            Self::Polar => {
+                let south = polar_south(center_lat);
+                if (if south { lat } else { -lat }) > POLAR_LIMIT {
+                    return None;
+                }
+                let (x, y) = polar_plane(lon, lat, south);
+                let (cx, cy) = polar_plane(center_lon, center_lat, south);
+                Some((x - cx, y - cy))
+            }
+        }
+    }
+
+    /// Inverse of `forward`; None off the edge of the map
+    pub fn inverse(self, x: f64, y: f64, (center_lon, center_lat): (f64, f64)) -> Option<(f64, f64)> {
+        match self {
+            Self::Equirectangular => {
+                let lat = center_lat + y.to_degrees();
+                (x.abs() <= PI && lat.abs() <= 90.0).then(|| (center_lon + x.to_degrees(), lat))
+            }
+            Self::Robinson => {
+                let (_, center_y) = robinson_table(center_lat);
+                let lat = robinson_lat(y / ROBINSON_YSCALE + center_y)?;
+                let dlon = x / (ROBINSON_XSCALE * robinson_table(lat).0);
+                (dlon.abs() <= PI).then(|| (center_lon + dlon.to_degrees(), lat))
+            }
+            Self::AzimuthalEquidistant => {
+                let c = x.hypot(y);
+                if c > PI {
+                    return None;
+                }
+                if c < 1e-12 {
+                    return Some((center_lon, center_lat));
+                }
+                let (sin_c, cos_c) = c.sin_cos();
+                let (sin_c0, cos_c0) = center_lat.to_radians().sin_cos();
+                let lat = (cos_c * sin_c0 + y * sin_c * cos_c0 / c).clamp(-1.0, 1.0).asin();
+                let dlon = (x * sin_c).atan2(c * cos_c0 * cos_c - y * sin_c0 * sin_c);
+                Some((wrap_lon(center_lon + dlon.to_degrees()), lat.to_degrees()))
+            }
+            Self::Polar => {
+                let south = polar_south(center_lat);
+                let (cx, cy) = polar_plane(center_lon, center_lat, south);
+                let (x, y) = (x + cx, y + cy);
+                let rho = x.hypot(y);
+                if rho > polar_rho(-POLAR_LIMIT) {
+                    return None;
+                }
+                let toward = 90.0 - 2.0 * (rho / 2.0).atan().to_degrees();
+                if south {
+                    Some((x.atan2(y).to_degrees(), -toward))
+                } else {
+                    Some((x.atan2(-y).to_degrees(), toward))
+                }
+            }
+        }
+    }
+
+    /// The edge of the world in plane coordinates, as a closed outline
+    fn outline(self, center: (f64, f64)) -> Vec<(f64, f64)> {
+        match self {
+            Self::AzimuthalEquidistant => (0..=360).map(|i| {
+                let a = (i as f64).to_radians();
+                (PI * a.cos(), PI * a.sin())
+            }).collect(),
+            Self::Polar => {
+                let south = polar_south(center.1);
+                let limit = if south { POLAR_LIMIT } else { -POLAR_LIMIT };
+                (0..=360).filter_map(|lon| self.forward(lon as f64 - 180.0, limit, center)).collect()
+            }
+            _ => {
+                // Up the east edge, across the north pole line, down the west
+                let east = center.0 + 179.999;
+                let west = center.0 - 179.999;
+                let up = (-90..=90).map(|lat| (east, lat as f64));
+                let down = (-90..=90).rev().map(|lat| (west, lat as f64));
+                up.chain(down).chain([(east, -90.0)])
+                    .filter_map(|(lon, lat)| self.forward(lon, lat, center))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Viewport onto one of the flat maps
+#[derive(Clone)]
+pub struct FlatViewport {
+    pub kind: FlatKind,
+    pub center_lon: f64,
+    pub center_lat: f64,
+    /// 1 = the whole world fits
+    pub zoom: f64,
+    pub width: usize,
+    pub height: usize,
+    /// Pixels per radian of plane
+    scale: f64,
+    half_w: f64,
+    half_h: f64,
+}
+
+impl FlatViewport {
+    pub fn new(kind: FlatKind, center_lon: f64, center_lat: f64, zoom: f64, width: usize, height: usize) -> Self {
+        let mut vp = Self { kind, center_lon, center_lat: center_lat.clamp(-90.0, 90.0), zoom, width, height, scale: 0.0, half_w: 0.0, half_h: 0.0 };
+        vp.recompute_derived();
+        vp
+    }
+
+    fn recompute_derived(&mut self) {
+        let (w, h) = self.kind.extent();
+        self.scale = self.zoom * (self.width as f64 / w).min(self.height as f64 / h);
+        self.half_w = self.width as f64 / 2.0;
+        self.half_h = self.height as f64 / 2.0;
+    }
+
+    fn to_pixels(&self, (x, y): (f64, f64)) -> (i32, i32) {
+        ((self.half_w + x * self.scale) as i32, (self.half_h - y * self.scale) as i32)
+    }
+
+    /// Whether two consecutive projected vertices straddle the edge of the
+    /// map (the far meridian, or the azimuthal rim) rather than being joined
+    /// by a line across it
+    pub fn is_seam(&self, a: (i32, i32), b: (i32, i32)) -> bool {
+        let (dx, dy) = ((a.0 - b.0) as f64, (a.1 - b.1) as f64);
+        dx.hypot(dy) > self.scale
+    }
+
+    pub fn zoom_at(&mut self, px: i32, py: i32, factor: f64) {
+        let target = self.unproject(px, py);
+        let zoom = (self.zoom * factor).clamp(1.0, 100.0);
+        if zoom == self.zoom {
+            return;
+        }
+        self.zoom = zoom;
+        self.recompute_derived();
+        // Pan until the point under the cursor is back under it. Panning is
+        // only linear near the center, so close in over a few passes.
+        let Some((lon, lat)) = target else { return };
+        for _ in 0..3 {
+            let Some((nx, ny)) = self.project(lon, lat) else { return };
+            self.pan(nx - px, ny - py);
+        }
+    }
+}
+
+impl MapProjection for FlatViewport {
+    fn name(&self) -> &'static str {
+        self.kind.name()
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn set_size(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.recompute_derived();
+    }
+
+    fn project(&self, lon: f64, lat: f64) -> Option<(i32, i32)> {
+        self.kind.forward(lon, lat, self.center()).map(|p| self.to_pixels(p))
+    }
+
+    fn unproject(&self, px: i32, py: i32) -> Option<(f64, f64)> {
+        let x = (px as f64 - self.half_w) / self.scale;
+        let y = (self.half_h - py as f64) / self.scale;
+        self.kind.inverse(x, y, self.center())
+    }
+
+    fn deg_to_pixels(&self, degrees: f64) -> f64 {
+        let local = match self.kind {
+            FlatKind::Polar => polar_scale(self.center_lat, polar_south(self.center_lat)),
+            _ => 1.0,
+        };
+        degrees.to_radians() * self.scale * local
+    }
+
+    fn scale_at(&self, _lon: f64, lat: f64) -> f64 {
+        match self.kind {
+            FlatKind::Polar => {
+                let south = polar_south(self.center_lat);
+                polar_scale(lat, south) / polar_scale(self.center_lat, south)
+            }
+            _ => 1.0,
+        }
+    }
+
+    fn effective_zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    fn center(&self) -> (f64, f64) {
+        (self.center_lon, self.center_lat)
+    }
+
+    fn visible_bounds(&self) -> (f64, f64, f64, f64) {
+        // How far the screen reaches from the center, in radians of plane
+        let (reach_x, reach_y) = (self.half_w / self.scale, self.half_h / self.scale);
+        let (min_lat, max_lat, reach_lon) = match self.kind {
+            FlatKind::Equirectangular => {
+                let reach_lat = reach_y.to_degrees();
+                (self.center_lat - reach_lat, self.center_lat + reach_lat, reach_x.to_degrees())
+            }
+            FlatKind::Robinson => {
+                let (_, center_y) = robinson_table(self.center_lat);
+                let lat_at = |y: f64| robinson_lat(center_y + y / ROBINSON_YSCALE).unwrap_or(90.0_f64.copysign(y));
+                let (min_lat, max_lat) = (lat_at(-reach_y), lat_at(reach_y));
+                // Parallels shorten toward the poles, so the screen's width
+                // covers the most longitude at the visible latitude nearest one
+                let (shortest, _) = robinson_table(min_lat.abs().max(max_lat.abs()));
+                (min_lat, max_lat, (reach_x / (ROBINSON_XSCALE * shortest)).to_degrees())
+            }
+            FlatKind::AzimuthalEquidistant => {
+                let reach = reach_x.hypot(reach_y).to_degrees();
+                let (min_lat, max_lat) = (self.center_lat - reach, self.center_lat + reach);
+                let widest = min_lat.abs().max(max_lat.abs()).min(90.0);
+                (min_lat, max_lat, reach / widest.to_radians().cos().max(1e-3))
+            }
+            FlatKind::Polar => {
+                let south = polar_south(self.center_lat);
+                let reach = reach_x.hypot(reach_y);
+                let (cx, cy) = polar_plane(self.center_lon, self.center_lat, south);
+                let from_pole = cx.hypot(cy);
+                // Latitude (toward the pole) at a distance from it
+                let toward = |rho: f64| 90.0 - 2.0 * (rho / 2.0).atan().to_degrees();
+                let (near, far) = (toward((from_pole - reach).max(0.0)), toward(from_pole + reach).max(-POLAR_LIMIT));
+                // With the pole on screen every meridian is
+                let reach_lon = if reach >= from_pole { 180.0 } else { (reach / from_pole).asin().to_degrees() };
+                if south { (-near, -far, reach_lon) } else { (far, near, reach_lon) }
+            }
+        };
+        let (min_lat, max_lat) = (min_lat.max(-90.0), max_lat.min(90.0));
+        let reach_lon = reach_lon.min(180.0);
+        (self.center_lon - reach_lon, min_lat, self.center_lon + reach_lon, max_lat)
+    }
+
+    fn set_view(&mut self, lon: f64, lat: f64, zoom: f64) {
+        *self = FlatViewport::new(self.kind, lon, lat, zoom, self.width, self.height);
+    }
+
+    fn pan(&mut self, dx: i32, dy: i32) {
+        if self.kind == FlatKind::Polar {
+            // Slide the plane, which carries the center over the pole, but
+            // keep it in the pole's hemisphere
+            let south = polar_south(self.center_lat);
+            let shifted = self.kind.inverse(dx as f64 / self.scale, -dy as f64 / self.scale, self.center());
+            if let Some((lon, lat)) = shifted {
+                self.center_lon = lon;
+                self.center_lat = if south { lat.min(-1e-6) } else { lat.max(0.0) };
+            }
+            return;
+        }
+        self.center_lon = wrap_lon(self.center_lon + (dx as f64 / self.scale).to_degrees());
+        self.center_lat = (self.center_lat - (dy as f64 / self.scale).to_degrees()).clamp(-90.0, 90.0);
+    }
+
+    fn zoom_in(&mut self) {
+        self.zoom = (self.zoom * 1.5).min(100.0);
+        self.recompute_derived();
+    }
+
+    fn zoom_out(&mut self) {
+        self.zoom = (self.zoom / 1.5).max(1.0);
+        self.recompute_derived();
+    }
+
+    fn zoom_in_at(&mut self, px: i32, py: i32) {
+        self.zoom_at(px, py, 1.5);
+    }
+
+    fn zoom_out_at(&mut self, px: i32, py: i32) {
+        self.zoom_at(px, py, 1.0 / 1.5);
+    }
+
+    fn zoom_at(&mut self, px: i32, py: i32, factor: f64) {
+        FlatViewport::zoom_at(self, px, py, factor);
+    }
+
+    fn segments(&self, line: &LineString, emit: &mut dyn FnMut((i32, i32), (i32, i32))) {
+        let mut prev: Option<(i32, i32)> = None;
+        for v in line.vecs() {
+            let cur = self.project_vec3(v);
+            if let (Some(a), Some(b)) = (prev, cur) {
+                if !self.is_seam(a, b) {
+                    if let Some((a, b)) = clip_segment(self.width, self.height, a, b) {
+                        emit(a, b);
+                    }
+                }
+            }
+            prev = cur;
+        }
+    }
+
+    /// The outline, dotted every other pixel
+    fn edge(&self) -> Vec<(i32, i32)> {
+        let outline: Vec<_> = self.kind.outline(self.center()).into_iter().map(|p| self.to_pixels(p)).collect();
+        let mut dots = Vec::new();
+        for pair in outline.windows(2) {
+            let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+            if !segment_on_screen(self.width, self.height, pair[0], pair[1]) {
+                continue;
+            }
+            let steps = ((x1 - x0).abs().max((y1 - y0).abs()) / 2).max(1);
+            dots.extend((0..steps).map(|i| (x0 + (x1 - x0) * i / steps, y0 + (y1 - y0) * i / steps)));
+        }
+        dots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geo::great_circle_km;
+
+    #[test]
+    fn forward_and_inverse_round_trip() {
+        let center = (100.0, 35.0);
+        for kind in FlatKind::ALL {
+            for &(lon, lat) in &[(100.0, 35.0), (-170.0, 10.0), (20.0, -60.0), (179.0, 80.0), (-75.0, -20.0)] {
+                let Some((x, y)) = kind.forward(lon, lat, center) else {
+                    // Past the polar map's edge
+                    assert!(kind == FlatKind::Polar && lat < -POLAR_LIMIT, "{kind:?} {lon},{lat}");
+                    continue;
+                };
+                let (back_lon, back_lat) = kind.inverse(x, y, center).unwrap();
+                assert!(wrap_lon(back_lon - lon).abs() < 1e-6 && (back_lat - lat).abs() < 1e-6, "{kind:?} {lon},{lat} → {back_lon},{back_lat}");
+            }
+        }
+    }
+
+    #[test]
+    fn azimuthal_distances_from_the_center_are_true() {
+        let center = (-74.0, 40.7);
+        for &(lon, lat) in &[(2.35, 48.85), (139.7, 35.7), (-58.4, -34.6)] {
+            let (x, y) = FlatKind::AzimuthalEquidistant.forward(lon, lat, center).unwrap();
+            let km = x.hypot(y) * 6371.0;
+            let expected = great_circle_km(center.0, center.1, lon, lat);
+            assert!((km - expected).abs() / expected < 0.01, "{km} vs {expected}");
+        }
+        assert!(FlatKind::AzimuthalEquidistant.forward(106.0, -40.7, center).is_none());
+    }
+
+    #[test]
+    fn cylindrical_maps_break_at_the_far_meridian() {
+        let vp = FlatViewport::new(FlatKind::Robinson, 0.0, 0.0, 1.0, 400, 200);
+        let west = vp.project(-179.9, 0.0).unwrap();
+        let east = vp.project(179.9, 0.0).unwrap();
+        assert!(vp.is_seam(west, east));
+        let (a, b) = (vp.project(10.0, 0.0).unwrap(), vp.project(12.0, 0.0).unwrap());
+        assert!(!vp.is_seam(a, b));
+
+        // The whole world fits at zoom 1
+        for (lon, lat) in [(-179.9, 0.0), (179.9, 0.0), (0.0, 90.0), (0.0, -90.0)] {
+            let (px, py) = vp.project(lon, lat).unwrap();
+            assert!((0..=400).contains(&px) && (0..=200).contains(&py), "{lon},{lat} → {px},{py}");
+        }
+    }
+
+    #[test]
+    fn polar_map_centers_on_the_pole_in_view() {
+        // Over the Arctic, north is toward the pole and east to the right
+        let vp = FlatViewport::new(FlatKind::Polar, 0.0, 70.0, 1.0, 400, 400);
+        let center = vp.project(0.0, 70.0).unwrap();
+        let (pole, north, east) = (vp.project(0.0, 90.0).unwrap(), vp.project(0.0, 75.0).unwrap(), vp.project(5.0, 70.0).unwrap());
+        assert_eq!(center, (200, 200));
+        assert!(north.1 < center.1 && pole.1 < north.1 && east.0 > center.0);
+        // The far side of the pole is on the map, the far hemisphere isn't
+        assert!(vp.project(180.0, 70.0).is_some());
+        assert!(vp.project(0.0, -45.0).is_none());
+
+        // Panning across the pole slides on rather than stopping at it
+        let mut over = vp.clone();
+        over.pan(0, -(pole.1 - center.1).abs() * 2);
+        assert!((over.center_lat - 70.0).abs() < 1.5 && wrap_lon(over.center_lon - 180.0).abs() < 0.5, "{:?}", over.center());
+
+        // The Antarctic gets the south pole, and blasts are drawn larger
+        // away from it, as the map stretches
+        let south = FlatViewport::new(FlatKind::Polar, 0.0, -80.0, 4.0, 400, 400);
+        let pole = south.project(0.0, -90.0).unwrap();
+        assert!(pole.1 > 200, "{pole:?}");
+        assert!(south.scale_at(0.0, -60.0) > 1.0 && south.scale_at(0.0, -89.0) < 1.0);
+        let (min_lon, min_lat, max_lon, max_lat) = south.visible_bounds();
+        assert!((min_lon, max_lon) == (-180.0, 180.0) && min_lat == -90.0 && (-50.0..-40.0).contains(&max_lat), "{:?}", south.visible_bounds());
+    }
+}
@@ -0,0 +1,162 @@
+//! Label "typefaces" from Unicode's letter variants.
+//!
+//! A terminal has one font, but Unicode has bold, double-struck and
+//! small-capital copies of the Latin alphabet, which most terminal fonts
+//! draw. Capitals can be set in bold or small caps and country names in
+//! double-struck letters, so the kinds of label read apart at a glance.
+//! Only ASCII letters and digits have variants; anything else (accents,
+//! CJK) passes through as it is. Text consoles whose fonts stop at a few
+//! hundred glyphs get plain ASCII instead.
+
+/// How capital and country labels are lettered
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LabelFonts {
+    /// Plain letters everywhere
+    #[default]
+    Plain,
+    /// Bold capitals, double-struck countries
+    Bold,
+    /// Small-capital capitals, double-struck countries
+    SmallCaps,
+}
+
+impl LabelFonts {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" | "ascii" | "off" => Some(Self::Plain),
+            "bold" => Some(Self::Bold),
+            "smallcaps" | "small-caps" => Some(Self::SmallCaps),
+            _ => None,
+        }
+    }
+
+    /// These fonts, or plain ones on a terminal named by `TERM` that can't
+    /// draw the variants (the Linux and BSD consoles, serial terminals)
+    pub fn for_term(self, term: Option<&str>) -> Self {
+        let limited = term.is_some_and(|t| t == "dumb" || t == "ansi" || t.starts_with("linux") || t.starts_with("vt") || t.starts_with("cons"));
+        if limited {
+            Self::Plain
+        } else {
+            self
+        }
+    }
+
+    /// A capital city's name in these fonts
+    pub fn capital(self, name: &str) -> String {
+        match self {
+            Self::Plain => name.to_string(),
+            Self::Bold => Variant::Bold.apply(name),
+            Self::SmallCaps => Variant::SmallCaps.apply(name),
+        }
+    }
+
+    /// A country's name in these fonts
+    pub fn country(self, name: &str) -> String {
+        match self {
+            Self::Plain => name.to_string(),
+            Self::Bold | Self::SmallCaps => Variant::DoubleStruck.apply(name),
+        }
+    }
+}
+
+/// A set of letter variants
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// 𝐌𝐚𝐭𝐡𝐞𝐦𝐚𝐭𝐢𝐜𝐚𝐥 𝐛𝐨𝐥𝐝
+    Bold,
+    /// Sᴍᴀʟʟ ᴄᴀᴘɪᴛᴀʟs for the lower case
+    SmallCaps,
+    /// 𝔻𝕠𝕦𝕓𝕝𝕖-𝕤𝕥𝕣𝕦𝕔𝕜
+    DoubleStruck,
+}
+
+/// Small capitals for a–z; there's no small x, so it stays as it is
+const SMALL_CAPS: [char; 26] = [
+    'ᴀ', 'ʙ', 'ᴄ', 'ᴅ', 'ᴇ', 'ꜰ', 'ɢ', 'ʜ', 'ɪ', 'ᴊ', 'ᴋ', 'ʟ', 'ᴍ', 'ɴ', 'ᴏ', 'ᴘ', 'ꞯ', 'ʀ', 'ꜱ', 'ᴛ', 'ᴜ', 'ᴠ', 'ᴡ', 'x', 'ʏ', 'ᴢ',
+];
+
+/// Double-struck capitals that were encoded early, in Letterlike Symbols,
+/// and left as holes in the mathematical block
+const DOUBLE_STRUCK_HOLES: [(char, char); 7] = [('C', 'ℂ'), ('H', 'ℍ'), ('N', 'ℕ'), ('P', 'ℙ'), ('Q', 'ℚ'), ('R', 'ℝ'), ('Z', 'ℤ')];
+
+impl Variant {
+    /// `text` with every ASCII letter and digit swapped for its variant
+    pub fn apply(self, text: &str) -> String {
+        text.chars().map(|c| self.char(c)).collect()
+    }
+
+    fn char(self, c: char) -> char {
+        // First code points of the upper case, lower case and digits
+        let (upper, lower, digits) = match self {
+            Self::Bold => (0x1D400, 0x1D41A, 0x1D7CE),
+            Self::DoubleStruck => (0x1D538, 0x1D552, 0x1D7D8),
+            Self::SmallCaps => {
+                return if c.is_ascii_lowercase() { SMALL_CAPS[(c as u8 - b'a') as usize] } else { c };
+            }
+        };
+        if self == Self::DoubleStruck {
+            if let Some(&(_, hole)) = DOUBLE_STRUCK_HOLES.iter().find(|&&(from, _)| from == c) {
+                return hole;
+            }
+        }
+        let offset = |start: u32, first: u8| char::from_u32(start + (c as u8 - first) as u32).unwrap_or(c);
+        match c {
+            'A'..='Z' => offset(upper, b'A'),
+            'a'..='z' => offset(lower, b'a'),
+            '0'..='9' => offset(digits, b'0'),
+            _ => c,
+        }
+    }
+}
+
+/// `text` with any variant letters turned back into ASCII
+pub fn plain(text: &str) -> String {
+    text.chars().map(plain_char).collect()
+}
+
+fn plain_char(c: char) -> char {
+    if let Some(i) = SMALL_CAPS.iter().position(|&s| s == c) {
+        return (b'a' + i as u8) as char;
+    }
+    if let Some(&(from, _)) = DOUBLE_STRUCK_HOLES.iter().find(|&&(_, hole)| hole == c) {
+        return from;
+    }
+    let code = c as u32;
+    let from = |start: u32, len: u32, first: u8| (start..start + len).contains(&code).then(|| (first + (code - start) as u8) as char);
+    [(0x1D400, 0x1D538), (0x1D41A, 0x1D552)]
+        .into_iter()
+        .zip([b'A', b'a'])
+        .find_map(|((bold, double), first)| from(bold, 26, first).or_else(|| from(double, 26, first)))
+        .or_else(|| from(0x1D7CE, 10, b'0'))
+        .or_else(|| from(0x1D7D8, 10, b'0'))
+        .unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::labels::text_width;
+
+    #[test]
+    fn letters_swap_and_swap_back() {
+        assert_eq!(Variant::Bold.apply("Paris 2"), "𝐏𝐚𝐫𝐢𝐬 𝟐");
+        assert_eq!(Variant::SmallCaps.apply("Paris"), "Pᴀʀɪꜱ");
+        assert_eq!(Variant::DoubleStruck.apply("CHINA"), "ℂℍ𝕀ℕ𝔸");
+        assert_eq!(Variant::Bold.apply("Zürich 東京"), "𝐙ü𝐫𝐢𝐜𝐡 東京");
+        for variant in [Variant::Bold, Variant::SmallCaps, Variant::DoubleStruck] {
+            let styled = variant.apply("Mexico City 42");
+            assert_eq!(text_width(&styled), 14, "{styled}");
+            assert_eq!(plain(&styled), "Mexico City 42");
+        }
+    }
+
+    #[test]
+    fn consoles_fall_back_to_plain() {
+        assert_eq!(LabelFonts::parse("small-caps"), Some(LabelFonts::SmallCaps));
+        assert_eq!(LabelFonts::Bold.for_term(Some("xterm-256color")), LabelFonts::Bold);
+        assert_eq!(LabelFonts::Bold.for_term(Some("linux")), LabelFonts::Plain);
+        assert_eq!(LabelFonts::SmallCaps.for_term(Some("vt100")), LabelFonts::Plain);
+        assert_eq!(LabelFonts::Plain.country("FRANCE"), "FRANCE");
+        assert_eq!(LabelFonts::SmallCaps.country("FRANCE"), "𝔽ℝ𝔸ℕℂ𝔼");
+    }
+}
@@ -1,4 +1,4 @@
-use crate::braille::BrailleCanvas;
+use crate::braille::{BrailleCanvas, CoverageCanvas};
 
 /// Draw a line using Bresenham's algorithm
 pub fn draw_line(canvas: &mut BrailleCanvas, x0: i32, y0: i32, x1: i32, y1: i32) {
@@ -38,6 +38,152 @@ pub fn draw_line(canvas: &mut BrailleCanvas, x0: i32, y0: i32, x1: i32, y1: i32)
     }
 }
 
+/// Draw an anti-aliased line with Xiaolin Wu's algorithm. Each step along
+/// the major axis splits one pixel of ink between the two pixels straddling
+/// the true line, by how close each is to it.
+pub fn draw_line_wu(canvas: &mut CoverageCanvas, x0: i32, y0: i32, x1: i32, y1: i32) {
+    // Walk along x; steep lines are walked with the axes swapped
+    let steep = (y1 - y0).abs() > (x1 - x0).abs();
+    let (mut x0, mut y0, mut x1, mut y1) = if steep { (y0, x0, y1, x1) } else { (x0, y0, x1, y1) };
+    if x0 > x1 {
+        (x0, x1, y0, y1) = (x1, x0, y1, y0);
+    }
+    let gradient = if x1 == x0 { 0.0 } else { (y1 - y0) as f32 / (x1 - x0) as f32 };
+    let mut plot = |major: i32, minor: i32, ink: f32| {
+        if steep {
+            canvas.add(minor, major, ink);
+        } else {
+            canvas.add(major, minor, ink);
+        }
+    };
+
+    let mut y = y0 as f32;
+    for x in x0..=x1 {
+        let base = y.floor();
+        let frac = y - base;
+        plot(x, base as i32, 1.0 - frac);
+        if frac > 0.0 {
+            plot(x, base as i32 + 1, frac);
+        }
+        y += gradient;
+    }
+}
+
+/// Fill a polygon given as screen-space rings, first the exterior then its
+/// holes, by scanlines with the even-odd rule: a pixel is inside when a ray
+/// from it crosses the rings an odd number of times, so holes stay empty.
+/// Edges are picked up as the scan reaches them, so each row only looks at
+/// the edges crossing it; rows off the canvas aren't scanned.
+pub fn fill_polygon(canvas: &mut BrailleCanvas, rings: &[Vec<(i32, i32)>]) {
+    let (width, height) = (canvas.char_width() as i32 * 2, canvas.char_height() as i32 * 4);
+
+    // Sampling rows at pixel centers, an edge from y1 down to y2 crosses
+    // rows y1..y2. Each as (first row, last row + 1, x there, x per row).
+    let mut edges: Vec<(i32, i32, f64, f64)> = Vec::new();
+    for ring in rings.iter().filter(|r| r.len() >= 3) {
+        for (i, &a) in ring.iter().enumerate() {
+            let b = ring[(i + 1) % ring.len()];
+            let ((x1, y1), (x2, y2)) = if a.1 <= b.1 { (a, b) } else { (b, a) };
+            if y1 == y2 || y2 <= 0 || y1 >= height {
+                continue;
+            }
+            let slope = (x2 - x1) as f64 / (y2 - y1) as f64;
+            let first = y1.max(0);
+            edges.push((first, y2.min(height), x1 as f64 + (first - y1) as f64 * slope, slope));
+        }
+    }
+    edges.sort_unstable_by_key(|e| e.0);
+
+    let mut active: Vec<(i32, f64, f64)> = Vec::new();
+    let mut crossings = Vec::new();
+    let mut next = 0;
+    let mut y = 0;
+    while y < height && (next < edges.len() || !active.is_empty()) {
+        if active.is_empty() {
+            y = edges[next].0;
+        }
+        while let Some(&(_, end, x, slope)) = edges.get(next).filter(|e| e.0 == y) {
+            active.push((end, x, slope));
+            next += 1;
+        }
+        active.retain(|e| e.0 > y);
+
+        crossings.clear();
+        crossings.extend(active.iter().map(|e| e.1));
+        crossings.sort_unstable_by(f64::total_cmp);
+        for pair in crossings.chunks_exact(2) {
+            let start = (pair[0].round() as i32).max(0);
+            let end = (pair[1].round() as i32).min(width);
+            for x in start..end {
+                canvas.set_pixel(x as usize, y as usize);
+            }
+        }
+
+        for edge in &mut active {
+            edge.1 += edge.2;
+        }
+        y += 1;
+    }
+}
+
+/// Area and centroid of a closed ring by the shoelace formula. The area is
+/// signed (positive counterclockwise); the centroid is the mean of the
+/// points for a degenerate ring.
+pub fn ring_centroid(ring: &[(f64, f64)]) -> (f64, (f64, f64)) {
+    let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+    for (i, &(x1, y1)) in ring.iter().enumerate() {
+        let (x2, y2) = ring[(i + 1) % ring.len()];
+        let cross = x1 * y2 - x2 * y1;
+        area += cross;
+        cx += (x1 + x2) * cross;
+        cy += (y1 + y2) * cross;
+    }
+    if area.abs() < 1e-12 {
+        let n = ring.len().max(1) as f64;
+        let (sx, sy) = ring.iter().fold((0.0, 0.0), |(sx, sy), &(x, y)| (sx + x, sy + y));
+        return (0.0, (sx / n, sy / n));
+    }
+    (area / 2.0, (cx / (3.0 * area), cy / (3.0 * area)))
+}
+
+/// Douglas-Peucker simplification: keep the endpoints, then recursively
+/// keep the point farthest from the chord between kept points until none
+/// strays more than `tolerance` (in the points' own units) from it.
+pub fn simplify_douglas_peucker(points: &[(f64, f64)], tolerance: f64) -> Vec<(f64, f64)> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        let (ax, ay) = points[first];
+        let (dx, dy) = (points[last].0 - ax, points[last].1 - ay);
+        let chord = (dx * dx + dy * dy).sqrt();
+        let (mut farthest, mut max_dist) = (first, 0.0);
+        for (i, &(px, py)) in points.iter().enumerate().take(last).skip(first + 1) {
+            // Distance to the chord, or to its start where it's a closed ring
+            let dist = if chord > 0.0 {
+                ((px - ax) * dy - (py - ay) * dx).abs() / chord
+            } else {
+                ((px - ax).powi(2) + (py - ay).powi(2)).sqrt()
+            };
+            if dist > max_dist {
+                (farthest, max_dist) = (i, dist);
+            }
+        }
+        if max_dist > tolerance {
+            keep[farthest] = true;
+            stack.push((first, farthest));
+            stack.push((farthest, last));
+        }
+    }
+
+    points.iter().zip(keep).filter(|(_, k)| *k).map(|(&p, _)| p).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -48,7 +194,70 @@ mod tests {
         draw_line(&mut canvas, 0, 0, 9, 0);
         // Should have pixels across the top
         let s = canvas.to_string();
-        assert!(s.contains('⠁') || s.contains('⠉') || s.len() > 0);
+        assert!(s.contains('⠁') || s.contains('⠉') || !s.is_empty());
+    }
+
+    #[test]
+    fn wu_lines_share_ink_between_straddling_pixels() {
+        // Axis-aligned: one full pixel per step, same dots as Bresenham
+        let mut coverage = CoverageCanvas::new(5, 1, 1);
+        draw_line_wu(&mut coverage, 0, 1, 9, 1);
+        let mut canvas = BrailleCanvas::new(5, 1);
+        draw_line(&mut canvas, 0, 1, 9, 1);
+        assert_eq!(coverage.resolve().to_string(), canvas.to_string());
+
+        // A shallow slope lights some dots above and below the true line,
+        // around one per column on average
+        let mut coverage = CoverageCanvas::new(8, 2, 1);
+        draw_line_wu(&mut coverage, 0, 2, 15, 5);
+        let dots: u32 = (0..2).flat_map(|row| coverage.resolve().row_raw(row).to_vec()).map(u8::count_ones).sum();
+        assert!((12..=20).contains(&dots), "{dots}");
+    }
+
+    #[test]
+    fn douglas_peucker_drops_points_within_tolerance() {
+        let zigzag = [(0.0, 0.0), (1.0, 0.05), (2.0, -0.05), (3.0, 2.0), (4.0, 0.0), (5.0, 0.0)];
+        assert_eq!(
+            simplify_douglas_peucker(&zigzag, 0.1),
+            [(0.0, 0.0), (2.0, -0.05), (3.0, 2.0), (4.0, 0.0), (5.0, 0.0)]
+        );
+        assert_eq!(simplify_douglas_peucker(&zigzag, 5.0), [(0.0, 0.0), (5.0, 0.0)]);
+
+        // A closed ring keeps its far side
+        let ring = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)];
+        assert_eq!(simplify_douglas_peucker(&ring, 0.8).len(), 3);
+    }
+
+    #[test]
+    fn polygons_fill_around_their_holes() {
+        // 8×8 square with a 4×4 hole in the middle
+        let square = vec![(0, 0), (8, 0), (8, 8), (0, 8)];
+        let hole = vec![(2, 2), (6, 2), (6, 6), (2, 6)];
+        let mut canvas = BrailleCanvas::new(4, 2);
+        fill_polygon(&mut canvas, &[square.clone(), hole]);
+        let dots: u32 = (0..2).flat_map(|row| canvas.row_raw(row).to_vec()).map(u8::count_ones).sum();
+        assert_eq!(dots, 64 - 16);
+        assert_eq!(canvas.row_raw(0)[0], 0xFF);
+        assert_eq!(canvas.row_raw(0)[1], 0x1B);
+
+        // Partly off canvas: only the on-screen part is filled
+        let mut canvas = BrailleCanvas::new(2, 1);
+        fill_polygon(&mut canvas, &[square.iter().map(|&(x, y)| (x - 6, y - 6)).collect()]);
+        assert_eq!(canvas.row_raw(0), [0x1B, 0]);
+    }
+
+    #[test]
+    fn centroids_weigh_by_area() {
+        // An L of three unit squares: its centroid is off the corner toward the arms
+        let ell = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)];
+        let (area, (x, y)) = ring_centroid(&ell);
+        assert!((area - 3.0).abs() < 1e-9);
+        assert!((x - 5.0 / 6.0).abs() < 1e-9 && (y - 5.0 / 6.0).abs() < 1e-9);
+
+        // Clockwise, the area turns negative but the centroid stays put
+        let reversed: Vec<_> = ell.iter().rev().copied().collect();
+        let (area, (rx, ry)) = ring_centroid(&reversed);
+        assert!((area + 3.0).abs() < 1e-9 && (rx - x).abs() < 1e-9 && (ry - y).abs() < 1e-9);
     }
 
     #[test]
@@ -56,6 +265,6 @@ mod tests {
         let mut canvas = BrailleCanvas::new(1, 2);
         draw_line(&mut canvas, 0, 0, 0, 7);
         let s = canvas.to_string();
-        assert!(s.len() > 0);
+        assert!(!s.is_empty());
     }
 }
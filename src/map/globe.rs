@@ -1,6 +1,7 @@
 pub use glam::DVec3;
 
-use crate::map::projection::Viewport;
+use crate::map::projection::{clip_segment, densify_ring, MapProjection, ScreenRings, Viewport};
+use crate::map::renderer::{LineString, Polygon};
 
 /// Globe viewport using orthographic projection of a rotating sphere.
 /// Orientation stored as a rotation matrix (3 column vectors) for
@@ -127,10 +128,7 @@ impl GlobeViewport {
         let sz = (1.0 - r2).sqrt();
         let p = self.right * sx + self.up * sy + self.forward * sz;
 
-        let lat = p.z.clamp(-1.0, 1.0).asin().to_degrees();
-        let lon = p.y.atan2(p.x).to_degrees();
-
-        Some((lon, lat))
+        Some(vec3_to_lonlat(p))
     }
 
     /// Rotate the globe by a pixel drag delta.
@@ -189,7 +187,7 @@ impl GlobeViewport {
     }
 
     /// Zoom by factor towards a specific pixel, keeping the geographic point under cursor fixed.
-    fn zoom_at(&mut self, px: i32, py: i32, factor: f64) {
+    pub fn zoom_at(&mut self, px: i32, py: i32, factor: f64) {
         // Get geo coords under cursor before zoom
         let target = self.unproject(px, py);
 
@@ -398,6 +396,239 @@ impl GlobeViewport {
     }
 }
 
+impl MapProjection for GlobeViewport {
+    fn name(&self) -> &'static str {
+        "Globe"
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn set_size(&mut self, width: usize, height: usize) {
+        GlobeViewport::set_size(self, width, height);
+    }
+
+    fn project(&self, lon: f64, lat: f64) -> Option<(i32, i32)> {
+        GlobeViewport::project(self, lon, lat)
+    }
+
+    fn unproject(&self, px: i32, py: i32) -> Option<(f64, f64)> {
+        GlobeViewport::unproject(self, px, py)
+    }
+
+    fn deg_to_pixels(&self, degrees: f64) -> f64 {
+        GlobeViewport::deg_to_pixels(self, degrees)
+    }
+
+    fn effective_zoom(&self) -> f64 {
+        GlobeViewport::effective_zoom(self)
+    }
+
+    fn center(&self) -> (f64, f64) {
+        self.center_lonlat()
+    }
+
+    fn visible_bounds(&self) -> (f64, f64, f64, f64) {
+        GlobeViewport::visible_bounds(self)
+    }
+
+    fn set_view(&mut self, lon: f64, lat: f64, zoom: f64) {
+        *self = GlobeViewport::new(lon, lat, zoom * self.width as f64 * 0.35, self.width, self.height);
+    }
+
+    fn pan(&mut self, dx: i32, dy: i32) {
+        self.rotate_drag(dx, dy);
+    }
+
+    fn zoom_in(&mut self) {
+        GlobeViewport::zoom_in(self);
+    }
+
+    fn zoom_out(&mut self) {
+        GlobeViewport::zoom_out(self);
+    }
+
+    fn zoom_in_at(&mut self, px: i32, py: i32) {
+        GlobeViewport::zoom_in_at(self, px, py);
+    }
+
+    fn zoom_out_at(&mut self, px: i32, py: i32) {
+        GlobeViewport::zoom_out_at(self, px, py);
+    }
+
+    fn zoom_at(&mut self, px: i32, py: i32, factor: f64) {
+        GlobeViewport::zoom_at(self, px, py, factor);
+    }
+
+    fn project_vec3(&self, v: DVec3) -> Option<(i32, i32)> {
+        GlobeViewport::project_vec3(self, v)
+    }
+
+    fn surface_point(&self, px: i32, py: i32) -> Option<DVec3> {
+        self.pixel_to_sphere_point(px, py)
+    }
+
+    /// Great circle subdivision.
+    /// Three-phase conservative approximation (à la FloeDB H3 joins):
+    ///   Phase 1: Bounding sphere cull — single dot product (O(1) vs 8 trig ops),
+    ///            then the same per chunk against the part of the globe on screen
+    ///   Phase 2: Per-segment back-face skip — 2 dot products
+    ///   Phase 3: Slerp + project the Vec3s of the chunks left, decoded as they're read
+    fn segments(&self, line: &LineString, emit: &mut dyn FnMut((i32, i32), (i32, i32))) {
+        if line.len() < 2 {
+            return;
+        }
+
+        // Phase 1: O(1) hemisphere cull via precomputed bounding sphere
+        if line.center_vec.dot(self.forward) < line.cull_dot {
+            return;
+        }
+
+        let forward = self.forward;
+        let half_w = self.width as i32 / 2;
+        // Angular radius of the part of the globe on screen (orthographic:
+        // a point θ from the center is R·sin θ pixels out)
+        let half_diagonal = self.half_w.hypot(self.half_h);
+        let view = (half_diagonal / self.radius).min(1.0).asin();
+
+        for chunk in &line.chunks {
+            // Runs of the line whose bounding cap misses the view entirely
+            if chunk.radius < std::f64::consts::FRAC_PI_2
+                && chunk.center_vec.dot(forward) < (view + chunk.radius + 0.01).min(std::f64::consts::PI).cos()
+            {
+                continue;
+            }
+
+            let mut prev_screen: Option<(i32, i32)> = None;
+            let mut prev_vec: Option<DVec3> = None;
+
+            // Phase 3: iterate the chunk's unit-sphere vectors
+            for cur in line.chunk_vecs(chunk) {
+                if let Some(pv) = prev_vec {
+                    // Phase 2: skip segments entirely behind the globe
+                    if cur.dot(forward) < -0.1 && pv.dot(forward) < -0.1 {
+                        prev_screen = None;
+                        prev_vec = Some(cur);
+                        continue;
+                    }
+
+                    let dot = pv.dot(cur).clamp(-1.0, 1.0);
+
+                    // Fast path: dot > cos(2°) ≈ 0.9994 means angle < 2°, steps = 1.
+                    // Skips acos + sin entirely — handles ~95% of segments.
+                    if dot > 0.9994 {
+                        match self.project_vec3(cur) {
+                            Some((px, py)) => {
+                                if let Some((prev_x, prev_y)) = prev_screen {
+                                    let dist = (px - prev_x).abs() + (py - prev_y).abs();
+                                    if dist < half_w {
+                                        if let Some((a, b)) = clip_segment(self.width, self.height, (prev_x, prev_y), (px, py)) {
+                                            emit(a, b);
+                                        }
+                                    }
+                                }
+                                prev_screen = Some((px, py));
+                            }
+                            None => prev_screen = None,
+                        }
+                    } else {
+                        // Slow path: large arc — subdivide with slerp
+                        let angle = dot.acos();
+                        let steps = ((angle.to_degrees() / 2.0).ceil() as usize).max(1);
+                        let sin_angle = angle.sin();
+
+                        if sin_angle.abs() < 1e-10 {
+                            prev_screen = self.project_vec3(cur);
+                        } else {
+                            for i in 1..=steps {
+                                let t = i as f64 / steps as f64;
+                                let sa = ((1.0 - t) * angle).sin() / sin_angle;
+                                let sb = (t * angle).sin() / sin_angle;
+                                let p = pv * sa + cur * sb;
+
+                                match self.project_vec3(p) {
+                                    Some((px, py)) => {
+                                        if let Some((prev_x, prev_y)) = prev_screen {
+                                            let dist = (px - prev_x).abs() + (py - prev_y).abs();
+                                            if dist < half_w {
+                                                if let Some((a, b)) = clip_segment(self.width, self.height, (prev_x, prev_y), (px, py)) {
+                                                    emit(a, b);
+                                                }
+                                            }
+                                        }
+                                        prev_screen = Some((px, py));
+                                    }
+                                    None => prev_screen = None,
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    prev_screen = self.project_vec3(cur);
+                }
+
+                prev_vec = Some(cur);
+            }
+        }
+    }
+
+    /// Vertices on the far side are pulled onto the rim, so a polygon
+    /// running over the horizon fills up to it. Polygons with no vertex on
+    /// this side are skipped.
+    fn polygon_rings(&self, polygon: &Polygon, emit: &mut dyn FnMut(&ScreenRings)) {
+        let mut visible = false;
+        let mut project = |lon: f64, lat: f64| {
+            let p = lonlat_to_vec3(lon, lat);
+            let (mut sx, mut sy) = (p.dot(self.right), p.dot(self.up));
+            if p.dot(self.forward) < 0.0 {
+                let len = sx.hypot(sy);
+                (sx, sy) = if len > 1e-9 { (sx / len, sy / len) } else { (1.0, 0.0) };
+            } else {
+                visible = true;
+            }
+            ((self.half_w + sx * self.radius) as i32, (self.half_h - sy * self.radius) as i32)
+        };
+        let mut rings = Vec::with_capacity(polygon.rings.len());
+        for ring in &polygon.rings {
+            rings.push(densify_ring(ring).map(|(lon, lat)| project(lon, lat)).collect::<Vec<_>>());
+        }
+        if visible {
+            emit(&rings);
+        }
+    }
+
+    /// The sphere's rim — only when it's inside the viewport
+    fn edge(&self) -> Vec<(i32, i32)> {
+        if self.radius >= self.width.min(self.height) as f64 / 2.0 {
+            return Vec::new();
+        }
+        let r = self.radius;
+        let circumference = 2.0 * std::f64::consts::PI * r;
+        let steps = (circumference * 0.5) as usize; // every other pixel for faintness
+        (0..steps)
+            .map(|i| {
+                let theta = 2.0 * std::f64::consts::PI * i as f64 / steps as f64;
+                ((self.half_w + r * theta.cos()) as i32, (self.half_h - r * theta.sin()) as i32)
+            })
+            .collect()
+    }
+
+    fn is_globe(&self) -> bool {
+        true
+    }
+
+    fn spin(&mut self, radians: f64) {
+        self.apply_momentum(radians, 0.0);
+    }
+
+    /// Scale the sphere with the canvas, so it fits the same way at any size
+    fn rescale(&mut self, width: usize, height: usize) {
+        self.radius *= (width as f64 / self.width.max(1) as f64).min(height as f64 / self.height.max(1) as f64);
+        GlobeViewport::set_size(self, width, height);
+    }
+}
+
 /// Convert lon/lat (degrees) to a unit sphere vector.
 #[inline(always)]
 pub fn lonlat_to_vec3(lon: f64, lat: f64) -> DVec3 {
@@ -410,6 +641,14 @@ pub fn lonlat_to_vec3(lon: f64, lat: f64) -> DVec3 {
     )
 }
 
+/// Convert a unit sphere vector to lon/lat (degrees).
+#[inline(always)]
+pub fn vec3_to_lonlat(p: DVec3) -> (f64, f64) {
+    let lat = p.z.clamp(-1.0, 1.0).asin().to_degrees();
+    let lon = p.y.atan2(p.x).to_degrees();
+    (lon, lat)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
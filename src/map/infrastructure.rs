@@ -0,0 +1,80 @@
+//! Transport infrastructure: roads and railways (lines), airports and ports
+//! (points). Each can be knocked out by strikes.
+
+use crate::map::renderer::LineString;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Infrastructure {
+    Road,
+    Railway,
+    Airport,
+    Port,
+}
+
+impl Infrastructure {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Road => "Roads",
+            Self::Railway => "Railways",
+            Self::Airport => "Airports",
+            Self::Port => "Ports",
+        }
+    }
+
+    /// Map glyph for point facilities
+    pub fn glyph(self) -> char {
+        match self {
+            Self::Airport => '⊕',
+            Self::Port => '⚓',
+            Self::Road | Self::Railway => '·',
+        }
+    }
+
+    /// Lowest Mercator zoom the layer is drawn at — dense networks wait
+    /// until there's room for them
+    pub fn min_zoom(self) -> f64 {
+        match self {
+            Self::Airport | Self::Port => 3.0,
+            Self::Railway => 4.0,
+            Self::Road => 6.0,
+        }
+    }
+
+    /// Same threshold for the globe, whose effective zoom runs lower
+    pub fn min_globe_zoom(self) -> f64 {
+        match self {
+            Self::Airport | Self::Port => 1.2,
+            Self::Railway => 1.5,
+            Self::Road => 2.5,
+        }
+    }
+
+    /// Knocked out by an EMP: anything that runs on electronics. Roads
+    /// survive a pulse.
+    pub fn emp_vulnerable(self) -> bool {
+        !matches!(self, Self::Road)
+    }
+}
+
+/// A road or railway segment
+#[derive(Clone)]
+pub struct Route {
+    pub line: LineString,
+    pub destroyed: bool,
+}
+
+impl Route {
+    pub fn new(line: LineString) -> Self {
+        Self { line, destroyed: false }
+    }
+}
+
+/// An airport or port
+#[derive(Clone, Debug)]
+pub struct Facility {
+    pub lon: f64,
+    pub lat: f64,
+    pub name: String,
+    pub kind: Infrastructure,
+    pub destroyed: bool,
+}
@@ -0,0 +1,174 @@
+//! Label placement: keeps city, country and state names from overlapping
+//! each other.
+//!
+//! Labels are placed in priority order onto a grid of character cells.
+//! Each marker claims its cell, and each name tries the slots around its
+//! marker — right, left, above, below — taking the first that's free.
+//! Area names have no marker and try to center on their point, then just
+//! above or below it.
+//! Slots that would cover a map line are passed over while a clear one is
+//! left. A name with no free slot is dropped. Placing the most important
+//! labels first means the ones dropped are the least important.
+//!
+//! Lengths are in terminal cells, not characters: CJK names take two cells
+//! a character and combining accents none.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Where a name sits relative to its marker, in the order they're tried
+const SLOTS: [Slot; 4] = [Slot::Right, Slot::Left, Slot::Above, Slot::Below];
+
+#[derive(Clone, Copy)]
+enum Slot {
+    Right,
+    Left,
+    Above,
+    Below,
+}
+
+impl Slot {
+    /// Leftmost cell of a `len`-character name for a marker at (x, y). Names
+    /// beside a marker keep a cell's gap from it.
+    fn origin(self, x: i32, y: i32, len: i32) -> (i32, i32) {
+        match self {
+            Self::Right => (x + 2, y),
+            Self::Left => (x - 1 - len, y),
+            Self::Above => (x - len / 2, y - 1),
+            Self::Below => (x - len / 2, y + 1),
+        }
+    }
+}
+
+/// Cells a name takes on screen
+pub fn text_width(text: &str) -> usize {
+    text.width()
+}
+
+/// The longest start of `text` that fits in `cells`, without splitting a
+/// wide character
+pub fn truncate_to_width(text: &str, cells: usize) -> &str {
+    let mut used = 0;
+    for (i, ch) in text.char_indices() {
+        used += ch.width().unwrap_or(0);
+        if used > cells {
+            return &text[..i];
+        }
+    }
+    text
+}
+
+/// Character cells taken by labels placed so far
+pub struct LabelPlacer {
+    width: usize,
+    height: usize,
+    taken: Vec<bool>,
+}
+
+impl LabelPlacer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, taken: vec![false; width * height] }
+    }
+
+    /// Whether a run of `len` cells from (x, y) is on screen and untaken
+    fn free(&self, x: i32, y: i32, len: usize) -> bool {
+        x >= 0 && y >= 0 && (y as usize) < self.height && x as usize + len <= self.width
+            && !self.taken[y as usize * self.width + x as usize..][..len].iter().any(|&t| t)
+    }
+
+    fn claim(&mut self, x: i32, y: i32, len: usize) {
+        self.taken[y as usize * self.width + x as usize..][..len].fill(true);
+    }
+
+    /// Claim a marker's cell; false if it's off screen or already taken
+    pub fn place_marker(&mut self, x: u16, y: u16) -> bool {
+        let (x, y) = (x as i32, y as i32);
+        if !self.free(x, y, 1) {
+            return false;
+        }
+        self.claim(x, y, 1);
+        true
+    }
+
+    /// Find a spot for a `len`-character name next to the marker at (x, y)
+    /// and claim it, returning its leftmost cell. Slots where `on_line`
+    /// holds for any cell are only used when no other slot is free.
+    pub fn place_name(&mut self, x: u16, y: u16, len: usize, on_line: impl Fn(usize, usize) -> bool) -> Option<(u16, u16)> {
+        let (x, y) = (x as i32, y as i32);
+        let (sx, sy) = self.place_first(&SLOTS.map(|slot| slot.origin(x, y, len as i32)), len, on_line)?;
+        // The gap between marker and a name beside it stays clear too
+        if sy == y as u16 {
+            self.claim(if sx as i32 > x { x + 1 } else { x - 1 }, y, 1);
+        }
+        Some((sx, sy))
+    }
+
+    /// Like `place_name` for a name with no marker, centered on (x, y) or
+    /// else on the row above or below
+    pub fn place_centered(&mut self, x: u16, y: u16, len: usize, on_line: impl Fn(usize, usize) -> bool) -> Option<(u16, u16)> {
+        let (x, y) = (x as i32 - len as i32 / 2, y as i32);
+        self.place_first(&[(x, y), (x, y - 1), (x, y + 1)], len, on_line)
+    }
+
+    /// Claim the first free candidate clear of lines, or failing that the
+    /// first free one
+    fn place_first(&mut self, candidates: &[(i32, i32)], len: usize, on_line: impl Fn(usize, usize) -> bool) -> Option<(u16, u16)> {
+        let clear = |&(sx, sy): &(i32, i32)| (0..len).all(|i| !on_line(sx as usize + i, sy as usize));
+        let (sx, sy) = candidates.iter().copied()
+            .filter(|&(sx, sy)| self.free(sx, sy, len))
+            .min_by_key(|slot| !clear(slot))?;
+        self.claim(sx, sy, len);
+        Some((sx as u16, sy as u16))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_and_cuts_in_cells() {
+        assert_eq!(text_width("Paris"), 5);
+        assert_eq!(text_width("東京"), 4);
+        // e + combining acute accent
+        assert_eq!(text_width("Me\u{301}xico"), 6);
+        assert_eq!(truncate_to_width("東京都", 5), "東京");
+        assert_eq!(truncate_to_width("Me\u{301}xico", 2), "Me\u{301}");
+        assert_eq!(truncate_to_width("Lima", 10), "Lima");
+    }
+
+    #[test]
+    fn names_move_around_their_neighbors_then_drop() {
+        let mut placer = LabelPlacer::new(20, 3);
+        let no_lines = |_, _| false;
+        assert!(placer.place_marker(5, 1));
+        assert_eq!(placer.place_name(5, 1, 4, no_lines), Some((7, 1)));
+
+        // A marker on a taken cell isn't placed at all
+        assert!(!placer.place_marker(8, 1));
+        assert!(placer.place_marker(12, 1));
+        assert_eq!(placer.place_name(12, 1, 6, no_lines), Some((14, 1)));
+
+        // Crowded on the right and left, a name goes above
+        assert!(placer.place_marker(11, 1));
+        assert_eq!(placer.place_name(11, 1, 4, no_lines), Some((9, 0)));
+
+        // Nowhere left: dropped, though the marker stays
+        assert!(placer.place_marker(16, 0));
+        assert_eq!(placer.place_name(16, 0, 4, no_lines), None);
+    }
+
+    #[test]
+    fn names_avoid_map_lines_while_they_can() {
+        let line_right = |x: usize, _| x >= 7;
+        let mut placer = LabelPlacer::new(20, 3);
+        assert_eq!(placer.place_name(5, 1, 3, line_right), Some((1, 1)));
+        // Lines everywhere: the first free slot wins anyway
+        assert_eq!(placer.place_name(15, 1, 3, |_, _| true), Some((17, 1)));
+
+        // Area names center on their point, or shift a row off a line
+        assert_eq!(placer.place_centered(10, 1, 4, |_, y| y == 1), Some((8, 0)));
+        assert_eq!(placer.place_centered(10, 1, 4, |_, _| false), Some((8, 1)));
+        assert_eq!(placer.place_centered(10, 1, 4, |_, _| false), Some((8, 2)));
+        assert_eq!(placer.place_centered(10, 1, 4, |_, _| false), None);
+    }
+}
@@ -1,9 +1,20 @@
+pub mod country;
+pub mod flat;
+pub mod fonts;
 pub mod geometry;
 pub mod globe;
+pub mod infrastructure;
+pub mod labels;
+pub mod packed;
 pub mod projection;
 pub mod renderer;
+pub mod snapshot;
 pub mod spatial;
+pub mod text;
 
+pub use country::CountryGrid;
+pub use flat::{FlatKind, FlatViewport};
 pub use globe::GlobeViewport;
-pub use projection::{Projection, Viewport, WRAP_OFFSETS};
-pub use renderer::{LineString, Lod, MapLayers, MapRenderer};
+pub use infrastructure::{Facility, Infrastructure, Route};
+pub use projection::{MapProjection, Projection, Viewport, WRAP_OFFSETS};
+pub use renderer::{Features, LineString, Lod, MapLayers, MapRenderer};
@@ -0,0 +1,213 @@
+//! Line coordinates packed to save memory.
+//!
+//! Points are fixed-point at 0.0001° (about 11 m, finer than Natural
+//! Earth's 10m scale), each stored as the zigzag varint difference from the
+//! one before, as the embedded world is. Most steps along a detailed line
+//! fit in one or two bytes an axis, so a point takes around 4 bytes where
+//! its precomputed Mercator and unit-sphere forms took 40. They're decoded
+//! as they're drawn.
+//!
+//! A `Mark` records where decoding stands partway through, so a stretch of
+//! a long line can be read without the points before it.
+
+use anyhow::{bail, Result};
+
+/// Stored units per degree
+pub const SCALE: f64 = 10_000.0;
+
+/// Bytes in the longest varint, one holding a whole `u64`
+const MAX_VARINT_BYTES: usize = 10;
+
+/// Packed points, lon/lat in degrees
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PackedCoords {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+/// Where decoding stands before a point: the byte it starts at and the
+/// point before it
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Mark {
+    offset: u32,
+    prev: (i32, i32),
+}
+
+fn write_varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        out.push((v as u8) | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+fn zigzag(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+fn unzigzag(v: u64) -> i64 {
+    (v >> 1) as i64 ^ -((v & 1) as i64)
+}
+
+impl PackedCoords {
+    /// Pack `points`, rounding each to the nearest 0.0001°
+    pub fn pack(points: &[(f64, f64)]) -> Self {
+        let mut bytes = Vec::with_capacity(points.len() * 4);
+        let mut prev = (0i64, 0i64);
+        for &(lon, lat) in points {
+            let at = ((lon * SCALE).round() as i64, (lat * SCALE).round() as i64);
+            write_varint(&mut bytes, zigzag(at.0 - prev.0));
+            write_varint(&mut bytes, zigzag(at.1 - prev.1));
+            prev = at;
+        }
+        bytes.shrink_to_fit();
+        Self { bytes, len: points.len() }
+    }
+
+    /// Packed bytes as `bytes` gives them, holding `len` points. Fails if
+    /// they don't decode to exactly that many.
+    pub fn from_bytes(bytes: Vec<u8>, len: usize) -> Result<Self> {
+        let (mut pos, mut varints) = (0, 0);
+        while pos < bytes.len() {
+            let start = pos;
+            while bytes.get(pos).is_some_and(|b| b & 0x80 != 0) {
+                pos += 1;
+            }
+            if pos >= bytes.len() {
+                bail!("truncated point");
+            }
+            pos += 1;
+            if pos - start > MAX_VARINT_BYTES {
+                bail!("coordinate {varints} runs over {MAX_VARINT_BYTES} bytes");
+            }
+            varints += 1;
+        }
+        if varints != len * 2 {
+            bail!("{} coordinates for {len} points", varints);
+        }
+        Ok(Self { bytes, len })
+    }
+
+    /// The packed bytes, for writing to disk
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn heap_bytes(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Every point, in order
+    pub fn iter(&self) -> Points<'_> {
+        self.iter_from(Mark::default())
+    }
+
+    /// The points from a `Mark` on
+    pub fn iter_from(&self, mark: Mark) -> Points<'_> {
+        Points { bytes: &self.bytes[mark.offset as usize..], at: mark.prev, offset: mark.offset }
+    }
+
+    /// Marks before every `step`th point, starting with the first
+    pub fn marks(&self, step: usize) -> Vec<Mark> {
+        let mut marks = Vec::with_capacity(self.len.div_ceil(step.max(1)));
+        let mut points = self.iter();
+        for i in 0..self.len {
+            if i % step.max(1) == 0 {
+                marks.push(points.mark());
+            }
+            points.next();
+        }
+        marks
+    }
+}
+
+/// Decodes points in order
+pub struct Points<'a> {
+    bytes: &'a [u8],
+    at: (i32, i32),
+    offset: u32,
+}
+
+impl Points<'_> {
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for (i, &byte) in self.bytes.iter().take(MAX_VARINT_BYTES).enumerate() {
+            value |= ((byte & 0x7F) as u64) << (7 * i);
+            if byte & 0x80 == 0 {
+                self.bytes = &self.bytes[i + 1..];
+                self.offset += i as u32 + 1;
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// Where decoding stands, to start again from later
+    pub fn mark(&self) -> Mark {
+        Mark { offset: self.offset, prev: self.at }
+    }
+}
+
+impl Iterator for Points<'_> {
+    type Item = (f64, f64);
+
+    #[inline]
+    fn next(&mut self) -> Option<(f64, f64)> {
+        let dx = unzigzag(self.varint()?);
+        let dy = unzigzag(self.varint()?);
+        self.at = ((self.at.0 as i64 + dx) as i32, (self.at.1 as i64 + dy) as i32);
+        Some((self.at.0 as f64 / SCALE, self.at.1 as f64 / SCALE))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_round_trip_to_a_ten_thousandth_of_a_degree() {
+        let points: Vec<(f64, f64)> = (0..100).map(|i| (-179.99 + i as f64 * 3.7, -89.5 + i as f64 * 1.79)).collect();
+        let packed = PackedCoords::pack(&points);
+        assert_eq!(packed.len(), 100);
+        for (a, b) in packed.iter().zip(&points) {
+            assert!((a.0 - b.0).abs() <= 0.5 / SCALE && (a.1 - b.1).abs() <= 0.5 / SCALE, "{a:?} {b:?}");
+        }
+
+        // A line past the antimeridian keeps going
+        let across = PackedCoords::pack(&[(179.5, 0.0), (181.25, 0.5)]);
+        assert_eq!(across.iter().last(), Some((181.25, 0.5)));
+
+        let copy = PackedCoords::from_bytes(packed.as_bytes().to_vec(), 100).unwrap();
+        assert_eq!(copy, packed);
+        assert!(PackedCoords::from_bytes(packed.as_bytes().to_vec(), 99).is_err());
+        assert!(PackedCoords::from_bytes(vec![0x80], 1).is_err());
+        // An 11-byte varint can't hold anything, and would overflow the shift
+        let mut long = vec![0x80; 10];
+        long.extend([0x01, 0x00]);
+        assert!(PackedCoords::from_bytes(long, 1).is_err());
+        let mut longest = vec![0x80; 9];
+        longest.extend([0x01, 0x00]);
+        assert!(PackedCoords::from_bytes(longest, 1).is_ok());
+    }
+
+    #[test]
+    fn marks_resume_partway_and_small_steps_pack_small() {
+        let points: Vec<(f64, f64)> = (0..70).map(|i| (10.0 + i as f64 * 0.01, 50.0 - i as f64 * 0.002)).collect();
+        let packed = PackedCoords::pack(&points);
+        let all: Vec<_> = packed.iter().collect();
+        let marks = packed.marks(32);
+        assert_eq!(marks.len(), 3);
+        assert_eq!(packed.iter_from(marks[1]).collect::<Vec<_>>(), all[32..]);
+        assert_eq!(packed.iter_from(marks[2]).next(), Some(all[64]));
+        // ~4 bytes a point once past the first
+        assert!(packed.heap_bytes() < 70 * 5, "{}", packed.heap_bytes());
+    }
+}
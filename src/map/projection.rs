@@ -1,4 +1,6 @@
-use crate::map::globe::GlobeViewport;
+use crate::map::flat::{FlatKind, FlatViewport};
+use crate::map::globe::{lonlat_to_vec3, vec3_to_lonlat, DVec3, GlobeViewport};
+use crate::map::renderer::{LineString, Polygon};
 use std::f64::consts::PI;
 
 /// Longitude offsets for handling date-line wrapping.
@@ -119,7 +121,7 @@ impl Viewport {
     }
 
     /// Zoom by factor towards a specific pixel location
-    fn zoom_at(&mut self, px: i32, py: i32, factor: f64) {
+    pub fn zoom_at(&mut self, px: i32, py: i32, factor: f64) {
         // Get the geographic coordinates under the mouse BEFORE zoom
         let (target_lon, target_lat) = self.unproject(px, py);
 
@@ -215,113 +217,592 @@ impl Viewport {
     }
 }
 
-/// Projection enum: Mercator flat map or orthographic globe.
-/// Two variants, constant-per-frame branching — the branch predictor handles this.
-#[derive(Clone)]
-pub enum Projection {
-    Mercator(Viewport),
-    Globe(GlobeViewport),
+/// What the renderer and UI need from a map projection. Coordinates are
+/// braille pixels, (0, 0) at the top left.
+///
+/// The required methods are the projection itself and its view state. The
+/// provided ones have defaults built on those, and projections override them
+/// where they can do better: Mercator draws wrapped copies of the world from
+/// precomputed coordinates, the globe subdivides long arcs. Projections are
+/// shared across threads, so the renderer can draw layers in parallel.
+pub trait MapProjection: CloneProjection + Send + Sync {
+    /// Shown in the status bar
+    fn name(&self) -> &'static str;
+
+    /// (width, height) in pixels
+    fn size(&self) -> (usize, usize);
+
+    fn set_size(&mut self, width: usize, height: usize);
+
+    /// Screen position of a geographic point, or `None` where the
+    /// projection can't show it (the far side of a globe, say). Projections
+    /// that repeat the world pick the copy on screen.
+    fn project(&self, lon: f64, lat: f64) -> Option<(i32, i32)>;
+
+    /// Geographic point under a pixel, or `None` off the edge of the map
+    fn unproject(&self, px: i32, py: i32) -> Option<(f64, f64)>;
+
+    /// Screen length of `degrees` of arc at the view center
+    fn deg_to_pixels(&self, degrees: f64) -> f64;
+
+    /// How much larger the map draws things at a point than at the view
+    /// center, where `deg_to_pixels` applies
+    fn scale_at(&self, _lon: f64, _lat: f64) -> f64 {
+        1.0
+    }
+
+    /// 1.0 = the whole world in view
+    fn effective_zoom(&self) -> f64;
+
+    /// (lon, lat) at the middle of the screen
+    fn center(&self) -> (f64, f64);
+
+    /// Approximate visible bounds as (min_lon, min_lat, max_lon, max_lat).
+    /// Longitudes may run past ±180 when the view crosses the dateline.
+    fn visible_bounds(&self) -> (f64, f64, f64, f64);
+
+    /// Jump to a center and effective zoom
+    fn set_view(&mut self, lon: f64, lat: f64, zoom: f64);
+
+    /// Move the view by a pixel drag
+    fn pan(&mut self, dx: i32, dy: i32);
+    fn zoom_in(&mut self);
+    fn zoom_out(&mut self);
+    fn zoom_in_at(&mut self, px: i32, py: i32);
+    fn zoom_out_at(&mut self, px: i32, py: i32);
+
+    /// Zoom by any factor, keeping the point under a pixel where it is.
+    /// The default recenters with `set_view` and pans the point back under
+    /// the pixel.
+    fn zoom_at(&mut self, px: i32, py: i32, factor: f64) {
+        let target = self.unproject(px, py);
+        let (lon, lat) = self.center();
+        self.set_view(lon, lat, (self.effective_zoom() * factor).clamp(1.0, 100.0));
+        let Some((lon, lat)) = target else { return };
+        for _ in 0..3 {
+            let Some((nx, ny)) = self.project(lon, lat) else { return };
+            self.pan(nx - px, ny - py);
+        }
+    }
+
+    /// Project a unit-sphere vector, as stored on `LineString`s
+    fn project_vec3(&self, v: DVec3) -> Option<(i32, i32)> {
+        let (lon, lat) = vec3_to_lonlat(v);
+        self.project(lon, lat)
+    }
+
+    /// Every on-screen position of a point, for projections that show the
+    /// world more than once
+    fn project_copies(&self, lon: f64, lat: f64) -> Vec<(i32, i32)> {
+        self.project(lon, lat).into_iter().collect()
+    }
+
+    /// Unit-sphere point under a pixel
+    fn surface_point(&self, px: i32, py: i32) -> Option<DVec3> {
+        self.unproject(px, py).map(|(lon, lat)| lonlat_to_vec3(lon, lat))
+    }
+
+    /// Call `emit` for each on-screen segment of `line`. The default joins
+    /// consecutive vertices, breaking where one can't be projected or where
+    /// a segment crossed the map's edge. A segment jumping across half the
+    /// screen may instead be a long straight one seen close up; the two are
+    /// told apart by where its middle lands.
+    fn segments(&self, line: &LineString, emit: &mut dyn FnMut((i32, i32), (i32, i32))) {
+        let (width, height) = self.size();
+        let max_jump = width as i32 / 2;
+        let mut prev: Option<(DVec3, Option<(i32, i32)>)> = None;
+        for v in line.vecs() {
+            let cur = self.project_vec3(v);
+            if let (Some((pv, Some(a))), Some(b)) = (prev, cur) {
+                let jump = (a.0 - b.0).abs() + (a.1 - b.1).abs();
+                let crossed_edge = jump >= max_jump
+                    && (pv + v).try_normalize().and_then(|mid| self.project_vec3(mid)).is_none_or(|m| {
+                        (2 * m.0 - a.0 - b.0).abs() + (2 * m.1 - a.1 - b.1).abs() > jump / 2
+                    });
+                if !crossed_edge {
+                    if let Some((a, b)) = clip_segment(width, height, a, b) {
+                        emit(a, b);
+                    }
+                }
+            }
+            prev = Some((v, cur));
+        }
+    }
+
+    /// Call `emit` with the rings of each on-screen copy of `polygon` in
+    /// screen coordinates, for filling. The default projects the rings
+    /// densified so long edges follow the projection's curves, and skips
+    /// polygons with a point it can't show.
+    fn polygon_rings(&self, polygon: &Polygon, emit: &mut dyn FnMut(&ScreenRings)) {
+        let rings: Option<Vec<Vec<(i32, i32)>>> = polygon.rings.iter()
+            .map(|ring| densify_ring(ring).map(|(lon, lat)| self.project(lon, lat)).collect())
+            .collect();
+        if let Some(rings) = rings {
+            emit(&rings);
+        }
+    }
+
+    /// Dots along the edge of the world where it's on screen, spaced for a
+    /// faint outline
+    fn edge(&self) -> Vec<(i32, i32)> {
+        Vec::new()
+    }
+
+    /// Views of the sphere itself, where the screen foreshortens toward
+    /// the rim. Effects are shaped on the surface there, and detail layers
+    /// use the globe's lower zoom thresholds.
+    fn is_globe(&self) -> bool {
+        false
+    }
+
+    /// Inertial rotation (radians) after a drag is released. Projections
+    /// that don't rotate ignore it.
+    fn spin(&mut self, _radians: f64) {}
+
+    /// Resize for rendering the same view at another resolution
+    fn rescale(&mut self, width: usize, height: usize) {
+        self.set_size(width, height);
+    }
+}
+
+/// Lets `Projection` clone its boxed `MapProjection`. Implemented for every
+/// projection that is `Clone`.
+pub trait CloneProjection {
+    fn clone_projection(&self) -> Box<dyn MapProjection>;
+}
+
+impl<T: MapProjection + Clone + 'static> CloneProjection for T {
+    fn clone_projection(&self) -> Box<dyn MapProjection> {
+        Box::new(self.clone())
+    }
+}
+
+/// Whether a segment's bounding box touches a `width`×`height` screen
+pub fn segment_on_screen(width: usize, height: usize, p1: (i32, i32), p2: (i32, i32)) -> bool {
+    p1.0.max(p2.0) >= 0 && p1.0.min(p2.0) < width as i32 && p1.1.max(p2.1) >= 0 && p1.1.min(p2.1) < height as i32
+}
+
+/// A polygon's rings in screen pixels, exterior first, then holes
+pub type ScreenRings = [Vec<(i32, i32)>];
+
+/// Degrees between the points `densify_ring` adds along an edge
+const DENSIFY_STEP: f64 = 2.0;
+
+/// A closed ring's points with extra ones along edges longer than
+/// `DENSIFY_STEP`, for projections that bend lines of constant slope in
+/// lon/lat. The closing edge back to the first point is included.
+pub fn densify_ring(ring: &[(f64, f64)]) -> impl Iterator<Item = (f64, f64)> + '_ {
+    ring.iter().enumerate().flat_map(move |(i, &(lon, lat))| {
+        let (next_lon, next_lat) = ring[(i + 1) % ring.len()];
+        let (dlon, dlat) = (next_lon - lon, next_lat - lat);
+        let steps = (dlon.abs().max(dlat.abs()) / DENSIFY_STEP).ceil().max(1.0) as usize;
+        (0..steps).map(move |step| {
+            let t = step as f64 / steps as f64;
+            (lon + dlon * t, lat + dlat * t)
+        })
+    })
+}
+
+/// The part of a segment on a `width`×`height` screen (Liang–Barsky), or
+/// None if it misses. Segments already on screen come back untouched;
+/// others are cut at the screen's edge, so lines aren't traced pixel by
+/// pixel off screen.
+pub fn clip_segment(width: usize, height: usize, a: (i32, i32), b: (i32, i32)) -> Option<((i32, i32), (i32, i32))> {
+    let on_screen = |(x, y): (i32, i32)| x >= 0 && y >= 0 && x < width as i32 && y < height as i32;
+    if on_screen(a) && on_screen(b) {
+        return Some((a, b));
+    }
+    if !segment_on_screen(width, height, a, b) {
+        return None;
+    }
+    let (x0, y0) = (a.0 as f64, a.1 as f64);
+    let (dx, dy) = (b.0 as f64 - x0, b.1 as f64 - y0);
+    let (x_max, y_max) = (width as f64 - 1.0, height as f64 - 1.0);
+    let (mut t0, mut t1) = (0.0_f64, 1.0_f64);
+    // Each edge as p·t ≤ q: entering where p < 0, leaving where p > 0
+    for (p, q) in [(-dx, x0), (dx, x_max - x0), (-dy, y0), (dy, y_max - y0)] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+    if t0 > t1 {
+        return None;
+    }
+    let at = |t: f64| ((x0 + t * dx).round() as i32, (y0 + t * dy).round() as i32);
+    Some((if t0 > 0.0 { at(t0) } else { a }, if t1 < 1.0 { at(t1) } else { b }))
+}
+
+/// Compute which wrap offsets are needed for this viewport.
+/// Offset 0 always needed; ±360 only when viewport crosses the dateline.
+fn needed_wrap_offsets(vp_min_lon: f64, vp_max_lon: f64) -> &'static [f64] {
+    let needs_neg = vp_max_lon > 180.0;  // viewport wraps east → need -360
+    let needs_pos = vp_min_lon < -180.0;  // viewport wraps west → need +360
+    match (needs_neg, needs_pos) {
+        (true, true) => &[0.0, -360.0, 360.0],
+        (true, false) => &[0.0, -360.0],
+        (false, true) => &[0.0, 360.0],
+        (false, false) => &[0.0],
+    }
+}
+
+impl Viewport {
+    /// Whether a Mercator-space bbox, projected, comes within `pad` pixels
+    /// of the screen
+    fn mercator_bbox_on_screen(&self, (min_x, min_y, max_x, max_y): (f64, f64, f64, f64), lon_offset: f64, pad: i32) -> bool {
+        let (px1, py1) = self.project_mercator(min_x, min_y, lon_offset);
+        let (px2, py2) = self.project_mercator(max_x, max_y, lon_offset);
+        px1.max(px2) >= -pad && px1.min(px2) <= self.width as i32 + pad
+            && py1.max(py2) >= -pad && py1.min(py2) <= self.height as i32 + pad
+    }
+
+    /// Draw one wrapped copy of a linestring. Only the chunks on screen
+    /// are decoded to Mercator coordinates.
+    fn segments_with_offset(&self, line: &LineString, lon_offset: f64, emit: &mut dyn FnMut((i32, i32), (i32, i32))) {
+        // Bbox early-out using precomputed Mercator bbox (pure arithmetic, no trig)
+        if !self.mercator_bbox_on_screen(line.mercator_bbox, lon_offset, 50) {
+            return;
+        }
+
+        for chunk in &line.chunks {
+            // Runs of the line entirely off screen aren't projected at all
+            if !self.mercator_bbox_on_screen(chunk.mercator_bbox, lon_offset, 1) {
+                continue;
+            }
+
+            let mut prev: Option<(i32, i32)> = None;
+            for (mx, my) in line.chunk_mercator(chunk) {
+                let (px, py) = self.project_mercator(mx, my, lon_offset);
+
+                // Lines run on past ±180 rather than jumping (see
+                // `LineString::new`), so every segment is a real one
+                if let Some(prev) = prev {
+                    if let Some((a, b)) = clip_segment(self.width, self.height, prev, (px, py)) {
+                        emit(a, b);
+                    }
+                }
+
+                prev = Some((px, py));
+            }
+        }
+    }
+}
+
+impl MapProjection for Viewport {
+    fn name(&self) -> &'static str {
+        "Mercator"
+    }
+
+    fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn set_size(&mut self, width: usize, height: usize) {
+        self.set_dimensions(width, height);
+    }
+
+    fn project(&self, lon: f64, lat: f64) -> Option<(i32, i32)> {
+        self.project_wrapped_first(lon, lat)
+    }
+
+    fn unproject(&self, px: i32, py: i32) -> Option<(f64, f64)> {
+        Some(Viewport::unproject(self, px, py))
+    }
+
+    fn deg_to_pixels(&self, degrees: f64) -> f64 {
+        degrees * self.zoom * self.width as f64 / 360.0
+    }
+
+    fn effective_zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    fn center(&self) -> (f64, f64) {
+        (self.center_lon, self.center_lat)
+    }
+
+    fn visible_bounds(&self) -> (f64, f64, f64, f64) {
+        let (_, top_lat) = Viewport::unproject(self, 0, 0);
+        let (_, bottom_lat) = Viewport::unproject(self, 0, self.height as i32);
+        (
+            self.center_lon - 180.0 / self.zoom,
+            bottom_lat.max(-85.0),
+            self.center_lon + 180.0 / self.zoom,
+            top_lat.min(85.0),
+        )
+    }
+
+    fn set_view(&mut self, lon: f64, lat: f64, zoom: f64) {
+        *self = Viewport::new(lon, lat.clamp(-85.0, 85.0), zoom, self.width, self.height);
+    }
+
+    fn pan(&mut self, dx: i32, dy: i32) {
+        Viewport::pan(self, dx, dy);
+    }
+
+    fn zoom_in(&mut self) {
+        Viewport::zoom_in(self);
+    }
+
+    fn zoom_out(&mut self) {
+        Viewport::zoom_out(self);
+    }
+
+    fn zoom_in_at(&mut self, px: i32, py: i32) {
+        Viewport::zoom_in_at(self, px, py);
+    }
+
+    fn zoom_out_at(&mut self, px: i32, py: i32) {
+        Viewport::zoom_out_at(self, px, py);
+    }
+
+    fn zoom_at(&mut self, px: i32, py: i32, factor: f64) {
+        Viewport::zoom_at(self, px, py, factor);
+    }
+
+    fn project_copies(&self, lon: f64, lat: f64) -> Vec<(i32, i32)> {
+        WRAP_OFFSETS.iter().filter_map(|&offset| {
+            let ((px, py), _) = self.project_wrapped(lon, lat, offset);
+            (px >= 0 && py >= 0 && px <= 30000 && py <= 30000).then_some((px, py))
+        }).collect()
+    }
+
+    /// Draws each wrapped copy of the line that reaches the screen. A line
+    /// crossing the antimeridian runs past ±180, so which copies those are
+    /// depends on the line as well as the view: each is tried, and its
+    /// Mercator bbox turns away the ones off screen.
+    fn segments(&self, line: &LineString, emit: &mut dyn FnMut((i32, i32), (i32, i32))) {
+        if line.len() < 2 {
+            return;
+        }
+        for &lon_offset in &WRAP_OFFSETS {
+            self.segments_with_offset(line, lon_offset, emit);
+        }
+    }
+
+    /// Each wrapped copy of the polygon that's on screen
+    fn polygon_rings(&self, polygon: &Polygon, emit: &mut dyn FnMut(&ScreenRings)) {
+        let (min_lon, min_lat, max_lon, max_lat) = polygon.bbox;
+        let bbox = (mercator_x(min_lon), mercator_y(max_lat), mercator_x(max_lon), mercator_y(min_lat));
+        let half_width_deg = 180.0 / self.zoom;
+        let offsets = needed_wrap_offsets(self.center_lon - half_width_deg, self.center_lon + half_width_deg);
+        for &lon_offset in offsets {
+            if !self.mercator_bbox_on_screen(bbox, lon_offset, 1) {
+                continue;
+            }
+            let rings: Vec<Vec<(i32, i32)>> = polygon.mercator.iter()
+                .map(|ring| ring.iter().map(|&(mx, my)| self.project_mercator(mx, my, lon_offset)).collect())
+                .collect();
+            emit(&rings);
+        }
+    }
+}
+
+/// Builds a projection from (lon, lat, zoom, width, height)
+type Constructor = fn(f64, f64, f64, usize, usize) -> Projection;
+
+/// The projections `g` cycles through, in order
+const CYCLE: [(&str, Constructor); 6] = [
+    ("Mercator", |lon, lat, zoom, w, h| Projection::new(Viewport::new(lon, lat.clamp(-85.0, 85.0), zoom, w, h))),
+    ("Globe", |lon, lat, zoom, w, h| Projection::new(GlobeViewport::new(lon, lat, zoom * w as f64 * 0.35, w, h))),
+    ("Equirectangular", |lon, lat, zoom, w, h| Projection::new(FlatViewport::new(FlatKind::Equirectangular, lon, lat, zoom, w, h))),
+    ("Robinson", |lon, lat, zoom, w, h| Projection::new(FlatViewport::new(FlatKind::Robinson, lon, lat, zoom, w, h))),
+    ("Azimuthal", |lon, lat, zoom, w, h| Projection::new(FlatViewport::new(FlatKind::AzimuthalEquidistant, lon, lat, zoom, w, h))),
+    ("Polar", |lon, lat, zoom, w, h| Projection::new(FlatViewport::new(FlatKind::Polar, lon, lat, zoom, w, h))),
+];
+
+/// The map's current projection. Anything implementing `MapProjection`
+/// can be wrapped; the renderer and UI only go through the trait.
+pub struct Projection(Box<dyn MapProjection>);
+
+impl Clone for Projection {
+    fn clone(&self) -> Self {
+        Self(self.0.clone_projection())
+    }
 }
 
 impl Projection {
-    /// Pan (Mercator) or rotate (Globe) by pixel delta.
-    pub fn pan(&mut self, dx: i32, dy: i32) {
-        match self {
-            Projection::Mercator(vp) => vp.pan(dx, dy),
-            Projection::Globe(g) => g.rotate_drag(dx, dy),
+    /// Names of the projections `g` cycles through
+    pub const NAMES: [&'static str; CYCLE.len()] = {
+        let mut names = [""; CYCLE.len()];
+        let mut i = 0;
+        while i < CYCLE.len() {
+            names[i] = CYCLE[i].0;
+            i += 1;
         }
+        names
+    };
+
+    pub fn new(projection: impl MapProjection + 'static) -> Self {
+        Self(Box::new(projection))
+    }
+
+    pub fn view(&self) -> &dyn MapProjection {
+        &*self.0
+    }
+
+    pub fn view_mut(&mut self) -> &mut dyn MapProjection {
+        &mut *self.0
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.0.name()
+    }
+
+    pub fn is_globe(&self) -> bool {
+        self.0.is_globe()
+    }
+
+    /// Pan (flat maps) or rotate (Globe) by pixel delta.
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        self.0.pan(dx, dy);
     }
 
     pub fn zoom_in(&mut self) {
-        match self {
-            Projection::Mercator(vp) => vp.zoom_in(),
-            Projection::Globe(g) => g.zoom_in(),
-        }
+        self.0.zoom_in();
     }
 
     pub fn zoom_out(&mut self) {
-        match self {
-            Projection::Mercator(vp) => vp.zoom_out(),
-            Projection::Globe(g) => g.zoom_out(),
-        }
+        self.0.zoom_out();
     }
 
     pub fn zoom_in_at(&mut self, px: i32, py: i32) {
-        match self {
-            Projection::Mercator(vp) => vp.zoom_in_at(px, py),
-            Projection::Globe(g) => g.zoom_in_at(px, py),
-        }
+        self.0.zoom_in_at(px, py);
     }
 
     pub fn zoom_out_at(&mut self, px: i32, py: i32) {
-        match self {
-            Projection::Mercator(vp) => vp.zoom_out_at(px, py),
-            Projection::Globe(g) => g.zoom_out_at(px, py),
-        }
+        self.0.zoom_out_at(px, py);
+    }
+
+    /// Zoom by a fractional factor about a pixel
+    pub fn zoom_at(&mut self, px: i32, py: i32, factor: f64) {
+        self.0.zoom_at(px, py, factor);
     }
 
     pub fn set_size(&mut self, width: usize, height: usize) {
-        match self {
-            Projection::Mercator(vp) => {
-                vp.set_dimensions(width, height);
-            }
-            Projection::Globe(g) => g.set_size(width, height),
-        }
+        self.0.set_size(width, height);
     }
 
-    /// Unproject pixel to geo coords. Returns `None` on globe if outside sphere.
+    /// (width, height) in pixels
+    pub fn size(&self) -> (usize, usize) {
+        self.0.size()
+    }
+
+    /// Unproject pixel to geo coords. `None` off the edge of the map.
     pub fn unproject(&self, px: i32, py: i32) -> Option<(f64, f64)> {
-        match self {
-            Projection::Mercator(vp) => Some(vp.unproject(px, py)),
-            Projection::Globe(g) => g.unproject(px, py),
-        }
+        self.0.unproject(px, py)
     }
 
     /// Project a geographic point to screen pixels.
     /// Mercator tries wrap offsets; globe returns None for back-face.
     pub fn project_point(&self, lon: f64, lat: f64) -> Option<(i32, i32)> {
-        match self {
-            Projection::Mercator(vp) => vp.project_wrapped_first(lon, lat),
-            Projection::Globe(g) => g.project(lon, lat),
-        }
+        self.0.project(lon, lat)
     }
 
-    /// Effective zoom level, normalized so 1.0 = world view for both projections.
+    /// Effective zoom level, normalized so 1.0 = world view for every projection.
     pub fn effective_zoom(&self) -> f64 {
-        match self {
-            Projection::Mercator(vp) => vp.zoom,
-            Projection::Globe(g) => g.effective_zoom(),
-        }
+        self.0.effective_zoom()
     }
 
     /// Convert geographic degrees to screen pixels for radius rendering.
     pub fn deg_to_pixels(&self, degrees: f64) -> f64 {
-        match self {
-            Projection::Mercator(vp) => degrees * vp.zoom * vp.width as f64 / 360.0,
-            Projection::Globe(g) => g.deg_to_pixels(degrees),
-        }
+        self.0.deg_to_pixels(degrees)
+    }
+
+    /// `deg_to_pixels` for something drawn at (lon, lat) rather than the
+    /// view center, on maps whose scale varies
+    pub fn deg_to_pixels_at(&self, lon: f64, lat: f64, degrees: f64) -> f64 {
+        self.0.deg_to_pixels(degrees) * self.0.scale_at(lon, lat)
     }
 
-    /// Toggle between Mercator and Globe, preserving center and zoom.
-    pub fn toggle(self) -> Self {
-        match self {
-            Projection::Mercator(vp) => Projection::Globe(GlobeViewport::from_mercator(&vp)),
-            Projection::Globe(g) => Projection::Mercator(g.to_mercator()),
+    /// Jump to a center and effective zoom (clamped to the 1–100 range).
+    pub fn set_view(&mut self, lon: f64, lat: f64, zoom: f64) {
+        self.0.set_view(lon, lat, zoom.clamp(1.0, 100.0));
+    }
+
+    /// Switch to the next projection in the cycle Mercator → Globe →
+    /// Equirectangular → Robinson → Azimuthal, preserving center and zoom.
+    /// A projection from outside the cycle switches to Mercator.
+    pub fn toggle(&mut self) {
+        let (lon, lat) = self.0.center();
+        let zoom = self.effective_zoom();
+        let (width, height) = self.size();
+        let next = CYCLE.iter().position(|&(name, _)| name == self.name()).map_or(0, |i| (i + 1) % CYCLE.len());
+        *self = (CYCLE[next].1)(lon, lat, zoom, width, height);
+    }
+
+    /// Switch to a projection from the cycle by name, preserving center and
+    /// zoom. Returns false for a name not in the cycle.
+    pub fn switch_to(&mut self, name: &str) -> bool {
+        let Some(&(_, build)) = CYCLE.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)) else {
+            return false;
+        };
+        let (lon, lat) = self.0.center();
+        let zoom = self.effective_zoom();
+        let (width, height) = self.size();
+        *self = build(lon, lat, zoom, width, height);
+        true
+    }
+
+    /// The center and effective zoom that frame `points` as closely as
+    /// possible, on this projection at its current size. `None` without
+    /// points.
+    pub fn fit(&self, points: &[(f64, f64)]) -> Option<(f64, f64, f64)> {
+        let &(first_lon, _) = points.first()?;
+        // Longitudes relative to the first point, so an extent across the
+        // antimeridian stays in one piece
+        let (mut west, mut east) = (0.0_f64, 0.0_f64);
+        let (mut south, mut north) = (f64::INFINITY, f64::NEG_INFINITY);
+        for &(lon, lat) in points {
+            let offset = crate::geo::wrap_lon(lon - first_lon);
+            (west, east) = (west.min(offset), east.max(offset));
+            (south, north) = (south.min(lat), north.max(lat));
+        }
+        let lon = crate::geo::wrap_lon(first_lon + (west + east) / 2.0);
+        let lat = (south + north) / 2.0;
+
+        // Zoom in for as long as every point stays on screen. Bisect on
+        // the log of the zoom, since each step in it looks the same size.
+        let (width, height) = self.size();
+        let mut scratch = self.clone();
+        let mut fits = |zoom: f64| {
+            scratch.set_view(lon, lat, zoom);
+            points.iter().all(|&(lon, lat)| {
+                scratch.project_point(lon, lat).is_some_and(|(x, y)| x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height)
+            })
+        };
+        let (mut lo, mut hi) = (1.0_f64.ln(), 100.0_f64.ln());
+        if fits(hi.exp()) {
+            return Some((lon, lat, hi.exp()));
         }
+        for _ in 0..30 {
+            let mid = (lo + hi) / 2.0;
+            if fits(mid.exp()) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        Some((lon, lat, lo.exp()))
+    }
+
+    /// Approximate visible geographic bounds as (min_lon, min_lat, max_lon, max_lat).
+    /// Longitudes may extend past ±180 when the view crosses the dateline.
+    pub fn visible_bounds(&self) -> (f64, f64, f64, f64) {
+        self.0.visible_bounds()
     }
 
     pub fn center_lon(&self) -> f64 {
-        match self {
-            Projection::Mercator(vp) => vp.center_lon,
-            Projection::Globe(g) => g.center_lon(),
-        }
+        self.0.center().0
     }
 
     pub fn center_lat(&self) -> f64 {
-        match self {
-            Projection::Mercator(vp) => vp.center_lat,
-            Projection::Globe(g) => g.center_lat(),
-        }
+        self.0.center().1
     }
 }
 
@@ -389,4 +870,223 @@ mod tests {
         assert!((vp.half_h - 75.0).abs() < 1e-10);
         assert!((vp.scale - 3.0 * 200.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn segments_are_clipped_to_the_screen() {
+        // On screen: untouched; crossing: cut at the edge; beside: dropped
+        assert_eq!(clip_segment(100, 50, (10, 10), (20, 30)), Some(((10, 10), (20, 30))));
+        assert_eq!(clip_segment(100, 50, (-100, 25), (200, 25)), Some(((0, 25), (99, 25))));
+        assert_eq!(clip_segment(100, 50, (50, -50), (50, 10)), Some(((50, 0), (50, 10))));
+        assert_eq!(clip_segment(100, 50, (-10, 60), (60, -10)), Some(((1, 49), (50, 0))));
+        assert_eq!(clip_segment(100, 50, (-10, 0), (10, -20)), None);
+
+        // Zoomed in on a long line, only its on-screen stretch is drawn
+        let line = LineString::new((0..=3600).map(|i| (-180.0 + i as f64 * 0.1, 0.0)).collect());
+        let vp = Viewport::new(10.0, 0.0, 50.0, 200, 100);
+        let mut segments = Vec::new();
+        vp.segments(&line, &mut |a, b| segments.push((a, b)));
+        assert!(!segments.is_empty());
+        assert!(segments.iter().all(|&(a, b)| [a, b].iter().all(|&(x, y)| (0..200).contains(&x) && (0..100).contains(&y))));
+    }
+
+    #[test]
+    fn lines_cross_the_antimeridian_at_any_zoom() {
+        // Across the Bering Strait: one short segment at the dateline, drawn
+        // from either side of it
+        let line = LineString::new(vec![(179.0, 66.0), (179.8, 66.0), (-179.8, 66.0), (-179.0, 66.0)]);
+        assert!(line.bbox.0 <= -179.0 && line.bbox.2 >= 179.0);
+        for center in [179.9, -179.9, 180.0] {
+            let vp = Viewport::new(center, 66.0, 200.0, 200, 100);
+            let mut segments = Vec::new();
+            vp.segments(&line, &mut |a, b| segments.push((a, b)));
+            let crossing = segments.iter().find(|(a, b)| a.0 < 100 && b.0 > 100 || b.0 < 100 && a.0 > 100);
+            assert!(crossing.is_some(), "centered at {center}: {segments:?}");
+            // Nothing streaks back across the screen
+            assert!(segments.iter().all(|(a, b)| (a.0 - b.0).abs() < 200), "{segments:?}");
+        }
+
+        // A long straight segment seen close up is drawn, not taken for a
+        // jump across the map
+        let long = LineString::new(vec![(-10.0, 0.0), (10.0, 0.0)]);
+        let vp = Viewport::new(0.0, 0.0, 40.0, 200, 100);
+        let mut segments = Vec::new();
+        vp.segments(&long, &mut |a, b| segments.push((a, b)));
+        assert_eq!(segments, [((0, 50), (199, 50))]);
+    }
+
+    /// Bare plate carrée with only the required methods, as a downstream
+    /// crate would write one
+    #[derive(Clone)]
+    struct PlateCarree {
+        center: (f64, f64),
+        zoom: f64,
+        size: (usize, usize),
+    }
+
+    impl PlateCarree {
+        fn scale(&self) -> f64 {
+            self.zoom * self.size.0 as f64 / 360.0
+        }
+    }
+
+    impl MapProjection for PlateCarree {
+        fn name(&self) -> &'static str { "Plate carrée" }
+        fn size(&self) -> (usize, usize) { self.size }
+        fn set_size(&mut self, width: usize, height: usize) { self.size = (width, height); }
+        fn project(&self, lon: f64, lat: f64) -> Option<(i32, i32)> {
+            let x = self.size.0 as f64 / 2.0 + (lon - self.center.0) * self.scale();
+            let y = self.size.1 as f64 / 2.0 - (lat - self.center.1) * self.scale();
+            Some((x as i32, y as i32))
+        }
+        fn unproject(&self, px: i32, py: i32) -> Option<(f64, f64)> {
+            let lon = self.center.0 + (px as f64 - self.size.0 as f64 / 2.0) / self.scale();
+            let lat = self.center.1 - (py as f64 - self.size.1 as f64 / 2.0) / self.scale();
+            Some((lon, lat))
+        }
+        fn deg_to_pixels(&self, degrees: f64) -> f64 { degrees * self.scale() }
+        fn effective_zoom(&self) -> f64 { self.zoom }
+        fn center(&self) -> (f64, f64) { self.center }
+        fn visible_bounds(&self) -> (f64, f64, f64, f64) { (-180.0, -90.0, 180.0, 90.0) }
+        fn set_view(&mut self, lon: f64, lat: f64, zoom: f64) { (self.center, self.zoom) = ((lon, lat), zoom); }
+        fn pan(&mut self, dx: i32, dy: i32) {
+            self.center.0 += dx as f64 / self.scale();
+            self.center.1 -= dy as f64 / self.scale();
+        }
+        fn zoom_in(&mut self) { self.zoom *= 1.5; }
+        fn zoom_out(&mut self) { self.zoom /= 1.5; }
+        fn zoom_in_at(&mut self, _px: i32, _py: i32) { self.zoom_in(); }
+        fn zoom_out_at(&mut self, _px: i32, _py: i32) { self.zoom_out(); }
+    }
+
+    #[test]
+    fn outside_projections_break_lines_only_at_the_map_edge() {
+        let projection = PlateCarree { center: (170.0, 0.0), zoom: 4.0, size: (240, 120) };
+        let mut segments = Vec::new();
+        projection.segments(&LineString::new(vec![(160.0, 0.0), (180.0, 0.0), (-175.0, 0.0)]), &mut |a, b| segments.push((a, b)));
+        // The step across ±180 goes off the map's edge, not back across it
+        assert_eq!(segments, [((93, 60), (146, 60))]);
+
+        // Simplified to two points, a long straight coast is still drawn
+        let projection = PlateCarree { center: (0.0, 0.0), zoom: 16.0, size: (240, 120) };
+        let mut segments = Vec::new();
+        projection.segments(&LineString::new(vec![(-10.0, 0.0), (10.0, 0.0)]), &mut |a, b| segments.push((a, b)));
+        assert_eq!(segments, [((13, 60), (226, 60))]);
+    }
+
+    #[test]
+    fn outside_projections_render_and_cycle_back() {
+        let mut renderer = crate::map::MapRenderer::new();
+        crate::data::generate_simple_world(&mut renderer);
+        renderer.build_spatial_indexes();
+
+        let mut projection = Projection::new(PlateCarree { center: (0.0, 0.0), zoom: 1.0, size: (240, 120) });
+        let snapshot = projection.clone();
+        projection.pan(60, 0);
+        assert_eq!(snapshot.center_lon(), 0.0);
+
+        let layers = renderer.render(120, 30, &projection);
+        assert!((0..layers.coastlines.char_height()).any(|y| layers.coastlines.row_raw(y).iter().any(|&b| b != 0)));
+        assert!(layers.globe_outline.is_none());
+
+        projection.toggle();
+        assert_eq!(projection.name(), "Mercator");
+        assert!((projection.center_lon() - 90.0).abs() < 1e-9);
+    }
+
+    mod properties {
+        use super::*;
+        use crate::geo::{destination_point, great_circle_km, wrap_lon};
+        use proptest::prelude::*;
+
+        /// Screen size the properties are checked at
+        const SIZE: (usize, usize) = (400, 200);
+
+        /// Pixels are whole, so a round trip can land anywhere in the pixel
+        /// the point fell in: projecting the result again must give the same
+        /// pixel, give or take one for points on its edge
+        fn same_pixel(view: &dyn MapProjection, (px, py): (i32, i32)) -> Result<(), TestCaseError> {
+            let point = view.unproject(px, py);
+            prop_assert!(point.is_some(), "({px}, {py}) didn't unproject");
+            let (lon, lat) = point.unwrap();
+            let pixel = view.project(lon, lat);
+            prop_assert!(pixel.is_some(), "({lon}, {lat}) from ({px}, {py}) didn't reproject");
+            let (qx, qy) = pixel.unwrap();
+            prop_assert!((qx - px).abs() <= 1 && (qy - py).abs() <= 1, "({px}, {py}) came back as ({qx}, {qy})");
+            Ok(())
+        }
+
+        proptest! {
+            #[test]
+            fn mercator_round_trips(
+                lon in -180.0..180.0f64,
+                lat in -85.0..85.0f64,
+                zoom in 1.0..60.0f64,
+                (dx, dy) in (-1.0..1.0f64, -1.0..1.0f64),
+            ) {
+                // Center within half a screen of the point, so it's on screen.
+                // Latitude stretches by 1 / cos toward the poles.
+                let center_lon = wrap_lon(lon + dx * 90.0 / zoom);
+                let center_lat = (lat + dy * 45.0 / zoom * lat.to_radians().cos()).clamp(-85.0, 85.0);
+                let vp = Viewport::new(center_lon, center_lat, zoom, SIZE.0, SIZE.1);
+                let pixel = MapProjection::project(&vp, lon, lat);
+                prop_assert!(pixel.is_some(), "({lon}, {lat}) near the center didn't project");
+                let pixel = pixel.unwrap();
+                let point = MapProjection::unproject(&vp, pixel.0, pixel.1);
+                prop_assert!(point.is_some(), "{pixel:?} didn't unproject");
+                let (lon2, lat2) = point.unwrap();
+
+                // Within a pixel; Mercator stretches latitude, so a pixel is
+                // never more degrees of latitude than of longitude
+                let pixel_deg = 1.0 / vp.deg_to_pixels(1.0);
+                prop_assert!(wrap_lon(lon2 - lon).abs() <= pixel_deg * 1.01, "lon {lon} -> {lon2}");
+                prop_assert!((lat2 - lat).abs() <= pixel_deg * 1.01, "lat {lat} -> {lat2}");
+                same_pixel(&vp, pixel)?;
+            }
+
+            #[test]
+            fn globe_round_trips(
+                center_lon in -180.0..180.0f64,
+                center_lat in -90.0..90.0f64,
+                zoom in 1.0..60.0f64,
+                bearing in 0.0..360.0f64,
+                km in 0.0..7_000.0f64,
+            ) {
+                let globe = GlobeViewport::new(center_lon, center_lat, zoom * SIZE.0 as f64 * 0.35, SIZE.0, SIZE.1);
+                let (lon, lat) = destination_point(center_lon, center_lat, bearing, km);
+                let Some(pixel) = MapProjection::project(&globe, lon, lat) else {
+                    // Zoomed in, most of the hemisphere is off screen
+                    return Ok(());
+                };
+                let point = MapProjection::unproject(&globe, pixel.0, pixel.1);
+                prop_assert!(point.is_some(), "{pixel:?} is off the globe");
+                let (lon2, lat2) = point.unwrap();
+
+                // A pixel covers more ground toward the limb, by 1 / cos of
+                // the angle from the center
+                let angle = km / 6371.0;
+                let pixel_km = 111.2 / globe.deg_to_pixels(1.0) / angle.cos();
+                let off = great_circle_km(lon, lat, lon2, lat2);
+                prop_assert!(off <= pixel_km * 1.5, "({lon}, {lat}) -> ({lon2}, {lat2}): {off} km, pixel {pixel_km} km");
+                same_pixel(&globe, pixel)?;
+            }
+
+            #[test]
+            fn visible_bounds_contain_the_center(
+                lon in -180.0..180.0f64,
+                lat in -80.0..80.0f64,
+                zoom in 1.0..60.0f64,
+                kind in 0..Projection::NAMES.len(),
+            ) {
+                let mut projection = Projection::new(Viewport::new(0.0, 0.0, 1.0, SIZE.0, SIZE.1));
+                projection.switch_to(Projection::NAMES[kind]);
+                projection.view_mut().set_view(lon, lat, zoom);
+                let (center_lon, center_lat) = projection.view().center();
+                let (min_lon, min_lat, max_lon, max_lat) = projection.view().visible_bounds();
+                // Bounds may run past ±180 across the dateline
+                let inside_lon = [-360.0, 0.0, 360.0].iter().any(|&k| (min_lon..=max_lon).contains(&(center_lon + k)));
+                prop_assert!(inside_lon, "{}: lon {center_lon} outside {min_lon}..{max_lon}", Projection::NAMES[kind]);
+                prop_assert!((min_lat..=max_lat).contains(&center_lat), "{}: lat {center_lat} outside {min_lat}..{max_lat}", Projection::NAMES[kind]);
+            }
+        }
+    }
 }
@@ -1,11 +1,19 @@
-use crate::braille::BrailleCanvas;
-use crate::map::geometry::draw_line;
-use crate::map::globe::{self, GlobeViewport};
-use crate::geo::{normalize_lat, normalize_lon};
-use crate::map::projection::{Projection, Viewport, WRAP_OFFSETS, mercator_x, mercator_y};
+use crate::braille::{BrailleCanvas, CoverageCanvas};
+use crate::data::tiles::DecodedTile;
+use crate::map::country::CountryGrid;
+use crate::map::fonts::LabelFonts;
+use crate::map::geometry::{draw_line, draw_line_wu, fill_polygon, ring_centroid, simplify_douglas_peucker};
+use crate::map::globe;
+use crate::map::packed::{Mark, PackedCoords};
+use crate::map::infrastructure::{Facility, Infrastructure, Route};
+use crate::map::labels::{text_width, LabelPlacer};
+use crate::geo::{great_circle_km, normalize_lat, normalize_lon, wrap_lon};
+use crate::map::projection::{MapProjection, Projection, mercator_x, mercator_y};
 use crate::map::spatial::{FeatureGrid, SpatialGrid};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 /// Rendered map layers with separate canvases for color differentiation.
 /// Static layers use Rc — cache hits are a refcount bump, not a memcpy.
@@ -14,8 +22,133 @@ pub struct MapLayers {
     pub borders: Rc<BrailleCanvas>,
     pub states: Rc<BrailleCanvas>,
     pub counties: Rc<BrailleCanvas>,
+    pub infrastructure: InfraCanvases,
+    pub fills: FillCanvases,
     pub globe_outline: Option<Rc<BrailleCanvas>>,
-    pub labels: Vec<(u16, u16, String, f32)>,
+    /// Changes whenever the canvases above are redrawn; equal generations
+    /// mean identical canvases
+    pub generation: u64,
+    /// Lines, cities and facilities in view
+    pub features: usize,
+    pub labels: Vec<MapLabel>,
+    /// Airports and ports as (x, y, kind, destroyed)
+    pub facilities: Vec<(u16, u16, Infrastructure, bool)>,
+}
+
+/// A city marker or name, or a country or state name, placed in a cell
+#[derive(Clone, Debug)]
+pub struct MapLabel {
+    pub x: u16,
+    pub y: u16,
+    pub text: String,
+    /// A city's population left, as a fraction of what it had (0 =
+    /// destroyed); 1 for places
+    pub health: f32,
+    /// The city's lights are out
+    pub blackout: bool,
+    /// Drawn this bright relative to an unharmed city, so place names sit
+    /// back behind the cities
+    pub brightness: f32,
+}
+
+/// Road and railway canvases. Destroyed segments go on their own canvas so
+/// they can be drawn in a scorched color.
+#[derive(Clone)]
+pub struct InfraCanvases {
+    pub roads: Rc<BrailleCanvas>,
+    pub railways: Rc<BrailleCanvas>,
+    pub wreckage: Rc<BrailleCanvas>,
+}
+
+/// Filled areas, one canvas per layer: land, lakes, and countries (for
+/// tinting the ones a faction holds)
+#[derive(Clone)]
+pub struct FillCanvases {
+    pub land: Rc<BrailleCanvas>,
+    pub lakes: Rc<BrailleCanvas>,
+    pub countries: Rc<BrailleCanvas>,
+}
+
+/// Fill the on-screen copies of each polygon whose bbox reaches into
+/// `bounds`, which may run past ±180
+fn fill_polygons<'a>(
+    canvas: &mut BrailleCanvas,
+    polygons: impl Iterator<Item = &'a Polygon>,
+    (min_lon, min_lat, max_lon, max_lat): (f64, f64, f64, f64),
+    view: &dyn MapProjection,
+) {
+    for polygon in polygons {
+        let (p_min_lon, p_min_lat, p_max_lon, p_max_lat) = polygon.bbox;
+        let in_view = p_max_lat >= min_lat && p_min_lat <= max_lat
+            && [0.0, -360.0, 360.0].iter().any(|o| p_min_lon + o <= max_lon && p_max_lon + o >= min_lon);
+        if in_view {
+            view.polygon_rings(polygon, &mut |rings| fill_polygon(canvas, rings));
+        }
+    }
+}
+
+/// Draw a linestring's on-screen segments
+fn draw_linestring(canvas: &mut BrailleCanvas, line: &LineString, view: &dyn MapProjection) {
+    view.segments(line, &mut |(x0, y0), (x1, y1)| draw_line(canvas, x0, y0, x1, y1));
+}
+
+/// Fine pixels per braille pixel for anti-aliased lines
+const SUPERSAMPLE: usize = 4;
+
+/// Where a layer's lines go: straight onto a braille canvas, or, with
+/// anti-aliasing on, as Wu lines at `SUPERSAMPLE`× resolution into a
+/// coverage buffer that's dithered into one at the end
+enum LinePen<'a> {
+    Plain(BrailleCanvas, &'a dyn MapProjection),
+    /// The view rescaled to the fine resolution
+    Smooth(CoverageCanvas, BrailleCanvas, &'a dyn MapProjection),
+}
+
+impl<'a> LinePen<'a> {
+    /// Pen drawing onto `canvas`, which should be blank
+    fn new(canvas: BrailleCanvas, view: &'a dyn MapProjection, fine_view: Option<&'a dyn MapProjection>) -> Self {
+        match fine_view {
+            Some(fine) => Self::Smooth(CoverageCanvas::new(canvas.char_width(), canvas.char_height(), SUPERSAMPLE), canvas, fine),
+            None => Self::Plain(canvas, view),
+        }
+    }
+
+    fn draw(&mut self, line: &LineString) {
+        match self {
+            Self::Plain(canvas, view) => draw_linestring(canvas, line, *view),
+            Self::Smooth(coverage, _, fine) => fine.segments(line, &mut |(x0, y0), (x1, y1)| draw_line_wu(coverage, x0, y0, x1, y1)),
+        }
+    }
+
+    /// Draw every line, then finish
+    fn draw_all(mut self, lines: &[&LineString]) -> BrailleCanvas {
+        for line in lines {
+            self.draw(line);
+        }
+        self.finish()
+    }
+
+    fn finish(self) -> BrailleCanvas {
+        match self {
+            Self::Plain(canvas, _) => canvas,
+            Self::Smooth(coverage, mut canvas, _) => {
+                coverage.resolve_into(&mut canvas);
+                canvas
+            }
+        }
+    }
+}
+
+/// A blank canvas, reusing the buffer of last render's `old` one if nothing
+/// else still holds it
+fn recycle(old: Option<Rc<BrailleCanvas>>, width: usize, height: usize) -> BrailleCanvas {
+    match old.and_then(|rc| Rc::try_unwrap(rc).ok()) {
+        Some(mut canvas) => {
+            canvas.reset(width, height);
+            canvas
+        }
+        None => BrailleCanvas::new(width, height),
+    }
 }
 
 /// Format population as compact string (e.g., 1.2M, 500K)
@@ -35,6 +168,9 @@ fn format_population(pop: u64) -> String {
 pub struct Polygon {
     pub rings: Vec<Vec<(f64, f64)>>,
     pub bbox: (f64, f64, f64, f64), // min_lon, min_lat, max_lon, max_lat
+    /// The rings in normalized Mercator coordinates, so filling doesn't
+    /// redo the trig every frame
+    pub mercator: Vec<Vec<(f64, f64)>>,
 }
 
 impl Polygon {
@@ -51,12 +187,47 @@ impl Polygon {
             }
         }
 
+        let mercator = rings.iter()
+            .map(|ring| ring.iter().map(|&(lon, lat)| (mercator_x(lon), mercator_y(lat))).collect())
+            .collect();
         Self {
             rings,
             bbox: (min_lon, min_lat, max_lon, max_lat),
+            mercator,
         }
     }
 
+    /// Bytes the rings take up, in both forms
+    pub fn heap_bytes(&self) -> usize {
+        let rings = |rings: &Vec<Vec<(f64, f64)>>| {
+            rings.capacity() * std::mem::size_of::<Vec<(f64, f64)>>()
+                + rings.iter().map(|r| r.capacity() * std::mem::size_of::<(f64, f64)>()).sum::<usize>()
+        };
+        rings(&self.rings) + rings(&self.mercator)
+    }
+
+    /// Area of the exterior ring less its holes, in square degrees
+    pub fn area(&self) -> f64 {
+        let mut rings = self.rings.iter().map(|r| ring_centroid(r).0.abs());
+        let exterior = rings.next().unwrap_or(0.0);
+        exterior - rings.sum::<f64>()
+    }
+
+    /// Area-weighted centroid: the exterior ring's, pulled away from its holes
+    pub fn centroid(&self) -> (f64, f64) {
+        let (mut area, mut cx, mut cy) = (0.0, 0.0, 0.0);
+        for (i, ring) in self.rings.iter().enumerate() {
+            let (a, (x, y)) = ring_centroid(ring);
+            let a = if i == 0 { a.abs() } else { -a.abs() };
+            area += a;
+            cx += a * x;
+            cy += a * y;
+        }
+        if area.abs() < 1e-12 {
+            return self.rings.first().map_or((0.0, 0.0), |r| ring_centroid(r).1);
+        }
+        (cx / area, cy / area)
+    }
 }
 
 /// A geographic line (sequence of lon/lat coordinates) with precomputed bounding box
@@ -64,33 +235,119 @@ impl Polygon {
 #[derive(Clone)]
 pub struct LineString {
     pub bbox: (f64, f64, f64, f64), // min_lon, min_lat, max_lon, max_lat
-    /// Precomputed unit-sphere vectors — eliminates trig in globe hot loop.
-    /// Amortized O(1) per frame vs O(n) sin/cos calls.
-    pub vecs: Vec<globe::DVec3>,
+    /// The points, packed. Longitude runs on past ±180 where the line
+    /// crosses the antimeridian rather than jumping across the map.
+    pub coords: PackedCoords,
     /// Bounding sphere center on unit sphere for O(1) hemisphere culling.
     /// Single dot product replaces 4× lonlat_to_vec3 + 4 dot products.
     pub center_vec: globe::DVec3,
     /// Feature invisible when center_vec·forward < cull_dot.
     /// Precomputed as -sin(angular_radius + padding).
     pub cull_dot: f64,
-    /// Precomputed Mercator coordinates — eliminates trig in Mercator hot loop.
-    pub mercator: Vec<(f64, f64)>,
     /// Mercator-space bounding box for trig-free bbox early-out.
     pub mercator_bbox: (f64, f64, f64, f64),
+    /// Coarser copies as (tolerance in degrees, line), ascending. Zoomed
+    /// out, most of a detailed line's points fall in the same braille pixel.
+    pub simplified: Vec<(f64, LineString)>,
+    /// Bounds of consecutive runs of segments, so projections can skip the
+    /// stretches of a long line that are off screen without projecting them
+    pub chunks: Vec<LineChunk>,
+}
+
+/// Segments per `LineChunk`
+pub const CHUNK_SEGMENTS: usize = 32;
+
+/// A run of up to `CHUNK_SEGMENTS` segments of a line. Neighboring chunks
+/// share their end vertex.
+#[derive(Clone)]
+pub struct LineChunk {
+    /// First and last vertex, inclusive
+    pub start: usize,
+    pub end: usize,
+    /// Where the packed points pick up at `start`
+    pub from: Mark,
+    pub mercator_bbox: (f64, f64, f64, f64),
+    /// Center and angular radius (radians) of a cap holding the vertices
+    pub center_vec: globe::DVec3,
+    pub radius: f64,
+}
+
+impl LineChunk {
+    fn new(start: usize, end: usize, from: Mark, mercator: &[(f64, f64)], vecs: &[globe::DVec3]) -> Self {
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+        for &(mx, my) in &mercator[start..=end] {
+            (min_x, max_x) = (min_x.min(mx), max_x.max(mx));
+            (min_y, max_y) = (min_y.min(my), max_y.max(my));
+        }
+        let sum = vecs[start..=end].iter().copied().fold(globe::DVec3::ZERO, |acc, v| acc + v);
+        let center_vec = if sum.length_squared() > 1e-10 { sum.normalize() } else { globe::DVec3::X };
+        let min_dot = vecs[start..=end].iter().map(|v| v.dot(center_vec)).fold(1.0_f64, f64::min);
+        Self { start, end, from, mercator_bbox: (min_x, min_y, max_x, max_y), center_vec, radius: min_dot.clamp(-1.0, 1.0).acos() }
+    }
+}
+
+/// Tolerances (degrees) that lines are simplified to at load time
+const SIMPLIFY_TOLERANCES: [f64; 5] = [0.01, 0.03, 0.1, 0.3, 1.0];
+
+/// Tolerances (degrees) for tiers derived from finer data, about the detail
+/// of Natural Earth's 50m and 110m files
+const DERIVED_MEDIUM_TOLERANCE: f64 = 0.03;
+const DERIVED_LOW_TOLERANCE: f64 = 0.15;
+
+/// Simplified copies of `lines` for a coarser tier, leaving out any that
+/// span less than `tolerance` (islets, border stubs)
+fn derive_tier(lines: &[LineString], tolerance: f64) -> Vec<LineString> {
+    use rayon::prelude::*;
+    lines.par_iter()
+        .filter(|line| {
+            let (min_lon, min_lat, max_lon, max_lat) = line.bbox;
+            (max_lon - min_lon).max(max_lat - min_lat) >= tolerance
+        })
+        .map(|line| LineString::new(simplify_douglas_peucker(&line.points().collect::<Vec<_>>(), tolerance)))
+        .collect()
 }
 
 impl LineString {
     pub fn new(points: Vec<(f64, f64)>) -> Self {
+        // The bbox comes from the points as given, so one drawn edge to
+        // edge at ±180 still spans the map once they're unwrapped below
+        let bbox = points.iter().fold((f64::MAX, f64::MAX, f64::MIN, f64::MIN), |(x0, y0, x1, y1), &(lon, lat)| {
+            let lon = if (-180.0..=180.0).contains(&lon) { lon } else { wrap_lon(lon) };
+            (x0.min(lon), y0.min(lat), x1.max(lon), y1.max(lat))
+        });
+        // Longitude along the line without jumps: a step across the
+        // antimeridian carries on past ±180 instead of leaping the map
+        let mut unwrapped: Option<f64> = None;
+        let points: Vec<(f64, f64)> = points.into_iter()
+            .map(|(lon, lat)| {
+                let lon = match unwrapped {
+                    Some(prev) => prev + wrap_lon(lon - prev),
+                    None => lon,
+                };
+                unwrapped = Some(lon);
+                (lon, lat)
+            })
+            .collect();
+        Self { bbox, ..Self::from_coords(PackedCoords::pack(&points)) }
+    }
+
+    /// A line from points already packed, as read back from disk
+    pub fn from_coords(coords: PackedCoords) -> Self {
         let (mut min_lon, mut max_lon) = (f64::MAX, f64::MIN);
         let (mut min_lat, mut max_lat) = (f64::MAX, f64::MIN);
         let (mut merc_min_x, mut merc_max_x) = (f64::MAX, f64::MIN);
         let (mut merc_min_y, mut merc_max_y) = (f64::MAX, f64::MIN);
 
-        let mut mercator = Vec::with_capacity(points.len());
-
-        for &(lon, lat) in &points {
-            min_lon = min_lon.min(lon);
-            max_lon = max_lon.max(lon);
+        // Mercator and unit-sphere forms, for the bounds; they're decoded
+        // again as the line is drawn rather than kept
+        let mut mercator = Vec::with_capacity(coords.len());
+        let mut vecs = Vec::with_capacity(coords.len());
+        for (lon, lat) in coords.iter() {
+            // The bbox stays within ±180 for the spatial indexes, so a line
+            // crossing the antimeridian spans the whole width there
+            let wrapped = if (-180.0..=180.0).contains(&lon) { lon } else { wrap_lon(lon) };
+            min_lon = min_lon.min(wrapped);
+            max_lon = max_lon.max(wrapped);
             min_lat = min_lat.min(lat);
             max_lat = max_lat.max(lat);
 
@@ -101,13 +358,9 @@ impl LineString {
             merc_min_y = merc_min_y.min(my);
             merc_max_y = merc_max_y.max(my);
             mercator.push((mx, my));
+            vecs.push(globe::lonlat_to_vec3(lon, lat));
         }
 
-        // Phase 1 (blog: "coverage generation"): precompute unit-sphere vectors
-        let vecs: Vec<globe::DVec3> = points.iter()
-            .map(|&(lon, lat)| globe::lonlat_to_vec3(lon, lat))
-            .collect();
-
         // Bounding sphere: normalized centroid + max angular distance
         let sum = vecs.iter().copied().fold(globe::DVec3::ZERO, |acc, v| acc + v);
         let center_vec = if sum.length_squared() > 1e-10 {
@@ -122,23 +375,94 @@ impl LineString {
         // Small padding (0.05 rad ≈ 3°) for horizon continuity
         let cull_dot = -(angular_radius + 0.05).sin();
 
+        let chunks = coords.marks(CHUNK_SEGMENTS).into_iter()
+            .zip((0..vecs.len().saturating_sub(1)).step_by(CHUNK_SEGMENTS))
+            .map(|(from, start)| LineChunk::new(start, (start + CHUNK_SEGMENTS).min(vecs.len() - 1), from, &mercator, &vecs))
+            .collect();
+
         Self {
             bbox: (min_lon, min_lat, max_lon, max_lat),
-            vecs,
+            coords,
             center_vec,
             cull_dot,
-            mercator,
             mercator_bbox: (merc_min_x, merc_min_y, merc_max_x, merc_max_y),
+            simplified: Vec::new(),
+            chunks,
+        }
+    }
+
+    /// The points as lon/lat. Longitude runs on past ±180 where the line
+    /// crosses the antimeridian.
+    pub fn points(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.coords.iter()
+    }
+
+    /// The points in normalized Mercator coordinates
+    pub fn mercator(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.coords.iter().map(|(lon, lat)| (mercator_x(lon), mercator_y(lat)))
+    }
+
+    /// The points on the unit sphere
+    pub fn vecs(&self) -> impl Iterator<Item = globe::DVec3> + '_ {
+        self.coords.iter().map(|(lon, lat)| globe::lonlat_to_vec3(lon, lat))
+    }
+
+    /// A chunk's vertices in normalized Mercator coordinates, decoded from
+    /// where it starts
+    pub fn chunk_mercator(&self, chunk: &LineChunk) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.coords.iter_from(chunk.from).take(chunk.end - chunk.start + 1)
+            .map(|(lon, lat)| (mercator_x(lon), mercator_y(lat)))
+    }
+
+    /// A chunk's vertices on the unit sphere
+    pub fn chunk_vecs(&self, chunk: &LineChunk) -> impl Iterator<Item = globe::DVec3> + '_ {
+        self.coords.iter_from(chunk.from).take(chunk.end - chunk.start + 1)
+            .map(|(lon, lat)| globe::lonlat_to_vec3(lon, lat))
+    }
+
+    /// Bytes the line's points, chunks and simplified copies take up
+    pub fn heap_bytes(&self) -> usize {
+        self.coords.heap_bytes()
+            + self.chunks.capacity() * std::mem::size_of::<LineChunk>()
+            + self.simplified.capacity() * std::mem::size_of::<(f64, LineString)>()
+            + self.simplified.iter().map(|(_, l)| l.heap_bytes()).sum::<usize>()
+    }
+
+    /// Precompute Douglas-Peucker copies at `SIMPLIFY_TOLERANCES`, skipping
+    /// any that would drop less than a quarter of the points left
+    pub fn build_simplified(&mut self) {
+        let points: Vec<(f64, f64)> = self.points().collect();
+        let mut simplified: Vec<(f64, LineString)> = Vec::new();
+        let mut kept = points.len();
+        for tolerance in SIMPLIFY_TOLERANCES {
+            let coarser = simplify_douglas_peucker(&points, tolerance);
+            if coarser.len() * 4 <= kept * 3 {
+                kept = coarser.len();
+                // Already unwrapped: a chord of half the world mustn't be
+                // turned round the other way
+                simplified.push((tolerance, LineString::from_coords(PackedCoords::pack(&coarser))));
+            }
         }
+        self.simplified = simplified;
+    }
+
+    /// The coarsest copy simplified within `tolerance` degrees, or the line
+    /// itself
+    pub fn at_tolerance(&self, tolerance: f64) -> &LineString {
+        self.simplified.iter().rev().find(|(t, _)| *t <= tolerance).map_or(self, |(_, line)| line)
     }
 
     pub fn len(&self) -> usize {
-        self.vecs.len()
+        self.coords.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.coords.is_empty()
     }
 }
 
 /// Level of detail for map data
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Lod {
     Low,    // 110m - world view
     Medium, // 50m - continental
@@ -158,6 +482,22 @@ impl Lod {
     }
 }
 
+/// A collection of lines or points with its own spatial index
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Features {
+    Coastlines(Lod),
+    /// Borders come in Medium and High; Low is Medium
+    Borders(Lod),
+    States,
+    Counties,
+    Roads,
+    Railways,
+    Facilities,
+}
+
+/// Cities at least this big are labelled ahead of state names
+const MAJOR_CITY_POPULATION: u64 = 1_000_000;
+
 /// A city marker with position, name, and metadata
 #[derive(Clone)]
 pub struct City {
@@ -168,10 +508,59 @@ pub struct City {
     pub original_population: u64,
     pub is_capital: bool,
     pub is_megacity: bool,
+    /// ISO 3166 alpha-3 code of the country the city belongs to ("" if unknown)
+    pub country: String,
     pub radius_km: f64,
     /// Pre-formatted population string ("1.2M", "500K", etc.)
     /// Updated only when population changes — avoids per-frame format!()
     pub cached_pop_label: String,
+    /// Frames left without power after an EMP (0 = powered)
+    pub blackout_frames: u32,
+    /// Fraction of the population infected by a bio weapon (0 = clear)
+    pub infection: f32,
+    /// People who fled to other cities and are counted there; the city
+    /// only grows back to its original size less them
+    pub displaced: u64,
+    /// The English name when `name` is in another language (`--names`),
+    /// so the city can still be found by it
+    pub english_name: Option<String>,
+}
+
+/// Which kind of area a `PlaceLabel` names
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlaceKind {
+    Country,
+    State,
+}
+
+/// An area's name, shown at its label point once zoomed in far enough
+#[derive(Clone)]
+pub struct PlaceLabel {
+    pub lon: f64,
+    pub lat: f64,
+    pub name: String,
+    pub kind: PlaceKind,
+    /// Mercator zoom the name appears at
+    pub min_zoom: f64,
+}
+
+impl PlaceLabel {
+    /// Label for an area, appearing at a zoom set by Natural Earth's
+    /// `min_label` (a web-map zoom level, lower for bigger areas) where the
+    /// dataset has one. Each web-map level doubles the scale; countries
+    /// come in no sooner than the world view, states no sooner than their
+    /// borders.
+    pub fn new(lon: f64, lat: f64, name: &str, kind: PlaceKind, min_label: Option<f64>) -> Self {
+        let min_zoom = match kind {
+            PlaceKind::Country => min_label.map_or(1.5, |l| 2f64.powf(l - 3.0)).clamp(1.0, 16.0),
+            PlaceKind::State => min_label.map_or(6.0, |l| 2f64.powf(l - 4.0)).clamp(4.0, 64.0),
+        };
+        let name = match kind {
+            PlaceKind::Country => name.to_uppercase(),
+            PlaceKind::State => name.to_string(),
+        };
+        Self { lon, lat, name, kind, min_zoom }
+    }
 }
 
 impl City {
@@ -218,6 +607,23 @@ pub struct DisplaySettings {
     pub show_cities: bool,
     pub show_labels: bool,
     pub show_population: bool,
+    pub show_roads: bool,
+    pub show_railways: bool,
+    pub show_airports: bool,
+    pub show_ports: bool,
+    /// Anti-aliased coastlines and borders (Wu lines, dithered)
+    pub antialias: bool,
+    /// How far simplified lines may stray, in pixels (0 = full detail)
+    pub simplify: f64,
+    /// Land and lake area fills, and country fills under the faction tint
+    pub show_fills: bool,
+    /// Tint borders and country fills by the faction holding them
+    pub show_factions: bool,
+    /// Language to read city, country and state names in (Natural Earth's
+    /// `name_<lang>` fields; None = the default English names)
+    pub names: Option<String>,
+    /// Letter variants for capital and country labels
+    pub label_fonts: LabelFonts,
 }
 
 impl Default for DisplaySettings {
@@ -230,6 +636,16 @@ impl Default for DisplaySettings {
             show_cities: true,
             show_labels: true,
             show_population: false,
+            show_roads: true,
+            show_railways: true,
+            show_airports: true,
+            show_ports: true,
+            antialias: false,
+            simplify: 0.5,
+            show_fills: true,
+            show_factions: false,
+            names: None,
+            label_fonts: LabelFonts::Plain,
         }
     }
 }
@@ -242,26 +658,43 @@ struct RenderCacheKey {
     center_lon: i64,  // Quantized to 0.001 degrees
     center_lat: i64,
     zoom: i64,        // Quantized to 0.01
-    is_globe: bool,
+    projection: &'static str,
     show_coastlines: bool,
     show_borders: bool,
     show_states: bool,
     show_counties: bool,
+    show_roads: bool,
+    show_railways: bool,
+    antialias: bool,
+    simplify: i64,    // Quantized to 0.01
+    show_fills: bool,
+    show_factions: bool,
+    tile_generation: u64,
+    infrastructure_generation: u64,
 }
 
 impl RenderCacheKey {
-    fn new(center_lon: f64, center_lat: f64, zoom: f64, is_globe: bool, width: usize, height: usize, settings: &DisplaySettings) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    fn new(center_lon: f64, center_lat: f64, zoom: f64, projection: &'static str, width: usize, height: usize, settings: &DisplaySettings, tile_generation: u64, infrastructure_generation: u64) -> Self {
         Self {
             width,
             height,
             center_lon: (center_lon * 1000.0) as i64,
             center_lat: (center_lat * 1000.0) as i64,
             zoom: (zoom * 100.0) as i64,
-            is_globe,
+            projection,
             show_coastlines: settings.show_coastlines,
             show_borders: settings.show_borders,
             show_states: settings.show_states,
             show_counties: settings.show_counties,
+            show_roads: settings.show_roads,
+            show_railways: settings.show_railways,
+            antialias: settings.antialias,
+            simplify: (settings.simplify * 100.0) as i64,
+            show_fills: settings.show_fills,
+            show_factions: settings.show_factions,
+            tile_generation,
+            infrastructure_generation,
         }
     }
 }
@@ -269,13 +702,65 @@ impl RenderCacheKey {
 /// Cached static layer renders (Rc-shared with MapLayers)
 struct RenderCache {
     key: RenderCacheKey,
+    generation: u64,
+    /// Lines drawn into the canvases
+    lines: usize,
     coastlines: Rc<BrailleCanvas>,
     borders: Rc<BrailleCanvas>,
     states: Rc<BrailleCanvas>,
     counties: Rc<BrailleCanvas>,
+    infrastructure: InfraCanvases,
+    fills: FillCanvases,
+    globe_outline: Option<Rc<BrailleCanvas>>,
+}
+
+impl RenderCache {
+    fn heap_bytes(&self) -> usize {
+        let InfraCanvases { roads, railways, wreckage } = &self.infrastructure;
+        let FillCanvases { land, lakes, countries } = &self.fills;
+        [&self.coastlines, &self.borders, &self.states, &self.counties, roads, railways, wreckage, land, lakes, countries]
+            .into_iter()
+            .chain(&self.globe_outline)
+            .map(|c| c.heap_bytes())
+            .sum()
+    }
+}
+
+/// The canvases of a cache entry being replaced, up for reuse
+#[derive(Default)]
+struct RecycledCanvases {
+    coastlines: Option<Rc<BrailleCanvas>>,
+    borders: Option<Rc<BrailleCanvas>>,
+    states: Option<Rc<BrailleCanvas>>,
+    counties: Option<Rc<BrailleCanvas>>,
+    roads: Option<Rc<BrailleCanvas>>,
+    railways: Option<Rc<BrailleCanvas>>,
+    wreckage: Option<Rc<BrailleCanvas>>,
+    land: Option<Rc<BrailleCanvas>>,
+    lakes: Option<Rc<BrailleCanvas>>,
+    countries: Option<Rc<BrailleCanvas>>,
     globe_outline: Option<Rc<BrailleCanvas>>,
 }
 
+impl From<Option<RenderCache>> for RecycledCanvases {
+    fn from(cache: Option<RenderCache>) -> Self {
+        let Some(cache) = cache else { return Self::default() };
+        Self {
+            coastlines: Some(cache.coastlines),
+            borders: Some(cache.borders),
+            states: Some(cache.states),
+            counties: Some(cache.counties),
+            roads: Some(cache.infrastructure.roads),
+            railways: Some(cache.infrastructure.railways),
+            wreckage: Some(cache.infrastructure.wreckage),
+            land: Some(cache.fills.land),
+            lakes: Some(cache.fills.lakes),
+            countries: Some(cache.fills.countries),
+            globe_outline: cache.globe_outline,
+        }
+    }
+}
+
 /// Fast land/water lookup grid with two-tier conservative approximation.
 /// Coarse 1° tier (360×180) classifies cells as all-land/all-water/mixed.
 /// Fine 0.1° tier (3600×1800) bitmap provides exact checks for coastal cells.
@@ -292,7 +777,7 @@ impl LandGrid {
     const RESOLUTION: f64 = 0.025;   // Fine tier: 0.025° per cell (~2.8km)
     const COARSE_RATIO: usize = 40;  // Fine cells per coarse cell (1° / 0.025°)
     const TOTAL_BITS: usize = Self::WIDTH * Self::HEIGHT; // 103,680,000
-    const BITMAP_LEN: usize = (Self::TOTAL_BITS + 63) / 64; // ~12.3MB
+    const BITMAP_LEN: usize = Self::TOTAL_BITS.div_ceil(64); // ~12.3MB
     /// Cache format version — bump when resolution or layout changes
     const CACHE_VERSION: u32 = 1;
 
@@ -303,15 +788,21 @@ impl LandGrid {
         }
     }
 
+    /// Bytes the two tiers take up
+    pub fn heap_bytes(&self) -> usize {
+        self.bitmap.capacity() * 8 + self.coarse.capacity()
+    }
+
     /// Build coarse 1° tier from fine bitmap.
     /// Each 1° cell covers COARSE_RATIO×COARSE_RATIO fine cells; classified as
-    /// all-water (0), mixed (1), or all-land (2).
+    /// all-water (0), mixed (1), or all-land (2). Rows in parallel.
     fn build_coarse(&mut self) {
+        use rayon::prelude::*;
         let r = Self::COARSE_RATIO;
         let all_land = r * r;
-        self.coarse = vec![0u8; 360 * 180];
-        for coarse_lat in 0..180usize {
-            for coarse_lon in 0..360usize {
+        let mut coarse = vec![0u8; 360 * 180];
+        coarse.par_chunks_mut(360).enumerate().for_each(|(coarse_lat, row)| {
+            for (coarse_lon, cell) in row.iter_mut().enumerate() {
                 let fine_lat_start = coarse_lat * r;
                 let fine_lon_start = coarse_lon * r;
                 let land_count = (0..r).flat_map(|fl| {
@@ -321,13 +812,14 @@ impl LandGrid {
                     self.get_bit(fine_idx)
                 }).count();
 
-                self.coarse[coarse_lat * 360 + coarse_lon] = match land_count {
+                *cell = match land_count {
                     0 => 0,              // all water
                     n if n == all_land => 2, // all land
                     _ => 1,              // mixed - needs fine check
                 };
             }
-        }
+        });
+        self.coarse = coarse;
     }
 
     #[inline(always)]
@@ -391,63 +883,71 @@ impl LandGrid {
 
     /// Scanline rasterization: for each row, compute edge crossings once
     /// then fill spans between pairs (even-odd rule). O(rows × edges)
-    /// vs old brute-force O(cells × edges). Parallelized with rayon.
+    /// vs old brute-force O(cells × edges). Parallelized with rayon over
+    /// bands of rows, each writing its own slice of the bitmap, so one huge
+    /// polygon is split across threads instead of landing on one.
     pub fn build_scanline(polygons: &[Polygon]) -> Self {
         use rayon::prelude::*;
+        // Rows are whole words (14400 = 225 × 64), so bands split cleanly
+        const ROW_WORDS: usize = LandGrid::WIDTH / 64;
 
-        let chunk_size = (polygons.len() / rayon::current_num_threads().max(1)).max(1);
-        let sub_bitmaps: Vec<Vec<u64>> = polygons.par_chunks(chunk_size)
-            .map(|chunk| {
-                let mut bitmap = vec![0u64; Self::BITMAP_LEN];
+        let mut grid = Self::new();
+        grid.bitmap
+            .par_chunks_mut(ROW_WORDS * Self::COARSE_RATIO)
+            .enumerate()
+            .for_each(|(band, words)| {
+                let row_start = band * Self::COARSE_RATIO;
+                let row_end = row_start + words.len() / ROW_WORDS;
+                let band_min = -90.0 + row_start as f64 * Self::RESOLUTION;
+                let band_max = -90.0 + row_end as f64 * Self::RESOLUTION;
+                let mut edges = Vec::new();
                 let mut crossings = Vec::new();
-                for polygon in chunk {
+
+                for polygon in polygons {
                     let (_, min_lat, _, max_lat) = polygon.bbox;
-                    let lat_start = (((min_lat + 90.0) / Self::RESOLUTION).floor() as usize).saturating_sub(1);
-                    let lat_end = (((max_lat + 90.0) / Self::RESOLUTION).ceil() as usize + 1).min(Self::HEIGHT);
+                    if max_lat < band_min || min_lat > band_max {
+                        continue;
+                    }
 
-                    for lat_idx in lat_start..lat_end {
+                    // Only the edges reaching into this band
+                    edges.clear();
+                    for ring in &polygon.rings {
+                        let n = ring.len();
+                        if n < 3 { continue; }
+                        for i in 0..n {
+                            let j = if i + 1 < n { i + 1 } else { 0 };
+                            let ((x1, y1), (x2, y2)) = (ring[i], ring[j]);
+                            if y1.max(y2) >= band_min && y1.min(y2) <= band_max {
+                                edges.push((x1, y1, x2, y2));
+                            }
+                        }
+                    }
+                    if edges.is_empty() { continue; }
+
+                    for lat_idx in row_start..row_end {
                         let lat = -90.0 + (lat_idx as f64 + 0.5) * Self::RESOLUTION;
 
                         crossings.clear();
-                        for ring in &polygon.rings {
-                            let n = ring.len();
-                            if n < 3 { continue; }
-                            for i in 0..n {
-                                let j = if i + 1 < n { i + 1 } else { 0 };
-                                let (x1, y1) = ring[i];
-                                let (x2, y2) = ring[j];
-                                if (y1 <= lat && y2 > lat) || (y2 <= lat && y1 > lat) {
-                                    let t = (lat - y1) / (y2 - y1);
-                                    crossings.push(x1 + t * (x2 - x1));
-                                }
+                        for &(x1, y1, x2, y2) in &edges {
+                            if (y1 <= lat && y2 > lat) || (y2 <= lat && y1 > lat) {
+                                let t = (lat - y1) / (y2 - y1);
+                                crossings.push(x1 + t * (x2 - x1));
                             }
                         }
 
                         crossings.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
 
+                        let row = &mut words[(lat_idx - row_start) * ROW_WORDS..][..ROW_WORDS];
                         for pair in crossings.chunks_exact(2) {
                             let col_start = ((pair[0] + 180.0) / Self::RESOLUTION).ceil() as usize;
                             let col_end = (((pair[1] + 180.0) / Self::RESOLUTION).floor() as usize + 1).min(Self::WIDTH);
-                            let row_base = lat_idx * Self::WIDTH;
                             for lon_idx in col_start..col_end {
-                                let idx = row_base + lon_idx;
-                                if idx < Self::TOTAL_BITS {
-                                    bitmap[idx / 64] |= 1u64 << (idx % 64);
-                                }
+                                row[lon_idx / 64] |= 1u64 << (lon_idx % 64);
                             }
                         }
                     }
                 }
-                bitmap
-            })
-            .collect();
-
-        let mut grid = Self::new();
-        for sub in sub_bitmaps {
-            for (i, bits) in sub.iter().enumerate() {
-                grid.bitmap[i] |= bits;
-            }
-        }
+            });
 
         grid.build_coarse();
         grid
@@ -504,6 +1004,12 @@ impl LandGrid {
     }
 }
 
+/// Cell size of the feature indexes, in degrees
+const FEATURE_CELL_SIZE: f64 = 5.0;
+
+/// Cell size of the city index, in degrees
+const CITY_CELL_SIZE: f64 = 10.0;
+
 /// Map renderer with multi-resolution coastline data and spatial indexes
 pub struct MapRenderer {
     pub coastlines_low: Vec<LineString>,
@@ -513,13 +1019,40 @@ pub struct MapRenderer {
     pub borders_high: Vec<LineString>,
     pub states: Vec<LineString>,
     pub counties: Vec<LineString>,
+    pub roads: Vec<Route>,
+    pub railways: Vec<Route>,
+    /// Airports and ports
+    pub facilities: Vec<Facility>,
     pub land_polygons_low: Vec<Polygon>,
     pub land_polygons_medium: Vec<Polygon>,
     pub land_polygons_high: Vec<Polygon>,
     pub land_grid: Option<LandGrid>,
+    /// Lake polygons, filled over land
+    pub lakes: Vec<Polygon>,
+    /// Admin-0 polygons tagged with their ISO alpha-3 code
+    pub country_polygons: Vec<(String, Polygon)>,
+    /// Country names as (ISO alpha-3 code, name)
+    pub country_names: Vec<(String, String)>,
+    /// English country names as (code, name), for countries whose name
+    /// in `country_names` is in another language (`--names`)
+    pub country_names_en: Vec<(String, String)>,
+    /// Country and state names
+    pub place_labels: Vec<PlaceLabel>,
+    pub country_grid: Option<CountryGrid>,
     pub city_grid: SpatialGrid<City>,
     pub settings: DisplaySettings,
+    /// Decoded vector tiles for the current viewport. When non-empty these
+    /// replace the Natural Earth coastline and border layers.
+    tiles: Vec<Arc<DecodedTile>>,
+    tile_generation: u64,
+    /// Bumped whenever infrastructure is destroyed, to invalidate the cache
+    infrastructure_generation: u64,
     cache: RefCell<Option<RenderCache>>,
+    /// Changes whenever the loaded data does (`invalidate_cache`), and is
+    /// never the same for two renderers
+    data_generation: u64,
+    /// Last generation handed to a cache entry
+    render_generation: Cell<u64>,
     // Conservative-approximation spatial indexes for O(1) viewport queries
     coastline_grid_low: FeatureGrid,
     coastline_grid_medium: FeatureGrid,
@@ -528,6 +1061,17 @@ pub struct MapRenderer {
     border_grid_high: FeatureGrid,
     state_grid: FeatureGrid,
     county_grid: FeatureGrid,
+    road_grid: FeatureGrid,
+    railway_grid: FeatureGrid,
+    facility_grid: FeatureGrid,
+}
+
+/// Source of `MapRenderer::data_generation`, shared so a new renderer
+/// never repeats an old one's
+static DATA_GENERATIONS: AtomicU64 = AtomicU64::new(0);
+
+fn next_data_generation() -> u64 {
+    DATA_GENERATIONS.fetch_add(1, Ordering::Relaxed) + 1
 }
 
 impl MapRenderer {
@@ -540,25 +1084,42 @@ impl MapRenderer {
             borders_high: Vec::new(),
             states: Vec::new(),
             counties: Vec::new(),
+            roads: Vec::new(),
+            railways: Vec::new(),
+            facilities: Vec::new(),
             land_polygons_low: Vec::new(),
             land_polygons_medium: Vec::new(),
             land_polygons_high: Vec::new(),
             land_grid: None,
-            city_grid: SpatialGrid::new(10.0),
+            lakes: Vec::new(),
+            country_polygons: Vec::new(),
+            country_names: Vec::new(),
+            country_names_en: Vec::new(),
+            place_labels: Vec::new(),
+            country_grid: None,
+            city_grid: SpatialGrid::new(CITY_CELL_SIZE),
             settings: DisplaySettings::default(),
+            tiles: Vec::new(),
+            tile_generation: 0,
+            infrastructure_generation: 0,
             cache: RefCell::new(None),
-            coastline_grid_low: FeatureGrid::new(5.0),
-            coastline_grid_medium: FeatureGrid::new(5.0),
-            coastline_grid_high: FeatureGrid::new(5.0),
-            border_grid_medium: FeatureGrid::new(5.0),
-            border_grid_high: FeatureGrid::new(5.0),
-            state_grid: FeatureGrid::new(5.0),
-            county_grid: FeatureGrid::new(5.0),
+            data_generation: next_data_generation(),
+            render_generation: Cell::new(0),
+            coastline_grid_low: FeatureGrid::new(FEATURE_CELL_SIZE),
+            coastline_grid_medium: FeatureGrid::new(FEATURE_CELL_SIZE),
+            coastline_grid_high: FeatureGrid::new(FEATURE_CELL_SIZE),
+            border_grid_medium: FeatureGrid::new(FEATURE_CELL_SIZE),
+            border_grid_high: FeatureGrid::new(FEATURE_CELL_SIZE),
+            state_grid: FeatureGrid::new(FEATURE_CELL_SIZE),
+            county_grid: FeatureGrid::new(FEATURE_CELL_SIZE),
+            road_grid: FeatureGrid::new(FEATURE_CELL_SIZE),
+            railway_grid: FeatureGrid::new(FEATURE_CELL_SIZE),
+            facility_grid: FeatureGrid::new(FEATURE_CELL_SIZE),
         }
     }
 
     /// Get coastlines for the given LOD
-    fn get_coastlines(&self, lod: Lod) -> &Vec<LineString> {
+    pub fn get_coastlines(&self, lod: Lod) -> &Vec<LineString> {
         match lod {
             Lod::High => {
                 if !self.coastlines_high.is_empty() {
@@ -580,8 +1141,20 @@ impl MapRenderer {
         }
     }
 
+    /// Get land polygons for the given LOD, falling back to whichever
+    /// tiers were loaded
+    pub fn get_land_polygons(&self, lod: Lod) -> &Vec<Polygon> {
+        let tiers = [&self.land_polygons_low, &self.land_polygons_medium, &self.land_polygons_high];
+        let want = match lod {
+            Lod::Low => 0,
+            Lod::Medium => 1,
+            Lod::High => 2,
+        };
+        tiers[..=want].iter().rev().chain(&tiers[want + 1..]).find(|t| !t.is_empty()).unwrap_or(&tiers[0])
+    }
+
     /// Get borders for the given LOD
-    fn get_borders(&self, lod: Lod) -> &Vec<LineString> {
+    pub fn get_borders(&self, lod: Lod) -> &Vec<LineString> {
         match lod {
             Lod::High => {
                 if !self.borders_high.is_empty() {
@@ -647,7 +1220,7 @@ impl MapRenderer {
         if n == 0 {
             return raw;
         }
-        let mut seen = vec![0u64; (n + 63) / 64];
+        let mut seen = vec![0u64; n.div_ceil(64)];
         let mut unique = Vec::with_capacity(raw.len().min(n));
         for idx in raw {
             let word = idx / 64;
@@ -660,14 +1233,31 @@ impl MapRenderer {
         unique
     }
 
-    /// Build spatial indexes for all feature collections in parallel.
+    /// Fill in empty Low and Medium tiers from the finest data loaded, so
+    /// a single 10m file still renders quickly zoomed out
+    pub fn derive_missing_lods(&mut self) {
+        if self.coastlines_medium.is_empty() && !self.coastlines_high.is_empty() {
+            self.coastlines_medium = derive_tier(&self.coastlines_high, DERIVED_MEDIUM_TOLERANCE);
+        }
+        if self.coastlines_low.is_empty() && !self.coastlines_medium.is_empty() {
+            self.coastlines_low = derive_tier(&self.coastlines_medium, DERIVED_LOW_TOLERANCE);
+        }
+        if self.borders_medium.is_empty() && !self.borders_high.is_empty() {
+            self.borders_medium = derive_tier(&self.borders_high, DERIVED_MEDIUM_TOLERANCE);
+        }
+    }
+
+    /// Build spatial indexes for all feature collections in parallel, and
+    /// the lines' simplified copies. Missing LOD tiers are derived first.
     /// Order is fixed: the Vec indices match the grid assignments below.
     pub fn build_spatial_indexes(&mut self) {
         use rayon::prelude::*;
-        const CELL_SIZE: f64 = 5.0;
+        const CELL_SIZE: f64 = FEATURE_CELL_SIZE;
+
+        self.derive_missing_lods();
 
         // Collect bboxes upfront so we can release the borrow on self.
-        // Order must match the assignment sequence below (0=coast_low, ..., 6=county).
+        // Order must match the assignment sequence below (0=coast_low, ..., 9=facility).
         let bbox_sets: Vec<Vec<(f64, f64, f64, f64)>> = vec![
             self.coastlines_low.iter().map(|l| l.bbox).collect(),
             self.coastlines_medium.iter().map(|l| l.bbox).collect(),
@@ -676,9 +1266,12 @@ impl MapRenderer {
             self.borders_high.iter().map(|l| l.bbox).collect(),
             self.states.iter().map(|l| l.bbox).collect(),
             self.counties.iter().map(|l| l.bbox).collect(),
+            self.roads.iter().map(|r| r.line.bbox).collect(),
+            self.railways.iter().map(|r| r.line.bbox).collect(),
+            self.facilities.iter().map(|f| (f.lon, f.lat, f.lon, f.lat)).collect(),
         ];
 
-        // Build all 7 grids in parallel
+        // Build all 10 grids in parallel
         let grids: Vec<FeatureGrid> = bbox_sets
             .into_par_iter()
             .map(|bbs| FeatureGrid::build(bbs.into_iter(), CELL_SIZE))
@@ -692,6 +1285,150 @@ impl MapRenderer {
         self.border_grid_high = grids.next().unwrap();
         self.state_grid = grids.next().unwrap();
         self.county_grid = grids.next().unwrap();
+        self.road_grid = grids.next().unwrap();
+        self.railway_grid = grids.next().unwrap();
+        self.facility_grid = grids.next().unwrap();
+
+        // Simplified copies of the drawn lines, for when they're zoomed out
+        for lines in [
+            &mut self.coastlines_low, &mut self.coastlines_medium, &mut self.coastlines_high,
+            &mut self.borders_medium, &mut self.borders_high, &mut self.states, &mut self.counties,
+        ] {
+            lines.par_iter_mut().for_each(LineString::build_simplified);
+        }
+    }
+
+    /// Rebuild one collection's index (and its lines' simplified copies)
+    /// after it was replaced, and drop the cached layers drawn from it
+    pub fn reindex(&mut self, features: Features) {
+        use rayon::prelude::*;
+        fn line_grid(lines: &mut [LineString]) -> FeatureGrid {
+            lines.par_iter_mut().for_each(LineString::build_simplified);
+            FeatureGrid::build(lines.iter().map(|l| l.bbox), FEATURE_CELL_SIZE)
+        }
+        match features {
+            Features::Coastlines(Lod::Low) => self.coastline_grid_low = line_grid(&mut self.coastlines_low),
+            Features::Coastlines(Lod::Medium) => self.coastline_grid_medium = line_grid(&mut self.coastlines_medium),
+            Features::Coastlines(Lod::High) => self.coastline_grid_high = line_grid(&mut self.coastlines_high),
+            Features::Borders(Lod::High) => self.border_grid_high = line_grid(&mut self.borders_high),
+            Features::Borders(_) => self.border_grid_medium = line_grid(&mut self.borders_medium),
+            Features::States => self.state_grid = line_grid(&mut self.states),
+            Features::Counties => self.county_grid = line_grid(&mut self.counties),
+            Features::Roads => {
+                self.road_grid = FeatureGrid::build(self.roads.iter().map(|r| r.line.bbox), FEATURE_CELL_SIZE);
+            }
+            Features::Railways => {
+                self.railway_grid = FeatureGrid::build(self.railways.iter().map(|r| r.line.bbox), FEATURE_CELL_SIZE);
+            }
+            Features::Facilities => {
+                self.facility_grid = FeatureGrid::build(self.facilities.iter().map(|f| (f.lon, f.lat, f.lon, f.lat)), FEATURE_CELL_SIZE);
+            }
+        }
+        self.invalidate_cache();
+    }
+
+    /// Effective zooms at which states, then counties, are drawn. Detail
+    /// layers come in sooner on the globe, whose effective zoom runs lower.
+    pub fn detail_zooms(is_globe: bool) -> (f64, f64) {
+        if is_globe { (1.5, 3.5) } else { (4.0, 7.0) }
+    }
+
+    /// Replace the counties with lines streamed in from chunks, which come
+    /// with their simplified copies already built
+    pub fn set_counties(&mut self, lines: Vec<LineString>) {
+        self.counties = lines;
+        self.county_grid = FeatureGrid::build(self.counties.iter().map(|l| l.bbox), FEATURE_CELL_SIZE);
+        self.invalidate_cache();
+    }
+
+    /// Bytes held per layer, biggest first: lines with their simplified
+    /// copies, polygons, the spatial indexes and the cached canvases
+    pub fn memory(&self) -> Vec<(&'static str, usize)> {
+        use std::mem::size_of;
+        let lines = |tiers: &[&Vec<LineString>]| -> usize {
+            tiers.iter().flat_map(|t| t.iter()).map(|l| size_of::<LineString>() + l.heap_bytes()).sum()
+        };
+        let polygons = |tiers: &[&Vec<Polygon>]| -> usize {
+            tiers.iter().flat_map(|t| t.iter()).map(|p| size_of::<Polygon>() + p.heap_bytes()).sum()
+        };
+        let routes = |routes: &[Route]| -> usize { routes.iter().map(|r| size_of::<Route>() + r.line.heap_bytes()).sum() };
+        let grids = [
+            &self.coastline_grid_low, &self.coastline_grid_medium, &self.coastline_grid_high,
+            &self.border_grid_medium, &self.border_grid_high, &self.state_grid, &self.county_grid,
+            &self.road_grid, &self.railway_grid, &self.facility_grid,
+        ];
+        let indexes = grids.iter().map(|g| g.heap_bytes()).sum::<usize>()
+            + self.land_grid.as_ref().map_or(0, LandGrid::heap_bytes)
+            + self.country_grid.as_ref().map_or(0, CountryGrid::heap_bytes);
+        let countries = self.country_polygons.iter().map(|(code, p)| size_of::<(String, Polygon)>() + code.capacity() + p.heap_bytes()).sum::<usize>()
+            + self.country_names.iter().chain(&self.country_names_en).map(|(code, name)| size_of::<(String, String)>() + code.capacity() + name.capacity()).sum::<usize>();
+        let cities = self.city_grid.heap_bytes()
+            + self.city_grid.iter().map(|c| c.name.capacity() + c.country.capacity() + c.english_name.as_ref().map_or(0, String::capacity)).sum::<usize>();
+
+        let mut layers = vec![
+            ("coastlines", lines(&[&self.coastlines_low, &self.coastlines_medium, &self.coastlines_high])),
+            ("borders", lines(&[&self.borders_medium, &self.borders_high])),
+            ("states", lines(&[&self.states])),
+            ("counties", lines(&[&self.counties])),
+            ("roads", routes(&self.roads)),
+            ("railways", routes(&self.railways)),
+            ("land", polygons(&[&self.land_polygons_low, &self.land_polygons_medium, &self.land_polygons_high])),
+            ("lakes", polygons(&[&self.lakes])),
+            ("countries", countries),
+            ("cities", cities),
+            ("indexes", indexes),
+            ("cache", self.cache.borrow().as_ref().map_or(0, RenderCache::heap_bytes)),
+        ];
+        layers.sort_by_key(|&(_, bytes)| std::cmp::Reverse(bytes));
+        layers
+    }
+
+    /// Whether any of a detail tier's coastlines, borders or land is loaded
+    pub fn has_tier(&self, lod: Lod) -> bool {
+        match lod {
+            Lod::Low => !self.coastlines_low.is_empty() || !self.land_polygons_low.is_empty(),
+            Lod::Medium => !self.coastlines_medium.is_empty() || !self.land_polygons_medium.is_empty(),
+            Lod::High => !self.coastlines_high.is_empty() || !self.borders_high.is_empty() || !self.land_polygons_high.is_empty(),
+        }
+    }
+
+    /// Free a detail tier's coastlines, borders and land to save memory.
+    /// Drawing falls back to the next coarser tier until it's loaded
+    /// again. Medium borders stay: they're drawn zoomed out too.
+    pub fn drop_tier(&mut self, lod: Lod) {
+        match lod {
+            Lod::Low => return,
+            Lod::Medium => {
+                self.coastlines_medium = Vec::new();
+                self.coastline_grid_medium = FeatureGrid::new(FEATURE_CELL_SIZE);
+                self.land_polygons_medium = Vec::new();
+            }
+            Lod::High => {
+                self.coastlines_high = Vec::new();
+                self.coastline_grid_high = FeatureGrid::new(FEATURE_CELL_SIZE);
+                self.borders_high = Vec::new();
+                self.border_grid_high = FeatureGrid::new(FEATURE_CELL_SIZE);
+                self.land_polygons_high = Vec::new();
+            }
+        }
+        self.invalidate_cache();
+    }
+
+    /// Drop the cached static layers, so the next frame draws them afresh
+    pub fn invalidate_cache(&mut self) {
+        *self.cache.get_mut() = None;
+        self.data_generation = next_data_generation();
+    }
+
+    /// For images drawn from the data outside the render cache, such as
+    /// the kitty basemap: equal generations mean the same data
+    pub fn data_generation(&self) -> u64 {
+        self.data_generation
+    }
+
+    /// Remove every city
+    pub fn clear_cities(&mut self) {
+        self.city_grid.clear();
     }
 
     /// Get max number of cities to show based on zoom
@@ -715,118 +1452,186 @@ impl MapRenderer {
         }
     }
 
-    /// Render all map features to separate layered canvases
+    /// Render all map features to separate layered canvases. Everything
+    /// goes through `MapProjection`, so any projection renders the same way.
     pub fn render(&self, width: usize, height: usize, projection: &Projection) -> MapLayers {
-        match projection {
-            Projection::Mercator(viewport) => self.render_mercator(width, height, viewport),
-            Projection::Globe(globe) => self.render_globe(width, height, globe),
-        }
-    }
-
-    /// Mercator render path (existing logic, unchanged)
-    fn render_mercator(&self, width: usize, height: usize, viewport: &Viewport) -> MapLayers {
-        let lod = Lod::from_zoom(viewport.zoom);
+        let view = projection.view();
+        let zoom = view.effective_zoom();
+        let is_globe = view.is_globe();
+        let lod = Lod::from_zoom(zoom);
         let mut labels = Vec::new();
 
-        // Viewport geographic bounds (exact Mercator unproject, not linear approx)
-        let vp_min_lon = viewport.center_lon - (180.0 / viewport.zoom);
-        let vp_max_lon = viewport.center_lon + (180.0 / viewport.zoom);
-        let (_, top_lat) = viewport.unproject(0, 0);
-        let (_, bottom_lat) = viewport.unproject(0, viewport.height as i32);
-        let vp_min_lat = bottom_lat.max(-85.0);
-        let vp_max_lat = top_lat.min(85.0);
-
-        // Padded bounds for FeatureGrid queries: match draw_linestring's 50px
-        // screen-space padding converted to geographic degrees at current zoom
-        let deg_per_px = 360.0 / (viewport.zoom * width as f64 * 2.0);
-        let pad = (50.0 * deg_per_px).max(5.0);
+        let (vp_min_lon, vp_min_lat, vp_max_lon, vp_max_lat) = view.visible_bounds();
+
+        // Padded bounds for FeatureGrid queries: 50px of screen-space padding
+        // converted to geographic degrees at current zoom
+        let pad = (50.0 / view.deg_to_pixels(1.0)).max(5.0);
         let fg_min_lon = vp_min_lon - pad;
         let fg_max_lon = vp_max_lon + pad;
         let fg_min_lat = (vp_min_lat - pad).max(-90.0);
         let fg_max_lat = (vp_max_lat + pad).min(90.0);
+        let fg_bounds = (fg_min_lon, fg_min_lat, fg_max_lon, fg_max_lat);
 
-        // Compute wrap offsets once per frame — skip ±360 when viewport doesn't cross dateline.
-        // Must use padded bounds (fg_*) not viewport bounds, since the spatial query fetches
-        // features in the padded region and draw_linestring needs matching offsets to render them.
-        let offsets = Self::needed_wrap_offsets(fg_min_lon, fg_max_lon);
+        let (states_zoom, counties_zoom) = Self::detail_zooms(is_globe);
 
         // Check if we can use cached static layers
-        let cache_key = RenderCacheKey::new(viewport.center_lon, viewport.center_lat, viewport.zoom, false, width, height, &self.settings);
+        let (center_lon, center_lat) = view.center();
+        let cache_key = RenderCacheKey::new(center_lon, center_lat, zoom, view.name(), width, height, &self.settings, self.tile_generation, self.infrastructure_generation);
         let cache_borrow = self.cache.borrow();
         let use_cache = cache_borrow.as_ref().map(|c| c.key == cache_key).unwrap_or(false);
 
-        let (coastlines_canvas, borders_canvas, states_canvas, counties_canvas, _globe_outline) = if use_cache {
+        let (coastlines_canvas, borders_canvas, states_canvas, counties_canvas, infrastructure, fills, globe_outline, generation, lines) = if use_cache {
             let cache = cache_borrow.as_ref().unwrap();
             (
                 Rc::clone(&cache.coastlines),
                 Rc::clone(&cache.borders),
                 Rc::clone(&cache.states),
                 Rc::clone(&cache.counties),
+                cache.infrastructure.clone(),
+                cache.fills.clone(),
                 cache.globe_outline.as_ref().map(Rc::clone),
+                cache.generation,
+                cache.lines,
             )
         } else {
             drop(cache_borrow);
 
-            let mut coastlines_canvas = BrailleCanvas::new(width, height);
-            let mut borders_canvas = BrailleCanvas::new(width, height);
-            let mut states_canvas = BrailleCanvas::new(width, height);
-            let mut counties_canvas = BrailleCanvas::new(width, height);
+            // Last render's canvases, to draw over again rather than allocate.
+            // The layers handed out then have normally been dropped by now.
+            let old = RecycledCanvases::from(self.cache.borrow_mut().take());
 
-            if self.settings.show_coastlines {
+            // Anti-aliased lines are drawn on a finer copy of the view
+            let fine_view = self.settings.antialias.then(|| {
+                let (w, h) = view.size();
+                let mut fine = view.clone_projection();
+                fine.rescale(w * SUPERSAMPLE, h * SUPERSAMPLE);
+                fine
+            });
+            // Lines simplified to within `settings.simplify` of the pixels
+            // they're drawn at
+            let pen_view = fine_view.as_deref().unwrap_or(view);
+            let tolerance = self.settings.simplify / pen_view.deg_to_pixels(1.0).max(f64::EPSILON);
+
+            // Gather each layer's lines, then draw the four layers at once:
+            // they're independent canvases
+            let mut coastline_lines: Vec<&LineString> = Vec::new();
+            let mut border_lines: Vec<&LineString> = Vec::new();
+            let mut state_lines: Vec<&LineString> = Vec::new();
+            let mut county_lines: Vec<&LineString> = Vec::new();
+
+            if !self.tiles.is_empty() {
+                for tile in &self.tiles {
+                    if self.settings.show_coastlines {
+                        coastline_lines.extend(&tile.coastlines);
+                    }
+                    if self.settings.show_borders {
+                        border_lines.extend(&tile.borders);
+                    }
+                }
+            } else if self.settings.show_coastlines {
                 let coastlines = self.get_coastlines(lod);
                 let grid = self.get_coastline_grid(lod);
                 let candidates = Self::query_grid_wrapped(grid, fg_min_lon, fg_min_lat, fg_max_lon, fg_max_lat);
-                for &idx in &candidates {
-                    self.draw_linestring(&mut coastlines_canvas, &coastlines[idx], viewport, offsets);
-                }
+                coastline_lines.extend(candidates.iter().map(|&idx| coastlines[idx].at_tolerance(tolerance)));
             }
 
             if self.settings.show_borders {
-                let borders = self.get_borders(lod);
-                let grid = self.get_border_grid(lod);
-                let candidates = Self::query_grid_wrapped(grid, fg_min_lon, fg_min_lat, fg_max_lon, fg_max_lat);
-                for &idx in &candidates {
-                    self.draw_linestring(&mut borders_canvas, &borders[idx], viewport, offsets);
+                if self.tiles.is_empty() {
+                    let borders = self.get_borders(lod);
+                    let grid = self.get_border_grid(lod);
+                    let candidates = Self::query_grid_wrapped(grid, fg_min_lon, fg_min_lat, fg_max_lon, fg_max_lat);
+                    border_lines.extend(candidates.iter().map(|&idx| borders[idx].at_tolerance(tolerance)));
                 }
 
-                if self.settings.show_states && viewport.zoom >= 4.0 {
+                if self.settings.show_states && zoom >= states_zoom {
                     let candidates = Self::query_grid_wrapped(&self.state_grid, fg_min_lon, fg_min_lat, fg_max_lon, fg_max_lat);
-                    for &idx in &candidates {
-                        self.draw_linestring(&mut states_canvas, &self.states[idx], viewport, offsets);
-                    }
+                    state_lines.extend(candidates.iter().map(|&idx| self.states[idx].at_tolerance(tolerance)));
                 }
 
-                if self.settings.show_counties && viewport.zoom >= 7.0 {
+                if self.settings.show_counties && zoom >= counties_zoom {
                     let candidates = Self::query_grid_wrapped(&self.county_grid, fg_min_lon, fg_min_lat, fg_max_lon, fg_max_lat);
-                    for &idx in &candidates {
-                        self.draw_linestring(&mut counties_canvas, &self.counties[idx], viewport, offsets);
-                    }
+                    county_lines.extend(candidates.iter().map(|&idx| self.counties[idx].at_tolerance(tolerance)));
                 }
             }
 
-            let coastlines_rc = Rc::new(coastlines_canvas);
-            let borders_rc = Rc::new(borders_canvas);
-            let states_rc = Rc::new(states_canvas);
-            let counties_rc = Rc::new(counties_canvas);
+            let fine = fine_view.as_deref();
+            let coastlines_pen = LinePen::new(recycle(old.coastlines, width, height), view, fine);
+            let borders_pen = LinePen::new(recycle(old.borders, width, height), view, fine);
+            let states_pen = LinePen::new(recycle(old.states, width, height), view, fine);
+            let counties_pen = LinePen::new(recycle(old.counties, width, height), view, fine);
+            // Area fills alongside: land, lakes, countries, each polygon with
+            // its holes left open
+            let mut fills = [old.land, old.lakes, old.countries].map(|c| recycle(c, width, height));
+            let fill_layers: [Vec<&Polygon>; 3] = if self.settings.show_fills {
+                // Countries are only filled to be tinted by faction
+                let countries = self.country_polygons.iter().map(|(_, p)| p).filter(|_| self.settings.show_factions);
+                [self.get_land_polygons(lod).iter().collect(), self.lakes.iter().collect(), countries.collect()]
+            } else {
+                Default::default()
+            };
+            let (((coastlines, borders), (states, counties)), ()) = rayon::join(
+                || rayon::join(
+                    || rayon::join(|| coastlines_pen.draw_all(&coastline_lines), || borders_pen.draw_all(&border_lines)),
+                    || rayon::join(|| states_pen.draw_all(&state_lines), || counties_pen.draw_all(&county_lines)),
+                ),
+                || {
+                    for (canvas, polygons) in fills.iter_mut().zip(&fill_layers) {
+                        fill_polygons(canvas, polygons.iter().copied(), fg_bounds, view);
+                    }
+                },
+            );
+            let [land, lakes, countries] = fills.map(Rc::new);
+            let fills = FillCanvases { land, lakes, countries };
+
+            let infrastructure = self.render_infrastructure(
+                [old.roads, old.railways, old.wreckage].map(|c| recycle(c, width, height)),
+                fg_bounds, zoom, is_globe,
+                |canvas, line| draw_linestring(canvas, line, view),
+            );
 
+            // Faint outline where the edge of the world is on screen
+            let edge = view.edge();
+            let globe_outline = (!edge.is_empty()).then(|| {
+                let mut outline = recycle(old.globe_outline, width, height);
+                for (x, y) in edge {
+                    if x >= 0 && y >= 0 {
+                        outline.set_pixel(x as usize, y as usize);
+                    }
+                }
+                Rc::new(outline)
+            });
+
+            let coastlines_rc = Rc::new(coastlines);
+            let borders_rc = Rc::new(borders);
+            let states_rc = Rc::new(states);
+            let counties_rc = Rc::new(counties);
+
+            let lines = coastline_lines.len() + border_lines.len() + state_lines.len() + county_lines.len();
+            let generation = self.render_generation.get() + 1;
+            self.render_generation.set(generation);
             *self.cache.borrow_mut() = Some(RenderCache {
                 key: cache_key,
+                generation,
+                lines,
                 coastlines: Rc::clone(&coastlines_rc),
                 borders: Rc::clone(&borders_rc),
                 states: Rc::clone(&states_rc),
                 counties: Rc::clone(&counties_rc),
-                globe_outline: None,
+                infrastructure: infrastructure.clone(),
+                fills: fills.clone(),
+                globe_outline: globe_outline.as_ref().map(Rc::clone),
             });
 
-            (coastlines_rc, borders_rc, states_rc, counties_rc, None)
+            (coastlines_rc, borders_rc, states_rc, counties_rc, infrastructure, fills, globe_outline, generation, lines)
         };
 
+        let (px_width, px_height) = view.size();
+        let on_screen = |(px, py): (i32, i32)| px >= 0 && py >= 0 && px < px_width as i32 + 10 && py < px_height as i32 + 10;
+
         // Collect cities for glyph rendering (viewport-aware filtering with wrapping)
+        let mut visible_cities: Vec<(&City, u16, u16)> = Vec::new();
         if self.settings.show_cities {
-            let mut candidate_indices = Vec::new();
-            candidate_indices.extend(
-                self.city_grid.query_bbox(vp_min_lon, vp_min_lat, vp_max_lon, vp_max_lat)
-            );
+            let mut candidate_indices = self.city_grid.query_bbox(vp_min_lon.max(-180.0), vp_min_lat, vp_max_lon.min(180.0), vp_max_lat);
             if vp_min_lon < -180.0 {
                 candidate_indices.extend(
                     self.city_grid.query_bbox(vp_min_lon + 360.0, vp_min_lat, 180.0, vp_max_lat)
@@ -837,207 +1642,208 @@ impl MapRenderer {
                     self.city_grid.query_bbox(-180.0, vp_min_lat, vp_max_lon - 360.0, vp_max_lat)
                 );
             }
+            candidate_indices.sort_unstable();
+            candidate_indices.dedup();
 
-            let mut visible_cities: Vec<(&City, u16, u16)> = candidate_indices
+            visible_cities = candidate_indices
                 .iter()
                 .filter_map(|&idx| self.city_grid.get(idx))
                 .flat_map(|city| {
-                    WRAP_OFFSETS.iter().filter_map(move |&offset| {
-                        let ((px, py), _) = viewport.project_wrapped(city.lon, city.lat, offset);
-                        if px < 0 || py < 0 || !viewport.is_visible(px, py) {
-                            return None;
-                        }
-                        Some((city, (px / 2) as u16, (py / 4) as u16))
-                    })
+                    view.project_copies(city.lon, city.lat)
+                        .into_iter()
+                        .filter(|&p| on_screen(p))
+                        .map(move |(px, py)| (city, (px / 2) as u16, (py / 4) as u16))
                 })
                 .collect();
 
-            visible_cities.sort_by(|a, b| b.0.original_population.cmp(&a.0.original_population));
-            let max_cities = Self::max_cities_for_zoom(viewport.zoom);
-            let max_pop = visible_cities.first().map(|(c, _, _)| c.original_population).unwrap_or(1);
-
-            self.collect_city_labels(&mut labels, visible_cities, max_cities, max_pop);
+            visible_cities.sort_by_key(|c| std::cmp::Reverse(c.0.original_population));
+            visible_cities.truncate(Self::max_cities_for_zoom(zoom));
         }
+        let max_pop = visible_cities.first().map(|(c, _, _)| c.original_population).unwrap_or(1);
+
+        // Country and state names, gated by zoom. The globe's effective
+        // zoom runs lower; scaled so state names still arrive with the
+        // state borders.
+        let place_zoom = if is_globe { zoom * 4.0 / states_zoom } else { zoom };
+        // Within a kind, the ones showing from further out go first
+        let visible_places = |kind: PlaceKind| -> Vec<(&PlaceLabel, u16, u16)> {
+            if !self.settings.show_labels {
+                return Vec::new();
+            }
+            let mut places: Vec<_> = self.place_labels
+                .iter()
+                .filter(|p| p.kind == kind && place_zoom >= p.min_zoom)
+                .flat_map(|p| {
+                    view.project_copies(p.lon, p.lat)
+                        .into_iter()
+                        .filter(|&(px, py)| px >= 0 && py >= 0 && px < px_width as i32 && py < px_height as i32)
+                        .map(move |(px, py)| (p, (px / 2) as u16, (py / 4) as u16))
+                })
+                .collect();
+            places.sort_by(|a, b| a.0.min_zoom.total_cmp(&b.0.min_zoom));
+            places
+        };
+
+        // Every label claims its cells in one pass, most important first:
+        // countries, big cities, states, then the smaller cities. Names
+        // steer clear of coastlines and borders where they can.
+        let on_line = |x: usize, y: usize| {
+            [&coastlines_canvas, &borders_canvas].iter().any(|c| y < c.char_height() && c.row_raw(y).get(x).is_some_and(|&b| b != 0))
+        };
+        let mut placer = LabelPlacer::new(width, height);
+        let major = visible_cities.partition_point(|c| c.0.original_population >= MAJOR_CITY_POPULATION);
+        let fonts = self.settings.label_fonts;
+        Self::collect_place_labels(&mut labels, &mut placer, on_line, visible_places(PlaceKind::Country), fonts);
+        self.collect_city_labels(&mut labels, &mut placer, on_line, &visible_cities[..major], max_pop);
+        Self::collect_place_labels(&mut labels, &mut placer, on_line, visible_places(PlaceKind::State), fonts);
+        self.collect_city_labels(&mut labels, &mut placer, on_line, &visible_cities[major..], max_pop);
+
+        let facilities = self.collect_facilities(fg_bounds, zoom, is_globe, |lon, lat| {
+            view.project_copies(lon, lat)
+                .into_iter()
+                .find(|&p| on_screen(p))
+                .map(|(px, py)| ((px / 2) as u16, (py / 4) as u16))
+        });
 
+        let features = lines + visible_cities.len() + facilities.len();
         MapLayers {
             coastlines: coastlines_canvas,
             borders: borders_canvas,
             states: states_canvas,
             counties: counties_canvas,
-            globe_outline: None,
+            infrastructure,
+            fills,
+            globe_outline,
+            generation,
+            features,
             labels,
+            facilities,
         }
     }
 
-    /// Globe render path: orthographic projection with great circle subdivision
-    fn render_globe(&self, width: usize, height: usize, globe: &GlobeViewport) -> MapLayers {
-        let zoom = globe.effective_zoom();
-        let lod = Lod::from_zoom(zoom);
-        let mut labels = Vec::new();
-
-        let (vp_min_lon, vp_min_lat, vp_max_lon, vp_max_lat) = globe.visible_bounds();
-
-        // Padded bounds for spatial queries
-        let pad = 5.0;
-        let fg_min_lon = (vp_min_lon - pad).max(-180.0);
-        let fg_max_lon = (vp_max_lon + pad).min(180.0);
-        let fg_min_lat = (vp_min_lat - pad).max(-90.0);
-        let fg_max_lat = (vp_max_lat + pad).min(90.0);
-
-        // Check cache
-        let cache_key = RenderCacheKey::new(globe.center_lon(), globe.center_lat(), globe.effective_zoom(), true, width, height, &self.settings);
-        let cache_borrow = self.cache.borrow();
-        let use_cache = cache_borrow.as_ref().map(|c| c.key == cache_key).unwrap_or(false);
-
-        let (coastlines_canvas, borders_canvas, states_canvas, counties_canvas, globe_outline_rc) = if use_cache {
-            let cache = cache_borrow.as_ref().unwrap();
-            (
-                Rc::clone(&cache.coastlines),
-                Rc::clone(&cache.borders),
-                Rc::clone(&cache.states),
-                Rc::clone(&cache.counties),
-                cache.globe_outline.as_ref().map(Rc::clone),
-            )
-        } else {
-            drop(cache_borrow);
-
-            let mut coastlines_canvas = BrailleCanvas::new(width, height);
-            let mut borders_canvas = BrailleCanvas::new(width, height);
-            let mut states_canvas = BrailleCanvas::new(width, height);
-            let mut counties_canvas = BrailleCanvas::new(width, height);
+    /// Whether an infrastructure layer is switched on and the view is zoomed
+    /// in far enough for it
+    fn infrastructure_visible(&self, kind: Infrastructure, zoom: f64, is_globe: bool) -> bool {
+        let shown = match kind {
+            Infrastructure::Road => self.settings.show_roads,
+            Infrastructure::Railway => self.settings.show_railways,
+            Infrastructure::Airport => self.settings.show_airports,
+            Infrastructure::Port => self.settings.show_ports,
+        };
+        shown && zoom >= if is_globe { kind.min_globe_zoom() } else { kind.min_zoom() }
+    }
 
-            // No wrap offsets needed for globe — natural wrapping
-            if self.settings.show_coastlines {
-                let coastlines = self.get_coastlines(lod);
-                let grid = self.get_coastline_grid(lod);
-                let candidates = Self::query_grid_wrapped(grid, fg_min_lon, fg_min_lat, fg_max_lon, fg_max_lat);
-                for &idx in &candidates {
-                    self.draw_linestring_globe(&mut coastlines_canvas, &coastlines[idx], globe);
-                }
+    /// Draw the visible roads and railways. Destroyed segments go on the
+    /// wreckage canvas instead. Shared by both render paths via `draw`.
+    fn render_infrastructure(
+        &self,
+        [mut roads, mut railways, mut wreckage]: [BrailleCanvas; 3],
+        (min_lon, min_lat, max_lon, max_lat): (f64, f64, f64, f64),
+        zoom: f64,
+        is_globe: bool,
+        draw: impl Fn(&mut BrailleCanvas, &LineString),
+    ) -> InfraCanvases {
+        for (kind, routes, grid, canvas) in [
+            (Infrastructure::Road, &self.roads, &self.road_grid, &mut roads),
+            (Infrastructure::Railway, &self.railways, &self.railway_grid, &mut railways),
+        ] {
+            if routes.is_empty() || !self.infrastructure_visible(kind, zoom, is_globe) {
+                continue;
             }
+            for idx in Self::query_grid_wrapped(grid, min_lon, min_lat, max_lon, max_lat) {
+                let route = &routes[idx];
+                draw(if route.destroyed { &mut wreckage } else { &mut *canvas }, &route.line);
+            }
+        }
+        InfraCanvases { roads: Rc::new(roads), railways: Rc::new(railways), wreckage: Rc::new(wreckage) }
+    }
 
-            if self.settings.show_borders {
-                let borders = self.get_borders(lod);
-                let grid = self.get_border_grid(lod);
-                let candidates = Self::query_grid_wrapped(grid, fg_min_lon, fg_min_lat, fg_max_lon, fg_max_lat);
-                for &idx in &candidates {
-                    self.draw_linestring_globe(&mut borders_canvas, &borders[idx], globe);
-                }
-
-                if self.settings.show_states && zoom >= 1.5 {
-                    let candidates = Self::query_grid_wrapped(&self.state_grid, fg_min_lon, fg_min_lat, fg_max_lon, fg_max_lat);
-                    for &idx in &candidates {
-                        self.draw_linestring_globe(&mut states_canvas, &self.states[idx], globe);
-                    }
-                }
+    /// Screen positions of the visible airports and ports
+    fn collect_facilities(
+        &self,
+        (min_lon, min_lat, max_lon, max_lat): (f64, f64, f64, f64),
+        zoom: f64,
+        is_globe: bool,
+        project: impl Fn(f64, f64) -> Option<(u16, u16)>,
+    ) -> Vec<(u16, u16, Infrastructure, bool)> {
+        if self.facilities.is_empty() {
+            return Vec::new();
+        }
+        Self::query_grid_wrapped(&self.facility_grid, min_lon, min_lat, max_lon, max_lat)
+            .into_iter()
+            .map(|idx| &self.facilities[idx])
+            .filter(|f| self.infrastructure_visible(f.kind, zoom, is_globe))
+            .filter_map(|f| project(f.lon, f.lat).map(|(x, y)| (x, y, f.kind, f.destroyed)))
+            .collect()
+    }
 
-                if self.settings.show_counties && zoom >= 3.5 {
-                    let candidates = Self::query_grid_wrapped(&self.county_grid, fg_min_lon, fg_min_lat, fg_max_lon, fg_max_lat);
-                    for &idx in &candidates {
-                        self.draw_linestring_globe(&mut counties_canvas, &self.counties[idx], globe);
-                    }
-                }
+    /// Wreck infrastructure within `radius_km` of (lon, lat), limited to the
+    /// kinds `hit` accepts. A road or railway goes if any of its vertices is
+    /// inside. Returns how many features were newly destroyed.
+    pub fn destroy_infrastructure(&mut self, lon: f64, lat: f64, radius_km: f64, hit: impl Fn(Infrastructure) -> bool) -> usize {
+        const EARTH_RADIUS_KM: f64 = 6371.0;
+        let dlat = radius_km / 111.0;
+        let dlon = dlat / lat.to_radians().cos().max(0.05);
+        let (min_lon, min_lat, max_lon, max_lat) = (lon - dlon, (lat - dlat).max(-90.0), lon + dlon, (lat + dlat).min(90.0));
+        let center = globe::lonlat_to_vec3(lon, lat);
+        let min_dot = (radius_km / EARTH_RADIUS_KM).min(std::f64::consts::PI).cos();
+
+        let mut destroyed = 0;
+        for (kind, routes, grid) in [
+            (Infrastructure::Road, &mut self.roads, &self.road_grid),
+            (Infrastructure::Railway, &mut self.railways, &self.railway_grid),
+        ] {
+            if !hit(kind) {
+                continue;
             }
-
-            // Globe outline — only when sphere edge is visible in viewport
-            let globe_outline_rc = if globe.radius < (globe.width.min(globe.height) as f64 / 2.0) {
-                let mut outline = BrailleCanvas::new(width, height);
-                let cx = globe.width as f64 / 2.0;
-                let cy = globe.height as f64 / 2.0;
-                let r = globe.radius;
-                let circumference = 2.0 * std::f64::consts::PI * r;
-                let steps = (circumference * 0.5) as usize; // every other pixel for faintness
-                for i in 0..steps {
-                    let theta = 2.0 * std::f64::consts::PI * i as f64 / steps as f64;
-                    let x = (cx + r * theta.cos()) as usize;
-                    let y = (cy - r * theta.sin()) as usize;
-                    outline.set_pixel(x, y);
+            for idx in Self::query_grid_wrapped(grid, min_lon, min_lat, max_lon, max_lat) {
+                let route = &mut routes[idx];
+                if !route.destroyed && route.line.vecs().any(|v| v.dot(center) >= min_dot) {
+                    route.destroyed = true;
+                    destroyed += 1;
                 }
-                Some(Rc::new(outline))
-            } else {
-                None
-            };
-
-            let coastlines_rc = Rc::new(coastlines_canvas);
-            let borders_rc = Rc::new(borders_canvas);
-            let states_rc = Rc::new(states_canvas);
-            let counties_rc = Rc::new(counties_canvas);
-
-            *self.cache.borrow_mut() = Some(RenderCache {
-                key: cache_key,
-                coastlines: Rc::clone(&coastlines_rc),
-                borders: Rc::clone(&borders_rc),
-                states: Rc::clone(&states_rc),
-                counties: Rc::clone(&counties_rc),
-                globe_outline: globe_outline_rc.as_ref().map(Rc::clone),
-            });
-
-            (coastlines_rc, borders_rc, states_rc, counties_rc, globe_outline_rc)
-        };
-
-        // Cities on globe
-        if self.settings.show_cities {
-            let candidate_indices = self.city_grid.query_bbox(
-                vp_min_lon, vp_min_lat, vp_max_lon, vp_max_lat
-            );
-
-            let mut visible_cities: Vec<(&City, u16, u16)> = candidate_indices
-                .iter()
-                .filter_map(|&idx| self.city_grid.get(idx))
-                .filter_map(|city| {
-                    let (px, py) = globe.project(city.lon, city.lat)?;
-                    if !globe.is_visible(px, py) {
-                        return None;
-                    }
-                    Some((city, (px / 2) as u16, (py / 4) as u16))
-                })
-                .collect();
-
-            visible_cities.sort_by(|a, b| b.0.original_population.cmp(&a.0.original_population));
-            let max_cities = Self::max_cities_for_zoom(zoom);
-            let max_pop = visible_cities.first().map(|(c, _, _)| c.original_population).unwrap_or(1);
-
-            self.collect_city_labels(&mut labels, visible_cities, max_cities, max_pop);
+            }
         }
-
-        MapLayers {
-            coastlines: coastlines_canvas,
-            borders: borders_canvas,
-            states: states_canvas,
-            counties: counties_canvas,
-            globe_outline: globe_outline_rc,
-            labels,
+        for idx in Self::query_grid_wrapped(&self.facility_grid, min_lon, min_lat, max_lon, max_lat) {
+            let facility = &mut self.facilities[idx];
+            if !facility.destroyed && hit(facility.kind) && great_circle_km(lon, lat, facility.lon, facility.lat) <= radius_km {
+                facility.destroyed = true;
+                destroyed += 1;
+            }
+        }
+        if destroyed > 0 {
+            self.infrastructure_generation += 1;
         }
+        destroyed
     }
 
     /// Shared city label collection logic used by both render paths
-    fn collect_city_labels(&self, labels: &mut Vec<(u16, u16, String, f32)>, visible_cities: Vec<(&City, u16, u16)>, max_cities: usize, max_pop: u64) {
-        for (city, char_x, char_y) in visible_cities.into_iter().take(max_cities) {
+    fn collect_city_labels(
+        &self,
+        labels: &mut Vec<MapLabel>,
+        placer: &mut LabelPlacer,
+        on_line: impl Fn(usize, usize) -> bool,
+        visible_cities: &[(&City, u16, u16)],
+        max_pop: u64,
+    ) {
+        for &(city, char_x, char_y) in visible_cities {
+            let label_y = char_y.saturating_sub(1);
+            // Bigger cities come first, so a smaller one's marker can't
+            // land on a cell they've taken
+            if !placer.place_marker(char_x, label_y) {
+                continue;
+            }
+
             let health = if city.original_population > 0 {
                 city.population as f32 / city.original_population as f32
             } else {
                 1.0
             };
 
-            let label_y = char_y.saturating_sub(1);
-
-            if city.population == 0 {
-                labels.push((char_x, label_y, "☠".to_string(), 0.0));
-                if self.settings.show_labels {
-                    if let Some(label_x) = char_x.checked_add(1) {
-                        let label = if self.settings.show_population {
-                            format!(" {} (0)", city.name)
-                        } else {
-                            format!(" {}", city.name)
-                        };
-                        labels.push((label_x, label_y, label, 0.0));
-                    }
-                }
-                continue;
-            }
-
             let ratio = city.original_population as f64 / max_pop.max(1) as f64;
-            let glyph = if city.is_capital {
+            let glyph = if city.population == 0 {
+                '☠'
+            } else if city.is_capital {
                 '⚜'
             } else if city.is_megacity || city.population >= 10_000_000 {
                 '★'
@@ -1055,166 +1861,47 @@ impl MapRenderer {
                 '·'
             };
 
-            labels.push((char_x, label_y, glyph.to_string(), health));
+            let (health, blackout) = if city.population == 0 { (0.0, false) } else { (health, city.blackout_frames > 0) };
+            let label = |x, y, text| MapLabel { x, y, text, health, blackout, brightness: 1.0 };
+            labels.push(label(char_x, label_y, glyph.to_string()));
 
             if self.settings.show_labels {
-                if let Some(label_x) = char_x.checked_add(1) {
-                    let label = if self.settings.show_population {
-                        format!(" {} ({})", city.name, city.cached_pop_label)
-                    } else {
-                        format!(" {}", city.name)
-                    };
-                    labels.push((label_x, label_y, label, health));
-                }
-            }
-        }
-    }
-
-    /// Compute which wrap offsets are needed for this viewport.
-    /// Offset 0 always needed; ±360 only when viewport crosses the dateline.
-    fn needed_wrap_offsets(vp_min_lon: f64, vp_max_lon: f64) -> &'static [f64] {
-        let needs_neg = vp_max_lon > 180.0;  // viewport wraps east → need -360
-        let needs_pos = vp_min_lon < -180.0;  // viewport wraps west → need +360
-        match (needs_neg, needs_pos) {
-            (true, true) => &[0.0, -360.0, 360.0],
-            (true, false) => &[0.0, -360.0],
-            (false, true) => &[0.0, 360.0],
-            (false, false) => &[0.0],
-        }
-    }
-
-    /// Draw a linestring with viewport culling and world wrapping
-    fn draw_linestring(&self, canvas: &mut BrailleCanvas, line: &LineString, viewport: &Viewport, offsets: &[f64]) {
-        if line.len() < 2 {
-            return;
-        }
-
-        for &lon_offset in offsets {
-            self.draw_linestring_with_offset(canvas, line, viewport, lon_offset);
-        }
-    }
-
-    /// Draw a linestring with a longitude offset (for wrapping).
-    /// Uses precomputed Mercator coordinates — pure arithmetic, zero trig per vertex.
-    fn draw_linestring_with_offset(&self, canvas: &mut BrailleCanvas, line: &LineString, viewport: &Viewport, lon_offset: f64) {
-        // Bbox early-out using precomputed Mercator bbox (pure arithmetic, no trig)
-        let (merc_min_x, merc_min_y, merc_max_x, merc_max_y) = line.mercator_bbox;
-        let (px1, py1) = viewport.project_mercator(merc_min_x, merc_min_y, lon_offset);
-        let (px2, py2) = viewport.project_mercator(merc_max_x, merc_max_y, lon_offset);
-        let bb_min_x = px1.min(px2);
-        let bb_max_x = px1.max(px2);
-        let bb_min_y = py1.min(py2);
-        let bb_max_y = py1.max(py2);
-
-        // Skip if bounding box is entirely outside viewport
-        if bb_max_x < -50 || bb_min_x > viewport.width as i32 + 50 ||
-           bb_max_y < -50 || bb_min_y > viewport.height as i32 + 50 {
-            return;
-        }
-
-        let mut prev: Option<(i32, i32)> = None;
-
-        for &(mx, my) in &line.mercator {
-            let (px, py) = viewport.project_mercator(mx, my, lon_offset);
-
-            if let Some((prev_x, prev_y)) = prev {
-                // Skip drawing if jump is too large (crossing date line within this offset)
-                let dx = (px - prev_x).abs();
-                let dy = (py - prev_y).abs();
-                let dist = (dx + dy) as usize;
-
-                // Only draw if the segment is reasonable and might be visible
-                if dist < viewport.width / 2 && viewport.line_might_be_visible((prev_x, prev_y), (px, py)) {
-                    draw_line(canvas, prev_x, prev_y, px, py);
+                let name = if city.is_capital { self.settings.label_fonts.capital(&city.name) } else { city.name.clone() };
+                let name = match (self.settings.show_population, city.population) {
+                    (false, _) => name,
+                    (true, 0) => format!("{name} (0)"),
+                    (true, _) => format!("{name} ({})", city.cached_pop_label),
+                };
+                // Names with no room anywhere around their marker are left out
+                if let Some((x, y)) = placer.place_name(char_x, label_y, text_width(&name), &on_line) {
+                    labels.push(label(x, y, name));
                 }
             }
-
-            prev = Some((px, py));
         }
     }
 
-    /// Draw a linestring on the globe with great circle subdivision.
-    /// Three-phase conservative approximation (à la FloeDB H3 joins):
-    ///   Phase 1: Bounding sphere cull — single dot product (O(1) vs 8 trig ops)
-    ///   Phase 2: Per-segment back-face skip — 2 dot products
-    ///   Phase 3: Slerp + project using precomputed Vec3s — zero trig in hot loop
-    fn draw_linestring_globe(&self, canvas: &mut BrailleCanvas, line: &LineString, globe: &GlobeViewport) {
-        if line.len() < 2 {
-            return;
-        }
-
-        // Phase 1: O(1) hemisphere cull via precomputed bounding sphere
-        if line.center_vec.dot(globe.forward_vec()) < line.cull_dot {
-            return;
-        }
-
-        let forward = globe.forward_vec();
-        let half_w = globe.width as i32 / 2;
-        let mut prev_screen: Option<(i32, i32)> = None;
-        let mut prev_vec: Option<globe::DVec3> = None;
-
-        // Phase 3: iterate precomputed unit-sphere vectors (zero lonlat_to_vec3 calls)
-        for &cur in &line.vecs {
-            if let Some(pv) = prev_vec {
-                // Phase 2: skip segments entirely behind the globe
-                if cur.dot(forward) < -0.1 && pv.dot(forward) < -0.1 {
-                    prev_screen = None;
-                    prev_vec = Some(cur);
-                    continue;
-                }
-
-                let dot = pv.dot(cur).clamp(-1.0, 1.0);
-
-                // Fast path: dot > cos(2°) ≈ 0.9994 means angle < 2°, steps = 1.
-                // Skips acos + sin entirely — handles ~95% of segments.
-                if dot > 0.9994 {
-                    match globe.project_vec3(cur) {
-                        Some((px, py)) => {
-                            if let Some((prev_x, prev_y)) = prev_screen {
-                                let dist = (px - prev_x).abs() + (py - prev_y).abs();
-                                if dist < half_w && globe.line_might_be_visible((prev_x, prev_y), (px, py)) {
-                                    draw_line(canvas, prev_x, prev_y, px, py);
-                                }
-                            }
-                            prev_screen = Some((px, py));
-                        }
-                        None => prev_screen = None,
-                    }
-                } else {
-                    // Slow path: large arc — subdivide with slerp
-                    let angle = dot.acos();
-                    let steps = ((angle.to_degrees() / 2.0).ceil() as usize).max(1);
-                    let sin_angle = angle.sin();
-
-                    if sin_angle.abs() < 1e-10 {
-                        prev_screen = globe.project_vec3(cur);
-                    } else {
-                        for i in 1..=steps {
-                            let t = i as f64 / steps as f64;
-                            let sa = ((1.0 - t) * angle).sin() / sin_angle;
-                            let sb = (t * angle).sin() / sin_angle;
-                            let p = pv * sa + cur * sb;
-
-                            match globe.project_vec3(p) {
-                                Some((px, py)) => {
-                                    if let Some((prev_x, prev_y)) = prev_screen {
-                                        let dist = (px - prev_x).abs() + (py - prev_y).abs();
-                                        if dist < half_w && globe.line_might_be_visible((prev_x, prev_y), (px, py)) {
-                                            draw_line(canvas, prev_x, prev_y, px, py);
-                                        }
-                                    }
-                                    prev_screen = Some((px, py));
-                                }
-                                None => prev_screen = None,
-                            }
-                        }
-                    }
-                }
-            } else {
-                prev_screen = globe.project_vec3(cur);
+    /// Country and state names centered on their label points. Names are
+    /// drawn dimmer than cities, states dimmest. Countries are lettered in
+    /// `fonts`.
+    fn collect_place_labels(
+        labels: &mut Vec<MapLabel>,
+        placer: &mut LabelPlacer,
+        on_line: impl Fn(usize, usize) -> bool,
+        visible_places: Vec<(&PlaceLabel, u16, u16)>,
+        fonts: LabelFonts,
+    ) {
+        for (place, char_x, char_y) in visible_places {
+            let brightness = match place.kind {
+                PlaceKind::Country => 0.7,
+                PlaceKind::State => 0.45,
+            };
+            let name = match place.kind {
+                PlaceKind::Country => fonts.country(&place.name),
+                PlaceKind::State => place.name.clone(),
+            };
+            if let Some((x, y)) = placer.place_centered(char_x, char_y, text_width(&name), &on_line) {
+                labels.push(MapLabel { x, y, text: name, health: 1.0, blackout: false, brightness });
             }
-
-            prev_vec = Some(cur);
         }
     }
 
@@ -1229,17 +1916,23 @@ impl MapRenderer {
     }
 
     /// Add a city marker
-    pub fn add_city(&mut self, lon: f64, lat: f64, name: &str, population: u64, is_capital: bool, is_megacity: bool) {
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_city(&mut self, lon: f64, lat: f64, name: &str, population: u64, is_capital: bool, is_megacity: bool, country: &str) {
         let radius_km = city_radius_from_population(population);
         self.city_grid.insert(lon, lat, City {
             lon,
             lat,
             cached_pop_label: format_population(population),
+            blackout_frames: 0,
+            infection: 0.0,
+            displaced: 0,
+            english_name: None,
             name: name.to_string(),
             population,
             original_population: population,
             is_capital,
             is_megacity,
+            country: country.to_string(),
             radius_km,
         });
     }
@@ -1266,6 +1959,19 @@ impl MapRenderer {
         }
     }
 
+    /// Build the country ownership raster: from admin-0 polygons if loaded,
+    /// otherwise from city locations. Call after cities and the land grid.
+    pub fn build_country_grid(&mut self) {
+        self.country_grid = Some(if !self.country_polygons.is_empty() {
+            CountryGrid::from_polygons(&self.country_polygons)
+        } else {
+            CountryGrid::from_cities(
+                self.city_grid.iter().map(|c| (c.lon, c.lat, c.country.as_str())),
+                |lon, lat| self.is_on_land(lon, lat),
+            )
+        });
+    }
+
     /// Check if a point is on land (O(1) grid lookup)
     #[inline(always)]
     pub fn is_on_land(&self, lon: f64, lat: f64) -> bool {
@@ -1287,6 +1993,84 @@ impl MapRenderer {
         }
     }
 
+    /// Find a loaded city by name (case-insensitive), shown or English.
+    /// Exact matches win over prefix matches; ties go to the most populous
+    /// city.
+    pub fn find_city(&self, query: &str) -> Option<&City> {
+        let q = query.trim().to_lowercase();
+        if q.is_empty() {
+            return None;
+        }
+        let best = |exact: bool| {
+            self.city_grid
+                .iter()
+                .filter(|c| {
+                    std::iter::once(&c.name).chain(&c.english_name).any(|name| {
+                        let name = name.to_lowercase();
+                        if exact { name == q } else { name.starts_with(&q) }
+                    })
+                })
+                .max_by_key(|c| c.original_population)
+        };
+        best(true).or_else(|| best(false))
+    }
+
+    /// Country by name, shown or English, or ISO alpha-3 code (exact name
+    /// first, then code, then prefix), with its shown name and its largest
+    /// polygon — its mainland, for countries with islands or overseas
+    /// territories
+    pub fn find_country(&self, query: &str) -> Option<(&str, &Polygon)> {
+        let q = query.trim().to_lowercase();
+        if q.is_empty() {
+            return None;
+        }
+        let names = || self.country_names.iter().chain(&self.country_names_en);
+        let (code, _) = names()
+            .find(|(_, name)| name.to_lowercase() == q)
+            .or_else(|| names().find(|(code, _)| code.to_lowercase() == q))
+            .or_else(|| names().find(|(_, name)| name.to_lowercase().starts_with(&q)))?;
+        let name = &self.country_names.iter().find(|(c, _)| c == code)?.1;
+        let polygon = self.country_polygons.iter()
+            .filter(|(c, _)| c == code)
+            .map(|(_, p)| p)
+            .max_by(|a, b| a.area().total_cmp(&b.area()))?;
+        Some((name, polygon))
+    }
+
+    /// Country at a point from the country grid, falling back to the nearest
+    /// known city within `max_degrees` (offshore strikes, unbuilt grid)
+    pub fn country_near(&self, lon: f64, lat: f64, max_degrees: f64) -> Option<&str> {
+        if let Some(code) = self.country_grid.as_ref().and_then(|g| g.country_at(lon, lat)) {
+            return Some(code);
+        }
+        self.city_grid
+            .query_radius(lon, lat, max_degrees)
+            .into_iter()
+            .filter_map(|idx| self.city_grid.get(idx))
+            .filter(|c| !c.country.is_empty())
+            .min_by(|a, b| {
+                let da = (a.lon - lon).powi(2) + (a.lat - lat).powi(2);
+                let db = (b.lon - lon).powi(2) + (b.lat - lat).powi(2);
+                da.total_cmp(&db)
+            })
+            .map(|c| c.country.as_str())
+    }
+
+    /// Largest surviving city of a country
+    pub fn largest_city_in(&self, country: &str) -> Option<&City> {
+        self.city_grid
+            .iter()
+            .filter(|c| c.country == country && c.population > 0)
+            .max_by_key(|c| c.population)
+    }
+
+    /// Replace the vector-tile basemap. `generation` changes whenever the
+    /// tile set does, invalidating the static layer cache.
+    pub fn set_tiles(&mut self, tiles: Vec<Arc<DecodedTile>>, generation: u64) {
+        self.tiles = tiles;
+        self.tile_generation = generation;
+    }
+
     /// Check if any data is loaded
     pub fn has_data(&self) -> bool {
         !self.coastlines_low.is_empty()
@@ -1309,6 +2093,12 @@ impl MapRenderer {
         self.settings.show_borders = !self.settings.show_borders;
     }
 
+    /// Toggle anti-aliased line drawing; returns whether it's now on
+    pub fn toggle_antialias(&mut self) -> bool {
+        self.settings.antialias = !self.settings.antialias;
+        self.settings.antialias
+    }
+
     /// Toggle state/province borders
     pub fn toggle_states(&mut self) {
         self.settings.show_states = !self.settings.show_states;
@@ -1323,6 +2113,24 @@ impl MapRenderer {
     pub fn toggle_cities(&mut self) {
         self.settings.show_cities = !self.settings.show_cities;
     }
+
+    /// Toggle one infrastructure layer. Returns whether it is now shown.
+    pub fn toggle_infrastructure(&mut self, kind: Infrastructure) -> bool {
+        let shown = match kind {
+            Infrastructure::Road => &mut self.settings.show_roads,
+            Infrastructure::Railway => &mut self.settings.show_railways,
+            Infrastructure::Airport => &mut self.settings.show_airports,
+            Infrastructure::Port => &mut self.settings.show_ports,
+        };
+        *shown = !*shown;
+        *shown
+    }
+}
+
+impl Default for LandGrid {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Default for MapRenderer {
@@ -1344,8 +2152,13 @@ mod tests {
             original_population: 5_000_000,
             is_capital: false,
             is_megacity: false,
+            country: String::new(),
             radius_km: 10.0,
             cached_pop_label: format_population(5_000_000),
+            blackout_frames: 0,
+            infection: 0.0,
+            displaced: 0,
+            english_name: None,
         };
         assert_eq!(city.cached_pop_label, "5.0M");
 
@@ -1357,12 +2170,35 @@ mod tests {
         assert_eq!(city.cached_pop_label, "0");
     }
 
+    #[test]
+    fn countries_are_found_by_name_or_code_at_their_mainland() {
+        let mut renderer = MapRenderer::new();
+        let square = |x0: f64, y0: f64, size: f64| {
+            vec![(x0, y0), (x0 + size, y0), (x0 + size, y0 + size), (x0, y0 + size), (x0, y0)]
+        };
+        // A 10° mainland with a 2° hole in its southwest, plus a small island
+        let mainland = Polygon::new(vec![square(0.0, 40.0, 10.0), square(1.0, 41.0, 2.0)]);
+        renderer.country_polygons.push(("MNL".to_string(), Polygon::new(vec![square(-30.0, 10.0, 1.0)])));
+        renderer.country_polygons.push(("MNL".to_string(), mainland));
+        renderer.country_names.push(("MNL".to_string(), "Mainlandia".to_string()));
+
+        for query in ["mainlandia", "MNL", "Main"] {
+            let (name, polygon) = renderer.find_country(query).unwrap();
+            assert_eq!(name, "Mainlandia");
+            let (lon, lat) = polygon.centroid();
+            // The hole nudges the centroid northeast of the square's center
+            assert!(lon > 5.0 && lon < 5.3 && lat > 45.0 && lat < 45.3, "{lon}, {lat}");
+            assert!((polygon.area() - 96.0).abs() < 1e-9);
+        }
+        assert!(renderer.find_country("Atlantis").is_none());
+    }
+
     #[test]
     fn linestring_len_matches_mercator_coords() {
         let pts = vec![(0.0, 0.0), (10.0, 20.0), (30.0, 40.0)];
         let ls = LineString::new(pts);
         assert_eq!(ls.len(), 3);
-        assert_eq!(ls.mercator.len(), 3);
+        assert_eq!(ls.mercator().count(), 3);
     }
 
     #[test]
@@ -1370,9 +2206,190 @@ mod tests {
         let pts = vec![(-10.0, -20.0), (30.0, 50.0), (0.0, 0.0)];
         let ls = LineString::new(pts);
         let (min_x, min_y, max_x, max_y) = ls.mercator_bbox;
-        for &(mx, my) in &ls.mercator {
+        for (mx, my) in ls.mercator() {
             assert!(mx >= min_x && mx <= max_x, "mx {mx} outside [{min_x}, {max_x}]");
             assert!(my >= min_y && my <= max_y, "my {my} outside [{min_y}, {max_y}]");
         }
     }
+
+    #[test]
+    fn strikes_wreck_infrastructure_by_kind() {
+        let mut renderer = MapRenderer::new();
+        renderer.roads.push(Route::new(LineString::new(vec![(9.0, 45.0), (10.0, 45.0)])));
+        renderer.railways.push(Route::new(LineString::new(vec![(20.0, 45.0), (21.0, 45.0)])));
+        for (lon, kind) in [(10.0, Infrastructure::Airport), (10.1, Infrastructure::Port)] {
+            renderer.facilities.push(Facility { lon, lat: 45.0, name: String::new(), kind, destroyed: false });
+        }
+        renderer.build_spatial_indexes();
+
+        // A pulse takes out the airport and port but the road survives
+        assert_eq!(renderer.destroy_infrastructure(10.0, 45.0, 30.0, Infrastructure::emp_vulnerable), 2);
+        assert!(renderer.facilities.iter().all(|f| f.destroyed));
+        assert!(!renderer.roads[0].destroyed);
+        assert_eq!(renderer.infrastructure_generation, 1);
+
+        // A blast takes the road; the railway is out of range
+        assert_eq!(renderer.destroy_infrastructure(10.0, 45.0, 30.0, |_| true), 1);
+        assert!(renderer.roads[0].destroyed);
+        assert!(!renderer.railways[0].destroyed);
+
+        // Nothing left to break doesn't invalidate the cache
+        assert_eq!(renderer.destroy_infrastructure(10.0, 45.0, 30.0, |_| true), 0);
+        assert_eq!(renderer.infrastructure_generation, 2);
+    }
+
+    #[test]
+    fn every_projection_draws_coastlines() {
+        let mut renderer = MapRenderer::new();
+        crate::data::generate_simple_world(&mut renderer);
+        renderer.build_spatial_indexes();
+
+        let mut projection = Projection::new(crate::map::Viewport::world(240, 120));
+        let mut names = Vec::new();
+        for _ in 0..6 {
+            let layers = renderer.render(120, 30, &projection);
+            let lit = (0..layers.coastlines.char_height()).any(|y| layers.coastlines.row_raw(y).iter().any(|&b| b != 0));
+            assert!(lit, "{} drew no coastlines", projection.name());
+            names.push(projection.name());
+            projection.toggle();
+        }
+        assert_eq!(names, ["Mercator", "Globe", "Equirectangular", "Robinson", "Azimuthal", "Polar"]);
+        assert_eq!(projection.name(), "Mercator");
+    }
+
+    #[test]
+    fn rerendering_into_recycled_canvases_leaves_no_stale_dots() {
+        let mut renderer = MapRenderer::new();
+        crate::data::generate_simple_world(&mut renderer);
+        renderer.build_spatial_indexes();
+        let mut projection = Projection::new(crate::map::Viewport::world(240, 120));
+
+        for antialias in [false, true] {
+            renderer.settings.antialias = antialias;
+            drop(renderer.render(120, 30, &projection));
+            projection.pan(37, 11);
+            let reused = renderer.render(120, 30, &projection);
+
+            let mut fresh = MapRenderer::new();
+            crate::data::generate_simple_world(&mut fresh);
+            fresh.build_spatial_indexes();
+            fresh.settings.antialias = antialias;
+            let expected = fresh.render(120, 30, &projection);
+            for y in 0..30 {
+                assert_eq!(reused.coastlines.row_raw(y), expected.coastlines.row_raw(y));
+                assert_eq!(reused.borders.row_raw(y), expected.borders.row_raw(y));
+            }
+        }
+    }
+
+    #[test]
+    fn land_grid_fills_polygons_around_their_holes() {
+        // Spans several bands of rows; the hole straddles a band boundary
+        let ring = |lo: f64, hi: f64| vec![(lo, lo), (hi, lo), (hi, hi), (lo, hi), (lo, lo)];
+        let grid = LandGrid::build_scanline(&[Polygon::new(vec![ring(-10.0, 10.0), ring(-2.5, 2.5)])]);
+        assert!(grid.is_land(-8.0, -8.0) && grid.is_land(9.9, 5.0));
+        assert!(!grid.is_land(0.0, 0.0) && !grid.is_land(2.0, -1.0));
+        assert!(!grid.is_land(11.0, 0.0) && !grid.is_land(0.0, -10.5));
+    }
+
+    #[test]
+    fn simplified_copies_are_picked_by_tolerance() {
+        // A dense, gently wiggling line out to the antimeridian, on the
+        // 0.0001° grid points are packed to
+        let points: Vec<(f64, f64)> = (0..=1800).map(|i| (i as f64 * 0.1, ((i as f64).sin() * 40.0).round() / 10_000.0 - 80.0)).collect();
+        let mut line = LineString::new(points.clone());
+        assert!(line.points().zip(&points).all(|(a, b)| (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9));
+
+        line.build_simplified();
+        assert!(std::ptr::eq(line.at_tolerance(0.0), &line));
+        let coarse = line.at_tolerance(0.5);
+        assert!(coarse.len() < 10, "{} points left", coarse.len());
+        let ends = (coarse.points().next().unwrap(), coarse.points().last().unwrap());
+        assert!((ends.0.0 - 0.0).abs() < 1e-9 && (ends.1.0 - 180.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn coarse_tiers_are_derived_from_high_detail_alone() {
+        let mut renderer = MapRenderer::new();
+        let coast: Vec<(f64, f64)> = (0..=2000).map(|i| (-100.0 + i as f64 * 0.1, (i as f64).sin() * 0.1)).collect();
+        renderer.add_coastline(coast, Lod::High);
+        renderer.add_coastline(vec![(5.0, 5.0), (5.05, 5.0), (5.05, 5.05), (5.0, 5.0)], Lod::High);
+        renderer.build_spatial_indexes();
+
+        assert_eq!(renderer.coastlines_medium.len(), 2);
+        // The islet is too small for the world view
+        assert_eq!(renderer.coastlines_low.len(), 1);
+        assert!(renderer.coastlines_low[0].len() < renderer.coastlines_medium[0].len());
+        assert!(renderer.coastlines_medium[0].len() < renderer.coastlines_high[0].len());
+
+        let projection = Projection::new(crate::map::Viewport::world(240, 120));
+        let layers = renderer.render(120, 30, &projection);
+        assert!((0..30).any(|y| layers.coastlines.row_raw(y).iter().any(|&b| b != 0)));
+    }
+
+    #[test]
+    fn every_projection_fills_land_around_its_holes() {
+        let square = |r: f64| vec![(-r, -r), (r, -r), (r, r), (-r, r), (-r, -r)];
+        let mut renderer = MapRenderer::new();
+        renderer.add_land_polygon(vec![square(60.0), square(30.0)], Lod::Low);
+        renderer.lakes.push(Polygon::new(vec![square(12.0)]));
+
+        let mut projection = Projection::new(crate::map::Viewport::world(240, 120));
+        for _ in 0..5 {
+            let layers = renderer.render(120, 30, &projection);
+            let cell = |canvas: &BrailleCanvas, lon: f64, lat: f64| {
+                let (px, py) = projection.project_point(lon, lat).unwrap();
+                canvas.row_raw(py as usize / 4)[px as usize / 2]
+            };
+            let name = projection.name();
+            assert_eq!(cell(&layers.fills.land, 45.0, 0.0), 0xFF, "{name}: land");
+            assert_eq!(cell(&layers.fills.land, 0.0, 0.0), 0, "{name}: hole");
+            assert_eq!(cell(&layers.fills.lakes, 0.0, 0.0), 0xFF, "{name}: lake");
+            assert_eq!(cell(&layers.fills.lakes, 45.0, 0.0), 0, "{name}: lake");
+            projection.toggle();
+        }
+
+        // Countries are filled only for the faction tint
+        let projection = Projection::new(crate::map::Viewport::world(240, 120));
+        let blank = |canvas: &BrailleCanvas| (0..30).all(|y| canvas.row_raw(y).iter().all(|&b| b == 0));
+        renderer.country_polygons.push(("Squareland".to_string(), Polygon::new(vec![square(60.0)])));
+        assert!(blank(&renderer.render(120, 30, &projection).fills.countries));
+        renderer.settings.show_factions = true;
+        assert!(!blank(&renderer.render(120, 30, &projection).fills.countries));
+
+        renderer.settings.show_fills = false;
+        let layers = renderer.render(120, 30, &projection);
+        assert!(blank(&layers.fills.land) && blank(&layers.fills.countries));
+    }
+
+    #[test]
+    fn place_names_show_by_zoom_without_overlapping_cities() {
+        let mut renderer = MapRenderer::new();
+        renderer.place_labels.push(PlaceLabel::new(10.0, 10.0, "Testland", PlaceKind::Country, Some(2.0)));
+        renderer.place_labels.push(PlaceLabel::new(12.0, 8.0, "Upper Test", PlaceKind::State, Some(7.0)));
+        for (i, name) in ["Alpha", "Beta", "Gamma"].iter().enumerate() {
+            renderer.add_city(9.5 + i as f64 * 0.5, 10.0, name, 2_000_000 - i as u64, false, false, "");
+        }
+
+        let names_at = |renderer: &MapRenderer, zoom: f64| {
+            let projection = Projection::new(crate::map::Viewport::new(10.0, 10.0, zoom, 240, 120));
+            let labels = renderer.render(120, 30, &projection).labels;
+            // No two labels share a cell
+            let mut cells = std::collections::HashSet::new();
+            for MapLabel { x, y, text, .. } in &labels {
+                for i in 0..text.chars().count() as u16 {
+                    assert!(cells.insert((x + i, *y)), "{text} overlaps at ({}, {y})", x + i);
+                }
+            }
+            labels.into_iter().map(|l| l.text).collect::<Vec<_>>()
+        };
+
+        let names = names_at(&renderer, 1.0);
+        assert!(names.contains(&"TESTLAND".to_string()), "{names:?}");
+        assert!(!names.contains(&"Upper Test".to_string()));
+
+        let names = names_at(&renderer, 10.0);
+        assert!(names.contains(&"TESTLAND".to_string()) && names.contains(&"Upper Test".to_string()), "{names:?}");
+        assert!(names.contains(&"Alpha".to_string()), "{names:?}");
+    }
 }
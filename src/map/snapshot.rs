@@ -0,0 +1,277 @@
+//! Screenshots: the current view re-rendered off screen as PNG or SVG.
+//!
+//! The terminal draws into braille cells. A screenshot reuses the same
+//! line data and projection code but draws real lines at any resolution,
+//! into either an RGB raster (written as PNG) or an SVG document. Both
+//! implement `Surface`, so the map is drawn once for either format.
+
+use crate::map::projection::Projection;
+use crate::map::renderer::{LineString, Lod, MapRenderer};
+use anyhow::{bail, Context, Result};
+use std::fmt::Write as _;
+use std::path::Path;
+
+pub type Rgb = (u8, u8, u8);
+
+pub const BACKGROUND: Rgb = (8, 10, 18);
+const OCEAN: Rgb = (14, 24, 44);
+const COASTLINE: Rgb = (0, 200, 220);
+const BORDER: Rgb = (90, 160, 180);
+const STATE: Rgb = (150, 140, 40);
+const CITY: Rgb = (235, 235, 235);
+const FIRE: Rgb = (255, 120, 0);
+const FALLOUT: Rgb = (140, 200, 40);
+
+/// Something the map can be drawn onto, in pixel coordinates
+pub trait Surface {
+    fn line(&mut self, from: (i32, i32), to: (i32, i32), color: Rgb);
+    /// Filled circle, blended over what's there at `opacity` (0–1)
+    fn disc(&mut self, center: (i32, i32), radius: f64, color: Rgb, opacity: f64);
+    fn label(&mut self, at: (i32, i32), text: &str, color: Rgb);
+}
+
+/// What's happening on the ground, drawn over the map
+#[derive(Default)]
+pub struct Overlays {
+    /// (lon, lat, intensity 0–255)
+    pub fires: Vec<(f64, f64, u8)>,
+    /// Fallout and gas puffs as (lon, lat, radius km)
+    pub plumes: Vec<(f64, f64, f64)>,
+}
+
+/// Copy of `projection` resized to `width`×`height` pixels, showing the same
+/// view. The globe's radius is in pixels, so it scales with whichever side
+/// grew least, keeping the whole view in frame.
+pub fn scaled_projection(projection: &Projection, width: usize, height: usize) -> Projection {
+    let mut scaled = projection.clone();
+    scaled.view_mut().rescale(width, height);
+    scaled
+}
+
+/// Draw the map and overlays. `projection` must already be sized to the
+/// surface.
+pub fn draw(surface: &mut impl Surface, renderer: &MapRenderer, projection: &Projection, overlays: &Overlays) {
+    draw_basemap(surface, renderer, projection);
+    let settings = &renderer.settings;
+
+    for &(lon, lat, radius_km) in &overlays.plumes {
+        if let Some(p) = projection.project_point(lon, lat) {
+            surface.disc(p, projection.deg_to_pixels(radius_km / 111.0), FALLOUT, 0.15);
+        }
+    }
+    let fire_radius = projection.deg_to_pixels(0.1).max(1.0);
+    for &(lon, lat, intensity) in &overlays.fires {
+        if let Some(p) = projection.project_point(lon, lat) {
+            surface.disc(p, fire_radius, FIRE, 0.3 + 0.7 * intensity as f64 / 255.0);
+        }
+    }
+
+    if settings.show_cities {
+        for city in renderer.city_grid.iter().filter(|c| c.population > 0) {
+            let Some(p) = projection.project_point(city.lon, city.lat) else { continue };
+            let radius = 1.0 + (city.population as f64).log10().max(4.0) - 4.0;
+            surface.disc(p, radius, CITY, 0.9);
+            if settings.show_labels && (city.is_capital || city.is_megacity) {
+                surface.label((p.0 + radius as i32 + 2, p.1 + 4), &city.name, CITY);
+            }
+        }
+    }
+}
+
+/// The globe's ocean, coastlines and borders: the map with nothing
+/// happening on it
+pub fn draw_basemap(surface: &mut impl Surface, renderer: &MapRenderer, projection: &Projection) {
+    let zoom = projection.effective_zoom();
+    let lod = Lod::from_zoom(zoom);
+    let settings = &renderer.settings;
+
+    if projection.is_globe() {
+        let (w, h) = projection.size();
+        let radius = projection.deg_to_pixels(1.0_f64.to_degrees());
+        surface.disc((w as i32 / 2, h as i32 / 2), radius, OCEAN, 1.0);
+    }
+    if settings.show_coastlines {
+        draw_lines(surface, renderer.get_coastlines(lod), projection, COASTLINE);
+    }
+    if settings.show_borders {
+        draw_lines(surface, renderer.get_borders(lod), projection, BORDER);
+        if settings.show_states && zoom >= 4.0 {
+            draw_lines(surface, &renderer.states, projection, STATE);
+        }
+    }
+}
+
+/// Draw each linestring's on-screen segments
+fn draw_lines(surface: &mut impl Surface, lines: &[LineString], projection: &Projection, color: Rgb) {
+    for line in lines {
+        projection.view().segments(line, &mut |a, b| surface.line(a, b, color));
+    }
+}
+
+/// Render the view at `width`×`height` and write it to `path`. The format
+/// follows the extension: `.png` or `.svg`.
+pub fn save(path: &Path, renderer: &MapRenderer, projection: &Projection, overlays: &Overlays, width: u32, height: u32) -> Result<()> {
+    let projection = scaled_projection(projection, width as usize, height as usize);
+    let bytes = match path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("png") => {
+            let mut raster = Raster::new(width, height, BACKGROUND);
+            draw(&mut raster, renderer, &projection, overlays);
+            raster.encode_png()?
+        }
+        Some("svg") => {
+            let mut svg = Svg::new(width, height, BACKGROUND);
+            draw(&mut svg, renderer, &projection, overlays);
+            svg.finish().into_bytes()
+        }
+        _ => bail!("screenshot must end in .png or .svg: {}", path.display()),
+    };
+    std::fs::write(path, bytes).with_context(|| format!("writing {}", path.display()))
+}
+
+/// RGB pixel buffer
+pub struct Raster {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Raster {
+    pub fn new(width: u32, height: u32, background: Rgb) -> Self {
+        let pixels = [background.0, background.1, background.2].repeat(width as usize * height as usize);
+        Self { width, height, pixels }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Rgb {
+        let i = (y as usize * self.width as usize + x as usize) * 3;
+        (self.pixels[i], self.pixels[i + 1], self.pixels[i + 2])
+    }
+
+    fn blend(&mut self, x: i32, y: i32, color: Rgb, opacity: f64) {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 3;
+        for (c, target) in [color.0, color.1, color.2].into_iter().zip(&mut self.pixels[i..i + 3]) {
+            *target = (*target as f64 + (c as f64 - *target as f64) * opacity).round() as u8;
+        }
+    }
+
+    /// PNG: 8-bit RGB
+    pub fn encode_png(&self) -> Result<Vec<u8>> {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().context("encoding PNG")?;
+        writer.write_image_data(&self.pixels).context("encoding PNG")?;
+        writer.finish().context("encoding PNG")?;
+        Ok(png)
+    }
+}
+
+impl Surface for Raster {
+    fn line(&mut self, from: (i32, i32), to: (i32, i32), color: Rgb) {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let steps = dx.abs().max(dy.abs());
+        // Off-screen segments can be huge; nothing of them would show
+        if steps > 4 * (self.width + self.height) as i32 {
+            return;
+        }
+        for i in 0..=steps {
+            let t = if steps == 0 { 0.0 } else { i as f64 / steps as f64 };
+            self.blend(from.0 + (dx as f64 * t).round() as i32, from.1 + (dy as f64 * t).round() as i32, color, 1.0);
+        }
+    }
+
+    fn disc(&mut self, (cx, cy): (i32, i32), radius: f64, color: Rgb, opacity: f64) {
+        let r = radius.ceil() as i32;
+        for y in (cy - r).max(0)..=(cy + r).min(self.height as i32 - 1) {
+            for x in (cx - r).max(0)..=(cx + r).min(self.width as i32 - 1) {
+                let (fx, fy) = ((x - cx) as f64, (y - cy) as f64);
+                if fx * fx + fy * fy <= radius * radius {
+                    self.blend(x, y, color, opacity);
+                }
+            }
+        }
+    }
+
+    /// No font to rasterize with — labels are SVG only
+    fn label(&mut self, _at: (i32, i32), _text: &str, _color: Rgb) {}
+}
+
+/// SVG document built up as text
+pub struct Svg {
+    body: String,
+}
+
+impl Svg {
+    pub fn new(width: u32, height: u32, background: Rgb) -> Self {
+        let mut body = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+        );
+        let _ = writeln!(body, "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>", hex(background));
+        let _ = writeln!(body, "<g stroke-width=\"1\" stroke-linecap=\"round\" font-family=\"monospace\" font-size=\"11\">");
+        Self { body }
+    }
+
+    pub fn finish(mut self) -> String {
+        self.body.push_str("</g>\n</svg>\n");
+        self.body
+    }
+}
+
+impl Surface for Svg {
+    fn line(&mut self, from: (i32, i32), to: (i32, i32), color: Rgb) {
+        let _ = writeln!(self.body, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\"/>", from.0, from.1, to.0, to.1, hex(color));
+    }
+
+    fn disc(&mut self, center: (i32, i32), radius: f64, color: Rgb, opacity: f64) {
+        let _ = writeln!(
+            self.body,
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{radius:.1}\" fill=\"{}\" fill-opacity=\"{opacity:.2}\"/>",
+            center.0, center.1, hex(color)
+        );
+    }
+
+    fn label(&mut self, at: (i32, i32), text: &str, color: Rgb) {
+        let text = text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        let _ = writeln!(self.body, "<text x=\"{}\" y=\"{}\" fill=\"{}\">{text}</text>", at.0, at.1, hex(color));
+    }
+}
+
+fn hex((r, g, b): Rgb) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn png_decodes_to_the_pixels_drawn() {
+        let mut raster = Raster::new(40, 20, BACKGROUND);
+        raster.line((0, 0), (39, 19), COASTLINE);
+        raster.disc((20, 10), 3.0, FIRE, 1.0);
+        assert_eq!(raster.pixel(0, 0), COASTLINE);
+        assert_eq!(raster.pixel(20, 10), FIRE);
+        assert_eq!(raster.pixel(39, 0), BACKGROUND);
+
+        let png = raster.encode_png().unwrap();
+        let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let frame = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((frame.width, frame.height, frame.color_type), (40, 20, png::ColorType::Rgb));
+        assert_eq!(pixels[..frame.buffer_size()], raster.pixels);
+    }
+
+    #[test]
+    fn svg_escapes_labels() {
+        let mut svg = Svg::new(10, 10, BACKGROUND);
+        svg.label((1, 2), "A&B <C>", CITY);
+        svg.line((0, 0), (5, 5), COASTLINE);
+        let doc = svg.finish();
+        assert!(doc.contains(">A&amp;B &lt;C&gt;</text>"));
+        assert!(doc.contains("stroke=\"#00c8dc\""));
+        assert!(doc.ends_with("</svg>\n"));
+    }
+}
@@ -15,6 +15,9 @@ pub struct SpatialGrid<T> {
     items: Vec<T>,
     /// Cell size in degrees
     cell_size: f64,
+    /// Bumped whenever the items may have changed: on insertion, clearing
+    /// and any mutable access
+    generation: u64,
 }
 
 impl<T> SpatialGrid<T> {
@@ -24,16 +27,30 @@ impl<T> SpatialGrid<T> {
             cells: HashMap::new(),
             items: Vec::new(),
             cell_size,
+            generation: 0,
         }
     }
 
+    /// Equal generations mean the items haven't changed in between
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Remove every item
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.items.clear();
+        self.generation += 1;
+    }
+
     /// Insert an item at a geographic position
     pub fn insert(&mut self, lon: f64, lat: f64, item: T) {
         let idx = self.items.len();
         self.items.push(item);
+        self.generation += 1;
 
         let cell = to_cell(lon, lat, self.cell_size);
-        self.cells.entry(cell).or_insert_with(Vec::new).push(idx);
+        self.cells.entry(cell).or_default().push(idx);
     }
 
     /// Query items in a radius around a point (returns indices)
@@ -86,14 +103,39 @@ impl<T> SpatialGrid<T> {
     /// Get mutable item by index
     #[inline(always)]
     pub fn get_mut(&mut self, idx: usize) -> Option<&mut T> {
+        self.generation += 1;
         self.items.get_mut(idx)
     }
 
+    /// Bytes the items and cells take up, not counting what the items
+    /// themselves point to
+    pub fn heap_bytes(&self) -> usize {
+        self.items.capacity() * std::mem::size_of::<T>()
+            + self.cells.values().map(|c| std::mem::size_of::<((i32, i32), Vec<usize>)>() + c.capacity() * std::mem::size_of::<usize>()).sum::<usize>()
+    }
+
+    /// Iterate all items in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+
+    /// Iterate all items mutably in insertion order
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.generation += 1;
+        self.items.iter_mut()
+    }
+
     /// Number of items
     #[inline(always)]
     pub fn len(&self) -> usize {
         self.items.len()
     }
+
+    /// Whether the grid holds no items
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
 }
 
 /// Spatial index for geographic features using flat row-major grid.
@@ -124,6 +166,12 @@ impl FeatureGrid {
         }
     }
 
+    /// Bytes the cells take up, indices included
+    pub fn heap_bytes(&self) -> usize {
+        self.cells.capacity() * std::mem::size_of::<Vec<usize>>()
+            + self.cells.iter().map(|c| c.capacity() * std::mem::size_of::<usize>()).sum::<usize>()
+    }
+
     /// Convert lon/lat to flat array index. Returns None if out of bounds.
     #[inline(always)]
     fn cell_index(&self, lon_cell: i32, lat_cell: i32) -> Option<usize> {
@@ -0,0 +1,252 @@
+//! Text dumps: the map composited into a grid of characters instead of a
+//! ratatui `Buffer`, then written out as plain braille or with 24-bit ANSI
+//! colors.
+//!
+//! Layers go down back to front in the same order as the terminal view —
+//! canvases, then plumes and fires in the gaps, then facilities and city
+//! labels on top — so a dump reads like the frame it came from.
+
+use crate::braille::BrailleCanvas;
+use crate::heatmap::ColorRamp;
+use crate::map::renderer::MapRenderer;
+use crate::map::snapshot::{Overlays, Rgb};
+use crate::map::fonts;
+use crate::map::labels::truncate_to_width;
+use crate::map::{Infrastructure, Projection};
+use unicode_width::UnicodeWidthChar;
+use std::fmt::Write as _;
+
+const GLOBE_OUTLINE: Rgb = (50, 50, 50);
+const COUNTY: Rgb = (110, 110, 110);
+const ROAD: Rgb = (140, 105, 60);
+const RAILWAY: Rgb = (110, 110, 130);
+const WRECKAGE: Rgb = (120, 40, 30);
+const STATE: Rgb = (205, 205, 0);
+const COASTLINE: Rgb = (0, 205, 205);
+const PLUME: Rgb = (120, 170, 40);
+const DEAD: Rgb = (110, 110, 110);
+
+#[derive(Clone, Copy, PartialEq)]
+struct Cell {
+    ch: char,
+    fg: Option<Rgb>,
+}
+
+const BLANK: Cell = Cell { ch: ' ', fg: None };
+
+/// Right half of a two-cell character, written out as nothing
+const WIDE_TAIL: char = '\0';
+
+/// A frame of characters, each with an optional foreground color
+pub struct TextFrame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl TextFrame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self { width, height, cells: vec![BLANK; width * height] }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn char_at(&self, x: usize, y: usize) -> char {
+        self.cells[y * self.width + x].ch
+    }
+
+    pub fn put(&mut self, x: usize, y: usize, ch: char, fg: Rgb) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = Cell { ch, fg: Some(fg) };
+        }
+    }
+
+    /// Like `put`, but leaves cells that already hold something
+    fn fill(&mut self, x: usize, y: usize, ch: char, fg: Rgb) {
+        if x < self.width && y < self.height && self.cells[y * self.width + x].ch == ' ' {
+            self.put(x, y, ch, fg);
+        }
+    }
+
+    /// Text running right from (x, y), cut at the right edge. Wide
+    /// characters take two cells; combining marks, which a cell of one
+    /// `char` can't hold, are dropped.
+    pub fn text(&mut self, x: usize, y: usize, text: &str, fg: Rgb) {
+        let text = truncate_to_width(text, self.width.saturating_sub(x));
+        let mut x = x;
+        for ch in text.chars() {
+            match ch.width().unwrap_or(0) {
+                0 => {}
+                1 => self.put(x, y, ch, fg),
+                _ => {
+                    self.put(x, y, ch, fg);
+                    self.put(x + 1, y, WIDE_TAIL, fg);
+                }
+            }
+            x += ch.width().unwrap_or(0);
+        }
+    }
+
+    /// Overwrite with a braille canvas's non-empty cells
+    pub fn layer(&mut self, canvas: &BrailleCanvas, fg: Rgb) {
+        for y in 0..canvas.char_height().min(self.height) {
+            for (x, &bits) in canvas.row_raw(y).iter().enumerate().take(self.width) {
+                if bits != 0 {
+                    self.put(x, y, char::from_u32(0x2800 + bits as u32).unwrap_or(' '), fg);
+                }
+            }
+        }
+    }
+
+    /// Rows of characters, trailing blanks trimmed. Styled label letters
+    /// are written as plain ASCII, so the names can be searched for.
+    pub fn to_plain(&self) -> String {
+        let mut out = String::with_capacity(self.cells.len() * 3);
+        for row in self.cells.chunks(self.width) {
+            let line: String = row.iter().map(|c| c.ch).filter(|&ch| ch != WIDE_TAIL).collect();
+            out.push_str(fonts::plain(&line).trim_end());
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Rows with 24-bit ANSI foreground colors. A color code is only
+    /// emitted when it changes, and every row ends reset.
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::with_capacity(self.cells.len() * 6);
+        for row in self.cells.chunks(self.width) {
+            let end = row.iter().rposition(|c| c.ch != ' ').map_or(0, |i| i + 1);
+            let mut current = None;
+            for cell in &row[..end] {
+                if cell.ch != ' ' && cell.fg != current {
+                    match cell.fg {
+                        Some((r, g, b)) => write!(out, "\x1b[38;2;{r};{g};{b}m").ok(),
+                        None => write!(out, "\x1b[39m").ok(),
+                    };
+                    current = cell.fg;
+                }
+                if cell.ch != WIDE_TAIL {
+                    out.push(cell.ch);
+                }
+            }
+            if current.is_some() {
+                out.push_str("\x1b[0m");
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Composite the map and overlays at `projection`'s size, one character
+/// per 2×4 braille pixels.
+pub fn compose(renderer: &MapRenderer, projection: &Projection, overlays: &Overlays) -> TextFrame {
+    let (px_width, px_height) = projection.size();
+    let (width, height) = (px_width / 2, px_height / 4);
+    let layers = renderer.render(width, height, projection);
+    let mut frame = TextFrame::new(width, height);
+
+    if let Some(outline) = &layers.globe_outline {
+        frame.layer(outline, GLOBE_OUTLINE);
+    }
+    frame.layer(&layers.counties, COUNTY);
+    frame.layer(&layers.infrastructure.roads, ROAD);
+    frame.layer(&layers.infrastructure.railways, RAILWAY);
+    frame.layer(&layers.infrastructure.wreckage, WRECKAGE);
+    frame.layer(&layers.states, STATE);
+    frame.layer(&layers.coastlines, COASTLINE);
+    frame.layer(&layers.borders, COASTLINE);
+
+    let to_cell = |lon: f64, lat: f64| {
+        let (px, py) = projection.project_point(lon, lat)?;
+        (px >= 0 && py >= 0).then_some((px as usize / 2, py as usize / 4))
+    };
+
+    for &(lon, lat, intensity) in &overlays.fires {
+        if let Some((x, y)) = to_cell(lon, lat) {
+            let (ch, fg) = ColorRamp::FIRE.flame(intensity);
+            frame.put(x, y, ch, fg);
+        }
+    }
+
+    // Plumes haze the empty cells they cover; cells are about twice as tall
+    // as they are wide
+    for &(lon, lat, radius_km) in &overlays.plumes {
+        let Some((cx, cy)) = to_cell(lon, lat) else { continue };
+        let r = (projection.deg_to_pixels(radius_km / 111.0) / 2.0).max(1.0);
+        let (rx, ry) = (r as isize, (r / 2.0).ceil() as isize);
+        for dy in -ry..=ry {
+            for dx in -rx..=rx {
+                let dist = ((dx as f64 / r).powi(2) + (2.0 * dy as f64 / r).powi(2)).sqrt();
+                let (x, y) = (cx as isize + dx, cy as isize + dy);
+                if dist <= 1.0 && x >= 0 && y >= 0 {
+                    frame.fill(x as usize, y as usize, if dist < 0.5 { '▒' } else { '░' }, PLUME);
+                }
+            }
+        }
+    }
+
+    for &(x, y, kind, destroyed) in &layers.facilities {
+        let (ch, fg) = match (kind, destroyed) {
+            (_, true) => ('✖', (140, 30, 20)),
+            (Infrastructure::Port, false) => (kind.glyph(), (80, 150, 200)),
+            _ => (kind.glyph(), (170, 190, 210)),
+        };
+        frame.put(x as usize, y as usize, ch, fg);
+    }
+
+    for label in &layers.labels {
+        let fg = if label.health == 0.0 {
+            DEAD
+        } else if label.blackout {
+            let b = label.health * 60.0 + 50.0;
+            (b as u8, (b * 1.1) as u8, (b * 1.6) as u8)
+        } else {
+            let b = (label.health * label.brightness * 200.0 + 55.0) as u8;
+            (b, b, b)
+        };
+        frame.text(label.x as usize, label.y as usize, &label.text, fg);
+    }
+
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ansi_codes_only_change_with_color() {
+        let mut frame = TextFrame::new(6, 2);
+        frame.text(0, 0, "ab", (255, 0, 0));
+        frame.put(3, 0, 'c', (0, 255, 0));
+        frame.put(1, 1, '⠁', (255, 0, 0));
+
+        assert_eq!(frame.to_plain(), "ab c\n ⠁\n");
+        assert_eq!(
+            frame.to_ansi(),
+            "\x1b[38;2;255;0;0mab \x1b[38;2;0;255;0mc\x1b[0m\n \x1b[38;2;255;0;0m⠁\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn fires_and_plumes_land_in_their_cells() {
+        let mut renderer = MapRenderer::new();
+        renderer.settings.show_cities = false;
+        let projection = Projection::new(crate::map::Viewport::world(80, 40));
+        let overlays = Overlays { fires: vec![(0.0, 0.0, 255)], plumes: vec![(90.0, 0.0, 2000.0)] };
+
+        let frame = compose(&renderer, &projection, &overlays);
+        assert_eq!((frame.width(), frame.height()), (40, 10));
+        let (px, py) = projection.project_point(0.0, 0.0).unwrap();
+        assert_eq!(frame.char_at(px as usize / 2, py as usize / 4), '█');
+        let (px, py) = projection.project_point(90.0, 0.0).unwrap();
+        assert_eq!(frame.char_at(px as usize / 2, py as usize / 4), '▒');
+    }
+}
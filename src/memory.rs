@@ -0,0 +1,124 @@
+//! Memory accounting, and a budget that keeps the map data under it.
+//!
+//! The renderer reports the bytes each layer holds: lines with their
+//! simplified copies, polygons, the spatial indexes and the cached
+//! canvases. Over budget, county chunks out of view go first, then whole
+//! detail tiers, least recently needed first. A dropped tier is read from
+//! its files again, off the main thread, when the view next needs it;
+//! until then drawing falls back to the next coarser tier. A tier the zoom
+//! is close to needing is kept, so zooming back and forth over a tier's
+//! threshold doesn't drop and read it each time.
+
+use crate::map::Lod;
+use anyhow::{anyhow, Result};
+
+/// Frames between checks against the budget (half a second at 60fps)
+const CHECK_FRAMES: u64 = 30;
+
+/// A tier isn't dropped while the zoom is within this factor of needing it
+const KEEP_NEAR: f64 = 1.25;
+
+/// The budget and what's been dropped to meet it
+#[derive(Clone, Debug, Default)]
+pub struct MemoryBudget {
+    /// Bytes to stay under, or None for no limit
+    pub limit: Option<usize>,
+    /// Tiers dropped to stay under budget, to load again when needed
+    pub evicted: Vec<Lod>,
+    /// Frame each tier (Low, Medium, High) was last needed by the view
+    last_needed: [u64; 3],
+    frame: u64,
+}
+
+fn tier_index(lod: Lod) -> usize {
+    match lod {
+        Lod::Low => 0,
+        Lod::Medium => 1,
+        Lod::High => 2,
+    }
+}
+
+impl MemoryBudget {
+    pub fn new(limit: Option<usize>) -> Self {
+        Self { limit, ..Self::default() }
+    }
+
+    /// Note a frame whose view needs `lod`. Returns true on the frames
+    /// memory should be checked against the budget.
+    pub fn need(&mut self, lod: Lod) -> bool {
+        self.frame += 1;
+        self.last_needed[tier_index(lod)] = self.frame;
+        self.frame.is_multiple_of(CHECK_FRAMES)
+    }
+
+    /// The tier to drop next: of the detail tiers `droppable` allows, the
+    /// one needed longest ago. The coarsest tier, the one `zoom` needs and
+    /// any it's within `KEEP_NEAR` of needing are never dropped.
+    pub fn victim(&self, zoom: f64, droppable: impl Fn(Lod) -> bool) -> Option<Lod> {
+        let near = [Lod::from_zoom(zoom / KEEP_NEAR), Lod::from_zoom(zoom), Lod::from_zoom(zoom * KEEP_NEAR)];
+        [Lod::Medium, Lod::High]
+            .into_iter()
+            .filter(|&lod| !near.contains(&lod) && !self.evicted.contains(&lod) && droppable(lod))
+            .min_by_key(|&lod| self.last_needed[tier_index(lod)])
+    }
+}
+
+/// A size as `512 KB`, `84.2 MB` or `1.5 GB`
+pub fn format_bytes(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    let b = bytes as f64;
+    if b < KB * KB {
+        format!("{:.0} KB", b / KB)
+    } else if b < KB * KB * KB {
+        format!("{:.1} MB", b / (KB * KB))
+    } else {
+        format!("{:.1} GB", b / (KB * KB * KB))
+    }
+}
+
+/// A budget as megabytes (`512`), or with a unit (`512M`, `2G`, `2GB`)
+pub fn parse_size(s: &str) -> Result<usize> {
+    let s = s.trim().to_ascii_lowercase();
+    let s = s.strip_suffix('b').unwrap_or(&s);
+    let (number, scale) = match s.strip_suffix('g') {
+        Some(n) => (n, 1024.0 * 1024.0 * 1024.0),
+        None => (s.strip_suffix('m').unwrap_or(s), 1024.0 * 1024.0),
+    };
+    let n: f64 = number.trim().parse().map_err(|_| anyhow!("invalid memory budget (expected e.g. 512M or 2G): {s}"))?;
+    if !(n > 0.0 && n.is_finite()) {
+        return Err(anyhow!("memory budget must be positive: {s}"));
+    }
+    Ok((n * scale) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_tier_needed_longest_ago_goes_first() {
+        let mut budget = MemoryBudget::new(Some(1));
+        budget.need(Lod::High);
+        budget.need(Lod::Medium);
+        budget.need(Lod::Low);
+        assert_eq!(budget.victim(1.0, |_| true), Some(Lod::High));
+        assert_eq!(budget.victim(20.0, |_| true), Some(Lod::Medium));
+        assert_eq!(budget.victim(1.0, |lod| lod != Lod::High), Some(Lod::Medium));
+        // Just short of needing the high tier, it stays
+        assert_eq!(budget.victim(5.0, |_| true), Some(Lod::High));
+        assert_eq!(budget.victim(7.0, |_| true), None);
+        budget.evicted = vec![Lod::High, Lod::Medium];
+        assert_eq!(budget.victim(1.0, |_| true), None);
+    }
+
+    #[test]
+    fn sizes_parse_and_print() {
+        assert_eq!(parse_size("512").unwrap(), 512 << 20);
+        assert_eq!(parse_size("2G").unwrap(), 2 << 30);
+        assert_eq!(parse_size("1.5gb").unwrap(), 3 << 29);
+        assert!(parse_size("lots").is_err());
+        assert!(parse_size("0").is_err());
+        assert_eq!(format_bytes(84 << 20), "84.0 MB");
+        assert_eq!(format_bytes(2048), "2 KB");
+    }
+}
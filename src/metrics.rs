@@ -0,0 +1,267 @@
+//! An HTTP endpoint for keeping an eye on long sessions left running on a
+//! server. `/metrics` answers in the Prometheus text format and `/state`
+//! with a JSON snapshot of the world.
+//!
+//! The main loop publishes both documents about once a second. A thread
+//! accepts connections and answers each on a thread of its own from
+//! whatever was published last, so a slow or stuck client never holds up a
+//! frame or another client. A client gets `MAX_REQUEST` bytes and
+//! `REQUEST_TIMEOUT` in all to ask, and only `MAX_CLIENTS` are answered at
+//! once. It's plain HTTP/1.0, one request per connection, which is all a
+//! scraper or `curl` needs.
+
+use crate::app::App;
+use anyhow::{Context, Result};
+use simd_json::json;
+use simd_json::prelude::*;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often the documents are brought up to date
+const PUBLISH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest a client gets to send its request, and to take the answer
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Most bytes of a request read, headers and all
+const MAX_REQUEST: u64 = 8 * 1024;
+
+/// Connections answered at once; more are closed straight away
+const MAX_CLIENTS: usize = 8;
+
+/// Cities listed in `/state`, worst hit first
+const STATE_CITIES: usize = 20;
+
+/// What the endpoint serves, as last published
+#[derive(Default)]
+struct Published {
+    metrics: String,
+    state: String,
+}
+
+pub struct MetricsServer {
+    published: Arc<Mutex<Published>>,
+    port: u16,
+    next_publish: Option<Instant>,
+}
+
+impl MetricsServer {
+    /// Listen on `port` at `addr`; 0 picks a free port
+    pub fn start(addr: IpAddr, port: u16) -> Result<Self> {
+        let listener = TcpListener::bind((addr, port)).with_context(|| format!("listening for metrics on {addr}:{port}"))?;
+        let port = listener.local_addr()?.port();
+        let published = Arc::new(Mutex::new(Published::default()));
+        let shared = Arc::clone(&published);
+        thread::spawn(move || {
+            let clients = Arc::new(AtomicUsize::new(0));
+            for stream in listener.incoming().flatten() {
+                if clients.fetch_add(1, Ordering::SeqCst) >= MAX_CLIENTS {
+                    clients.fetch_sub(1, Ordering::SeqCst);
+                    continue;
+                }
+                let (shared, clients) = (Arc::clone(&shared), Arc::clone(&clients));
+                thread::spawn(move || {
+                    let _ = answer(stream, &shared);
+                    clients.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        Ok(Self { published, port, next_publish: None })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Bring the documents up to date, if it's been long enough since
+    /// they last were
+    pub fn publish(&mut self, app: &App, now: Instant) {
+        if self.next_publish.is_some_and(|next| now < next) {
+            return;
+        }
+        self.next_publish = Some(now + PUBLISH_INTERVAL);
+        let (metrics, state) = (metrics_text(app), state_json(app));
+        if let Ok(mut published) = self.published.lock() {
+            *published = Published { metrics, state };
+        }
+    }
+}
+
+/// Read one request and answer it
+fn answer(stream: TcpStream, published: &Mutex<Published>) -> io::Result<()> {
+    stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+    let until = Instant::now() + REQUEST_TIMEOUT;
+    let mut reader = BufReader::new(Deadline { stream: &stream, until }.take(MAX_REQUEST));
+    let mut request = String::new();
+    reader.read_line(&mut request)?;
+    if !request.ends_with('\n') {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "request line too long"));
+    }
+    // Headers aren't needed, but a client may wait until they're read
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 2 {
+        line.clear();
+    }
+
+    let mut parts = request.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = path.split('?').next().unwrap_or(path);
+    let (status, content_type, body) = {
+        let published = published.lock().map_err(|_| io::Error::other("metrics poisoned"))?;
+        match (method, path) {
+            ("GET" | "HEAD", "/metrics") => ("200 OK", "text/plain; version=0.0.4", published.metrics.clone()),
+            ("GET" | "HEAD", "/state") => ("200 OK", "application/json", published.state.clone()),
+            ("GET" | "HEAD", _) => ("404 Not Found", "text/plain", "try /metrics or /state\n".to_string()),
+            _ => ("405 Method Not Allowed", "text/plain", String::new()),
+        }
+    };
+    let mut stream = &stream;
+    write!(stream, "HTTP/1.0 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len())?;
+    if method != "HEAD" {
+        stream.write_all(body.as_bytes())?;
+    }
+    stream.flush()
+}
+
+/// A stream that gives up once `until` has passed, however slowly the
+/// bytes trickle in
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    until: Instant,
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        self.stream.read(buf)
+    }
+}
+
+/// Prometheus text exposition of the numbers worth graphing
+fn metrics_text(app: &App) -> String {
+    let gauges: [(&str, &str, f64); 9] = [
+        ("fps", "Frames drawn per second", app.perf.fps()),
+        ("frame_ms", "Time to draw and step one frame, in milliseconds", app.perf.frame_ms()),
+        ("features", "Map features drawn in the last frame", app.perf.features as f64),
+        ("fires", "Fire cells burning", app.fires.len() as f64),
+        ("projectiles", "Missiles in flight", app.projectiles.len() as f64),
+        ("explosions", "Blasts still expanding", app.explosions.len() as f64),
+        ("fallout_zones", "Fallout plumes drifting", app.fallout.len() as f64),
+        ("zoom", "Map zoom", app.projection.effective_zoom()),
+        ("sim_time_seconds", "Simulated time, as unix seconds", app.clock.unix_secs()),
+    ];
+    let counters: [(&str, &str, f64); 6] = [
+        ("casualties_total", "People killed so far", app.casualties as f64),
+        ("births_total", "People born back into rebuilding cities", app.stats.births as f64),
+        ("strikes_launched_total", "Strikes launched by the player", app.stats.strikes_launched as f64),
+        ("retaliation_waves_total", "Adversary counterstrikes received", app.stats.waves as f64),
+        ("intercepts_total", "Missiles shot down", app.stats.intercepts as f64),
+        ("frames_total", "Simulation frames stepped", app.frame as f64),
+    ];
+    let mut out = String::new();
+    for (kind, metrics) in [("gauge", &gauges[..]), ("counter", &counters[..])] {
+        for (name, help, value) in metrics {
+            let _ = writeln!(out, "# HELP tui_map_{name} {help}\n# TYPE tui_map_{name} {kind}\ntui_map_{name} {value}");
+        }
+    }
+    out
+}
+
+/// The world as JSON: totals, the view, and the worst-hit cities
+fn state_json(app: &App) -> String {
+    let cities: Vec<_> = app
+        .damaged_cities()
+        .iter()
+        .take(STATE_CITIES)
+        .map(|c| {
+            json!({
+                "name": c.name.as_str(),
+                "country": c.country.as_str(),
+                "lon": c.lon,
+                "lat": c.lat,
+                "population": c.population,
+                "casualties": c.original_population - c.population,
+            })
+        })
+        .collect();
+    json!({
+        "frame": app.frame,
+        "sim_time": app.clock.unix_secs(),
+        "casualties": app.casualties,
+        "fires": app.fires.len() as u64,
+        "projectiles": app.projectiles.len() as u64,
+        "strikes_launched": app.stats.strikes_launched,
+        "retaliation_waves": app.stats.waves,
+        "intercepts": app.stats.intercepts,
+        "view": {
+            "projection": app.projection.name(),
+            "lon": app.projection.center_lon(),
+            "lat": app.projection.center_lat(),
+            "zoom": app.projection.effective_zoom(),
+        },
+        "worst_hit": cities,
+    })
+    .encode()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(port: u16, path: &str) -> String {
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_metrics_and_state_once_published() {
+        let mut app = App::new(80, 24);
+        app.casualties = 1_500;
+        let mut server = MetricsServer::start(IpAddr::from([127, 0, 0, 1]), 0).unwrap();
+        let now = Instant::now();
+        server.publish(&app, now);
+
+        let metrics = get(server.port(), "/metrics");
+        assert!(metrics.starts_with("HTTP/1.0 200 OK\r\n"), "{metrics}");
+        assert!(metrics.contains("# TYPE tui_map_casualties_total counter\ntui_map_casualties_total 1500\n"), "{metrics}");
+        assert!(metrics.contains("tui_map_fires 0\n"));
+
+        let state = get(server.port(), "/state?pretty");
+        let body = state.split("\r\n\r\n").nth(1).unwrap();
+        let mut bytes = body.as_bytes().to_vec();
+        let value = simd_json::to_owned_value(&mut bytes).unwrap();
+        assert_eq!(value["casualties"], 1_500u64);
+        assert!(get(server.port(), "/").starts_with("HTTP/1.0 404"));
+
+        // Published no more than once a second
+        app.casualties = 2_000;
+        server.publish(&app, now + Duration::from_millis(500));
+        assert!(get(server.port(), "/metrics").contains("tui_map_casualties_total 1500\n"));
+        server.publish(&app, now + PUBLISH_INTERVAL);
+        assert!(get(server.port(), "/metrics").contains("tui_map_casualties_total 2000\n"));
+    }
+
+    #[test]
+    fn oversized_requests_are_dropped_unanswered() {
+        let server = MetricsServer::start(IpAddr::from([127, 0, 0, 1]), 0).unwrap();
+        let mut stream = TcpStream::connect(("127.0.0.1", server.port())).unwrap();
+        let _ = stream.write_all(&vec![b'a'; MAX_REQUEST as usize + 1]);
+        let mut response = String::new();
+        let _ = stream.read_to_string(&mut response);
+        assert!(response.is_empty(), "{response}");
+        // Others are still answered
+        assert!(get(server.port(), "/metrics").starts_with("HTTP/1.0 200 OK\r\n"));
+    }
+}
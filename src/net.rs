@@ -0,0 +1,445 @@
+//! Two-player mode over TCP: one host, one client, one planet.
+//!
+//! Both sides run the full simulation. Launches, bomb drops and intercepts are
+//! replicated as they happen; the host is authoritative for city damage and
+//! periodically sends the client the populations that changed. City indexes
+//! are positions in the city grid, so both players need the same map data.
+//!
+//! Wire format: each message is a big-endian `u32` payload length followed by
+//! the payload — a one-byte tag and little-endian fields.
+
+use crate::app::WeaponType;
+use crate::faction::Faction;
+use anyhow::{anyhow, bail, Context, Result};
+use std::io::{Read, Write};
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Frames between host → client city syncs
+pub const SYNC_INTERVAL: u64 = 30;
+
+/// Every this many frames the host resends every city, so a client that
+/// reset its world catches up
+pub const FULL_SYNC_INTERVAL: u64 = SYNC_INTERVAL * 20;
+
+/// Largest payload we accept (a full city sync is ~12 bytes per city)
+const MAX_FRAME: usize = 16 << 20;
+
+/// How long a write may wait on a peer that's stopped reading before the
+/// connection is given up as lost
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    /// Host → client on connect: the faction the client plays
+    Welcome { faction: Faction },
+    /// The sender put a missile in the air; a MIRV bus lists where its
+    /// warheads go. The yield (kilotons) is sent as 0 when unknown.
+    Launch { id: u32, from: (f64, f64), to: (f64, f64), weapon: WeaponType, radius_km: f64, yield_kt: Option<f64>, warheads: Vec<(f64, f64)> },
+    /// The sender's bomber dropped its payload
+    Detonation { lon: f64, lat: f64, weapon: WeaponType, radius_km: f64, yield_kt: Option<f64> },
+    /// The sender's defenses shot down the receiver's missile `id`
+    Intercepted { id: u32 },
+    /// Host → client: total casualties and (city index, population) for cities that changed
+    Cities { casualties: u64, changed: Vec<(u32, u64)> },
+    Bye,
+}
+
+pub(crate) fn weapon_code(weapon: WeaponType) -> u8 {
+    match weapon {
+        WeaponType::Nuke => 0,
+        WeaponType::Bio => 1,
+        WeaponType::Emp => 2,
+        WeaponType::Chem => 3,
+        WeaponType::Airburst => 4,
+        WeaponType::Tsunami => 5,
+        WeaponType::Asteroid => 6,
+    }
+}
+
+pub(crate) fn weapon_from(code: u8) -> Result<WeaponType> {
+    Ok(match code {
+        0 => WeaponType::Nuke,
+        1 => WeaponType::Bio,
+        2 => WeaponType::Emp,
+        3 => WeaponType::Chem,
+        4 => WeaponType::Airburst,
+        5 => WeaponType::Tsunami,
+        6 => WeaponType::Asteroid,
+        _ => bail!("unknown weapon code {code}"),
+    })
+}
+
+/// Cursor over a payload
+pub(crate) struct Fields<'a> {
+    pub(crate) buf: &'a [u8],
+}
+
+impl Fields<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        if self.buf.len() < N {
+            bail!("truncated message");
+        }
+        let (head, rest) = self.buf.split_at(N);
+        self.buf = rest;
+        Ok(head.try_into().unwrap())
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn f64(&mut self) -> Result<f64> {
+        Ok(f64::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn point(&mut self) -> Result<(f64, f64)> {
+        Ok((self.f64()?, self.f64()?))
+    }
+
+    /// A yield in kilotons, 0 for unknown
+    fn yield_kt(&mut self) -> Result<Option<f64>> {
+        Ok(Some(self.f64()?).filter(|&kt| kt > 0.0))
+    }
+}
+
+impl Message {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let f = |out: &mut Vec<u8>, v: f64| out.extend_from_slice(&v.to_le_bytes());
+        match self {
+            Message::Welcome { faction } => {
+                out.push(1);
+                out.push(faction.index() as u8);
+            }
+            Message::Launch { id, from, to, weapon, radius_km, yield_kt, warheads } => {
+                out.push(2);
+                out.extend_from_slice(&id.to_le_bytes());
+                for v in [from.0, from.1, to.0, to.1] {
+                    f(&mut out, v);
+                }
+                out.push(weapon_code(*weapon));
+                f(&mut out, *radius_km);
+                f(&mut out, yield_kt.unwrap_or(0.0));
+                out.push(warheads.len() as u8);
+                for &(lon, lat) in warheads {
+                    f(&mut out, lon);
+                    f(&mut out, lat);
+                }
+            }
+            Message::Detonation { lon, lat, weapon, radius_km, yield_kt } => {
+                out.push(3);
+                f(&mut out, *lon);
+                f(&mut out, *lat);
+                out.push(weapon_code(*weapon));
+                f(&mut out, *radius_km);
+                f(&mut out, yield_kt.unwrap_or(0.0));
+            }
+            Message::Intercepted { id } => {
+                out.push(4);
+                out.extend_from_slice(&id.to_le_bytes());
+            }
+            Message::Cities { casualties, changed } => {
+                out.push(5);
+                out.extend_from_slice(&casualties.to_le_bytes());
+                out.extend_from_slice(&(changed.len() as u32).to_le_bytes());
+                for (idx, population) in changed {
+                    out.extend_from_slice(&idx.to_le_bytes());
+                    out.extend_from_slice(&population.to_le_bytes());
+                }
+            }
+            Message::Bye => out.push(6),
+        }
+        out
+    }
+
+    pub fn decode(payload: &[u8]) -> Result<Self> {
+        let mut r = Fields { buf: payload };
+        let msg = match r.u8()? {
+            1 => {
+                let idx = r.u8()? as usize;
+                let faction = *Faction::ALL.get(idx).ok_or_else(|| anyhow!("unknown faction {idx}"))?;
+                Message::Welcome { faction }
+            }
+            2 => Message::Launch {
+                id: r.u32()?,
+                from: r.point()?,
+                to: r.point()?,
+                weapon: weapon_from(r.u8()?)?,
+                radius_km: r.f64()?,
+                yield_kt: r.yield_kt()?,
+                warheads: (0..r.u8()?).map(|_| r.point()).collect::<Result<_>>()?,
+            },
+            3 => Message::Detonation {
+                lon: r.f64()?,
+                lat: r.f64()?,
+                weapon: weapon_from(r.u8()?)?,
+                radius_km: r.f64()?,
+                yield_kt: r.yield_kt()?,
+            },
+            4 => Message::Intercepted { id: r.u32()? },
+            5 => {
+                let casualties = r.u64()?;
+                let n = r.u32()? as usize;
+                if n > r.buf.len() / 12 {
+                    bail!("city count {n} exceeds payload");
+                }
+                let changed = (0..n).map(|_| Ok((r.u32()?, r.u64()?))).collect::<Result<_>>()?;
+                Message::Cities { casualties, changed }
+            }
+            6 => Message::Bye,
+            tag => bail!("unknown message tag {tag}"),
+        };
+        if !r.buf.is_empty() {
+            bail!("{} trailing bytes", r.buf.len());
+        }
+        Ok(msg)
+    }
+}
+
+/// Write one length-prefixed message
+pub fn write_message(w: &mut impl Write, msg: &Message) -> Result<()> {
+    write_frame(w, &msg.encode())
+}
+
+/// Read one length-prefixed message (blocking)
+pub fn read_message(r: &mut impl Read) -> Result<Message> {
+    Message::decode(&read_frame(r)?)
+}
+
+/// Write a payload with its length in front
+pub(crate) fn write_frame(w: &mut impl Write, payload: &[u8]) -> Result<()> {
+    w.write_all(&(payload.len() as u32).to_be_bytes())?;
+    w.write_all(payload)?;
+    w.flush()?;
+    Ok(())
+}
+
+/// Read one length-prefixed payload (blocking)
+pub(crate) fn read_frame(r: &mut impl Read) -> Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    r.read_exact(&mut len)?;
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME {
+        bail!("message too large ({len} bytes)");
+    }
+    let mut payload = vec![0u8; len];
+    r.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+/// (index, population) for cities that changed since `synced`, which is
+/// brought up to date
+pub(crate) fn changed_populations(synced: &mut Vec<u64>, populations: impl Iterator<Item = u64>) -> Vec<(u32, u64)> {
+    let mut changed = Vec::new();
+    for (i, population) in populations.enumerate() {
+        if i >= synced.len() {
+            synced.push(u64::MAX);
+        }
+        if synced[i] != population {
+            synced[i] = population;
+            changed.push((i as u32, population));
+        }
+    }
+    changed
+}
+
+/// A live connection to the other player. Messages are read and written on
+/// background threads, so a slow peer never holds up a frame.
+pub struct NetSession {
+    pub is_host: bool,
+    /// The faction this side plays
+    pub faction: Faction,
+    pub peer_faction: Faction,
+    outgoing: Sender<Message>,
+    writer: Option<JoinHandle<()>>,
+    incoming: Receiver<Result<Message>>,
+    /// Populations as of the last sync (host only)
+    synced: Vec<u64>,
+    next_id: u32,
+}
+
+impl NetSession {
+    /// Listen on `bind`:`port` and wait for one player to connect (blocking)
+    pub fn host(bind: IpAddr, port: u16) -> Result<Self> {
+        let listener = TcpListener::bind((bind, port)).with_context(|| format!("listening on {bind}:{port}"))?;
+        Self::accept(&listener)
+    }
+
+    /// Accept one player from `listener`. The host plays the West and hands
+    /// the client the East.
+    pub fn accept(listener: &TcpListener) -> Result<Self> {
+        let (mut stream, _) = listener.accept().context("accepting player")?;
+        write_message(&mut stream, &Message::Welcome { faction: Faction::East })?;
+        Self::start(stream, true, Faction::West, Faction::East)
+    }
+
+    /// Connect to a host and receive our faction (blocking)
+    pub fn connect(addr: &str) -> Result<Self> {
+        let mut stream = TcpStream::connect(addr).with_context(|| format!("connecting to {addr}"))?;
+        match read_message(&mut stream).context("waiting for host")? {
+            Message::Welcome { faction } => {
+                let peer = if faction == Faction::West { Faction::East } else { Faction::West };
+                Self::start(stream, false, faction, peer)
+            }
+            other => bail!("expected welcome from host, got {other:?}"),
+        }
+    }
+
+    fn start(mut stream: TcpStream, is_host: bool, faction: Faction, peer_faction: Faction) -> Result<Self> {
+        stream.set_nodelay(true)?;
+        stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+        let mut reader = stream.try_clone()?;
+        let (tx, rx) = mpsc::channel();
+        let errors = tx.clone();
+        thread::spawn(move || loop {
+            let msg = read_message(&mut reader);
+            let done = matches!(msg, Err(_) | Ok(Message::Bye));
+            if tx.send(msg).is_err() || done {
+                break;
+            }
+        });
+        // A failed write reaches the game the way a failed read does
+        let (outgoing, queued) = mpsc::channel::<Message>();
+        let writer = thread::spawn(move || {
+            for msg in queued {
+                if let Err(e) = write_message(&mut stream, &msg) {
+                    let _ = errors.send(Err(e));
+                    break;
+                }
+                if msg == Message::Bye {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            is_host,
+            faction,
+            peer_faction,
+            outgoing,
+            writer: Some(writer),
+            incoming: rx,
+            synced: Vec::new(),
+            next_id: 1,
+        })
+    }
+
+    /// Queue `msg` for the writer thread. If it can't be written, `poll`
+    /// reports the connection lost.
+    pub fn send(&self, msg: Message) {
+        let _ = self.outgoing.send(msg);
+    }
+
+    /// Next received message, if any. A dropped connection shows up as an error.
+    pub fn poll(&mut self) -> Option<Result<Message>> {
+        self.incoming.try_recv().ok()
+    }
+
+    /// Id for our next missile
+    pub fn next_launch_id(&mut self) -> u32 {
+        self.next_id = self.next_id.wrapping_add(1);
+        self.next_id
+    }
+
+    /// Forget what the client has; the next `city_updates` sends everything
+    pub fn resync(&mut self) {
+        self.synced.clear();
+    }
+
+    /// (index, population) for cities that changed since the last call
+    pub fn city_updates(&mut self, populations: impl Iterator<Item = u64>) -> Vec<(u32, u64)> {
+        changed_populations(&mut self.synced, populations)
+    }
+}
+
+impl Drop for NetSession {
+    /// Say goodbye, waiting (at most the write timeout) for it to go out
+    fn drop(&mut self) {
+        self.send(Message::Bye);
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_round_trip() {
+        let messages = [
+            Message::Welcome { faction: Faction::East },
+            Message::Launch { id: 7, from: (-101.35, 48.42), to: (37.6, 55.75), weapon: WeaponType::Chem, radius_km: 80.5, yield_kt: None, warheads: Vec::new() },
+            Message::Launch { id: 8, from: (0.0, 0.0), to: (1.0, 1.0), weapon: WeaponType::Nuke, radius_km: 40.0, yield_kt: Some(100.0), warheads: vec![(1.2, 0.9), (0.8, 1.1)] },
+            Message::Detonation { lon: 2.35, lat: 48.85, weapon: WeaponType::Emp, radius_km: 150.0, yield_kt: Some(1_000.0) },
+            Message::Intercepted { id: 42 },
+            Message::Cities { casualties: 1_234_567, changed: vec![(0, 10), (7000, 0)] },
+            Message::Bye,
+        ];
+        let mut wire = Vec::new();
+        for msg in &messages {
+            write_message(&mut wire, msg).unwrap();
+        }
+        let mut reader = wire.as_slice();
+        for msg in &messages {
+            assert_eq!(&read_message(&mut reader).unwrap(), msg);
+        }
+        assert!(read_message(&mut reader).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_payloads() {
+        assert!(Message::decode(&[]).is_err());
+        assert!(Message::decode(&[99]).is_err());
+        assert!(Message::decode(&[4, 1, 0]).is_err());
+        assert!(Message::decode(&[6, 0]).is_err());
+        // Claims a billion cities
+        let mut bogus = vec![5];
+        bogus.extend_from_slice(&0u64.to_le_bytes());
+        bogus.extend_from_slice(&1_000_000_000u32.to_le_bytes());
+        assert!(Message::decode(&bogus).is_err());
+    }
+
+    #[test]
+    fn syncs_only_changed_cities() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let client = thread::spawn(move || NetSession::connect(&addr).unwrap());
+        let mut host = NetSession::accept(&listener).unwrap();
+        let mut client = client.join().unwrap();
+        assert_eq!((host.faction, client.faction), (Faction::West, Faction::East));
+
+        assert_eq!(host.city_updates([5, 6].into_iter()).len(), 2);
+        assert_eq!(host.city_updates([5, 4, 9].into_iter()), vec![(1, 4), (2, 9)]);
+
+        host.send(Message::Intercepted { id: 3 });
+        let msg = loop {
+            if let Some(msg) = client.poll() {
+                break msg.unwrap();
+            }
+            thread::yield_now();
+        };
+        assert_eq!(msg, Message::Intercepted { id: 3 });
+
+        // The host leaving reaches the client as a goodbye
+        drop(host);
+        let msg = loop {
+            if let Some(msg) = client.poll() {
+                break msg.unwrap();
+            }
+            thread::yield_now();
+        };
+        assert_eq!(msg, Message::Bye);
+    }
+}
@@ -0,0 +1,321 @@
+//! Overlays: layers drawn over the map by code outside the renderer.
+//!
+//! A [`LayerProvider`] is asked each frame for what to draw in the current
+//! view: lines, points, labels and pulsing rings in longitude and latitude,
+//! each with its own color. The map projects them and draws them over the fires and city
+//! labels, beneath the missiles and the reticle. Register one with
+//! `app.overlays.register`; `:overlay` lists them and shows or hides them
+//! by name.
+//!
+//! [`Graticule`], the latitude/longitude grid, is the built-in example.
+
+use crate::command::Switch;
+use crate::geo::wrap_lon;
+use crate::map::MapProjection;
+use anyhow::Result;
+use ratatui::style::Color;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// Draws an overlay for the view it's given
+pub trait LayerProvider {
+    /// Short name, as `:overlay` knows it
+    fn name(&self) -> &str;
+
+    /// What to draw in `view`. Called every frame the overlay is shown, so
+    /// cull to `view.visible_bounds()` if there's a lot of it.
+    fn shapes(&mut self, view: &dyn MapProjection) -> Shapes;
+
+    /// Something to tell the user, such as a feed that failed to load.
+    /// Asked every frame, whether or not the overlay is shown.
+    fn message(&mut self) -> Option<String> {
+        None
+    }
+}
+
+/// What an overlay draws, in degrees
+#[derive(Clone, Debug, Default)]
+pub struct Shapes {
+    pub lines: Vec<OverlayLine>,
+    pub points: Vec<OverlayPoint>,
+    pub labels: Vec<OverlayLabel>,
+    pub pulses: Vec<OverlayPulse>,
+}
+
+/// A line through `(lon, lat)` points, joined as on the map's other lines:
+/// great circles on the globe, straight on flat maps
+#[derive(Clone, Debug)]
+pub struct OverlayLine {
+    pub points: Vec<(f64, f64)>,
+    pub color: Color,
+}
+
+/// A single character at a place
+#[derive(Clone, Debug)]
+pub struct OverlayPoint {
+    pub lon: f64,
+    pub lat: f64,
+    pub glyph: char,
+    pub color: Color,
+}
+
+/// Text starting at a place
+#[derive(Clone, Debug)]
+pub struct OverlayLabel {
+    pub lon: f64,
+    pub lat: f64,
+    pub text: String,
+    pub color: Color,
+}
+
+/// A ring that keeps spreading out from a place and fading, drawn like
+/// the shock rings of an explosion
+#[derive(Clone, Debug)]
+pub struct OverlayPulse {
+    pub lon: f64,
+    pub lat: f64,
+    /// How far the ring spreads before it starts again
+    pub radius_km: f64,
+    pub color: Color,
+}
+
+impl Shapes {
+    pub fn line(&mut self, points: Vec<(f64, f64)>, color: Color) {
+        self.lines.push(OverlayLine { points, color });
+    }
+
+    pub fn point(&mut self, lon: f64, lat: f64, glyph: char, color: Color) {
+        self.points.push(OverlayPoint { lon, lat, glyph, color });
+    }
+
+    pub fn label(&mut self, lon: f64, lat: f64, text: impl Into<String>, color: Color) {
+        self.labels.push(OverlayLabel { lon, lat, text: text.into(), color });
+    }
+
+    pub fn pulse(&mut self, lon: f64, lat: f64, radius_km: f64, color: Color) {
+        self.pulses.push(OverlayPulse { lon, lat, radius_km, color });
+    }
+}
+
+struct Overlay {
+    provider: Box<dyn LayerProvider>,
+    shown: bool,
+}
+
+/// The registered overlays, drawn in the order they were registered
+pub struct Overlays {
+    overlays: Vec<Overlay>,
+}
+
+impl Default for Overlays {
+    /// The built-in overlays, all hidden
+    fn default() -> Self {
+        let mut overlays = Self { overlays: Vec::new() };
+        overlays.register(Box::new(Graticule), false);
+        overlays
+    }
+}
+
+impl Overlays {
+    /// Add an overlay, drawn above those already registered. One with the
+    /// same name as an earlier one replaces it.
+    pub fn register(&mut self, provider: Box<dyn LayerProvider>, shown: bool) {
+        self.overlays.retain(|o| o.provider.name() != provider.name());
+        self.overlays.push(Overlay { provider, shown });
+    }
+
+    /// Each overlay's name, and whether it's showing
+    pub fn list(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.overlays.iter().map(|o| (o.provider.name(), o.shown))
+    }
+
+    /// Show or hide the overlay named `name`, or the one name it starts.
+    /// Returns its full name and whether it's now showing.
+    pub fn set(&mut self, name: &str, switch: Switch) -> Result<(String, bool), String> {
+        let index = find_by_name(self.overlays.iter().map(|o| o.provider.name()), name, "overlay")?;
+        let overlay = &mut self.overlays[index];
+        overlay.shown = switch.apply(overlay.shown);
+        Ok((overlay.provider.name().to_string(), overlay.shown))
+    }
+
+    /// What the overlays have to say since they were last asked
+    pub fn messages(&mut self) -> Vec<String> {
+        self.overlays.iter_mut().filter_map(|o| o.provider.message()).collect()
+    }
+
+    /// What the shown overlays draw in `view`, bottom first
+    pub fn shapes(&mut self, view: &dyn MapProjection) -> Vec<Shapes> {
+        self.overlays.iter_mut().filter(|o| o.shown).map(|o| o.provider.shapes(view)).collect()
+    }
+}
+
+/// Which of `names` is `query`, or the only one it starts, ignoring case.
+/// `what` names the kind of thing in errors.
+pub(crate) fn find_by_name<'a>(names: impl Iterator<Item = &'a str>, query: &str, what: &str) -> Result<usize, String> {
+    let query = query.to_ascii_lowercase();
+    let names: Vec<String> = names.map(str::to_ascii_lowercase).collect();
+    if let Some(exact) = names.iter().position(|n| *n == query) {
+        return Ok(exact);
+    }
+    let matches: Vec<usize> = (0..names.len()).filter(|&i| names[i].starts_with(&query)).collect();
+    match matches[..] {
+        [] => Err(format!("unknown {what}: {query}")),
+        [only] => Ok(only),
+        _ => Err(format!("ambiguous {what}: {query}")),
+    }
+}
+
+/// Fetches an overlay's data on a worker thread every so often, so a slow
+/// feed never holds up a frame
+pub struct Poller<T> {
+    updates: Receiver<Result<T>>,
+}
+
+impl<T: Send + 'static> Poller<T> {
+    /// Call `fetch` now and then every `interval`, until the poller is
+    /// dropped
+    pub fn spawn(interval: Duration, mut fetch: impl FnMut() -> Result<T> + Send + 'static) -> Self {
+        let (tx, updates) = mpsc::channel();
+        thread::spawn(move || {
+            while tx.send(fetch()).is_ok() {
+                thread::sleep(interval);
+            }
+        });
+        Self { updates }
+    }
+
+    /// The fetches finished since the last call, oldest first
+    pub fn updates(&self) -> impl Iterator<Item = Result<T>> + '_ {
+        self.updates.try_iter()
+    }
+}
+
+/// Grid spacings to pick from, coarsest first, in degrees
+const GRID_STEPS: [f64; 8] = [30.0, 15.0, 10.0, 5.0, 2.0, 1.0, 0.5, 0.25];
+
+/// Closest the grid lines come on screen, in braille pixels
+const MIN_GRID_GAP_PX: f64 = 48.0;
+
+/// Meridians and parallels, closer together as the map zooms in, each
+/// labelled with its degree where it crosses the middle of the view
+pub struct Graticule;
+
+impl Graticule {
+    /// The finest spacing that keeps lines `MIN_GRID_GAP_PX` apart
+    pub fn step(view: &dyn MapProjection) -> f64 {
+        GRID_STEPS.into_iter().take_while(|&step| view.deg_to_pixels(step) >= MIN_GRID_GAP_PX).last().unwrap_or(GRID_STEPS[0])
+    }
+}
+
+impl LayerProvider for Graticule {
+    fn name(&self) -> &str {
+        "graticule"
+    }
+
+    fn shapes(&mut self, view: &dyn MapProjection) -> Shapes {
+        const LINE: Color = Color::Rgb(60, 70, 90);
+        const TEXT: Color = Color::Rgb(110, 125, 150);
+        let step = Self::step(view);
+        let (min_lon, min_lat, max_lon, max_lat) = view.visible_bounds();
+        let (min_lat, max_lat) = (min_lat.max(-90.0), max_lat.min(90.0));
+        let whole_world = max_lon - min_lon >= 360.0;
+        let in_view = |lon: f64| whole_world || [-360.0, 0.0, 360.0].iter().any(|o| (min_lon..=max_lon).contains(&(lon + o)));
+        // Samples close enough to bend with the globe and the flat maps' curves
+        let sample = step.min(2.0);
+        let (center_lon, center_lat) = view.center();
+        let mut shapes = Shapes::default();
+
+        let first_lat = (min_lat / step).ceil() as i64;
+        let last_lat = (max_lat / step).floor() as i64;
+        let lons: Vec<f64> = {
+            let (from, to) = if whole_world || min_lon < -180.0 || max_lon > 180.0 { (-180.0, 180.0) } else { (min_lon, max_lon) };
+            let n = ((to - from) / sample).ceil().max(1.0) as usize;
+            (0..=n).map(|i| from + (to - from) * i as f64 / n as f64).collect()
+        };
+        for k in first_lat..=last_lat {
+            let lat = k as f64 * step;
+            if lat.abs() >= 90.0 {
+                continue;
+            }
+            shapes.line(lons.iter().map(|&lon| (lon, lat)).collect(), LINE);
+            shapes.label(center_lon, lat, degrees(lat, 'N', 'S'), TEXT);
+        }
+
+        let n = ((max_lat - min_lat) / sample).ceil().max(1.0) as usize;
+        let lats: Vec<f64> = (0..=n).map(|i| min_lat + (max_lat - min_lat) * i as f64 / n as f64).collect();
+        let per_turn = (360.0 / step).round() as i64;
+        for k in -per_turn / 2..per_turn / 2 {
+            let lon = k as f64 * step;
+            if !in_view(lon) {
+                continue;
+            }
+            shapes.line(lats.iter().map(|&lat| (lon, lat)).collect(), LINE);
+            shapes.label(lon, center_lat, degrees(wrap_lon(lon), 'E', 'W'), TEXT);
+        }
+        shapes
+    }
+}
+
+/// `30°N`, `0°`, `2.5°W`
+fn degrees(value: f64, positive: char, negative: char) -> String {
+    let hemisphere = match value {
+        v if v > 0.0 => positive.to_string(),
+        v if v < 0.0 => negative.to_string(),
+        _ => String::new(),
+    };
+    format!("{}°{hemisphere}", (value.abs() * 100.0).round() / 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::{Projection, Viewport};
+
+    struct Servers;
+
+    impl LayerProvider for Servers {
+        fn name(&self) -> &str {
+            "servers"
+        }
+
+        fn shapes(&mut self, _view: &dyn MapProjection) -> Shapes {
+            let mut shapes = Shapes::default();
+            shapes.point(-77.0, 38.9, '■', Color::Green);
+            shapes.label(-76.0, 38.9, "us-east", Color::Green);
+            shapes
+        }
+    }
+
+    #[test]
+    fn overlays_draw_only_while_shown() {
+        let projection = Projection::new(Viewport::new(0.0, 0.0, 1.0, 200, 100));
+        let mut overlays = Overlays::default();
+        overlays.register(Box::new(Servers), true);
+        assert_eq!(overlays.list().collect::<Vec<_>>(), [("graticule", false), ("servers", true)]);
+        let shapes = overlays.shapes(projection.view());
+        assert_eq!(shapes.len(), 1);
+        assert_eq!(shapes[0].labels[0].text, "us-east");
+
+        assert_eq!(overlays.set("grat", Switch::Toggle), Ok(("graticule".to_string(), true)));
+        assert_eq!(overlays.set("servers", Switch::Off), Ok(("servers".to_string(), false)));
+        assert!(overlays.set("x", Switch::On).is_err());
+        let shapes = overlays.shapes(projection.view());
+        assert_eq!(shapes.len(), 1);
+        assert!(shapes[0].lines.len() > 6, "meridians and parallels: {}", shapes[0].lines.len());
+    }
+
+    #[test]
+    fn the_graticule_grows_finer_as_the_map_zooms_in() {
+        let mut projection = Projection::new(Viewport::new(0.0, 0.0, 1.0, 200, 100));
+        let world = Graticule::step(projection.view());
+        let shapes = Graticule.shapes(projection.view());
+        assert!(shapes.labels.iter().any(|l| l.text == "150°W"));
+        for _ in 0..8 {
+            projection.zoom_in();
+        }
+        assert!(Graticule::step(projection.view()) < world);
+        assert_eq!(degrees(-2.5, 'E', 'W'), "2.5°W");
+        assert_eq!(degrees(0.0, 'N', 'S'), "0°");
+    }
+}
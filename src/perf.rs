@@ -0,0 +1,245 @@
+//! Frame timing: the frame-rate cap, the numbers behind the performance
+//! HUD, and automatic degradation.
+//!
+//! The main loop times the render and simulation phases of every frame and
+//! hands them here, smoothed into running averages. When rendering plus
+//! simulation keeps running over the frame budget, effect detail drops a
+//! step — fewer explosions, thinner gas clouds, grid-binned fires, then no
+//! weather — and comes back once there's plenty of headroom again.
+
+use std::time::{Duration, Instant};
+
+/// Frame-rate cap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FpsCap {
+    Thirty,
+    Sixty,
+    Uncapped,
+}
+
+impl FpsCap {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "30" => Some(Self::Thirty),
+            "60" => Some(Self::Sixty),
+            "uncapped" | "0" => Some(Self::Uncapped),
+            _ => None,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Thirty => Self::Sixty,
+            Self::Sixty => Self::Uncapped,
+            Self::Uncapped => Self::Thirty,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Thirty => "30 fps",
+            Self::Sixty => "60 fps",
+            Self::Uncapped => "uncapped",
+        }
+    }
+
+    /// Shortest time between frames (zero when uncapped)
+    pub fn frame_time(self) -> Duration {
+        match self {
+            Self::Thirty => Duration::from_micros(33_333),
+            Self::Sixty => Duration::from_micros(16_667),
+            Self::Uncapped => Duration::ZERO,
+        }
+    }
+}
+
+/// A timed part of the frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Render,
+    Sim,
+}
+
+/// Lowest effect detail: each step below full sheds another effect
+pub const MIN_DETAIL: u8 = 2;
+
+/// Weight of the newest sample in the running averages
+const SMOOTHING: f64 = 0.1;
+/// Frames over budget before detail drops a step (about a second at 60fps)
+const DEGRADE_AFTER: u32 = 60;
+/// Frames under half the budget before detail comes back a step
+const RECOVER_AFTER: u32 = 180;
+
+/// Frame timings and the effect detail they've settled on
+#[derive(Clone, Debug)]
+pub struct FrameStats {
+    pub cap: FpsCap,
+    /// Show the performance HUD
+    pub show_hud: bool,
+    /// Drop effect detail when frames run over budget
+    pub auto_degrade: bool,
+    /// Effect detail steps shed, 0 (full) to `MIN_DETAIL`
+    pub detail: u8,
+    /// Visible map features in the last render, for the HUD
+    pub features: usize,
+    frame_ms: f64,
+    render_ms: f64,
+    sim_ms: f64,
+    over: u32,
+    under: u32,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self {
+            cap: FpsCap::Sixty,
+            show_hud: false,
+            auto_degrade: true,
+            detail: 0,
+            features: 0,
+            frame_ms: 0.0,
+            render_ms: 0.0,
+            sim_ms: 0.0,
+            over: 0,
+            under: 0,
+        }
+    }
+}
+
+fn smooth(avg: &mut f64, ms: f64) {
+    *avg = if *avg == 0.0 { ms } else { *avg + (ms - *avg) * SMOOTHING };
+}
+
+impl FrameStats {
+    /// Time spent in one phase of this frame
+    pub fn record(&mut self, phase: Phase, elapsed: Duration) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        match phase {
+            Phase::Render => smooth(&mut self.render_ms, ms),
+            Phase::Sim => smooth(&mut self.sim_ms, ms),
+        }
+    }
+
+    /// How long to keep waiting for input before the frame that started at
+    /// `start` is due
+    pub fn wait(&self, start: Instant) -> Duration {
+        self.cap.frame_time().saturating_sub(start.elapsed())
+    }
+
+    /// Close out a frame that took `elapsed` from start to start, and step
+    /// effect detail down or up if the work has stayed over or well under
+    /// budget for a while
+    pub fn end_frame(&mut self, elapsed: Duration) {
+        smooth(&mut self.frame_ms, elapsed.as_secs_f64() * 1000.0);
+        if !self.auto_degrade {
+            self.detail = 0;
+            return;
+        }
+        let work = self.render_ms + self.sim_ms;
+        let budget = self.budget_ms();
+        if work > budget {
+            self.over += 1;
+            self.under = 0;
+        } else if work < budget / 2.0 {
+            self.under += 1;
+            self.over = 0;
+        } else {
+            self.over = 0;
+            self.under = 0;
+        }
+        if self.over >= DEGRADE_AFTER && self.detail < MIN_DETAIL {
+            self.detail += 1;
+            self.over = 0;
+        }
+        if self.under >= RECOVER_AFTER && self.detail > 0 {
+            self.detail -= 1;
+            self.under = 0;
+        }
+    }
+
+    /// Render and simulation time a frame can take, in ms. Uncapped frames
+    /// are held to 60fps.
+    pub fn budget_ms(&self) -> f64 {
+        match self.cap {
+            FpsCap::Uncapped => 1000.0 / 60.0,
+            cap => cap.frame_time().as_secs_f64() * 1000.0,
+        }
+    }
+
+    pub fn fps(&self) -> f64 {
+        if self.frame_ms > 0.0 { 1000.0 / self.frame_ms } else { 0.0 }
+    }
+
+    pub fn frame_ms(&self) -> f64 {
+        self.frame_ms
+    }
+
+    pub fn render_ms(&self) -> f64 {
+        self.render_ms
+    }
+
+    pub fn sim_ms(&self) -> f64 {
+        self.sim_ms
+    }
+
+    /// Effect detail for the HUD
+    pub fn detail_label(&self) -> &'static str {
+        match self.detail {
+            0 => "full",
+            1 => "reduced",
+            _ => "minimal",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detail_drops_over_budget_and_recovers_with_headroom() {
+        let mut stats = FrameStats::default();
+        let frame = |stats: &mut FrameStats, render_ms: u64| {
+            stats.record(Phase::Render, Duration::from_millis(render_ms));
+            stats.record(Phase::Sim, Duration::from_millis(1));
+            stats.end_frame(Duration::from_millis(render_ms + 1));
+        };
+
+        // A one-off slow frame doesn't count; a second of them does
+        frame(&mut stats, 40);
+        for _ in 0..10 {
+            frame(&mut stats, 5);
+        }
+        assert_eq!(stats.detail, 0);
+        for _ in 0..(DEGRADE_AFTER * 3) {
+            frame(&mut stats, 40);
+        }
+        assert_eq!(stats.detail, MIN_DETAIL);
+
+        // Near the budget it holds; well under, it climbs back
+        for _ in 0..RECOVER_AFTER * 2 {
+            frame(&mut stats, 12);
+        }
+        assert_eq!(stats.detail, MIN_DETAIL);
+        for _ in 0..RECOVER_AFTER * 3 {
+            frame(&mut stats, 2);
+        }
+        assert_eq!(stats.detail, 0);
+
+        // A 30fps cap doubles the budget
+        stats.cap = FpsCap::Thirty;
+        for _ in 0..DEGRADE_AFTER * 2 {
+            frame(&mut stats, 25);
+        }
+        assert_eq!(stats.detail, 0);
+    }
+
+    #[test]
+    fn caps_cycle_and_parse() {
+        assert_eq!(FpsCap::parse("30"), Some(FpsCap::Thirty));
+        assert_eq!(FpsCap::parse("Uncapped"), Some(FpsCap::Uncapped));
+        assert_eq!(FpsCap::parse("45"), None);
+        assert_eq!(FpsCap::Sixty.next().next().next(), FpsCap::Sixty);
+        assert_eq!(FpsCap::Uncapped.frame_time(), Duration::ZERO);
+    }
+}
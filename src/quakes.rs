@@ -0,0 +1,212 @@
+//! The earthquakes overlay: recent quakes from a USGS GeoJSON feed, each a
+//! ring pulsing out from the epicenter, wider for a bigger quake and
+//! fading as it gets older.
+//!
+//! The feed is fetched on a worker thread once a minute, which is as often
+//! as USGS updates it, so the map picks up new quakes without waiting on
+//! the network. Fetching needs the `online` feature; a local file in the
+//! feed's format is read the same way without it.
+
+use crate::map::MapProjection;
+use crate::overlay::{LayerProvider, Poller, Shapes};
+use anyhow::{Context, Result};
+use geojson::{GeoJson, Value};
+use ratatui::style::Color;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// USGS summary feeds, by name: `2.5_day`, `all_hour`, `4.5_week`,
+/// `significant_month`, ...
+const USGS_URL: &str = "https://earthquake.usgs.gov/earthquakes/feed/v1.0/summary/";
+
+/// How often the feed is fetched again
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How far a magnitude 2.5 quake's ring spreads, in km. Each whole
+/// magnitude spreads it `RING_GROWTH` times further.
+const RING_KM: f64 = 25.0;
+const RING_GROWTH: f64 = 1.8;
+
+/// The oldest quake in the feed is drawn this bright, the newest at full
+const OLDEST_BRIGHTNESS: f64 = 0.2;
+
+/// Quakes this big are labelled with their magnitude, and from
+/// `PLACE_MAGNITUDE` with where they were too
+const LABEL_MAGNITUDE: f64 = 5.0;
+const PLACE_MAGNITUDE: f64 = 6.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quake {
+    pub lon: f64,
+    pub lat: f64,
+    pub magnitude: f64,
+    /// Unix seconds
+    pub time: f64,
+    /// "12 km SSW of Somewhere"
+    pub place: String,
+}
+
+/// Where the feed comes from
+#[derive(Clone, Debug, PartialEq)]
+enum Source {
+    Url(String),
+    File(PathBuf),
+}
+
+impl Source {
+    /// A USGS feed name, or a path to a file in the feed's format
+    fn parse(feed: &str) -> Self {
+        let path = PathBuf::from(feed);
+        if path.extension().is_some_and(|ext| ext == "json" || ext == "geojson") {
+            Source::File(path)
+        } else {
+            Source::Url(format!("{USGS_URL}{feed}.geojson"))
+        }
+    }
+
+    fn read(&self) -> Result<Vec<Quake>> {
+        let text = match self {
+            Source::Url(url) => {
+                String::from_utf8(crate::data::fetch::get(url)?).context("the feed isn't text")?
+            }
+            Source::File(path) => std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?,
+        };
+        parse_feed(text)
+    }
+}
+
+/// The quakes in a USGS GeoJSON feed. Those without a magnitude yet are
+/// left out.
+pub fn parse_feed(text: String) -> Result<Vec<Quake>> {
+    let GeoJson::FeatureCollection(fc) = crate::data::parse_geojson(text)? else {
+        anyhow::bail!("expected a FeatureCollection");
+    };
+    Ok(fc
+        .features
+        .iter()
+        .filter_map(|feature| {
+            let props = feature.properties.as_ref()?;
+            let Value::Point(coords) = &feature.geometry.as_ref()?.value else { return None };
+            Some(Quake {
+                lon: *coords.first()?,
+                lat: *coords.get(1)?,
+                magnitude: props.get("mag")?.as_f64()?,
+                time: props.get("time")?.as_f64()? / 1000.0,
+                place: props.get("place").and_then(|p| p.as_str()).unwrap_or("").to_string(),
+            })
+        })
+        .collect())
+}
+
+pub struct Quakes {
+    quakes: Vec<Quake>,
+    /// Fetches the feed, and is dropped with the overlay (`None` in tests)
+    poller: Option<Poller<Vec<Quake>>>,
+    message: Option<String>,
+}
+
+impl Quakes {
+    /// Start polling `feed`: a USGS feed name such as `2.5_day`, or a
+    /// `.json`/`.geojson` file
+    pub fn poll(feed: &str) -> Self {
+        let source = Source::parse(feed);
+        let poller = Poller::spawn(POLL_INTERVAL, move || source.read());
+        Self { quakes: Vec::new(), poller: Some(poller), message: None }
+    }
+
+    /// Take in whatever the worker has fetched since the last frame
+    fn update(&mut self) {
+        for update in self.poller.iter().flat_map(Poller::updates) {
+            match update {
+                Ok(quakes) => self.quakes = quakes,
+                Err(e) => self.message = Some(format!("earthquake feed: {e:#}")),
+            }
+        }
+    }
+
+    /// What to draw at `now`, in unix seconds
+    pub fn shapes_at(&self, now: f64) -> Shapes {
+        let mut shapes = Shapes::default();
+        // Fade over the span the feed covers, however long that is
+        let oldest = self.quakes.iter().map(|q| q.time).fold(now, f64::min);
+        let span = (now - oldest).max(3600.0);
+        for quake in &self.quakes {
+            let age = ((now - quake.time) / span).clamp(0.0, 1.0);
+            let brightness = 1.0 - (1.0 - OLDEST_BRIGHTNESS) * age;
+            let color = magnitude_color(quake.magnitude, brightness);
+            let radius_km = RING_KM * RING_GROWTH.powf(quake.magnitude - 2.5);
+            shapes.pulse(quake.lon, quake.lat, radius_km, color);
+            shapes.point(quake.lon, quake.lat, if quake.magnitude >= LABEL_MAGNITUDE { '◉' } else { '•' }, color);
+            if quake.magnitude >= PLACE_MAGNITUDE {
+                shapes.label(quake.lon, quake.lat, format!("  M{:.1} {}", quake.magnitude, quake.place), color);
+            } else if quake.magnitude >= LABEL_MAGNITUDE {
+                shapes.label(quake.lon, quake.lat, format!("  M{:.1}", quake.magnitude), color);
+            }
+        }
+        shapes
+    }
+}
+
+/// Yellow for small quakes through orange to red for big ones, dimmed by
+/// `brightness`
+fn magnitude_color(magnitude: f64, brightness: f64) -> Color {
+    let t = ((magnitude - 2.5) / 4.5).clamp(0.0, 1.0);
+    let scale = |v: f64| (v * brightness).round() as u8;
+    Color::Rgb(scale(255.0), scale(220.0 - 180.0 * t), scale(90.0 - 60.0 * t))
+}
+
+impl LayerProvider for Quakes {
+    fn name(&self) -> &str {
+        "quakes"
+    }
+
+    fn shapes(&mut self, _view: &dyn MapProjection) -> Shapes {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64());
+        self.shapes_at(now)
+    }
+
+    fn message(&mut self) -> Option<String> {
+        self.update();
+        self.message.take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"{"type": "FeatureCollection", "features": [
+        {"type": "Feature", "id": "us1",
+         "properties": {"mag": 6.4, "place": "40 km E of Hualien City, Taiwan", "time": 1700000000000},
+         "geometry": {"type": "Point", "coordinates": [121.9, 23.9, 12.0]}},
+        {"type": "Feature", "id": "ak2",
+         "properties": {"mag": 2.7, "place": "Central Alaska", "time": 1700080000000},
+         "geometry": {"type": "Point", "coordinates": [-150.1, 63.2, 90.5]}},
+        {"type": "Feature", "id": "nc3",
+         "properties": {"mag": null, "place": "Northern California", "time": 1700080000000},
+         "geometry": {"type": "Point", "coordinates": [-122.8, 38.8, 2.0]}}
+    ]}"#;
+
+    #[test]
+    fn big_quakes_spread_further_and_old_ones_fade() {
+        let quakes = parse_feed(FEED.to_string()).unwrap();
+        assert_eq!(quakes.len(), 2, "the one without a magnitude yet is left out");
+        assert_eq!((quakes[0].lon, quakes[0].lat, quakes[0].time), (121.9, 23.9, 1_700_000_000.0));
+
+        let overlay = Quakes { quakes, poller: None, message: None };
+        let shapes = overlay.shapes_at(1_700_086_400.0);
+        assert_eq!(shapes.pulses.len(), 2);
+        let (taiwan, alaska) = (&shapes.pulses[0], &shapes.pulses[1]);
+        assert!(taiwan.radius_km > 5.0 * alaska.radius_km);
+        let red = |c: Color| match c {
+            Color::Rgb(r, _, _) => r,
+            _ => 0,
+        };
+        assert!(red(taiwan.color) < red(alaska.color), "a day old against a couple of hours");
+        assert_eq!(shapes.labels.len(), 1);
+        assert!(shapes.labels[0].text.contains("M6.4 40 km E of Hualien"));
+
+        assert_eq!(Source::parse("4.5_week"), Source::Url(format!("{USGS_URL}4.5_week.geojson")));
+        assert_eq!(Source::parse("quakes.geojson"), Source::File(PathBuf::from("quakes.geojson")));
+    }
+}
@@ -0,0 +1,206 @@
+//! The satellites overlay: where each satellite in a TLE set is right now,
+//! and the ground tracks of the ones being followed.
+//!
+//! Positions are for the real time, not the simulation clock, so the ISS
+//! goes over when it really does. Elements come from a file, or with the
+//! `online` feature, straight from Celestrak. Celestrak is fetched on a
+//! worker thread, so startup doesn't wait on it, and again every few hours
+//! for fresh elements.
+
+use crate::map::MapProjection;
+use crate::overlay::{LayerProvider, Poller, Shapes};
+use crate::sim::orbit::Tle;
+use anyhow::{bail, Context, Result};
+use ratatui::style::Color;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Celestrak's element sets by group (`stations`, `visual`, `starlink`, ...)
+const CELESTRAK_URL: &str = "https://celestrak.org/NORAD/elements/gp.php?FORMAT=tle&GROUP=";
+
+/// How often Celestrak is fetched again
+const REFRESH_INTERVAL: Duration = Duration::from_secs(4 * 3600);
+
+/// How much of the track behind a followed satellite is drawn, in orbits
+const TRACK_BEHIND: f64 = 0.35;
+/// And ahead of it
+const TRACK_AHEAD: f64 = 1.0;
+/// Seconds between track points
+const TRACK_STEP_SECS: f64 = 30.0;
+
+pub struct Satellites {
+    tles: Vec<Tle>,
+    /// Whether each satellite's ground track is drawn
+    followed: Vec<bool>,
+    /// What to follow, for element sets still to come in
+    follow: Vec<String>,
+    /// Fetches from Celestrak (`None` for a file)
+    poller: Option<Poller<Vec<Tle>>>,
+    message: Option<String>,
+}
+
+impl Satellites {
+    /// Load element sets from `source`, a TLE file or `celestrak:<group>`.
+    /// A file is read now; Celestrak is fetched in the background and the
+    /// overlay stays empty until it answers. See [`Satellites::new`] for
+    /// `follow`.
+    pub fn load(source: &str, follow: &[String]) -> Result<Self> {
+        match source.strip_prefix("celestrak:") {
+            Some(group) => {
+                let url = format!("{CELESTRAK_URL}{group}");
+                let poller = Poller::spawn(REFRESH_INTERVAL, move || {
+                    let body = crate::data::fetch::get(&url)?;
+                    let text = String::from_utf8(body).context("Celestrak sent something other than text")?;
+                    parse(&text, &url)
+                });
+                Ok(Self { tles: Vec::new(), followed: Vec::new(), follow: follow.to_vec(), poller: Some(poller), message: None })
+            }
+            None => {
+                let text = std::fs::read_to_string(source).with_context(|| format!("reading {source}"))?;
+                Self::new(parse(&text, source)?, follow)
+            }
+        }
+    }
+
+    /// Follow the satellites whose name contains one of `follow` (any case)
+    /// or whose catalog number it is. With none given, the ISS is followed
+    /// if it's there.
+    pub fn new(tles: Vec<Tle>, follow: &[String]) -> Result<Self> {
+        let mut satellites =
+            Self { tles: Vec::new(), followed: Vec::new(), follow: follow.to_vec(), poller: None, message: None };
+        satellites.set_elements(tles)?;
+        Ok(satellites)
+    }
+
+    /// Show `tles` in place of the element sets shown now
+    fn set_elements(&mut self, tles: Vec<Tle>) -> Result<()> {
+        let matches = |tle: &Tle, query: &str| {
+            tle.name.to_lowercase().contains(&query.to_lowercase()) || query.parse() == Ok(tle.catalog)
+        };
+        for query in &self.follow {
+            if !tles.iter().any(|t| matches(t, query)) {
+                bail!("no satellite matches {query}");
+            }
+        }
+        self.followed = tles
+            .iter()
+            .map(|t| match &self.follow[..] {
+                [] => t.name.starts_with("ISS"),
+                follow => follow.iter().any(|q| matches(t, q)),
+            })
+            .collect();
+        self.tles = tles;
+        Ok(())
+    }
+
+    /// Take in whatever the worker has fetched since the last frame
+    fn update(&mut self) {
+        let updates: Vec<Result<Vec<Tle>>> = self.poller.iter().flat_map(Poller::updates).collect();
+        for update in updates {
+            if let Err(e) = update.and_then(|tles| self.set_elements(tles)) {
+                self.message = Some(format!("satellites: {e:#}"));
+            }
+        }
+    }
+
+    /// What to draw at `unix` seconds
+    pub fn shapes_at(&self, unix: f64) -> Shapes {
+        const MARKER: Color = Color::Rgb(150, 170, 190);
+        const FOLLOWED: Color = Color::Rgb(255, 230, 120);
+        const AHEAD: Color = Color::Rgb(150, 130, 60);
+        const BEHIND: Color = Color::Rgb(80, 70, 40);
+        let mut shapes = Shapes::default();
+        for (tle, &followed) in self.tles.iter().zip(&self.followed) {
+            let Some(now) = tle.position_at(unix) else { continue };
+            if !followed {
+                shapes.point(now.lon, now.lat, '+', MARKER);
+                continue;
+            }
+            let period = tle.period();
+            for (from, to, color) in [(-TRACK_BEHIND, 0.0, BEHIND), (0.0, TRACK_AHEAD, AHEAD)] {
+                let steps = ((to - from) * period / TRACK_STEP_SECS).ceil() as usize;
+                let track = (0..=steps).filter_map(|i| {
+                    let p = tle.position_at(unix + (from + (to - from) * i as f64 / steps as f64) * period)?;
+                    Some((p.lon, p.lat))
+                });
+                for piece in split_at_antimeridian(track) {
+                    shapes.line(piece, color);
+                }
+            }
+            shapes.label(now.lon, now.lat, format!("✹ {} {:.0} km", tle.name, now.altitude_km), FOLLOWED);
+        }
+        shapes
+    }
+}
+
+impl LayerProvider for Satellites {
+    fn name(&self) -> &str {
+        "satellites"
+    }
+
+    fn shapes(&mut self, _view: &dyn MapProjection) -> Shapes {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64());
+        self.shapes_at(now)
+    }
+
+    fn message(&mut self) -> Option<String> {
+        self.update();
+        self.message.take()
+    }
+}
+
+/// The element sets in `text`, from `source`; none at all is an error
+fn parse(text: &str, source: &str) -> Result<Vec<Tle>> {
+    let tles = Tle::parse_all(text).with_context(|| format!("parsing {source}"))?;
+    if tles.is_empty() {
+        bail!("no element sets in {source}");
+    }
+    Ok(tles)
+}
+
+/// Break a track where it crosses 180°, so no piece runs back across the map
+fn split_at_antimeridian(points: impl Iterator<Item = (f64, f64)>) -> Vec<Vec<(f64, f64)>> {
+    let mut pieces: Vec<Vec<(f64, f64)>> = vec![Vec::new()];
+    for point in points {
+        let piece = pieces.last_mut().expect("never empty");
+        if piece.last().is_some_and(|&(lon, _)| (point.0 - lon).abs() > 180.0) {
+            pieces.push(vec![point]);
+        } else {
+            piece.push(point);
+        }
+    }
+    pieces.retain(|p| p.len() >= 2);
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TLES: &str = "ISS (ZARYA)
+1 25544U 98067A   24001.50000000  .00016717  00000-0  30000-3 0  9990
+2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.50377579431231
+HST
+1 20580U 90037B   24001.50000000  .00001000  00000-0  50000-4 0  9996
+2 20580  28.4700  90.0000 0002500  90.0000 270.0000 15.27000000 12347
+";
+
+    #[test]
+    fn the_iss_is_followed_and_the_rest_are_marked() {
+        let tles = Tle::parse_all(TLES).unwrap();
+        let epoch = tles[0].epoch;
+        let satellites = Satellites::new(tles.clone(), &[]).unwrap();
+        let shapes = satellites.shapes_at(epoch);
+        assert_eq!(shapes.points.len(), 1, "Hubble, as a marker");
+        assert_eq!(shapes.labels.len(), 1);
+        assert!(shapes.labels[0].text.starts_with("✹ ISS (ZARYA)"));
+        // An orbit and a third of track goes round the world, so it's split
+        assert!(shapes.lines.len() >= 3, "{}", shapes.lines.len());
+        for line in &shapes.lines {
+            assert!(line.points.windows(2).all(|w| (w[1].0 - w[0].0).abs() <= 180.0));
+        }
+
+        let satellites = Satellites::new(tles.clone(), &["20580".to_string()]).unwrap();
+        assert!(satellites.shapes_at(epoch).labels[0].text.starts_with("✹ HST"));
+        assert!(Satellites::new(tles, &["tiangong".to_string()]).is_err());
+    }
+}
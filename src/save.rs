@@ -0,0 +1,97 @@
+//! Save files: the world as it stands, to pick up again later.
+//!
+//! A save holds what a timeline snapshot does (`history`): city populations
+//! and infections, the burning cells, casualties and the clock. It adds the
+//! burn scars, which the timeline leaves out because they only ever grow.
+//! City indexes are positions in the city grid, so a save loads only over
+//! the same map data; the city count is kept to check that.
+//!
+//! Layout: a magic string and version byte, the city count as a
+//! little-endian `u32`, then the snapshot and the scars, each
+//! length-prefixed the way network messages are (`net`).
+
+use crate::app::BurnScarGrid;
+use crate::history::Snapshot;
+use crate::net::{read_frame, write_frame};
+use anyhow::{bail, Context, Result};
+use std::io::Read;
+use std::path::Path;
+
+const MAGIC: &[u8; 7] = b"TUIMAP\0";
+const VERSION: u8 = 1;
+
+/// A saved world
+pub struct SaveFile {
+    /// Cities in the grid it was saved from
+    pub cities: u32,
+    pub snapshot: Snapshot,
+    pub scars: BurnScarGrid,
+}
+
+impl SaveFile {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.extend_from_slice(&self.cities.to_le_bytes());
+        // Writing to a Vec can't fail
+        let _ = write_frame(&mut out, &self.snapshot.encode());
+        let _ = write_frame(&mut out, &self.scars.encode());
+        out
+    }
+
+    pub fn decode(mut bytes: &[u8]) -> Result<Self> {
+        let mut header = [0u8; 12];
+        bytes.read_exact(&mut header).context("not a save file")?;
+        if &header[..7] != MAGIC {
+            bail!("not a save file");
+        }
+        if header[7] != VERSION {
+            bail!("save file version {} (expected {VERSION})", header[7]);
+        }
+        let cities = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+        let snapshot = Snapshot::decode(&read_frame(&mut bytes)?).context("reading the world")?;
+        let scars = BurnScarGrid::decode(&read_frame(&mut bytes)?).context("reading burn scars")?;
+        if !bytes.is_empty() {
+            bail!("{} trailing bytes", bytes.len());
+        }
+        Ok(Self { cities, snapshot, scars })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.encode()).with_context(|| format!("writing {}", path.display()))
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::decode(&bytes).with_context(|| format!("loading {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{FireField, WeaponType};
+
+    #[test]
+    fn saves_round_trip_with_their_scars() {
+        let mut fires = FireField::new();
+        fires.ignite(20.0, 10.0, 200, WeaponType::Nuke);
+        let mut scars = BurnScarGrid::new();
+        scars.record(&fires);
+        let save = SaveFile {
+            cities: 7000,
+            snapshot: Snapshot { frame: 900, casualties: 12, cities: vec![(3, 100)], ..Default::default() },
+            scars,
+        };
+        let loaded = SaveFile::decode(&save.encode()).unwrap();
+        assert_eq!(loaded.cities, 7000);
+        assert_eq!(loaded.snapshot, save.snapshot);
+        assert_eq!(loaded.scars.burn_at(20.0, 10.0), 200);
+        assert!(!loaded.scars.is_empty());
+
+        let bytes = save.encode();
+        assert!(SaveFile::decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(SaveFile::decode(b"GeoJSON!....").is_err());
+    }
+}
@@ -0,0 +1,90 @@
+//! Nuclear winter: soot lofted by firestorms into the stratosphere, and the
+//! global cooling it causes.
+//!
+//! Soot is tracked in teragrams (Tg), the unit climate studies use. The
+//! cooling curve roughly follows published results: a few Tg (a regional war)
+//! gives about a degree of cooling, ~150 Tg (a full exchange) nearer ten.
+//! Lofting and washout are sped up so a session sees the whole arc.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Climate {
+    /// Stratospheric soot load (Tg)
+    pub soot_tg: f64,
+}
+
+impl Climate {
+    /// Soot lofted per unit of fire intensity per step
+    pub const TG_PER_FIRE_INTENSITY: f64 = 1e-9;
+    /// Above this the sky visibly darkens and the map desaturates
+    pub const DIMMING_TG: f64 = 5.0;
+    /// Above this crops fail and populations start to decline worldwide
+    pub const FAMINE_TG: f64 = 20.0;
+    /// Steps between population-decline passes
+    pub const DECLINE_INTERVAL: u64 = 60;
+
+    /// Deepest cooling the model reaches (°C)
+    const MAX_COOLING_C: f64 = 10.0;
+    /// Soot load at which cooling reaches ~63% of the maximum
+    const COOLING_SCALE_TG: f64 = 60.0;
+    /// Fraction of the soot left after each step (~10 min half-life at 60fps)
+    const RETAINED_PER_STEP: f64 = 0.99998;
+    /// Population lost per decline pass per °C of cooling past famine onset
+    const DECLINE_PER_DEGREE: f64 = 0.0005;
+
+    pub fn inject(&mut self, tg: f64) {
+        self.soot_tg += tg;
+    }
+
+    /// Wash soot out of the stratosphere for one step
+    pub fn step(&mut self) {
+        self.soot_tg *= Self::RETAINED_PER_STEP;
+    }
+
+    /// Global mean temperature change (°C, negative = cooling)
+    pub fn temperature_anomaly_c(&self) -> f64 {
+        -Self::MAX_COOLING_C * (1.0 - (-self.soot_tg / Self::COOLING_SCALE_TG).exp())
+    }
+
+    /// How washed-out the world looks (0 = normal, 1 = fully gray)
+    pub fn desaturation(&self) -> f32 {
+        if self.soot_tg <= Self::DIMMING_TG {
+            return 0.0;
+        }
+        (1.0 - (-(self.soot_tg - Self::DIMMING_TG) / Self::COOLING_SCALE_TG).exp()) as f32
+    }
+
+    /// Fraction of every city's population lost in one decline pass
+    pub fn decline_rate(&self) -> f64 {
+        if self.soot_tg <= Self::FAMINE_TG {
+            return 0.0;
+        }
+        let onset = Climate { soot_tg: Self::FAMINE_TG }.temperature_anomaly_c();
+        (onset - self.temperature_anomaly_c()) * Self::DECLINE_PER_DEGREE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cooling_and_decline_follow_soot() {
+        let mut climate = Climate::default();
+        assert_eq!(climate.temperature_anomaly_c(), 0.0);
+        assert_eq!(climate.desaturation(), 0.0);
+
+        climate.inject(5.0);
+        assert!((-1.5..-0.5).contains(&climate.temperature_anomaly_c()));
+        assert_eq!(climate.decline_rate(), 0.0);
+
+        climate.inject(145.0);
+        assert!((-10.0..-8.0).contains(&climate.temperature_anomaly_c()));
+        assert!(climate.desaturation() > 0.8);
+        assert!(climate.decline_rate() > 0.0);
+
+        for _ in 0..100_000 {
+            climate.step();
+        }
+        assert!(climate.soot_tg < 25.0);
+    }
+}
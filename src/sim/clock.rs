@@ -0,0 +1,263 @@
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Simulation speed multiplier relative to wall time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockSpeed {
+    Paused,
+    RealTime,
+    Minute,
+    Hour,
+}
+
+impl ClockSpeed {
+    /// Simulated seconds per wall-clock second
+    pub fn multiplier(self) -> f64 {
+        match self {
+            ClockSpeed::Paused => 0.0,
+            ClockSpeed::RealTime => 1.0,
+            ClockSpeed::Minute => 60.0,
+            ClockSpeed::Hour => 3600.0,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ClockSpeed::Paused => "⏸",
+            ClockSpeed::RealTime => "1x",
+            ClockSpeed::Minute => "60x",
+            ClockSpeed::Hour => "3600x",
+        }
+    }
+
+    fn next(self) -> Self {
+        match self {
+            ClockSpeed::Paused => ClockSpeed::RealTime,
+            ClockSpeed::RealTime => ClockSpeed::Minute,
+            ClockSpeed::Minute => ClockSpeed::Hour,
+            ClockSpeed::Hour => ClockSpeed::Paused,
+        }
+    }
+}
+
+/// Simulated UTC clock, advanced from wall-clock deltas or — for the
+/// fixed-timestep simulation — by a fixed step per tick.
+pub struct SimClock {
+    /// Simulated time as seconds since the Unix epoch
+    unix_secs: f64,
+    speed: ClockSpeed,
+    /// Speed to restore when unpausing
+    resume_speed: ClockSpeed,
+    last_tick: Option<Instant>,
+    /// Wall seconds credited per tick instead of measured time (reproducible runs)
+    fixed_step: Option<f64>,
+}
+
+impl SimClock {
+    /// Longest wall-clock gap credited per tick — a stalled frame (resize,
+    /// data reload) shouldn't fast-forward the simulation.
+    const MAX_TICK_SECS: f64 = 0.25;
+
+    /// One frame of the ~60fps main loop
+    pub const FRAME_SECS: f64 = 1.0 / 60.0;
+
+    pub fn new(unix_secs: f64, speed: ClockSpeed) -> Self {
+        let resume_speed = if speed == ClockSpeed::Paused { ClockSpeed::Minute } else { speed };
+        Self { unix_secs, speed, resume_speed, last_tick: None, fixed_step: None }
+    }
+
+    /// Credit exactly `real_secs` per tick regardless of frame timing, so the
+    /// simulation depends only on the number of ticks
+    pub fn with_fixed_step(mut self, real_secs: f64) -> Self {
+        self.fixed_step = Some(real_secs);
+        self
+    }
+
+    /// Start at the current system time, running at 60x.
+    pub fn now() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+        Self::new(secs, ClockSpeed::Minute)
+    }
+
+    /// Advance by the wall time since the previous tick (or the fixed step).
+    /// Returns the simulated seconds elapsed.
+    pub fn tick(&mut self) -> f64 {
+        if let Some(step) = self.fixed_step {
+            return self.advance(step);
+        }
+        let now = Instant::now();
+        let real_dt = self
+            .last_tick
+            .map(|t| now.duration_since(t).as_secs_f64())
+            .unwrap_or(0.0);
+        self.last_tick = Some(now);
+        self.advance(real_dt)
+    }
+
+    /// Advance by an explicit wall-clock delta (seconds).
+    /// Returns the simulated seconds elapsed.
+    pub fn advance(&mut self, real_dt: f64) -> f64 {
+        let sim_dt = real_dt.clamp(0.0, Self::MAX_TICK_SECS) * self.speed.multiplier();
+        self.unix_secs += sim_dt;
+        sim_dt
+    }
+
+    /// Cycle ⏸ → 1x → 60x → 3600x → ⏸
+    pub fn cycle_speed(&mut self) {
+        self.set_speed(self.speed.next());
+    }
+
+    pub fn toggle_pause(&mut self) {
+        let next = if self.speed == ClockSpeed::Paused { self.resume_speed } else { ClockSpeed::Paused };
+        self.set_speed(next);
+    }
+
+    pub fn set_speed(&mut self, speed: ClockSpeed) {
+        if self.speed != ClockSpeed::Paused {
+            self.resume_speed = self.speed;
+        }
+        self.speed = speed;
+    }
+
+    pub fn speed(&self) -> ClockSpeed {
+        self.speed
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.speed == ClockSpeed::Paused
+    }
+
+    pub fn unix_secs(&self) -> f64 {
+        self.unix_secs
+    }
+
+    /// Jump to another instance's time, keeping speed
+    pub fn set_unix_secs(&mut self, unix_secs: f64) {
+        self.unix_secs = unix_secs;
+    }
+
+    /// "YYYY-MM-DD HH:MMZ"
+    pub fn format_utc(&self) -> String {
+        let secs = self.unix_secs.floor() as i64;
+        let (y, m, d) = civil_from_days(secs.div_euclid(86_400));
+        let sod = secs.rem_euclid(86_400);
+        format!("{y:04}-{m:02}-{d:02} {:02}:{:02}Z", sod / 3600, (sod / 60) % 60)
+    }
+
+    /// Point on Earth where the sun is directly overhead, as (lon, lat).
+    /// Low-precision model (declination from day of year, no equation of
+    /// time) — good to about a degree, plenty for a terminator overlay.
+    pub fn subsolar_point(&self) -> (f64, f64) {
+        let days = self.unix_secs / 86_400.0;
+        let utc_hours = days.rem_euclid(1.0) * 24.0;
+        let (y, _, _) = civil_from_days(days.floor() as i64);
+        let day_of_year = days.floor() - days_from_civil(y, 1, 1) as f64;
+        let decl = -23.44 * (std::f64::consts::TAU / 365.0 * (day_of_year + 10.0)).cos();
+        let lon = crate::geo::wrap_lon(-(utc_hours - 12.0) * 15.0);
+        (lon, decl)
+    }
+
+    /// Sun elevation above the horizon in degrees at (lon, lat).
+    pub fn sun_elevation(&self, lon: f64, lat: f64) -> f64 {
+        let (sun_lon, sun_lat) = self.subsolar_point();
+        sun_elevation_from(sun_lon, sun_lat, lon, lat)
+    }
+}
+
+/// Sun elevation at (lon, lat) given a precomputed subsolar point —
+/// use in per-cell loops to avoid recomputing the sun position.
+#[inline]
+pub fn sun_elevation_from(sun_lon: f64, sun_lat: f64, lon: f64, lat: f64) -> f64 {
+    let (lat, sun_lat) = (lat.to_radians(), sun_lat.to_radians());
+    let dlon = (lon - sun_lon).to_radians();
+    (lat.sin() * sun_lat.sin() + lat.cos() * sun_lat.cos() * dlon.cos())
+        .clamp(-1.0, 1.0)
+        .asin()
+        .to_degrees()
+}
+
+/// Days since 1970-01-01 → (year, month, day). Howard Hinnant's algorithm.
+fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+/// (year, month, day) → days since 1970-01-01.
+pub(crate) fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let m = m as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_utc_dates() {
+        assert_eq!(SimClock::new(0.0, ClockSpeed::Paused).format_utc(), "1970-01-01 00:00Z");
+        // 2024-02-29 12:34:56 UTC (leap day)
+        assert_eq!(SimClock::new(1_709_210_096.0, ClockSpeed::Paused).format_utc(), "2024-02-29 12:34Z");
+        assert_eq!(days_from_civil(2024, 2, 29), 19_782);
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+    }
+
+    #[test]
+    fn advance_scales_and_clamps() {
+        let mut clock = SimClock::new(0.0, ClockSpeed::Minute);
+        assert_eq!(clock.advance(0.1), 6.0);
+        // A 10s stall only credits MAX_TICK_SECS
+        assert_eq!(clock.advance(10.0), 15.0);
+        clock.toggle_pause();
+        assert_eq!(clock.advance(0.1), 0.0);
+        clock.toggle_pause();
+        assert_eq!(clock.speed(), ClockSpeed::Minute);
+    }
+
+    #[test]
+    fn fixed_step_ignores_wall_time() {
+        let mut clock = SimClock::new(0.0, ClockSpeed::Minute).with_fixed_step(SimClock::FRAME_SECS);
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        assert_eq!(clock.tick(), 1.0);
+        assert_eq!(clock.tick(), 1.0);
+        assert_eq!(clock.unix_secs(), 2.0);
+    }
+
+    #[test]
+    fn cycle_wraps_through_pause() {
+        let mut clock = SimClock::new(0.0, ClockSpeed::Hour);
+        clock.cycle_speed();
+        assert!(clock.is_paused());
+        clock.cycle_speed();
+        assert_eq!(clock.speed(), ClockSpeed::RealTime);
+    }
+
+    #[test]
+    fn subsolar_point_tracks_time_and_season() {
+        // June solstice noon UTC: sun over the Tropic of Cancer near Greenwich
+        let june = SimClock::new(days_from_civil(2025, 6, 21) as f64 * 86_400.0 + 43_200.0, ClockSpeed::Paused);
+        let (lon, lat) = june.subsolar_point();
+        assert!(lon.abs() < 1.0, "lon {lon}");
+        assert!((lat - 23.44).abs() < 0.5, "lat {lat}");
+        assert!(june.sun_elevation(0.0, 23.44) > 88.0);
+        assert!(june.sun_elevation(180.0, 0.0) < 0.0);
+
+        // Midnight UTC: sun over the antimeridian
+        let (lon, _) = SimClock::new(0.0, ClockSpeed::Paused).subsolar_point();
+        assert!((lon.abs() - 180.0).abs() < 1e-6);
+    }
+}
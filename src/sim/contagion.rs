@@ -0,0 +1,131 @@
+//! Bio-weapon contagion: logistic growth inside each city, distance-weighted
+//! jumps between cities, hops along the travel network, and a cure that
+//! eventually ends the outbreak.
+//!
+//! The model only works on infected fractions. The city list and the
+//! bookkeeping of who is infected live in `App`.
+
+/// Infected fraction a city starts at when the disease jumps to it
+pub const SEED_INFECTION: f32 = 0.005;
+
+/// Below this the city counts as clear again
+pub const MIN_INFECTION: f32 = 0.001;
+
+/// An outbreak in progress: one pathogen, one race for the cure
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Outbreak {
+    /// Basic reproduction number. Above 1 the disease grows; below 1 it dies out.
+    pub r0: f64,
+    /// Fraction of the infected who die per interval
+    pub mortality: f64,
+    /// Steps until the cure is deployed (0 = cured)
+    pub cure_in: u64,
+}
+
+impl Outbreak {
+    /// Default basic reproduction number
+    pub const DEFAULT_R0: f64 = 3.0;
+    /// Time to a cure: 4 minutes at 60fps
+    pub const CURE_FRAMES: u64 = 4 * 60 * 60;
+    /// Steps between contagion updates
+    pub const INTERVAL: u64 = 10;
+    /// Furthest a city can infect another
+    pub const SPREAD_KM: f64 = 600.0;
+    /// Infected fraction a city needs before it can infect others
+    pub const CONTAGIOUS: f32 = 0.05;
+
+    /// Logistic growth rate per interval for each unit of R0 above 1
+    const GROWTH_PER_R0: f32 = 0.0125;
+    /// Default fraction of the infected who die per interval
+    pub const DEFAULT_MORTALITY: f64 = 0.0002;
+    /// Per-interval jump chance scale and distance falloff
+    const SPREAD_RATE: f64 = 0.004;
+    const SPREAD_FALLOFF_KM: f64 = 200.0;
+    /// Per-interval chance scale of a hop along the busiest travel route
+    const ROUTE_RATE: f64 = 0.01;
+    /// Infected fraction kept per interval once the cure is out
+    const CURED_RETAINED: f32 = 0.95;
+
+    pub fn new(r0: f64, mortality: f64) -> Self {
+        Self { r0, mortality, cure_in: Self::CURE_FRAMES }
+    }
+
+    /// Share of the infected who die per minute at 60fps, for display
+    pub fn lethality_per_minute(mortality: f64) -> f64 {
+        1.0 - (1.0 - mortality).powf((60 * 60 / Self::INTERVAL) as f64)
+    }
+
+    pub fn is_cured(&self) -> bool {
+        self.cure_in == 0
+    }
+
+    /// Infected fraction after one interval of logistic growth (or decline,
+    /// when R0 < 1 or the cure is out)
+    pub fn grow(&self, infected: f32) -> f32 {
+        if self.is_cured() {
+            return infected * Self::CURED_RETAINED;
+        }
+        let rate = (self.r0 as f32 - 1.0) * Self::GROWTH_PER_R0;
+        (infected + rate * infected * (1.0 - infected)).clamp(0.0, 1.0)
+    }
+
+    /// Fraction of a city's population that dies this interval
+    pub fn deaths(&self, infected: f32) -> f64 {
+        if self.is_cured() {
+            0.0
+        } else {
+            infected as f64 * self.mortality
+        }
+    }
+
+    /// Chance this interval that a city at `infected` seeds one `dist_km` away
+    pub fn spread_chance(&self, infected: f32, dist_km: f64) -> f64 {
+        if self.is_cured() || infected < Self::CONTAGIOUS || dist_km >= Self::SPREAD_KM {
+            return 0.0;
+        }
+        Self::SPREAD_RATE * infected as f64 * self.r0 * (-dist_km / Self::SPREAD_FALLOFF_KM).exp()
+    }
+
+    /// Chance this interval that a city at `infected` seeds one a travel
+    /// route of `weight` away, however far that is
+    pub fn route_chance(&self, infected: f32, weight: f64) -> f64 {
+        if self.is_cured() || infected < Self::CONTAGIOUS {
+            return 0.0;
+        }
+        Self::ROUTE_RATE * infected as f64 * self.r0 * weight
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn growth_follows_r0() {
+        let outbreak = Outbreak::new(3.0, Outbreak::DEFAULT_MORTALITY);
+        let mut infected = 0.01;
+        for _ in 0..2000 {
+            infected = outbreak.grow(infected);
+        }
+        assert!(infected > 0.99, "{infected}");
+
+        let fizzle = Outbreak::new(0.5, Outbreak::DEFAULT_MORTALITY);
+        assert!(fizzle.grow(0.1) < 0.1);
+    }
+
+    #[test]
+    fn cure_stops_deaths_and_spread() {
+        let mut outbreak = Outbreak::new(3.0, Outbreak::DEFAULT_MORTALITY);
+        assert!(outbreak.deaths(0.5) > 0.0);
+        assert!(outbreak.route_chance(0.5, 1.0) > outbreak.route_chance(0.5, 0.1));
+        assert_eq!(outbreak.route_chance(0.01, 1.0), 0.0);
+        assert!(outbreak.spread_chance(0.5, 100.0) > outbreak.spread_chance(0.5, 400.0));
+        assert_eq!(outbreak.spread_chance(0.5, Outbreak::SPREAD_KM), 0.0);
+
+        outbreak.cure_in = 0;
+        assert_eq!(outbreak.deaths(0.5), 0.0);
+        assert_eq!(outbreak.spread_chance(0.5, 100.0), 0.0);
+        assert_eq!(outbreak.route_chance(0.5, 1.0), 0.0);
+        assert!(outbreak.grow(0.5) < 0.5);
+    }
+}
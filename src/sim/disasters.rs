@@ -0,0 +1,244 @@
+//! Natural disasters for the sandbox: volcanic eruptions, hurricanes and
+//! wildfires, rolled at random while the toggle is on or fired from a
+//! scenario script.
+//!
+//! Eruptions happen at real volcanoes. Each one keeps lava burning at the
+//! vent and puts out an ash plume that drifts downwind. Hurricanes form
+//! over the tropical basins. They run west, then recurve poleward, and
+//! they batter the coasts they pass. Over land they wear themselves out.
+//! Wildfires are ordinary fires lit on open ground.
+
+use crate::geo::destination_point;
+use crate::hash::{hash3, rand_simple};
+use crate::sim::plume::Plume;
+
+/// A volcano in the built-in list
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Volcano {
+    pub name: &'static str,
+    pub lon: f64,
+    pub lat: f64,
+}
+
+const fn volcano(name: &'static str, lon: f64, lat: f64) -> Volcano {
+    Volcano { name, lon, lat }
+}
+
+/// Volcanoes that have erupted in recorded history (Smithsonian GVP
+/// summit coordinates)
+pub const VOLCANOES: &[Volcano] = &[
+    volcano("Etna", 14.999, 37.748),
+    volcano("Vesuvius", 14.426, 40.821),
+    volcano("Stromboli", 15.213, 38.789),
+    volcano("Santorini", 25.396, 36.404),
+    volcano("Hekla", -19.666, 63.983),
+    volcano("Eyjafjallajokull", -19.633, 63.633),
+    volcano("Mount St. Helens", -122.18, 46.2),
+    volcano("Mount Rainier", -121.758, 46.853),
+    volcano("Yellowstone", -110.67, 44.43),
+    volcano("Kilauea", -155.287, 19.421),
+    volcano("Mauna Loa", -155.608, 19.475),
+    volcano("Popocatepetl", -98.622, 19.023),
+    volcano("Soufriere Hills", -62.18, 16.72),
+    volcano("Cotopaxi", -78.436, -0.677),
+    volcano("Tungurahua", -78.442, -1.467),
+    volcano("Villarrica", -71.93, -39.42),
+    volcano("Nyiragongo", 29.25, -1.52),
+    volcano("Kilimanjaro", 37.35, -3.07),
+    volcano("Erta Ale", 40.67, 13.6),
+    volcano("Mount Fuji", 138.731, 35.361),
+    volcano("Sakurajima", 130.657, 31.593),
+    volcano("Klyuchevskoy", 160.638, 56.056),
+    volcano("Pinatubo", 120.35, 15.13),
+    volcano("Mayon", 123.685, 13.257),
+    volcano("Taal", 120.993, 14.002),
+    volcano("Krakatau", 105.423, -6.102),
+    volcano("Merapi", 110.446, -7.542),
+    volcano("Tambora", 118.0, -8.25),
+    volcano("Ruapehu", 175.57, -39.28),
+    volcano("Erebus", 167.17, -77.53),
+];
+
+impl Volcano {
+    /// A volcano by name, ignoring case; "Mount" may be left off
+    pub fn find(name: &str) -> Option<&'static Volcano> {
+        let name = name.trim().to_lowercase();
+        VOLCANOES.iter().find(|v| {
+            let full = v.name.to_lowercase();
+            full == name || full.strip_prefix("mount ") == Some(name.as_str())
+        })
+    }
+}
+
+/// Kinds of natural disaster, for the event log
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hazard {
+    Volcano,
+    Hurricane,
+    Wildfire,
+}
+
+impl Hazard {
+    const ALL: [Hazard; 3] = [Hazard::Volcano, Hazard::Hurricane, Hazard::Wildfire];
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Hazard::Volcano => "▲",
+            Hazard::Hurricane => "◎",
+            Hazard::Wildfire => "♨",
+        }
+    }
+}
+
+/// Frames between rolls for a random disaster (1s at 60fps)
+pub const ROLL_FRAMES: u64 = 60;
+/// Chance a roll brings a disaster: about one every 40 seconds
+const ROLL_CHANCE: f64 = 1.0 / 40.0;
+
+/// The disaster a roll brings on `frame`, if any
+pub fn roll(seed: u64, frame: u64) -> Option<Hazard> {
+    let key = hash3(seed, frame, 0xD15A);
+    (rand_simple(key) < ROLL_CHANCE).then(|| Hazard::ALL[(hash3(key, 1, 0) % Hazard::ALL.len() as u64) as usize])
+}
+
+/// An erupting volcano: lava at the vent and an ash plume downwind
+#[derive(Clone)]
+pub struct Eruption {
+    pub name: String,
+    pub lon: f64,
+    pub lat: f64,
+    /// Falls by one a frame; the eruption is over at zero
+    pub intensity: u16,
+    pub plume: Plume,
+}
+
+impl Eruption {
+    /// A fresh eruption lasts ~100s at 60fps
+    pub const INTENSITY: u16 = 6000;
+    /// Ash column at the vent, and how wide the cloud spreads
+    const ASH_KM: f64 = 40.0;
+    const ASH_SPREAD_KM: f64 = 450.0;
+    /// Lava flows reach this far from the vent
+    pub const LAVA_KM: f64 = 25.0;
+
+    pub fn new(name: impl Into<String>, lon: f64, lat: f64) -> Self {
+        Self {
+            name: name.into(),
+            lon,
+            lat,
+            intensity: Self::INTENSITY,
+            plume: Plume::new(lon, lat, Self::ASH_KM, Self::ASH_SPREAD_KM),
+        }
+    }
+}
+
+/// Where tropical cyclones form: the Atlantic, eastern and western
+/// Pacific, the Bay of Bengal, the south Indian Ocean and the Coral Sea
+const BASINS: [(f64, f64); 6] = [(-45.0, 14.0), (-105.0, 13.0), (140.0, 13.0), (89.0, 14.0), (68.0, -12.0), (155.0, -14.0)];
+
+/// A hurricane moving along its track
+#[derive(Clone, Debug)]
+pub struct Hurricane {
+    pub lon: f64,
+    pub lat: f64,
+    /// Compass heading (degrees). Northern storms turn clockwise through
+    /// north, southern ones anticlockwise through south.
+    heading: f64,
+    /// 0–1; the storm dies below `MIN_STRENGTH`
+    pub strength: f64,
+    pub age: u32,
+    pub seed: u64,
+}
+
+impl Hurricane {
+    /// Track speed. Real storms move at ~20 km/h; this crosses an ocean in
+    /// about a minute.
+    const SPEED_KM_PER_STEP: f64 = 1.5;
+    /// Reach of the storm's winds
+    pub const RADIUS_KM: f64 = 350.0;
+    /// Degrees a step the track bends poleward, at full recurvature
+    const RECURVE_PER_STEP: f64 = 0.12;
+    /// Past this latitude the water is too cool to feed the storm
+    const WARM_WATER_LAT: f64 = 30.0;
+    const MIN_STRENGTH: f64 = 0.1;
+    /// Two minutes at 60fps, whatever happens
+    const MAX_AGE: u32 = 7200;
+
+    pub fn new(lon: f64, lat: f64, seed: u64) -> Self {
+        // West-northwest in the north, west-southwest in the south
+        let heading = if lat >= 0.0 { 285.0 } else { 255.0 };
+        Self { lon, lat, heading, strength: 0.6, age: 0, seed }
+    }
+
+    /// A storm forming in one of the basins
+    pub fn spawn(seed: u64) -> Self {
+        let (lon, lat) = BASINS[(seed % BASINS.len() as u64) as usize];
+        let lon = lon + (rand_simple(hash3(seed, 1, 0)) - 0.5) * 20.0;
+        let lat = lat + (rand_simple(hash3(seed, 2, 0)) - 0.5) * 6.0;
+        Self::new(lon, lat, seed)
+    }
+
+    /// Move one step along the track, feeding on warm sea and wearing down
+    /// over land or cool water
+    pub fn step(&mut self, over_land: bool) {
+        self.age += 1;
+        let recurve = Self::RECURVE_PER_STEP * (self.lat.abs() / Self::WARM_WATER_LAT).min(1.0);
+        self.heading = if self.lat >= 0.0 {
+            (self.heading + recurve).min(405.0)
+        } else {
+            (self.heading - recurve).max(135.0)
+        };
+        (self.lon, self.lat) = destination_point(self.lon, self.lat, self.heading, Self::SPEED_KM_PER_STEP);
+        self.strength = if over_land {
+            self.strength * 0.995
+        } else if self.lat.abs() > Self::WARM_WATER_LAT {
+            self.strength * 0.999
+        } else {
+            (self.strength + 0.0005).min(1.0)
+        };
+    }
+
+    /// Saffir–Simpson category, 1–5
+    pub fn category(&self) -> u8 {
+        (self.strength * 5.0).ceil().clamp(1.0, 5.0) as u8
+    }
+
+    pub fn is_spent(&self) -> bool {
+        self.strength < Self::MIN_STRENGTH || self.age >= Self::MAX_AGE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn volcanoes_are_found_by_name() {
+        assert_eq!(Volcano::find("etna").map(|v| v.name), Some("Etna"));
+        assert_eq!(Volcano::find("St. Helens").map(|v| v.name), Some("Mount St. Helens"));
+        assert!(Volcano::find("Paris").is_none());
+    }
+
+    #[test]
+    fn hurricanes_recurve_and_die_over_land() {
+        let mut storm = Hurricane::new(-45.0, 14.0, 1);
+        let start = (storm.lon, storm.lat);
+        for _ in 0..2000 {
+            storm.step(false);
+        }
+        assert!(storm.lon < start.0 && storm.lat > start.1, "{:?}", (storm.lon, storm.lat));
+        assert!(storm.heading > 360.0 - 45.0);
+
+        while !storm.is_spent() {
+            storm.step(true);
+        }
+        assert!(storm.strength < Hurricane::MIN_STRENGTH);
+    }
+
+    #[test]
+    fn rolls_are_rare_and_reproducible() {
+        let hits = (0..4000).filter(|&f| roll(7, f).is_some()).count();
+        assert!((50..200).contains(&hits), "{hits}");
+        assert_eq!(roll(7, 123), roll(7, 123));
+    }
+}
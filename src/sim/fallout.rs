@@ -0,0 +1,149 @@
+//! Accumulated radiation dose on the ground.
+//!
+//! Fallout plumes drift and fade within seconds on screen, but what they drop
+//! stays put. `FalloutGrid` keeps the dose deposited in each 0.25° cell and
+//! lets it decay on simulated time, so contamination outlives the plume that
+//! laid it down.
+
+use crate::geo::{normalize_lat, normalize_lon};
+
+pub struct FalloutGrid {
+    /// Accumulated dose per cell (Gy)
+    dose: Vec<f32>,
+    /// Indices of cells holding any dose, so decay skips clean ground
+    active: Vec<usize>,
+}
+
+impl FalloutGrid {
+    pub const RESOLUTION: f64 = 0.25;
+    pub(crate) const WIDTH: usize = 1440;
+    pub(crate) const HEIGHT: usize = 720;
+    /// Dose halves every simulated day
+    pub const HALF_LIFE_SECS: f64 = 24.0 * 3600.0;
+    /// Roughly fatal for anyone caught in the open
+    pub const LETHAL_GY: f32 = 8.0;
+    /// Below this a cell counts as clean again
+    const MIN_GY: f32 = 0.01;
+
+    pub fn new() -> Self {
+        Self { dose: vec![0.0; Self::WIDTH * Self::HEIGHT], active: Vec::new() }
+    }
+
+    /// Cell holding (lon, lat), in the 0.25° layout the burn scars share
+    pub(crate) fn index(lon: f64, lat: f64) -> usize {
+        let col = ((normalize_lon(lon) / Self::RESOLUTION) as usize).min(Self::WIDTH - 1);
+        let row = ((normalize_lat(lat) / Self::RESOLUTION) as usize).min(Self::HEIGHT - 1);
+        row * Self::WIDTH + col
+    }
+
+    fn add(&mut self, idx: usize, gy: f32) {
+        if self.dose[idx] == 0.0 {
+            self.active.push(idx);
+        }
+        self.dose[idx] += gy;
+    }
+
+    /// Deposit `gy` at (lon, lat), falling off with the square of the
+    /// distance to nothing at `radius_km` — the same shape as fallout damage
+    pub fn deposit(&mut self, lon: f64, lat: f64, radius_km: f64, gy: f32) {
+        if gy <= 0.0 || radius_km <= 0.0 {
+            return;
+        }
+        let dlat = radius_km / 111.0;
+        let dlon = dlat / lat.to_radians().cos().max(0.05);
+        let mut y = lat - dlat;
+        while y <= lat + dlat {
+            let mut x = lon - dlon;
+            while x <= lon + dlon {
+                let dx = (x - lon) / dlon;
+                let dy = (y - lat) / dlat;
+                let falloff = 1.0 - (dx * dx + dy * dy);
+                if falloff > 0.0 {
+                    self.add(Self::index(x, y), gy * falloff as f32);
+                }
+                x += Self::RESOLUTION;
+            }
+            y += Self::RESOLUTION;
+        }
+        // Small plumes still mark the cell they sit in
+        if dlat < Self::RESOLUTION {
+            self.add(Self::index(lon, lat), gy);
+        }
+    }
+
+    /// Let `sim_secs` of radioactive decay pass
+    pub fn decay(&mut self, sim_secs: f64) {
+        if sim_secs <= 0.0 || self.active.is_empty() {
+            return;
+        }
+        let factor = 0.5f64.powf(sim_secs / Self::HALF_LIFE_SECS) as f32;
+        let dose = &mut self.dose;
+        self.active.retain(|&idx| {
+            dose[idx] *= factor;
+            if dose[idx] < Self::MIN_GY {
+                dose[idx] = 0.0;
+                return false;
+            }
+            true
+        });
+    }
+
+    /// Accumulated dose at a point (Gy)
+    pub fn dose_at(&self, lon: f64, lat: f64) -> f32 {
+        self.dose[Self::index(lon, lat)]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Contaminated cells as (row, col, dose in Gy)
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, f32)> + '_ {
+        self.active.iter().map(|&idx| (idx / Self::WIDTH, idx % Self::WIDTH, self.dose[idx]))
+    }
+
+    /// Ground carrying any dose (km²)
+    pub fn area_km2(&self) -> f64 {
+        let cell_km = Self::RESOLUTION * 111.0;
+        self.active
+            .iter()
+            .map(|&idx| {
+                let lat = (idx / Self::WIDTH) as f64 * Self::RESOLUTION - 90.0 + Self::RESOLUTION / 2.0;
+                cell_km * cell_km * lat.to_radians().cos()
+            })
+            .sum()
+    }
+}
+
+impl Default for FalloutGrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dose_accumulates_and_halves_each_day() {
+        let mut grid = FalloutGrid::new();
+        grid.deposit(30.0, 50.0, 100.0, 4.0);
+        grid.deposit(30.0, 50.0, 100.0, 4.0);
+        let center = grid.dose_at(30.0, 50.0);
+        assert!((7.5..=8.0).contains(&center), "{center}");
+        assert!(grid.dose_at(30.0, 50.6) < center);
+        assert_eq!(grid.dose_at(30.0, 52.0), 0.0);
+        let disc = std::f64::consts::PI * 100.0 * 100.0;
+        assert!((grid.area_km2() - disc).abs() / disc < 0.25, "{}", grid.area_km2());
+
+        grid.decay(FalloutGrid::HALF_LIFE_SECS);
+        assert!((grid.dose_at(30.0, 50.0) - center / 2.0).abs() < 1e-3);
+
+        for _ in 0..20 {
+            grid.decay(FalloutGrid::HALF_LIFE_SECS);
+        }
+        assert!(grid.is_empty());
+        assert_eq!(grid.dose_at(30.0, 50.0), 0.0);
+    }
+}
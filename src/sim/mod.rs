@@ -0,0 +1,26 @@
+pub mod climate;
+pub mod clock;
+pub mod contagion;
+pub mod disasters;
+pub mod fallout;
+pub mod orbit;
+pub mod plume;
+pub mod refugees;
+pub mod response;
+pub mod travel;
+pub mod tsunami;
+pub mod weather;
+pub mod wind;
+
+pub use climate::Climate;
+pub use clock::{ClockSpeed, SimClock};
+pub use contagion::Outbreak;
+pub use disasters::{Eruption, Hurricane};
+pub use fallout::FalloutGrid;
+pub use plume::{Plume, Puff};
+pub use refugees::Flow;
+pub use response::Response;
+pub use travel::TravelNetwork;
+pub use tsunami::Tsunami;
+pub use weather::WeatherGrid;
+pub use wind::{WindField, WindGrid};
@@ -0,0 +1,142 @@
+//! Satellite positions from two-line element sets (TLEs), propagated with
+//! SGP4 (the `sgp4` crate), the model TLEs are fitted for. Positions come
+//! out in the TEME frame and are turned into points on the ground with the
+//! Greenwich sidereal angle.
+
+use anyhow::{bail, Context, Result};
+use sgp4::{Constants, Elements, MinutesSinceEpoch};
+use std::f64::consts::TAU;
+
+/// Equatorial radius, km
+const EARTH_RADIUS_KM: f64 = 6378.137;
+const SECS_PER_DAY: f64 = 86_400.0;
+
+/// One satellite's orbit, as published
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tle {
+    pub name: String,
+    /// NORAD catalog number
+    pub catalog: u32,
+    /// Unix seconds the elements hold at
+    pub epoch: f64,
+    /// Revolutions per day
+    revs_per_day: f64,
+    constants: Constants,
+}
+
+/// Where a satellite is over the ground
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SubPoint {
+    pub lon: f64,
+    /// Geocentric latitude
+    pub lat: f64,
+    pub altitude_km: f64,
+}
+
+impl Tle {
+    /// Every element set in `text`, in two-line or three-line (named) form.
+    /// Blank lines are skipped; an element set that doesn't parse is an
+    /// error naming it.
+    pub fn parse_all(text: &str) -> Result<Vec<Tle>> {
+        let mut tles = Vec::new();
+        let mut name: Option<&str> = None;
+        let mut lines = text.lines().map(str::trim_end).filter(|l| !l.trim().is_empty());
+        while let Some(line) = lines.next() {
+            if line.starts_with("1 ") {
+                let line2 = lines.next().context("TLE line 1 without a line 2")?;
+                let which = tles_name(name, line);
+                let tle = Self::parse(name.take(), line, line2).with_context(|| format!("bad TLE{which}"))?;
+                tles.push(tle);
+            } else {
+                name = Some(line.trim().trim_start_matches("0 "));
+            }
+        }
+        Ok(tles)
+    }
+
+    fn parse(name: Option<&str>, line1: &str, line2: &str) -> Result<Self> {
+        let elements = Elements::from_tle(name.map(str::to_string), line1.as_bytes(), line2.as_bytes())?;
+        if elements.mean_motion <= 0.0 || !(0.0..1.0).contains(&elements.eccentricity) {
+            bail!("not a closed orbit");
+        }
+        let constants = Constants::from_elements(&elements)?;
+        let catalog = elements.norad_id as u32;
+        Ok(Self {
+            name: elements.object_name.clone().unwrap_or_else(|| format!("#{catalog}")),
+            catalog,
+            epoch: elements.datetime.and_utc().timestamp_micros() as f64 / 1e6,
+            revs_per_day: elements.mean_motion,
+            constants,
+        })
+    }
+
+    /// Seconds per orbit
+    pub fn period(&self) -> f64 {
+        SECS_PER_DAY / self.revs_per_day
+    }
+
+    /// The point beneath the satellite at `unix` seconds, or `None` once
+    /// the elements no longer hold there (the orbit has decayed)
+    pub fn position_at(&self, unix: f64) -> Option<SubPoint> {
+        let prediction = self.constants.propagate(MinutesSinceEpoch((unix - self.epoch) / 60.0)).ok()?;
+        let [x, y, z] = prediction.position;
+        let lon = (y.atan2(x) - sidereal_angle(unix)).to_degrees();
+        Some(SubPoint {
+            lon: crate::geo::wrap_lon(lon),
+            lat: z.atan2(x.hypot(y)).to_degrees(),
+            altitude_km: (x * x + y * y + z * z).sqrt() - EARTH_RADIUS_KM,
+        })
+    }
+}
+
+/// " for NAME", or the catalog number when there's no name, for errors
+fn tles_name(name: Option<&str>, line1: &str) -> String {
+    match name {
+        Some(name) => format!(" for {name}"),
+        None => line1.get(2..7).map_or_else(String::new, |n| format!(" #{}", n.trim())),
+    }
+}
+
+/// Greenwich mean sidereal angle at `unix` seconds, in radians
+fn sidereal_angle(unix: f64) -> f64 {
+    let days_since_j2000 = unix / SECS_PER_DAY - 10_957.5;
+    (280.460_618_37 + 360.985_647_366_29 * days_since_j2000).to_radians().rem_euclid(TAU)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   24001.50000000  .00016717  00000-0  30000-3 0  9990
+2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.50377579431231
+";
+
+    #[test]
+    fn the_iss_circles_every_ninety_minutes_within_its_inclination() {
+        let tles = Tle::parse_all(ISS).unwrap();
+        assert_eq!(tles.len(), 1);
+        let iss = &tles[0];
+        assert_eq!((iss.name.as_str(), iss.catalog), ("ISS (ZARYA)", 25544));
+        // 2024-01-01 12:00 UTC
+        assert_eq!(iss.epoch, 1_704_110_400.0);
+        assert!((iss.period() / 60.0 - 92.88).abs() < 0.05, "{}", iss.period() / 60.0);
+
+        for hour in 0..24 {
+            let p = iss.position_at(iss.epoch + hour as f64 * 3600.0).unwrap();
+            assert!(p.lat.abs() <= 51.7, "{p:?}");
+            assert!((400.0..440.0).contains(&p.altitude_km), "{p:?}");
+        }
+        // One orbit later it's back at the same latitude, further west by
+        // the turn of the Earth beneath it
+        let start = iss.position_at(iss.epoch).unwrap();
+        let next = iss.position_at(iss.epoch + iss.period()).unwrap();
+        assert!((next.lat - start.lat).abs() < 0.5, "{start:?} {next:?}");
+        let shift = crate::geo::wrap_lon(next.lon - start.lon);
+        assert!((-24.5..-22.5).contains(&shift), "{shift}");
+
+        assert!(Tle::parse_all("1 25544U truncated\n2 25544").is_err());
+        let bad_checksum = ISS.replace("431231", "431234");
+        assert!(Tle::parse_all(&bad_checksum).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::hash::hash2;
+use crate::sim::wind::WindField;
+
+/// One advected parcel of a plume. Concentration (`weight`) falls as the
+/// puff spreads so total mass is conserved while the footprint grows.
+#[derive(Clone)]
+pub struct Puff {
+    pub lon: f64,
+    pub lat: f64,
+    pub radius_km: f64,
+    /// Relative concentration in (0, 1]
+    pub weight: f32,
+    /// Stable per-puff seed for render texture (position changes every tick)
+    pub seed: u64,
+}
+
+/// A contaminant plume: a chain of puffs released from a fixed source and
+/// carried downwind, so the footprint streaks instead of sitting as a circle.
+#[derive(Clone)]
+pub struct Plume {
+    pub puffs: Vec<Puff>,
+    source: (f64, f64),
+    release_radius_km: f64,
+    max_radius_km: f64,
+    /// Simulated seconds since the last release
+    since_release: f64,
+}
+
+impl Plume {
+    /// Transport is sped up relative to the wind so a plume visibly travels
+    /// within its short on-screen lifetime (minutes of sim time at 60x).
+    pub const ADVECTION_GAIN: f64 = 30.0;
+    /// Turbulent spreading: each puff's radius closes this fraction of the gap
+    /// to `max_radius_km` per (gained) second — fast at first, then asymptotic
+    const SPREAD_RATE: f64 = 1.5e-4;
+    /// Simulated seconds between puff releases
+    const RELEASE_INTERVAL: f64 = 20.0;
+    const MAX_PUFFS: usize = 16;
+
+    pub fn new(lon: f64, lat: f64, radius_km: f64, max_radius_km: f64) -> Self {
+        let seed = hash2((lon * 1000.0).to_bits(), (lat * 1000.0).to_bits());
+        Self {
+            puffs: vec![Puff { lon, lat, radius_km, weight: 1.0, seed }],
+            source: (lon, lat),
+            release_radius_km: radius_km * 0.6,
+            max_radius_km: max_radius_km.max(radius_km),
+            since_release: 0.0,
+        }
+    }
+
+    /// Advance `sim_dt` simulated seconds: release, advect and spread puffs.
+    pub fn step(&mut self, wind: &WindField, sim_dt: f64) {
+        if sim_dt <= 0.0 {
+            return;
+        }
+        let transport_dt = sim_dt * Self::ADVECTION_GAIN;
+
+        self.since_release += sim_dt;
+        if self.since_release >= Self::RELEASE_INTERVAL && self.puffs.len() < Self::MAX_PUFFS {
+            self.since_release = 0.0;
+            let (lon, lat) = self.source;
+            let seed = hash2(self.puffs[0].seed, self.puffs.len() as u64);
+            self.puffs.push(Puff { lon, lat, radius_km: self.release_radius_km, weight: 1.0, seed });
+        }
+
+        for puff in &mut self.puffs {
+            let (u, v) = wind.sample(puff.lon, puff.lat);
+            let km_per_deg_lon = (111.32 * puff.lat.to_radians().cos()).max(1.0);
+            puff.lon = crate::geo::wrap_lon(puff.lon + u * transport_dt / 1000.0 / km_per_deg_lon);
+            puff.lat = (puff.lat + v * transport_dt / 1000.0 / 110.57).clamp(-85.0, 85.0);
+
+            let old_r = puff.radius_km;
+            let spread = 1.0 - (-Self::SPREAD_RATE * transport_dt).exp();
+            puff.radius_km = old_r + (self.max_radius_km - old_r) * spread;
+            puff.weight *= ((old_r / puff.radius_km).powi(2)) as f32;
+        }
+    }
+
+    /// Sum of puff weights — normalizes per-puff damage so a plume's total
+    /// dose doesn't scale with how many puffs it has released.
+    pub fn total_weight(&self) -> f32 {
+        self.puffs.iter().map(|p| p.weight).sum::<f32>().max(f32::EPSILON)
+    }
+
+    /// Share of the plume's load over a point, 1.0 at the center of a lone
+    /// puff. Each puff's share falls off with the square of the distance to
+    /// nothing at its edge, the same shape its ground dose takes.
+    pub fn concentration_at(&self, lon: f64, lat: f64) -> f64 {
+        let total = self.total_weight() as f64;
+        self.puffs
+            .iter()
+            .map(|p| {
+                let d = crate::geo::great_circle_km(lon, lat, p.lon, p.lat) / p.radius_km.max(1e-6);
+                (1.0 - d * d).max(0.0) * p.weight as f64 / total
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn puffs_drift_downwind_and_dilute() {
+        let wind = WindField::Zonal { season_shift: 0.0 };
+        // Mid-latitude westerlies carry the plume east
+        let mut plume = Plume::new(0.0, 45.0, 20.0, 200.0);
+        for _ in 0..60 {
+            plume.step(&wind, 1.0);
+        }
+        let first = &plume.puffs[0];
+        assert!(first.lon > 0.2, "lon {}", first.lon);
+        assert!(first.radius_km > 20.0 && first.weight < 1.0);
+        // Releases accumulate behind the lead puff
+        assert_eq!(plume.puffs.len(), 4);
+        assert!(plume.puffs[3].lon < first.lon);
+    }
+
+    #[test]
+    fn paused_clock_freezes_plume() {
+        let wind = WindField::Zonal { season_shift: 0.0 };
+        let mut plume = Plume::new(10.0, 10.0, 20.0, 200.0);
+        plume.step(&wind, 0.0);
+        assert_eq!(plume.puffs.len(), 1);
+        assert_eq!((plume.puffs[0].lon, plume.puffs[0].lat), (10.0, 10.0));
+    }
+}
@@ -0,0 +1,81 @@
+//! Refugees: survivors leaving a struck city for the nearest ones still
+//! standing, and for the cities its flight routes reach.
+//!
+//! A flow moves its people a share at a time over `TICKS` steps, so the
+//! struck city empties and the towns taking them in fill up gradually
+//! rather than at once. People leave and arrive on the same step; the
+//! stream drawn along the great circle between them is only for show. A
+//! town takes in no more than `MAX_INFLUX` of its own size; whoever it
+//! can't take stays put.
+
+use crate::geo::great_circle_point;
+
+/// One stream of people from one city to another
+#[derive(Clone, Debug)]
+pub struct Flow {
+    /// City grid indices
+    pub from: usize,
+    pub to: usize,
+    pub from_pos: (f64, f64),
+    pub to_pos: (f64, f64),
+    /// Everyone setting out
+    pub people: u64,
+    /// Those who have arrived so far
+    pub moved: u64,
+    ticks: u32,
+}
+
+impl Flow {
+    /// Share of a struck city's survivors who leave
+    pub const SHARE: f64 = 0.3;
+    /// Cities a struck one's refugees split between
+    pub const DESTINATIONS: usize = 3;
+    /// Radii (degrees) searched in turn for cities to flee to
+    pub const SEARCH_DEG: [f64; 3] = [3.0, 8.0, 20.0];
+    /// Smallest exodus worth a stream
+    pub const MIN_PEOPLE: u64 = 1_000;
+    /// Frames between steps
+    pub const TICK_FRAMES: u64 = 10;
+    /// Steps to move everyone: 10 s at 60fps
+    pub const TICKS: u32 = 60;
+    /// Most a town grows past its original size by taking people in, as a
+    /// share of that size
+    pub const MAX_INFLUX: f64 = 0.5;
+
+    pub fn new(from: usize, from_pos: (f64, f64), to: usize, to_pos: (f64, f64), people: u64) -> Self {
+        Self { from, to, from_pos, to_pos, people, moved: 0, ticks: 0 }
+    }
+
+    /// People setting out this step
+    pub fn tick(&mut self) -> u64 {
+        self.ticks = (self.ticks + 1).min(Self::TICKS);
+        let due = self.people * self.ticks as u64 / Self::TICKS as u64;
+        let leaving = due - self.moved;
+        self.moved = due;
+        leaving
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.ticks >= Self::TICKS
+    }
+
+    /// Point `t` of the way along the route
+    pub fn point_at(&self, t: f64) -> (f64, f64) {
+        great_circle_point(self.from_pos.0, self.from_pos.1, self.to_pos.0, self.to_pos.1, t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_everyone_in_even_shares() {
+        let mut flow = Flow::new(0, (0.0, 0.0), 1, (1.0, 0.0), 1_001);
+        let steps: Vec<u64> = (0..Flow::TICKS).map(|_| flow.tick()).collect();
+        assert!(flow.is_done());
+        assert_eq!(steps.iter().sum::<u64>(), 1_001);
+        assert!(steps.iter().all(|&n| (16..=17).contains(&n)), "{steps:?}");
+        assert_eq!(flow.tick(), 0);
+    }
+}
@@ -0,0 +1,134 @@
+//! Emergency response: each country's hospitals, fire crews and relief
+//! effort, which keep some of the people caught in fires, fallout and gas
+//! alive.
+//!
+//! A country's capacity starts full and every strike on one of its cities
+//! knocks a share off it, so a few strikes in a row break it. It rebuilds
+//! slowly, but never past the share of the country's city population still
+//! alive: the people who staff and are served by it.
+
+use std::collections::HashMap;
+
+/// How far a country's response has been run down
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    Coping,
+    Strained,
+    Overwhelmed,
+    Collapsed,
+}
+
+impl Status {
+    fn of(level: f64) -> Self {
+        match level {
+            l if l < 0.1 => Status::Collapsed,
+            l if l < 0.3 => Status::Overwhelmed,
+            l if l < 0.6 => Status::Strained,
+            _ => Status::Coping,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Status::Coping => "coping",
+            Status::Strained => "strained",
+            Status::Overwhelmed => "overwhelmed",
+            Status::Collapsed => "collapsed",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Capacity {
+    /// 0 (none) to 1 (full strength)
+    level: f64,
+    /// Most the level can rebuild to
+    ceiling: f64,
+    /// Worst status logged since it last recovered
+    logged: Status,
+}
+
+impl Default for Capacity {
+    fn default() -> Self {
+        Self { level: 1.0, ceiling: 1.0, logged: Status::Coping }
+    }
+}
+
+/// Every country's response, by ISO 3166 alpha-3 code. Countries nobody
+/// has touched are at full strength.
+#[derive(Default)]
+pub struct Response {
+    countries: HashMap<String, Capacity>,
+}
+
+impl Response {
+    /// Share of ongoing deaths a full-strength response prevents
+    pub const MAX_RELIEF: f64 = 0.6;
+    /// Capacity lost per strike on a country
+    const STRIKE_LOSS: f64 = 0.25;
+    /// Capacity rebuilt per `rebuild` call
+    const REBUILD: f64 = 0.01;
+
+    /// 0 (none) to 1 (full strength)
+    pub fn level(&self, country: &str) -> f64 {
+        self.countries.get(country).map_or(1.0, |c| c.level)
+    }
+
+    /// Multiplier on ongoing deaths in `country`
+    pub fn mortality(&self, country: &str) -> f64 {
+        1.0 - Self::MAX_RELIEF * self.level(country)
+    }
+
+    /// A strike hit `country`. Returns its new status if that's worse than
+    /// any logged since it last recovered.
+    pub fn strike(&mut self, country: &str) -> Option<Status> {
+        let capacity = self.countries.entry(country.to_string()).or_default();
+        capacity.level = (capacity.level - Self::STRIKE_LOSS).max(0.0);
+        let status = Status::of(capacity.level);
+        (status > capacity.logged).then(|| {
+            capacity.logged = status;
+            status
+        })
+    }
+
+    /// Rebuild a step, each country up to the share of its `(population,
+    /// original population)` still alive
+    pub fn rebuild(&mut self, populations: &HashMap<&str, (u64, u64)>) {
+        for (country, capacity) in &mut self.countries {
+            if let Some(&(population, original)) = populations.get(country.as_str()) {
+                capacity.ceiling = (population as f64 / original.max(1) as f64).min(1.0);
+            }
+            capacity.level = (capacity.level + Self::REBUILD).min(capacity.ceiling);
+            if Status::of(capacity.level) == Status::Coping {
+                capacity.logged = Status::Coping;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_strikes_break_it_and_losses_cap_its_recovery() {
+        let mut response = Response::default();
+        assert_eq!(response.mortality("FRA"), 1.0 - Response::MAX_RELIEF);
+
+        assert_eq!(response.strike("FRA"), None);
+        assert_eq!(response.strike("FRA"), Some(Status::Strained));
+        assert_eq!(response.strike("FRA"), Some(Status::Overwhelmed));
+        assert_eq!(response.strike("FRA"), Some(Status::Collapsed));
+        assert_eq!(response.strike("FRA"), None);
+        assert_eq!(response.mortality("FRA"), 1.0);
+        assert_eq!(response.level("DEU"), 1.0);
+
+        // Back to what's left of the country, and no further
+        let populations = HashMap::from([("FRA", (700, 1_000))]);
+        for _ in 0..200 {
+            response.rebuild(&populations);
+        }
+        assert!((response.level("FRA") - 0.7).abs() < 1e-9);
+        assert_eq!(response.strike("FRA"), Some(Status::Strained));
+    }
+}
@@ -0,0 +1,170 @@
+//! The travel network an outbreak rides between distant cities.
+//!
+//! The biggest cities are linked to the partners a gravity model says they
+//! trade most travellers with: traffic grows with both populations and
+//! falls with the square of the distance. Each route is a great circle
+//! between two city grid indices, with its traffic as a weight relative
+//! to the busiest route.
+//!
+//! Drawn as the flight routes layer, the network is bundled for the view:
+//! hubs that land in the same patch of screen share their routes, so a
+//! zoomed-out map shows a few thick corridors rather than a hairball.
+
+use crate::geo::great_circle_km;
+use std::collections::HashMap;
+
+/// A city as the network sees it: grid index, position and population
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hub {
+    pub idx: usize,
+    pub lon: f64,
+    pub lat: f64,
+    pub population: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Route {
+    pub from: usize,
+    pub to: usize,
+    /// Traffic relative to the busiest route, in (0, 1]
+    pub weight: f64,
+}
+
+/// Routes merged between groups of hubs, end to end in degrees
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bundle {
+    pub from: (f64, f64),
+    pub to: (f64, f64),
+    /// Summed traffic of the routes in the bundle
+    pub weight: f64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TravelNetwork {
+    /// The cities taken in, biggest first
+    pub hubs: Vec<Hub>,
+    pub routes: Vec<Route>,
+}
+
+impl TravelNetwork {
+    /// Cities taken into the network, biggest first
+    pub const HUBS: usize = 80;
+    /// Routes out of each hub to its busiest partners
+    const ROUTES_PER_HUB: usize = 4;
+    /// Distances closer than this count as this, so neighbouring suburbs
+    /// don't swamp the network
+    const MIN_KM: f64 = 300.0;
+
+    pub fn build(hubs: impl IntoIterator<Item = Hub>) -> Self {
+        let mut hubs: Vec<Hub> = hubs.into_iter().filter(|h| h.population > 0).collect();
+        hubs.sort_by_key(|h| std::cmp::Reverse(h.population));
+        hubs.truncate(Self::HUBS);
+
+        let mut routes: Vec<Route> = Vec::new();
+        for (i, a) in hubs.iter().enumerate() {
+            let mut partners: Vec<(f64, usize)> = hubs.iter().enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(j, b)| {
+                    let km = great_circle_km(a.lon, a.lat, b.lon, b.lat).max(Self::MIN_KM);
+                    (a.population as f64 * b.population as f64 / (km * km), j)
+                })
+                .collect();
+            partners.sort_by(|x, y| y.0.total_cmp(&x.0));
+            for &(traffic, j) in partners.iter().take(Self::ROUTES_PER_HUB) {
+                let (from, to) = (a.idx.min(hubs[j].idx), a.idx.max(hubs[j].idx));
+                if !routes.iter().any(|r| r.from == from && r.to == to) {
+                    routes.push(Route { from, to, weight: traffic });
+                }
+            }
+        }
+        let busiest = routes.iter().map(|r| r.weight).fold(0.0, f64::max);
+        for route in &mut routes {
+            route.weight /= busiest;
+        }
+        Self { hubs, routes }
+    }
+
+    /// Cities one route away from `idx`, with the route's weight
+    pub fn neighbors(&self, idx: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        self.routes.iter().filter_map(move |r| match idx {
+            _ if r.from == idx => Some((r.to, r.weight)),
+            _ if r.to == idx => Some((r.from, r.weight)),
+            _ => None,
+        })
+    }
+
+    /// The hub for city `idx`, if the city is in the network
+    pub fn hub(&self, idx: usize) -> Option<&Hub> {
+        self.hubs.iter().find(|h| h.idx == idx)
+    }
+
+    /// Routes merged between hubs that share a `group`, busiest first.
+    /// Each group is drawn from its biggest hub; a hub with no group
+    /// stands alone, and routes inside one group are dropped.
+    pub fn bundled<K: Eq + std::hash::Hash>(&self, group: impl Fn(&Hub) -> Option<K>) -> Vec<Bundle> {
+        // Hubs are biggest first, so a group's first hub leads it
+        let mut leaders: HashMap<K, usize> = HashMap::new();
+        let leader: HashMap<usize, usize> = self.hubs.iter()
+            .map(|hub| (hub.idx, group(hub).map_or(hub.idx, |key| *leaders.entry(key).or_insert(hub.idx))))
+            .collect();
+        let mut merged: HashMap<(usize, usize), f64> = HashMap::new();
+        for route in &self.routes {
+            let (Some(&a), Some(&b)) = (leader.get(&route.from), leader.get(&route.to)) else { continue };
+            if a != b {
+                *merged.entry((a.min(b), a.max(b))).or_default() += route.weight;
+            }
+        }
+        let position = |idx: usize| self.hub(idx).map(|h| (h.lon, h.lat));
+        let mut bundles: Vec<Bundle> = merged.into_iter()
+            .filter_map(|((a, b), weight)| Some(Bundle { from: position(a)?, to: position(b)?, weight }))
+            .collect();
+        bundles.sort_by(|x, y| y.weight.total_cmp(&x.weight));
+        bundles
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn big_nearby_cities_get_the_busiest_routes() {
+        let hubs = [
+            Hub { idx: 0, lon: 139.7, lat: 35.7, population: 37_000_000 },
+            Hub { idx: 1, lon: 135.5, lat: 34.7, population: 19_000_000 },
+            Hub { idx: 2, lon: -74.0, lat: 40.7, population: 18_000_000 },
+            Hub { idx: 3, lon: 2.35, lat: 48.86, population: 11_000_000 },
+            Hub { idx: 4, lon: 0.0, lat: 0.0, population: 0 },
+        ];
+        let network = TravelNetwork::build(hubs);
+        let tokyo_osaka = network.routes.iter().find(|r| (r.from, r.to) == (0, 1)).unwrap();
+        assert_eq!(tokyo_osaka.weight, 1.0);
+        assert!(network.routes.iter().all(|r| r.from < r.to && r.weight > 0.0 && r.weight <= 1.0));
+        assert!(network.neighbors(4).next().is_none());
+        assert!(network.neighbors(2).any(|(to, _)| to == 3));
+    }
+
+    #[test]
+    fn bundling_merges_routes_between_grouped_hubs() {
+        let hubs = [
+            Hub { idx: 0, lon: 139.7, lat: 35.7, population: 37_000_000 },
+            Hub { idx: 1, lon: 135.5, lat: 34.7, population: 19_000_000 },
+            Hub { idx: 2, lon: -74.0, lat: 40.7, population: 18_000_000 },
+            Hub { idx: 3, lon: -87.6, lat: 41.9, population: 9_000_000 },
+        ];
+        let network = TravelNetwork::build(hubs);
+        let unbundled = network.bundled(|_| None::<()>);
+        assert_eq!(unbundled.len(), network.routes.len());
+
+        // Japan and the US each collapse onto their biggest city
+        let bundles = network.bundled(|h| Some(h.lon > 0.0));
+        assert_eq!(bundles.len(), 1);
+        assert_eq!((bundles[0].from, bundles[0].to), ((139.7, 35.7), (-74.0, 40.7)));
+        let across: f64 = network.routes.iter().filter(|r| (r.from < 2) != (r.to < 2)).map(|r| r.weight).sum();
+        assert!((bundles[0].weight - across).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::geo::{normalize_lat, normalize_lon};
+use crate::hash::hash3;
+use crate::sim::wind::WindField;
+
+/// Procedural cloud and rain cover on a fixed lon/lat grid.
+/// Like `FireGrid`, a flat row-major array rebuilt periodically so
+/// per-cell lookups from the fire update and render passes are O(1).
+pub struct WeatherGrid {
+    /// Cloud cover per cell (0 = clear, 255 = overcast)
+    pub cloud: Vec<u8>,
+    /// Precipitation per cell (0 = dry, 255 = downpour)
+    pub rain: Vec<u8>,
+    pub width: usize,
+    pub height: usize,
+    pub resolution: f64,
+}
+
+impl WeatherGrid {
+    /// Size of a weather system in degrees (noise lattice spacing)
+    const FEATURE_DEG: f64 = 12.0;
+    /// Simulated seconds for the noise field to decorrelate
+    const EVOLVE_SECS: f64 = 6.0 * 3600.0;
+    /// Advection restarts on this period (crossfaded) so wind shear
+    /// can't smear systems into stripes over long runs
+    const ADVECT_PERIOD_SECS: f64 = 48.0 * 3600.0;
+
+    pub fn new(resolution: f64) -> Self {
+        let width = (360.0 / resolution) as usize;
+        let height = (180.0 / resolution) as usize;
+        let size = width * height;
+        Self { cloud: vec![0; size], rain: vec![0; size], width, height, resolution }
+    }
+
+    /// Recompute cover for simulated time `unix_secs`, drifting with `wind`.
+    pub fn update(&mut self, wind: &WindField, unix_secs: f64) {
+        for row in 0..self.height {
+            let lat = 90.0 - (row as f64 + 0.5) * self.resolution;
+            for col in 0..self.width {
+                let lon = -180.0 + (col as f64 + 0.5) * self.resolution;
+                let (cloud, rain) = Self::sample(wind, lon, lat, unix_secs);
+                let idx = row * self.width + col;
+                self.cloud[idx] = cloud;
+                self.rain[idx] = rain;
+            }
+        }
+    }
+
+    /// Cloud and rain at a point, from two crossfaded advection phases.
+    fn sample(wind: &WindField, lon: f64, lat: f64, unix_secs: f64) -> (u8, u8) {
+        let (u, v) = wind.sample(lon, lat);
+        let period = Self::ADVECT_PERIOD_SECS;
+        let z = unix_secs / Self::EVOLVE_SECS;
+
+        let mut cover = 0.0;
+        let mut wetness = 0.0;
+        for phase in [0.0, 0.5] {
+            let t = (unix_secs / period + phase).rem_euclid(1.0);
+            // Triangle weight: each phase fades out as its advection resets
+            let weight = 1.0 - (2.0 * t - 1.0).abs();
+            let drift = t * period;
+            let x = (lon - u * drift / 1000.0 / 111.0) / Self::FEATURE_DEG;
+            let y = (lat - v * drift / 1000.0 / 111.0) / Self::FEATURE_DEG;
+            // Separate lattice per phase so the crossfade doesn't show a seam
+            let salt = (phase * 2.0) as u64 * 7919;
+            cover += weight * fbm(x, y, z, salt);
+            wetness += weight * fbm(x * 2.0, y * 2.0, z * 1.5, salt + 104_729);
+        }
+
+        // Threshold into patchy systems with clear gaps between
+        let cloud = smoothstep(0.45, 0.75, cover);
+        let rain = smoothstep(0.5, 0.8, cloud * wetness * 1.6);
+        ((cloud * 255.0) as u8, (rain * 255.0) as u8)
+    }
+
+    #[inline(always)]
+    fn index(&self, lon: f64, lat: f64) -> usize {
+        let col = ((normalize_lon(lon) / self.resolution) as usize).min(self.width - 1);
+        // Row 0 is the north edge — flip the normalized latitude
+        let row = ((180.0 - normalize_lat(lat)) / self.resolution) as usize;
+        row.min(self.height - 1) * self.width + col
+    }
+
+    #[inline(always)]
+    pub fn cloud_at(&self, lon: f64, lat: f64) -> u8 {
+        self.cloud[self.index(lon, lat)]
+    }
+
+    #[inline(always)]
+    pub fn rain_at(&self, lon: f64, lat: f64) -> u8 {
+        self.rain[self.index(lon, lat)]
+    }
+
+    /// No cloud anywhere, as before the first `update`
+    pub fn is_clear(&self) -> bool {
+        self.cloud.iter().all(|&c| c == 0)
+    }
+}
+
+#[inline(always)]
+fn smoothstep(edge0: f64, edge1: f64, x: f64) -> f64 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Lattice value in [0, 1). Longitude cells wrap so the field is seamless at the dateline.
+#[inline(always)]
+fn lattice(ix: i64, iy: i64, iz: i64, salt: u64, wrap: i64) -> f64 {
+    let h = hash3(ix.rem_euclid(wrap) as u64, (iy as u64).wrapping_add(salt), iz as u64);
+    (h >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Smoothly interpolated 3D value noise in [0, 1).
+fn value_noise(x: f64, y: f64, z: f64, salt: u64, wrap: i64) -> f64 {
+    let (x0, y0, z0) = (x.floor(), y.floor(), z.floor());
+    let (fx, fy, fz) = (x - x0, y - y0, z - z0);
+    let (sx, sy, sz) = (fx * fx * (3.0 - 2.0 * fx), fy * fy * (3.0 - 2.0 * fy), fz * fz * (3.0 - 2.0 * fz));
+    let (ix, iy, iz) = (x0 as i64, y0 as i64, z0 as i64);
+
+    let corner = |dx: i64, dy: i64, dz: i64| lattice(ix + dx, iy + dy, iz + dz, salt, wrap);
+    let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+    let plane = |dz: i64| {
+        lerp(
+            lerp(corner(0, 0, dz), corner(1, 0, dz), sx),
+            lerp(corner(0, 1, dz), corner(1, 1, dz), sx),
+            sy,
+        )
+    };
+    lerp(plane(0), plane(1), sz)
+}
+
+/// Two-octave fractal noise, normalized to [0, 1).
+fn fbm(x: f64, y: f64, z: f64, salt: u64) -> f64 {
+    let wrap = (360.0 / WeatherGrid::FEATURE_DEG) as i64;
+    let base = value_noise(x, y, z, salt, wrap);
+    let detail = value_noise(x * 2.0, y * 2.0, z, salt ^ 0x5EED, wrap * 2);
+    (base * 2.0 + detail) / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_produces_patchy_cover() {
+        let wind = WindField::Zonal { season_shift: 0.0 };
+        let mut grid = WeatherGrid::new(2.0);
+        grid.update(&wind, 1_750_000_000.0);
+        let cloudy = grid.cloud.iter().filter(|&&c| c > 128).count();
+        let frac = cloudy as f64 / grid.cloud.len() as f64;
+        assert!(frac > 0.05 && frac < 0.8, "cloudy fraction {frac}");
+        // Rain only falls from cloud
+        for (&c, &r) in grid.cloud.iter().zip(&grid.rain) {
+            assert!(r == 0 || c > 0);
+        }
+    }
+
+    #[test]
+    fn cover_evolves_with_time() {
+        let wind = WindField::Zonal { season_shift: 0.0 };
+        let mut a = WeatherGrid::new(4.0);
+        let mut b = WeatherGrid::new(4.0);
+        a.update(&wind, 1_750_000_000.0);
+        b.update(&wind, 1_750_000_000.0 + 12.0 * 3600.0);
+        assert_ne!(a.cloud, b.cloud);
+    }
+
+    #[test]
+    fn noise_wraps_at_dateline() {
+        let wrap = (360.0 / WeatherGrid::FEATURE_DEG) as i64;
+        let x_west = -180.0 / WeatherGrid::FEATURE_DEG;
+        let x_east = 180.0 / WeatherGrid::FEATURE_DEG;
+        let a = value_noise(x_west, 1.3, 0.7, 0, wrap);
+        let b = value_noise(x_east, 1.3, 0.7, 0, wrap);
+        assert!((a - b).abs() < 1e-12);
+    }
+
+    #[test]
+    fn lookup_matches_grid_cells() {
+        let mut grid = WeatherGrid::new(10.0);
+        let idx = grid.index(-175.0, 85.0);
+        assert_eq!(idx, 0);
+        let idx = grid.index(5.0, -5.0);
+        grid.rain[idx] = 200;
+        assert_eq!(grid.rain_at(5.0, -5.0), 200);
+        assert_eq!(grid.rain_at(5.0, 5.0), 0);
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::{bail, Context, Result};
+use std::f64::consts::PI;
+use std::path::Path;
+
+/// Global surface wind field. Returns (u, v) in m/s — u eastward, v northward.
+pub enum WindField {
+    /// Idealized three-cell circulation (trades, westerlies, polar easterlies)
+    /// with belts shifted toward the summer hemisphere.
+    Zonal {
+        /// Latitude offset of the circulation belts in degrees (+ = north)
+        season_shift: f64,
+    },
+    /// Regular lon/lat grid loaded from a file, bilinearly interpolated.
+    Grid(WindGrid),
+}
+
+impl WindField {
+    /// Zonal pattern for a given time: the ITCZ and belts migrate ~5°
+    /// north in July and south in January.
+    pub fn seasonal(unix_secs: f64) -> Self {
+        let year_frac = (unix_secs / 86_400.0 / 365.2425).rem_euclid(1.0);
+        // Peak northward shift in late July (~0.56 through the year)
+        let season_shift = 5.0 * (2.0 * PI * (year_frac - 0.31)).sin();
+        WindField::Zonal { season_shift }
+    }
+
+    /// Re-derive the seasonal shift; no-op for file-driven grids.
+    pub fn update_season(&mut self, unix_secs: f64) {
+        if let WindField::Zonal { .. } = self {
+            *self = Self::seasonal(unix_secs);
+        }
+    }
+
+    pub fn sample(&self, lon: f64, lat: f64) -> (f64, f64) {
+        match self {
+            WindField::Zonal { season_shift } => zonal_wind(lat - season_shift),
+            WindField::Grid(grid) => grid.sample(lon, lat),
+        }
+    }
+}
+
+/// Idealized zonal-mean surface wind at latitude `lat` (degrees).
+fn zonal_wind(lat: f64) -> (f64, f64) {
+    let phi = lat.abs().min(90.0);
+    // Meridional component points toward the equator in the trade and polar
+    // cells and poleward in the Ferrel cell
+    let toward_pole = lat.signum();
+    let (u, v) = if phi < 30.0 {
+        let s = (PI * phi / 30.0).sin();
+        (-7.0 * s, -2.0 * toward_pole * s)
+    } else if phi < 60.0 {
+        let s = (PI * (phi - 30.0) / 30.0).sin();
+        (10.0 * s, toward_pole * s)
+    } else {
+        let s = (PI * (phi - 60.0) / 30.0).sin();
+        (-4.0 * s, -toward_pole * s)
+    };
+    (u, v)
+}
+
+/// Wind vectors on a regular grid spanning the whole globe.
+///
+/// File format (whitespace-separated text):
+/// ```text
+/// <cols> <rows>
+/// u,v u,v u,v ...   # row 0 = 90°N, columns from 180°W eastward
+/// ...
+/// ```
+pub struct WindGrid {
+    cols: usize,
+    rows: usize,
+    data: Vec<(f64, f64)>,
+}
+
+impl WindGrid {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("reading wind grid {}", path.display()))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut tokens = content
+            .lines()
+            .map(|l| l.split('#').next().unwrap_or(""))
+            .flat_map(str::split_whitespace);
+
+        let mut dim = |name: &str| -> Result<usize> {
+            let t = tokens.next().with_context(|| format!("missing {name}"))?;
+            t.parse().with_context(|| format!("invalid {name}: {t}"))
+        };
+        let cols = dim("column count")?;
+        let rows = dim("row count")?;
+        if cols < 2 || rows < 2 {
+            bail!("wind grid must be at least 2x2, got {cols}x{rows}");
+        }
+
+        let mut data = Vec::with_capacity(cols * rows);
+        for t in tokens {
+            let (u, v) = t.split_once(',').with_context(|| format!("expected u,v pair, got {t}"))?;
+            data.push((u.parse()?, v.parse()?));
+        }
+        if data.len() != cols * rows {
+            bail!("wind grid expects {} vectors, found {}", cols * rows, data.len());
+        }
+        Ok(Self { cols, rows, data })
+    }
+
+    fn at(&self, col: usize, row: usize) -> (f64, f64) {
+        self.data[row * self.cols + col]
+    }
+
+    pub fn sample(&self, lon: f64, lat: f64) -> (f64, f64) {
+        // Columns wrap around the globe; rows clamp at the poles
+        let x = crate::geo::normalize_lon(lon) / 360.0 * self.cols as f64;
+        let y = ((90.0 - lat) / 180.0 * (self.rows - 1) as f64).clamp(0.0, (self.rows - 1) as f64);
+
+        let (x0, y0) = (x.floor() as usize % self.cols, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1) % self.cols, (y0 + 1).min(self.rows - 1));
+        let (fx, fy) = (x - x.floor(), y - y.floor());
+
+        let lerp = |a: (f64, f64), b: (f64, f64), t: f64| (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+        let top = lerp(self.at(x0, y0), self.at(x1, y0), fx);
+        let bottom = lerp(self.at(x0, y1), self.at(x1, y1), fx);
+        lerp(top, bottom, fy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zonal_belts_have_expected_direction() {
+        let field = WindField::Zonal { season_shift: 0.0 };
+        // Trade winds blow from the east, toward the equator
+        let (u, v) = field.sample(0.0, 15.0);
+        assert!(u < -5.0 && v < 0.0);
+        let (_, v) = field.sample(0.0, -15.0);
+        assert!(v > 0.0);
+        // Mid-latitude westerlies
+        assert!(field.sample(0.0, 45.0).0 > 5.0);
+        assert!(field.sample(0.0, -45.0).0 > 5.0);
+        // Polar easterlies
+        assert!(field.sample(0.0, 75.0).0 < 0.0);
+    }
+
+    #[test]
+    fn season_shifts_belts_north_in_july() {
+        // 2025-07-25 vs 2025-01-25
+        let july = WindField::seasonal(1_753_401_600.0);
+        let jan = WindField::seasonal(1_737_763_200.0);
+        let (WindField::Zonal { season_shift: s_jul }, WindField::Zonal { season_shift: s_jan }) = (july, jan) else {
+            unreachable!()
+        };
+        assert!(s_jul > 4.0 && s_jan < -4.0, "jul {s_jul} jan {s_jan}");
+    }
+
+    #[test]
+    fn grid_parses_and_interpolates() {
+        let grid = WindGrid::parse("2 2\n# north\n0,0 10,0\n0,0 10,10\n").unwrap();
+        // Halfway between columns 0 and 1 on the north row
+        let (u, v) = grid.sample(-90.0, 90.0);
+        assert!((u - 5.0).abs() < 1e-9 && v.abs() < 1e-9);
+        // Wraps: 180°E sits on column 0 again
+        assert_eq!(grid.sample(180.0, 90.0), (0.0, 0.0));
+        assert!(WindGrid::parse("2 2\n0,0 1,1\n").is_err());
+        assert!(WindGrid::parse("2 2\n0,0 1,1 2 3,3\n").is_err());
+    }
+}
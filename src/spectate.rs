@@ -0,0 +1,373 @@
+//! Spectator mode: one instance broadcasts its world, others watch it.
+//!
+//! The host sends every watcher the same stream of diffs over TCP: fire
+//! cells that lit, went out or changed heat, cities whose population
+//! changed, and blasts set off since the last one. A watcher runs none of
+//! the simulation itself and can't launch anything, but it has its own
+//! camera, projection and layers. Someone joining late is sent the whole
+//! state once, on its own, before the diffs everyone gets.
+//!
+//! Framing is the two-player mode's (`net`): a length-prefixed payload,
+//! here a MessagePack array of the diff's fields. City indexes are
+//! positions in the city grid, so watchers need the same map data as the
+//! host.
+
+use crate::app::{App, WeaponType};
+use crate::net;
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Frames between diffs
+pub const SYNC_INTERVAL: u64 = 10;
+
+/// A fire cell is resent once its heat drifts this far from what watchers
+/// last got. Fires cool a step every few frames, and sending each step
+/// would resend every fire on the map.
+const FIRE_TOLERANCE: u8 = 16;
+
+/// Diffs queued for a watcher that isn't reading them before it's dropped
+const WATCHER_BACKLOG: usize = 64;
+
+/// Longest a write to a watcher may block before it's dropped
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What changed on the host since the last diff
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diff {
+    pub frame: u64,
+    pub sim_time: f64,
+    pub casualties: u64,
+    /// (city index, population)
+    pub cities: Vec<(u32, u64)>,
+    /// (fire cell, intensity, weapon); intensity 0 puts a cell out
+    pub fires: Vec<(u32, u8, WeaponType)>,
+    /// (lon, lat, weapon, radius_km) of new blasts
+    pub explosions: Vec<(f64, f64, WeaponType, f64)>,
+}
+
+impl Diff {
+    pub fn encode(&self) -> Vec<u8> {
+        use rmp::encode::{write_array_len, write_f64, write_uint};
+        let mut out = Vec::with_capacity(32 + self.cities.len() * 10 + self.fires.len() * 8 + self.explosions.len() * 30);
+        // Writing to a Vec can't fail
+        let _ = write_array_len(&mut out, 6);
+        let _ = write_uint(&mut out, self.frame);
+        let _ = write_f64(&mut out, self.sim_time);
+        let _ = write_uint(&mut out, self.casualties);
+        let _ = write_array_len(&mut out, self.cities.len() as u32);
+        for &(idx, population) in &self.cities {
+            let _ = write_array_len(&mut out, 2);
+            let _ = write_uint(&mut out, idx as u64);
+            let _ = write_uint(&mut out, population);
+        }
+        let _ = write_array_len(&mut out, self.fires.len() as u32);
+        for &(cell, intensity, weapon) in &self.fires {
+            let _ = write_array_len(&mut out, 3);
+            let _ = write_uint(&mut out, cell as u64);
+            let _ = write_uint(&mut out, intensity as u64);
+            let _ = write_uint(&mut out, net::weapon_code(weapon) as u64);
+        }
+        let _ = write_array_len(&mut out, self.explosions.len() as u32);
+        for &(lon, lat, weapon, radius_km) in &self.explosions {
+            let _ = write_array_len(&mut out, 4);
+            let _ = write_f64(&mut out, lon);
+            let _ = write_f64(&mut out, lat);
+            let _ = write_uint(&mut out, net::weapon_code(weapon) as u64);
+            let _ = write_f64(&mut out, radius_km);
+        }
+        out
+    }
+
+    pub fn decode(payload: &[u8]) -> Result<Self> {
+        use rmp::decode::{read_array_len, read_f64, read_int};
+        let mut r = payload;
+        // Arrays must be the expected length, and entry counts can't claim
+        // more than the bytes left
+        let array = |r: &mut &[u8], len: u32| -> Result<()> {
+            let n = read_array_len(r)?;
+            if n != len {
+                bail!("{n} fields, expected {len}");
+            }
+            Ok(())
+        };
+        let count = |r: &mut &[u8], size: usize| -> Result<usize> {
+            let n = read_array_len(r)? as usize;
+            if n > r.len() / size {
+                bail!("{n} entries exceed payload");
+            }
+            Ok(n)
+        };
+        array(&mut r, 6)?;
+        let (frame, sim_time, casualties) = (read_int(&mut r)?, read_f64(&mut r)?, read_int(&mut r)?);
+        let n = count(&mut r, 3)?;
+        let cities = (0..n)
+            .map(|_| {
+                array(&mut r, 2)?;
+                Ok((read_int(&mut r)?, read_int(&mut r)?))
+            })
+            .collect::<Result<_>>()?;
+        let n = count(&mut r, 4)?;
+        let fires = (0..n)
+            .map(|_| {
+                array(&mut r, 3)?;
+                Ok((read_int(&mut r)?, read_int(&mut r)?, net::weapon_from(read_int(&mut r)?)?))
+            })
+            .collect::<Result<_>>()?;
+        let n = count(&mut r, 29)?;
+        let explosions = (0..n)
+            .map(|_| {
+                array(&mut r, 4)?;
+                Ok((read_f64(&mut r)?, read_f64(&mut r)?, net::weapon_from(read_int(&mut r)?)?, read_f64(&mut r)?))
+            })
+            .collect::<Result<_>>()?;
+        if !r.is_empty() {
+            bail!("{} trailing bytes", r.len());
+        }
+        Ok(Self { frame, sim_time, casualties, cities, fires, explosions })
+    }
+}
+
+/// Where a watcher's writer thread takes its diffs from
+type Watcher = SyncSender<Arc<Vec<u8>>>;
+
+/// Broadcasts diffs to everyone watching. Watchers are written to on their
+/// own threads, so a slow one never holds up a frame; one that falls too
+/// far behind, or whose writes block for `WRITE_TIMEOUT`, is dropped.
+pub struct SpectatorHost {
+    watchers: Vec<Watcher>,
+    /// Connected since the last diff, and not sent anything yet
+    joined: Arc<Mutex<Vec<Watcher>>>,
+    port: u16,
+    /// Populations as watchers last got them
+    cities: Vec<u64>,
+    /// Burning cells as watchers last got them
+    fires: HashMap<u32, (u8, WeaponType)>,
+}
+
+impl SpectatorHost {
+    /// Listen on `port` at `addr`; 0 picks a free port
+    pub fn start(addr: IpAddr, port: u16) -> Result<Self> {
+        let listener =
+            TcpListener::bind((addr, port)).with_context(|| format!("listening for spectators on {addr}:{port}"))?;
+        let port = listener.local_addr()?.port();
+        let joined = Arc::new(Mutex::new(Vec::new()));
+        let shared = Arc::clone(&joined);
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let _ = stream.set_nodelay(true);
+                if stream.set_write_timeout(Some(WRITE_TIMEOUT)).is_err() {
+                    continue;
+                }
+                let (tx, rx) = mpsc::sync_channel::<Arc<Vec<u8>>>(WATCHER_BACKLOG);
+                thread::spawn(move || {
+                    for payload in rx {
+                        if net::write_frame(&mut stream, &payload).is_err() {
+                            break;
+                        }
+                    }
+                });
+                if let Ok(mut joined) = shared.lock() {
+                    joined.push(tx);
+                }
+            }
+        });
+        Ok(Self { watchers: Vec::new(), joined, port, cities: Vec::new(), fires: HashMap::new() })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// People watching, counting any who have only just joined
+    pub fn watchers(&self) -> usize {
+        self.watchers.len() + self.joined.lock().map_or(0, |j| j.len())
+    }
+
+    /// Send everyone what changed since the last call, and anyone who has
+    /// joined since then the whole world
+    pub fn broadcast(&mut self, app: &App) {
+        let joined = self.joined.lock().map(|mut j| std::mem::take(&mut *j)).unwrap_or_default();
+        if self.watchers.is_empty() && joined.is_empty() {
+            return;
+        }
+        let diff = self.diff(app);
+        if !self.watchers.is_empty() {
+            let payload = Arc::new(diff.encode());
+            self.watchers.retain(|tx| tx.try_send(Arc::clone(&payload)).is_ok());
+        }
+        if !joined.is_empty() {
+            let payload = Arc::new(self.everything(diff).encode());
+            self.watchers.extend(joined.into_iter().filter(|tx| tx.try_send(Arc::clone(&payload)).is_ok()));
+        }
+    }
+
+    /// What changed since the last diff
+    fn diff(&mut self, app: &App) -> Diff {
+        let cities = net::changed_populations(&mut self.cities, app.map_renderer.city_grid.iter().map(|c| c.population));
+
+        let mut fires = Vec::new();
+        let mut burning = HashMap::with_capacity(self.fires.len());
+        for (cell, intensity, weapon) in app.fires.cell_states() {
+            let sent = match self.fires.remove(&cell) {
+                Some((heat, was)) if was == weapon && heat.abs_diff(intensity) < FIRE_TOLERANCE => (heat, was),
+                _ => {
+                    fires.push((cell, intensity, weapon));
+                    (intensity, weapon)
+                }
+            };
+            burning.insert(cell, sent);
+        }
+        // Whatever is left has gone out
+        fires.extend(self.fires.drain().map(|(cell, _)| (cell, 0, WeaponType::Nuke)));
+        self.fires = burning;
+
+        // Blasts age a frame a step, so the young ones are new since the
+        // last diff
+        let explosions = app
+            .explosions
+            .iter()
+            .filter(|e| (e.frame as u64) < SYNC_INTERVAL)
+            .map(|e| (e.lon, e.lat, e.weapon_type, e.radius_km))
+            .collect();
+        Diff { frame: app.frame, sim_time: app.clock.unix_secs(), casualties: app.casualties, cities, fires, explosions }
+    }
+
+    /// The world as the other watchers have it once `diff` is applied, for
+    /// someone starting from nothing
+    fn everything(&self, diff: Diff) -> Diff {
+        Diff {
+            cities: self.cities.iter().enumerate().map(|(idx, &population)| (idx as u32, population)).collect(),
+            fires: self.fires.iter().map(|(&cell, &(heat, weapon))| (cell, heat, weapon)).collect(),
+            ..diff
+        }
+    }
+}
+
+/// A read-only view of someone else's world. Diffs are read on a
+/// background thread.
+pub struct Spectator {
+    incoming: Receiver<Result<Diff>>,
+    pub host: String,
+}
+
+impl Spectator {
+    /// Connect to a host broadcasting with `--broadcast` (blocking)
+    pub fn connect(addr: &str) -> Result<Self> {
+        let stream = TcpStream::connect(addr).with_context(|| format!("connecting to {addr}"))?;
+        stream.set_nodelay(true)?;
+        Ok(Self::read_from(stream, addr))
+    }
+
+    fn read_from(mut stream: TcpStream, addr: &str) -> Self {
+        let (tx, incoming) = mpsc::channel();
+        thread::spawn(move || loop {
+            let diff = net::read_frame(&mut stream).and_then(|payload| Diff::decode(&payload));
+            let failed = diff.is_err();
+            if tx.send(diff).is_err() || failed {
+                break;
+            }
+        });
+        Self { incoming, host: addr.to_string() }
+    }
+
+    /// Next diff received, if any. A dropped connection shows up as an error.
+    pub fn poll(&mut self) -> Option<Result<Diff>> {
+        self.incoming.try_recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_round_trip_and_reject_bad_counts() {
+        let diff = Diff {
+            frame: 600,
+            sim_time: 1_750_507_200.0,
+            casualties: 1_234_567,
+            cities: vec![(0, 10), (7000, 0)],
+            fires: vec![(12_345_678, 200, WeaponType::Nuke), (9, 0, WeaponType::Chem)],
+            explosions: vec![(2.35, 48.85, WeaponType::Emp, 150.0)],
+        };
+        assert_eq!(Diff::decode(&diff.encode()).unwrap(), diff);
+        assert_eq!(Diff::decode(&Diff::default().encode()).unwrap(), Diff::default());
+
+        // Header, then a city count far past the bytes that follow
+        let mut bogus = Diff::default().encode()[..11].to_vec();
+        rmp::encode::write_array_len(&mut bogus, 1_000_000_000).unwrap();
+        assert!(Diff::decode(&bogus).is_err());
+        assert!(Diff::decode(&diff.encode()[..50]).is_err());
+        let mut trailing = diff.encode();
+        trailing.push(0);
+        assert!(Diff::decode(&trailing).is_err());
+    }
+
+    /// Wait up to five seconds for `done`
+    fn within_deadline(what: &str, mut done: impl FnMut() -> bool) {
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while !done() {
+            assert!(std::time::Instant::now() < deadline, "{what} within 5s");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn watchers_mirror_the_hosts_world() {
+        let localhost = IpAddr::from([127, 0, 0, 1]);
+        let mut host = SpectatorHost::start(localhost, 0).unwrap();
+        let addr = format!("127.0.0.1:{}", host.port());
+        let mut spectator = Spectator::connect(&addr).unwrap();
+        within_deadline("a watcher joining", || host.watchers() == 1);
+
+        let mut world = App::new(80, 24);
+        world.fires.ignite(2.35, 48.85, 200, WeaponType::Nuke);
+        world.fires.ignite(139.7, 35.7, 150, WeaponType::Chem);
+        world.casualties = 42;
+        let mut watcher = App::new(80, 24);
+        let next = |spectator: &mut Spectator| {
+            let mut diff = None;
+            within_deadline("a diff", || {
+                diff = spectator.poll();
+                diff.is_some()
+            });
+            diff.unwrap().unwrap()
+        };
+
+        // Everything on joining
+        host.broadcast(&world);
+        let diff = next(&mut spectator);
+        assert_eq!(diff.fires.len(), 2);
+        assert_eq!(diff.cities.len(), world.map_renderer.city_grid.len());
+        watcher.apply_diff(diff);
+        assert_eq!(watcher.fires.cell_states().collect::<Vec<_>>(), world.fires.cell_states().collect::<Vec<_>>());
+        assert_eq!(watcher.casualties, 42);
+
+        // Then only what changed enough, and what went out
+        let (cell, _, weapon) = world.fires.cell_states().find(|f| f.2 == WeaponType::Chem).unwrap();
+        world.fires.set_cell(cell, 145, weapon);
+        host.broadcast(&world);
+        assert!(next(&mut spectator).fires.is_empty());
+
+        // A late joiner gets the world on its own; the first watcher only
+        // what changed
+        let mut late = Spectator::connect(&addr).unwrap();
+        within_deadline("a second watcher joining", || host.watchers() == 2);
+        world.fires.set_cell(cell, 0, weapon);
+        host.broadcast(&world);
+        let diff = next(&mut spectator);
+        assert_eq!(diff.fires, vec![(cell, 0, WeaponType::Nuke)]);
+        assert!(diff.cities.is_empty());
+        watcher.apply_diff(diff);
+        assert_eq!(watcher.fires.len(), 1);
+        assert_eq!(watcher.fires.fine.burning, 1);
+        let caught_up = next(&mut late);
+        assert_eq!(caught_up.fires.len(), 1);
+        assert_eq!(caught_up.cities.len(), world.map_renderer.city_grid.len());
+    }
+}
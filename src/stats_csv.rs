@@ -0,0 +1,118 @@
+//! `--stats-out`: the run as a CSV time series, for analysis afterwards.
+//!
+//! One row per second of simulation (every 60 steps, so pausing and slow
+//! motion stretch it like everything else): the totals the dashboard charts,
+//! plus burned ground and each faction's population. Rows are appended, so
+//! several runs can share a file; the header is only written to an empty
+//! one. Each row starts with its run's id (the Unix time in milliseconds
+//! the run opened the file) and seed, so runs can be told apart.
+
+use crate::app::App;
+use crate::faction::Faction;
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// Steps between rows
+pub const INTERVAL: u64 = 60;
+
+const HEADER: &str = "run,seed,frame,seconds,sim_time,casualties,births,fires,burning_cells,burned_km2,fallout_km2,population_west,population_east,population_non_aligned";
+
+pub struct StatsCsv<W: Write> {
+    out: W,
+    run: u64,
+}
+
+impl StatsCsv<File> {
+    /// Append to `path`, creating it if need be
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path).with_context(|| format!("opening {}", path.display()))?;
+        let empty = file.metadata()?.len() == 0;
+        let run = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis() as u64);
+        Self::new(file, empty, run)
+    }
+}
+
+impl<W: Write> StatsCsv<W> {
+    pub fn new(mut out: W, header: bool, run: u64) -> Result<Self> {
+        if header {
+            writeln!(out, "{HEADER}")?;
+        }
+        Ok(Self { out, run })
+    }
+
+    /// Write a row for the world as it is now
+    pub fn record(&mut self, app: &App) -> Result<()> {
+        let population = |faction: Faction| app.faction_stats[faction.index()].population;
+        // An empty float sum is -0.0, which would print as "-0"
+        let km2 = |area: f64| area.abs();
+        // One write per row, so a reader never sees half of one
+        let row = format!(
+            "{},{},{},{:.0},{:.0},{},{},{},{},{:.0},{:.0},{},{},{}\n",
+            self.run,
+            app.seed.map(|seed| seed.to_string()).unwrap_or_default(),
+            app.frame,
+            app.frame as f64 / INTERVAL as f64,
+            app.clock.unix_secs(),
+            app.casualties,
+            app.stats.births,
+            app.fires.len(),
+            app.fires.fine.burning,
+            km2(app.burn_scars.area_km2()),
+            km2(app.radiation.area_km2()),
+            population(Faction::West),
+            population(Faction::East),
+            population(Faction::NonAligned),
+        );
+        self.out.write_all(row.as_bytes())?;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::WeaponType;
+
+    #[test]
+    fn writes_a_header_then_a_row_per_record() {
+        let mut app = App::new(80, 24);
+        app.map_renderer.add_city(2.35, 48.85, "Paris", 2_000_000, true, false, "FRA");
+        app.refresh_faction_stats();
+        app.casualties = 1_500;
+        app.fires.ignite(2.35, 48.85, 200, WeaponType::Nuke);
+        app.burn_scars.record(&app.fires);
+
+        app.seed = Some(7);
+        let mut csv = StatsCsv::new(Vec::new(), true, 1_700_000_000_000).unwrap();
+        csv.record(&app).unwrap();
+        app.frame = 60;
+        csv.record(&app).unwrap();
+        let text = String::from_utf8(csv.into_inner()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], HEADER);
+        let row: Vec<&str> = lines[2].split(',').collect();
+        assert_eq!(row.len(), HEADER.split(',').count());
+        assert_eq!((row[0], row[1]), ("1700000000000", "7"));
+        assert_eq!((row[2], row[3], row[5], row[7], row[8]), ("60", "1", "1500", "1", "1"));
+        // A 0.25° cell at Paris's latitude
+        let burned: f64 = row[9].parse().unwrap();
+        assert!((400.0..600.0).contains(&burned), "{burned}");
+        assert!(row[11..].contains(&"2000000"), "{row:?}");
+
+        // Unseeded runs leave the seed blank
+        app.seed = None;
+        let mut csv = StatsCsv::new(Vec::new(), false, 1).unwrap();
+        csv.record(&app).unwrap();
+        assert!(String::from_utf8(csv.into_inner()).unwrap().starts_with("1,,60,"));
+    }
+}
@@ -0,0 +1,202 @@
+//! Strike patterns: how a click picks targets.
+//!
+//! A point reticle strikes where it's aimed. Corridor and area reticles
+//! take two points — two clicks, or a right-button drag — and lay a
+//! pattern between them: detonations spaced along the great circle from
+//! one to the other, or a staggered grid filling the box they span. Blasts
+//! are spaced to overlap a little, and a pattern too big for its warheads
+//! spreads them out instead of growing without bound.
+//!
+//! A MIRV bus splits late in its flight into several smaller warheads that
+//! spread out around its aim point.
+
+use crate::geo::{destination_point, great_circle_km, great_circle_point, wrap_lon};
+
+/// How clicks pick strike targets
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reticle {
+    Point,
+    /// Detonations along the line between two points
+    Corridor,
+    /// A bombing pattern filling the box between two corners
+    Area,
+}
+
+/// Most detonations one pattern strike sends
+pub const MAX_PATTERN_STRIKES: usize = 24;
+
+/// Blast spacing in blast radii: neighbors overlap without piling up
+const SPACING_RADII: f64 = 1.5;
+
+impl Reticle {
+    pub const ALL: [Reticle; 3] = [Self::Point, Self::Corridor, Self::Area];
+
+    pub fn next(self) -> Self {
+        match self {
+            Self::Point => Self::Corridor,
+            Self::Corridor => Self::Area,
+            Self::Area => Self::Point,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Point => "point",
+            Self::Corridor => "corridor",
+            Self::Area => "area",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| r.label().eq_ignore_ascii_case(s))
+    }
+
+    /// Where the blasts of a pattern from `from` to `to` land, for blasts
+    /// of `radius_km`
+    pub fn targets(self, from: (f64, f64), to: (f64, f64), radius_km: f64) -> Vec<(f64, f64)> {
+        let spacing = (radius_km * SPACING_RADII).max(1.0);
+        match self {
+            Self::Point => vec![to],
+            Self::Corridor => corridor(from, to, spacing),
+            Self::Area => area(from, to, spacing),
+        }
+    }
+}
+
+/// Evenly spaced points from `from` to `to` along the great circle, both
+/// ends included
+fn corridor(from: (f64, f64), to: (f64, f64), spacing_km: f64) -> Vec<(f64, f64)> {
+    let km = great_circle_km(from.0, from.1, to.0, to.1);
+    let n = ((km / spacing_km).round() as usize + 1).min(MAX_PATTERN_STRIKES);
+    if n < 2 {
+        return vec![from];
+    }
+    (0..n)
+        .map(|i| great_circle_point(from.0, from.1, to.0, to.1, i as f64 / (n - 1) as f64))
+        .collect()
+}
+
+/// Staggered rows filling the box between two corners, taking the short
+/// way around in longitude
+fn area(from: (f64, f64), to: (f64, f64), spacing_km: f64) -> Vec<(f64, f64)> {
+    let west = if wrap_lon(to.0 - from.0) >= 0.0 { from.0 } else { to.0 };
+    let width_deg = wrap_lon(to.0 - from.0).abs();
+    let (south, north) = (from.1.min(to.1), from.1.max(to.1));
+    let mid_lat = (south + north) / 2.0;
+    let width_km = width_deg * 111.0 * mid_lat.to_radians().cos().max(0.05);
+    let height_km = (north - south) * 111.0;
+
+    let mut spacing = spacing_km;
+    loop {
+        // Rows of a triangular lattice sit closer than the blasts in a row
+        let rows = ((height_km / (spacing * 0.866)).round() as usize).max(1);
+        let cols = ((width_km / spacing).round() as usize).max(1);
+        let count = rows * cols - if cols > 1 { rows / 2 } else { 0 };
+        if count <= MAX_PATTERN_STRIKES {
+            let mut targets = Vec::with_capacity(count);
+            for row in 0..rows {
+                let lat = south + (north - south) * (row as f64 + 0.5) / rows as f64;
+                // Odd rows shift half a step and lose their last blast
+                let (offset, n) = if row % 2 == 1 && cols > 1 { (1.0, cols - 1) } else { (0.5, cols) };
+                for col in 0..n {
+                    let lon = west + width_deg * (col as f64 + offset) / cols as f64;
+                    targets.push((wrap_lon(lon), lat));
+                }
+            }
+            return targets;
+        }
+        spacing *= (count as f64 / MAX_PATTERN_STRIKES as f64).sqrt().max(1.05);
+    }
+}
+
+/// Multiple warheads on one missile
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mirv {
+    pub warheads: u8,
+    /// Radius around the aim point the warheads spread over
+    pub spread_km: f64,
+}
+
+impl Default for Mirv {
+    fn default() -> Self {
+        Self { warheads: 6, spread_km: 80.0 }
+    }
+}
+
+impl Mirv {
+    pub const MIN_WARHEADS: u8 = 2;
+    pub const MAX_WARHEADS: u8 = 16;
+
+    /// Blast radius of each warhead when a launch's yield is split among
+    /// them (radius goes with the cube root of yield)
+    pub fn warhead_radius_km(&self, radius_km: f64) -> f64 {
+        radius_km / (self.warheads as f64).cbrt()
+    }
+
+    /// Where each warhead lands: a sunflower spiral that fills the spread
+    /// disc evenly, without clumping at the center
+    pub fn footprint(&self, aim: (f64, f64)) -> Vec<(f64, f64)> {
+        const GOLDEN_ANGLE_DEG: f64 = 137.507_764;
+        let n = self.warheads as f64;
+        (0..self.warheads)
+            .map(|i| {
+                let km = self.spread_km * ((i as f64 + 0.5) / n).sqrt();
+                destination_point(aim.0, aim.1, i as f64 * GOLDEN_ANGLE_DEG, km)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corridors_space_blasts_along_the_line() {
+        // 10° of longitude on the equator is ~1112 km: 100 km blasts every 150 km
+        let line = Reticle::Corridor.targets((0.0, 0.0), (10.0, 0.0), 100.0);
+        assert_eq!(line.len(), 8);
+        assert_eq!(line[0], (0.0, 0.0));
+        assert!((line[7].0 - 10.0).abs() < 1e-9);
+        assert!(line.iter().all(|&(_, lat)| lat.abs() < 1e-9));
+
+        // Too long a line spreads the warheads it has; a click in place is one
+        assert_eq!(Reticle::Corridor.targets((0.0, 0.0), (90.0, 0.0), 10.0).len(), MAX_PATTERN_STRIKES);
+        assert_eq!(Reticle::Corridor.targets((5.0, 5.0), (5.0, 5.0), 50.0), vec![(5.0, 5.0)]);
+    }
+
+    #[test]
+    fn areas_fill_their_box_across_the_antimeridian() {
+        let inside = |targets: &[(f64, f64)], west: f64, east: f64, south: f64, north: f64| {
+            targets.iter().all(|&(lon, lat)| {
+                let lon = if west > east && lon < 0.0 { lon + 360.0 } else { lon };
+                let east = if west > east { east + 360.0 } else { east };
+                lon > west && lon < east && lat > south && lat < north
+            })
+        };
+        let targets = Reticle::Area.targets((10.0, 40.0), (14.0, 44.0), 50.0);
+        assert!(targets.len() > 4 && targets.len() <= MAX_PATTERN_STRIKES, "{}", targets.len());
+        assert!(inside(&targets, 10.0, 14.0, 40.0, 44.0));
+
+        // Dragged west over the antimeridian: the short way, not around the world
+        let targets = Reticle::Area.targets((-178.0, -20.0), (178.0, -16.0), 60.0);
+        assert!(inside(&targets, 178.0, -178.0, -20.0, -16.0), "{targets:?}");
+
+        // Huge boxes still get at most a salvo's worth
+        assert!(Reticle::Area.targets((-60.0, -30.0), (60.0, 30.0), 20.0).len() <= MAX_PATTERN_STRIKES);
+    }
+
+    #[test]
+    fn mirv_warheads_spread_within_their_radius() {
+        let mirv = Mirv { warheads: 8, spread_km: 100.0 };
+        let footprint = mirv.footprint((179.9, 50.0));
+        assert_eq!(footprint.len(), 8);
+        let dists: Vec<f64> = footprint.iter().map(|&(lon, lat)| great_circle_km(179.9, 50.0, lon, lat)).collect();
+        assert!(dists.iter().all(|&d| d > 10.0 && d <= 100.0 + 1e-6), "{dists:?}");
+        // Both sides of the antimeridian, all in range
+        assert!(footprint.iter().any(|&(lon, _)| lon < 0.0) && footprint.iter().any(|&(lon, _)| lon > 0.0));
+
+        // Eight warheads of half the radius carry the yield of one
+        assert!((mirv.warhead_radius_km(100.0) - 50.0).abs() < 1e-9);
+    }
+}
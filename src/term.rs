@@ -0,0 +1,121 @@
+//! The terminal around the map. Its window title follows the casualty
+//! count and zoom, and the bell or a desktop notification (OSC 9) marks
+//! each city wiped out and each retaliation launched, so a map left in a
+//! background tab still gets noticed.
+
+use crate::app::App;
+use crate::ui::format_casualties;
+use std::io::{self, Write};
+
+pub struct Integration {
+    /// Keep the window title up to date
+    title: bool,
+    /// Ring the bell on alerts
+    bell: bool,
+    /// Send alerts as desktop notifications
+    notify: bool,
+    /// Title last set, so it's only sent when it changes
+    last_title: Option<String>,
+}
+
+impl Integration {
+    /// Starts by saving the title the terminal had, to put back when done
+    pub fn new(out: &mut impl Write, title: bool, bell: bool, notify: bool) -> io::Result<Self> {
+        if title {
+            out.write_all(b"\x1b[22;0t")?;
+        }
+        Ok(Self { title, bell, notify, last_title: None })
+    }
+
+    /// Retitle the window if the title has changed, and pass on `alerts`
+    pub fn update(&mut self, out: &mut impl Write, app: &App, alerts: &[String]) -> io::Result<()> {
+        let mut wrote = false;
+        if self.title {
+            let title = title_for(app);
+            if self.last_title.as_ref() != Some(&title) {
+                write!(out, "\x1b]2;{title}\x07")?;
+                self.last_title = Some(title);
+                wrote = true;
+            }
+        }
+        if self.notify {
+            for alert in alerts {
+                write!(out, "\x1b]9;{}\x07", printable(alert))?;
+                wrote = true;
+            }
+        }
+        // One ring for the lot, not one per city
+        if self.bell && !alerts.is_empty() {
+            out.write_all(b"\x07")?;
+            wrote = true;
+        }
+        if wrote {
+            out.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Stop writing anything, after the terminal stopped taking it
+    pub fn disable(&mut self) {
+        self.title = false;
+        self.bell = false;
+        self.notify = false;
+    }
+
+    /// Put back the title the terminal had before
+    pub fn finish(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.title {
+            self.title = false;
+            out.write_all(b"\x1b[23;0t")?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// "tui-map — 1.2B casualties · 3.0x", or just the zoom before anyone's died
+pub fn title_for(app: &App) -> String {
+    match app.net_casualties() {
+        0 => format!("tui-map — {}", app.zoom_level()),
+        n => format!("tui-map — {} casualties · {}", format_casualties(n), app.zoom_level()),
+    }
+}
+
+/// `text` without control characters, which would end the sequence early
+fn printable(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_title_changes_with_the_toll_and_alerts_ring_once() {
+        let mut app = App::new(80, 24);
+        let mut out = Vec::new();
+        let mut term = Integration::new(&mut out, true, true, true).unwrap();
+        assert_eq!(out, b"\x1b[22;0t");
+        out.clear();
+
+        term.update(&mut out, &app, &[]).unwrap();
+        let zoom = app.zoom_level();
+        assert_eq!(String::from_utf8_lossy(&out), format!("\x1b]2;tui-map — {zoom}\x07"));
+        out.clear();
+        term.update(&mut out, &app, &[]).unwrap();
+        assert!(out.is_empty(), "title unchanged");
+
+        app.casualties = 1_234_000_000;
+        let alerts = ["Delhi destroyed".to_string(), "IND retaliates:\x1b 3 inbound".to_string()];
+        term.update(&mut out, &app, &alerts).unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            format!("\x1b]2;tui-map — 1.2B casualties · {zoom}\x07\x1b]9;Delhi destroyed\x07\x1b]9;IND retaliates: 3 inbound\x07\x07")
+        );
+
+        out.clear();
+        term.finish(&mut out).unwrap();
+        term.finish(&mut out).unwrap();
+        assert_eq!(out, b"\x1b[23;0t");
+    }
+}
@@ -0,0 +1,288 @@
+//! Color themes.
+//!
+//! A theme picks the colors of the static map layers outright and recolors
+//! everything else — weapons, fires, overlays, panels — through its tone,
+//! once the frame is drawn. Palettes keep each color's brightness and swap
+//! its hue for the nearest one they have, so fire gradients and dimmed
+//! layers still read as gradients.
+
+use crate::halfblock::Rgb;
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+
+/// How a theme recolors what it doesn't set directly
+#[derive(Debug, PartialEq)]
+pub enum Tone {
+    /// Colors as drawn
+    Full,
+    /// Nearest hue from a fixed set at the original brightness. Near-grays
+    /// go to the nearest gray by brightness, or stay neutral without a ramp.
+    Palette { hues: &'static [Rgb], grays: Option<&'static [Rgb]> },
+    /// Green phosphor, brightness only
+    Phosphor,
+    /// No color at all, for terminals that can't show any
+    Mono,
+}
+
+/// Map layer colors and how to recolor the rest
+#[derive(Debug, PartialEq)]
+pub struct Theme {
+    pub name: &'static str,
+    pub coastline: Color,
+    pub border: Color,
+    pub state: Color,
+    pub county: Color,
+    pub road: Color,
+    pub railway: Color,
+    pub wreckage: Color,
+    pub globe_outline: Color,
+    /// Background tint of land and lakes
+    pub land: Color,
+    pub lake: Color,
+    pub tone: Tone,
+}
+
+const SOLARIZED_HUES: &[Rgb] = &[
+    (181, 137, 0), (203, 75, 22), (220, 50, 47), (211, 54, 130),
+    (108, 113, 196), (38, 139, 210), (42, 161, 152), (133, 153, 0),
+];
+const SOLARIZED_BASES: &[Rgb] = &[
+    (0, 43, 54), (7, 54, 66), (88, 110, 117), (101, 123, 131),
+    (131, 148, 150), (147, 161, 161), (238, 232, 213), (253, 246, 227),
+];
+/// Okabe–Ito: hues that stay apart with red-green color blindness
+const OKABE_ITO_HUES: &[Rgb] = &[
+    (230, 159, 0), (86, 180, 233), (0, 158, 115), (240, 228, 66),
+    (0, 114, 178), (213, 94, 0), (204, 121, 167),
+];
+
+/// Built-in themes, in the order they cycle
+pub static THEMES: [Theme; 5] = [
+    Theme {
+        name: "classic",
+        coastline: Color::Cyan,
+        border: Color::Cyan,
+        state: Color::Yellow,
+        county: Color::DarkGray,
+        road: Color::Rgb(140, 105, 60),
+        railway: Color::Rgb(110, 110, 130),
+        wreckage: Color::Rgb(120, 40, 30),
+        globe_outline: Color::Rgb(50, 50, 50),
+        land: Color::Rgb(22, 32, 22),
+        lake: Color::Rgb(10, 22, 40),
+        tone: Tone::Full,
+    },
+    Theme {
+        name: "solarized",
+        coastline: Color::Rgb(42, 161, 152),
+        border: Color::Rgb(38, 139, 210),
+        state: Color::Rgb(181, 137, 0),
+        county: Color::Rgb(88, 110, 117),
+        road: Color::Rgb(160, 59, 17),
+        railway: Color::Rgb(108, 113, 196),
+        wreckage: Color::Rgb(150, 34, 32),
+        globe_outline: Color::Rgb(7, 54, 66),
+        land: Color::Rgb(7, 54, 66),
+        lake: Color::Rgb(0, 43, 54),
+        tone: Tone::Palette { hues: SOLARIZED_HUES, grays: Some(SOLARIZED_BASES) },
+    },
+    Theme {
+        name: "norad",
+        coastline: Color::Rgb(70, 255, 110),
+        border: Color::Rgb(70, 255, 110),
+        state: Color::Rgb(40, 170, 70),
+        county: Color::Rgb(20, 90, 35),
+        road: Color::Rgb(30, 130, 50),
+        railway: Color::Rgb(30, 130, 50),
+        wreckage: Color::Rgb(150, 255, 170),
+        globe_outline: Color::Rgb(20, 70, 30),
+        land: Color::Rgb(0, 26, 8),
+        lake: Color::Rgb(0, 0, 0),
+        tone: Tone::Phosphor,
+    },
+    Theme {
+        name: "colorblind",
+        coastline: Color::Rgb(86, 180, 233),
+        border: Color::Rgb(86, 180, 233),
+        state: Color::Rgb(240, 228, 66),
+        county: Color::Rgb(110, 110, 110),
+        road: Color::Rgb(150, 104, 0),
+        railway: Color::Rgb(120, 120, 120),
+        wreckage: Color::Rgb(170, 75, 0),
+        globe_outline: Color::Rgb(50, 50, 50),
+        land: Color::Rgb(28, 28, 28),
+        lake: Color::Rgb(0, 28, 44),
+        tone: Tone::Palette { hues: OKABE_ITO_HUES, grays: None },
+    },
+    Theme {
+        name: "mono",
+        coastline: Color::Reset,
+        border: Color::Reset,
+        state: Color::Reset,
+        county: Color::Reset,
+        road: Color::Reset,
+        railway: Color::Reset,
+        wreckage: Color::Reset,
+        globe_outline: Color::Reset,
+        land: Color::Reset,
+        lake: Color::Reset,
+        tone: Tone::Mono,
+    },
+];
+
+impl Theme {
+    /// Look up a built-in theme by name (`monochrome` works for `mono`)
+    pub fn by_name(name: &str) -> Option<&'static Theme> {
+        let name = name.to_ascii_lowercase();
+        let name = if name == "monochrome" { "mono" } else { &name };
+        THEMES.iter().find(|t| t.name == name)
+    }
+
+    /// The theme after this one, wrapping around
+    pub fn next(&self) -> &'static Theme {
+        let i = THEMES.iter().position(|t| t == self).unwrap_or(0);
+        &THEMES[(i + 1) % THEMES.len()]
+    }
+
+    /// Recolor one color through the tone
+    pub fn recolor(&self, color: Color) -> Color {
+        match self.tone {
+            Tone::Full => color,
+            Tone::Mono => Color::Reset,
+            _ => {
+                let Some((r, g, b)) = named_rgb(color) else { return color };
+                let (r, g, b) = match self.tone {
+                    Tone::Palette { hues, grays } => nearest_hue((r, g, b), hues, grays),
+                    _ => {
+                        let l = luma((r, g, b));
+                        ((l * 0.25) as u8, l as u8, (l * 0.4) as u8)
+                    }
+                };
+                Color::Rgb(r, g, b)
+            }
+        }
+    }
+
+    /// Recolor every cell of a drawn frame
+    pub fn apply(&self, buf: &mut Buffer) {
+        if self.tone == Tone::Full {
+            return;
+        }
+        for cell in &mut buf.content {
+            cell.fg = self.recolor(cell.fg);
+            cell.bg = self.recolor(cell.bg);
+        }
+    }
+}
+
+pub(crate) fn luma((r, g, b): Rgb) -> f32 {
+    0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32
+}
+
+/// The sixteen named colors at xterm's defaults
+pub(crate) const ANSI: [(Color, Rgb); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// RGB of a color, with named colors at xterm's defaults. The terminal's
+/// default colors and indexed ones have none.
+fn named_rgb(color: Color) -> Option<Rgb> {
+    match color {
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        _ => ANSI.iter().find(|&&(c, _)| c == color).map(|&(_, rgb)| rgb),
+    }
+}
+
+/// The palette hue closest in direction to `rgb`, scaled to its brightness
+fn nearest_hue(rgb: Rgb, hues: &[Rgb], grays: Option<&[Rgb]>) -> Rgb {
+    let (r, g, b) = rgb;
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = luma(rgb);
+    if max == 0 || ((max - min) as f32) < max as f32 * 0.25 {
+        return match grays {
+            Some(ramp) => *ramp.iter().min_by(|a, b| (luma(**a) - l).abs().total_cmp(&(luma(**b) - l).abs())).unwrap(),
+            None => (l as u8, l as u8, l as u8),
+        };
+    }
+    let dir = |(r, g, b): Rgb| {
+        let m = r.max(g).max(b).max(1) as f32;
+        (r as f32 / m, g as f32 / m, b as f32 / m)
+    };
+    let (x, y, z) = dir(rgb);
+    let &hue = hues.iter().min_by(|a, b| {
+        let dist = |c: Rgb| {
+            let (u, v, w) = dir(c);
+            (u - x).powi(2) + (v - y).powi(2) + (w - z).powi(2)
+        };
+        dist(**a).total_cmp(&dist(**b))
+    }).unwrap();
+    let scale = l / luma(hue).max(1.0);
+    let channel = |c: u8| (c as f32 * scale).min(255.0) as u8;
+    (channel(hue.0), channel(hue.1), channel(hue.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+
+    #[test]
+    fn themes_cycle_and_look_up_by_name() {
+        let mut theme = Theme::by_name("classic").unwrap();
+        let mut seen = vec![theme.name];
+        for _ in 1..THEMES.len() {
+            theme = theme.next();
+            seen.push(theme.name);
+        }
+        assert_eq!(seen, ["classic", "solarized", "norad", "colorblind", "mono"]);
+        assert_eq!(theme.next().name, "classic");
+        assert_eq!(Theme::by_name("Monochrome").unwrap().name, "mono");
+        assert!(Theme::by_name("neon").is_none());
+    }
+
+    #[test]
+    fn tones_recolor_the_frame() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 3, 1));
+        buf[(0, 0)].set_fg(Color::Red).set_bg(Color::Rgb(22, 32, 22));
+        buf[(1, 0)].set_fg(Color::Rgb(0, 255, 50));
+
+        // Classic leaves everything alone; mono strips every color
+        let mut classic = buf.clone();
+        Theme::by_name("classic").unwrap().apply(&mut classic);
+        assert_eq!(classic, buf);
+        let mut mono = buf.clone();
+        Theme::by_name("mono").unwrap().apply(&mut mono);
+        assert!(mono.content.iter().all(|c| c.fg == Color::Reset && c.bg == Color::Reset));
+
+        // Red and green land on different Okabe–Ito hues at their own
+        // brightness; near-grays stay gray
+        let colorblind = Theme::by_name("colorblind").unwrap();
+        let red = colorblind.recolor(Color::Red);
+        let green = colorblind.recolor(Color::Rgb(0, 255, 50));
+        assert_ne!(red, green);
+        let Color::Rgb(r, g, b) = red else { panic!("{red:?}") };
+        assert!((luma((r, g, b)) - luma((205, 0, 0))).abs() < 3.0);
+        assert_eq!(colorblind.recolor(Color::Rgb(100, 100, 110)), Color::Rgb(101, 101, 101));
+
+        // Phosphor keeps brightness order
+        let norad = Theme::by_name("norad").unwrap();
+        let green_of = |c| match norad.recolor(c) { Color::Rgb(_, g, _) => g, c => panic!("{c:?}") };
+        assert!(green_of(Color::White) > green_of(Color::Red));
+    }
+}
@@ -1,7 +1,26 @@
-use crate::app::{App, WeaponType};
+use crate::app::{format_lethality, format_rate, App, Crater, DefenseSite, Interception, PlannedStrike, Stockpile, View, WeaponType};
+use crate::command;
+use crate::data::fetch::FileState;
+use crate::faction::Faction;
+use crate::events::EventKind;
+use crate::game::{GameState, Outcome};
+use crate::geo::{compass_point, great_circle_km, great_circle_point, wrap_lon};
+use crate::graphics::Protocol;
+use crate::halfblock::{HalfBlockCanvas, Rgb};
 use crate::hash::{hash2, hash3};
-use crate::map::{GlobeViewport, MapLayers, Projection, WRAP_OFFSETS};
-use crate::map::globe::lonlat_to_vec3;
+use crate::heatmap::{ColorRamp, HeatmapLayer};
+use crate::legend::{self, Swatch};
+use crate::braille::BrailleCanvas;
+use crate::map::geometry::draw_line;
+use crate::map::renderer::{LandGrid, MapLabel};
+use crate::map::{Infrastructure, LineString, MapLayers, MapProjection, Projection};
+use crate::map::globe::{lonlat_to_vec3, vec3_to_lonlat, DVec3};
+use crate::overlay::Shapes;
+use crate::sim::clock::sun_elevation_from;
+use crate::perf;
+use crate::sim::{FalloutGrid, Hurricane, SimClock};
+use crate::targeting::Reticle;
+use crate::theme::Theme;
 
 /// Fast pseudo-angle using diamond angle technique.
 /// Returns a value in [0, 4) that varies monotonically with angle,
@@ -20,7 +39,7 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Widget},
+    widgets::{Block, Borders, Clear, Paragraph, Sparkline, Widget, Wrap},
     Frame,
 };
 
@@ -28,17 +47,851 @@ use ratatui::{
 pub fn render(frame: &mut Frame, app: &mut App) {
     let area = frame.area();
 
-    // Split into map area and status bar
+    // Split into map area, event log and status bar
+    let log_height = if app.show_event_log && area.height > 24 { EVENT_LOG_HEIGHT } else { 0 };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Min(3),    // Map
-            Constraint::Length(1), // Status bar
+            Constraint::Min(3),             // Map
+            Constraint::Length(log_height), // Event log
+            Constraint::Length(1),          // Status bar
         ])
         .split(area);
 
-    render_map(frame, app, chunks[0]);
-    render_status_bar(frame, app, chunks[1]);
+    // Sidebar on the right so map columns keep their screen offsets
+    let (map_area, sidebar_area) = if app.show_sidebar && chunks[0].width > 60 {
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(20), Constraint::Length(SIDEBAR_WIDTH)])
+            .split(chunks[0]);
+        (cols[0], Some(cols[1]))
+    } else {
+        (chunks[0], None)
+    };
+
+    match app.view {
+        View::Map if app.text_mode => render_text_view(frame, app, map_area),
+        View::Map => {
+            render_map(frame, app, map_area);
+            render_hazard_tooltip(frame, app, map_area);
+        }
+        View::Stats => render_dashboard(frame, app, map_area),
+    }
+    if let Some(sidebar) = sidebar_area {
+        render_sidebar(frame, app, sidebar);
+    }
+    if log_height > 0 {
+        render_event_log(frame, app, chunks[1]);
+    }
+    render_status_bar(frame, app, chunks[2]);
+    if let Some(corners) = app.box_zoom {
+        render_box_zoom(frame, corners, map_area);
+    }
+    if app.show_legend && app.view == View::Map && !app.text_mode {
+        render_legend(frame, app, map_area);
+    }
+    if app.show_hud && app.view == View::Map && !app.text_mode {
+        render_hud(frame, app, map_area);
+    }
+    if app.show_arsenal {
+        render_arsenal(frame, app, chunks[0]);
+    }
+    if app.data_manager.open {
+        render_data_manager(frame, app, chunks[0]);
+    }
+    if let GameState::Over { outcome, .. } = app.game {
+        render_summary(frame, app, outcome, area);
+    }
+    if app.pandemic && app.view == View::Map && !app.text_mode {
+        render_pandemic_panel(frame, app, map_area);
+    }
+    if app.history.is_scrubbing() {
+        render_timeline(frame, app, map_area);
+    }
+    if app.perf.show_hud {
+        render_perf_hud(frame, app, map_area);
+    }
+    // Everything not colored by a layer gets the theme's tone, then
+    // whatever the terminal can't show falls back to its nearest color
+    app.theme.apply(frame.buffer_mut());
+    app.color_depth.apply(frame.buffer_mut());
+}
+
+/// Performance HUD in the map's top right corner: frame rate and cap,
+/// frame, render and simulation times, what's on screen, effect detail
+fn render_perf_hud(frame: &mut Frame, app: &App, area: Rect) {
+    let perf = &app.perf;
+    let label = Style::default().fg(Color::DarkGray);
+    let value = Style::default().fg(Color::White);
+    let budget = perf.budget_ms();
+    let work = perf.render_ms() + perf.sim_ms();
+    let row = |name: &'static str, text: String, style: Style| Line::from(vec![Span::styled(format!("{name:<9}"), label), Span::styled(text, style)]);
+    let lines = vec![
+        row("FPS", format!("{:.0} ({})", perf.fps(), perf.cap.label()), value),
+        row("Frame", format!("{:.1} ms", perf.frame_ms()), value),
+        row("Render", format!("{:.1} ms", perf.render_ms()), Style::default().fg(if work > budget { Color::Red } else { Color::White })),
+        row("Sim", format!("{:.1} ms", perf.sim_ms()), value),
+        row("Fires", app.fires.len().to_string(), value),
+        row("Features", perf.features.to_string(), value),
+        row("Detail", if perf.auto_degrade { perf.detail_label().to_string() } else { "full (fixed)".to_string() },
+            Style::default().fg(if perf.detail > 0 { Color::Yellow } else { Color::White })),
+    ];
+    let width = 28.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup = Rect { x: area.right().saturating_sub(width + 1), y: area.y + 1, width, height };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(Span::styled(" Perf ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
+    let inner = block.inner(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(block, popup);
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Overlay pulses drawn at most, the widest first; each scans the square
+/// its ring fits in
+const MAX_OVERLAY_PULSES: usize = 200;
+
+/// Busiest flight routes drawn at once; quieter ones come in as the map
+/// zooms in and the busy ones leave the view
+const MAX_FLIGHT_ROUTES: usize = 40;
+/// Hubs this close on screen (braille pixels) share their routes
+const FLIGHT_BUNDLE_PX: i32 = 16;
+/// Route colors from the quietest to the busiest
+const FLIGHT_COLORS: [Color; 4] = [
+    Color::Rgb(45, 60, 85),
+    Color::Rgb(70, 100, 145),
+    Color::Rgb(100, 145, 205),
+    Color::Rgb(140, 190, 255),
+];
+
+/// The travel network's routes as great-circle arcs, bundled and limited
+/// for the view, brighter the busier they are
+fn flight_routes(app: &App, projection: &Projection, area: Rect) -> Shapes {
+    let (width, height) = (area.width as i32 * 2, area.height as i32 * 4);
+    let on_screen = |lon: f64, lat: f64| {
+        projection.project_point(lon, lat).filter(|&(x, y)| (0..width).contains(&x) && (0..height).contains(&y))
+    };
+    let bundles = app.travel.bundled(|hub| {
+        on_screen(hub.lon, hub.lat).map(|(x, y)| (x / FLIGHT_BUNDLE_PX, y / FLIGHT_BUNDLE_PX))
+    });
+    let busiest = bundles.first().map_or(1.0, |b| b.weight);
+    let mut shapes = Shapes::default();
+    for bundle in bundles {
+        let ((lon0, lat0), (lon1, lat1)) = (bundle.from, bundle.to);
+        // About every 150 km, so long arcs bend on every projection
+        let n = (great_circle_km(lon0, lat0, lon1, lat1) / 150.0).ceil().clamp(2.0, 96.0) as usize;
+        let points: Vec<(f64, f64)> = (0..=n).map(|i| great_circle_point(lon0, lat0, lon1, lat1, i as f64 / n as f64)).collect();
+        if !points.iter().any(|&(lon, lat)| on_screen(lon, lat).is_some()) {
+            continue;
+        }
+        let level = ((bundle.weight / busiest).sqrt() * FLIGHT_COLORS.len() as f64).ceil() as usize;
+        shapes.line(points, FLIGHT_COLORS[level.clamp(1, FLIGHT_COLORS.len()) - 1]);
+        if shapes.lines.len() == MAX_FLIGHT_ROUTES {
+            break;
+        }
+    }
+    shapes
+}
+
+/// The history scrubber along the bottom of the map: how far back the
+/// snapshots go, where the view is among them, and "now" at the right
+fn render_timeline(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(cursor) = app.history.cursor() else { return };
+    let frames: Vec<u64> = app.history.frames().collect();
+    let ago = |f: u64| app.frame.saturating_sub(f) as f64 * SimClock::FRAME_SECS;
+    let viewed = format!(" ⏪ -{} ", format_duration(ago(frames[cursor])));
+    let oldest = format!("-{} ", format_duration(ago(frames[0])));
+    let hint = " now  Alt+←/→ scrub, Esc: now ";
+    let used = (viewed.chars().count() + oldest.chars().count() + hint.chars().count()) as u16 + 2;
+    let width = area.width.saturating_sub(2);
+    if width <= used + 4 || area.height < 3 {
+        return;
+    }
+    let track = (width - used) as usize;
+    // The track runs from the oldest snapshot to now, one slot past the newest
+    let marker = cursor * (track - 1) / frames.len().max(1);
+    let bar: String = (0..track).map(|i| if i == marker { '●' } else { '─' }).collect();
+    let strip = Rect { x: area.x + 1, y: area.bottom().saturating_sub(2), width, height: 1 };
+    let line = Line::from(vec![
+        Span::styled(viewed, Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        Span::styled(format!(" {oldest}"), Style::default().fg(Color::DarkGray)),
+        Span::styled("├", Style::default().fg(Color::DarkGray)),
+        Span::styled(bar, Style::default().fg(Color::Yellow)),
+        Span::styled("┤", Style::default().fg(Color::DarkGray)),
+        Span::styled(hint, Style::default().fg(Color::DarkGray)),
+    ]);
+    frame.render_widget(Clear, strip);
+    frame.render_widget(Paragraph::new(line), strip);
+}
+
+/// Pandemic mode's control panel in the map's bottom right corner: the
+/// R0 and lethality the keys adjust, and how the outbreak is going
+fn render_pandemic_panel(frame: &mut Frame, app: &App, area: Rect) {
+    let label = Style::default().fg(Color::DarkGray);
+    let value = Style::default().fg(Color::White);
+    let row = |name: &'static str, text: String, style: Style| Line::from(vec![Span::styled(format!("{name:<10}"), label), Span::styled(text, style)]);
+    let cure = match app.outbreak {
+        Some(outbreak) if outbreak.is_cured() => "found".to_string(),
+        Some(outbreak) => format!("in {}", format_duration(outbreak.cure_in as f64 / 60.0)),
+        None => "—".to_string(),
+    };
+    let lines = vec![
+        row("R0", format!("{:.2}", app.r0), Style::default().fg(weapon_color(WeaponType::Bio))),
+        row("Lethality", format_lethality(app.mortality), Style::default().fg(weapon_color(WeaponType::Bio))),
+        row("Infected", format!("{} cities", app.infected.len()), value),
+        row("Deaths", format_casualties(app.casualties), Style::default().fg(Color::Red)),
+        row("Cure", cure, value),
+        row("Routes", app.travel.routes.len().to_string(), value),
+        Line::styled("( ) R0  ; ' lethality", label),
+    ];
+    let width = 26.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup = Rect { x: area.right().saturating_sub(width + 1), y: area.bottom().saturating_sub(height + 1), width, height };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(Span::styled(" Pandemic ", Style::default().fg(weapon_color(WeaponType::Bio)).add_modifier(Modifier::BOLD)));
+    let inner = block.inner(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(block, popup);
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Text mode's stand-in for the map: the view described in words, then
+/// what's under the mouse and the latest events, plain text only
+fn render_text_view(frame: &mut Frame, app: &mut App, area: Rect) {
+    let mut lines = vec![Line::raw(app.view_description().to_string()), Line::raw("")];
+    if let Some((lon, lat, hazards)) = app.hovered_hazards() {
+        let mut parts = Vec::new();
+        if let Some((intensity, weapon)) = hazards.fire {
+            parts.push(format!("{} fire at {:.0}%", weapon.label().to_lowercase(), intensity as f64 / 2.55));
+        }
+        if hazards.fallout_gy_h >= 0.01 {
+            parts.push(format!("fallout {:.2} Gy/h", hazards.fallout_gy_h));
+        }
+        for (weapon, share) in &hazards.gas {
+            parts.push(format!("{} gas {:.0}%", weapon.label().to_lowercase(), share * 100.0));
+        }
+        if !parts.is_empty() {
+            lines.push(Line::raw(format!("Under the mouse at {lat:.2}, {lon:.2}: {}.", parts.join(", "))));
+            lines.push(Line::raw(""));
+        }
+    }
+    let rows = area.height.saturating_sub(lines.len() as u16 + 4) as usize;
+    if !app.events.is_empty() && rows > 0 {
+        lines.push(Line::raw("Latest events:"));
+        lines.extend(app.events.visible(rows.min(5)).map(|e| Line::raw(format!("{} {}", e.time_label(), e.text))));
+    }
+    let block = Block::default().borders(Borders::ALL).title(" Text mode ");
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: true }).block(block), area);
+}
+
+/// Detailed HUD in the map's top left corner: the active weapon, wind
+/// under the mouse, the clock and what's left in the stockpiles. The map
+/// shows through, dimmed, and sections that don't fit are left off.
+fn render_hud(frame: &mut Frame, app: &App, area: Rect) {
+    let label = Style::default().fg(Color::DarkGray);
+    let value = Style::default().fg(Color::White);
+    let row = |name: &'static str, text: String, style: Style| Line::from(vec![Span::styled(format!("{name:<8}"), label), Span::styled(text, style)]);
+    let weapon = app.active_weapon;
+
+    let mut strike = format!("{} {} {}", weapon.symbol(), weapon.label(), app.payload_label(weapon));
+    if app.mirv_armed {
+        strike.push_str(&format!(" MIRV×{}", app.mirv.warheads));
+    }
+    let (u, v) = app.hud_wind();
+    let speed = u.hypot(v);
+    // Meteorological convention: where the wind blows from
+    let from = (-u).atan2(-v).to_degrees().rem_euclid(360.0);
+    let mut clock = app.clock_status();
+    if app.paused {
+        clock.push_str(" ⏸");
+    } else if app.sim_rate != 1.0 {
+        clock.push_str(&format!(" ▶ {}", format_rate(app.sim_rate)));
+    }
+
+    // Most important first; the panel keeps as many as fit
+    let mut sections = vec![
+        vec![
+            row("Weapon", strike, Style::default().fg(weapon_color(weapon))),
+            row("Blast", format!("{:.0} km, reload {:.2}s", app.blast_radius_km(weapon), weapon.cooldown_frames() as f64 / 60.0), value),
+            row("Via", format!("{} from {}", app.delivery.label(), app.launch_site_label()), value),
+            row("Reticle", app.reticle.label().to_lowercase(), value),
+        ],
+        vec![row("Wind", format!("{speed:.1} m/s from {} ({from:.0}°)", compass_point(from)), Style::default().fg(Color::Cyan))],
+        vec![row("Clock", clock, value)],
+    ];
+    // Inset a cell from the map's border on the left, and kept off it on the right
+    let width = 40.min(area.width.saturating_sub(2));
+    let inner_width = width.saturating_sub(2) as usize;
+    let mut stock = Vec::new();
+    let mut spans = vec![Span::styled(format!("{:<8}", "Stock"), label)];
+    let mut used = 8;
+    for &w in WeaponType::ALL.iter() {
+        let Stockpile { capacity: Some(capacity), remaining, .. } = app.stockpiles[w.index()] else { continue };
+        let text = format!("{}{remaining}/{capacity} ", w.symbol());
+        let len = text.chars().count();
+        if used + len > inner_width && used > 8 {
+            stock.push(Line::from(std::mem::replace(&mut spans, vec![Span::raw(" ".repeat(8))])));
+            used = 8;
+        }
+        used += len;
+        spans.push(Span::styled(text, Style::default().fg(if remaining == 0 { Color::DarkGray } else { weapon_color(w) })));
+    }
+    if spans.len() == 1 {
+        spans.push(Span::styled("unlimited", value));
+    }
+    stock.push(Line::from(spans));
+    sections.push(stock);
+
+    let room = area.height.saturating_sub(3) as usize;
+    let mut lines: Vec<Line> = Vec::new();
+    for section in sections {
+        if lines.len() + section.len() > room {
+            break;
+        }
+        lines.extend(section);
+    }
+    if lines.is_empty() || width < 20 {
+        return;
+    }
+    let height = lines.len() as u16 + 2;
+    let popup = Rect { x: area.x + 1, y: area.y + 1, width, height };
+    // Dim the map under the panel instead of clearing it
+    let buf = frame.buffer_mut();
+    for y in popup.top()..popup.bottom() {
+        for x in popup.left()..popup.right() {
+            buf[(x, y)].set_fg(Color::DarkGray).set_bg(Color::Reset);
+        }
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(Span::styled(" HUD ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// The shift-drag zoom rectangle, outlined over the map
+fn render_box_zoom(frame: &mut Frame, ((c0, r0), (c1, r1)): ((u16, u16), (u16, u16)), area: Rect) {
+    let clamp_col = |c: u16| c.clamp(area.left(), area.right().saturating_sub(1));
+    let clamp_row = |r: u16| r.clamp(area.top(), area.bottom().saturating_sub(1));
+    let (left, right) = (clamp_col(c0.min(c1)), clamp_col(c0.max(c1)));
+    let (top, bottom) = (clamp_row(r0.min(r1)), clamp_row(r0.max(r1)));
+    let style = Style::default().fg(Color::Yellow);
+    let buf = frame.buffer_mut();
+    for x in left..=right {
+        buf[(x, top)].set_char('─').set_style(style);
+        buf[(x, bottom)].set_char('─').set_style(style);
+    }
+    for y in top..=bottom {
+        buf[(left, y)].set_char('│').set_style(style);
+        buf[(right, y)].set_char('│').set_style(style);
+    }
+    for (x, y, ch) in [(left, top, '┌'), (right, top, '┐'), (left, bottom, '└'), (right, bottom, '┘')] {
+        buf[(x, y)].set_char(ch).set_style(style);
+    }
+}
+
+/// Legend in the map's bottom left corner: a swatch, name and range for
+/// each layer showing
+fn render_legend(frame: &mut Frame, app: &App, area: Rect) {
+    let entries = legend::entries(app);
+    if entries.is_empty() {
+        return;
+    }
+    let name_width = entries.iter().map(|e| e.name.chars().count()).max().unwrap_or(0);
+    let lines: Vec<Line> = entries.into_iter().map(|entry| {
+        let mut spans = match entry.swatch {
+            Swatch::Line(color) => vec![Span::styled("━━━━━━", Style::default().fg(color))],
+            Swatch::Glyph(ch, color) => vec![Span::styled(format!("  {ch}   "), Style::default().fg(color))],
+            Swatch::Ramp(colors) => colors.into_iter()
+                .map(|(r, g, b)| Span::styled("█", Style::default().fg(Color::Rgb(r, g, b))))
+                .collect(),
+        };
+        spans.push(Span::styled(format!(" {:<name_width$}", entry.name), Style::default().fg(Color::White)));
+        if let Some(range) = entry.range {
+            spans.push(Span::styled(format!(" {range}"), Style::default().fg(Color::DarkGray)));
+        }
+        Line::from(spans)
+    }).collect();
+    let width = (lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 2).min(area.width.saturating_sub(2));
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup = Rect { x: area.x + 1, y: area.bottom().saturating_sub(height + 1), width, height };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(Span::styled(" Legend ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
+    let inner = block.inner(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(block, popup);
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Tooltip beside the mouse over fires, fallout and gas: fire intensity,
+/// fallout dose rate and ground dose, gas concentration
+fn render_hazard_tooltip(frame: &mut Frame, app: &App, area: Rect) {
+    let Some((col, row)) = app.mouse_pos else { return };
+    if !area.contains((col, row).into()) {
+        return;
+    }
+    let Some((lon, lat, hazards)) = app.hovered_hazards() else { return };
+    let label = Style::default().fg(Color::DarkGray);
+    let row_of = |name: String, text: String, color: Color| {
+        Line::from(vec![Span::styled(format!("{name:<9}"), label), Span::styled(text, Style::default().fg(color))])
+    };
+    let mut lines = Vec::new();
+    if let Some((intensity, weapon)) = hazards.fire {
+        let text = format!("{:.0}% ({})", intensity as f64 / 2.55, weapon.label().to_lowercase());
+        let (_, (r, g, b)) = fire_fill(weapon, intensity);
+        lines.push(row_of("Fire".into(), text, Color::Rgb(r, g, b)));
+    }
+    if hazards.fallout_gy_h >= 0.01 {
+        lines.push(row_of("Fallout".into(), format!("{:.2} Gy/h", hazards.fallout_gy_h), Color::LightGreen));
+    }
+    if hazards.ground_gy >= 0.01 {
+        let color = if hazards.ground_gy >= FalloutGrid::LETHAL_GY { Color::Red } else { Color::Yellow };
+        lines.push(row_of("Ground".into(), format!("{:.2} Gy", hazards.ground_gy), color));
+    }
+    for (weapon, share) in &hazards.gas {
+        lines.push(row_of(format!("{} gas", weapon.label().to_lowercase()), format!("{:.0}%", share * 100.0), weapon_color(*weapon)));
+    }
+
+    let title = format!(" {:.2}°{} {:.2}°{} ", lat.abs(), if lat >= 0.0 { 'N' } else { 'S' }, lon.abs(), if lon >= 0.0 { 'E' } else { 'W' });
+    let width = (lines.iter().map(Line::width).max().unwrap_or(0).max(title.chars().count()) as u16 + 2).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    // Down and right of the pointer, flipped to stay on the map
+    let x = if col + 2 + width <= area.right() { col + 2 } else { col.saturating_sub(width + 1).max(area.x) };
+    let y = if row + 1 + height <= area.bottom() { row + 1 } else { row.saturating_sub(height).max(area.y) };
+    let popup = Rect { x, y, width, height };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(Span::styled(title, Style::default().fg(Color::White)));
+    let inner = block.inner(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(block, popup);
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Weapon palette: every weapon with its key, yield, blast radius, cooldown
+/// and effects. The active weapon is highlighted.
+fn render_arsenal(frame: &mut Frame, app: &App, area: Rect) {
+    let width = area.width.saturating_sub(4).min(91);
+    let height = (WeaponType::ALL.len() as u16 + 4).min(area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .title(Span::styled(" Arsenal ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)))
+        .title_bottom(Span::styled(" 1-9: select  m: yield  i/Esc: close ", Style::default().fg(Color::DarkGray)));
+    let inner = block.inner(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(block, popup);
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!("    {:<11} {:<12} {:>8} {:>6} {:>6}  Effects", "Weapon", "Yield", "Blast", "Reload", "Stock"),
+        Style::default().fg(Color::DarkGray),
+    ))];
+    for (i, &weapon) in WeaponType::ALL.iter().enumerate() {
+        let selected = weapon == app.active_weapon;
+        let style = if selected {
+            Style::default().fg(weapon_color(weapon)).add_modifier(Modifier::BOLD | Modifier::REVERSED)
+        } else {
+            Style::default().fg(weapon_color(weapon))
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {} ", i + 1), Style::default().fg(Color::Yellow)),
+            Span::styled(format!("{} {:<9}", weapon.symbol(), weapon.label()), style),
+            Span::styled(
+                format!(
+                    " {:<12} {:>5.0} km {:>5.2}s {:>6}  {}",
+                    app.payload_label(weapon),
+                    app.blast_radius_km(weapon),
+                    weapon.cooldown_frames() as f64 / 60.0,
+                    match app.stockpiles[weapon.index()] {
+                        Stockpile { capacity: Some(capacity), remaining, .. } => format!("{remaining}/{capacity}"),
+                        Stockpile { capacity: None, .. } => "∞".to_string(),
+                    },
+                    weapon.effects(),
+                ),
+                Style::default().fg(if selected { Color::White } else { Color::Gray }),
+            ),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Natural Earth files for the selected resolution, with download progress
+fn render_data_manager(frame: &mut Frame, app: &App, area: Rect) {
+    let manager = &app.data_manager;
+    let width = area.width.saturating_sub(4).min(78);
+    let height = (manager.files.len() as u16 + 5).min(area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(Span::styled(" Natural Earth data ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)))
+        .title_bottom(Span::styled(
+            " 1: 110m  2: 50m  3: 10m  v: verify  Esc: close ",
+            Style::default().fg(Color::DarkGray),
+        ));
+    let inner = block.inner(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(block, popup);
+
+    let present = manager.files.iter().filter(|(_, state)| *state != FileState::Missing).count();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            if present == 0 {
+                format!("No map data in {}/ yet. Pick a resolution to download:", manager.dir.display())
+            } else {
+                format!("{} files in {}/ ({present} of {} present)", manager.resolution.label(), manager.dir.display(), manager.files.len())
+            },
+            Style::default().fg(Color::White),
+        )),
+        Line::raw(""),
+    ];
+    for (dataset, state) in &manager.files {
+        let (text, color) = match state {
+            FileState::Missing => ("missing".to_string(), Color::DarkGray),
+            FileState::Present => ("present".to_string(), Color::Green),
+            FileState::Queued => ("queued".to_string(), Color::DarkGray),
+            FileState::Downloading { bytes, total: Some(total) } => {
+                let done = *bytes as f64 / (*total).max(1) as f64;
+                (format!("{} {:>3.0}% of {:.1} MB", progress_bar(done), done * 100.0, *total as f64 / 1e6), Color::Yellow)
+            }
+            FileState::Downloading { bytes, total: None } => (format!("{:.1} MB", *bytes as f64 / 1e6), Color::Yellow),
+            FileState::Retrying { attempt, error } => (format!("retrying ({attempt}): {error}"), Color::LightRed),
+            FileState::Downloaded => ("downloaded".to_string(), Color::Green),
+            FileState::Failed(error) => (format!("failed: {error}"), Color::Red),
+            FileState::Verified => ("checksum ok".to_string(), Color::Green),
+            FileState::Mismatch => ("changed since download (1-3 fetches again)".to_string(), Color::Red),
+            FileState::Unrecorded => ("present, no checksum recorded".to_string(), Color::Gray),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {:<32} ", dataset.file), Style::default().fg(Color::Gray)),
+            Span::styled(text, Style::default().fg(color)),
+        ]));
+    }
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Twenty-cell bar for a 0–1 fraction
+fn progress_bar(done: f64) -> String {
+    const CELLS: usize = 20;
+    let full = ((done * CELLS as f64).round() as usize).min(CELLS);
+    format!("{}{}", "█".repeat(full), "░".repeat(CELLS - full))
+}
+
+/// End-of-game summary: outcome, totals, casualties over time and per faction
+fn render_summary(frame: &mut Frame, app: &App, outcome: Outcome, area: Rect) {
+    let width = area.width.saturating_sub(4).min(72);
+    let height = area.height.saturating_sub(2).min(26);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let color = match outcome {
+        Outcome::Victory => Color::Green,
+        Outcome::Defeat => Color::Red,
+        Outcome::Ended => Color::Yellow,
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(color))
+        .title(Span::styled(format!(" {} ", outcome.label()), Style::default().fg(color).add_modifier(Modifier::BOLD)))
+        .title_bottom(Span::styled(" Enter: keep playing  r: restart  q: quit ", Style::default().fg(Color::DarkGray)));
+    let inner = block.inner(popup);
+    frame.render_widget(Clear, popup);
+    frame.render_widget(block, popup);
+
+    let stats = &app.stats;
+    let (play_secs, sim_secs) = stats.elapsed(app.frame, app.clock.unix_secs());
+    let label = Style::default().fg(Color::DarkGray);
+    let value = Style::default().fg(Color::White);
+    let row = |name: &str, text: String| Line::from(vec![Span::styled(format!("{name:<20}"), label), Span::styled(text, value)]);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            app.game.objective().map_or_else(|| "Free play".to_string(), |o| o.describe()),
+            Style::default().fg(Color::Cyan),
+        )),
+        Line::raw(""),
+        row("Time elapsed", format!("{} ({} simulated)", format_duration(play_secs), format_duration(sim_secs))),
+        row("Strikes launched", stats.strikes_launched.to_string()),
+        row("Retaliation waves", stats.waves.to_string()),
+        row("Missiles intercepted", stats.intercepts.to_string()),
+        row("Largest firestorm", format!("{} fires", stats.peak_fires)),
+        row("Infrastructure lost", stats.infrastructure_destroyed.to_string()),
+        row("Born while rebuilding", format_casualties(stats.births)),
+        Line::from(vec![
+            Span::styled(format!("{:<20}", "Casualties"), label),
+            Span::styled(format_casualties(app.net_casualties()), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+        ]),
+        Line::raw(""),
+    ];
+
+    let bar_width = (inner.width as usize).saturating_sub(34);
+    for faction in Faction::ALL {
+        let faction_stats = &app.faction_stats[faction.index()];
+        let (r, g, b) = faction.color();
+        let filled = ((1.0 - faction_stats.surviving()) * bar_width as f64).round() as usize;
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<14}", faction.label()), Style::default().fg(Color::Rgb(r, g, b))),
+            Span::styled("█".repeat(filled), Style::default().fg(Color::Red)),
+            Span::styled("░".repeat(bar_width.saturating_sub(filled)), label),
+            Span::styled(
+                format!(" {:>7} dead {:>3.0}%", format_casualties(faction_stats.casualties()), (1.0 - faction_stats.surviving()) * 100.0),
+                value,
+            ),
+        ]));
+    }
+    lines.push(Line::raw(""));
+    lines.push(Line::from(Span::styled("Casualties over time", label)));
+
+    let text_height = (lines.len() as u16).min(inner.height);
+    frame.render_widget(Paragraph::new(lines), Rect { height: text_height, ..inner });
+
+    let chart_area = Rect {
+        y: inner.y + text_height,
+        height: inner.height.saturating_sub(text_height),
+        ..inner
+    };
+    if chart_area.height > 0 {
+        let history = stats.history_resampled(chart_area.width as usize);
+        frame.render_widget(Sparkline::default().data(&history).style(Style::default().fg(Color::Red)), chart_area);
+    }
+}
+
+/// Format seconds as `1h02m`, `3m05s` or `42s`
+fn format_duration(secs: f64) -> String {
+    let secs = secs.max(0.0) as u64;
+    if secs >= 3600 {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{secs}s")
+    }
+}
+
+/// Reload gauge for the status line: empties as the weapon gets ready
+fn reload_bar(remaining: f64) -> String {
+    const CELLS: usize = 6;
+    let full = ((remaining * CELLS as f64).ceil() as usize).min(CELLS);
+    format!(" {}{}", "▰".repeat(full), "▱".repeat(CELLS - full))
+}
+
+const SIDEBAR_WIDTH: u16 = 26;
+
+/// Per-faction population and casualties
+fn render_sidebar(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(Span::styled(" Factions ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let bar_width = inner.width.saturating_sub(2) as usize;
+    let mut lines = Vec::new();
+    for faction in Faction::ALL {
+        let stats = &app.faction_stats[faction.index()];
+        let (r, g, b) = faction.color();
+        let color = Color::Rgb(r, g, b);
+        let filled = (stats.surviving() * bar_width as f64).round() as usize;
+        lines.push(Line::from(Span::styled(faction.label(), Style::default().fg(color).add_modifier(Modifier::BOLD))));
+        lines.push(Line::from(vec![
+            Span::styled("█".repeat(filled), Style::default().fg(color)),
+            Span::styled("░".repeat(bar_width.saturating_sub(filled)), Style::default().fg(Color::DarkGray)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("Pop  ", Style::default().fg(Color::DarkGray)),
+            Span::styled(format_casualties(stats.population), Style::default().fg(Color::White)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("Dead ", Style::default().fg(Color::DarkGray)),
+            Span::styled(format_casualties(stats.casualties()), Style::default().fg(Color::Red)),
+        ]));
+        lines.push(Line::raw(""));
+    }
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+const EVENT_LOG_HEIGHT: u16 = 10;
+const WORST_HIT_WIDTH: u16 = 36;
+
+/// Recent events on the left, the worst-hit cities on the right
+fn render_event_log(frame: &mut Frame, app: &App, area: Rect) {
+    let (log_area, cities_area) = if area.width > 80 {
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(30), Constraint::Length(WORST_HIT_WIDTH)])
+            .split(area);
+        (cols[0], Some(cols[1]))
+    } else {
+        (area, None)
+    };
+
+    let label = Style::default().fg(Color::DarkGray);
+    let scroll = app.events.scroll();
+    let title = if scroll > 0 { format!(" Events ({scroll} newer) ") } else { " Events ".to_string() };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(label)
+        .title(Span::styled(title, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)))
+        .title_bottom(Span::styled(" PgUp/PgDn: scroll  J: close ", label));
+    let inner = block.inner(log_area);
+    frame.render_widget(block, log_area);
+
+    let lines: Vec<Line> = if app.events.is_empty() {
+        vec![Line::from(Span::styled("Nothing has happened yet", label))]
+    } else {
+        app.events.visible(inner.height as usize).map(|event| {
+            let (symbol, color) = match event.kind {
+                EventKind::Strike(weapon) => (weapon.symbol(), weapon_color(weapon)),
+                EventKind::Intercept => ("⛨", Color::Green),
+                EventKind::Retaliation => ("⚠", Color::Red),
+                EventKind::Response => ("✚", Color::Yellow),
+                EventKind::Disaster(hazard) => (hazard.symbol(), Color::Rgb(230, 140, 60)),
+                EventKind::Outbreak => ("☣", weapon_color(WeaponType::Bio)),
+            };
+            let mut spans = vec![
+                Span::styled(format!("{} ", event.time_label()), label),
+                Span::styled(format!("{symbol} "), Style::default().fg(color)),
+                Span::styled(event.text.clone(), Style::default().fg(Color::White)),
+            ];
+            match event.casualties {
+                Some(0) => spans.push(Span::styled(", no casualties", label)),
+                Some(n) => spans.push(Span::styled(format!(", {} casualties", format_casualties(n)), Style::default().fg(Color::Red))),
+                None => {}
+            }
+            Line::from(spans)
+        }).collect()
+    };
+    frame.render_widget(Paragraph::new(lines), inner);
+
+    if let Some(area) = cities_area {
+        render_worst_hit(frame, app, area, usize::MAX);
+    }
+}
+
+/// Cumulative losses per city, worst first, at most `limit` of them
+fn render_worst_hit(frame: &mut Frame, app: &App, area: Rect, limit: usize) {
+    let label = Style::default().fg(Color::DarkGray);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(label)
+        .title(Span::styled(" Worst hit ", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let name_width = (inner.width as usize).saturating_sub(14);
+    let lines: Vec<Line> = app.damaged_cities()
+        .into_iter()
+        .take(limit.min(inner.height as usize))
+        .map(|city| {
+            let lost = city.original_population - city.population;
+            let percent = lost as f64 / city.original_population.max(1) as f64 * 100.0;
+            let name: String = city.name.chars().take(name_width).collect();
+            Line::from(vec![
+                Span::styled(format!("{name:<name_width$}"), Style::default().fg(Color::White)),
+                Span::styled(format!(" {:>6}", format_casualties(lost)), Style::default().fg(Color::Red)),
+                Span::styled(format!(" {percent:>4.0}%"), label),
+            ])
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Stats view: casualties, fires and fallout over the last minute, plus the
+/// ten hardest-hit cities
+fn render_dashboard(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title(Span::styled(" Statistics ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)))
+        .title_bottom(Span::styled(" Tab: back to map ", Style::default().fg(Color::DarkGray)));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let (charts_area, cities_area) = if inner.width > 70 {
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(30), Constraint::Length(WORST_HIT_WIDTH)])
+            .split(inner);
+        (cols[0], Some(cols[1]))
+    } else {
+        (inner, None)
+    };
+
+    let series = &app.dashboard;
+    let charts = [
+        ("Casualties", &series.casualties, Color::Red, (|v| format_casualties(v as u64)) as fn(f64) -> String),
+        ("Burning cells", &series.burning_cells, Color::Rgb(255, 140, 0), |v| format!("{v:.0}")),
+        ("Fallout area", &series.fallout_km2, Color::Rgb(160, 210, 40), |v| format!("{v:.0} km²")),
+    ];
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Ratio(1, 3); 3])
+        .split(charts_area);
+    for ((name, data, color, format), &row) in charts.into_iter().zip(rows.iter()) {
+        let title = Line::from(vec![
+            Span::styled(format!(" {name} "), Style::default().fg(Color::DarkGray)),
+            Span::styled(format(data.latest()), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            Span::styled(format!("  (peak {})", format(data.max())), Style::default().fg(Color::DarkGray)),
+        ]);
+        frame.render_widget(Paragraph::new(title), Rect { height: 1.min(row.height), ..row });
+        let chart = Rect { y: row.y + 1, height: row.height.saturating_sub(1), ..row };
+        render_braille_sparkline(frame.buffer_mut(), &data.resampled(chart.width as usize * 2), chart, color);
+    }
+
+    if let Some(area) = cities_area {
+        render_worst_hit(frame, app, area, 10);
+    }
+}
+
+/// Filled area chart, two samples per character column, scaled to the
+/// largest value
+fn render_braille_sparkline(buf: &mut Buffer, values: &[f64], area: Rect, color: Color) {
+    if area.width == 0 || area.height == 0 {
+        return;
+    }
+    let mut canvas = crate::braille::BrailleCanvas::new(area.width as usize, area.height as usize);
+    let pixel_height = area.height as usize * 4;
+    let max = values.iter().copied().fold(0.0, f64::max);
+    if max > 0.0 {
+        for (x, &v) in values.iter().enumerate() {
+            let filled = ((v / max) * pixel_height as f64).round() as usize;
+            for y in pixel_height - filled.min(pixel_height)..pixel_height {
+                canvas.set_pixel(x, y);
+            }
+        }
+    }
+    for row in 0..area.height as usize {
+        for (col, &b) in canvas.row_raw(row).iter().enumerate() {
+            if b != 0 {
+                let ch = char::from_u32(0x2800 + b as u32).unwrap_or(' ');
+                buf[(area.x + col as u16, area.y + row as u16)].set_char(ch).set_fg(color);
+            }
+        }
+    }
 }
 
 fn render_map(frame: &mut Frame, app: &mut App, area: Rect) {
@@ -62,6 +915,9 @@ fn render_map(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // Render map layers
     let layers = app.map_renderer.render(inner.width as usize, inner.height as usize, projection);
+    app.perf.features = layers.features;
+    // Effect detail shed to keep frames within budget
+    let detail = app.perf.detail;
 
     // Get mouse cursor position for marker
     let cursor_pos = app.mouse_pixel_pos().and_then(|(px, py)| {
@@ -77,29 +933,16 @@ fn render_map(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // Convert explosions to screen coordinates with aggressive culling
     let mut explosions: Vec<ExplosionRender> = Vec::with_capacity(50);
-    let is_globe = matches!(projection, Projection::Globe(_));
     for exp in &app.explosions {
-        // Globe: single project call (no wrapping needed)
-        // Mercator: try wrap offsets
-        let screen_positions: Vec<(i32, i32)> = if is_globe {
-            projection.project_point(exp.lon, exp.lat).into_iter().collect()
-        } else {
-            if let Projection::Mercator(ref vp) = projection {
-                WRAP_OFFSETS.iter().filter_map(|&offset| {
-                    let ((px, py), _) = vp.project_wrapped(exp.lon, exp.lat, offset);
-                    (px >= 0 && py >= 0 && px <= 30000 && py <= 30000).then_some((px, py))
-                }).collect()
-            } else {
-                Vec::new()
-            }
-        };
+        // Every copy on screen (Mercator repeats the world)
+        let screen_positions = projection.view().project_copies(exp.lon, exp.lat);
 
         for (px, py) in screen_positions {
             let cx = (px / 2) as u16;
             let cy = (py / 4) as u16;
 
             let degrees = exp.radius_km / 111.0;
-            let pixels = projection.deg_to_pixels(degrees) as u16;
+            let pixels = projection.deg_to_pixels_at(exp.lon, exp.lat, degrees) as u16;
             let radius = (pixels / 2).max(3);
 
             if radius < 2 {
@@ -122,58 +965,65 @@ fn render_map(frame: &mut Frame, app: &mut App, area: Rect) {
         }
     }
 
-    // Limit max visible explosions (sort by radius descending, show biggest)
+    // Limit max visible explosions (sort by radius descending, show biggest),
+    // halved for each step of detail shed
     const MAX_VISIBLE_EXPLOSIONS: usize = 50;
-    if explosions.len() > MAX_VISIBLE_EXPLOSIONS {
+    let max_explosions = MAX_VISIBLE_EXPLOSIONS >> detail;
+    if explosions.len() > max_explosions {
         explosions.sort_by_key(|e| std::cmp::Reverse(e.radius));
-        explosions.truncate(MAX_VISIBLE_EXPLOSIONS);
+        explosions.truncate(max_explosions);
     }
 
-    // Project gas clouds to screen coordinates
-    let mut gas_clouds: Vec<GasCloudRender> = Vec::with_capacity(app.gas_clouds.len());
-    for cloud in &app.gas_clouds {
-        let screen_positions: Vec<(i32, i32)> = if is_globe {
-            projection.project_point(cloud.lon, cloud.lat).into_iter().collect()
-        } else {
-            if let Projection::Mercator(ref vp) = projection {
-                WRAP_OFFSETS.iter().filter_map(|&offset| {
-                    let ((px, py), _) = vp.project_wrapped(cloud.lon, cloud.lat, offset);
-                    (px >= 0 && py >= 0 && px <= 30000 && py <= 30000).then_some((px, py))
-                }).collect()
-            } else {
-                Vec::new()
-            }
-        };
+    // Project plume puffs (gas clouds, fallout and volcanic ash) to screen
+    // coordinates. Fallout renders through the Nuke channel as a faint
+    // contamination haze, and ash through the Asteroid channel as dust.
+    // With detail shed, only every second or fourth puff is drawn, each
+    // standing in for the ones skipped.
+    let puff_step = 1usize << detail;
+    let plumes = app.gas_clouds.iter()
+        .map(|c| (&c.plume, c.intensity, c.weapon_type))
+        .chain(app.fallout.iter().map(|f| (&f.plume, f.intensity / 2, WeaponType::Nuke)))
+        .chain(app.eruptions.iter().map(|e| (&e.plume, e.intensity, WeaponType::Asteroid)));
+    let mut gas_clouds: Vec<GasCloudRender> = Vec::with_capacity(app.gas_clouds.len() + app.fallout.len() + app.eruptions.len());
+    for (plume, intensity, weapon_type) in plumes {
+        for puff in plume.puffs.iter().step_by(puff_step) {
+            let screen_positions = projection.view().project_copies(puff.lon, puff.lat);
+
+            // Concentration thins as the puff spreads
+            let puff_intensity = (intensity as f32 * puff.weight * puff_step as f32) as u16;
+
+            for (px, py) in screen_positions {
+                let cx = (px / 2) as u16;
+                let cy = (py / 4) as u16;
+
+                let degrees = puff.radius_km / 111.0;
+                let pixels = projection.deg_to_pixels_at(puff.lon, puff.lat, degrees) as u16;
+                let radius = (pixels / 2).max(3);
 
-        for (px, py) in screen_positions {
-            let cx = (px / 2) as u16;
-            let cy = (py / 4) as u16;
+                if radius < 2 { continue; }
 
-            let degrees = cloud.current_radius_km / 111.0;
-            let pixels = projection.deg_to_pixels(degrees) as u16;
-            let radius = (pixels / 2).max(3);
+                let left_edge = cx.saturating_sub(radius);
+                let top_edge = cy.saturating_sub(radius);
+                let right_edge = cx.saturating_add(radius);
+                let bottom_edge = cy.saturating_add(radius);
 
-            if radius < 2 { continue; }
-
-            let left_edge = cx.saturating_sub(radius);
-            let top_edge = cy.saturating_sub(radius);
-            let right_edge = cx.saturating_add(radius);
-            let bottom_edge = cy.saturating_add(radius);
+                if right_edge < 1 || bottom_edge < 1 || left_edge >= inner.width || top_edge >= inner.height {
+                    continue;
+                }
 
-            if right_edge < 1 || bottom_edge < 1 || left_edge >= inner.width || top_edge >= inner.height {
-                continue;
+                gas_clouds.push(GasCloudRender {
+                    x: cx, y: cy, radius, intensity: puff_intensity, weapon_type,
+                    lon: puff.lon, lat: puff.lat, radius_km: puff.radius_km, seed: puff.seed,
+                });
             }
-
-            gas_clouds.push(GasCloudRender {
-                x: cx, y: cy, radius, intensity: cloud.intensity, weapon_type: cloud.weapon_type,
-                lon: cloud.lon, lat: cloud.lat, radius_km: cloud.current_radius_km,
-            });
         }
     }
 
-    // Screen-space fire map: reuse buffers across frames to avoid per-frame allocation
+    // Screen-space fire map: reuse buffers across frames to avoid per-frame
+    // allocation. A row per fill row, each `fire_dots` braille pixels tall.
     let fire_map_width = inner.width as usize;
-    let fire_map_height = inner.height as usize;
+    let fire_map_height = inner.height as usize * app.fill_mode.rows();
+    let fire_dots = 4 / app.fill_mode.rows() as i32;
     let fire_map_size = fire_map_width * fire_map_height;
     if app.fire_map_dims != (fire_map_width, fire_map_height) {
         app.fire_map_intensity = vec![0; fire_map_size];
@@ -199,28 +1049,16 @@ fn render_map(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // Compute viewport bounds for fire culling
     let zoom = projection.effective_zoom();
-    let (vp_min_lon, vp_min_lat, vp_max_lon, vp_max_lat) = if is_globe {
-        if let Projection::Globe(ref g) = projection {
-            let bounds = g.visible_bounds();
-            // Add padding for fire rendering
-            ((bounds.0 - 5.0).max(-180.0), (bounds.1 - 5.0).max(-90.0),
-             (bounds.2 + 5.0).min(180.0), (bounds.3 + 5.0).min(90.0))
+    let (vp_min_lon, vp_min_lat, vp_max_lon, vp_max_lat) = {
+        let bounds = projection.visible_bounds();
+        // Add padding for fire rendering. Longitudes stay unwrapped unless
+        // the whole world is in view.
+        let (min_lon, max_lon) = if bounds.2 - bounds.0 >= 360.0 {
+            (-180.0, 180.0)
         } else {
-            unreachable!()
-        }
-    } else {
-        if let Projection::Mercator(ref vp) = projection {
-            let half_width_deg = 180.0 / vp.zoom;
-            let min_lon = vp.center_lon - half_width_deg * 1.5;
-            let max_lon = vp.center_lon + half_width_deg * 1.5;
-            let (_, top_lat) = vp.unproject(0, 0);
-            let (_, bottom_lat) = vp.unproject(0, vp.height as i32);
-            let lat_pad = (top_lat - bottom_lat).abs() * 0.25;
-            ((min_lon), (bottom_lat - lat_pad).max(-90.0),
-             (max_lon), (top_lat + lat_pad).min(90.0))
-        } else {
-            unreachable!()
-        }
+            (bounds.0 - 5.0, bounds.2 + 5.0)
+        };
+        (min_lon, (bounds.1 - 5.0).max(-90.0), max_lon, (bounds.3 + 5.0).min(90.0))
     };
 
     // Hierarchical fire rendering based on zoom:
@@ -229,17 +1067,14 @@ fn render_map(frame: &mut Frame, app: &mut App, area: Rect) {
     // - Individual fires for high zoom (avoids blocky grid artifacts)
     let deg_per_char = 360.0 / (zoom * inner.width as f64);
 
-    if deg_per_char < 0.25 {
-        // High zoom: render individual fires for organic, scattered appearance.
-        // Bilinear land_fraction fades intensity near coastlines to avoid the
-        // bitmap staircase from the land grid.
-        for fire in &app.fires {
-            if fire.lat < vp_min_lat || fire.lat > vp_max_lat {
-                continue;
-            }
+    if deg_per_char < 0.25 && detail == 0 {
+        // High zoom: scatter sparks through each burning cell for an organic
+        // look. Bilinear land_fraction fades intensity near coastlines to
+        // avoid the bitmap staircase from the land grid.
+        for fire in app.fires.sparks_in(vp_min_lon, vp_min_lat, vp_max_lon, vp_max_lat) {
             if let Some((px, py)) = projection.project_point(fire.lon, fire.lat) {
                 let cx = px / 2;
-                let cy = py / 4;
+                let cy = py / fire_dots;
                 if cx >= 0 && cy >= 0 {
                     let frac = app.map_renderer.land_fraction(fire.lon, fire.lat);
                     let intensity = (fire.intensity as f64 * frac) as u8;
@@ -250,7 +1085,7 @@ fn render_map(frame: &mut Frame, app: &mut App, area: Rect) {
             }
         }
     } else {
-        let grid = if deg_per_char >= 1.0 { &app.fire_grid } else { &app.fire_grid_fine };
+        let grid = if deg_per_char >= 1.0 || detail >= 2 { &app.fires.coarse } else { &app.fires.fine };
         let res = grid.resolution;
 
         let cell_dots_h = projection.deg_to_pixels(res);
@@ -264,7 +1099,7 @@ fn render_map(frame: &mut Frame, app: &mut App, area: Rect) {
         ) {
             (Some((_, y0)), Some((_, y1))) => {
                 let cell_dots_v = (y1 - y0).unsigned_abs() as f64;
-                ((cell_dots_v / 4.0 - 1.0) / 2.0).max(0.0).ceil() as i32
+                ((cell_dots_v / fire_dots as f64 - 1.0) / 2.0).max(0.0).ceil() as i32
             }
             _ => 0,
         };
@@ -272,19 +1107,17 @@ fn render_map(frame: &mut Frame, app: &mut App, area: Rect) {
         let mut fires_data = grid.fires_in_region(
             vp_min_lon.max(-180.0), vp_min_lat, vp_max_lon.min(180.0), vp_max_lat,
         );
-        if !is_globe {
-            if vp_min_lon < -180.0 {
-                fires_data.extend(grid.fires_in_region(vp_min_lon + 360.0, vp_min_lat, 180.0, vp_max_lat));
-            }
-            if vp_max_lon > 180.0 {
-                fires_data.extend(grid.fires_in_region(-180.0, vp_min_lat, vp_max_lon - 360.0, vp_max_lat));
-            }
+        if vp_min_lon < -180.0 {
+            fires_data.extend(grid.fires_in_region(vp_min_lon + 360.0, vp_min_lat, 180.0, vp_max_lat));
+        }
+        if vp_max_lon > 180.0 {
+            fires_data.extend(grid.fires_in_region(-180.0, vp_min_lat, vp_max_lon - 360.0, vp_max_lat));
         }
 
         for (lon, lat, intensity, weapon) in fires_data {
             if let Some((px, py)) = projection.project_point(lon, lat) {
-                let cx = (px / 2) as i32;
-                let cy = (py / 4) as i32;
+                let cx = px / 2;
+                let cy = py / fire_dots;
                 for dy in -pad_y..=pad_y {
                     for dx in -pad_x..=pad_x {
                         let fx = cx + dx;
@@ -318,14 +1151,296 @@ fn render_map(frame: &mut Frame, app: &mut App, area: Rect) {
         projection.unproject(cx as i32 * 2, cy as i32 * 4)
     });
 
-    // Blast radius in km (EMP is 1.5× wider)
-    let cursor_blast_km = {
-        let base_radius = 50.0 + 700.0 / zoom;
-        match app.active_weapon {
-            WeaponType::Emp => base_radius * 1.5,
-            _ => base_radius,
+    // Sun glint: the highlight sits where the surface faces halfway
+    // between the sun and the viewer
+    let glint = (app.show_glint && projection.is_globe()).then(|| {
+        let (sun_lon, sun_lat) = app.clock.subsolar_point();
+        let (lon, lat) = projection.view().center();
+        (lonlat_to_vec3(sun_lon, sun_lat) + lonlat_to_vec3(lon, lat)).normalize_or_zero()
+    });
+
+    // Blast radius in km for the active weapon
+    let cursor_blast_km = app.blast_radius_km(app.active_weapon);
+
+    // Per-cell environment from one unproject pass: day/night shade
+    // (0 = day, 1 = twilight, 2 = night; civil twilight marks the terminator
+    // band), weather cover as (cloud, rain), faction border tint, ground dose,
+    // craters, burn scars and population density. The ground overlays get a sample per
+    // fill row, so two per cell with half blocks.
+    let country_grid = app.map_renderer.country_grid.as_ref().filter(|_| app.map_renderer.settings.show_factions);
+    let show_radiation = app.show_radiation && !app.radiation.is_empty();
+    let show_scars = app.show_scars && !app.burn_scars.is_empty();
+    let show_craters = !app.craters.is_empty();
+    // Painted as a sixel image instead, when the terminal can show one
+    let sixel = app.graphics == Some(Protocol::Sixel);
+    let population = app.population.as_ref().filter(|_| app.show_population && !sixel);
+    let heatmaps: Vec<&HeatmapLayer> = match sixel {
+        true => Vec::new(),
+        false => app.heatmaps.shown().collect(),
+    };
+    let fill_rows = app.fill_mode.rows();
+    // Weather is the last effect to go when detail is shed
+    let show_weather = app.show_weather && detail < perf::MIN_DETAIL;
+    let per_cell = app.show_night || show_weather || country_grid.is_some() || show_radiation || show_scars || show_craters
+        || population.is_some() || !heatmaps.is_empty();
+    let (night_shade, weather_cells, faction_cells, radiation_cells, crater_cells, scar_cells, density_cells, heatmap_cells) = if per_cell {
+        let (sun_lon, sun_lat) = app.clock.subsolar_point();
+        let (w, h) = (inner.width as usize, inner.height as usize);
+        let mut shade = vec![0u8; w * h];
+        let mut weather = vec![(0u8, 0u8); w * h];
+        let mut factions: Vec<Option<Faction>> = vec![None; w * h];
+        let mut dose = vec![0f32; w * h * fill_rows];
+        let mut craters = vec![None; w * h * fill_rows];
+        let mut scars = vec![0u8; w * h * fill_rows];
+        let mut density = vec![0f32; w * h * fill_rows];
+        let mut heat = vec![None; w * h * fill_rows];
+        let ground = show_radiation || show_scars || show_craters || population.is_some() || !heatmaps.is_empty();
+        for cy in 0..h {
+            for cx in 0..w {
+                let center = projection.unproject(cx as i32 * 2 + 1, cy as i32 * 4 + 2);
+                let idx = cy * w + cx;
+                if let Some((lon, lat)) = center {
+                    if app.show_night {
+                        let elevation = sun_elevation_from(sun_lon, sun_lat, lon, lat);
+                        shade[idx] = if elevation < -6.0 { 2 } else if elevation < 0.0 { 1 } else { 0 };
+                    }
+                    if show_weather {
+                        weather[idx] = (app.weather.cloud_at(lon, lat), app.weather.rain_at(lon, lat));
+                    }
+                    if let Some(grid) = country_grid {
+                        factions[idx] = grid.country_at(lon, lat).map(Faction::of);
+                    }
+                }
+                if !ground {
+                    continue;
+                }
+                for sub in 0..fill_rows {
+                    let sample = if fill_rows == 1 {
+                        center
+                    } else {
+                        projection.unproject(cx as i32 * 2 + 1, cy as i32 * 4 + (4 * sub as i32 + 2) / fill_rows as i32)
+                    };
+                    let Some((lon, lat)) = sample else { continue };
+                    let i = (cy * fill_rows + sub) * w + cx;
+                    if show_radiation {
+                        dose[i] = app.radiation.dose_at(lon, lat);
+                    }
+                    if show_craters {
+                        craters[i] = app.craters.iter().filter_map(|c| c.reach(lon, lat)).min_by(f64::total_cmp);
+                    }
+                    if show_scars {
+                        scars[i] = app.burn_scars.burn_at(lon, lat);
+                    }
+                    if let Some(grid) = population {
+                        density[i] = grid.density_at(lon, lat);
+                    }
+                    heat[i] = heatmaps.iter().find_map(|h| h.fill_at(lon, lat));
+                }
+            }
         }
+        (
+            app.show_night.then_some(shade),
+            show_weather.then_some(weather),
+            country_grid.is_some().then_some(factions),
+            show_radiation.then_some(dose),
+            show_craters.then_some(craters),
+            show_scars.then_some(scars),
+            population.is_some().then_some(density),
+            (!heatmaps.is_empty()).then_some(heat),
+        )
+    } else {
+        (None, None, None, None, None, None, None, None)
+    };
+
+    // Missile trails: sample the most recent stretch of each arc (oldest
+    // first, head last) so the trail follows the great circle on both projections
+    const TRAIL_SAMPLES: usize = 24;
+    const TRAIL_LENGTH: f64 = 0.35;
+    // An asteroid's whole fall stays lit as one long streak
+    const ENTRY_TRAIL_LENGTH: f64 = 0.8;
+    const MIRV_RELEASE_FLASH_FRAMES: u16 = 8;
+    let to_cell = |lon: f64, lat: f64| {
+        let (px, py) = projection.project_point(lon, lat)?;
+        let (cx, cy) = (px / 2, py / 4);
+        (cx >= 0 && cy >= 0 && cx < inner.width as i32 && cy < inner.height as i32)
+            .then_some((cx as u16, cy as u16))
     };
+    let projectiles: Vec<ProjectileRender> = app.projectiles.iter().map(|p| {
+        let t = p.progress();
+        let length = if p.weapon_type == WeaponType::Asteroid { ENTRY_TRAIL_LENGTH } else { TRAIL_LENGTH };
+        let start = (t - length).max(0.0);
+        let trail = (0..=TRAIL_SAMPLES).filter_map(|i| {
+            let f = i as f64 / TRAIL_SAMPLES as f64;
+            let (lon, lat) = p.position_at(start + (t - start) * f);
+            to_cell(lon, lat).map(|(x, y)| (x, y, f as f32))
+        }).collect();
+        // A MIRV's warheads flash where they leave the bus for a moment
+        let release = p.released_at
+            .filter(|_| p.frame < MIRV_RELEASE_FLASH_FRAMES)
+            .and_then(|(lon, lat)| to_cell(lon, lat));
+        ProjectileRender { trail, weapon_type: p.weapon_type, hostile: p.hostile, release }
+    }).collect();
+
+    let silos: Vec<(u16, u16, bool)> = app.silos.iter().enumerate()
+        .filter_map(|(i, site)| {
+            to_cell(site.lon, site.lat).map(|(x, y)| (x, y, app.selected_silo == Some(i)))
+        })
+        .collect();
+
+    let defenses: Vec<(u16, u16, bool)> = app.defenses.iter()
+        .filter_map(|d| to_cell(d.lon, d.lat).map(|(x, y)| (x, y, d.is_ready())))
+        .collect();
+    let infection: Vec<(u16, u16, f32)> = if app.show_infection {
+        app.infected.iter()
+            .filter_map(|&idx| app.map_renderer.city_grid.get(idx))
+            .filter_map(|c| to_cell(c.lon, c.lat).map(|(x, y)| (x, y, c.infection)))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    // A storm whose eye is off screen still shows the bands that reach in
+    let hurricanes: Vec<HurricaneRender> = app.hurricanes.iter()
+        .filter_map(|storm| {
+            let (px, py) = projection.project_point(storm.lon, storm.lat)?;
+            let pixels = projection.deg_to_pixels_at(storm.lon, storm.lat, Hurricane::RADIUS_KM / 111.0);
+            let radius = (pixels / 2.0).max(3.0) as f32;
+            let (x, y) = (px.div_euclid(2), py.div_euclid(4));
+            let reach = radius.ceil() as i32;
+            let on_screen = x + reach >= 0 && x - reach < inner.width as i32
+                && y + reach / 2 >= 0 && y - reach / 2 < inner.height as i32;
+            on_screen.then_some(HurricaneRender { x, y, radius, strength: storm.strength as f32, north: storm.lat >= 0.0 })
+        })
+        .collect();
+    let tsunami_front: Vec<(u16, u16, f32)> = app.tsunamis.iter()
+        .flat_map(|wave| {
+            let amplitude = wave.amplitude() as f32;
+            wave.front().filter_map(move |(lon, lat)| to_cell(lon, lat).map(|(x, y)| (x, y, amplitude)))
+        })
+        .collect();
+    let interceptions: Vec<(u16, u16, u8, bool)> = app.interceptions.iter()
+        .filter_map(|b| to_cell(b.lon, b.lat).map(|(x, y)| (x, y, b.frame, b.hit)))
+        .collect();
+
+    // Refugee streams, with knots of people drifting along toward the city
+    // taking them in
+    const REFUGEE_SAMPLES: u64 = 32;
+    let drift = REFUGEE_SAMPLES - app.frame / 6 % REFUGEE_SAMPLES;
+    let refugee_streams: Vec<(u16, u16, bool)> = app.refugees.iter()
+        .flat_map(|flow| {
+            (1..REFUGEE_SAMPLES).filter_map(move |i| {
+                let (lon, lat) = flow.point_at(i as f64 / REFUGEE_SAMPLES as f64);
+                to_cell(lon, lat).map(|(x, y)| (x, y, (i + drift).is_multiple_of(8)))
+            })
+        })
+        .collect();
+
+    // Submarine and its course to a new station
+    let submarine = to_cell(app.submarine.lon, app.submarine.lat);
+    let mut platform_routes: Vec<(u16, u16, Color)> = Vec::new();
+    if let Some((dlon, dlat)) = app.submarine.destination {
+        let sub = &app.submarine;
+        for i in 1..=16 {
+            let (lon, lat) = great_circle_point(sub.lon, sub.lat, dlon, dlat, i as f64 / 16.0);
+            platform_routes.extend(to_cell(lon, lat).map(|(x, y)| (x, y, Color::Rgb(40, 80, 130))));
+        }
+    }
+
+    // Bombers: flown trail behind, planned great-circle leg ahead
+    let bombers: Vec<BomberRender> = app.bombers.iter().map(|b| {
+        for &(lon, lat) in &b.trail {
+            platform_routes.extend(to_cell(lon, lat).map(|(x, y)| (x, y, Color::Rgb(90, 90, 90))));
+        }
+        for i in 1..=20 {
+            let (lon, lat) = great_circle_point(b.lon, b.lat, b.target.0, b.target.1, i as f64 / 20.0);
+            if i % 2 == 0 {
+                platform_routes.extend(to_cell(lon, lat).map(|(x, y)| (x, y, Color::Rgb(110, 60, 40))));
+            }
+        }
+        BomberRender {
+            pos: to_cell(b.lon, b.lat),
+            target: to_cell(b.target.0, b.target.1),
+            weapon_type: b.weapon_type,
+        }
+    }).collect();
+
+    // Engagement range rings while placing batteries (geographic, so globe-correct)
+    let mut defense_rings = Vec::new();
+    if app.placing_defense {
+        let radius_deg = DefenseSite::RANGE_KM / 111.0;
+        for d in &app.defenses {
+            let cos_lat = d.lat.to_radians().cos().max(0.1);
+            for i in 0..96u32 {
+                let angle = (i as f64 / 96.0) * std::f64::consts::TAU;
+                let lon = d.lon + radius_deg * angle.cos() / cos_lat;
+                let lat = (d.lat + radius_deg * angle.sin()).clamp(-89.9, 89.9);
+                defense_rings.extend(to_cell(lon, lat));
+            }
+        }
+    }
+
+    // Pattern strike preview: the line or box being laid and where its
+    // blasts would land
+    let mut strike_outline = Vec::new();
+    let mut strike_targets = Vec::new();
+    if let Some(PlannedStrike { from, to, targets }) = app.planned_strikes() {
+        if app.reticle == Reticle::Corridor {
+            for i in 0..=48 {
+                let (lon, lat) = great_circle_point(from.0, from.1, to.0, to.1, i as f64 / 48.0);
+                strike_outline.extend(to_cell(lon, lat));
+            }
+        } else {
+            let dlon = wrap_lon(to.0 - from.0);
+            for i in 0..=48 {
+                let f = i as f64 / 48.0;
+                let lon = wrap_lon(from.0 + dlon * f);
+                let lat = from.1 + (to.1 - from.1) * f;
+                strike_outline.extend(to_cell(lon, from.1));
+                strike_outline.extend(to_cell(lon, to.1));
+                strike_outline.extend(to_cell(from.0, lat));
+                strike_outline.extend(to_cell(to.0, lat));
+            }
+        }
+        strike_targets.extend(targets.iter().filter_map(|&(lon, lat)| to_cell(lon, lat)));
+    }
+
+    // Strike queue markers, numbered in launch order
+    let queued: Vec<(u16, u16, usize, WeaponType)> = app.strike_queue.iter().enumerate()
+        .filter_map(|(i, t)| to_cell(t.lon, t.lat).map(|(x, y)| (x, y, i + 1, t.weapon)))
+        .collect();
+
+    // Registered overlays: lines onto a braille canvas per color, points
+    // and labels to cells
+    let mut overlay_lines: Vec<(Color, BrailleCanvas)> = Vec::new();
+    let mut overlay_points: Vec<(u16, u16, char, Color)> = Vec::new();
+    let mut overlay_labels: Vec<(u16, u16, String, Color)> = Vec::new();
+    let mut overlay_pulses: Vec<PulseRender> = Vec::new();
+    let flights = app.show_flights.then(|| flight_routes(app, projection, inner));
+    for shapes in flights.into_iter().chain(app.overlays.shapes(projection.view())) {
+        for line in shapes.lines {
+            let canvas = match overlay_lines.iter().position(|(color, _)| *color == line.color) {
+                Some(i) => &mut overlay_lines[i].1,
+                None => {
+                    overlay_lines.push((line.color, BrailleCanvas::new(inner.width as usize, inner.height as usize)));
+                    &mut overlay_lines.last_mut().unwrap().1
+                }
+            };
+            let line = LineString::new(line.points);
+            projection.view().segments(&line, &mut |(x0, y0), (x1, y1)| draw_line(canvas, x0, y0, x1, y1));
+        }
+        overlay_points.extend(shapes.points.iter().filter_map(|p| to_cell(p.lon, p.lat).map(|(x, y)| (x, y, p.glyph, p.color))));
+        overlay_labels.extend(shapes.labels.into_iter().filter_map(|l| to_cell(l.lon, l.lat).map(|(x, y)| (x, y, l.text, l.color))));
+        // Sized like explosions, so a ring covers the same ground at any zoom
+        overlay_pulses.extend(shapes.pulses.iter().filter_map(|p| {
+            let (x, y) = to_cell(p.lon, p.lat)?;
+            let px = projection.deg_to_pixels_at(p.lon, p.lat, p.radius_km / 111.0) / 2.0;
+            let radius = (px.min(inner.width.max(inner.height) as f64) as u16).max(2);
+            Some(PulseRender { x, y, radius, lon: p.lon, lat: p.lat, radius_km: p.radius_km, color: p.color })
+        }));
+    }
+    if overlay_pulses.len() > MAX_OVERLAY_PULSES {
+        overlay_pulses.sort_by_key(|p| std::cmp::Reverse(p.radius));
+        overlay_pulses.truncate(MAX_OVERLAY_PULSES);
+    }
 
     // Render braille map
     let map_widget = MapWidget {
@@ -341,6 +1456,41 @@ fn render_map(frame: &mut Frame, app: &mut App, area: Rect) {
         inner_height: inner.height,
         frame: app.frame,
         projection,
+        glint,
+        land: app.map_renderer.land_grid.as_ref(),
+        fill_rows,
+        night_shade,
+        weather_cells,
+        faction_cells,
+        radiation_cells,
+        crater_cells,
+        scar_cells,
+        density_cells,
+        heatmap_cells,
+        projectiles,
+        silos,
+        defenses,
+        interceptions,
+        defense_rings,
+        strike_outline,
+        strike_targets,
+        queued,
+        overlay_lines,
+        overlay_points,
+        overlay_labels,
+        overlay_pulses,
+        submarine,
+        bombers,
+        platform_routes,
+        refugee_streams,
+        dust: app.dust as f32,
+        desaturation: app.climate.desaturation(),
+        infection,
+        tsunami_front,
+        hurricanes,
+        theme: app.theme,
+        backdrop: &mut app.backdrop,
+        image_basemap: app.graphics == Some(Protocol::Kitty),
     };
     frame.render_widget(map_widget, inner);
 }
@@ -357,10 +1507,51 @@ struct ExplosionRender {
     radius_km: f64,
 }
 
+/// An overlay's pulsing ring to render
+struct PulseRender {
+    x: u16,
+    y: u16,
+    radius: u16, // Visual radius in chars
+    lon: f64,
+    lat: f64,
+    radius_km: f64,
+    color: Color,
+}
+
+/// A missile in flight: visible trail cells as (x, y, age) where age runs
+/// 0.0 (tail) → 1.0 (warhead)
+struct ProjectileRender {
+    trail: Vec<(u16, u16, f32)>,
+    weapon_type: WeaponType,
+    /// Inbound adversary missile — drawn in a colder, angrier trail
+    hostile: bool,
+    /// Where a MIRV warhead just left its bus
+    release: Option<(u16, u16)>,
+}
+
+/// A hurricane's eye and the reach of its bands, in cells. The eye may
+/// be off screen.
+struct HurricaneRender {
+    x: i32,
+    y: i32,
+    radius: f32,
+    strength: f32,
+    /// Northern storms turn anticlockwise, southern ones clockwise
+    north: bool,
+}
+
+/// A bomber in flight
+struct BomberRender {
+    pos: Option<(u16, u16)>,
+    target: Option<(u16, u16)>,
+    weapon_type: WeaponType,
+}
+
 /// A fire to render
 #[derive(Clone, Copy)]
 struct FireRender {
     x: u16,
+    /// Fill row: the character row, or its half with half blocks
     y: u16,
     intensity: u8,
     weapon_type: WeaponType,
@@ -376,6 +1567,8 @@ struct GasCloudRender {
     lon: f64,
     lat: f64,
     radius_km: f64,
+    /// Stable texture seed — puffs move every tick, so position can't be the key
+    seed: u64,
 }
 
 /// Custom widget that renders braille map with text labels overlaid
@@ -392,12 +1585,264 @@ struct MapWidget<'a> {
     inner_height: u16,
     frame: u64,
     projection: &'a Projection,
+    /// Half-vector between sun and viewer for the ocean glint, globe only
+    glint: Option<DVec3>,
+    /// Land mask, to keep the glint on water
+    land: Option<&'a LandGrid>,
+    /// Rows of fire and ground samples per character row (2 for half blocks)
+    fill_rows: usize,
+    night_shade: Option<Vec<u8>>,
+    weather_cells: Option<Vec<(u8, u8)>>,
+    /// Owning faction per cell, for tinting borders
+    faction_cells: Option<Vec<Option<Faction>>>,
+    /// Ground radiation dose per fill row of each cell (Gy)
+    radiation_cells: Option<Vec<f32>>,
+    /// Distance from the nearest crater in crater radii, per fill row of each cell
+    crater_cells: Option<Vec<Option<f64>>>,
+    /// Hottest past burn per fill row of each cell (0 = unburned)
+    scar_cells: Option<Vec<u8>>,
+    /// Population density per fill row of each cell (people/km²)
+    density_cells: Option<Vec<f32>>,
+    /// Shade of the first shown heatmap with data, per fill row of each cell
+    heatmap_cells: Option<Vec<Option<(char, Rgb)>>>,
+    projectiles: Vec<ProjectileRender>,
+    /// Launch sites as (x, y, selected)
+    silos: Vec<(u16, u16, bool)>,
+    /// ABM batteries as (x, y, ready)
+    defenses: Vec<(u16, u16, bool)>,
+    /// Interceptor bursts as (x, y, frame, hit)
+    interceptions: Vec<(u16, u16, u8, bool)>,
+    defense_rings: Vec<(u16, u16)>,
+    /// Line or box of a pattern strike being laid
+    strike_outline: Vec<(u16, u16)>,
+    /// Where the pattern's blasts would land
+    strike_targets: Vec<(u16, u16)>,
+    /// Strike queue as (x, y, number, weapon)
+    queued: Vec<(u16, u16, usize, WeaponType)>,
+    /// Registered overlays' lines, a canvas per color
+    overlay_lines: Vec<(Color, BrailleCanvas)>,
+    /// Overlay points as (x, y, glyph, color)
+    overlay_points: Vec<(u16, u16, char, Color)>,
+    /// Overlay labels as (x, y, text, color)
+    overlay_labels: Vec<(u16, u16, String, Color)>,
+    /// Registered overlays' pulsing rings
+    overlay_pulses: Vec<PulseRender>,
+    submarine: Option<(u16, u16)>,
+    bombers: Vec<BomberRender>,
+    /// Dotted courses (submarine heading, bomber trails and legs)
+    platform_routes: Vec<(u16, u16, Color)>,
+    /// Refugee streams as (x, y, people passing)
+    refugee_streams: Vec<(u16, u16, bool)>,
+    /// Global dust veil (0–1)
+    dust: f32,
+    /// Nuclear-winter palette washout (0–1)
+    desaturation: f32,
+    /// Infected cities as (x, y, infected fraction)
+    infection: Vec<(u16, u16, f32)>,
+    /// Tsunami wavefront cells as (x, y, wave height)
+    tsunami_front: Vec<(u16, u16, f32)>,
+    hurricanes: Vec<HurricaneRender>,
+    theme: &'a Theme,
+    backdrop: &'a mut Backdrop,
+    /// The basemap is an image beneath the cells, so the backdrop stays
+    /// empty for it to show through
+    image_basemap: bool,
+}
+
+/// The map's static backdrop — sky and line layers — as last composited.
+/// Drawing it walks every canvas for every cell; while nothing it depends
+/// on has changed, which is most frames when only overlays are animating,
+/// the cells are copied over instead. A resize, a pan or projection change,
+/// or a change in shading redraws it in full.
+#[derive(Default)]
+pub struct Backdrop {
+    key: Option<u64>,
+    cells: Buffer,
+    /// Frames served from the copy, for tests
+    hits: u64,
+}
+
+/// Fold a per-cell overlay into a fingerprint
+fn fingerprint<T>(seed: u64, cells: Option<&[T]>, value: impl Fn(&T) -> u64) -> u64 {
+    cells.map_or(seed, |cells| cells.iter().fold(hash2(seed, 1), |h, c| hash2(h, value(c))))
+}
+
+/// Scale a layer color's brightness (night side, cloud cover). Named colors
+/// are mapped to approximate RGB so they can be scaled.
+fn dim_color(color: Color, factor: f32) -> Color {
+    if factor >= 0.999 {
+        return color;
+    }
+    let Some((r, g, b)) = approx_rgb(color) else { return color };
+    let scale = |c: u8| (c as f32 * factor) as u8;
+    Color::Rgb(scale(r), scale(g), scale(b))
+}
+
+/// Pull a layer color toward gray (soot-darkened sky). 0 = unchanged, 1 = gray.
+fn desaturate(color: Color, amount: f32) -> Color {
+    if amount <= 0.001 {
+        return color;
+    }
+    let Some((r, g, b)) = approx_rgb(color) else { return color };
+    let luma = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+    let mix = |c: u8| (c as f32 + (luma - c as f32) * amount) as u8;
+    Color::Rgb(mix(r), mix(g), mix(b))
 }
 
-impl<'a> MapWidget<'a> {
+fn approx_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        Color::Cyan => Some((0, 205, 205)),
+        Color::Yellow => Some((205, 205, 0)),
+        Color::DarkGray => Some((110, 110, 110)),
+        _ => None,
+    }
+}
+
+impl<'a> MapWidget<'a> {
+    /// Brightness factor for map lines in a cell: night and cloud both dim.
+    fn visibility(&self, idx: usize) -> f32 {
+        let night = match self.night_shade.as_ref().and_then(|s| s.get(idx)) {
+            Some(1) => 0.7,
+            Some(2) => 0.45,
+            _ => 1.0,
+        };
+        let cloud = self.weather_cells.as_ref().and_then(|w| w.get(idx)).map_or(0, |c| c.0);
+        night * (1.0 - cloud as f32 / 255.0 * 0.45) * (1.0 - self.dust * 0.5)
+    }
+
+    /// Green halo around infected cities in empty cells, growing with the
+    /// infected fraction. Cells are about twice as tall as wide.
+    fn render_infection(&self, area: Rect, buf: &mut Buffer) {
+        for &(x, y, infection) in &self.infection {
+            let r = 1.0 + infection * 3.0;
+            let (ri, rx) = (r.ceil() as i32, (r * 2.0).ceil() as i32);
+            for dy in -ri..=ri {
+                for dx in -rx..=rx {
+                    let (cx, cy) = (x as i32 + dx, y as i32 + dy);
+                    if cx < 0 || cy < 0 || cx >= area.width as i32 || cy >= area.height as i32 {
+                        continue;
+                    }
+                    let dist = ((dx as f32 / 2.0).powi(2) + (dy * dy) as f32).sqrt() / r;
+                    if dist > 1.0 {
+                        continue;
+                    }
+                    let cell = &mut buf[(area.x + cx as u16, area.y + cy as u16)];
+                    if cell.symbol() != " " {
+                        continue;
+                    }
+                    let density = (1.0 - dist) * (0.4 + infection * 0.6);
+                    let ch = if density > 0.5 { '▒' } else { '░' };
+                    let g = (90.0 + density * 165.0) as u8;
+                    cell.set_char(ch).set_fg(Color::Rgb(g / 5, g, g / 4));
+                }
+            }
+        }
+    }
+
+    /// Radiation, then scorched earth, then population, then heatmaps, each
+    /// in whatever cells the ones before left empty. With half blocks the samples hold
+    /// two rows per cell and each half gets its own color.
+    fn render_ground(&self, area: Rect, buf: &mut Buffer) {
+        let w = area.width as usize;
+        let mut canvas = (self.fill_rows == 2).then(|| HalfBlockCanvas::new(w, area.height as usize));
+        let mut paint = |idx: usize, fill: Option<(char, Rgb)>| {
+            let Some((ch, (r, g, b))) = fill else { return };
+            match canvas.as_mut() {
+                Some(canvas) => canvas.fill(idx % w, idx / w, (r, g, b)),
+                None => {
+                    let cell = &mut buf[(area.x + (idx % w) as u16, area.y + (idx / w) as u16)];
+                    if cell.symbol() == " " {
+                        cell.set_char(ch).set_fg(Color::Rgb(r, g, b));
+                    }
+                }
+            }
+        };
+        for (idx, &dose) in self.radiation_cells.iter().flatten().enumerate() {
+            paint(idx, radiation_fill(dose));
+        }
+        for (idx, &reach) in self.crater_cells.iter().flatten().enumerate() {
+            paint(idx, reach.and_then(crater_fill));
+        }
+        for (idx, &burn) in self.scar_cells.iter().flatten().enumerate() {
+            paint(idx, scar_fill(burn));
+        }
+        for (idx, &density) in self.density_cells.iter().flatten().enumerate() {
+            paint(idx, population_fill(density));
+        }
+        for (idx, &fill) in self.heatmap_cells.iter().flatten().enumerate() {
+            paint(idx, fill);
+        }
+        if let Some(canvas) = &canvas {
+            draw_halfblocks(canvas, area, buf, true);
+        }
+    }
+
+    /// Cloud stipple and falling rain in empty cells, beneath fires and effects.
+    fn render_weather(&self, area: Rect, buf: &mut Buffer) {
+        let Some(cells) = self.weather_cells.as_ref() else { return };
+        let w = area.width as usize;
+        // Rain falls one row every 3 frames
+        let fall = self.frame / 3;
+        for (idx, &(cloud, rain)) in cells.iter().enumerate() {
+            if cloud < 100 {
+                continue;
+            }
+            let (col, row) = ((idx % w) as u16, (idx / w) as u16);
+            let cell = &mut buf[(area.x + col, area.y + row)];
+            if cell.symbol() != " " {
+                continue;
+            }
+            let seed = hash3(col as u64, (row as u64).wrapping_sub(fall), 0xC10D);
+            if rain > 80 && seed.is_multiple_of(5) {
+                cell.set_char('/').set_fg(Color::Rgb(60, 100, 150));
+            } else if hash2(col as u64, row as u64) % 256 < cloud as u64 / 3 {
+                let ch = if cloud > 200 { '░' } else { '·' };
+                cell.set_char(ch).set_fg(Color::Rgb(55, 55, 65));
+            }
+        }
+    }
+
     /// Render a braille canvas layer with a specific color.
     /// Reads raw bytes directly — zero String allocations per frame.
     fn render_layer(&self, canvas: &crate::braille::BrailleCanvas, color: Color, area: Rect, buf: &mut Buffer) {
+        self.render_layer_with(canvas, |_| color, area, buf);
+    }
+
+    /// Render a layer tinted by the faction owning each cell (`fallback` elsewhere)
+    fn render_faction_layer(&self, canvas: &crate::braille::BrailleCanvas, fallback: Color, area: Rect, buf: &mut Buffer) {
+        let Some(factions) = self.faction_cells.as_ref() else {
+            return self.render_layer(canvas, fallback, area, buf);
+        };
+        self.render_layer_with(canvas, |idx| match factions.get(idx).copied().flatten() {
+            Some(f) => {
+                let (r, g, b) = f.color();
+                Color::Rgb(r, g, b)
+            }
+            None => fallback,
+        }, area, buf);
+    }
+
+    /// Tint the background of the cells a fill canvas mostly covers;
+    /// `color_at` gives None to leave a cell be
+    fn render_fill(&self, canvas: &crate::braille::BrailleCanvas, color_at: impl Fn(usize) -> Option<Color>, area: Rect, buf: &mut Buffer) {
+        let rows = canvas.char_height().min(area.height as usize);
+        for row_idx in 0..rows {
+            let y = area.y + row_idx as u16;
+            for (col_idx, &b) in canvas.row_raw(row_idx).iter().enumerate().take(area.width as usize) {
+                if b.count_ones() < 4 {
+                    continue;
+                }
+                let idx = row_idx * area.width as usize + col_idx;
+                if let Some(color) = color_at(idx) {
+                    let color = desaturate(color, self.desaturation);
+                    buf[(area.x + col_idx as u16, y)].set_bg(dim_color(color, self.visibility(idx)));
+                }
+            }
+        }
+    }
+
+    fn render_layer_with(&self, canvas: &crate::braille::BrailleCanvas, color_at: impl Fn(usize) -> Color, area: Rect, buf: &mut Buffer) {
         let rows = canvas.char_height().min(area.height as usize);
         for row_idx in 0..rows {
             let y = area.y + row_idx as u16;
@@ -408,76 +1853,185 @@ impl<'a> MapWidget<'a> {
                 if b == 0 { continue; } // skip empty
                 let ch = unsafe { char::from_u32_unchecked(0x2800 + b as u32) };
                 let x = area.x + col_idx as u16;
-                buf[(x, y)].set_char(ch).set_fg(color);
+                let idx = row_idx * area.width as usize + col_idx;
+                let color = desaturate(color_at(idx), self.desaturation);
+                buf[(x, y)].set_char(ch).set_fg(dim_color(color, self.visibility(idx)));
             }
         }
     }
+
+    /// Everything the backdrop's cells depend on, hashed
+    fn backdrop_key(&self, area: Rect) -> u64 {
+        let mut key = hash3(self.layers.generation, area.width as u64, area.height as u64);
+        key = hash3(key, area.x as u64, area.y as u64);
+        for v in self.glint.map_or([0.0; 3], |g| g.to_array()) {
+            key = hash2(key, v.to_bits());
+        }
+        key = hash3(key, self.dust.to_bits() as u64, self.desaturation.to_bits() as u64);
+        key = self.theme.name.bytes().fold(key, |h, b| hash2(h, b as u64));
+        key = fingerprint(key, self.night_shade.as_deref(), |&s| s as u64);
+        key = fingerprint(key, self.weather_cells.as_deref(), |&(cloud, _)| cloud as u64);
+        fingerprint(key, self.faction_cells.as_deref(), |f| f.map_or(0, |f| f as u64 + 1))
+    }
+
+    fn render_backdrop(&self, area: Rect, buf: &mut Buffer) {
+        if self.image_basemap {
+            return;
+        }
+        // 0. Space, atmosphere and glint behind the globe, then its outline
+        if self.projection.is_globe() {
+            let land = self.land;
+            render_sky(area, buf, self.projection, self.glint, |lon, lat| land.is_some_and(|grid| grid.is_land(lon, lat)));
+        }
+
+        // 0b. Area fills as background tints: land, lakes over it, then the
+        // territory factions hold
+        let fills = &self.layers.fills;
+        let theme = self.theme;
+        self.render_fill(&fills.land, |_| Some(theme.land), area, buf);
+        self.render_fill(&fills.lakes, |_| Some(theme.lake), area, buf);
+        if let Some(factions) = self.faction_cells.as_ref() {
+            self.render_fill(&fills.countries, |idx| {
+                let (r, g, b) = factions.get(idx).copied().flatten()?.color();
+                Some(Color::Rgb(r / 4, g / 4, b / 4))
+            }, area, buf);
+        }
+
+        if let Some(ref outline) = self.layers.globe_outline {
+            self.render_layer(outline, theme.globe_outline, area, buf);
+        }
+
+        // 1. County borders (at back)
+        self.render_layer(&self.layers.counties, theme.county, area, buf);
+
+        // 1b. Roads and railways, wrecked segments in rust
+        let infra = &self.layers.infrastructure;
+        self.render_layer(&infra.roads, theme.road, area, buf);
+        self.render_layer(&infra.railways, theme.railway, area, buf);
+        self.render_layer(&infra.wreckage, theme.wreckage, area, buf);
+
+        // 2. State borders
+        self.render_layer(&self.layers.states, theme.state, area, buf);
+
+        // 3. Coastlines
+        self.render_layer(&self.layers.coastlines, theme.coastline, area, buf);
+
+        // 4. Country borders (faction-tinted, on top so always visible above states)
+        self.render_faction_layer(&self.layers.borders, theme.border, area, buf);
+    }
 }
 
 impl<'a> Widget for MapWidget<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         // Render layers from back to front:
-        // 0. Globe outline (very faint, behind everything)
-        if let Some(ref outline) = self.layers.globe_outline {
-            self.render_layer(outline, Color::Rgb(50, 50, 50), area, buf);
+        // 0–4. The static backdrop, redrawn only when its inputs change
+        let key = self.backdrop_key(area);
+        if self.backdrop.key == Some(key) && self.backdrop.cells.area == area {
+            self.backdrop.hits += 1;
+        } else {
+            let mut cells = std::mem::take(&mut self.backdrop.cells);
+            cells.resize(area);
+            cells.reset();
+            self.render_backdrop(area, &mut cells);
+            self.backdrop.cells = cells;
+            self.backdrop.key = Some(key);
+        }
+        let width = area.width as usize;
+        for y in (area.top()..area.bottom()).filter(|_| width > 0) {
+            let (to, from) = (buf.index_of(area.x, y), self.backdrop.cells.index_of(area.x, y));
+            buf.content[to..to + width].clone_from_slice(&self.backdrop.cells.content[from..from + width]);
         }
 
-        // 1. County borders (DarkGray - at back)
-        self.render_layer(&self.layers.counties, Color::DarkGray, area, buf);
-
-        // 2. State borders (Yellow)
-        self.render_layer(&self.layers.states, Color::Yellow, area, buf);
-
-        // 3. Coastlines (Cyan)
-        self.render_layer(&self.layers.coastlines, Color::Cyan, area, buf);
+        // 5. Weather overlay (clouds/rain in the gaps between map lines)
+        self.render_weather(area, buf);
 
-        // 4. Country borders (Cyan - on top so always visible above states)
-        self.render_layer(&self.layers.borders, Color::Cyan, area, buf);
+        // 6. Ground radiation, then scorched earth (both beneath fires),
+        // then the population choropleth in whatever ground is left
+        self.render_ground(area, buf);
 
         // Render fires — weapon-tinted color gradients
+        let mut fire_canvas = (self.fill_rows == 2).then(|| HalfBlockCanvas::new(area.width as usize, area.height as usize));
         for fire in &self.fires {
+            let seed = hash3(fire.x as u64, fire.y as u64, self.frame);
+            let flicker = ((seed & 0x1F) as i16) - 16;
+            let vi = (fire.intensity as i16 + flicker).clamp(0, 255) as u8;
+            let (ch, (r, g, b)) = fire_fill(fire.weapon_type, vi);
+            if let Some(canvas) = fire_canvas.as_mut() {
+                canvas.set(fire.x as usize, fire.y as usize, (r, g, b));
+                continue;
+            }
             let x = area.x + fire.x;
             let y = area.y + fire.y;
             if x < area.x + area.width && y < area.y + area.height {
-                let seed = hash3(fire.x as u64, fire.y as u64, self.frame);
-                let flicker = ((seed & 0x1F) as i16) - 16;
-                let vi = (fire.intensity as i16 + flicker).clamp(0, 255) as u8;
-
-                let (r, g, b, ch) = match fire.weapon_type {
-                    WeaponType::Chem => {
-                        // Purple-tinted fire: white → magenta → purple → dark plum
-                        if vi > 220      { (255, 220, 255, '█') }
-                        else if vi > 180 { (240, 140, 255, '█') }
-                        else if vi > 140 { (200, 80, 220, '▓') }
-                        else if vi > 100 { (180, 40, 180, '▓') }
-                        else if vi > 60  { (140, 20, 140, '▒') }
-                        else if vi > 30  { (100, 10, 100, '▒') }
-                        else if vi > 15  { (70, 5, 70, '░') }
-                        else             { (45, 0, 45, '░') }
-                    }
-                    _ => {
-                        // Nuke (and any other): standard orange/red heat palette
-                        if vi > 220      { (255, 255, 240, '█') }
-                        else if vi > 180 { (255, 240, 100, '█') }
-                        else if vi > 140 { (255, 180, 30, '▓') }
-                        else if vi > 100 { (255, 120, 0, '▓') }
-                        else if vi > 60  { (255, 60, 0, '▒') }
-                        else if vi > 30  { (200, 30, 0, '▒') }
-                        else if vi > 15  { (140, 20, 0, '░') }
-                        else             { (90, 10, 0, '░') }
-                    }
-                };
-
                 buf[(x, y)].set_char(ch).set_fg(Color::Rgb(r, g, b));
             }
         }
+        if let Some(canvas) = &fire_canvas {
+            draw_halfblocks(canvas, area, buf, false);
+        }
 
         // Render gas clouds — merged density so overlapping clouds blend
         render_gas_clouds_merged(&self.gas_clouds, area, self.frame, buf, self.projection);
 
+        self.render_infection(area, buf);
+
+        // Tsunami fronts — white crests on taller waves, flickering as they roll
+        for &(tx, ty, height) in &self.tsunami_front {
+            let flicker = (hash3(tx as u64, ty as u64, self.frame / 4) & 0x3F) as f32 / 63.0;
+            let level = (height.min(1.0) * 0.7 + flicker * 0.3).clamp(0.2, 1.0);
+            let (ch, color) = if level > 0.6 {
+                ('≈', Color::Rgb((200.0 * level) as u8, (230.0 * level) as u8, 255))
+            } else {
+                ('~', Color::Rgb(0, (90.0 + 120.0 * level) as u8, (150.0 + 105.0 * level) as u8))
+            };
+            buf[(area.x + tx, area.y + ty)].set_char(ch).set_fg(color);
+        }
+
+        // Hurricanes — rain bands spiralling into the eye, turning as they go
+        for storm in &self.hurricanes {
+            const ARMS: usize = 3;
+            const SAMPLES: usize = 48;
+            let spin = if storm.north { -1.0 } else { 1.0 };
+            let turn = self.frame as f32 * 0.04 * spin;
+            for arm in 0..ARMS {
+                for i in 1..=SAMPLES {
+                    let t = i as f32 / SAMPLES as f32;
+                    // Bands wind a turn and a quarter in from the edge
+                    let angle = turn + arm as f32 * std::f32::consts::TAU / ARMS as f32 - spin * t * 2.5 * std::f32::consts::PI;
+                    let r = storm.radius * t;
+                    // Cells are ~2:1 tall, so halve the vertical reach to keep it round
+                    let x = storm.x as f32 + r * angle.cos();
+                    let y = storm.y as f32 + r * angle.sin() * 0.5;
+                    if x < 0.0 || y < 0.0 || x >= area.width as f32 || y >= area.height as f32 {
+                        continue;
+                    }
+                    let level = (storm.strength * (1.2 - t)).clamp(0.25, 1.0);
+                    let v = (120.0 + 135.0 * level) as u8;
+                    let ch = if t < 0.4 { '≈' } else { '~' };
+                    buf[(area.x + x as u16, area.y + y as u16)].set_char(ch).set_fg(Color::Rgb(v, v, 255));
+                }
+            }
+            if (0..area.width as i32).contains(&storm.x) && (0..area.height as i32).contains(&storm.y) {
+                buf[(area.x + storm.x as u16, area.y + storm.y as u16)].set_char('◎').set_fg(Color::White);
+            }
+        }
+
+        // Airports and ports; destroyed ones become a dark red cross
+        for &(fx, fy, kind, destroyed) in &self.layers.facilities {
+            if fy >= self.inner_height || fx >= self.inner_width {
+                continue;
+            }
+            let (ch, color) = match (kind, destroyed) {
+                (_, true) => ('✖', Color::Rgb(140, 30, 20)),
+                (Infrastructure::Port, false) => (kind.glyph(), Color::Rgb(80, 150, 200)),
+                _ => (kind.glyph(), Color::Rgb(170, 190, 210)),
+            };
+            buf[(area.x + fx, area.y + fy)].set_char(ch).set_fg(color);
+        }
+
         // City markers and labels — rendered ON TOP of fires so population
         // damage is visible through the flames
-        for (lx, ly, text, health) in &self.layers.labels {
+        for MapLabel { x: lx, y: ly, text, health, blackout, brightness } in &self.layers.labels {
             if *ly >= self.inner_height || *lx >= self.inner_width {
                 continue;
             }
@@ -498,37 +2052,147 @@ impl<'a> Widget for MapWidget<'a> {
                 } else {
                     Style::default().fg(Color::DarkGray).bg(Color::Reset).add_modifier(Modifier::CROSSED_OUT)
                 }
+            } else if *blackout {
+                // Lights out: dim steel blue, still scaled by damage
+                let brightness = health * 60.0 + 50.0;
+                Style::default()
+                    .fg(Color::Rgb(brightness as u8, (brightness * 1.1) as u8, (brightness * 1.6) as u8))
+                    .bg(Color::Reset)
             } else {
-                let brightness = (health * 200.0 + 55.0) as u8; // 55..255
+                let brightness = (health * brightness * 200.0 + 55.0) as u8; // 55..255
                 Style::default().fg(Color::Rgb(brightness, brightness, brightness)).bg(Color::Reset)
             };
 
-            let max_len = (self.inner_width.saturating_sub(*lx)) as usize;
-            let display_text: String = if is_marker {
-                display_text_raw.chars().take(1).collect()
+            // Measured in cells, so wide CJK names and combining accents
+            // land where the placer put them
+            let max_width = if is_marker { 1 } else { self.inner_width.saturating_sub(*lx) as usize };
+            buf.set_stringn(x, y, display_text_raw, max_width, style);
+        }
+
+        // Registered overlays, above the cities
+        let globe_ref = self.projection.is_globe().then(|| self.projection.view());
+        for pulse in &self.overlay_pulses {
+            render_pulse(pulse, area, self.frame, buf, globe_ref);
+        }
+        for (color, canvas) in &self.overlay_lines {
+            for row in 0..canvas.char_height().min(area.height as usize) {
+                for (col, &b) in canvas.row_raw(row).iter().enumerate().take(area.width as usize) {
+                    if b != 0 {
+                        let ch = char::from_u32(0x2800 + b as u32).unwrap_or(' ');
+                        buf[(area.x + col as u16, area.y + row as u16)].set_char(ch).set_fg(*color);
+                    }
+                }
+            }
+        }
+        for &(x, y, glyph, color) in &self.overlay_points {
+            buf[(area.x + x, area.y + y)].set_char(glyph).set_fg(color);
+        }
+        for (x, y, text, color) in &self.overlay_labels {
+            buf.set_stringn(area.x + x, area.y + y, text, self.inner_width.saturating_sub(*x) as usize, Style::default().fg(*color));
+        }
+
+        // Launch sites
+        for &(sx, sy, selected) in &self.silos {
+            let color = if selected { Color::Rgb(180, 255, 180) } else { Color::Rgb(70, 160, 70) };
+            buf[(area.x + sx, area.y + sy)].set_char('▲').set_fg(color);
+        }
+
+        // ABM batteries (dim while reloading) and their range while placing
+        for &(rx, ry) in &self.defense_rings {
+            buf[(area.x + rx, area.y + ry)].set_char('·').set_fg(Color::Rgb(0, 90, 120));
+        }
+        for &(dx, dy, ready) in &self.defenses {
+            let color = if ready { Color::Rgb(0, 220, 255) } else { Color::Rgb(0, 90, 110) };
+            buf[(area.x + dx, area.y + dy)].set_char('⊕').set_fg(color);
+        }
+
+        for &(rx, ry, passing) in &self.refugee_streams {
+            let cell = &mut buf[(area.x + rx, area.y + ry)];
+            if passing {
+                cell.set_char('•').set_fg(Color::Rgb(200, 180, 130));
+            } else if cell.symbol() == " " {
+                cell.set_char('·').set_fg(Color::Rgb(110, 100, 75));
+            }
+        }
+
+        // Delivery platforms
+        for &(rx, ry, color) in &self.platform_routes {
+            buf[(area.x + rx, area.y + ry)].set_char('·').set_fg(color);
+        }
+        if let Some((sx, sy)) = self.submarine {
+            buf[(area.x + sx, area.y + sy)].set_char('▬').set_fg(Color::Rgb(80, 160, 255));
+        }
+        for b in &self.bombers {
+            if let Some((tx, ty)) = b.target {
+                buf[(area.x + tx, area.y + ty)].set_char('×').set_fg(weapon_color(b.weapon_type));
+            }
+            if let Some((bx, by)) = b.pos {
+                buf[(area.x + bx, area.y + by)].set_char('✈').set_fg(Color::Rgb(230, 230, 230));
+            }
+        }
+
+        // Missiles in flight — trail glows hotter toward the warhead
+        for p in &self.projectiles {
+            if let Some((rx, ry)) = p.release {
+                buf[(area.x + rx, area.y + ry)].set_char('✺').set_fg(weapon_color(p.weapon_type));
+            }
+            for &(tx, ty, age) in &p.trail {
+                let cell = &mut buf[(area.x + tx, area.y + ty)];
+                if age >= 1.0 {
+                    let flicker = hash3(tx as u64, ty as u64, self.frame) & 1 == 0;
+                    cell.set_char(if flicker { '✦' } else { '◆' }).set_fg(weapon_color(p.weapon_type));
+                    continue;
+                }
+                let heat = age * age;
+                let (r, g, b) = if p.weapon_type == WeaponType::Asteroid {
+                    // Plasma sheath: white-hot behind the rock, cooling to ember red
+                    (255, (60.0 + heat * 195.0) as u8, (heat * 200.0) as u8)
+                } else if p.hostile {
+                    ((90.0 + heat * 165.0) as u8, (heat * 60.0) as u8, (40.0 + heat * 140.0) as u8)
+                } else {
+                    ((90.0 + heat * 165.0) as u8, (20.0 + heat * 180.0) as u8, (heat * 80.0) as u8)
+                };
+                let ch = if age > 0.6 { '•' } else { '·' };
+                cell.set_char(ch).set_fg(Color::Rgb(r, g, b));
+            }
+        }
+
+        // Interceptor bursts — an expanding ring of debris; misses fizzle grey
+        for &(bx, by, frame, hit) in &self.interceptions {
+            let fade = 1.0 - frame as f32 / Interception::FRAMES as f32;
+            let radius = 1 + frame as i32 / 6;
+            let color = if hit {
+                Color::Rgb(255, (120.0 + 135.0 * fade) as u8, (200.0 * fade) as u8)
             } else {
-                display_text_raw.chars().take(max_len).collect()
+                let v = (60.0 + 100.0 * fade) as u8;
+                Color::Rgb(v, v, v)
             };
-
-            for (i, ch) in display_text.chars().enumerate() {
-                let px = x + i as u16;
-                if px < area.x + area.width {
-                    buf[(px, y)].set_char(ch).set_style(style);
+            if hit && frame < 6 {
+                buf[(area.x + bx, area.y + by)].set_char('✸').set_fg(Color::White);
+            }
+            for (ox, oy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)] {
+                // Cells are ~2:1 tall, so stretch horizontally to keep the ring round
+                let x = bx as i32 + ox * radius * 2;
+                let y = by as i32 + oy * radius;
+                if x >= 0 && y >= 0 && x < area.width as i32 && y < area.height as i32 {
+                    buf[(area.x + x as u16, area.y + y as u16)]
+                        .set_char(if hit { '*' } else { '·' })
+                        .set_fg(color);
                 }
             }
         }
 
         // Render explosions — dispatch per weapon type
-        let globe_ref = match &self.projection {
-            Projection::Globe(g) => Some(g),
-            _ => None,
-        };
         for exp in &self.explosions {
             let x = area.x + exp.x;
             let y = area.y + exp.y;
 
             match exp.weapon_type {
-                WeaponType::Nuke => render_nuke_explosion(exp, x, y, area, self.frame, buf, globe_ref),
+                WeaponType::Nuke | WeaponType::Airburst | WeaponType::Asteroid => {
+                    render_nuke_explosion(exp, x, y, area, self.frame, buf, globe_ref)
+                }
+                // Expanding shock rings read as the surge leaving ground zero
+                WeaponType::Tsunami => render_emp_explosion(exp, x, y, area, self.frame, buf, globe_ref),
                 WeaponType::Bio => render_bio_explosion(exp, x, y, area, self.frame, buf, globe_ref),
                 WeaponType::Emp => render_emp_explosion(exp, x, y, area, self.frame, buf, globe_ref),
                 WeaponType::Chem => render_chem_explosion(exp, x, y, area, self.frame, buf, globe_ref),
@@ -541,7 +2205,7 @@ impl<'a> Widget for MapWidget<'a> {
             let center_x = area.x as i32 + cx as i32;
             let center_y = area.y as i32 + cy as i32;
 
-            if let Projection::Globe(ref globe) = self.projection {
+            if self.projection.is_globe() {
                 // Globe: project geographic circle onto sphere surface
                 if let Some((cursor_lon, cursor_lat)) = self.cursor_geo {
                     let radius_deg = self.cursor_blast_km / 111.0;
@@ -552,7 +2216,7 @@ impl<'a> Widget for MapWidget<'a> {
                         let dlat = radius_deg * angle.sin();
                         let dlon = (radius_deg * angle.cos()) / cos_lat;
 
-                        if let Some((px, py)) = globe.project(cursor_lon + dlon, cursor_lat + dlat) {
+                        if let Some((px, py)) = self.projection.project_point(cursor_lon + dlon, cursor_lat + dlat) {
                             let scx = px / 2;
                             let scy = py / 4;
 
@@ -566,9 +2230,12 @@ impl<'a> Widget for MapWidget<'a> {
                     }
                 }
             } else {
-                // Mercator: screen-space circle
+                // Flat maps: screen-space circle
                 let degrees = self.cursor_blast_km / 111.0;
-                let pixels = self.projection.deg_to_pixels(degrees) as u16;
+                let pixels = match self.cursor_geo {
+                    Some((lon, lat)) => self.projection.deg_to_pixels_at(lon, lat, degrees),
+                    None => self.projection.deg_to_pixels(degrees),
+                } as u16;
                 let radius = (pixels / 2).max(3);
                 let r = radius as i32;
 
@@ -605,6 +2272,159 @@ impl<'a> Widget for MapWidget<'a> {
                     .set_fg(reticle_color);
             }
         }
+
+        // Pattern strike preview
+        for &(x, y) in &self.strike_outline {
+            buf[(area.x + x, area.y + y)].set_char('·').set_fg(reticle_color);
+        }
+        for &(x, y) in &self.strike_targets {
+            buf[(area.x + x, area.y + y)].set_char('✕').set_fg(reticle_color);
+        }
+
+        // Strike queue: a marker and its number beside it
+        for &(x, y, number, weapon) in &self.queued {
+            let color = weapon_color(weapon);
+            buf[(area.x + x, area.y + y)].set_char('◎').set_fg(color);
+            for (i, digit) in number.to_string().chars().enumerate() {
+                let dx = x + 1 + i as u16;
+                if dx < self.inner_width {
+                    buf[(area.x + dx, area.y + y)].set_char(digit).set_fg(color);
+                }
+            }
+        }
+    }
+}
+
+/// Radiation heatmap: green through yellow to red on a log scale, a tenth
+/// of a lethal dose up to ten lethal doses
+fn radiation_fill(dose: f32) -> Option<(char, Rgb)> {
+    let level = ((dose / FalloutGrid::LETHAL_GY).log10() + 1.0) / 2.0;
+    (level > 0.0).then(|| ColorRamp::RADIATION.fill(level.min(1.0)))
+}
+
+/// Scorched earth: dark gray ground, denser where the fire burned hotter
+fn scar_fill(burn: u8) -> Option<(char, Rgb)> {
+    if burn == 0 {
+        return None;
+    }
+    let shade = 38 + burn / 8;
+    Some((if burn > 150 { '▒' } else { '░' }, (shade, shade - 4, shade - 8)))
+}
+
+/// Impact crater: a dark bowl inside a pale raised rim, with a thinning
+/// blanket of ejecta beyond it
+fn crater_fill(reach: f64) -> Option<(char, Rgb)> {
+    match reach {
+        r if r < 0.8 => Some(('▓', (48, 40, 34))),
+        r if r < 1.1 => Some(('█', (150, 124, 96))),
+        r if r < Crater::EJECTA_REACH => {
+            let shade = (110.0 - (r - 1.1) * 60.0) as u8;
+            Some((if r < 1.4 { '▒' } else { '░' }, (shade, shade - 14, shade - 28)))
+        }
+        _ => None,
+    }
+}
+
+/// Population density: dark violet for sparse countryside up to bright
+/// lilac at 1000+ people/km², on a log scale
+fn population_fill(density: f32) -> Option<(char, Rgb)> {
+    (density >= 1.0).then(|| ColorRamp::POPULATION.fill((density.log10() / 3.0).min(1.0)))
+}
+
+/// Fire color by weapon at (flickered) intensity `vi`
+fn fire_fill(weapon: WeaponType, vi: u8) -> (char, Rgb) {
+    let (r, g, b, ch) = match weapon {
+        WeaponType::Chem => {
+            // Purple-tinted fire: white → magenta → purple → dark plum
+            if vi > 220      { (255, 220, 255, '█') }
+            else if vi > 180 { (240, 140, 255, '█') }
+            else if vi > 140 { (200, 80, 220, '▓') }
+            else if vi > 100 { (180, 40, 180, '▓') }
+            else if vi > 60  { (140, 20, 140, '▒') }
+            else if vi > 30  { (100, 10, 100, '▒') }
+            else if vi > 15  { (70, 5, 70, '░') }
+            else             { (45, 0, 45, '░') }
+        }
+        // Nuke (and any other): the standard heat palette
+        _ => return ColorRamp::FIRE.flame(vi),
+    };
+    (ch, (r, g, b))
+}
+
+/// Write a half-block canvas into the buffer, optionally only into cells
+/// that are still empty
+fn draw_halfblocks(canvas: &HalfBlockCanvas, area: Rect, buf: &mut Buffer, empty_only: bool) {
+    for row in 0..area.height {
+        for col in 0..area.width {
+            let Some((ch, (r, g, b), bottom)) = canvas.cell(col as usize, row as usize) else { continue };
+            let cell = &mut buf[(area.x + col, area.y + row)];
+            if empty_only && cell.symbol() != " " {
+                continue;
+            }
+            cell.set_char(ch).set_fg(Color::Rgb(r, g, b));
+            if let Some((r, g, b)) = bottom {
+                cell.set_bg(Color::Rgb(r, g, b));
+            }
+        }
+    }
+}
+
+const SPACE: (f32, f32, f32) = (4.0, 5.0, 12.0);
+const ATMOSPHERE: (f32, f32, f32) = (40.0, 130.0, 170.0);
+
+fn mix_rgb(a: (f32, f32, f32), b: (f32, f32, f32), t: f32) -> (f32, f32, f32) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t, a.2 + (b.2 - a.2) * t)
+}
+
+/// Backgrounds around and on the globe: a dark starfield, an atmosphere
+/// glow fading out from the limb and a fainter one just inside it, and
+/// optionally the sun's specular highlight on the ocean (never on land).
+/// Colors go in cell backgrounds, so every layer drawn afterwards sits on top.
+fn render_sky(area: Rect, buf: &mut Buffer, projection: &Projection, glint: Option<DVec3>, is_land: impl Fn(f64, f64) -> bool) {
+    let view = projection.view();
+    let (w, h) = view.size();
+    let (cx, cy) = (w as f64 / 2.0, h as f64 / 2.0);
+    let radius = view.deg_to_pixels(1.0_f64.to_degrees());
+    if radius <= 0.0 {
+        return;
+    }
+    // Glow widths as fractions of the radius, never thinner than a few pixels
+    let outer = (6.0 / radius).max(0.06);
+    let inner = (4.0 / radius).max(0.05);
+
+    for row in 0..area.height {
+        for col in 0..area.width {
+            let (px, py) = (col as i32 * 2 + 1, row as i32 * 4 + 2);
+            let d = ((px as f64 - cx).hypot(py as f64 - cy) / radius) as f32;
+            let cell = &mut buf[(area.x + col, area.y + row)];
+            if d > 1.0 {
+                let glow = (1.0 - (d - 1.0) / outer as f32).max(0.0).powi(2);
+                let (r, g, b) = mix_rgb(SPACE, ATMOSPHERE, glow * 0.8);
+                cell.set_bg(Color::Rgb(r as u8, g as u8, b as u8));
+                // Fixed stars, kept clear of the glow
+                let seed = hash2(col as u64, row as u64);
+                if glow == 0.0 && seed.is_multiple_of(47) {
+                    let shade = [70, 110, 170][(seed >> 8) as usize % 3];
+                    let ch = if (seed >> 4).is_multiple_of(5) { '✦' } else { '·' };
+                    cell.set_char(ch).set_fg(Color::Rgb(shade, shade, shade + 20));
+                }
+                continue;
+            }
+
+            let limb = ((d - (1.0 - inner as f32)) / inner as f32).max(0.0).powi(2) * 0.5;
+            let mut rgb = mix_rgb((0.0, 0.0, 0.0), ATMOSPHERE, limb);
+            if let Some((point, half)) = glint.and_then(|half| Some((view.surface_point(px, py)?, half))) {
+                let spec = point.dot(half).max(0.0).powi(60) as f32;
+                // Land is only looked up where there's a highlight to keep off it
+                let (lon, lat) = vec3_to_lonlat(point);
+                if spec > 0.01 && !is_land(lon, lat) {
+                    rgb = mix_rgb(rgb, (150.0, 160.0, 170.0), spec * 0.7);
+                }
+            }
+            if rgb.0 + rgb.1 + rgb.2 >= 6.0 {
+                cell.set_bg(Color::Rgb(rgb.0 as u8, rgb.1 as u8, rgb.2 as u8));
+            }
+        }
     }
 }
 
@@ -615,13 +2435,16 @@ fn weapon_color(weapon: WeaponType) -> Color {
         WeaponType::Bio => Color::Rgb(0, 255, 50),
         WeaponType::Emp => Color::Rgb(0, 200, 255),
         WeaponType::Chem => Color::Rgb(200, 0, 200),
+        WeaponType::Airburst => Color::Rgb(255, 150, 0),
+        WeaponType::Tsunami => Color::Rgb(0, 120, 255),
+        WeaponType::Asteroid => Color::Rgb(190, 150, 110),
     }
 }
 
 // ── Per-weapon explosion renderers ──────────────────────────────────────────
 
 /// Nuke: mushroom cloud rising UPWARD — white → yellow → orange → red → smoke
-fn render_nuke_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, global_frame: u64, buf: &mut Buffer, globe: Option<&GlobeViewport>) {
+fn render_nuke_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, global_frame: u64, buf: &mut Buffer, globe: Option<&dyn MapProjection>) {
     let progress = if exp.frame < 20 {
         (exp.frame as f32 / 20.0).powf(0.7)
     } else if exp.frame < 40 {
@@ -691,7 +2514,7 @@ fn render_nuke_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, glob
                 if let Some(g) = globe {
                     let bx = (px as i32 - area.x as i32) * 2;
                     let by = (py as i32 - area.y as i32) * 4;
-                    if g.pixel_to_sphere_point(bx, by).is_none() { continue; }
+                    if g.surface_point(bx, by).is_none() { continue; }
                 }
 
                 let radial_dist = dist_sq.sqrt() / (cap_width * height_factor);
@@ -718,7 +2541,7 @@ fn render_nuke_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, glob
                 } else if cooling_phase {
                     let cooling_progress = (exp.frame - 25) as f32 / 20.0;
                     if dist_norm < 0.15 {
-                        let pulse = if (exp.frame / 3) % 2 == 0 { 60 } else { 20 };
+                        let pulse = if (exp.frame / 3).is_multiple_of(2) { 60 } else { 20 };
                         (255, pulse, 30, '☢')
                     } else if dist_norm < 0.4 {
                         ((220.0 - cooling_progress * 80.0 - flicker * 40.0) as u8, (60.0 - cooling_progress * 20.0) as u8, 0, '▓')
@@ -740,7 +2563,7 @@ fn render_nuke_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, glob
 }
 
 /// Bio: low creeping fog — wide but stays low, neon green palette, irregular tendrils
-fn render_bio_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, global_frame: u64, buf: &mut Buffer, globe: Option<&GlobeViewport>) {
+fn render_bio_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, global_frame: u64, buf: &mut Buffer, globe: Option<&dyn MapProjection>) {
     let progress = if exp.frame < 20 {
         (exp.frame as f32 / 20.0).powf(0.5) // Faster initial spread
     } else if exp.frame < 40 {
@@ -801,7 +2624,7 @@ fn render_bio_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, globa
                 if let Some(g) = globe {
                     let bx = (px as i32 - area.x as i32) * 2;
                     let by = (py as i32 - area.y as i32) * 4;
-                    if g.pixel_to_sphere_point(bx, by).is_none() { continue; }
+                    if g.surface_point(bx, by).is_none() { continue; }
                 }
 
                 let radial_dist = dist_sq.sqrt() / (cap_width * height_factor).max(1.0);
@@ -823,7 +2646,7 @@ fn render_bio_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, globa
                 } else if creep_phase {
                     let p = (exp.frame - 20) as f32 / 25.0;
                     if dist_norm < 0.15 {
-                        let pulse = if (exp.frame / 4) % 2 == 0 { 255 } else { 180 };
+                        let pulse = if (exp.frame / 4).is_multiple_of(2) { 255 } else { 180 };
                         (0, pulse, 30, '☣')
                     } else if dist_norm < 0.4 {
                         ((40.0 + flicker * 20.0) as u8, (180.0 - p * 60.0) as u8, (20.0 - p * 10.0) as u8, '▓')
@@ -854,7 +2677,7 @@ fn render_bio_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, globa
 }
 
 /// EMP: expanding concentric rings — electric blue/cyan, fast, short duration
-fn render_emp_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, global_frame: u64, buf: &mut Buffer, globe: Option<&GlobeViewport>) {
+fn render_emp_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, global_frame: u64, buf: &mut Buffer, globe: Option<&dyn MapProjection>) {
     // 3 rings expanding at staggered speeds, fills radius by frame 15
     let progress = (exp.frame as f32 / 15.0).min(1.0); // Full expansion by frame 15
     let fade = if exp.frame > 15 { (exp.frame - 15) as f32 / 15.0 } else { 0.0 };
@@ -893,7 +2716,7 @@ fn render_emp_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, globa
             let dist: f32 = if let Some(g) = globe {
                 let bx = (px as i32 - area.x as i32) * 2;
                 let by = (py as i32 - area.y as i32) * 4;
-                match g.pixel_to_sphere_point(bx, by) {
+                match g.surface_point(bx, by) {
                     None => continue, // outside globe disk
                     Some(p) => {
                         let dot = p.dot(center_vec).clamp(-1.0, 1.0);
@@ -909,10 +2732,8 @@ fn render_emp_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, globa
             for (i, &ring_r) in ring_radii.iter().enumerate() {
                 if ring_r < 1.0 { continue; }
                 let proximity = (dist - ring_r).abs();
-                if proximity <= ring_thickness {
-                    if best_ring.is_none() || proximity < best_ring.unwrap().0 {
-                        best_ring = Some((proximity, i));
-                    }
+                if proximity <= ring_thickness && best_ring.is_none_or(|(best, _)| proximity < best) {
+                    best_ring = Some((proximity, i));
                 }
             }
 
@@ -955,8 +2776,60 @@ fn render_emp_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, globa
     }
 }
 
+/// An overlay's pulse: one ring spreading out from the center and fading,
+/// over and over, like a slow single EMP ring
+fn render_pulse(pulse: &PulseRender, area: Rect, global_frame: u64, buf: &mut Buffer, globe: Option<&dyn MapProjection>) {
+    const PERIOD: u64 = 90;
+    // Neighbouring pulses start at different times rather than in step
+    let offset = hash3(pulse.lon.to_bits(), pulse.lat.to_bits(), 0) % PERIOD;
+    let progress = ((global_frame + offset) % PERIOD) as f32 / PERIOD as f32;
+    let ring_r = pulse.radius as f32 * progress;
+    if ring_r < 1.0 { return; }
+    let brightness = 1.0 - progress;
+    let color = match pulse.color {
+        Color::Rgb(r, g, b) => {
+            let scale = |v: u8| (v as f32 * brightness) as u8;
+            Color::Rgb(scale(r), scale(g), scale(b))
+        }
+        other => other,
+    };
+
+    let (x, y) = (area.x + pulse.x, area.y + pulse.y);
+    let center_vec = lonlat_to_vec3(pulse.lon, pulse.lat);
+    let geo_scale = pulse.radius as f64 / (pulse.radius_km / 6371.0);
+    let scan_r = ring_r as i16 + 1;
+
+    for dy in -scan_r..=scan_r {
+        let py_signed = (y as i16) + dy;
+        if py_signed < area.y as i16 || py_signed >= (area.y + area.height) as i16 { continue; }
+        let py = py_signed as u16;
+
+        for dx in -scan_r..=scan_r {
+            let px_signed = (x as i16) + dx;
+            if px_signed < area.x as i16 || px_signed >= (area.x + area.width) as i16 { continue; }
+            let px = px_signed as u16;
+
+            // Distance: geographic on globe, screen-space on flat maps
+            let dist: f32 = if let Some(g) = globe {
+                let bx = (px as i32 - area.x as i32) * 2;
+                let by = (py as i32 - area.y as i32) * 4;
+                match g.surface_point(bx, by) {
+                    None => continue,
+                    Some(p) => (p.dot(center_vec).clamp(-1.0, 1.0).acos() * geo_scale) as f32,
+                }
+            } else {
+                ((dx * dx + dy * dy) as f32).sqrt()
+            };
+
+            if (dist - ring_r).abs() <= 0.5 {
+                buf[(px, py)].set_char(if brightness > 0.5 { '∘' } else { '·' }).set_fg(color);
+            }
+        }
+    }
+}
+
 /// Chem: dense dome/sphere expanding in ALL directions — purple palette, dripping
-fn render_chem_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, global_frame: u64, buf: &mut Buffer, globe: Option<&GlobeViewport>) {
+fn render_chem_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, global_frame: u64, buf: &mut Buffer, globe: Option<&dyn MapProjection>) {
     let progress = if exp.frame < 20 {
         (exp.frame as f32 / 20.0).powf(0.6)
     } else if exp.frame < 40 {
@@ -1006,7 +2879,7 @@ fn render_chem_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, glob
             let dist: f32 = if let Some(g) = globe {
                 let bx = (px as i32 - area.x as i32) * 2;
                 let by = (py as i32 - area.y as i32) * 4;
-                match g.pixel_to_sphere_point(bx, by) {
+                match g.surface_point(bx, by) {
                     None => continue, // outside globe disk
                     Some(p) => {
                         let dot = p.dot(center_vec).clamp(-1.0, 1.0);
@@ -1059,7 +2932,7 @@ fn render_chem_explosion(exp: &ExplosionRender, x: u16, y: u16, area: Rect, glob
                 } else if cooling_phase {
                     let p = (exp.frame - 22) as f32 / 23.0;
                     if dist_norm < 0.15 {
-                        let pulse = if (exp.frame / 3) % 2 == 0 { 200 } else { 120 };
+                        let pulse = if (exp.frame / 3).is_multiple_of(2) { 200 } else { 120 };
                         (pulse, 0, (200.0 - p * 40.0) as u8, '☠')
                     } else if dist_norm < 0.4 {
                         ((80.0 + flicker * 30.0 - p * 20.0) as u8, 0, (120.0 - p * 30.0) as u8, '▓')
@@ -1089,13 +2962,10 @@ fn render_gas_clouds_merged(clouds: &[GasCloudRender], area: Rect, global_frame:
     let h = area.height as usize;
     if w == 0 || h == 0 { return; }
 
-    // Per-pixel density accumulation: (bio_density, chem_density)
-    let mut density_buf = vec![(0.0f32, 0.0f32); w * h];
+    // Per-pixel density accumulation: (bio_density, chem_density, fallout_density, ash_density)
+    let mut density_buf = vec![(0.0f32, 0.0f32, 0.0f32, 0.0f32); w * h];
 
-    let globe = match projection {
-        Projection::Globe(g) => Some(g),
-        _ => None,
-    };
+    let globe = projection.is_globe().then(|| projection.view());
     let time_slow = global_frame / 180;
     let time_glacial = global_frame / 300;
 
@@ -1108,10 +2978,7 @@ fn render_gas_clouds_merged(clouds: &[GasCloudRender], area: Rect, global_frame:
         let intensity_norm = (cloud.intensity as f32 / 2000.0).min(1.0);
         let intensity_scale = 0.3 + intensity_norm * 0.7;
 
-        let cloud_id = hash2(
-            (cloud.lon * 1000.0).to_bits(),
-            (cloud.lat * 1000.0).to_bits(),
-        );
+        let cloud_id = cloud.seed;
 
         let radius_rad = cloud.radius_km / 6371.0;
 
@@ -1119,7 +2986,7 @@ fn render_gas_clouds_merged(clouds: &[GasCloudRender], area: Rect, global_frame:
 
         const N_LOBES: usize = 12;
         let mut lobe_factor = [0.0f32; N_LOBES];
-        for i in 0..N_LOBES {
+        for (i, factor) in lobe_factor.iter_mut().enumerate() {
             let seed_a = hash3(i as u64, cloud_id, time_slow);
             let seed_b = hash3(i as u64, cloud_id, time_slow.wrapping_add(1));
             let na = (seed_a & 0xFF) as f32 / 255.0;
@@ -1129,7 +2996,7 @@ fn render_gas_clouds_merged(clouds: &[GasCloudRender], area: Rect, global_frame:
             let t_smooth = (1.0 - (t_frac * std::f32::consts::PI).cos()) * 0.5;
             let n = na * (1.0 - t_smooth) + nb * t_smooth;
 
-            lobe_factor[i] = (0.55 + n * 0.4) * intensity_scale;
+            *factor = (0.55 + n * 0.4) * intensity_scale;
         }
 
         let scan_r = if globe.is_some() { r + r / 4 } else { r };
@@ -1155,7 +3022,7 @@ fn render_gas_clouds_merged(clouds: &[GasCloudRender], area: Rect, global_frame:
                 let dist_norm = if let Some(g) = globe {
                     let bx = (px as i32 - area.x as i32) * 2;
                     let by = (py as i32 - area.y as i32) * 4;
-                    let point = match g.pixel_to_sphere_point(bx, by) {
+                    let point = match g.surface_point(bx, by) {
                         Some(p) => p,
                         None => continue,
                     };
@@ -1192,6 +3059,8 @@ fn render_gas_clouds_merged(clouds: &[GasCloudRender], area: Rect, global_frame:
                 match cloud.weapon_type {
                     WeaponType::Bio => density_buf[idx].0 += density,
                     WeaponType::Chem => density_buf[idx].1 += density,
+                    WeaponType::Nuke => density_buf[idx].2 += density,
+                    WeaponType::Asteroid => density_buf[idx].3 += density,
                     _ => {}
                 }
             }
@@ -1202,8 +3071,8 @@ fn render_gas_clouds_merged(clouds: &[GasCloudRender], area: Rect, global_frame:
     for row in 0..h {
         for col in 0..w {
             let idx = row * w + col;
-            let (bio_d, chem_d) = density_buf[idx];
-            if bio_d < 0.05 && chem_d < 0.05 { continue; }
+            let (bio_d, chem_d, fallout_d, ash_d) = density_buf[idx];
+            if bio_d < 0.05 && chem_d < 0.05 && fallout_d < 0.05 && ash_d < 0.05 { continue; }
 
             let px = area.x + col as u16;
             let py = area.y + row as u16;
@@ -1212,7 +3081,11 @@ fn render_gas_clouds_merged(clouds: &[GasCloudRender], area: Rect, global_frame:
             let shade = ((shade_seed & 0x1F) as f32) / 31.0;
 
             // Dominant type determines color; combined density determines intensity
-            let (r, g, b, ch) = if bio_d >= chem_d {
+            let (r, g, b, ch) = if ash_d > bio_d.max(chem_d).max(fallout_d) {
+                ash_density_color(ash_d, shade)
+            } else if fallout_d > bio_d.max(chem_d) {
+                fallout_density_color(fallout_d, shade)
+            } else if bio_d >= chem_d {
                 bio_density_color(bio_d, shade)
             } else {
                 chem_density_color(chem_d, shade)
@@ -1239,6 +3112,24 @@ fn bio_density_color(d: f32, shade: f32) -> (u8, u8, u8, char) {
     }
 }
 
+/// Map accumulated fallout density to color — dim olive haze, never solid
+fn fallout_density_color(d: f32, shade: f32) -> (u8, u8, u8, char) {
+    if d > 0.5 {
+        ((110.0 + shade * 30.0) as u8, (120.0 + shade * 30.0) as u8, 0, '▒')
+    } else if d > 0.2 {
+        ((75.0 + shade * 20.0) as u8, (85.0 + shade * 20.0) as u8, 0, '░')
+    } else {
+        ((45.0 + shade * 15.0) as u8, (50.0 + shade * 15.0) as u8, 0, '░')
+    }
+}
+
+/// Map accumulated ash density to color — gray-brown, thick near the vent
+fn ash_density_color(d: f32, shade: f32) -> (u8, u8, u8, char) {
+    let v = 70.0 + d.min(1.5) * 60.0 + shade * 20.0;
+    let ch = if d > 1.0 { '▓' } else if d > 0.4 { '▒' } else { '░' };
+    (v as u8, (v * 0.92) as u8, (v * 0.82) as u8, ch)
+}
+
 /// Map accumulated chem density to color
 fn chem_density_color(d: f32, shade: f32) -> (u8, u8, u8, char) {
     if d > 1.0 {
@@ -1258,9 +3149,48 @@ fn chem_density_color(d: f32, shade: f32) -> (u8, u8, u8, char) {
 fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
     let settings = &app.map_renderer.settings;
 
+    // Search prompt replaces the status line while typing
+    if let Some(query) = &app.search {
+        let prompt = Line::from(vec![
+            Span::styled("/", Style::default().fg(Color::Yellow)),
+            Span::raw(query.as_str()),
+            Span::styled("█", Style::default().fg(Color::DarkGray)),
+            Span::styled("  (Enter: go, Esc: cancel)", Style::default().fg(Color::DarkGray)),
+        ]);
+        frame.render_widget(Paragraph::new(prompt), area);
+        return;
+    }
+
+    // ...and so does the command line
+    if let Some(line) = &app.command.text {
+        // Tab's choices, else the usage of the command being typed
+        let hint = if !app.command.candidates.is_empty() {
+            app.command.candidates.join("  ")
+        } else if let Some(usage) = command::usage(line) {
+            usage
+        } else {
+            "Tab: complete, ↑↓: history, Esc: cancel".to_string()
+        };
+        let prompt = Line::from(vec![
+            Span::styled(":", Style::default().fg(Color::Yellow)),
+            Span::raw(line.as_str()),
+            Span::styled("█", Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("  ({hint})"), Style::default().fg(Color::DarkGray)),
+        ]);
+        frame.render_widget(Paragraph::new(prompt), area);
+        return;
+    }
+
+    let stock = app.stockpiles[app.active_weapon.index()];
+    let toll = if app.pandemic { "DEATHS" } else { "CASUALTIES" };
     let status = Line::from(vec![
+        match (app.macros.recording(), app.macros.playing()) {
+            (Some(register), _) => Span::styled(format!("● REC {register} "), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+            (None, Some(register)) => Span::styled(format!("▶ @{register} "), Style::default().fg(Color::Green)),
+            (None, None) => Span::raw(""),
+        },
         Span::styled(
-            if app.is_globe() { "[G]lobe " } else { "[M]ap " },
+            format!("[{}] ", app.projection.name()),
             Style::default().fg(if app.is_globe() { Color::Magenta } else { Color::Cyan }),
         ),
         Span::styled("Zoom: ", Style::default().fg(Color::DarkGray)),
@@ -1292,21 +3222,133 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
             if settings.show_population { "[P]op " } else { "[p]op " },
             Style::default().fg(if settings.show_population { Color::Green } else { Color::DarkGray }),
         ),
+        match app.tile_status() {
+            Some(tiles) => Span::styled(format!("{tiles} "), Style::default().fg(Color::Blue)),
+            None => Span::raw(""),
+        },
+        Span::styled("| ", Style::default().fg(Color::DarkGray)),
+        if app.history.is_scrubbing() {
+            Span::styled("⏪ HISTORY ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        } else if app.paused {
+            Span::styled("⏸ PAUSED ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        } else if app.sim_rate != 1.0 {
+            Span::styled(format!("▶ {} ", format_rate(app.sim_rate)), Style::default().fg(Color::Yellow))
+        } else {
+            Span::raw("")
+        },
+        Span::styled(
+            format!("{} ", app.clock_status()),
+            Style::default().fg(if app.clock.is_paused() { Color::DarkGray } else { Color::White }),
+        ),
         Span::styled("| ", Style::default().fg(Color::DarkGray)),
         Span::styled(app.center_coords(), Style::default().fg(Color::Cyan)),
         Span::styled("| ", Style::default().fg(Color::DarkGray)),
+        if app.pandemic {
+            Span::styled("☣ PANDEMIC — click a city to seed", Style::default().fg(weapon_color(WeaponType::Bio)))
+        } else {
+            Span::styled(
+                format!(
+                    "{} {} {}{} via {}{}",
+                    app.active_weapon.symbol(),
+                    app.active_weapon.label(),
+                    app.payload_label(app.active_weapon),
+                    if app.mirv_armed { format!(" MIRV×{}", app.mirv.warheads) } else { String::new() },
+                    app.delivery.label(),
+                    if app.reticle == Reticle::Point { String::new() } else { format!(" [{}]", app.reticle.label()) },
+                ),
+                Style::default().fg(if stock.is_empty() { Color::DarkGray } else { weapon_color(app.active_weapon) }),
+            )
+        },
+        match stock.capacity {
+            _ if app.pandemic => Span::raw(""),
+            Some(_) if stock.is_empty() => Span::styled(
+                format!(" EMPTY ({})", format_duration(stock.restock_in as f64 / 60.0)),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+            Some(capacity) => Span::styled(format!(" {}/{capacity}", stock.remaining), Style::default().fg(Color::White)),
+            None => Span::raw(""),
+        },
         Span::styled(
-            format!("{} {}", app.active_weapon.symbol(), app.active_weapon.label()),
-            Style::default().fg(weapon_color(app.active_weapon)),
+            if app.pandemic { String::new() } else { reload_bar(app.reload_remaining()) },
+            Style::default().fg(if app.reload_remaining() > 0.0 { Color::Yellow } else { Color::Green }),
         ),
-        if app.casualties > 0 {
+        match app.strike_queue.len() {
+            0 => Span::raw(""),
+            n if app.is_launching_package() => Span::styled(format!(" | ◎ {n} launching"), Style::default().fg(Color::LightRed)),
+            n => Span::styled(format!(" | ◎ {n} queued (Enter)"), Style::default().fg(Color::Yellow)),
+        },
+        match app.outbreak {
+            Some(outbreak) => Span::styled(
+                if outbreak.is_cured() {
+                    format!(" | ☣ {} infected, cured", app.infected.len())
+                } else {
+                    format!(" | ☣ {} infected, cure in {}", app.infected.len(), format_duration(outbreak.cure_in as f64 / 60.0))
+                },
+                Style::default().fg(weapon_color(WeaponType::Bio)),
+            ),
+            None => Span::raw(""),
+        },
+        if app.blacked_out.is_empty() {
+            Span::raw("")
+        } else {
+            Span::styled(
+                format!(" | ⚡ {} BLACKED OUT", app.blacked_out.len()),
+                Style::default().fg(weapon_color(WeaponType::Emp)),
+            )
+        },
+        if app.dust > 0.0 {
+            Span::styled(format!(" | ☄ DUST {:.0}%", app.dust * 100.0), Style::default().fg(weapon_color(WeaponType::Asteroid)))
+        } else {
+            Span::raw("")
+        },
+        if app.climate.soot_tg >= 0.5 {
+            Span::styled(
+                format!(" | 🌡 {:+.1}°C ({:.0} Tg soot)", app.climate.temperature_anomaly_c(), app.climate.soot_tg),
+                Style::default().fg(Color::Rgb(150, 170, 200)),
+            )
+        } else {
+            Span::raw("")
+        },
+        if app.casualties > 0 && app.stats.births > 0 {
+            Span::styled(
+                format!(" | {toll}: {} (+{} born)", format_casualties(app.net_casualties()), format_casualties(app.stats.births)),
+                Style::default().fg(Color::Red),
+            )
+        } else if app.casualties > 0 {
             Span::styled(
-                format!(" | CASUALTIES: {}", format_casualties(app.casualties)),
+                format!(" | {toll}: {}", format_casualties(app.casualties)),
                 Style::default().fg(Color::Red),
             )
         } else {
             Span::raw("")
         },
+        match app.game {
+            GameState::Playing(objective) => Span::styled(
+                format!(" | {}", objective.progress(&app.standing(), &app.stats)),
+                Style::default().fg(Color::Green),
+            ),
+            _ => Span::raw(""),
+        },
+        match &app.net {
+            Some(net) => {
+                let (r, g, b) = net.faction.color();
+                Span::styled(format!(" | NET {}", net.faction.label()), Style::default().fg(Color::Rgb(r, g, b)))
+            }
+            None => Span::raw(""),
+        },
+        match &app.adversary {
+            Some(ai) => Span::styled(format!(" | AI {}", ai.difficulty.label()), Style::default().fg(Color::LightRed)),
+            None => Span::raw(""),
+        },
+        if app.placing_defense {
+            Span::styled(" | ⊕ PLACE ABM", Style::default().fg(Color::Rgb(0, 220, 255)))
+        } else {
+            Span::raw("")
+        },
+        match &app.status_message {
+            Some(msg) => Span::styled(format!(" | {msg}"), Style::default().fg(Color::Yellow)),
+            None => Span::raw(""),
+        },
     ]);
 
     let paragraph = Paragraph::new(status);
@@ -1314,7 +3356,7 @@ fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 /// Format casualties with suffix (K, M, B)
-fn format_casualties(n: u64) -> String {
+pub(crate) fn format_casualties(n: u64) -> String {
     if n >= 1_000_000_000 {
         format!("{:.1}B", n as f64 / 1_000_000_000.0)
     } else if n >= 1_000_000 {
@@ -1338,10 +3380,55 @@ mod tests {
             (-1.0, 0.0), (-1.0, -1.0), (0.0, -1.0), (1.0, -1.0),
         ] {
             let a = fast_pseudo_angle(dx, dy);
-            assert!(a >= 0.0 && a < 4.0, "angle {a} out of range for ({dx}, {dy})");
+            assert!((0.0..4.0).contains(&a), "angle {a} out of range for ({dx}, {dy})");
         }
     }
 
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(42.7), "42s");
+        assert_eq!(format_duration(185.0), "3m05s");
+        assert_eq!(format_duration(3720.0), "1h02m");
+    }
+
+    #[test]
+    fn sparkline_fills_up_to_each_value() {
+        let area = Rect::new(0, 0, 2, 2);
+        let mut buf = Buffer::empty(area);
+        // Columns at 0, ¼, ½ and full height
+        render_braille_sparkline(&mut buf, &[0.0, 1.0, 2.0, 4.0], area, Color::Red);
+        assert_eq!(buf[(0, 0)].symbol(), " ");
+        assert_eq!(buf[(0, 1)].symbol(), "⢠");
+        assert_eq!(buf[(1, 0)].symbol(), "⢸");
+        assert_eq!(buf[(1, 1)].symbol(), "⣿");
+    }
+
+    #[test]
+    fn sky_glows_at_the_limb_and_glints_toward_the_sun() {
+        use crate::map::GlobeViewport;
+        // 60×30 cells is 120×120 pixels; a 40px globe leaves a ring of space
+        let area = Rect::new(0, 0, 60, 30);
+        let projection = Projection::new(GlobeViewport::new(0.0, 0.0, 40.0, 120, 120));
+        let mut buf = Buffer::empty(area);
+        render_sky(area, &mut buf, &projection, Some(lonlat_to_vec3(0.0, 0.0)), |_, _| false);
+
+        let blue = |x: u16, y: u16| match buf[(x, y)].bg {
+            Color::Rgb(_, _, b) => b,
+            _ => 0,
+        };
+        // Just past the rim (x = 101px) outshines deep space and the open ocean
+        assert!(blue(50, 15) > blue(59, 0) + 40);
+        assert!(blue(59, 0) > 0 && buf[(45, 15)].bg == Color::Reset);
+        // Sun straight behind the viewer: the glint is in the middle
+        assert!(blue(30, 15) > blue(50, 15));
+        assert!(buf.content.iter().any(|c| c.symbol() == "·" || c.symbol() == "✦"));
+
+        // ...unless the middle is land
+        let mut buf = Buffer::empty(area);
+        render_sky(area, &mut buf, &projection, Some(lonlat_to_vec3(0.0, 0.0)), |lon, _| lon.abs() < 20.0);
+        assert_eq!(buf[(30, 15)].bg, Color::Reset);
+    }
+
     #[test]
     fn fast_pseudo_angle_zero() {
         assert_eq!(fast_pseudo_angle(0.0, 0.0), 0.0);
@@ -1358,4 +3445,88 @@ mod tests {
         assert!(a1 > a2, "not monotonic: {a1} <= {a2}");
         assert!(a2 > a3, "not monotonic: {a2} <= {a3}");
     }
+
+    #[test]
+    fn hud_keeps_the_sections_that_fit() {
+        use ratatui::{backend::TestBackend, Terminal};
+        let text = |width: u16, height: u16| {
+            let mut app = App::new(100, 100);
+            app.show_hud = true;
+            app.stockpiles[WeaponType::Nuke.index()] = Stockpile::new(Some(3));
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            let buffer = terminal.draw(|frame| render(frame, &mut app)).unwrap().buffer.clone();
+            buffer.content.iter().map(|c| c.symbol()).collect::<String>()
+        };
+        let tall = text(100, 40);
+        assert!(tall.contains(" HUD ") && tall.contains("Wind") && tall.contains("Clock") && tall.contains("3/3"));
+        let short = text(100, 10);
+        assert!(short.contains("Weapon") && !short.contains("Stock"));
+        // Narrower than the panel, it stays inside the map
+        let narrow = text(40, 40);
+        assert!(narrow.contains(" HUD "));
+    }
+
+    #[test]
+    fn hurricanes_show_their_bands_with_the_eye_off_screen() {
+        use ratatui::{backend::TestBackend, Terminal};
+        let mut app = App::new(160, 160);
+        app.projection.set_view(-40.0, 15.0, 8.0);
+        let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+        let text = |terminal: &mut Terminal<TestBackend>, app: &mut App| {
+            let buffer = terminal.draw(|frame| render(frame, app)).unwrap().buffer.clone();
+            buffer.content.iter().map(|c| c.symbol()).collect::<String>()
+        };
+        assert!(!text(&mut terminal, &mut app).contains('≈'));
+
+        // Just past the left edge
+        let (left, _) = app.projection.unproject(0, 80).unwrap();
+        app.hurricanes.push(Hurricane::new(left - 1.0, 15.0, 1));
+        let shown = text(&mut terminal, &mut app);
+        assert!(shown.contains('≈') && !shown.contains('◎'));
+    }
+
+    #[test]
+    fn backdrop_is_reused_until_the_view_moves() {
+        use ratatui::{backend::TestBackend, Terminal};
+        let mut app = App::new(160, 160);
+        crate::data::generate_simple_world(&mut app.map_renderer);
+        app.map_renderer.build_spatial_indexes();
+        let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+
+        let first = terminal.draw(|frame| render(frame, &mut app)).unwrap().buffer.clone();
+        let second = terminal.draw(|frame| render(frame, &mut app)).unwrap().buffer.clone();
+        assert_eq!(app.backdrop.hits, 1);
+        assert_eq!(first, second);
+
+        app.projection.pan(20, 0);
+        let panned = terminal.draw(|frame| render(frame, &mut app)).unwrap().buffer.clone();
+        assert_eq!(app.backdrop.hits, 1);
+        assert_ne!(panned, second);
+    }
+
+    #[test]
+    fn flight_routes_bundle_when_zoomed_out() {
+        let mut app = App::new(160, 160);
+        for i in 0..80 {
+            let (lon, lat) = ((i % 16) as f64 * 22.0 - 170.0, (i / 16) as f64 * 20.0 - 40.0);
+            app.map_renderer.add_city(lon, lat, &format!("City {i}"), 1_000_000 + i as u64 * 50_000, false, false, "");
+        }
+        app.map_renderer.build_spatial_indexes();
+        app.rebuild_travel();
+        let area = Rect::new(0, 0, 60, 20);
+        app.projection.set_size(120, 80);
+
+        let world = flight_routes(&app, &app.projection, area);
+        assert!(world.lines.len() <= MAX_FLIGHT_ROUTES);
+        assert!(world.lines.iter().any(|l| l.color == FLIGHT_COLORS[3]));
+        let bundles = app.travel.bundled(|_| None::<()>).len();
+        assert!(bundles > MAX_FLIGHT_ROUTES, "{bundles}");
+
+        // Zoomed in on a few hubs, only their routes are left to draw
+        for _ in 0..6 {
+            app.projection.zoom_in();
+        }
+        let close = flight_routes(&app, &app.projection, area);
+        assert!(!close.lines.is_empty() && close.lines.len() < world.lines.len(), "{} {}", close.lines.len(), world.lines.len());
+    }
 }